@@ -0,0 +1,470 @@
+// Copyright 2019-2021 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+//! Generates TypeScript bindings for the commands registered with `generate_handler!`.
+
+use anyhow::Context;
+use syn::{
+  parse::Parser, punctuated::Punctuated, visit::Visit, FnArg, GenericArgument, Item, ItemFn,
+  ItemMod, Macro, Pat, PathArguments, ReturnType, Token, Type, TypePath,
+};
+
+use std::{
+  fmt::Write as _,
+  fs::{read_dir, read_to_string, write},
+  path::Path,
+};
+
+/// Command arguments that are injected by Tauri instead of sent by the webview.
+const INJECTED_ARGUMENTS: &[&str] = &[
+  "Window",
+  "State",
+  "AppHandle",
+  "CancellationToken",
+  "InvokeMessage",
+];
+
+/// A command parsed from the source code.
+#[derive(Clone)]
+struct Command {
+  /// The path of the module that defines the command.
+  module: Vec<String>,
+  name: String,
+  args: Vec<(String, String)>,
+  response: String,
+}
+
+/// A command registered with `generate_handler!`.
+struct Handler {
+  /// The path of the module that calls `generate_handler!`.
+  module: Vec<String>,
+  path: Vec<String>,
+}
+
+/// A source file of the application, with the path of its module.
+struct SourceFile {
+  module: Vec<String>,
+  file: syn::File,
+}
+
+/// Writes the TypeScript bindings of the commands registered on the `src` directory to `out`.
+///
+/// The commands that aren't defined on the application, such as the plugin commands, are skipped with a warning.
+pub fn generate(src: &Path, out: &Path) -> crate::Result<()> {
+  let mut files = Vec::new();
+  read_sources(src, Vec::new(), &mut files)?;
+  let (bindings, missing) = generate_bindings(&files)?;
+  for path in missing {
+    println!(
+      "cargo:warning=no TypeScript bindings generated for the `{}` command, its definition wasn't found",
+      path
+    );
+  }
+  write(out, bindings).with_context(|| format!("failed to write {}", out.display()))
+}
+
+/// Generates the bindings of the registered commands,
+/// returning the paths of the registered commands that aren't defined on the given files.
+fn generate_bindings(files: &[SourceFile]) -> crate::Result<(String, Vec<String>)> {
+  let mut defined = Vec::new();
+  let mut handlers = Vec::new();
+  for source in files {
+    find_commands_in_items(&source.module, &source.file.items, &mut defined);
+    let mut visitor = HandlerVisitor {
+      module: source.module.clone(),
+      handlers: &mut handlers,
+    };
+    visitor.visit_file(&source.file);
+  }
+
+  let mut commands: Vec<Command> = Vec::new();
+  let mut missing = Vec::new();
+  for handler in handlers {
+    match resolve_handler(&handler, &defined) {
+      Some(command) => {
+        if !commands.iter().any(|c| c.name == command.name) {
+          commands.push(command.clone());
+        }
+      }
+      None => missing.push(handler.path.join("::")),
+    }
+  }
+  commands.sort_by(|a, b| a.name.cmp(&b.name));
+
+  let uses_channel = commands
+    .iter()
+    .any(|c| c.args.iter().any(|(_, ty)| ty.starts_with("Channel<")));
+
+  let mut bindings =
+    String::from("// This file was generated by tauri-build. Do not edit it manually.\n\n");
+  if uses_channel {
+    bindings.push_str("import { invoke, Channel } from '@tauri-apps/api/tauri'\n");
+  } else {
+    bindings.push_str("import { invoke } from '@tauri-apps/api/tauri'\n");
+  }
+
+  for command in commands {
+    let function_name = snake_case_to_camel_case(&command.name);
+    if command.args.is_empty() {
+      writeln!(
+        bindings,
+        "\nexport async function {}(): Promise<{}> {{\n  return invoke('{}')\n}}",
+        function_name, command.response, command.name
+      )?;
+    } else {
+      let args = command
+        .args
+        .iter()
+        .map(|(name, ty)| format!("{}: {}", name, ty))
+        .collect::<Vec<_>>()
+        .join("; ");
+      writeln!(
+        bindings,
+        "\nexport async function {}(args: {{ {} }}): Promise<{}> {{\n  return invoke('{}', args)\n}}",
+        function_name, args, command.response, command.name
+      )?;
+    }
+  }
+
+  Ok((bindings, missing))
+}
+
+/// Reads the Rust files of the directory, whose module path is `module`.
+fn read_sources(dir: &Path, module: Vec<String>, files: &mut Vec<SourceFile>) -> crate::Result<()> {
+  for entry in read_dir(dir).with_context(|| format!("failed to read {}", dir.display()))? {
+    let path = entry?.path();
+    let name = match path.file_stem().and_then(|name| name.to_str()) {
+      Some(name) => name.to_string(),
+      None => continue,
+    };
+    if path.is_dir() {
+      let mut module = module.clone();
+      module.push(name);
+      read_sources(&path, module, files)?;
+    } else if path.extension().map(|e| e == "rs").unwrap_or(false) {
+      let source = read_to_string(&path)?;
+      let file =
+        syn::parse_file(&source).with_context(|| format!("failed to parse {}", path.display()))?;
+      let mut module = module.clone();
+      if !["main", "lib", "mod"].contains(&name.as_str()) {
+        module.push(name);
+      }
+      files.push(SourceFile { module, file });
+    }
+  }
+  Ok(())
+}
+
+fn find_commands_in_items(module: &[String], items: &[Item], commands: &mut Vec<Command>) {
+  for item in items {
+    match item {
+      Item::Fn(function) if is_command(function) => {
+        commands.push(parse_command(module.to_vec(), function))
+      }
+      Item::Mod(item_mod) => {
+        if let Some((_, items)) = &item_mod.content {
+          let mut module = module.to_vec();
+          module.push(item_mod.ident.to_string());
+          find_commands_in_items(&module, items, commands);
+        }
+      }
+      _ => {}
+    }
+  }
+}
+
+/// Collects the commands registered with `generate_handler!`.
+struct HandlerVisitor<'a> {
+  module: Vec<String>,
+  handlers: &'a mut Vec<Handler>,
+}
+
+impl<'ast> Visit<'ast> for HandlerVisitor<'_> {
+  fn visit_item_mod(&mut self, item_mod: &'ast ItemMod) {
+    self.module.push(item_mod.ident.to_string());
+    syn::visit::visit_item_mod(self, item_mod);
+    self.module.pop();
+  }
+
+  fn visit_macro(&mut self, mac: &'ast Macro) {
+    let is_generate_handler = mac
+      .path
+      .segments
+      .last()
+      .map(|segment| segment.ident == "generate_handler")
+      .unwrap_or(false);
+    if is_generate_handler {
+      if let Ok(paths) =
+        Punctuated::<syn::Path, Token![,]>::parse_terminated.parse2(mac.tokens.clone())
+      {
+        for path in paths {
+          self.handlers.push(Handler {
+            module: self.module.clone(),
+            path: path
+              .segments
+              .iter()
+              .map(|segment| segment.ident.to_string())
+              .collect(),
+          });
+        }
+      }
+    }
+    syn::visit::visit_macro(self, mac);
+  }
+}
+
+/// Finds the definition of a registered command.
+///
+/// The path is resolved from the crate root and from the module that registers it,
+/// falling back to the only command with its name for the commands imported with `use`.
+fn resolve_handler<'a>(handler: &Handler, commands: &'a [Command]) -> Option<&'a Command> {
+  let (name, path) = handler.path.split_last()?;
+  let absolute: Vec<String> = path
+    .iter()
+    .filter(|segment| *segment != "crate" && *segment != "self")
+    .cloned()
+    .collect();
+  let mut relative = handler.module.clone();
+  for segment in path {
+    match segment.as_str() {
+      "crate" => relative.clear(),
+      "self" => {}
+      "super" => {
+        relative.pop();
+      }
+      _ => relative.push(segment.clone()),
+    }
+  }
+  let candidates: Vec<&Command> = commands.iter().filter(|c| &c.name == name).collect();
+  candidates
+    .iter()
+    .find(|c| c.module == absolute || c.module == relative)
+    .copied()
+    .or_else(|| match candidates.as_slice() {
+      [command] => Some(*command),
+      _ => None,
+    })
+}
+
+fn is_command(function: &ItemFn) -> bool {
+  function.attrs.iter().any(|attr| {
+    attr
+      .path
+      .segments
+      .last()
+      .map(|segment| segment.ident == "command")
+      .unwrap_or(false)
+  })
+}
+
+fn parse_command(module: Vec<String>, function: &ItemFn) -> Command {
+  let args = function
+    .sig
+    .inputs
+    .iter()
+    .filter_map(|arg| match arg {
+      FnArg::Typed(arg) => match arg.pat.as_ref() {
+        Pat::Ident(ident) if !is_injected(&arg.ty) => Some((
+          snake_case_to_camel_case(&ident.ident.to_string()),
+          to_typescript(&arg.ty),
+        )),
+        _ => None,
+      },
+      FnArg::Receiver(_) => None,
+    })
+    .collect();
+
+  let response = match &function.sig.output {
+    ReturnType::Default => "void".into(),
+    ReturnType::Type(_, ty) => to_typescript(ty),
+  };
+
+  Command {
+    module,
+    name: function.sig.ident.to_string(),
+    args,
+    response,
+  }
+}
+
+fn last_segment(ty: &Type) -> Option<&syn::PathSegment> {
+  match ty {
+    Type::Path(TypePath { path, .. }) => path.segments.last(),
+    Type::Reference(reference) => last_segment(&reference.elem),
+    _ => None,
+  }
+}
+
+fn is_injected(ty: &Type) -> bool {
+  last_segment(ty)
+    .map(|segment| INJECTED_ARGUMENTS.contains(&segment.ident.to_string().as_str()))
+    .unwrap_or(false)
+}
+
+fn generic_arguments(segment: &syn::PathSegment) -> Vec<&Type> {
+  match &segment.arguments {
+    PathArguments::AngleBracketed(arguments) => arguments
+      .args
+      .iter()
+      .filter_map(|arg| match arg {
+        GenericArgument::Type(ty) => Some(ty),
+        _ => None,
+      })
+      .collect(),
+    _ => Vec::new(),
+  }
+}
+
+/// Maps a Rust type to its TypeScript representation.
+/// Types that can't be mapped, such as custom structs, are mapped to `unknown`.
+fn to_typescript(ty: &Type) -> String {
+  match ty {
+    Type::Reference(reference) => to_typescript(&reference.elem),
+    Type::Slice(slice) => array_of(&slice.elem),
+    Type::Array(array) => array_of(&array.elem),
+    Type::Tuple(tuple) if tuple.elems.is_empty() => "void".into(),
+    Type::Tuple(tuple) => format!(
+      "[{}]",
+      tuple
+        .elems
+        .iter()
+        .map(to_typescript)
+        .collect::<Vec<_>>()
+        .join(", ")
+    ),
+    Type::Path(_) => {
+      let segment = last_segment(ty).unwrap();
+      let generics = generic_arguments(segment);
+      match (segment.ident.to_string().as_str(), generics.as_slice()) {
+        ("String", _) | ("str", _) | ("char", _) | ("PathBuf", _) | ("Path", _) => "string".into(),
+        ("bool", _) => "boolean".into(),
+        ("u8", _)
+        | ("u16", _)
+        | ("u32", _)
+        | ("u64", _)
+        | ("u128", _)
+        | ("usize", _)
+        | ("i8", _)
+        | ("i16", _)
+        | ("i32", _)
+        | ("i64", _)
+        | ("i128", _)
+        | ("isize", _)
+        | ("f32", _)
+        | ("f64", _) => "number".into(),
+        ("Binary", _) | ("FileBinary", _) => "ArrayBuffer".into(),
+        ("Value", _) => "unknown".into(),
+        ("Option", [inner]) => format!("{} | null", to_typescript(inner)),
+        ("Vec", [inner]) | ("HashSet", [inner]) | ("BTreeSet", [inner]) | ("VecDeque", [inner]) => {
+          array_of(inner)
+        }
+        ("HashMap", [_, value]) | ("BTreeMap", [_, value]) => {
+          format!("Record<string, {}>", to_typescript(value))
+        }
+        ("Result", [ok, ..]) => to_typescript(ok),
+        ("Box", [inner]) | ("Arc", [inner]) | ("Rc", [inner]) | ("Cow", [.., inner]) => {
+          to_typescript(inner)
+        }
+        ("Channel", [inner]) => format!("Channel<{}>", to_typescript(inner)),
+        _ => "unknown".into(),
+      }
+    }
+    _ => "unknown".into(),
+  }
+}
+
+fn array_of(ty: &Type) -> String {
+  let item = to_typescript(ty);
+  if item.contains(' ') {
+    format!("({})[]", item)
+  } else {
+    format!("{}[]", item)
+  }
+}
+
+/// Convert a snake_case string into camelCase, matching the argument keys of the `#[command]` macro.
+fn snake_case_to_camel_case(key: &str) -> String {
+  let mut camel = String::with_capacity(key.len());
+  let mut to_upper = false;
+
+  for c in key.chars() {
+    match c {
+      '_' => to_upper = true,
+      c if std::mem::take(&mut to_upper) => camel.push(c.to_ascii_uppercase()),
+      c => camel.push(c),
+    }
+  }
+
+  camel
+}
+
+#[cfg(test)]
+mod test {
+  use super::{generate_bindings, SourceFile};
+
+  fn source(module: &[&str], source: &str) -> SourceFile {
+    SourceFile {
+      module: module.iter().map(|segment| segment.to_string()).collect(),
+      file: syn::parse_file(source).expect("failed to parse the test source"),
+    }
+  }
+
+  #[test]
+  fn only_registered_commands() {
+    let files = vec![
+      source(
+        &[],
+        r#"
+          mod commands;
+          #[tauri::command]
+          fn unregistered() {}
+          fn main() {
+            tauri::Builder::default()
+              .invoke_handler(tauri::generate_handler![commands::greet, plugin_command])
+              .run(tauri::generate_context!())
+              .unwrap();
+          }
+        "#,
+      ),
+      source(
+        &["commands"],
+        r#"
+          #[tauri::command]
+          pub fn greet(user_name: String, window: tauri::Window) -> Result<Vec<u32>, String> {
+            Ok(Vec::new())
+          }
+        "#,
+      ),
+    ];
+    let (bindings, missing) = generate_bindings(&files).unwrap();
+    assert!(bindings.contains(
+      "export async function greet(args: { userName: string }): Promise<number[]> {\n  return invoke('greet', args)\n}"
+    ));
+    assert!(!bindings.contains("unregistered"));
+    assert_eq!(missing, vec!["plugin_command".to_string()]);
+  }
+
+  #[test]
+  fn resolves_the_command_module() {
+    let files = vec![source(
+      &[],
+      r#"
+          mod a {
+            #[tauri::command]
+            pub fn save(path: std::path::PathBuf) {}
+          }
+          mod b {
+            #[tauri::command]
+            pub fn save(value: Option<bool>) {}
+          }
+          fn main() {
+            tauri::Builder::default().invoke_handler(tauri::generate_handler![b::save]);
+          }
+        "#,
+    )];
+    let (bindings, missing) = generate_bindings(&files).unwrap();
+    assert!(bindings.contains("save(args: { value: boolean | null }): Promise<void>"));
+    assert!(missing.is_empty());
+  }
+}
@@ -14,6 +14,9 @@ mod codegen;
 #[cfg(feature = "codegen")]
 pub use codegen::context::CodegenContext;
 
+#[cfg(feature = "typescript")]
+mod bindings;
+
 /// Attributes used on Windows.
 #[allow(dead_code)]
 pub struct WindowsAttributes {
@@ -47,6 +50,8 @@ impl WindowsAttributes {
 pub struct Attributes {
   #[allow(dead_code)]
   windows_attributes: WindowsAttributes,
+  #[allow(dead_code)]
+  typescript_bindings_path: Option<PathBuf>,
 }
 
 impl Attributes {
@@ -60,12 +65,25 @@ impl Attributes {
     self.windows_attributes = windows_attributes;
     self
   }
+
+  /// Generates a TypeScript module with typed `invoke` wrappers for the commands
+  /// registered with `generate_handler!` on the `src` directory, writing it to the given path.
+  ///
+  /// Types that can't be mapped to TypeScript, such as custom structs, are typed as `unknown`.
+  #[cfg(feature = "typescript")]
+  #[cfg_attr(doc_cfg, doc(cfg(feature = "typescript")))]
+  pub fn typescript_bindings<P: AsRef<Path>>(mut self, path: P) -> Self {
+    self.typescript_bindings_path = Some(path.as_ref().into());
+    self
+  }
 }
 
 /// Run all build time helpers for your Tauri Application.
 ///
 /// The current helpers include the following:
 /// * Generates a Windows Resource file when targeting Windows.
+/// * Generates the TypeScript bindings of the commands if [`Attributes::typescript_bindings`] is set.
+/// * Forwards the target platform and profile to `generate_context!`, which merges the config overlays.
 ///
 /// # Platforms
 ///
@@ -90,17 +108,44 @@ pub fn build() {
 /// Non-panicking [`build()`].
 #[allow(unused_variables)]
 pub fn try_build(attributes: Attributes) -> Result<()> {
+  // the proc macros don't get the build script variables, but see the `rustc-env` ones
+  if let Ok(platform) = std::env::var("CARGO_CFG_TARGET_OS") {
+    println!("cargo:rustc-env=TAURI_TARGET_OS={}", platform);
+  }
+  if let Ok(profile) = std::env::var("PROFILE") {
+    println!("cargo:rustc-env=TAURI_PROFILE={}", profile);
+  }
+
+  #[cfg(feature = "typescript")]
+  if let Some(path) = &attributes.typescript_bindings_path {
+    println!("cargo:rerun-if-changed=src");
+    bindings::generate(Path::new("src"), path)?;
+  }
+
   #[cfg(windows)]
   {
     use anyhow::{anyhow, Context};
-    use std::fs::read_to_string;
-    use tauri_utils::config::Config;
+    use std::{fs::read_to_string, path::Path};
+    use tauri_utils::config::{
+      interpolate_json, merge, overlay_file_names, Config, CONFIG_FILE_NAME,
+    };
     use winres::WindowsResource;
 
-    let config: Config = serde_json::from_str(
-      &read_to_string("tauri.conf.json").expect("failed to read tauri.conf.json"),
-    )
-    .expect("failed to parse tauri.conf.json");
+    let read_json = |path: &str| -> serde_json::Value {
+      serde_json::from_str(
+        &read_to_string(path).unwrap_or_else(|_| panic!("failed to read {}", path)),
+      )
+      .unwrap_or_else(|_| panic!("failed to parse {}", path))
+    };
+    let mut config = read_json(CONFIG_FILE_NAME);
+    let profile = std::env::var("PROFILE").ok();
+    for name in overlay_file_names("windows", profile.as_deref()) {
+      if Path::new(&name).exists() {
+        merge(&mut config, &read_json(&name));
+      }
+    }
+    interpolate_json(&mut config)?;
+    let config: Config = serde_json::from_value(config).expect("failed to parse tauri.conf.json");
 
     let icon_path_string = attributes
       .windows_attributes
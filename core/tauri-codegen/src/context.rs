@@ -6,7 +6,10 @@ use crate::embedded_assets::{AssetOptions, EmbeddedAssets, EmbeddedAssetsError};
 use proc_macro2::TokenStream;
 use quote::quote;
 use std::path::{Path, PathBuf};
-use tauri_utils::config::{AppUrl, Config, WindowUrl};
+use tauri_utils::{
+  config::{AppUrl, Config, PatternKind, WindowUrl},
+  platform::{file_checksum, sidecar_file_name, target_triple},
+};
 
 /// Necessary data needed by [`context_codegen`] to generate code for a Tauri application context.
 pub struct ContextData {
@@ -25,7 +28,7 @@ pub fn context_codegen(data: ContextData) -> Result<TokenStream, EmbeddedAssetsE
     root,
   } = data;
 
-  let mut options = AssetOptions::new();
+  let mut options = AssetOptions::new().compression(config.build.asset_compression);
   if let Some(csp) = &config.tauri.security.csp {
     options = options.csp(csp.clone());
   }
@@ -38,7 +41,10 @@ pub fn context_codegen(data: ContextData) -> Result<TokenStream, EmbeddedAssetsE
 
   let assets = match app_url {
     AppUrl::Url(url) => match url {
-      WindowUrl::External(_) => Default::default(),
+      WindowUrl::External(_) => {
+        let assets = EmbeddedAssets::default();
+        quote!(#assets)
+      }
       WindowUrl::App(path) => {
         if path.components().count() == 0 {
           panic!(
@@ -54,14 +60,32 @@ pub fn context_codegen(data: ContextData) -> Result<TokenStream, EmbeddedAssetsE
             path
           )
         }
-        EmbeddedAssets::new(&assets_path, options)?
+        let assets = EmbeddedAssets::new(&assets_path, options)?;
+        if dev {
+          // serve the frontend changes without recompiling on debug builds
+          let dir = assets_path
+            .canonicalize()
+            .unwrap_or(assets_path)
+            .display()
+            .to_string();
+          let csp = match &config.tauri.security.csp {
+            Some(csp) => quote!(Some(#csp)),
+            None => quote!(None),
+          };
+          quote!(#assets.with_dir(#dir, #csp))
+        } else {
+          quote!(#assets)
+        }
       }
       _ => unimplemented!(),
     },
-    AppUrl::Files(files) => EmbeddedAssets::load_paths(
-      files.iter().map(|p| config_parent.join(p)).collect(),
-      options,
-    )?,
+    AppUrl::Files(files) => {
+      let assets = EmbeddedAssets::load_paths(
+        files.iter().map(|p| config_parent.join(p)).collect(),
+        options,
+      )?;
+      quote!(#assets)
+    }
     _ => unimplemented!(),
   };
 
@@ -144,6 +168,60 @@ pub fn context_codegen(data: ContextData) -> Result<TokenStream, EmbeddedAssetsE
     quote!(None)
   };
 
+  let pattern = match &config.tauri.pattern {
+    PatternKind::Brownfield => quote!(#root::Pattern::Brownfield),
+    PatternKind::Isolation { dir } => {
+      let dir = config_parent.join(dir);
+      if !dir.exists() {
+        panic!(
+          "The isolation application path is set to `{:?}` but it doesn't exist",
+          dir
+        )
+      }
+      let assets = EmbeddedAssets::new(
+        &dir,
+        AssetOptions::new().compression(config.build.asset_compression),
+      )?;
+      quote!(#root::Pattern::Isolation {
+        assets: ::std::sync::Arc::new(#assets),
+      })
+    }
+  };
+
+  let sidecar_checksums = if config.tauri.bundle.verify_sidecar_checksums {
+    // the build scripts know the target, the macro falls back to the host
+    let target_triple = std::env::var("TARGET")
+      .ok()
+      .or_else(|| target_triple().ok())
+      .expect("failed to determine the target triple");
+    let checksums = config
+      .tauri
+      .bundle
+      .external_bin
+      .iter()
+      .filter(|bin| bin.is_bundled_for(&target_triple))
+      .map(|bin| {
+        let path = config_parent.join(sidecar_file_name(bin.path(), &target_triple));
+        let checksum = file_checksum(&path).unwrap_or_else(|e| {
+          panic!(
+            "failed to compute the checksum of the `{}` sidecar at {:?}: {}",
+            bin.name(),
+            path,
+            e
+          )
+        });
+        let name = bin.name();
+        quote!(checksums.insert(#name.into(), #checksum.into());)
+      });
+    quote!({
+      let mut checksums = ::std::collections::HashMap::new();
+      #(#checksums)*
+      checksums
+    })
+  } else {
+    quote!(::std::collections::HashMap::new())
+  };
+
   // double braces are purposeful to force the code into a block expression
   Ok(quote!(#root::Context::new(
     #config,
@@ -151,6 +229,8 @@ pub fn context_codegen(data: ContextData) -> Result<TokenStream, EmbeddedAssetsE
     #default_window_icon,
     #system_tray_icon,
     #package_info,
+    #pattern,
+    #sidecar_checksums,
   )))
 }
 
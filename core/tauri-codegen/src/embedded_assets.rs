@@ -8,9 +8,13 @@ use std::{
   collections::HashMap,
   ffi::OsStr,
   fs::File,
+  io::Write,
   path::{Path, PathBuf},
 };
-use tauri_utils::{assets::AssetKey, html::inject_csp};
+use tauri_utils::{
+  assets::{AssetCompression, AssetKey},
+  html::inject_csp,
+};
 use thiserror::Error;
 use walkdir::WalkDir;
 
@@ -20,8 +24,14 @@ const TARGET_PATH: &str = "tauri-codegen-assets";
 /// The minimum size needed for the hasher to use multiple threads.
 const MULTI_HASH_SIZE_LIMIT: usize = 131_072; // 128KiB
 
-/// (key, (original filepath, compressed bytes))
-type Asset = (AssetKey, (PathBuf, PathBuf));
+/// The extensions of the formats that are already compressed, embedded without compression.
+const COMPRESSED_EXTENSIONS: &[&str] = &[
+  "png", "jpg", "jpeg", "gif", "webp", "avif", "ico", "woff", "woff2", "mp3", "mp4", "ogg", "webm",
+  "zip", "gz", "br", "zst",
+];
+
+/// (key, (original filepath, compressed bytes, compression))
+type Asset = (AssetKey, (PathBuf, PathBuf, AssetCompression));
 
 /// All possible errors while reading and compressing an [`EmbeddedAssets`] directory
 #[derive(Debug, Error)]
@@ -62,12 +72,13 @@ pub enum EmbeddedAssetsError {
 /// through [`ToTokens`]. The generated code is meant to be injected into an application to include
 /// the compressed assets in that application's binary.
 #[derive(Default)]
-pub struct EmbeddedAssets(HashMap<AssetKey, (PathBuf, PathBuf)>);
+pub struct EmbeddedAssets(HashMap<AssetKey, (PathBuf, PathBuf, AssetCompression)>);
 
 /// Options used to embed assets.
 #[derive(Default)]
 pub struct AssetOptions {
   csp: Option<String>,
+  compression: AssetCompression,
 }
 
 impl AssetOptions {
@@ -81,6 +92,12 @@ impl AssetOptions {
     self.csp.replace(csp);
     self
   }
+
+  /// Sets the compression of the assets.
+  pub fn compression(mut self, compression: AssetCompression) -> Self {
+    self.compression = compression;
+    self
+  }
 }
 
 impl EmbeddedAssets {
@@ -160,6 +177,29 @@ impl EmbeddedAssets {
     }
   }
 
+  /// Use highest brotli quality for release, the fastest one for everything else
+  fn brotli_quality() -> u32 {
+    if cfg!(debug_assertions) {
+      1
+    } else {
+      11
+    }
+  }
+
+  /// The compression of the file, skipping the formats that are already compressed.
+  fn file_compression(path: &Path, options: &AssetOptions) -> AssetCompression {
+    let compressed = path
+      .extension()
+      .and_then(OsStr::to_str)
+      .map(|ext| COMPRESSED_EXTENSIONS.contains(&ext.to_lowercase().as_str()))
+      .unwrap_or(false);
+    if compressed {
+      AssetCompression::None
+    } else {
+      options.compression
+    }
+  }
+
   /// Compress a file and spit out the information in a [`HashMap`] friendly form.
   fn compress_file(
     prefix: &Path,
@@ -188,9 +228,13 @@ impl EmbeddedAssets {
     // make sure that our output directory is created
     std::fs::create_dir_all(&out_dir).map_err(|_| EmbeddedAssetsError::OutDir)?;
 
+    let compression = Self::file_compression(path, options);
+
     // get a hash of the input - allows for caching existing files
     let hash = {
       let mut hasher = blake3::Hasher::new();
+      // the same input is written differently for each compression
+      hasher.update(format!("{:?}", compression).as_bytes());
       if input.len() < MULTI_HASH_SIZE_LIMIT {
         hasher.update(&input);
       } else {
@@ -208,17 +252,30 @@ impl EmbeddedAssets {
 
     // only compress and write to the file if it doesn't already exist.
     if !out_path.exists() {
-      let out_file = File::create(&out_path).map_err(|error| EmbeddedAssetsError::AssetWrite {
-        path: out_path.clone(),
-        error,
-      })?;
+      let mut out_file =
+        File::create(&out_path).map_err(|error| EmbeddedAssetsError::AssetWrite {
+          path: out_path.clone(),
+          error,
+        })?;
 
       // entirely write input to the output file path with compression
-      zstd::stream::copy_encode(&*input, out_file, Self::compression_level()).map_err(|error| {
-        EmbeddedAssetsError::AssetWrite {
-          path: path.to_owned(),
-          error,
+      let written = match compression {
+        AssetCompression::None => out_file.write_all(&input),
+        AssetCompression::Zstd => {
+          zstd::stream::copy_encode(&*input, out_file, Self::compression_level())
+        }
+        AssetCompression::Brotli => {
+          let mut writer =
+            brotli::CompressorWriter::new(out_file, 4096, Self::brotli_quality(), 22);
+          // the stream is finished when the writer is consumed
+          writer.write_all(&input).map(|_| {
+            writer.into_inner();
+          })
         }
+      };
+      written.map_err(|error| EmbeddedAssetsError::AssetWrite {
+        path: path.to_owned(),
+        error,
       })?;
     }
 
@@ -231,14 +288,14 @@ impl EmbeddedAssets {
         path: path.to_owned(),
       })?;
 
-    Ok((key, (path.into(), out_path)))
+    Ok((key, (path.into(), out_path, compression)))
   }
 }
 
 impl ToTokens for EmbeddedAssets {
   fn to_tokens(&self, tokens: &mut TokenStream) {
     let mut map = TokenStream::new();
-    for (key, (input, output)) in &self.0 {
+    for (key, (input, output, compression)) in &self.0 {
       let key: &str = key.as_ref();
       let input = input.display().to_string();
       let output = output.display().to_string();
@@ -246,14 +303,14 @@ impl ToTokens for EmbeddedAssets {
       // add original asset as a compiler dependency, rely on dead code elimination to clean it up
       map.append_all(quote!(#key => {
         const _: &[u8] = include_bytes!(#input);
-        include_bytes!(#output)
+        (#compression, include_bytes!(#output))
       },));
     }
 
     // we expect phf related items to be in path when generating the path code
     tokens.append_all(quote! {{
         use ::tauri::api::assets::{EmbeddedAssets, phf, phf::phf_map};
-        EmbeddedAssets::from_zstd(phf_map! { #map })
+        EmbeddedAssets::new(phf_map! { #map })
     }});
   }
 }
@@ -10,6 +10,7 @@ use std::{
   path::{Path, PathBuf},
 };
 pub use tauri_utils::config::Config;
+use tauri_utils::config::{interpolate_json, merge, overlay_file_names};
 use thiserror::Error;
 
 mod context;
@@ -40,12 +41,39 @@ pub enum ConfigError {
     path: PathBuf,
     error: std::io::Error,
   },
+
+  #[error("Tauri config overlay at {0} must be a JSON object")]
+  Overlay(PathBuf),
+
+  #[error("unable to resolve the Tauri config placeholders: {0}")]
+  Interpolation(tauri_utils::Error),
+}
+
+fn read_json(path: &Path) -> Result<serde_json::Value, ConfigError> {
+  File::open(path)
+    .map_err(|error| ConfigError::Io {
+      path: path.to_path_buf(),
+      error,
+    })
+    .map(BufReader::new)
+    .and_then(|file| {
+      serde_json::from_reader(file).map_err(|error| ConfigError::Format {
+        path: path.to_path_buf(),
+        error,
+      })
+    })
 }
 
 /// Get the [`Config`] from the `TAURI_CONFIG` environmental variable, or read from the passed path.
 ///
 /// If the passed path is relative, it should be relative to the current working directory of the
 /// compiling crate.
+///
+/// When reading from the path, the platform and profile overlays next to it
+/// (see [`tauri_utils::config::overlay_file_names`]) are merged over the config.
+/// The platform is read from `TAURI_TARGET_OS` and the profile from `TAURI_PROFILE`, set by `tauri-build` for the
+/// `generate_context!` macro, falling back to the `CARGO_CFG_TARGET_OS` and `PROFILE` variables Cargo sets on build scripts.
+/// The `${VAR}` placeholders are then resolved with the environment variables (see [`tauri_utils::config::interpolate`]).
 pub fn get_config(path: &Path) -> Result<(Config, PathBuf), ConfigError> {
   let path = if path.is_relative() {
     let cwd = std::env::current_dir().map_err(ConfigError::CurrentDir)?;
@@ -54,6 +82,12 @@ pub fn get_config(path: &Path) -> Result<(Config, PathBuf), ConfigError> {
     Cow::Borrowed(path)
   };
 
+  // this should be impossible because of the use of `current_dir()` above, but handle it anyways
+  let parent = path
+    .parent()
+    .map(ToOwned::to_owned)
+    .ok_or_else(|| ConfigError::Parent(path.clone().into_owned()))?;
+
   // in the future we may want to find a way to not need the TAURI_CONFIG env var so that
   // it is impossible for the content of two separate configs to get mixed up. The chances are
   // already unlikely unless the developer goes out of their way to run the cli on a different
@@ -61,25 +95,30 @@ pub fn get_config(path: &Path) -> Result<(Config, PathBuf), ConfigError> {
   let config = if let Ok(env) = std::env::var("TAURI_CONFIG") {
     serde_json::from_str(&env).map_err(ConfigError::FormatInline)?
   } else {
-    File::open(&path)
-      .map_err(|error| ConfigError::Io {
-        path: path.clone().into_owned(),
-        error,
-      })
-      .map(BufReader::new)
-      .and_then(|file| {
-        serde_json::from_reader(file).map_err(|error| ConfigError::Format {
-          path: path.clone().into_owned(),
-          error,
-        })
-      })?
+    let mut config = read_json(&path)?;
+    // Cargo only sets these variables on build scripts, so `tauri-build` forwards them to the proc macros
+    let platform = std::env::var("TAURI_TARGET_OS")
+      .or_else(|_| std::env::var("CARGO_CFG_TARGET_OS"))
+      .unwrap_or_else(|_| std::env::consts::OS.into());
+    let profile = std::env::var("TAURI_PROFILE")
+      .or_else(|_| std::env::var("PROFILE"))
+      .ok();
+    for name in overlay_file_names(&platform, profile.as_deref()) {
+      let overlay_path = parent.join(name);
+      if overlay_path.exists() {
+        let overlay = read_json(&overlay_path)?;
+        if !overlay.is_object() {
+          return Err(ConfigError::Overlay(overlay_path));
+        }
+        merge(&mut config, &overlay);
+      }
+    }
+    interpolate_json(&mut config).map_err(ConfigError::Interpolation)?;
+    serde_json::from_value(config).map_err(|error| ConfigError::Format {
+      path: path.into_owned(),
+      error,
+    })?
   };
 
-  // this should be impossible because of the use of `current_dir()` above, but handle it anyways
-  let parent = path
-    .parent()
-    .map(ToOwned::to_owned)
-    .ok_or_else(|| ConfigError::Parent(path.into_owned()))?;
-
   Ok((config, parent))
 }
@@ -6,10 +6,12 @@ use proc_macro::TokenStream;
 use proc_macro2::TokenStream as TokenStream2;
 use quote::{format_ident, quote};
 use syn::{
+  parenthesized,
   parse::{Parse, ParseBuffer},
   parse_macro_input,
+  punctuated::Punctuated,
   spanned::Spanned,
-  FnArg, Ident, ItemFn, Pat, Token, Visibility,
+  FnArg, Ident, ItemFn, LitStr, Pat, Token, Visibility,
 };
 
 /// The execution context of the command.
@@ -18,21 +20,44 @@ enum ExecutionContext {
   Blocking,
 }
 
-impl Parse for ExecutionContext {
+/// The attributes of the command, e.g. `#[command(async, windows("main"))]`.
+struct CommandAttributes {
+  context: ExecutionContext,
+  /// The labels of the windows allowed to call the command, or `None` if every window is allowed.
+  windows: Option<Vec<LitStr>>,
+}
+
+impl Parse for CommandAttributes {
   fn parse(input: &ParseBuffer) -> syn::Result<Self> {
-    if input.is_empty() {
-      return Ok(Self::Blocking);
+    let mut attributes = Self {
+      context: ExecutionContext::Blocking,
+      windows: None,
+    };
+
+    while !input.is_empty() {
+      if input.peek(Token![async]) {
+        input.parse::<Token![async]>()?;
+        attributes.context = ExecutionContext::Async;
+      } else {
+        let ident = input.parse::<Ident>()?;
+        if ident != "windows" {
+          return Err(syn::Error::new(
+            ident.span(),
+            "only the `async` and `windows(...)` items are currently allowed",
+          ));
+        }
+        let content;
+        parenthesized!(content in input);
+        let labels = Punctuated::<LitStr, Token![,]>::parse_terminated(&content)?;
+        attributes.windows = Some(labels.into_iter().collect());
+      }
+
+      if !input.is_empty() {
+        input.parse::<Token![,]>()?;
+      }
     }
 
-    input
-      .parse::<Token![async]>()
-      .map(|_| Self::Async)
-      .map_err(|_| {
-        syn::Error::new(
-          input.span(),
-          "only a single item `async` is currently allowed",
-        )
-      })
+    Ok(attributes)
   }
 }
 
@@ -60,14 +85,34 @@ pub fn wrapper(attributes: TokenStream, item: TokenStream) -> TokenStream {
   };
 
   // body to the command wrapper or a `compile_error!` of an error occurred while parsing it.
-  let body = syn::parse::<ExecutionContext>(attributes)
-    .map(|context| match function.sig.asyncness {
-      Some(_) => ExecutionContext::Async,
-      None => context,
-    })
-    .and_then(|context| match context {
-      ExecutionContext::Async => body_async(&function, &invoke),
-      ExecutionContext::Blocking => body_blocking(&function, &invoke),
+  let body = syn::parse::<CommandAttributes>(attributes)
+    .and_then(|attributes| {
+      let context = match function.sig.asyncness {
+        Some(_) => ExecutionContext::Async,
+        None => attributes.context,
+      };
+      let body = match context {
+        ExecutionContext::Async => body_async(&function, &invoke),
+        ExecutionContext::Blocking => body_blocking(&function, &invoke),
+      }?;
+      Ok(match attributes.windows {
+        Some(windows) => {
+          let Invoke { message, resolver } = &invoke;
+          let command = &function.sig.ident;
+          quote! {
+            if !::tauri::command::is_window_allowed(&#message, &[#(#windows),*]) {
+              return #resolver.reject(format!(
+                "command {} is not allowed on window {}",
+                stringify!(#command),
+                #message.window_ref().label()
+              ));
+            }
+
+            #body
+          }
+        }
+        None => body,
+      })
     })
     .unwrap_or_else(syn::Error::into_compile_error);
 
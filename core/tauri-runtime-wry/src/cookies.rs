@@ -0,0 +1,760 @@
+// Copyright 2019-2021 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+//! Native cookie stores of the webviews, including the `HttpOnly` cookies hidden from the page.
+//!
+//! The cookies are read and written asynchronously by the webview engines, so the results are sent once they're done.
+//! WebView2 isn't reachable from the window, so the cookies are unsupported on Windows.
+
+use tauri_runtime::{webview::Cookie, Error, Result};
+use url::Url;
+use wry::application::window::Window;
+
+use std::sync::mpsc::Sender;
+
+/// Sends the cookies sent on the requests to the URL of the page loaded on the webview.
+pub(crate) fn cookies(window: &Window, tx: Sender<Result<Vec<Cookie>>>) {
+  match crate::policy::webview_url(window) {
+    Some(url) => platform::cookies(window, &url, tx),
+    None => {
+      let _ = tx.send(Ok(Vec::new()));
+    }
+  }
+}
+
+/// Sets a cookie for the page loaded on the webview, sending whether it was set.
+pub(crate) fn set_cookie(window: &Window, cookie: Cookie, tx: Sender<Result<()>>) {
+  let result = validate_cookie(&cookie).and_then(|_| page_url(window));
+  match result {
+    Ok(url) => platform::set_cookie(window, &url, cookie, tx),
+    Err(e) => {
+      let _ = tx.send(Err(e));
+    }
+  }
+}
+
+/// Deletes the cookies with the given name and path sent to the page loaded on the webview.
+pub(crate) fn delete_cookie(
+  window: &Window,
+  name: String,
+  path: Option<String>,
+  tx: Sender<Result<()>>,
+) {
+  match page_url(window) {
+    Ok(url) => platform::delete_cookie(window, &url, name, path, tx),
+    Err(e) => {
+      let _ = tx.send(Err(e));
+    }
+  }
+}
+
+fn page_url(window: &Window) -> Result<Url> {
+  crate::policy::webview_url(window).ok_or_else(|| cookie_err("the webview has no page loaded"))
+}
+
+fn cookie_err<S: Into<String>>(message: S) -> Error {
+  Error::Cookie(message.into())
+}
+
+/// Checks that the cookie fields can't add attributes to the cookie, as defined by RFC 6265.
+pub(crate) fn validate_cookie(cookie: &Cookie) -> Result<()> {
+  let is_separator = |c: char| "()<>@,;:\\\"/[]?={} \t".contains(c);
+  if cookie.name.is_empty()
+    || !cookie
+      .name
+      .chars()
+      .all(|c| c.is_ascii_graphic() && !is_separator(c))
+  {
+    return Err(cookie_err(format!("invalid name `{}`", cookie.name)));
+  }
+  if !cookie
+    .value
+    .chars()
+    .all(|c| c.is_ascii_graphic() && !"\",;\\".contains(c))
+  {
+    return Err(cookie_err(format!("invalid value for `{}`", cookie.name)));
+  }
+  let is_attribute_value = |value: &Option<String>| {
+    value
+      .as_ref()
+      .map(|value| value.chars().all(|c| !c.is_control() && c != ';'))
+      .unwrap_or(true)
+  };
+  if !is_attribute_value(&cookie.domain)
+    || cookie
+      .domain
+      .as_ref()
+      .map(|domain| domain.is_empty() || domain.contains(char::is_whitespace))
+      .unwrap_or(false)
+  {
+    return Err(cookie_err(format!("invalid domain for `{}`", cookie.name)));
+  }
+  if !is_attribute_value(&cookie.path) {
+    return Err(cookie_err(format!("invalid path for `{}`", cookie.name)));
+  }
+  Ok(())
+}
+
+/// The domain stored with the cookie: domain cookies start with a dot, host-only cookies are set for the page host.
+#[cfg_attr(windows, allow(dead_code))]
+pub(crate) fn cookie_domain(cookie: &Cookie, url: &Url) -> Option<String> {
+  match &cookie.domain {
+    Some(domain) if domain.starts_with('.') => Some(domain.to_lowercase()),
+    Some(domain) => Some(format!(".{}", domain.to_lowercase())),
+    None => url.host_str().map(Into::into),
+  }
+}
+
+/// The default path of the cookies set for the URL, as defined by RFC 6265.
+#[cfg_attr(windows, allow(dead_code))]
+pub(crate) fn default_path(url: &Url) -> String {
+  let path = url.path();
+  match path.rfind('/') {
+    Some(index) if index > 0 => path[..index].into(),
+    _ => "/".into(),
+  }
+}
+
+/// Whether a stored cookie is sent on the requests to the URL, as defined by RFC 6265.
+#[cfg_attr(not(target_os = "macos"), allow(dead_code))]
+pub(crate) fn cookie_matches(cookie: &Cookie, url: &Url) -> bool {
+  let host = match url.host_str() {
+    Some(host) => host.to_lowercase(),
+    None => return false,
+  };
+  let domain_matches = match &cookie.domain {
+    Some(domain) if domain.starts_with('.') => {
+      host == domain[1..].to_lowercase() || host.ends_with(&domain.to_lowercase())
+    }
+    Some(domain) => host == domain.to_lowercase(),
+    None => true,
+  };
+  let path = cookie.path.as_deref().unwrap_or("/");
+  let request_path = url.path();
+  let path_matches = request_path == path
+    || (request_path.starts_with(path)
+      && (path.ends_with('/') || request_path[path.len()..].starts_with('/')));
+  domain_matches && path_matches && (!cookie.secure || url.scheme() == "https")
+}
+
+/// Whether a stored cookie is deleted by [`delete_cookie`]. The path defaults to `/`.
+#[cfg_attr(windows, allow(dead_code))]
+fn is_deleted(cookie: &Cookie, name: &str, path: Option<&str>) -> bool {
+  cookie.name == name && cookie.path.as_deref().unwrap_or("/") == path.unwrap_or("/")
+}
+
+#[cfg(target_os = "macos")]
+mod platform {
+  use super::{cookie_domain, cookie_err, cookie_matches, default_path, is_deleted};
+  use super::{Cookie, Result, Sender, Url, Window};
+  use crate::native::{ns_string, webview};
+
+  use block::ConcreteBlock;
+  use cocoa::base::{id, nil, BOOL, YES};
+  use objc::{class, msg_send, sel, sel_impl};
+
+  use std::{cell::Cell, ffi::CStr, os::raw::c_char, rc::Rc};
+
+  #[link(name = "Foundation", kind = "framework")]
+  extern "C" {
+    static NSHTTPCookieName: id;
+    static NSHTTPCookieValue: id;
+    static NSHTTPCookieDomain: id;
+    static NSHTTPCookiePath: id;
+    static NSHTTPCookieMaxAge: id;
+    static NSHTTPCookieSecure: id;
+  }
+
+  /// The property of the `HttpOnly` flag, which has no constant.
+  const HTTP_ONLY_PROPERTY: &str = "HttpOnly";
+
+  unsafe fn to_string(string: id) -> Option<String> {
+    if string == nil {
+      return None;
+    }
+    let string: *const c_char = msg_send![string, UTF8String];
+    if string.is_null() {
+      None
+    } else {
+      Some(CStr::from_ptr(string).to_string_lossy().into_owned())
+    }
+  }
+
+  unsafe fn cookie_store(window: &Window) -> Result<id> {
+    let webview = webview(window)?;
+    let configuration: id = msg_send![webview, configuration];
+    let data_store: id = msg_send![configuration, websiteDataStore];
+    Ok(msg_send![data_store, httpCookieStore])
+  }
+
+  unsafe fn from_ns_cookie(cookie: id) -> Cookie {
+    let secure: BOOL = msg_send![cookie, isSecure];
+    let http_only: BOOL = msg_send![cookie, isHTTPOnly];
+    Cookie {
+      name: to_string(msg_send![cookie, name]).unwrap_or_default(),
+      value: to_string(msg_send![cookie, value]).unwrap_or_default(),
+      domain: to_string(msg_send![cookie, domain]),
+      path: to_string(msg_send![cookie, path]),
+      max_age: None,
+      secure: secure == YES,
+      http_only: http_only == YES,
+    }
+  }
+
+  /// Calls the handler with the stored cookies sent to the URL, and their `NSHTTPCookie`.
+  unsafe fn get_cookies<F: Fn(Vec<(Cookie, id)>) + 'static>(
+    window: &Window,
+    url: &Url,
+    handler: F,
+  ) -> Result<()> {
+    let store = cookie_store(window)?;
+    let url = url.clone();
+    let completion_handler = ConcreteBlock::new(move |ns_cookies: id| {
+      let count: usize = msg_send![ns_cookies, count];
+      let cookies = (0..count)
+        .map(|index| {
+          let ns_cookie: id = msg_send![ns_cookies, objectAtIndex: index];
+          (from_ns_cookie(ns_cookie), ns_cookie)
+        })
+        .filter(|(cookie, _)| cookie_matches(cookie, &url))
+        .collect();
+      handler(cookies);
+    })
+    .copy();
+    let _: () = msg_send![store, getAllCookies: &*completion_handler];
+    Ok(())
+  }
+
+  pub fn cookies(window: &Window, url: &Url, tx: Sender<Result<Vec<Cookie>>>) {
+    let tx_ = tx.clone();
+    let result = unsafe {
+      get_cookies(window, url, move |cookies| {
+        let _ = tx_.send(Ok(cookies.into_iter().map(|(cookie, _)| cookie).collect()));
+      })
+    };
+    if let Err(e) = result {
+      let _ = tx.send(Err(e));
+    }
+  }
+
+  pub fn set_cookie(window: &Window, url: &Url, cookie: Cookie, tx: Sender<Result<()>>) {
+    let result = unsafe {
+      cookie_store(window).and_then(|store| {
+        let properties: id = msg_send![class!(NSMutableDictionary), dictionary];
+        let set = |key: id, value: &str| {
+          let _: () = msg_send![properties, setObject: ns_string(value) forKey: key];
+        };
+        set(NSHTTPCookieName, &cookie.name);
+        set(NSHTTPCookieValue, &cookie.value);
+        set(
+          NSHTTPCookieDomain,
+          &cookie_domain(&cookie, url).unwrap_or_default(),
+        );
+        set(
+          NSHTTPCookiePath,
+          &cookie.path.clone().unwrap_or_else(|| default_path(url)),
+        );
+        if let Some(max_age) = cookie.max_age {
+          set(NSHTTPCookieMaxAge, &max_age.to_string());
+        }
+        if cookie.secure {
+          set(NSHTTPCookieSecure, "TRUE");
+        }
+        if cookie.http_only {
+          set(ns_string(HTTP_ONLY_PROPERTY), "TRUE");
+        }
+        let ns_cookie: id = msg_send![class!(NSHTTPCookie), cookieWithProperties: properties];
+        if ns_cookie == nil {
+          return Err(cookie_err(format!("invalid cookie `{}`", cookie.name)));
+        }
+        let tx = tx.clone();
+        let completion_handler = ConcreteBlock::new(move || {
+          let _ = tx.send(Ok(()));
+        })
+        .copy();
+        let _: () = msg_send![store, setCookie: ns_cookie completionHandler: &*completion_handler];
+        Ok(())
+      })
+    };
+    if let Err(e) = result {
+      let _ = tx.send(Err(e));
+    }
+  }
+
+  pub fn delete_cookie(
+    window: &Window,
+    url: &Url,
+    name: String,
+    path: Option<String>,
+    tx: Sender<Result<()>>,
+  ) {
+    let tx_ = tx.clone();
+    let result = unsafe {
+      let store = cookie_store(window);
+      store.and_then(|store| {
+        get_cookies(window, url, move |cookies| {
+          let deleted: Vec<id> = cookies
+            .into_iter()
+            .filter(|(cookie, _)| is_deleted(cookie, &name, path.as_deref()))
+            .map(|(_, ns_cookie)| ns_cookie)
+            .collect();
+          if deleted.is_empty() {
+            let _ = tx_.send(Ok(()));
+            return;
+          }
+          // the result is sent once all the cookies are deleted
+          let pending = Rc::new(Cell::new(deleted.len()));
+          for ns_cookie in deleted {
+            let pending = pending.clone();
+            let tx = tx_.clone();
+            let completion_handler = ConcreteBlock::new(move || {
+              pending.set(pending.get() - 1);
+              if pending.get() == 0 {
+                let _ = tx.send(Ok(()));
+              }
+            })
+            .copy();
+            let _: () =
+              msg_send![store, deleteCookie: ns_cookie completionHandler: &*completion_handler];
+          }
+        })
+      })
+    };
+    if let Err(e) = result {
+      let _ = tx.send(Err(e));
+    }
+  }
+}
+
+#[cfg(target_os = "linux")]
+mod platform {
+  use super::{cookie_domain, cookie_err, default_path, is_deleted};
+  use super::{Cookie, Error, Result, Sender, Url, Window};
+  use crate::native::webview;
+
+  use glib::object::ObjectType;
+  use webkit2gtk::{WebContextExt, WebViewExt};
+
+  use std::{
+    cell::Cell,
+    convert::TryFrom,
+    ffi::{CStr, CString},
+    os::raw::{c_char, c_int, c_void},
+    ptr,
+    rc::Rc,
+  };
+
+  /// `GList`.
+  #[repr(C)]
+  struct GList {
+    data: *mut c_void,
+    next: *mut GList,
+    prev: *mut GList,
+  }
+
+  /// `GError`.
+  #[repr(C)]
+  struct GError {
+    domain: u32,
+    code: c_int,
+    message: *mut c_char,
+  }
+
+  /// `GAsyncReadyCallback`.
+  type AsyncReadyCallback =
+    unsafe extern "C" fn(source: *mut c_void, result: *mut c_void, user_data: *mut c_void);
+
+  // the cookie store functions were added on WebKitGTK 2.20 and aren't bound by `webkit2gtk`
+  extern "C" {
+    fn webkit_cookie_manager_get_cookies(
+      manager: *mut c_void,
+      uri: *const c_char,
+      cancellable: *mut c_void,
+      callback: AsyncReadyCallback,
+      user_data: *mut c_void,
+    );
+    fn webkit_cookie_manager_get_cookies_finish(
+      manager: *mut c_void,
+      result: *mut c_void,
+      error: *mut *mut GError,
+    ) -> *mut GList;
+    fn webkit_cookie_manager_add_cookie(
+      manager: *mut c_void,
+      cookie: *mut c_void,
+      cancellable: *mut c_void,
+      callback: AsyncReadyCallback,
+      user_data: *mut c_void,
+    );
+    fn webkit_cookie_manager_add_cookie_finish(
+      manager: *mut c_void,
+      result: *mut c_void,
+      error: *mut *mut GError,
+    ) -> c_int;
+    fn webkit_cookie_manager_delete_cookie(
+      manager: *mut c_void,
+      cookie: *mut c_void,
+      cancellable: *mut c_void,
+      callback: AsyncReadyCallback,
+      user_data: *mut c_void,
+    );
+    fn webkit_cookie_manager_delete_cookie_finish(
+      manager: *mut c_void,
+      result: *mut c_void,
+      error: *mut *mut GError,
+    ) -> c_int;
+    fn soup_cookie_new(
+      name: *const c_char,
+      value: *const c_char,
+      domain: *const c_char,
+      path: *const c_char,
+      max_age: c_int,
+    ) -> *mut c_void;
+    fn soup_cookie_get_name(cookie: *mut c_void) -> *const c_char;
+    fn soup_cookie_get_value(cookie: *mut c_void) -> *const c_char;
+    fn soup_cookie_get_domain(cookie: *mut c_void) -> *const c_char;
+    fn soup_cookie_get_path(cookie: *mut c_void) -> *const c_char;
+    fn soup_cookie_get_secure(cookie: *mut c_void) -> c_int;
+    fn soup_cookie_get_http_only(cookie: *mut c_void) -> c_int;
+    fn soup_cookie_set_secure(cookie: *mut c_void, secure: c_int);
+    fn soup_cookie_set_http_only(cookie: *mut c_void, http_only: c_int);
+    fn soup_cookie_free(cookie: *mut c_void);
+    fn g_list_free(list: *mut GList);
+    fn g_error_free(error: *mut GError);
+  }
+
+  /// The cookie manager of the web context of the webview, which owns it.
+  fn cookie_manager(window: &Window) -> Result<*mut c_void> {
+    webview(window)?
+      .get_context()
+      .and_then(|context| context.get_cookie_manager())
+      .map(|manager| manager.as_ptr() as *mut c_void)
+      .ok_or(Error::WebviewNotFound)
+  }
+
+  unsafe fn to_string(string: *const c_char) -> Option<String> {
+    if string.is_null() {
+      None
+    } else {
+      Some(CStr::from_ptr(string).to_string_lossy().into_owned())
+    }
+  }
+
+  unsafe fn take_error(error: *mut GError) -> Error {
+    let message = to_string((*error).message).unwrap_or_default();
+    g_error_free(error);
+    cookie_err(message)
+  }
+
+  fn c_string(string: &str) -> CString {
+    // the cookie fields are validated and the URL can't contain a NUL character
+    CString::new(string).unwrap()
+  }
+
+  unsafe fn from_soup_cookie(cookie: *mut c_void) -> Cookie {
+    Cookie {
+      name: to_string(soup_cookie_get_name(cookie)).unwrap_or_default(),
+      value: to_string(soup_cookie_get_value(cookie)).unwrap_or_default(),
+      domain: to_string(soup_cookie_get_domain(cookie)),
+      path: to_string(soup_cookie_get_path(cookie)),
+      max_age: None,
+      secure: soup_cookie_get_secure(cookie) != 0,
+      http_only: soup_cookie_get_http_only(cookie) != 0,
+    }
+  }
+
+  /// Calls the handler with the stored cookies sent to the URL, and their `SoupCookie` freed after the call.
+  fn get_cookies<F: FnOnce(Result<Vec<(Cookie, *mut c_void)>>) + 'static>(
+    manager: *mut c_void,
+    url: &Url,
+    handler: F,
+  ) {
+    unsafe extern "C" fn callback<F: FnOnce(Result<Vec<(Cookie, *mut c_void)>>)>(
+      manager: *mut c_void,
+      result: *mut c_void,
+      user_data: *mut c_void,
+    ) {
+      let handler = Box::from_raw(user_data as *mut F);
+      let mut error = ptr::null_mut();
+      let list = webkit_cookie_manager_get_cookies_finish(manager, result, &mut error);
+      if !error.is_null() {
+        handler(Err(take_error(error)));
+        return;
+      }
+      let mut soup_cookies = Vec::new();
+      let mut node = list;
+      while !node.is_null() {
+        soup_cookies.push((*node).data);
+        node = (*node).next;
+      }
+      g_list_free(list);
+      handler(Ok(
+        soup_cookies
+          .iter()
+          .map(|soup_cookie| (from_soup_cookie(*soup_cookie), *soup_cookie))
+          .collect(),
+      ));
+      for soup_cookie in soup_cookies {
+        soup_cookie_free(soup_cookie);
+      }
+    }
+    let url = c_string(url.as_str());
+    unsafe {
+      webkit_cookie_manager_get_cookies(
+        manager,
+        url.as_ptr(),
+        ptr::null_mut(),
+        callback::<F>,
+        Box::into_raw(Box::new(handler)) as *mut c_void,
+      );
+    }
+  }
+
+  /// The callback of the cookie store functions returning whether they succeeded.
+  unsafe extern "C" fn finish_callback(
+    manager: *mut c_void,
+    result: *mut c_void,
+    user_data: *mut c_void,
+  ) {
+    let (finish, handler) = *Box::from_raw(
+      user_data
+        as *mut (
+          unsafe extern "C" fn(*mut c_void, *mut c_void, *mut *mut GError) -> c_int,
+          Box<dyn FnOnce(Result<()>)>,
+        ),
+    );
+    let mut error = ptr::null_mut();
+    if finish(manager, result, &mut error) == 0 && !error.is_null() {
+      handler(Err(take_error(error)));
+    } else {
+      handler(Ok(()));
+    }
+  }
+
+  pub fn cookies(window: &Window, url: &Url, tx: Sender<Result<Vec<Cookie>>>) {
+    match cookie_manager(window) {
+      Ok(manager) => get_cookies(manager, url, move |cookies| {
+        let _ =
+          tx.send(cookies.map(|cookies| cookies.into_iter().map(|(cookie, _)| cookie).collect()));
+      }),
+      Err(e) => {
+        let _ = tx.send(Err(e));
+      }
+    }
+  }
+
+  pub fn set_cookie(window: &Window, url: &Url, cookie: Cookie, tx: Sender<Result<()>>) {
+    let manager = match cookie_manager(window) {
+      Ok(manager) => manager,
+      Err(e) => {
+        let _ = tx.send(Err(e));
+        return;
+      }
+    };
+    let name = c_string(&cookie.name);
+    let value = c_string(&cookie.value);
+    let domain = c_string(&cookie_domain(&cookie, url).unwrap_or_default());
+    let path = c_string(&cookie.path.clone().unwrap_or_else(|| default_path(url)));
+    // -1 creates a session cookie
+    let max_age = cookie
+      .max_age
+      .map(|max_age| c_int::try_from(max_age.max(0)).unwrap_or(c_int::MAX))
+      .unwrap_or(-1);
+    let handler: Box<dyn FnOnce(Result<()>)> = Box::new(move |result| {
+      let _ = tx.send(result);
+    });
+    unsafe {
+      let soup_cookie = soup_cookie_new(
+        name.as_ptr(),
+        value.as_ptr(),
+        domain.as_ptr(),
+        path.as_ptr(),
+        max_age,
+      );
+      soup_cookie_set_secure(soup_cookie, cookie.secure as c_int);
+      soup_cookie_set_http_only(soup_cookie, cookie.http_only as c_int);
+      let finish: unsafe extern "C" fn(*mut c_void, *mut c_void, *mut *mut GError) -> c_int =
+        webkit_cookie_manager_add_cookie_finish;
+      webkit_cookie_manager_add_cookie(
+        manager,
+        soup_cookie,
+        ptr::null_mut(),
+        finish_callback,
+        Box::into_raw(Box::new((finish, handler))) as *mut c_void,
+      );
+      // the cookie is copied by the cookie manager
+      soup_cookie_free(soup_cookie);
+    }
+  }
+
+  pub fn delete_cookie(
+    window: &Window,
+    url: &Url,
+    name: String,
+    path: Option<String>,
+    tx: Sender<Result<()>>,
+  ) {
+    let manager = match cookie_manager(window) {
+      Ok(manager) => manager,
+      Err(e) => {
+        let _ = tx.send(Err(e));
+        return;
+      }
+    };
+    get_cookies(manager, url, move |cookies| {
+      let deleted: Vec<*mut c_void> = match cookies {
+        Ok(cookies) => cookies
+          .into_iter()
+          .filter(|(cookie, _)| is_deleted(cookie, &name, path.as_deref()))
+          .map(|(_, soup_cookie)| soup_cookie)
+          .collect(),
+        Err(e) => {
+          let _ = tx.send(Err(e));
+          return;
+        }
+      };
+      if deleted.is_empty() {
+        let _ = tx.send(Ok(()));
+        return;
+      }
+      // the result is sent once all the cookies are deleted
+      let pending = Rc::new(Cell::new(deleted.len()));
+      for soup_cookie in deleted {
+        let pending = pending.clone();
+        let tx = tx.clone();
+        let handler: Box<dyn FnOnce(Result<()>)> = Box::new(move |result| {
+          pending.set(pending.get() - 1);
+          if result.is_err() || pending.get() == 0 {
+            let _ = tx.send(result);
+          }
+        });
+        let finish: unsafe extern "C" fn(*mut c_void, *mut c_void, *mut *mut GError) -> c_int =
+          webkit_cookie_manager_delete_cookie_finish;
+        unsafe {
+          webkit_cookie_manager_delete_cookie(
+            manager,
+            soup_cookie,
+            ptr::null_mut(),
+            finish_callback,
+            Box::into_raw(Box::new((finish, handler))) as *mut c_void,
+          );
+        }
+      }
+    });
+  }
+}
+
+#[cfg(windows)]
+mod platform {
+  use super::{Cookie, Error, Result, Sender, Url, Window};
+
+  fn unsupported() -> Error {
+    Error::Unsupported("the webview cookies on Windows".into())
+  }
+
+  pub fn cookies(_window: &Window, _url: &Url, tx: Sender<Result<Vec<Cookie>>>) {
+    let _ = tx.send(Err(unsupported()));
+  }
+
+  pub fn set_cookie(_window: &Window, _url: &Url, _cookie: Cookie, tx: Sender<Result<()>>) {
+    let _ = tx.send(Err(unsupported()));
+  }
+
+  pub fn delete_cookie(
+    _window: &Window,
+    _url: &Url,
+    _name: String,
+    _path: Option<String>,
+    tx: Sender<Result<()>>,
+  ) {
+    let _ = tx.send(Err(unsupported()));
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn url(url: &str) -> Url {
+    Url::parse(url).unwrap()
+  }
+
+  #[test]
+  fn rejects_cookies_injecting_attributes() {
+    assert!(validate_cookie(&Cookie::new("session", "abc123")).is_ok());
+    assert!(validate_cookie(&Cookie::new("", "abc123")).is_err());
+    assert!(validate_cookie(&Cookie::new("a=b", "c")).is_err());
+    assert!(validate_cookie(&Cookie::new("session", "abc; HttpOnly")).is_err());
+    assert!(validate_cookie(&Cookie::new("session", "a b")).is_err());
+    assert!(validate_cookie(&Cookie {
+      domain: Some("example.com; secure".into()),
+      ..Cookie::new("session", "abc")
+    })
+    .is_err());
+    assert!(validate_cookie(&Cookie {
+      path: Some("/app\n".into()),
+      ..Cookie::new("session", "abc")
+    })
+    .is_err());
+  }
+
+  #[test]
+  fn sets_the_cookie_domain_and_path() {
+    let page = url("https://app.example.com/docs/page.html");
+    assert_eq!(
+      cookie_domain(&Cookie::new("a", "b"), &page),
+      Some("app.example.com".into())
+    );
+    assert_eq!(
+      cookie_domain(
+        &Cookie {
+          domain: Some("Example.com".into()),
+          ..Cookie::new("a", "b")
+        },
+        &page
+      ),
+      Some(".example.com".into())
+    );
+    assert_eq!(default_path(&page), "/docs");
+    assert_eq!(default_path(&url("https://example.com/")), "/");
+    assert_eq!(default_path(&url("https://example.com/page")), "/");
+  }
+
+  #[test]
+  fn matches_the_cookies_sent_to_the_url() {
+    let cookie = |domain: &str, path: &str, secure: bool| Cookie {
+      domain: Some(domain.into()),
+      path: Some(path.into()),
+      secure,
+      ..Cookie::new("a", "b")
+    };
+    let page = url("https://app.example.com/docs/page.html");
+    assert!(cookie_matches(&cookie("app.example.com", "/", true), &page));
+    assert!(cookie_matches(
+      &cookie(".example.com", "/docs", false),
+      &page
+    ));
+    assert!(!cookie_matches(&cookie("example.com", "/", false), &page));
+    assert!(!cookie_matches(&cookie(".other.com", "/", false), &page));
+    assert!(!cookie_matches(
+      &cookie("app.example.com", "/doc", false),
+      &page
+    ));
+    assert!(!cookie_matches(
+      &cookie("app.example.com", "/", true),
+      &url("http://app.example.com/")
+    ));
+  }
+
+  #[test]
+  fn deletes_the_cookies_with_the_name_and_path() {
+    let cookie = Cookie {
+      path: Some("/".into()),
+      ..Cookie::new("session", "abc")
+    };
+    assert!(is_deleted(&cookie, "session", None));
+    assert!(is_deleted(&cookie, "session", Some("/")));
+    assert!(!is_deleted(&cookie, "session", Some("/docs")));
+    assert!(!is_deleted(&cookie, "other", None));
+  }
+}
@@ -0,0 +1,442 @@
+// Copyright 2019-2021 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+//! Native drag and drop of files out of the windows.
+//!
+//! The drag is started on the event loop like the window operations, so the mouse button may be released
+//! by the time it's handled: it fails instead of starting a drag that follows the cursor without a button pressed.
+
+use tauri_runtime::{window::DragItem, Error, Result};
+use wry::application::window::Window;
+
+use std::sync::mpsc::Sender;
+
+/// Starts dragging the item out of the window, sending whether it started.
+///
+/// On Windows the result is sent before the modal loop of the drag, which returns when the item is dropped.
+pub(crate) fn start_drag(window: &Window, item: &DragItem, tx: Sender<Result<()>>) {
+  platform::start_drag(window, item, |result| {
+    let _ = tx.send(result);
+  });
+}
+
+fn drag_err<S: Into<String>>(message: S) -> Error {
+  Error::StartDrag(message.into())
+}
+
+fn button_released() -> Error {
+  drag_err("the mouse button was released")
+}
+
+fn no_file() -> Error {
+  drag_err("none of the files can be dragged")
+}
+
+#[cfg(target_os = "macos")]
+mod platform {
+  use super::{button_released, no_file, DragItem, Result, Window};
+
+  use cocoa::{
+    base::{id, nil},
+    foundation::{NSPoint, NSRect, NSSize, NSString},
+  };
+  use objc::{
+    class,
+    declare::ClassDecl,
+    msg_send,
+    runtime::{Class, Object, Sel},
+    sel, sel_impl,
+  };
+  use wry::application::platform::macos::WindowExtMacOS;
+
+  /// `NSDragOperationCopy`.
+  const DRAG_OPERATION_COPY: usize = 1;
+  /// `NSEventTypeLeftMouseDown`.
+  const LEFT_MOUSE_DOWN: usize = 1;
+  /// The size of the file icons shown under the cursor.
+  const ICON_SIZE: f64 = 64.0;
+  /// The largest side of the image previews shown under the cursor.
+  const IMAGE_PREVIEW_SIZE: f64 = 128.0;
+
+  extern "C" fn source_operation_mask(
+    _this: &Object,
+    _cmd: Sel,
+    _session: id,
+    _context: isize,
+  ) -> usize {
+    DRAG_OPERATION_COPY
+  }
+
+  /// The `NSDraggingSource` of the drag sessions, which only allows copying the files.
+  fn drag_source_class() -> &'static Class {
+    if let Some(class) = Class::get("TauriDragSource") {
+      return class;
+    }
+    let mut decl = ClassDecl::new("TauriDragSource", class!(NSObject))
+      .expect("failed to declare the drag source class");
+    unsafe {
+      decl.add_method(
+        sel!(draggingSession:sourceOperationMaskForDraggingContext:),
+        source_operation_mask as extern "C" fn(&Object, Sel, id, isize) -> usize,
+      );
+    }
+    decl.register()
+  }
+
+  pub fn start_drag<F: FnOnce(Result<()>)>(window: &Window, item: &DragItem, started: F) {
+    unsafe {
+      let ns_window = window.ns_window() as id;
+      let view = window.ns_view() as id;
+      let buttons: usize = msg_send![class!(NSEvent), pressedMouseButtons];
+      if buttons & 1 == 0 {
+        return started(Err(button_released()));
+      }
+      let mouse: NSPoint = msg_send![ns_window, mouseLocationOutsideOfEventStream];
+      let location: NSPoint = msg_send![view, convertPoint: mouse fromView: nil];
+      // the event of the `mousedown` was already handled by the webview, so the session starts from a new one
+      let window_number: isize = msg_send![ns_window, windowNumber];
+      let process_info: id = msg_send![class!(NSProcessInfo), processInfo];
+      let timestamp: f64 = msg_send![process_info, systemUptime];
+      let event: id = msg_send![
+        class!(NSEvent),
+        mouseEventWithType: LEFT_MOUSE_DOWN
+        location: mouse
+        modifierFlags: 0usize
+        timestamp: timestamp
+        windowNumber: window_number
+        context: nil
+        eventNumber: 0isize
+        clickCount: 1isize
+        pressure: 1.0f32
+      ];
+
+      let items: id = msg_send![class!(NSMutableArray), array];
+      for path in item.paths() {
+        let path = match path.to_str() {
+          Some(path) => NSString::alloc(nil).init_str(path),
+          None => continue,
+        };
+        let url: id = msg_send![class!(NSURL), fileURLWithPath: path];
+        let (preview, size) = match item {
+          DragItem::Image(_) => {
+            let image: id = msg_send![class!(NSImage), alloc];
+            let image: id = msg_send![image, initWithContentsOfFile: path];
+            if image == nil {
+              continue;
+            }
+            let size: NSSize = msg_send![image, size];
+            let scale = (IMAGE_PREVIEW_SIZE / size.width.max(size.height)).min(1.0);
+            (image, NSSize::new(size.width * scale, size.height * scale))
+          }
+          _ => {
+            let workspace: id = msg_send![class!(NSWorkspace), sharedWorkspace];
+            let icon: id = msg_send![workspace, iconForFile: path];
+            (icon, NSSize::new(ICON_SIZE, ICON_SIZE))
+          }
+        };
+        let dragging_item: id = msg_send![class!(NSDraggingItem), alloc];
+        let dragging_item: id = msg_send![dragging_item, initWithPasteboardWriter: url];
+        let frame = NSRect::new(
+          NSPoint::new(
+            location.x - size.width / 2.0,
+            location.y - size.height / 2.0,
+          ),
+          size,
+        );
+        let _: () = msg_send![dragging_item, setDraggingFrame: frame contents: preview];
+        let _: () = msg_send![items, addObject: dragging_item];
+        let _: () = msg_send![dragging_item, release];
+      }
+
+      let count: usize = msg_send![items, count];
+      if count == 0 {
+        return started(Err(no_file()));
+      }
+      // the session doesn't retain its source, so it lives for the whole app
+      let source: id = msg_send![drag_source_class(), new];
+      let _: id = msg_send![view, beginDraggingSessionWithItems: items event: event source: source];
+      started(Ok(()));
+    }
+  }
+}
+
+#[cfg(windows)]
+mod platform {
+  use super::{button_released, drag_err, no_file, DragItem, Result, Window};
+
+  use winapi::{
+    shared::{
+      guiddef::{IsEqualGUID, GUID, REFIID},
+      minwindef::{BOOL, DWORD, ULONG},
+      winerror::{
+        DRAGDROP_S_CANCEL, DRAGDROP_S_DROP, DRAGDROP_S_USEDEFAULTCURSORS, E_NOINTERFACE, S_OK,
+      },
+    },
+    um::{
+      combaseapi::CoTaskMemFree,
+      objidl::IDataObject,
+      ole2::DoDragDrop,
+      oleidl::{IDropSource, IDropSourceVtbl, DROPEFFECT_COPY},
+      shobjidl_core::IShellItemArray,
+      unknwnbase::{IUnknown, IUnknownVtbl},
+      winnt::HRESULT,
+      winuser::{
+        GetAsyncKeyState, GetSystemMetrics, MK_LBUTTON, SM_SWAPBUTTON, VK_LBUTTON, VK_RBUTTON,
+      },
+    },
+    Interface,
+  };
+
+  use std::{
+    ffi::c_void,
+    iter::once,
+    os::windows::ffi::OsStrExt,
+    ptr::null_mut,
+    sync::atomic::{AtomicU32, Ordering},
+  };
+
+  /// `BHID_DataObject`, binding a shell item array to the `IDataObject` the file managers accept.
+  const BHID_DATA_OBJECT: GUID = GUID {
+    Data1: 0xb8c0_bd9f,
+    Data2: 0xed24,
+    Data3: 0x455c,
+    Data4: [0x83, 0xe6, 0xd5, 0x39, 0x0c, 0x4f, 0xe8, 0xc4],
+  };
+
+  #[link(name = "shell32")]
+  extern "system" {
+    fn SHParseDisplayName(
+      name: *const u16,
+      bind_context: *mut c_void,
+      pidl: *mut *mut c_void,
+      attributes_in: u32,
+      attributes_out: *mut u32,
+    ) -> HRESULT;
+    fn SHCreateShellItemArrayFromIDLists(
+      count: u32,
+      pidls: *const *const c_void,
+      items: *mut *mut IShellItemArray,
+    ) -> HRESULT;
+  }
+
+  /// The `IDropSource` of the drag operations: drops on mouse release and cancels on escape.
+  #[repr(C)]
+  struct DropSource {
+    vtbl: *const IDropSourceVtbl,
+    refs: AtomicU32,
+  }
+
+  static DROP_SOURCE_VTBL: IDropSourceVtbl = IDropSourceVtbl {
+    parent: IUnknownVtbl {
+      QueryInterface: query_interface,
+      AddRef: add_ref,
+      Release: release,
+    },
+    QueryContinueDrag: query_continue_drag,
+    GiveFeedback: give_feedback,
+  };
+
+  unsafe extern "system" fn query_interface(
+    this: *mut IUnknown,
+    riid: REFIID,
+    object: *mut *mut c_void,
+  ) -> HRESULT {
+    if IsEqualGUID(&*riid, &IUnknown::uuidof()) || IsEqualGUID(&*riid, &IDropSource::uuidof()) {
+      add_ref(this);
+      *object = this as *mut c_void;
+      S_OK
+    } else {
+      *object = null_mut();
+      E_NOINTERFACE
+    }
+  }
+
+  unsafe extern "system" fn add_ref(this: *mut IUnknown) -> ULONG {
+    (*(this as *mut DropSource))
+      .refs
+      .fetch_add(1, Ordering::SeqCst)
+      + 1
+  }
+
+  unsafe extern "system" fn release(this: *mut IUnknown) -> ULONG {
+    let refs = (*(this as *mut DropSource))
+      .refs
+      .fetch_sub(1, Ordering::SeqCst)
+      - 1;
+    if refs == 0 {
+      drop(Box::from_raw(this as *mut DropSource));
+    }
+    refs
+  }
+
+  unsafe extern "system" fn query_continue_drag(
+    _this: *mut IDropSource,
+    escape_pressed: BOOL,
+    key_state: DWORD,
+  ) -> HRESULT {
+    if escape_pressed != 0 {
+      DRAGDROP_S_CANCEL
+    } else if key_state & MK_LBUTTON as DWORD == 0 {
+      DRAGDROP_S_DROP
+    } else {
+      S_OK
+    }
+  }
+
+  unsafe extern "system" fn give_feedback(_this: *mut IDropSource, _effect: DWORD) -> HRESULT {
+    DRAGDROP_S_USEDEFAULTCURSORS
+  }
+
+  /// Creates the shell data object of the files, as the Explorer does.
+  unsafe fn data_object(item: &DragItem) -> Option<*mut IDataObject> {
+    let mut pidls = Vec::new();
+    for path in item.paths() {
+      let name: Vec<u16> = path.as_os_str().encode_wide().chain(once(0)).collect();
+      let mut pidl = null_mut();
+      if SHParseDisplayName(name.as_ptr(), null_mut(), &mut pidl, 0, null_mut()) == S_OK {
+        pidls.push(pidl as *const c_void);
+      }
+    }
+    if pidls.is_empty() {
+      return None;
+    }
+
+    let mut items: *mut IShellItemArray = null_mut();
+    let result = SHCreateShellItemArrayFromIDLists(pidls.len() as u32, pidls.as_ptr(), &mut items);
+    for pidl in pidls {
+      CoTaskMemFree(pidl as *mut c_void);
+    }
+    if result != S_OK {
+      return None;
+    }
+
+    let mut data_object: *mut IDataObject = null_mut();
+    let result = (*items).BindToHandler(
+      null_mut(),
+      &BHID_DATA_OBJECT,
+      &IDataObject::uuidof(),
+      &mut data_object as *mut *mut IDataObject as *mut *mut c_void,
+    );
+    (*items).Release();
+    if result == S_OK {
+      Some(data_object)
+    } else {
+      None
+    }
+  }
+
+  pub fn start_drag<F: FnOnce(Result<()>)>(_window: &Window, item: &DragItem, started: F) {
+    unsafe {
+      // the left button is the secondary one when the buttons are swapped
+      let button = if GetSystemMetrics(SM_SWAPBUTTON) != 0 {
+        VK_RBUTTON
+      } else {
+        VK_LBUTTON
+      };
+      if GetAsyncKeyState(button) as u16 & 0x8000 == 0 {
+        return started(Err(button_released()));
+      }
+      if item.paths().is_empty() {
+        return started(Err(no_file()));
+      }
+      let data_object = match data_object(item) {
+        Some(data_object) => data_object,
+        None => {
+          return started(Err(drag_err(
+            "failed to create the data object of the files",
+          )))
+        }
+      };
+      let source = Box::into_raw(Box::new(DropSource {
+        vtbl: &DROP_SOURCE_VTBL,
+        refs: AtomicU32::new(1),
+      }));
+      let mut effect = 0;
+      started(Ok(()));
+      // runs a modal loop until the files are dropped
+      DoDragDrop(
+        data_object,
+        source as *mut IDropSource,
+        DROPEFFECT_COPY,
+        &mut effect,
+      );
+      (*data_object).Release();
+      release(source as *mut IUnknown);
+    }
+  }
+}
+
+#[cfg(target_os = "linux")]
+mod platform {
+  use super::{button_released, drag_err, no_file, DragItem, Result, Window};
+
+  use gtk::prelude::*;
+  use wry::application::platform::unix::WindowExtUnix;
+
+  use std::{cell::RefCell, rc::Rc};
+
+  pub fn start_drag<F: FnOnce(Result<()>)>(window: &Window, item: &DragItem, started: F) {
+    let gtk_window = window.gtk_window();
+    let gdk_window = match gtk_window.get_window() {
+      Some(gdk_window) => gdk_window,
+      None => return started(Err(drag_err("the window isn't realized"))),
+    };
+    let pointer = match gdk_window
+      .get_display()
+      .get_default_seat()
+      .and_then(|seat| seat.get_pointer())
+    {
+      Some(pointer) => pointer,
+      None => return started(Err(drag_err("no pointer device"))),
+    };
+    let (_, x, y, modifiers) = gdk_window.get_device_position(&pointer);
+    if !modifiers.contains(gdk::ModifierType::BUTTON1_MASK) {
+      return started(Err(button_released()));
+    }
+
+    let uris: Vec<String> = item
+      .paths()
+      .into_iter()
+      .filter_map(|path| url::Url::from_file_path(path).ok())
+      .map(String::from)
+      .collect();
+    if uris.is_empty() {
+      return started(Err(no_file()));
+    }
+
+    // the handlers are removed when the drag ends, so they don't answer the next drags
+    let handlers = Rc::new(RefCell::new(Vec::new()));
+    handlers.borrow_mut().push(gtk_window.connect_drag_data_get(
+      move |_, _, selection_data, _, _| {
+        let uris: Vec<&str> = uris.iter().map(String::as_str).collect();
+        selection_data.set_uris(&uris);
+      },
+    ));
+    let end_handlers = handlers.clone();
+    handlers
+      .borrow_mut()
+      .push(gtk_window.connect_drag_end(move |gtk_window, _| {
+        for handler in end_handlers.borrow_mut().drain(..) {
+          gtk_window.disconnect(handler);
+        }
+      }));
+
+    let targets = gtk::TargetList::new(&[gtk::TargetEntry::new(
+      "text/uri-list",
+      gtk::TargetFlags::OTHER_APP,
+      0,
+    )]);
+    // the event of the `mousedown` was already handled by the webview, so GTK uses the pointer of the default seat
+    let context =
+      gtk_window.drag_begin_with_coordinates(&targets, gdk::DragAction::COPY, 1, None, x, y);
+    if context.is_some() {
+      started(Ok(()));
+    } else {
+      for handler in handlers.borrow_mut().drain(..) {
+        gtk_window.disconnect(handler);
+      }
+      started(Err(drag_err("GTK refused to start the drag")));
+    }
+  }
+}
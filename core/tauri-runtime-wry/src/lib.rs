@@ -7,17 +7,21 @@
 use tauri_runtime::{
   monitor::Monitor,
   webview::{
-    FileDropEvent, FileDropHandler, RpcRequest, WebviewRpcHandler, WindowBuilder, WindowBuilderBase,
+    Cookie, DataTypes, FileDropEvent, FileDropHandler, FileDropModifiers, FindOptions, FindResult,
+    PermissionRequest, PrintOptions, ProxyConfig, RpcRequest, WebviewAttributes, WebviewRpcHandler,
+    WindowBuilder, WindowBuilderBase,
   },
   window::{
     dpi::{LogicalPosition, LogicalSize, PhysicalPosition, PhysicalSize, Position, Size},
-    DetachedWindow, PendingWindow, WindowEvent,
+    DetachedWindow, DragItem, PendingWindow, ThumbbarButton, WindowEvent,
   },
-  Dispatch, Error, Icon, Params, Result, RunIteration, Runtime, RuntimeHandle,
+  Dispatch, Error, Icon, Params, Result, RunEvent, RunIteration, Runtime, RuntimeHandle, UserEvent,
 };
 
 #[cfg(feature = "menu")]
 use tauri_runtime::window::MenuEvent;
+#[cfg(feature = "touchbar")]
+use tauri_runtime::MenuId;
 #[cfg(feature = "system-tray")]
 use tauri_runtime::{SystemTray, SystemTrayEvent};
 #[cfg(windows)]
@@ -27,7 +31,9 @@ use wry::application::platform::windows::WindowBuilderExtWindows;
 #[cfg(feature = "system-tray")]
 use wry::application::system_tray::{SystemTray as WrySystemTray, SystemTrayBuilder};
 
+use serde_json::Value as JsonValue;
 use tauri_utils::config::WindowConfig;
+use url::Url;
 use uuid::Uuid;
 use wry::{
   application::{
@@ -36,7 +42,7 @@ use wry::{
       PhysicalPosition as WryPhysicalPosition, PhysicalSize as WryPhysicalSize,
       Position as WryPosition, Size as WrySize,
     },
-    event::{Event, WindowEvent as WryWindowEvent},
+    event::{Event, ModifiersState, WindowEvent as WryWindowEvent},
     event_loop::{ControlFlow, EventLoop, EventLoopProxy, EventLoopWindowTarget},
     monitor::MonitorHandle,
     window::{Fullscreen, Icon as WindowIcon, Window, WindowBuilder as WryWindowBuilder, WindowId},
@@ -48,25 +54,239 @@ use wry::{
 };
 
 use std::{
+  cell::{Cell, RefCell},
   collections::HashMap,
   convert::TryFrom,
+  fmt,
   fs::read,
+  path::PathBuf,
   sync::{
     mpsc::{channel, Sender},
     Arc, Mutex, MutexGuard,
   },
+  thread::{current as current_thread, ThreadId},
 };
 
+mod cookies;
+mod drag;
 #[cfg(any(feature = "menu", feature = "system-tray"))]
 mod menu;
 #[cfg(any(feature = "menu", feature = "system-tray"))]
 use menu::*;
+mod native;
+mod policy;
+mod print;
+#[cfg(windows)]
+mod thumbbar;
+#[cfg(feature = "touchbar")]
+mod touchbar;
+#[cfg(feature = "touchbar")]
+use touchbar::{TouchBar, TouchBarEvent, TouchBarEventListeners};
 
 type MainTask = Arc<Mutex<Option<Box<dyn FnOnce() + Send>>>>;
 type CreateWebviewHandler =
   Box<dyn FnOnce(&EventLoopWindowTarget<Message>) -> Result<WebviewWrapper> + Send>;
-type WindowEventHandler = Box<dyn Fn(&WindowEvent) + Send>;
+#[cfg(feature = "system-tray")]
+type CreateTrayHandler = Box<
+  dyn FnOnce(
+      &EventLoopWindowTarget<Message>,
+    ) -> Result<(WrySystemTray, HashMap<u32, WryCustomMenuItem>)>
+    + Send,
+>;
+type WindowEventHandler = Arc<Mutex<Box<dyn Fn(&WindowEvent) + Send>>>;
 type WindowEventListeners = Arc<Mutex<HashMap<Uuid, WindowEventHandler>>>;
+type WryFileDropHandler = Arc<dyn Fn(&Window, FileDropEvent) -> bool>;
+/// The senders of the scripts waiting for their result, with the window that evaluates them.
+type ScriptResults =
+  Arc<Mutex<HashMap<Uuid, (WindowId, Sender<std::result::Result<JsonValue, String>>)>>>;
+/// The settings shared by all the webviews, taken from the first window.
+type SharedWebviewSettingsStore = Arc<Mutex<Option<SharedWebviewSettings>>>;
+
+/// The webview settings applied to the browser or network process of the webview engine,
+/// which is started with the first webview and shared by all of them.
+#[derive(Clone)]
+struct SharedWebviewSettings {
+  proxy: Option<ProxyConfig>,
+  /// Only applied to the process on Windows, the other webviews set the user agent per window.
+  user_agent: Option<String>,
+}
+
+/// Script that defines the function used by the scripts of the runtime to call the internal RPC methods.
+///
+/// It runs before the scripts of the page and keeps the original `postMessage` and `JSON.stringify`,
+/// and the function can't be replaced, so the page can't intercept the token of the window.
+const INTERNAL_RPC_SCRIPT: &str = r#"
+(function () {
+  var stringify = JSON.stringify;
+  var post = window.chrome && window.chrome.webview
+    ? window.chrome.webview.postMessage.bind(window.chrome.webview)
+    : window.webkit.messageHandlers.external.postMessage.bind(window.webkit.messageHandlers.external);
+  Object.defineProperty(window, "__TAURI_INTERNAL_RPC__", {
+    value: function (token, method) {
+      var params = "[" + stringify(token);
+      for (var i = 2; i < arguments.length; i++) {
+        var param = stringify(arguments[i]);
+        params += "," + (param === undefined ? "null" : param);
+      }
+      post('{"jsonrpc":"2.0","method":' + stringify(method) + ',"params":' + params + "]}");
+    }
+  });
+})();
+"#;
+
+/// The RPC methods called by the scripts of the runtime, which must send the token of the window.
+const INTERNAL_RPC_METHODS: &[&str] = &[
+  SCRIPT_RESULT_RPC_METHOD,
+  NAVIGATED_RPC_METHOD,
+  ZOOM_RPC_METHOD,
+];
+
+/// The parameters of an internal RPC call, if its first parameter is the token of the window.
+fn internal_rpc_params<'a>(params: &'a JsonValue, token: &str) -> Option<&'a [JsonValue]> {
+  match params.as_array().map(|params| params.split_first()) {
+    Some(Some((JsonValue::String(t), params))) if t == token => Some(params),
+    _ => None,
+  }
+}
+
+/// The RPC method used to send the result of a script evaluated with [`WryDispatcher::eval_script_with_result`].
+const SCRIPT_RESULT_RPC_METHOD: &str = "__tauriScriptResult";
+
+/// The RPC method used to report the URL of the page loaded on the webview.
+const NAVIGATED_RPC_METHOD: &str = "__tauriNavigated";
+
+/// Script that reports the URL of the loaded page, including history and hash changes,
+/// and whether a new document was loaded or not.
+const NAVIGATED_SCRIPT: &str = r#"
+(function () {
+  function notify(loaded) {
+    window.__TAURI_INTERNAL_RPC__("__RPC_TOKEN__", "__NAVIGATED_RPC_METHOD__", window.location.href, loaded);
+  }
+  window.addEventListener("DOMContentLoaded", function () { notify(true); });
+  window.addEventListener("popstate", function () { notify(false); });
+  window.addEventListener("hashchange", function () { notify(false); });
+})();
+"#;
+
+/// The RPC method used by the zoom hotkeys script to zoom the webview in, out or back to its default size.
+const ZOOM_RPC_METHOD: &str = "__tauriZoom";
+const MIN_ZOOM: f64 = 0.2;
+const MAX_ZOOM: f64 = 5.0;
+/// The zoom factor added or removed by the zoom hotkeys.
+const ZOOM_STEP: f64 = 0.1;
+
+/// Script that sends the Ctrl+scroll and Ctrl+=/Ctrl+-/Ctrl+0 (Cmd on macOS) hotkeys to the runtime,
+/// which zooms the webview natively.
+#[cfg(not(windows))]
+const ZOOM_HOTKEYS_SCRIPT: &str = r#"
+(function () {
+  function zoom(action) {
+    window.__TAURI_INTERNAL_RPC__("__RPC_TOKEN__", "__ZOOM_RPC_METHOD__", action);
+  }
+  function isZoomModifier(e) {
+    return navigator.platform.indexOf("Mac") === 0 ? e.metaKey : e.ctrlKey;
+  }
+  window.addEventListener("keydown", function (e) {
+    if (!isZoomModifier(e)) return;
+    if (e.key === "=" || e.key === "+") {
+      zoom("in");
+    } else if (e.key === "-") {
+      zoom("out");
+    } else if (e.key === "0") {
+      zoom("reset");
+    } else {
+      return;
+    }
+    e.preventDefault();
+  });
+  window.addEventListener(
+    "wheel",
+    function (e) {
+      if (!isZoomModifier(e) || e.deltaY === 0) return;
+      e.preventDefault();
+      zoom(e.deltaY < 0 ? "in" : "out");
+    },
+    { passive: false }
+  );
+})();
+"#;
+
+/// Clamps a zoom factor between the minimum and maximum zoom of the webviews.
+fn clamp_zoom(zoom: f64) -> f64 {
+  zoom.max(MIN_ZOOM).min(MAX_ZOOM)
+}
+
+/// The zoom factor after a zoom hotkey action sent by [`ZOOM_HOTKEYS_SCRIPT`].
+fn zoom_hotkey(zoom: f64, action: &str) -> Option<f64> {
+  match action {
+    "in" => Some(clamp_zoom(zoom + ZOOM_STEP)),
+    "out" => Some(clamp_zoom(zoom - ZOOM_STEP)),
+    "reset" => Some(1.0),
+    _ => None,
+  }
+}
+
+/// Wraps a script so its completion value (or resolved value, if it's a promise) is sent back to the runtime.
+fn script_with_result(id: Uuid, rpc_token: &str, script: &str) -> String {
+  format!(
+    r#"(function () {{
+  function notify(error, result) {{
+    window.__TAURI_INTERNAL_RPC__("{token}", "{method}", "{id}", error, result === undefined ? null : result);
+  }}
+  try {{
+    // indirect eval so the script runs on the global scope
+    Promise.resolve((0, eval)({script})).then(
+      function (result) {{ notify(null, result); }},
+      function (error) {{ notify(String(error), null); }}
+    );
+  }} catch (error) {{
+    notify(String(error), null);
+  }}
+}})();"#,
+    token = rpc_token,
+    method = SCRIPT_RESULT_RPC_METHOD,
+    id = id,
+    script = JsonValue::String(script.into())
+  )
+}
+
+/// Script that adds a stylesheet to the page, if it wasn't added yet.
+fn inject_css_script(id: Uuid, css: &str) -> String {
+  format!(
+    r#"(function () {{
+  if (document.getElementById("__tauri_style_{id}")) return;
+  var style = document.createElement("style");
+  style.id = "__tauri_style_{id}";
+  style.textContent = {css};
+  (document.head || document.documentElement).appendChild(style);
+}})();"#,
+    id = id,
+    css = JsonValue::String(css.into())
+  )
+}
+
+/// Script that removes a stylesheet added with [`inject_css_script`].
+fn remove_css_script(id: Uuid) -> String {
+  format!(
+    r#"(function () {{
+  var style = document.getElementById("__tauri_style_{id}");
+  if (style) style.remove();
+}})();"#,
+    id = id
+  )
+}
+
+/// Script that runs an initialization script added at runtime once per page.
+fn init_script(index: usize, script: &str) -> String {
+  format!(
+    r#"if (!window.__TAURI_INIT_SCRIPT_{index}__) {{
+  window.__TAURI_INIT_SCRIPT_{index}__ = true;
+  (0, eval)({script});
+}}"#,
+    index = index,
+    script = JsonValue::String(script.into())
+  )
+}
 
 /// Wrapper around a [`wry::application::window::Icon`] that can be created from an [`Icon`].
 pub struct WryIcon(WindowIcon);
@@ -240,10 +460,37 @@ impl From<Position> for PositionWrapper {
   }
 }
 
+/// A navigation handler set with [`WindowBuilder::on_navigation`].
+#[derive(Clone)]
+struct NavigationHandler(Arc<dyn Fn(&Url) -> bool + Send + Sync>);
+
+impl fmt::Debug for NavigationHandler {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    f.debug_struct("NavigationHandler").finish()
+  }
+}
+
+/// A permission request handler set with [`WindowBuilder::on_permission_request`].
+#[derive(Clone)]
+struct PermissionHandler(Arc<dyn Fn(&PermissionRequest) -> bool + Send + Sync>);
+
+impl fmt::Debug for PermissionHandler {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    f.debug_struct("PermissionHandler").finish()
+  }
+}
+
 #[derive(Debug, Clone, Default)]
 pub struct WindowBuilderWrapper {
   inner: WryWindowBuilder,
   center: bool,
+  proxy: Option<ProxyConfig>,
+  user_agent: Option<String>,
+  incognito: bool,
+  spellcheck: Option<bool>,
+  spellcheck_languages: Vec<String>,
+  navigation_handler: Option<NavigationHandler>,
+  permission_handler: Option<PermissionHandler>,
   #[cfg(feature = "menu")]
   menu_items: HashMap<u32, WryCustomMenuItem>,
 }
@@ -402,6 +649,48 @@ impl WindowBuilder for WindowBuilderWrapper {
     self
   }
 
+  fn proxy(mut self, proxy: ProxyConfig) -> Self {
+    self.proxy.replace(proxy);
+    self
+  }
+
+  fn user_agent<S: Into<String>>(mut self, user_agent: S) -> Self {
+    self.user_agent.replace(user_agent.into());
+    self
+  }
+
+  fn incognito(mut self, incognito: bool) -> Self {
+    self.incognito = incognito;
+    self
+  }
+
+  fn spellcheck(mut self, enabled: bool) -> Self {
+    self.spellcheck.replace(enabled);
+    self
+  }
+
+  fn spellcheck_languages(mut self, languages: Vec<String>) -> Self {
+    self.spellcheck_languages = languages;
+    self
+  }
+
+  fn on_navigation<F: Fn(&Url) -> bool + Send + Sync + 'static>(mut self, handler: F) -> Self {
+    self
+      .navigation_handler
+      .replace(NavigationHandler(Arc::new(handler)));
+    self
+  }
+
+  fn on_permission_request<F: Fn(&PermissionRequest) -> bool + Send + Sync + 'static>(
+    mut self,
+    handler: F,
+  ) -> Self {
+    self
+      .permission_handler
+      .replace(PermissionHandler(Arc::new(handler)));
+    self
+  }
+
   fn has_icon(&self) -> bool {
     self.inner.window.window_icon.is_some()
   }
@@ -419,18 +708,122 @@ impl From<RpcRequestWrapper> for RpcRequest {
     Self {
       command: request.0.method,
       params: request.0.params,
+      url: None,
     }
   }
 }
 
-pub struct FileDropEventWrapper(WryFileDropEvent);
+struct FileDropModifiersWrapper(ModifiersState);
 
-impl From<FileDropEventWrapper> for FileDropEvent {
-  fn from(event: FileDropEventWrapper) -> Self {
-    match event.0 {
-      WryFileDropEvent::Hovered(paths) => FileDropEvent::Hovered(paths),
-      WryFileDropEvent::Dropped(paths) => FileDropEvent::Dropped(paths),
-      WryFileDropEvent::Cancelled => FileDropEvent::Cancelled,
+impl From<FileDropModifiersWrapper> for FileDropModifiers {
+  fn from(modifiers: FileDropModifiersWrapper) -> Self {
+    Self {
+      shift: modifiers.0.shift(),
+      ctrl: modifiers.0.ctrl(),
+      alt: modifiers.0.alt(),
+      logo: modifiers.0.logo(),
+    }
+  }
+}
+
+/// Reads the cursor position relative to the window content from the OS.
+///
+/// The windows don't receive cursor events while the OS drag and drop loop runs,
+/// so the last [`WryWindowEvent::CursorMoved`] position is only used if this fails.
+#[allow(unused_variables)]
+fn cursor_position(window: &Window) -> Option<PhysicalPosition<f64>> {
+  #[cfg(windows)]
+  unsafe {
+    use winapi::{
+      shared::windef::POINT,
+      um::winuser::{GetCursorPos, ScreenToClient},
+    };
+    use wry::application::platform::windows::WindowExtWindows;
+    let mut point = POINT { x: 0, y: 0 };
+    if GetCursorPos(&mut point) == 0 || ScreenToClient(window.hwnd() as HWND, &mut point) == 0 {
+      return None;
+    }
+    Some(PhysicalPosition::new(point.x as f64, point.y as f64))
+  }
+  #[cfg(target_os = "macos")]
+  unsafe {
+    use cocoa::{
+      base::id,
+      foundation::{NSPoint, NSRect},
+    };
+    use objc::{class, msg_send, sel, sel_impl};
+    use wry::application::platform::macos::WindowExtMacOS;
+    let ns_window = window.ns_window() as id;
+    let screen_point: NSPoint = msg_send![class!(NSEvent), mouseLocation];
+    let point: NSPoint = msg_send![ns_window, convertPointFromScreen: screen_point];
+    let content_view: id = msg_send![ns_window, contentView];
+    let frame: NSRect = msg_send![content_view, frame];
+    // AppKit coordinates start at the bottom left corner
+    let scale_factor = window.scale_factor();
+    Some(PhysicalPosition::new(
+      point.x * scale_factor,
+      (frame.size.height - point.y) * scale_factor,
+    ))
+  }
+  #[cfg(target_os = "linux")]
+  {
+    use gtk::prelude::*;
+    use wry::application::platform::unix::WindowExtUnix;
+    let gdk_window = window.gtk_window().get_window()?;
+    let pointer = gdk_window.get_display().get_default_seat()?.get_pointer()?;
+    let (_, x, y, _) = gdk_window.get_device_position(&pointer);
+    let scale_factor = window.scale_factor();
+    Some(PhysicalPosition::new(
+      x as f64 * scale_factor,
+      y as f64 * scale_factor,
+    ))
+  }
+}
+
+/// Tracks the cursor and keyboard state of a window so file drop events can report them.
+#[derive(Default)]
+struct FileDropState {
+  /// The last position received on the [`WryWindowEvent::CursorMoved`] events.
+  position: PhysicalPosition<f64>,
+  modifiers: ModifiersState,
+  /// The paths being dragged over the window, if a drag is in progress.
+  hovered_paths: Option<Vec<PathBuf>>,
+}
+
+impl FileDropState {
+  fn hovered_event(&self) -> Option<FileDropEvent> {
+    self
+      .hovered_paths
+      .clone()
+      .map(|paths| FileDropEvent::Hovered {
+        paths,
+        position: self.position,
+        modifiers: FileDropModifiersWrapper(self.modifiers).into(),
+      })
+  }
+
+  fn to_event(&mut self, window: &Window, event: WryFileDropEvent) -> FileDropEvent {
+    if let Some(position) = cursor_position(window) {
+      self.position = position;
+    }
+    match event {
+      WryFileDropEvent::Hovered(paths) => {
+        self.hovered_paths.replace(paths);
+        // safe to unwrap since we just set the hovered paths
+        self.hovered_event().unwrap()
+      }
+      WryFileDropEvent::Dropped(paths) => {
+        self.hovered_paths = None;
+        FileDropEvent::Dropped {
+          paths,
+          position: self.position,
+          modifiers: FileDropModifiersWrapper(self.modifiers).into(),
+        }
+      }
+      WryFileDropEvent::Cancelled => {
+        self.hovered_paths = None;
+        FileDropEvent::Cancelled
+      }
     }
   }
 }
@@ -480,14 +873,46 @@ enum WindowMessage {
   SetIcon(WindowIcon),
   SetSkipTaskbar(bool),
   DragWindow,
+  StartDrag(DragItem, Sender<Result<()>>),
+  #[cfg(windows)]
+  SetThumbbarButtons(Vec<thumbbar::NativeButton>),
   #[cfg(feature = "menu")]
   UpdateMenuItem(u32, menu::MenuUpdate),
+  #[cfg(all(feature = "touchbar", target_os = "macos"))]
+  SetTouchBar(Option<touchbar::TouchBarLayout>, touchbar::TouchBarHandler),
 }
 
 #[derive(Debug, Clone)]
 enum WebviewMessage {
+  // Getters
+  Zoom(Sender<Result<f64>>),
+  IsPlayingAudio(Sender<Result<bool>>),
+  #[cfg(feature = "devtools")]
+  IsDevtoolsOpen(Sender<Result<bool>>),
+  Find(String, FindOptions, Sender<Result<FindResult>>),
+  /// Searches the previous match if `true`, the next one otherwise.
+  FindNext(bool, Sender<Result<bool>>),
+  Cookies(Sender<Result<Vec<Cookie>>>),
+  SetCookie(Cookie, Sender<Result<()>>),
+  DeleteCookie(String, Option<String>, Sender<Result<()>>),
+  ClearBrowsingData(DataTypes, Sender<Result<()>>),
+  PrintToPdf(PathBuf, PrintOptions, Sender<Result<()>>),
+  // Setters
   EvaluateScript(String),
+  EvaluateScriptWithResult(Uuid, String),
   Print,
+  PrintWithOptions(PrintOptions),
+  SetZoom(f64),
+  SetUserAgent(String),
+  SetMuted(bool),
+  InjectCss(Uuid, String),
+  AddInitScript(String),
+  RemoveCss(Uuid),
+  ClearFind,
+  #[cfg(feature = "devtools")]
+  OpenDevtools,
+  #[cfg(feature = "devtools")]
+  CloseDevtools,
 }
 
 #[cfg(feature = "system-tray")]
@@ -506,15 +931,33 @@ pub(crate) enum Message {
   Webview(WindowId, WebviewMessage),
   #[cfg(feature = "system-tray")]
   Tray(TrayMessage),
-  CreateWebview(Arc<Mutex<Option<CreateWebviewHandler>>>, Sender<WindowId>),
+  CreateWebview(
+    Arc<Mutex<Option<CreateWebviewHandler>>>,
+    Sender<Result<WindowId>>,
+  ),
+  #[cfg(feature = "system-tray")]
+  CreateTray(Arc<Mutex<Option<CreateTrayHandler>>>, Sender<Result<()>>),
+  UserEvent(Arc<Mutex<Option<UserEvent>>>),
 }
 
 #[derive(Clone)]
 struct DispatcherContext {
+  /// The thread running the event loop.
+  main_thread_id: ThreadId,
   proxy: EventLoopProxy<Message>,
   window_event_listeners: WindowEventListeners,
+  script_results: ScriptResults,
+  shared_webview_settings: SharedWebviewSettingsStore,
   #[cfg(feature = "menu")]
   menu_event_listeners: MenuEventListeners,
+  #[cfg(feature = "touchbar")]
+  touchbar_event_listeners: TouchBarEventListeners,
+}
+
+impl DispatcherContext {
+  fn is_main_thread(&self) -> bool {
+    current_thread().id() == self.main_thread_id
+  }
 }
 
 /// The Tauri [`Dispatch`] for [`Wry`].
@@ -536,6 +979,22 @@ macro_rules! dispatcher_getter {
   }};
 }
 
+macro_rules! webview_getter {
+  ($self: ident, $message: expr) => {{
+    // the webview is only reachable from the event loop, so waiting on the main thread would deadlock
+    if $self.context.is_main_thread() {
+      return Err(Error::WaitOnMainThread);
+    }
+    let (tx, rx) = channel();
+    $self
+      .context
+      .proxy
+      .send_event(Message::Webview($self.window_id, $message(tx)))
+      .map_err(|_| Error::FailedToSendMessage)?;
+    rx.recv().map_err(|_| Error::FailedToReceiveMessage)?
+  }};
+}
+
 impl Dispatch for WryDispatcher {
   type Runtime = Wry;
   type WindowBuilder = WindowBuilderWrapper;
@@ -555,7 +1014,7 @@ impl Dispatch for WryDispatcher {
       .window_event_listeners
       .lock()
       .unwrap()
-      .insert(id, Box::new(f));
+      .insert(id, Arc::new(Mutex::new(Box::new(f))));
     id
   }
 
@@ -571,6 +1030,20 @@ impl Dispatch for WryDispatcher {
     id
   }
 
+  #[cfg(feature = "touchbar")]
+  fn on_touchbar_event<F: Fn(&TouchBarEvent) + Send + 'static>(&self, f: F) -> Uuid {
+    let id = Uuid::new_v4();
+    self
+      .context
+      .touchbar_event_listeners
+      .lock()
+      .unwrap()
+      .entry(self.window_id)
+      .or_default()
+      .insert(id, Box::new(f));
+    id
+  }
+
   // Getters
 
   fn scale_factor(&self) -> Result<f64> {
@@ -638,6 +1111,10 @@ impl Dispatch for WryDispatcher {
     )
   }
 
+  fn zoom(&self) -> Result<f64> {
+    webview_getter!(self, WebviewMessage::Zoom)
+  }
+
   #[cfg(windows)]
   fn hwnd(&self) -> Result<*mut std::ffi::c_void> {
     Ok(dispatcher_getter!(self, WindowMessage::Hwnd).0)
@@ -657,31 +1134,180 @@ impl Dispatch for WryDispatcher {
       .map_err(|_| Error::FailedToSendMessage)
   }
 
-  // Creates a window by dispatching a message to the event loop.
-  // Note that this must be called from a separate thread, otherwise the channel will introduce a deadlock.
-  fn create_window<P: Params<Runtime = Self::Runtime>>(
-    &mut self,
-    pending: PendingWindow<P>,
-  ) -> Result<DetachedWindow<P>> {
-    let (tx, rx) = channel();
-    let label = pending.label.clone();
-    let context = self.context.clone();
+  fn print_with_options(&self, options: PrintOptions) -> Result<()> {
+    native::check_supported("the print options")?;
     self
       .context
       .proxy
-      .send_event(Message::CreateWebview(
-        Arc::new(Mutex::new(Some(Box::new(move |event_loop| {
-          create_webview(event_loop, context, pending)
-        })))),
-        tx,
+      .send_event(Message::Webview(
+        self.window_id,
+        WebviewMessage::PrintWithOptions(options),
+      ))
+      .map_err(|_| Error::FailedToSendMessage)
+  }
+
+  fn print_to_pdf(&self, path: PathBuf, options: PrintOptions) -> Result<()> {
+    native::check_supported("printing to PDF")?;
+    webview_getter!(self, |tx| WebviewMessage::PrintToPdf(path, options, tx))
+  }
+
+  fn clear_browsing_data(&self, data_types: DataTypes) -> Result<()> {
+    native::check_supported("clearing the browsing data")?;
+    webview_getter!(self, |tx| WebviewMessage::ClearBrowsingData(data_types, tx))
+  }
+
+  fn cookies(&self) -> Result<Vec<Cookie>> {
+    native::check_supported("the webview cookies")?;
+    webview_getter!(self, WebviewMessage::Cookies)
+  }
+
+  fn set_cookie(&self, cookie: Cookie) -> Result<()> {
+    native::check_supported("the webview cookies")?;
+    cookies::validate_cookie(&cookie)?;
+    webview_getter!(self, |tx| WebviewMessage::SetCookie(cookie, tx))
+  }
+
+  fn delete_cookie(&self, name: &str, path: Option<&str>) -> Result<()> {
+    native::check_supported("the webview cookies")?;
+    webview_getter!(self, |tx| WebviewMessage::DeleteCookie(
+      name.into(),
+      path.map(Into::into),
+      tx
+    ))
+  }
+
+  #[cfg(feature = "devtools")]
+  fn open_devtools(&self) -> Result<()> {
+    native::check_supported("the devtools")?;
+    self
+      .context
+      .proxy
+      .send_event(Message::Webview(
+        self.window_id,
+        WebviewMessage::OpenDevtools,
+      ))
+      .map_err(|_| Error::FailedToSendMessage)
+  }
+
+  #[cfg(feature = "devtools")]
+  fn close_devtools(&self) -> Result<()> {
+    native::check_supported("the devtools")?;
+    self
+      .context
+      .proxy
+      .send_event(Message::Webview(
+        self.window_id,
+        WebviewMessage::CloseDevtools,
+      ))
+      .map_err(|_| Error::FailedToSendMessage)
+  }
+
+  #[cfg(feature = "devtools")]
+  fn is_devtools_open(&self) -> Result<bool> {
+    webview_getter!(self, WebviewMessage::IsDevtoolsOpen)
+  }
+
+  fn find(&self, text: String, options: FindOptions) -> Result<FindResult> {
+    webview_getter!(self, |tx| WebviewMessage::Find(text, options, tx))
+  }
+
+  fn find_next(&self) -> Result<bool> {
+    webview_getter!(self, |tx| WebviewMessage::FindNext(false, tx))
+  }
+
+  fn find_previous(&self) -> Result<bool> {
+    webview_getter!(self, |tx| WebviewMessage::FindNext(true, tx))
+  }
+
+  fn clear_find(&self) -> Result<()> {
+    native::check_supported("find in page")?;
+    self
+      .context
+      .proxy
+      .send_event(Message::Webview(self.window_id, WebviewMessage::ClearFind))
+      .map_err(|_| Error::FailedToSendMessage)
+  }
+
+  fn is_playing_audio(&self) -> Result<bool> {
+    webview_getter!(self, WebviewMessage::IsPlayingAudio)
+  }
+
+  fn inject_css(&self, css: String) -> Result<Uuid> {
+    let id = Uuid::new_v4();
+    self
+      .context
+      .proxy
+      .send_event(Message::Webview(
+        self.window_id,
+        WebviewMessage::InjectCss(id, css),
       ))
       .map_err(|_| Error::FailedToSendMessage)?;
-    let window_id = rx.recv().unwrap();
-    let dispatcher = WryDispatcher {
-      window_id,
-      context: self.context.clone(),
-    };
-    Ok(DetachedWindow { label, dispatcher })
+    Ok(id)
+  }
+
+  fn remove_css(&self, id: Uuid) -> Result<()> {
+    self
+      .context
+      .proxy
+      .send_event(Message::Webview(
+        self.window_id,
+        WebviewMessage::RemoveCss(id),
+      ))
+      .map_err(|_| Error::FailedToSendMessage)
+  }
+
+  fn add_init_script(&self, script: String) -> Result<()> {
+    self
+      .context
+      .proxy
+      .send_event(Message::Webview(
+        self.window_id,
+        WebviewMessage::AddInitScript(script),
+      ))
+      .map_err(|_| Error::FailedToSendMessage)
+  }
+
+  fn set_muted(&self, muted: bool) -> Result<()> {
+    native::check_audio_supported()?;
+    self
+      .context
+      .proxy
+      .send_event(Message::Webview(
+        self.window_id,
+        WebviewMessage::SetMuted(muted),
+      ))
+      .map_err(|_| Error::FailedToSendMessage)
+  }
+
+  fn set_user_agent(&self, user_agent: String) -> Result<()> {
+    native::check_supported("changing the user agent of a webview")?;
+    self
+      .context
+      .proxy
+      .send_event(Message::Webview(
+        self.window_id,
+        WebviewMessage::SetUserAgent(user_agent),
+      ))
+      .map_err(|_| Error::FailedToSendMessage)
+  }
+
+  fn set_zoom(&self, scale_factor: f64) -> Result<()> {
+    native::check_supported("the webview zoom")?;
+    self
+      .context
+      .proxy
+      .send_event(Message::Webview(
+        self.window_id,
+        WebviewMessage::SetZoom(scale_factor),
+      ))
+      .map_err(|_| Error::FailedToSendMessage)
+  }
+
+  fn create_window<P: Params<Runtime = Self::Runtime>>(
+    &mut self,
+    pending: PendingWindow<P>,
+  ) -> Result<DetachedWindow<P>> {
+    dispatch_create_window(&self.context, pending)
   }
 
   fn set_resizable(&self, resizable: bool) -> Result<()> {
@@ -877,6 +1503,73 @@ impl Dispatch for WryDispatcher {
       .map_err(|_| Error::FailedToSendMessage)
   }
 
+  fn start_drag(&self, item: DragItem) -> Result<()> {
+    let (tx, rx) = channel();
+    self
+      .context
+      .proxy
+      .send_event(Message::Window(
+        self.window_id,
+        WindowMessage::StartDrag(item, tx),
+      ))
+      .map_err(|_| Error::FailedToSendMessage)?;
+    rx.recv().map_err(|_| Error::FailedToReceiveMessage)?
+  }
+
+  #[allow(unused_variables)]
+  fn set_thumbbar_buttons(&self, buttons: Vec<ThumbbarButton>) -> Result<()> {
+    if buttons.len() > ThumbbarButton::MAX {
+      return Err(Error::Unsupported(format!(
+        "more than {} thumbnail toolbar buttons",
+        ThumbbarButton::MAX
+      )));
+    }
+    #[cfg(windows)]
+    {
+      let buttons = buttons
+        .into_iter()
+        .map(thumbbar::NativeButton::new)
+        .collect::<Result<Vec<_>>>()?;
+      self
+        .context
+        .proxy
+        .send_event(Message::Window(
+          self.window_id,
+          WindowMessage::SetThumbbarButtons(buttons),
+        ))
+        .map_err(|_| Error::FailedToSendMessage)
+    }
+    #[cfg(not(windows))]
+    Ok(())
+  }
+
+  #[cfg(feature = "touchbar")]
+  #[allow(unused_variables)]
+  fn set_touchbar<I: MenuId>(&self, touchbar: Option<TouchBar<I>>) -> Result<()> {
+    #[cfg(target_os = "macos")]
+    {
+      let listeners = self.context.touchbar_event_listeners.clone();
+      let window_id = self.window_id;
+      let handler = touchbar::TouchBarHandler(Arc::new(move |event| {
+        if let Some(handlers) = listeners.lock().unwrap().get(&window_id) {
+          for handler in handlers.values() {
+            handler(&event);
+          }
+        }
+      }));
+      self
+        .context
+        .proxy
+        .send_event(Message::Window(
+          self.window_id,
+          WindowMessage::SetTouchBar(touchbar.map(Into::into), handler),
+        ))
+        .map_err(|_| Error::FailedToSendMessage)
+    }
+    #[cfg(not(target_os = "macos"))]
+    Ok(())
+  }
+
   fn eval_script<S: Into<String>>(&self, script: S) -> Result<()> {
     self
       .context
@@ -888,6 +1581,37 @@ impl Dispatch for WryDispatcher {
       .map_err(|_| Error::FailedToSendMessage)
   }
 
+  fn eval_script_with_result<S: Into<String>>(&self, script: S) -> Result<JsonValue> {
+    // the scripts are evaluated by the event loop, so waiting on the main thread would deadlock
+    if self.context.is_main_thread() {
+      return Err(Error::WaitOnMainThread);
+    }
+    let id = Uuid::new_v4();
+    let (tx, rx) = channel();
+    self
+      .context
+      .script_results
+      .lock()
+      .unwrap()
+      .insert(id, (self.window_id, tx));
+    if let Err(e) = self
+      .context
+      .proxy
+      .send_event(Message::Webview(
+        self.window_id,
+        WebviewMessage::EvaluateScriptWithResult(id, script.into()),
+      ))
+      .map_err(|_| Error::FailedToSendMessage)
+    {
+      self.context.script_results.lock().unwrap().remove(&id);
+      return Err(e);
+    }
+    // the sender is dropped when the page is unloaded or the window is closed
+    rx.recv()
+      .map_err(|_| Error::FailedToReceiveMessage)?
+      .map_err(Error::Script)
+  }
+
   #[cfg(feature = "menu")]
   fn update_menu_item(&self, id: u32, update: menu::MenuUpdate) -> Result<()> {
     self
@@ -909,19 +1633,134 @@ struct TrayContext {
   items: SystemTrayItems,
 }
 
+/// A temporary webview data directory that is removed when dropped.
+struct IncognitoDataDirectory(PathBuf);
+
+impl IncognitoDataDirectory {
+  /// The prefix of the directory names, followed by the id of the process that created them.
+  const PREFIX: &'static str = "tauri-incognito-";
+
+  fn new() -> Self {
+    Self(std::env::temp_dir().join(format!(
+      "{}{}-{}",
+      Self::PREFIX,
+      std::process::id(),
+      Uuid::new_v4()
+    )))
+  }
+
+  /// Removes the directories left by the processes that didn't exit cleanly.
+  fn remove_stale() {
+    if let Ok(entries) = std::fs::read_dir(std::env::temp_dir()) {
+      for entry in entries.flatten() {
+        let pid = entry
+          .file_name()
+          .to_str()
+          .and_then(|name| name.strip_prefix(Self::PREFIX))
+          .and_then(|name| name.split('-').next())
+          .and_then(|pid| pid.parse::<u32>().ok());
+        if let Some(pid) = pid {
+          if !is_process_running(pid) {
+            let _ = std::fs::remove_dir_all(entry.path());
+          }
+        }
+      }
+    }
+  }
+}
+
+/// Whether a process with the given id is running or not.
+///
+/// Processes that can't be queried are considered running.
+fn is_process_running(pid: u32) -> bool {
+  #[cfg(windows)]
+  unsafe {
+    use winapi::{
+      shared::{minwindef::FALSE, winerror::ERROR_ACCESS_DENIED},
+      um::{
+        errhandlingapi::GetLastError,
+        handleapi::CloseHandle,
+        minwinbase::STILL_ACTIVE,
+        processthreadsapi::{GetExitCodeProcess, OpenProcess},
+        winnt::PROCESS_QUERY_LIMITED_INFORMATION,
+      },
+    };
+    let process = OpenProcess(PROCESS_QUERY_LIMITED_INFORMATION, FALSE, pid);
+    if process.is_null() {
+      return GetLastError() == ERROR_ACCESS_DENIED;
+    }
+    let mut exit_code = 0;
+    let running = GetExitCodeProcess(process, &mut exit_code) == 0 || exit_code == STILL_ACTIVE;
+    CloseHandle(process);
+    running
+  }
+  #[cfg(not(windows))]
+  {
+    extern "C" {
+      fn kill(pid: i32, signal: i32) -> i32;
+    }
+    /// `EPERM`, the process exists but belongs to another user.
+    const EPERM: i32 = 1;
+    unsafe { kill(pid as i32, 0) == 0 }
+    || std::io::Error::last_os_error().raw_os_error() == Some(EPERM)
+  }
+}
+
+impl Drop for IncognitoDataDirectory {
+  fn drop(&mut self) {
+    let _ = std::fs::remove_dir_all(&self.0);
+  }
+}
+
+/// Drops the senders of the scripts evaluated on a window, so the callers waiting for their result get an error.
+fn cancel_script_results(script_results: &ScriptResults, window_id: WindowId) {
+  script_results
+    .lock()
+    .unwrap()
+    .retain(|_, (id, _)| *id != window_id);
+}
+
+/// Cancels the scripts waiting for their result when the window is closed.
+struct PendingScriptResults {
+  script_results: ScriptResults,
+  window_id: WindowId,
+}
+
+impl Drop for PendingScriptResults {
+  fn drop(&mut self) {
+    cancel_script_results(&self.script_results, self.window_id);
+  }
+}
+
 struct WebviewWrapper {
   inner: WebView,
+  // declared after `inner` so the webview is dropped before its data directory is removed
+  _incognito_data_directory: Option<IncognitoDataDirectory>,
+  _pending_script_results: PendingScriptResults,
+  /// The token sent by the scripts of the runtime to call the internal RPC methods.
+  rpc_token: String,
+  file_drop_state: Arc<Mutex<FileDropState>>,
+  file_drop_handler: Option<WryFileDropHandler>,
+  /// The text and options of the current find-in-page search.
+  search: Option<(String, FindOptions)>,
+  styles: Arc<Mutex<HashMap<Uuid, String>>>,
+  init_scripts: Arc<Mutex<Vec<String>>>,
   #[cfg(feature = "menu")]
   menu_items: HashMap<u32, WryCustomMenuItem>,
 }
 
 /// A Tauri [`Runtime`] wrapper around wry.
 pub struct Wry {
+  main_thread_id: ThreadId,
   event_loop: EventLoop<Message>,
   webviews: Arc<Mutex<HashMap<WindowId, WebviewWrapper>>>,
   window_event_listeners: WindowEventListeners,
+  script_results: ScriptResults,
+  shared_webview_settings: SharedWebviewSettingsStore,
   #[cfg(feature = "menu")]
   menu_event_listeners: MenuEventListeners,
+  #[cfg(feature = "touchbar")]
+  touchbar_event_listeners: TouchBarEventListeners,
   #[cfg(feature = "system-tray")]
   tray_context: TrayContext,
 }
@@ -935,31 +1774,51 @@ pub struct WryHandle {
 impl RuntimeHandle for WryHandle {
   type Runtime = Wry;
 
-  // Creates a window by dispatching a message to the event loop.
-  // Note that this must be called from a separate thread, otherwise the channel will introduce a deadlock.
   fn create_window<P: Params<Runtime = Self::Runtime>>(
     &self,
     pending: PendingWindow<P>,
   ) -> Result<DetachedWindow<P>> {
+    dispatch_create_window(&self.dispatcher_context, pending)
+  }
+
+  #[cfg(feature = "system-tray")]
+  fn system_tray<I: MenuId>(&self, system_tray: SystemTray<I>) -> Result<SystemTrayHandle> {
+    let context = &self.dispatcher_context;
+    // like the windows, the tray is created right away when the main thread is handling an event
+    if context.is_main_thread() {
+      let event_loop = EVENT_LOOP_TARGET.with(|target| target.get());
+      if event_loop.is_null() {
+        return Err(Error::CreateOnMainThread);
+      }
+      // the target outlives the guard of the event being handled, which is still alive on this thread
+      let tray = create_tray(unsafe { &*event_loop }, system_tray)?;
+      CREATED_TRAY.with(|created| created.borrow_mut().replace(tray));
+      return Ok(SystemTrayHandle {
+        proxy: context.proxy.clone(),
+      });
+    }
     let (tx, rx) = channel();
-    let label = pending.label.clone();
-    let dispatcher_context = self.dispatcher_context.clone();
-    self
-      .dispatcher_context
+    context
       .proxy
-      .send_event(Message::CreateWebview(
+      .send_event(Message::CreateTray(
         Arc::new(Mutex::new(Some(Box::new(move |event_loop| {
-          create_webview(event_loop, dispatcher_context, pending)
+          create_tray(event_loop, system_tray)
         })))),
         tx,
       ))
       .map_err(|_| Error::FailedToSendMessage)?;
-    let window_id = rx.recv().unwrap();
-    let dispatcher = WryDispatcher {
-      window_id,
-      context: self.dispatcher_context.clone(),
-    };
-    Ok(DetachedWindow { label, dispatcher })
+    rx.recv().map_err(|_| Error::FailedToReceiveMessage)??;
+    Ok(SystemTrayHandle {
+      proxy: context.proxy.clone(),
+    })
+  }
+
+  fn send_event(&self, event: UserEvent) -> Result<()> {
+    self
+      .dispatcher_context
+      .proxy
+      .send_event(Message::UserEvent(Arc::new(Mutex::new(Some(event)))))
+      .map_err(|_| Error::FailedToSendMessage)
   }
 
   #[cfg(all(windows, feature = "system-tray"))]
@@ -980,12 +1839,18 @@ impl Runtime for Wry {
 
   fn new() -> Result<Self> {
     let event_loop = EventLoop::<Message>::with_user_event();
+    IncognitoDataDirectory::remove_stale();
     Ok(Self {
+      main_thread_id: current_thread().id(),
       event_loop,
       webviews: Default::default(),
       window_event_listeners: Default::default(),
+      script_results: Default::default(),
+      shared_webview_settings: Default::default(),
       #[cfg(feature = "menu")]
       menu_event_listeners: Default::default(),
+      #[cfg(feature = "touchbar")]
+      touchbar_event_listeners: Default::default(),
       #[cfg(feature = "system-tray")]
       tray_context: Default::default(),
     })
@@ -993,12 +1858,7 @@ impl Runtime for Wry {
 
   fn handle(&self) -> Self::Handle {
     WryHandle {
-      dispatcher_context: DispatcherContext {
-        proxy: self.event_loop.create_proxy(),
-        window_event_listeners: self.window_event_listeners.clone(),
-        #[cfg(feature = "menu")]
-        menu_event_listeners: self.menu_event_listeners.clone(),
-      },
+      dispatcher_context: self.dispatcher_context(),
     }
   }
 
@@ -1007,26 +1867,11 @@ impl Runtime for Wry {
     pending: PendingWindow<P>,
   ) -> Result<DetachedWindow<P>> {
     let label = pending.label.clone();
-    let proxy = self.event_loop.create_proxy();
-    let webview = create_webview(
-      &self.event_loop,
-      DispatcherContext {
-        proxy: proxy.clone(),
-        window_event_listeners: self.window_event_listeners.clone(),
-        #[cfg(feature = "menu")]
-        menu_event_listeners: self.menu_event_listeners.clone(),
-      },
-      pending,
-    )?;
+    let webview = create_webview(&self.event_loop, self.dispatcher_context(), pending)?;
 
     let dispatcher = WryDispatcher {
       window_id: webview.inner.window().id(),
-      context: DispatcherContext {
-        proxy,
-        window_event_listeners: self.window_event_listeners.clone(),
-        #[cfg(feature = "menu")]
-        menu_event_listeners: self.menu_event_listeners.clone(),
-      },
+      context: self.dispatcher_context(),
     };
 
     self
@@ -1040,21 +1885,7 @@ impl Runtime for Wry {
 
   #[cfg(feature = "system-tray")]
   fn system_tray<I: MenuId>(&self, system_tray: SystemTray<I>) -> Result<Self::TrayHandler> {
-    let icon = system_tray
-      .icon
-      .expect("tray icon not set")
-      .into_tray_icon();
-
-    let mut items = HashMap::new();
-
-    let tray = SystemTrayBuilder::new(
-      icon,
-      system_tray
-        .menu
-        .map(|menu| to_wry_context_menu(&mut items, menu)),
-    )
-    .build(&self.event_loop)
-    .map_err(|e| Error::SystemTray(Box::new(e)))?;
+    let (tray, items) = create_tray(&self.event_loop, system_tray)?;
 
     *self.tray_context.items.lock().unwrap() = items;
     *self.tray_context.tray.lock().unwrap() = Some(Arc::new(Mutex::new(tray)));
@@ -1113,7 +1944,7 @@ impl Runtime for Wry {
     iteration
   }
 
-  fn run<F: Fn() + 'static>(self, callback: F) {
+  fn run<F: FnMut(RunEvent) + 'static>(self, mut callback: F) {
     let webviews = self.webviews.clone();
     let window_event_listeners = self.window_event_listeners.clone();
     #[cfg(feature = "menu")]
@@ -1127,7 +1958,7 @@ impl Runtime for Wry {
         event_loop,
         control_flow,
         EventLoopIterationContext {
-          callback: Some(&callback),
+          callback: Some(&mut callback),
           webviews: webviews.lock().expect("poisoned webview collection"),
           window_event_listeners: window_event_listeners.clone(),
           #[cfg(feature = "menu")]
@@ -1140,8 +1971,24 @@ impl Runtime for Wry {
   }
 }
 
+impl Wry {
+  fn dispatcher_context(&self) -> DispatcherContext {
+    DispatcherContext {
+      main_thread_id: self.main_thread_id,
+      proxy: self.event_loop.create_proxy(),
+      window_event_listeners: self.window_event_listeners.clone(),
+      script_results: self.script_results.clone(),
+      shared_webview_settings: self.shared_webview_settings.clone(),
+      #[cfg(feature = "menu")]
+      menu_event_listeners: self.menu_event_listeners.clone(),
+      #[cfg(feature = "touchbar")]
+      touchbar_event_listeners: self.touchbar_event_listeners.clone(),
+    }
+  }
+}
+
 struct EventLoopIterationContext<'a> {
-  callback: Option<&'a (dyn Fn() + 'static)>,
+  callback: Option<&'a mut (dyn FnMut(RunEvent) + 'static)>,
   webviews: MutexGuard<'a, HashMap<WindowId, WebviewWrapper>>,
   window_event_listeners: WindowEventListeners,
   #[cfg(feature = "menu")]
@@ -1166,6 +2013,10 @@ fn handle_event_loop(
     tray_context,
   } = context;
   *control_flow = ControlFlow::Wait;
+  let _event_loop_target = EventLoopTargetGuard::new(event_loop);
+  insert_created_webviews(&mut webviews);
+  #[cfg(feature = "system-tray")]
+  insert_created_tray(&tray_context);
 
   for (_, w) in webviews.iter() {
     if let Err(e) = w.inner.evaluate_script() {
@@ -1217,9 +2068,7 @@ fn handle_event_loop(
     }
     Event::WindowEvent { event, window_id } => {
       if let Some(event) = WindowEventWrapper::from(&event).0 {
-        for handler in window_event_listeners.lock().unwrap().values() {
-          handler(&event);
-        }
+        emit_window_event(&window_event_listeners, &event);
       }
       match event {
         WryWindowEvent::CloseRequested => {
@@ -1227,7 +2076,7 @@ fn handle_event_loop(
           if webviews.is_empty() {
             *control_flow = ControlFlow::Exit;
             if let Some(callback) = callback {
-              callback();
+              callback(RunEvent::Exit);
             }
           }
         }
@@ -1236,6 +2085,23 @@ fn handle_event_loop(
             eprintln!("{}", e);
           }
         }
+        WryWindowEvent::CursorMoved { position, .. } => {
+          if let Some(webview) = webviews.get(&window_id) {
+            let event = {
+              let mut state = webview.file_drop_state.lock().unwrap();
+              state.position = PhysicalPositionWrapper(position).into();
+              state.hovered_event()
+            };
+            if let (Some(event), Some(handler)) = (event, &webview.file_drop_handler) {
+              handler(webview.inner.window(), event);
+            }
+          }
+        }
+        WryWindowEvent::ModifiersChanged(modifiers) => {
+          if let Some(webview) = webviews.get(&window_id) {
+            webview.file_drop_state.lock().unwrap().modifiers = modifiers;
+          }
+        }
         _ => {}
       }
     }
@@ -1339,6 +2205,13 @@ fn handle_event_loop(
             WindowMessage::DragWindow => {
               let _ = window.drag_window();
             }
+            WindowMessage::StartDrag(item, tx) => {
+              drag::start_drag(window, &item, tx);
+            }
+            #[cfg(windows)]
+            WindowMessage::SetThumbbarButtons(buttons) => {
+              thumbbar::set_thumbbar_buttons(window, buttons, window_event_listeners.clone());
+            }
             #[cfg(feature = "menu")]
             WindowMessage::UpdateMenuItem(id, update) => {
               let item = webview
@@ -1355,18 +2228,100 @@ fn handle_event_loop(
                 }
               }
             }
+            #[cfg(all(feature = "touchbar", target_os = "macos"))]
+            WindowMessage::SetTouchBar(layout, handler) => {
+              touchbar::set_touchbar(window, layout, handler);
+            }
           }
         }
       }
       Message::Webview(id, webview_message) => {
         if let Some(webview) = webviews.get_mut(&id) {
           match webview_message {
+            // Getters
+            WebviewMessage::Zoom(tx) => {
+              let _ = tx.send(native::zoom(webview.inner.window()));
+            }
+            WebviewMessage::IsPlayingAudio(tx) => {
+              let _ = tx.send(native::is_playing_audio(webview.inner.window()));
+            }
+            #[cfg(feature = "devtools")]
+            WebviewMessage::IsDevtoolsOpen(tx) => {
+              let _ = tx.send(native::is_devtools_open(webview.inner.window()));
+            }
+            WebviewMessage::Find(text, options, tx) => {
+              native::find(webview.inner.window(), &text, &options, tx);
+              webview.search.replace((text, options));
+            }
+            WebviewMessage::Cookies(tx) => cookies::cookies(webview.inner.window(), tx),
+            WebviewMessage::SetCookie(cookie, tx) => {
+              cookies::set_cookie(webview.inner.window(), cookie, tx)
+            }
+            WebviewMessage::DeleteCookie(name, path, tx) => {
+              cookies::delete_cookie(webview.inner.window(), name, path, tx)
+            }
+            WebviewMessage::ClearBrowsingData(data_types, tx) => {
+              native::clear_browsing_data(webview.inner.window(), data_types, tx)
+            }
+            WebviewMessage::PrintToPdf(path, options, tx) => {
+              print::print_to_pdf(webview.inner.window(), &path, &options, tx)
+            }
+            WebviewMessage::FindNext(backwards, tx) => match &webview.search {
+              Some((text, options)) => {
+                native::find_next(webview.inner.window(), text, options, backwards, tx)
+              }
+              None => {
+                let _ = tx.send(Ok(false));
+              }
+            },
+            // Setters
             WebviewMessage::EvaluateScript(script) => {
               let _ = webview.inner.dispatch_script(&script);
             }
+            WebviewMessage::EvaluateScriptWithResult(id, script) => {
+              let _ =
+                webview
+                  .inner
+                  .dispatch_script(&script_with_result(id, &webview.rpc_token, &script));
+            }
             WebviewMessage::Print => {
               let _ = webview.inner.print();
             }
+            WebviewMessage::PrintWithOptions(options) => {
+              let _ = print::print_with_options(webview.inner.window(), &options);
+            }
+            WebviewMessage::SetZoom(zoom) => {
+              let _ = native::set_zoom(webview.inner.window(), clamp_zoom(zoom));
+            }
+            WebviewMessage::InjectCss(id, css) => {
+              let _ = webview.inner.dispatch_script(&inject_css_script(id, &css));
+              webview.styles.lock().unwrap().insert(id, css);
+            }
+            WebviewMessage::RemoveCss(id) => {
+              webview.styles.lock().unwrap().remove(&id);
+              let _ = webview.inner.dispatch_script(&remove_css_script(id));
+            }
+            WebviewMessage::ClearFind => {
+              webview.search = None;
+              let _ = native::clear_find(webview.inner.window());
+            }
+            #[cfg(feature = "devtools")]
+            WebviewMessage::OpenDevtools => {
+              let _ = native::open_devtools(webview.inner.window());
+            }
+            #[cfg(feature = "devtools")]
+            WebviewMessage::CloseDevtools => {
+              let _ = native::close_devtools(webview.inner.window());
+            }
+            WebviewMessage::AddInitScript(script) => {
+              webview.init_scripts.lock().unwrap().push(script);
+            }
+            WebviewMessage::SetMuted(muted) => {
+              let _ = native::set_muted(webview.inner.window(), muted);
+            }
+            WebviewMessage::SetUserAgent(user_agent) => {
+              let _ = native::set_user_agent(webview.inner.window(), &user_agent);
+            }
           }
         }
       }
@@ -1375,16 +2330,24 @@ fn handle_event_loop(
           let mut lock = handler.lock().expect("poisoned create webview handler");
           std::mem::take(&mut *lock).unwrap()
         };
-        match handler(event_loop) {
-          Ok(webview) => {
-            let window_id = webview.inner.window().id();
-            webviews.insert(window_id, webview);
-            sender.send(window_id).unwrap();
-          }
-          Err(e) => {
-            eprintln!("{}", e);
-          }
-        }
+        let window_id = handler(event_loop).map(|webview| {
+          let window_id = webview.inner.window().id();
+          webviews.insert(window_id, webview);
+          window_id
+        });
+        let _ = sender.send(window_id);
+      }
+      #[cfg(feature = "system-tray")]
+      Message::CreateTray(handler, sender) => {
+        let handler = {
+          let mut lock = handler.lock().expect("poisoned create tray handler");
+          std::mem::take(&mut *lock).unwrap()
+        };
+        let result = handler(event_loop).map(|(tray, items)| {
+          *tray_context.items.lock().unwrap() = items;
+          *tray_context.tray.lock().unwrap() = Some(Arc::new(Mutex::new(tray)));
+        });
+        let _ = sender.send(result);
       }
       #[cfg(feature = "system-tray")]
       Message::Tray(tray_message) => match tray_message {
@@ -1414,15 +2377,138 @@ fn handle_event_loop(
           }
         }
       },
+      Message::UserEvent(event) => {
+        if let (Some(event), Some(callback)) = (event.lock().unwrap().take(), callback) {
+          callback(RunEvent::UserEvent(event));
+        }
+      }
     },
     _ => (),
   }
 
+  insert_created_webviews(&mut webviews);
+  #[cfg(feature = "system-tray")]
+  insert_created_tray(&tray_context);
+
   RunIteration {
     webview_count: webviews.len(),
   }
 }
 
+thread_local! {
+  /// The event loop target while the event loop handles an event, so the windows requested on the main thread are created right away.
+  static EVENT_LOOP_TARGET: Cell<*const EventLoopWindowTarget<Message>> = Cell::new(std::ptr::null());
+  /// The windows created on the main thread, added to the webview collection by the event loop.
+  static CREATED_WEBVIEWS: RefCell<Vec<WebviewWrapper>> = RefCell::new(Vec::new());
+  /// The tray created on the main thread, added to the tray context by the event loop.
+  #[cfg(feature = "system-tray")]
+  static CREATED_TRAY: RefCell<Option<(WrySystemTray, HashMap<u32, WryCustomMenuItem>)>> = RefCell::new(None);
+}
+
+/// Exposes the event loop target while an event is handled, restoring the previous one when dropped.
+struct EventLoopTargetGuard(*const EventLoopWindowTarget<Message>);
+
+impl EventLoopTargetGuard {
+  fn new(event_loop: &EventLoopWindowTarget<Message>) -> Self {
+    Self(EVENT_LOOP_TARGET.with(|target| target.replace(event_loop)))
+  }
+}
+
+impl Drop for EventLoopTargetGuard {
+  fn drop(&mut self) {
+    EVENT_LOOP_TARGET.with(|target| target.set(self.0));
+  }
+}
+
+fn insert_created_webviews(webviews: &mut HashMap<WindowId, WebviewWrapper>) {
+  for webview in CREATED_WEBVIEWS.with(|created| created.borrow_mut().split_off(0)) {
+    webviews.insert(webview.inner.window().id(), webview);
+  }
+}
+
+#[cfg(feature = "system-tray")]
+fn insert_created_tray(tray_context: &TrayContext) {
+  if let Some((tray, items)) = CREATED_TRAY.with(|created| created.borrow_mut().take()) {
+    *tray_context.items.lock().unwrap() = items;
+    *tray_context.tray.lock().unwrap() = Some(Arc::new(Mutex::new(tray)));
+  }
+}
+
+// Creates a window by dispatching a message to the event loop and waiting for its window id.
+// The main thread can't wait for the event loop, so the window is created right away there when the event loop
+// is handling an event (e.g. on a `run_on_main_thread` closure), and the request fails otherwise instead of deadlocking.
+fn dispatch_create_window<P: Params<Runtime = Wry>>(
+  context: &DispatcherContext,
+  pending: PendingWindow<P>,
+) -> Result<DetachedWindow<P>> {
+  let label = pending.label.clone();
+  if context.is_main_thread() {
+    let event_loop = EVENT_LOOP_TARGET.with(|target| target.get());
+    if event_loop.is_null() {
+      return Err(Error::CreateOnMainThread);
+    }
+    // the target outlives the guard of the event being handled, which is still alive on this thread
+    let webview = create_webview(unsafe { &*event_loop }, context.clone(), pending)?;
+    let window_id = webview.inner.window().id();
+    // the webview collection is locked while the event is handled
+    CREATED_WEBVIEWS.with(|created| created.borrow_mut().push(webview));
+    let dispatcher = WryDispatcher {
+      window_id,
+      context: context.clone(),
+    };
+    return Ok(DetachedWindow { label, dispatcher });
+  }
+  let (tx, rx) = channel();
+  let dispatcher_context = context.clone();
+  context
+    .proxy
+    .send_event(Message::CreateWebview(
+      Arc::new(Mutex::new(Some(Box::new(move |event_loop| {
+        create_webview(event_loop, dispatcher_context, pending)
+      })))),
+      tx,
+    ))
+    .map_err(|_| Error::FailedToSendMessage)?;
+  let window_id = rx.recv().map_err(|_| Error::FailedToReceiveMessage)??;
+  let dispatcher = WryDispatcher {
+    window_id,
+    context: context.clone(),
+  };
+  Ok(DetachedWindow { label, dispatcher })
+}
+
+#[cfg(feature = "system-tray")]
+fn create_tray<I: MenuId>(
+  event_loop: &EventLoopWindowTarget<Message>,
+  system_tray: SystemTray<I>,
+) -> Result<(WrySystemTray, HashMap<u32, WryCustomMenuItem>)> {
+  let icon = system_tray
+    .icon
+    .expect("tray icon not set")
+    .into_tray_icon();
+
+  let mut items = HashMap::new();
+
+  let tray = SystemTrayBuilder::new(
+    icon,
+    system_tray
+      .menu
+      .map(|menu| to_wry_context_menu(&mut items, menu)),
+  )
+  .build(event_loop)
+  .map_err(|e| Error::SystemTray(Box::new(e)))?;
+
+  Ok((tray, items))
+}
+
+/// Calls the window event handlers, cloned out of the listeners so the handlers can add or remove listeners.
+fn emit_window_event(listeners: &WindowEventListeners, event: &WindowEvent) {
+  let handlers: Vec<WindowEventHandler> = listeners.lock().unwrap().values().cloned().collect();
+  for handler in handlers {
+    (handler.lock().unwrap())(event);
+  }
+}
+
 fn center_window(window: &Window) -> Result<()> {
   if let Some(monitor) = window.current_monitor() {
     let screen_size = monitor.size();
@@ -1451,6 +2537,80 @@ fn create_webview<P: Params<Runtime = Wry>>(
     ..
   } = pending;
 
+  // WKWebView only uses the system proxy settings
+  #[cfg(target_os = "macos")]
+  {
+    if window_builder.proxy.is_some() {
+      return Err(Error::Unsupported("the webview proxy on macOS".into()));
+    }
+  }
+  #[cfg_attr(target_os = "macos", allow(unused_variables))]
+  let shared_settings = {
+    let mut shared_settings = context.shared_webview_settings.lock().unwrap();
+    let shared_settings = shared_settings.get_or_insert_with(|| SharedWebviewSettings {
+      proxy: window_builder.proxy.clone(),
+      user_agent: window_builder.user_agent.clone(),
+    });
+    if window_builder.proxy.is_some() && window_builder.proxy != shared_settings.proxy {
+      return Err(Error::Unsupported(
+        "a proxy different from the proxy of the first window".into(),
+      ));
+    }
+    #[cfg(windows)]
+    if window_builder.user_agent.is_some()
+      && window_builder.user_agent != shared_settings.user_agent
+    {
+      return Err(Error::Unsupported(
+        "a user agent different from the user agent of the first window".into(),
+      ));
+    }
+    shared_settings.clone()
+  };
+  #[cfg(windows)]
+  let _environment = set_webview_environment(&webview_attributes, &shared_settings)?;
+  #[cfg(not(windows))]
+  let user_agent = window_builder.user_agent;
+  let incognito_data_directory = if window_builder.incognito {
+    Some(IncognitoDataDirectory::new())
+  } else {
+    None
+  };
+  let spellcheck = window_builder.spellcheck;
+  let spellcheck_languages = window_builder.spellcheck_languages;
+  // WebView2 isn't reachable from the window, and WebKit only has the system languages on macOS
+  let navigation_handler = window_builder.navigation_handler;
+  let permission_handler = window_builder.permission_handler;
+  #[cfg(windows)]
+  {
+    if spellcheck.is_some() {
+      return Err(Error::Unsupported(
+        "the spellcheck setting on Windows".into(),
+      ));
+    }
+    if navigation_handler.is_some() {
+      return Err(Error::Unsupported(
+        "the navigation handler on Windows".into(),
+      ));
+    }
+    if permission_handler.is_some() {
+      return Err(Error::Unsupported(
+        "the permission handler on Windows".into(),
+      ));
+    }
+    if webview_attributes.ipc_allowed_origins.is_some() {
+      return Err(Error::Unsupported(
+        "the IPC origin allowlist on Windows".into(),
+      ));
+    }
+  }
+  #[cfg(not(target_os = "linux"))]
+  {
+    if !spellcheck_languages.is_empty() {
+      return Err(Error::Unsupported(
+        "the spellcheck languages on macOS and Windows".into(),
+      ));
+    }
+  }
   let is_window_transparent = window_builder.inner.window.transparent;
   #[cfg(feature = "menu")]
   let menu_items = window_builder.menu_items;
@@ -1460,23 +2620,134 @@ fn create_webview<P: Params<Runtime = Wry>>(
   }
   let mut webview_builder = WebViewBuilder::new(window)
     .map_err(|e| Error::CreateWebview(Box::new(e)))?
-    .with_url(&url)
-    .unwrap() // safe to unwrap because we validate the URL beforehand
     .with_transparent(is_window_transparent);
-  if let Some(handler) = rpc_handler {
-    webview_builder =
-      webview_builder.with_rpc_handler(create_rpc_handler(context.clone(), label.clone(), handler));
+  // the other webviews load the URL once their native settings and handlers are set
+  #[cfg(windows)]
+  {
+    webview_builder = webview_builder.with_url(&url).unwrap(); // safe to unwrap because we validate the URL beforehand
+  }
+  // the page can't read the initialization scripts, so they can send the token
+  let rpc_token = Uuid::new_v4().to_string();
+  webview_builder = webview_builder
+    .with_initialization_script(INTERNAL_RPC_SCRIPT)
+    .with_initialization_script(
+      &NAVIGATED_SCRIPT
+        .replace("__RPC_TOKEN__", &rpc_token)
+        .replace("__NAVIGATED_RPC_METHOD__", NAVIGATED_RPC_METHOD),
+    );
+  // WebView2 handles the zoom hotkeys itself
+  let zoom_hotkeys_enabled = webview_attributes.zoom_hotkeys_enabled;
+  #[cfg(not(windows))]
+  {
+    if zoom_hotkeys_enabled {
+      webview_builder = webview_builder.with_initialization_script(
+        &ZOOM_HOTKEYS_SCRIPT
+          .replace("__RPC_TOKEN__", &rpc_token)
+          .replace("__ZOOM_RPC_METHOD__", ZOOM_RPC_METHOD),
+      );
+    }
   }
-  if let Some(handler) = file_drop_handler {
-    webview_builder =
-      webview_builder.with_file_drop_handler(create_file_drop_handler(context, label, handler));
+  let rpc_handler =
+    rpc_handler.map(|handler| create_rpc_handler(context.clone(), label.clone(), handler));
+  let script_results = context.script_results.clone();
+  let script_results_ = script_results.clone();
+  let window_event_listeners = context.window_event_listeners.clone();
+  let proxy = context.proxy.clone();
+  let styles: Arc<Mutex<HashMap<Uuid, String>>> = Default::default();
+  let styles_ = styles.clone();
+  let init_scripts: Arc<Mutex<Vec<String>>> = Default::default();
+  let init_scripts_ = init_scripts.clone();
+  // the internal RPC methods are always handled, even if the webview has no Tauri RPC handler
+  let rpc_token_ = rpc_token.clone();
+  webview_builder = webview_builder.with_rpc_handler(Box::new(move |window, request| {
+    if !INTERNAL_RPC_METHODS.contains(&request.method.as_str()) {
+      return rpc_handler
+        .as_ref()
+        .and_then(|handler| handler(window, request));
+    }
+    // the calls without the token come from the page
+    let params = match request
+      .params
+      .as_ref()
+      .and_then(|params| internal_rpc_params(params, &rpc_token_))
+    {
+      Some(params) => params,
+      None => return None,
+    };
+    if request.method == ZOOM_RPC_METHOD {
+      if zoom_hotkeys_enabled {
+        let zoom = params
+          .get(0)
+          .and_then(|action| action.as_str())
+          .and_then(|action| {
+            native::zoom(window)
+              .ok()
+              .and_then(|zoom| zoom_hotkey(zoom, action))
+          });
+        if let Some(zoom) = zoom {
+          let _ = native::set_zoom(window, zoom);
+        }
+      }
+    } else if request.method == NAVIGATED_RPC_METHOD {
+      if let Some(url) = params
+        .get(0)
+        .and_then(|url| url.as_str())
+        .and_then(|url| Url::parse(url).ok())
+      {
+        // the scripts evaluated on the previous document won't return their result
+        if params.get(1).and_then(|loaded| loaded.as_bool()) == Some(true) {
+          cancel_script_results(&script_results_, window.id());
+        }
+        // the injected stylesheets must be applied again
+        for (id, css) in styles_.lock().unwrap().iter() {
+          let _ = proxy.send_event(Message::Webview(
+            window.id(),
+            WebviewMessage::EvaluateScript(inject_css_script(*id, css)),
+          ));
+        }
+        // the scripts added at runtime are executed once on each new page
+        for (index, script) in init_scripts_.lock().unwrap().iter().enumerate() {
+          let _ = proxy.send_event(Message::Webview(
+            window.id(),
+            WebviewMessage::EvaluateScript(init_script(index, script)),
+          ));
+        }
+        emit_window_event(&window_event_listeners, &WindowEvent::Navigated(url));
+      }
+    } else if request.method == SCRIPT_RESULT_RPC_METHOD {
+      let sender = params
+        .get(0)
+        .and_then(|id| id.as_str())
+        .and_then(|id| Uuid::parse_str(id).ok())
+        .and_then(|id| script_results_.lock().unwrap().remove(&id));
+      if let Some((_, sender)) = sender {
+        let result = match params.get(1).and_then(|error| error.as_str()) {
+          Some(error) => Err(error.to_string()),
+          None => Ok(params.get(2).cloned().unwrap_or(JsonValue::Null)),
+        };
+        let _ = sender.send(result);
+      }
+    }
+    None
+  }));
+  let file_drop_state = Arc::new(Mutex::new(FileDropState::default()));
+  let file_drop_handler =
+    file_drop_handler.map(|handler| create_file_drop_handler(context, label, handler));
+  if let Some(handler) = file_drop_handler.clone() {
+    let state = file_drop_state.clone();
+    webview_builder = webview_builder.with_file_drop_handler(move |window, event| {
+      let event = state.lock().unwrap().to_event(window, event);
+      handler(window, event)
+    });
   }
   for (scheme, protocol) in webview_attributes.uri_scheme_protocols {
     webview_builder = webview_builder.with_custom_protocol(scheme, move |_window, url| {
       protocol(url).map_err(|_| wry::Error::InitScriptError)
     });
   }
-  if let Some(data_directory) = webview_attributes.data_directory {
+  if let Some(incognito_data_directory) = &incognito_data_directory {
+    webview_builder = webview_builder.with_data_directory(incognito_data_directory.0.clone());
+  } else if let Some(data_directory) = webview_attributes.data_directory {
     webview_builder = webview_builder.with_data_directory(data_directory);
   }
   for script in webview_attributes.initialization_scripts {
@@ -1486,14 +2757,142 @@ fn create_webview<P: Params<Runtime = Wry>>(
   let webview = webview_builder
     .build()
     .map_err(|e| Error::CreateWebview(Box::new(e)))?;
+  let pending_script_results = PendingScriptResults {
+    script_results,
+    window_id: webview.window().id(),
+  };
+  if let Some(handler) = navigation_handler {
+    policy::set_navigation_handler(webview.window(), handler);
+  }
+  if let Some(handler) = permission_handler {
+    policy::set_permission_handler(webview.window(), handler);
+  }
+  policy::set_spellcheck(webview.window(), spellcheck, &spellcheck_languages);
+  // WebView2 reads the user agent from the environment
+  #[cfg(not(windows))]
+  {
+    if let Some(user_agent) = &user_agent {
+      native::set_user_agent(webview.window(), user_agent)?;
+    }
+    // the network process of the web context is started with the first page load
+    #[cfg(target_os = "linux")]
+    {
+      if let Some(proxy) = &shared_settings.proxy {
+        native::set_proxy(webview.window(), proxy)?;
+      }
+    }
+    native::load_url(webview.window(), &url)?;
+  }
 
   Ok(WebviewWrapper {
     inner: webview,
+    _incognito_data_directory: incognito_data_directory,
+    _pending_script_results: pending_script_results,
+    rpc_token,
+    file_drop_state,
+    file_drop_handler,
+    search: None,
+    styles,
+    init_scripts,
     #[cfg(feature = "menu")]
     menu_items,
   })
 }
 
+/// Process environment variables set while a webview is created, restored to their previous values when dropped.
+///
+/// wry doesn't expose the WebView2 environment options, so they are set with their environment variables,
+/// read by WebView2 when the first webview starts its browser process.
+/// Unlike `setenv` on the other platforms, `SetEnvironmentVariableW` is thread-safe.
+#[cfg(windows)]
+#[derive(Default)]
+struct WebviewEnvironment(Vec<(&'static str, Option<std::ffi::OsString>)>);
+
+#[cfg(windows)]
+impl WebviewEnvironment {
+  fn set<V: AsRef<std::ffi::OsStr>>(&mut self, name: &'static str, value: Option<V>) {
+    self.0.push((name, std::env::var_os(name)));
+    match value {
+      Some(value) => std::env::set_var(name, value),
+      None => std::env::remove_var(name),
+    }
+  }
+}
+
+#[cfg(windows)]
+impl Drop for WebviewEnvironment {
+  fn drop(&mut self) {
+    for (name, value) in self.0.drain(..).rev() {
+      match value {
+        Some(value) => std::env::set_var(name, value),
+        None => std::env::remove_var(name),
+      }
+    }
+  }
+}
+
+/// The arguments of the WebView2 browser process, with the app-wide proxy and user agent.
+///
+/// WebView2 doesn't support the proxy credentials, it prompts the user for them.
+#[cfg_attr(not(windows), allow(dead_code))]
+fn webview2_browser_args(
+  additional_args: Option<&str>,
+  shared_settings: &SharedWebviewSettings,
+) -> Option<String> {
+  let mut args = Vec::new();
+  if let Some(additional_args) = additional_args {
+    args.push(additional_args.to_string());
+  }
+  if let Some(proxy) = &shared_settings.proxy {
+    args.push(format!(
+      "--proxy-server={}://{}:{}",
+      proxy.scheme(),
+      proxy.host,
+      proxy.port
+    ));
+  }
+  if let Some(user_agent) = &shared_settings.user_agent {
+    args.push(format!(
+      "--user-agent=\"{}\"",
+      user_agent.replace('"', "\\\"")
+    ));
+  }
+  if args.is_empty() {
+    None
+  } else {
+    Some(args.join(" "))
+  }
+}
+
+/// Configures the WebView2 environment when a webview is created.
+///
+/// The proxy and the user agent are app-wide: WebView2 starts a single browser process
+/// for all the webviews, so the settings of the first window are used for every window.
+#[cfg(windows)]
+fn set_webview_environment(
+  webview_attributes: &WebviewAttributes,
+  shared_settings: &SharedWebviewSettings,
+) -> Result<WebviewEnvironment> {
+  let mut environment = WebviewEnvironment::default();
+  if let Some(path) = &webview_attributes.webview2_runtime_path {
+    if !path.is_dir() {
+      return Err(Error::WebviewRuntimeNotFound(path.clone()));
+    }
+  }
+  environment.set(
+    "WEBVIEW2_BROWSER_EXECUTABLE_FOLDER",
+    webview_attributes.webview2_runtime_path.as_ref(),
+  );
+  environment.set(
+    "WEBVIEW2_ADDITIONAL_BROWSER_ARGUMENTS",
+    webview2_browser_args(
+      webview_attributes.additional_browser_args.as_deref(),
+      shared_settings,
+    ),
+  );
+  Ok(environment)
+}
+
 /// Create a wry rpc handler from a tauri rpc handler.
 fn create_rpc_handler<P: Params<Runtime = Wry>>(
   context: DispatcherContext,
@@ -1501,6 +2900,8 @@ fn create_rpc_handler<P: Params<Runtime = Wry>>(
   handler: WebviewRpcHandler<P>,
 ) -> Box<dyn Fn(&Window, WryRpcRequest) -> Option<RpcResponse> + 'static> {
   Box::new(move |window, request| {
+    let mut request: RpcRequest = RpcRequestWrapper(request).into();
+    request.url = policy::webview_url(window);
     handler(
       DetachedWindow {
         dispatcher: WryDispatcher {
@@ -1509,7 +2910,7 @@ fn create_rpc_handler<P: Params<Runtime = Wry>>(
         },
         label: label.clone(),
       },
-      RpcRequestWrapper(request).into(),
+      request,
     );
     None
   })
@@ -1520,10 +2921,10 @@ fn create_file_drop_handler<P: Params<Runtime = Wry>>(
   context: DispatcherContext,
   label: P::Label,
   handler: FileDropHandler<P>,
-) -> Box<dyn Fn(&Window, WryFileDropEvent) -> bool + 'static> {
-  Box::new(move |window, event| {
+) -> WryFileDropHandler {
+  Arc::new(move |window, event| {
     handler(
-      FileDropEventWrapper(event).into(),
+      event,
       DetachedWindow {
         dispatcher: WryDispatcher {
           window_id: window.id(),
@@ -1534,3 +2935,101 @@ fn create_file_drop_handler<P: Params<Runtime = Wry>>(
     )
   })
 }
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn script_with_result_escapes_the_script() {
+    let id = Uuid::new_v4();
+    let script = script_with_result(id, "token", "\"); window.close(); (\"");
+    assert!(script.contains(&format!("(0, eval)({})", r#""\"); window.close(); (\"""#)));
+    assert!(script.contains(&format!(
+      r#""token", "{}", "{}""#,
+      SCRIPT_RESULT_RPC_METHOD, id
+    )));
+  }
+
+  #[test]
+  fn internal_rpc_calls_require_the_token() {
+    let params = serde_json::json!(["token", "in"]);
+    assert_eq!(
+      internal_rpc_params(&params, "token"),
+      Some(&[JsonValue::String("in".into())][..])
+    );
+    assert_eq!(internal_rpc_params(&params, "other token"), None);
+    assert_eq!(
+      internal_rpc_params(&serde_json::json!(["token"]), "token"),
+      Some(&[][..])
+    );
+    assert_eq!(internal_rpc_params(&serde_json::json!([]), "token"), None);
+    assert_eq!(
+      internal_rpc_params(&serde_json::json!([1, "in"]), "1"),
+      None
+    );
+    assert_eq!(
+      internal_rpc_params(&serde_json::json!("token"), "token"),
+      None
+    );
+  }
+
+  #[test]
+  fn cancels_the_pending_script_results() {
+    let script_results = ScriptResults::default();
+    let window_id = unsafe { WindowId::dummy() };
+    let (tx, rx) = channel();
+    script_results
+      .lock()
+      .unwrap()
+      .insert(Uuid::new_v4(), (window_id, tx));
+    cancel_script_results(&script_results, window_id);
+    assert!(script_results.lock().unwrap().is_empty());
+    assert!(rx.recv().is_err());
+
+    let (tx, rx) = channel();
+    script_results
+      .lock()
+      .unwrap()
+      .insert(Uuid::new_v4(), (window_id, tx));
+    drop(PendingScriptResults {
+      script_results: script_results.clone(),
+      window_id,
+    });
+    assert!(rx.recv().is_err());
+  }
+
+  #[test]
+  fn webview2_browser_args_include_the_shared_settings() {
+    let mut shared_settings = SharedWebviewSettings {
+      proxy: None,
+      user_agent: None,
+    };
+    assert_eq!(webview2_browser_args(None, &shared_settings), None);
+    assert_eq!(
+      webview2_browser_args(Some("--disable-gpu"), &shared_settings),
+      Some("--disable-gpu".into())
+    );
+
+    shared_settings.proxy =
+      Some(ProxyConfig::socks5("localhost", 1080).credentials("user", "password"));
+    shared_settings.user_agent = Some(r#"Tauri "test""#.into());
+    assert_eq!(
+      webview2_browser_args(Some("--disable-gpu"), &shared_settings),
+      Some(
+        r#"--disable-gpu --proxy-server=socks5://localhost:1080 --user-agent="Tauri \"test\"""#
+          .into()
+      )
+    );
+  }
+
+  #[test]
+  fn zoom_hotkeys_are_clamped() {
+    assert_eq!(zoom_hotkey(1.0, "in"), Some(1.0 + ZOOM_STEP));
+    assert_eq!(zoom_hotkey(1.0, "out"), Some(1.0 - ZOOM_STEP));
+    assert_eq!(zoom_hotkey(3.0, "reset"), Some(1.0));
+    assert_eq!(zoom_hotkey(MAX_ZOOM, "in"), Some(MAX_ZOOM));
+    assert_eq!(zoom_hotkey(MIN_ZOOM, "out"), Some(MIN_ZOOM));
+    assert_eq!(zoom_hotkey(1.0, "0.5"), None);
+  }
+}
@@ -0,0 +1,782 @@
+// Copyright 2019-2021 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+//! Native APIs of the webviews that wry doesn't expose.
+//!
+//! wry doesn't expose the webview, so it is looked up in the views of the window.
+//! WebView2 isn't reachable from the window, so these APIs return [`Error::Unsupported`] on Windows.
+
+use tauri_runtime::{
+  webview::{DataTypes, FindOptions, FindResult},
+  Error, Result,
+};
+use wry::application::window::Window;
+
+use std::sync::mpsc::Sender;
+
+#[cfg(target_os = "macos")]
+pub(crate) use platform::ns_string;
+#[cfg(target_os = "linux")]
+pub(crate) use platform::set_proxy;
+pub(crate) use platform::{
+  clear_browsing_data, clear_find, find, find_next, is_playing_audio, set_muted, set_user_agent,
+  set_zoom, zoom,
+};
+#[cfg(feature = "devtools")]
+pub(crate) use platform::{close_devtools, is_devtools_open, open_devtools};
+#[cfg(not(windows))]
+pub(crate) use platform::{find_webview, load_url, webview};
+
+/// Returns an error if the webview APIs aren't supported on the current platform.
+pub(crate) fn check_supported(api: &str) -> Result<()> {
+  if cfg!(windows) {
+    Err(unsupported(api))
+  } else {
+    Ok(())
+  }
+}
+
+/// Returns an error if the audio of the webviews can't be controlled on the current platform.
+///
+/// `WKWebView` only has private APIs to mute the page.
+pub(crate) fn check_audio_supported() -> Result<()> {
+  if cfg!(target_os = "linux") {
+    Ok(())
+  } else {
+    Err(unsupported_audio())
+  }
+}
+
+fn unsupported(api: &str) -> Error {
+  Error::Unsupported(format!("{} on Windows", api))
+}
+
+fn unsupported_audio() -> Error {
+  Error::Unsupported("the webview audio on macOS and Windows".into())
+}
+
+/// The `WKWebsiteDataType` values of the browsing data types.
+#[cfg_attr(not(target_os = "macos"), allow(dead_code))]
+fn website_data_types(data_types: DataTypes) -> Vec<&'static str> {
+  let mut types = Vec::new();
+  if data_types.cookies {
+    types.push("WKWebsiteDataTypeCookies");
+  }
+  if data_types.cache {
+    types.extend(&[
+      "WKWebsiteDataTypeDiskCache",
+      "WKWebsiteDataTypeMemoryCache",
+      "WKWebsiteDataTypeOfflineWebApplicationCache",
+      "WKWebsiteDataTypeFetchCache",
+    ]);
+  }
+  if data_types.local_storage {
+    types.extend(&[
+      "WKWebsiteDataTypeLocalStorage",
+      "WKWebsiteDataTypeSessionStorage",
+    ]);
+  }
+  if data_types.indexed_db {
+    types.extend(&[
+      "WKWebsiteDataTypeIndexedDBDatabases",
+      "WKWebsiteDataTypeWebSQLDatabases",
+    ]);
+  }
+  types
+}
+
+/// The `WebKitWebsiteDataTypes` flags of the browsing data types.
+#[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+fn website_data_flags(data_types: DataTypes) -> u32 {
+  const MEMORY_CACHE: u32 = 1 << 0;
+  const DISK_CACHE: u32 = 1 << 1;
+  const OFFLINE_APPLICATION_CACHE: u32 = 1 << 2;
+  const SESSION_STORAGE: u32 = 1 << 3;
+  const LOCAL_STORAGE: u32 = 1 << 4;
+  const WEBSQL_DATABASES: u32 = 1 << 5;
+  const INDEXEDDB_DATABASES: u32 = 1 << 6;
+  const COOKIES: u32 = 1 << 8;
+
+  let mut flags = 0;
+  if data_types.cookies {
+    flags |= COOKIES;
+  }
+  if data_types.cache {
+    flags |= MEMORY_CACHE | DISK_CACHE | OFFLINE_APPLICATION_CACHE;
+  }
+  if data_types.local_storage {
+    flags |= LOCAL_STORAGE | SESSION_STORAGE;
+  }
+  if data_types.indexed_db {
+    flags |= INDEXEDDB_DATABASES | WEBSQL_DATABASES;
+  }
+  flags
+}
+
+#[cfg(target_os = "macos")]
+mod platform {
+  use super::{
+    unsupported_audio, website_data_types, DataTypes, Error, FindOptions, FindResult, Result,
+    Sender, Window,
+  };
+
+  use block::ConcreteBlock;
+  use cocoa::{
+    base::{id, nil, BOOL, NO, YES},
+    foundation::{NSPoint, NSString},
+  };
+  use objc::{class, msg_send, runtime::Sel, sel, sel_impl};
+  use wry::application::platform::macos::WindowExtMacOS;
+
+  pub(crate) unsafe fn find_webview(view: id) -> Option<id> {
+    let is_webview: BOOL = msg_send![view, isKindOfClass: class!(WKWebView)];
+    if is_webview == YES {
+      return Some(view);
+    }
+    let subviews: id = msg_send![view, subviews];
+    let count: usize = msg_send![subviews, count];
+    (0..count).find_map(|index| {
+      let subview: id = msg_send![subviews, objectAtIndex: index];
+      find_webview(subview)
+    })
+  }
+
+  /// The `WKWebView` of the window.
+  pub(crate) unsafe fn webview(window: &Window) -> Result<id> {
+    let content_view: id = msg_send![window.ns_window() as id, contentView];
+    find_webview(content_view).ok_or(Error::WebviewNotFound)
+  }
+
+  unsafe fn responds_to(object: id, selector: Sel) -> bool {
+    let responds: BOOL = msg_send![object, respondsToSelector: selector];
+    responds == YES
+  }
+
+  pub(crate) unsafe fn ns_string(string: &str) -> id {
+    let string = NSString::alloc(nil).init_str(string);
+    msg_send![string, autorelease]
+  }
+
+  /// Removes the data of all the websites from the data store of the webview.
+  pub fn clear_browsing_data(window: &Window, data_types: DataTypes, tx: Sender<Result<()>>) {
+    let result = unsafe {
+      webview(window).map(|webview| {
+        let configuration: id = msg_send![webview, configuration];
+        let data_store: id = msg_send![configuration, websiteDataStore];
+        let types: id = msg_send![class!(NSMutableSet), set];
+        for data_type in website_data_types(data_types) {
+          let _: () = msg_send![types, addObject: ns_string(data_type)];
+        }
+        let since: id = msg_send![class!(NSDate), distantPast];
+        let tx = tx.clone();
+        let completion_handler = ConcreteBlock::new(move || {
+          let _ = tx.send(Ok(()));
+        })
+        .copy();
+        let _: () = msg_send![data_store, removeDataOfTypes: types modifiedSince: since completionHandler: &*completion_handler];
+      })
+    };
+    if let Err(e) = result {
+      let _ = tx.send(Err(e));
+    }
+  }
+
+  pub fn load_url(window: &Window, url: &str) -> Result<()> {
+    unsafe {
+      let webview = webview(window)?;
+      let url: id = msg_send![class!(NSURL), URLWithString: ns_string(url)];
+      let request: id = msg_send![class!(NSURLRequest), requestWithURL: url];
+      let _: id = msg_send![webview, loadRequest: request];
+    }
+    Ok(())
+  }
+
+  pub fn set_user_agent(window: &Window, user_agent: &str) -> Result<()> {
+    unsafe {
+      let webview = webview(window)?;
+      let _: () = msg_send![webview, setCustomUserAgent: ns_string(user_agent)];
+    }
+    Ok(())
+  }
+
+  /// The private `_WKInspector` of the webview, added on macOS 10.14.4.
+  #[cfg(feature = "devtools")]
+  unsafe fn inspector(webview: id) -> Result<id> {
+    if responds_to(webview, sel!(_inspector)) {
+      Ok(msg_send![webview, _inspector])
+    } else {
+      Err(Error::Unsupported(
+        "the devtools before macOS 10.14.4".into(),
+      ))
+    }
+  }
+
+  #[cfg(feature = "devtools")]
+  pub fn open_devtools(window: &Window) -> Result<()> {
+    unsafe {
+      let webview = webview(window)?;
+      let configuration: id = msg_send![webview, configuration];
+      let preferences: id = msg_send![configuration, preferences];
+      let enabled: id = msg_send![class!(NSNumber), numberWithBool: YES];
+      let _: () =
+        msg_send![preferences, setValue: enabled forKey: ns_string("developerExtrasEnabled")];
+      let _: () = msg_send![inspector(webview)?, show];
+    }
+    Ok(())
+  }
+
+  #[cfg(feature = "devtools")]
+  pub fn close_devtools(window: &Window) -> Result<()> {
+    unsafe {
+      let _: () = msg_send![inspector(webview(window)?)?, close];
+    }
+    Ok(())
+  }
+
+  #[cfg(feature = "devtools")]
+  pub fn is_devtools_open(window: &Window) -> Result<bool> {
+    unsafe {
+      let visible: BOOL = msg_send![inspector(webview(window)?)?, isVisible];
+      Ok(visible == YES)
+    }
+  }
+
+  pub fn is_playing_audio(_window: &Window) -> Result<bool> {
+    Err(unsupported_audio())
+  }
+
+  pub fn set_muted(_window: &Window, _muted: bool) -> Result<()> {
+    Err(unsupported_audio())
+  }
+
+  /// Searches the text from the current selection, calling the handler with whether it was found.
+  ///
+  /// `findString:withConfiguration:completionHandler:` was added on macOS 11.
+  unsafe fn find_string<F: Fn(bool) + 'static>(
+    window: &Window,
+    text: &str,
+    options: &FindOptions,
+    backwards: bool,
+    handler: F,
+  ) -> Result<()> {
+    let webview = webview(window)?;
+    if !responds_to(
+      webview,
+      sel!(findString:withConfiguration:completionHandler:),
+    ) {
+      return Err(Error::Unsupported("find in page before macOS 11".into()));
+    }
+    let bool_value = |value: bool| if value { YES } else { NO };
+    let configuration: id = msg_send![class!(WKFindConfiguration), new];
+    let _: () = msg_send![configuration, setBackwards: bool_value(backwards)];
+    let _: () = msg_send![configuration, setCaseSensitive: bool_value(options.case_sensitive)];
+    let _: () = msg_send![configuration, setWraps: bool_value(options.wrap_around)];
+    let completion_handler = ConcreteBlock::new(move |result: id| {
+      let found: BOOL = msg_send![result, matchFound];
+      handler(found == YES);
+    })
+    .copy();
+    let _: () = msg_send![
+      webview,
+      findString: ns_string(text)
+      withConfiguration: configuration
+      completionHandler: &*completion_handler
+    ];
+    let _: () = msg_send![configuration, release];
+    Ok(())
+  }
+
+  /// WebKit doesn't count the matches on macOS.
+  pub fn find(window: &Window, text: &str, options: &FindOptions, tx: Sender<Result<FindResult>>) {
+    let tx_ = tx.clone();
+    let result = unsafe {
+      find_string(window, text, options, false, move |found| {
+        let _ = tx_.send(Ok(FindResult {
+          matches: None,
+          found,
+        }));
+      })
+    };
+    if let Err(e) = result {
+      let _ = tx.send(Err(e));
+    }
+  }
+
+  pub fn find_next(
+    window: &Window,
+    text: &str,
+    options: &FindOptions,
+    backwards: bool,
+    tx: Sender<Result<bool>>,
+  ) {
+    let tx_ = tx.clone();
+    let result = unsafe {
+      find_string(window, text, options, backwards, move |found| {
+        let _ = tx_.send(Ok(found));
+      })
+    };
+    if let Err(e) = result {
+      let _ = tx.send(Err(e));
+    }
+  }
+
+  /// The match is highlighted with the selection of the page.
+  pub fn clear_find(window: &Window) -> Result<()> {
+    unsafe {
+      let webview = webview(window)?;
+      let _: () = msg_send![
+        webview,
+        evaluateJavaScript: ns_string("window.getSelection().removeAllRanges()")
+        completionHandler: nil
+      ];
+    }
+    Ok(())
+  }
+
+  /// The page zoom is only available on macOS 11 and newer, the older versions magnify the webview instead.
+  pub fn zoom(window: &Window) -> Result<f64> {
+    unsafe {
+      let webview = webview(window)?;
+      let zoom: f64 = if responds_to(webview, sel!(pageZoom)) {
+        msg_send![webview, pageZoom]
+      } else {
+        msg_send![webview, magnification]
+      };
+      Ok(zoom)
+    }
+  }
+
+  pub fn set_zoom(window: &Window, zoom: f64) -> Result<()> {
+    unsafe {
+      let webview = webview(window)?;
+      if responds_to(webview, sel!(setPageZoom:)) {
+        let _: () = msg_send![webview, setPageZoom: zoom];
+      } else {
+        let _: () =
+          msg_send![webview, setMagnification: zoom centeredAtPoint: NSPoint::new(0., 0.)];
+      }
+    }
+    Ok(())
+  }
+}
+
+#[cfg(target_os = "linux")]
+mod platform {
+  use super::{
+    website_data_flags, DataTypes, Error, FindOptions, FindResult, Result, Sender, Window,
+  };
+
+  use glib::{object::ObjectType, SignalHandlerId};
+  use gtk::prelude::*;
+  use tauri_runtime::webview::ProxyConfig;
+  use webkit2gtk::{FindController, FindControllerExt, SettingsExt, WebView, WebViewExt};
+  #[cfg(feature = "devtools")]
+  use webkit2gtk::{WebInspector, WebInspectorExt};
+
+  use std::{
+    cell::RefCell,
+    ffi::{c_void, CStr, CString},
+    os::raw::{c_char, c_int},
+    ptr,
+    rc::Rc,
+  };
+  use wry::application::platform::unix::WindowExtUnix;
+
+  /// `WEBKIT_NETWORK_PROXY_MODE_CUSTOM`.
+  const NETWORK_PROXY_MODE_CUSTOM: c_int = 2;
+
+  /// `GError`.
+  #[repr(C)]
+  struct GError {
+    domain: u32,
+    code: c_int,
+    message: *mut c_char,
+  }
+
+  // the network proxy settings and the website data manager were added on WebKitGTK 2.16 and 2.10
+  // and aren't bound by `webkit2gtk`
+  extern "C" {
+    fn webkit_web_context_get_website_data_manager(context: *mut c_void) -> *mut c_void;
+    fn webkit_website_data_manager_clear(
+      manager: *mut c_void,
+      types: u32,
+      timespan: i64,
+      cancellable: *mut c_void,
+      callback: unsafe extern "C" fn(*mut c_void, *mut c_void, *mut c_void),
+      user_data: *mut c_void,
+    );
+    fn webkit_website_data_manager_clear_finish(
+      manager: *mut c_void,
+      result: *mut c_void,
+      error: *mut *mut GError,
+    ) -> c_int;
+    fn g_error_free(error: *mut GError);
+    fn webkit_network_proxy_settings_new(
+      default_proxy_uri: *const c_char,
+      ignore_hosts: *const *const c_char,
+    ) -> *mut c_void;
+    fn webkit_network_proxy_settings_free(settings: *mut c_void);
+    fn webkit_web_context_set_network_proxy_settings(
+      context: *mut c_void,
+      mode: c_int,
+      settings: *mut c_void,
+    );
+  }
+
+  pub(crate) fn find_webview(widget: &gtk::Widget) -> Option<WebView> {
+    if let Some(webview) = widget.downcast_ref::<WebView>() {
+      return Some(webview.clone());
+    }
+    widget
+      .downcast_ref::<gtk::Container>()?
+      .get_children()
+      .iter()
+      .find_map(find_webview)
+  }
+
+  pub(crate) fn webview(window: &Window) -> Result<WebView> {
+    find_webview(window.gtk_window().upcast_ref()).ok_or(Error::WebviewNotFound)
+  }
+
+  pub fn load_url(window: &Window, url: &str) -> Result<()> {
+    webview(window)?.load_uri(url);
+    Ok(())
+  }
+
+  pub fn set_user_agent(window: &Window, user_agent: &str) -> Result<()> {
+    if let Some(settings) = webview(window)?.get_settings() {
+      settings.set_user_agent(Some(user_agent));
+    }
+    Ok(())
+  }
+
+  #[cfg(feature = "devtools")]
+  fn inspector(webview: &WebView) -> Result<WebInspector> {
+    webview.get_inspector().ok_or(Error::WebviewNotFound)
+  }
+
+  #[cfg(feature = "devtools")]
+  pub fn open_devtools(window: &Window) -> Result<()> {
+    let webview = webview(window)?;
+    // the inspector is only available with the developer extras
+    if let Some(settings) = webview.get_settings() {
+      settings.set_enable_developer_extras(true);
+    }
+    inspector(&webview)?.show();
+    Ok(())
+  }
+
+  #[cfg(feature = "devtools")]
+  pub fn close_devtools(window: &Window) -> Result<()> {
+    inspector(&webview(window)?)?.close();
+    Ok(())
+  }
+
+  /// The inspector has no webview until it's opened, and releases it when it's closed.
+  #[cfg(feature = "devtools")]
+  pub fn is_devtools_open(window: &Window) -> Result<bool> {
+    Ok(inspector(&webview(window)?)?.get_web_view().is_some())
+  }
+
+  /// Removes the data of all the websites from the data manager of the web context of the webview.
+  pub fn clear_browsing_data(window: &Window, data_types: DataTypes, tx: Sender<Result<()>>) {
+    unsafe extern "C" fn callback(
+      manager: *mut c_void,
+      result: *mut c_void,
+      user_data: *mut c_void,
+    ) {
+      let tx = Box::from_raw(user_data as *mut Sender<Result<()>>);
+      let mut error = ptr::null_mut();
+      webkit_website_data_manager_clear_finish(manager, result, &mut error);
+      let result = if error.is_null() {
+        Ok(())
+      } else {
+        let message = CStr::from_ptr((*error).message)
+          .to_string_lossy()
+          .into_owned();
+        g_error_free(error);
+        Err(Error::ClearBrowsingData(message))
+      };
+      let _ = tx.send(result);
+    }
+
+    let context = match webview(window)
+      .and_then(|webview| webview.get_context().ok_or(Error::WebviewNotFound))
+    {
+      Ok(context) => context,
+      Err(e) => {
+        let _ = tx.send(Err(e));
+        return;
+      }
+    };
+    unsafe {
+      let manager = webkit_web_context_get_website_data_manager(context.as_ptr() as *mut c_void);
+      // a zero timespan clears the data of any age
+      webkit_website_data_manager_clear(
+        manager,
+        website_data_flags(data_types),
+        0,
+        ptr::null_mut(),
+        callback,
+        Box::into_raw(Box::new(tx)) as *mut c_void,
+      );
+    }
+  }
+
+  /// Sets the proxy of the web context of the webview, used by its network process.
+  ///
+  /// The credentials are sent from the proxy URL.
+  pub fn set_proxy(window: &Window, proxy: &ProxyConfig) -> Result<()> {
+    let context = webview(window)?
+      .get_context()
+      .ok_or(Error::WebviewNotFound)?;
+    let url = CString::new(proxy.url()).map_err(|e| Error::CreateWebview(Box::new(e)))?;
+    unsafe {
+      let settings = webkit_network_proxy_settings_new(url.as_ptr(), std::ptr::null());
+      webkit_web_context_set_network_proxy_settings(
+        context.as_ptr() as *mut c_void,
+        NETWORK_PROXY_MODE_CUSTOM,
+        settings,
+      );
+      webkit_network_proxy_settings_free(settings);
+    }
+    Ok(())
+  }
+
+  /// `WEBKIT_FIND_OPTIONS_CASE_INSENSITIVE`.
+  const FIND_CASE_INSENSITIVE: u32 = 1 << 0;
+  /// `WEBKIT_FIND_OPTIONS_WRAP_AROUND`.
+  const FIND_WRAP_AROUND: u32 = 1 << 4;
+  /// The maximum number of matches counted by a search.
+  const MAX_FIND_MATCHES: u32 = 1000;
+
+  fn find_controller(window: &Window) -> Result<FindController> {
+    webview(window)?
+      .get_find_controller()
+      .ok_or(Error::WebviewNotFound)
+  }
+
+  /// Calls the handler with the result of the next search of the controller.
+  fn on_find_result<F: FnOnce(FindResult) + 'static>(controller: &FindController, handler: F) {
+    let handler = RefCell::new(Some(handler));
+    let signal_handlers: Rc<RefCell<Vec<SignalHandlerId>>> = Default::default();
+    let finish = {
+      let signal_handlers = signal_handlers.clone();
+      Rc::new(move |controller: &FindController, result: FindResult| {
+        for signal_handler in signal_handlers.borrow_mut().drain(..) {
+          controller.disconnect(signal_handler);
+        }
+        if let Some(handler) = handler.borrow_mut().take() {
+          handler(result);
+        }
+      })
+    };
+    let finish_ = finish.clone();
+    let found = controller.connect_found_text(move |controller, matches| {
+      finish_(
+        controller,
+        FindResult {
+          // WebKit sends `G_MAXUINT` when there are more matches than the maximum
+          matches: if matches > MAX_FIND_MATCHES {
+            None
+          } else {
+            Some(matches)
+          },
+          found: true,
+        },
+      );
+    });
+    let failed = controller.connect_failed_to_find_text(move |controller| {
+      finish(
+        controller,
+        FindResult {
+          matches: Some(0),
+          found: false,
+        },
+      );
+    });
+    signal_handlers.borrow_mut().extend(vec![found, failed]);
+  }
+
+  pub fn find(window: &Window, text: &str, options: &FindOptions, tx: Sender<Result<FindResult>>) {
+    let controller = match find_controller(window) {
+      Ok(controller) => controller,
+      Err(e) => {
+        let _ = tx.send(Err(e));
+        return;
+      }
+    };
+    on_find_result(&controller, move |result| {
+      let _ = tx.send(Ok(result));
+    });
+    let mut flags = 0;
+    if !options.case_sensitive {
+      flags |= FIND_CASE_INSENSITIVE;
+    }
+    if options.wrap_around {
+      flags |= FIND_WRAP_AROUND;
+    }
+    controller.search(text, flags, MAX_FIND_MATCHES);
+  }
+
+  /// The search text and options are kept by the find controller.
+  pub fn find_next(
+    window: &Window,
+    _text: &str,
+    _options: &FindOptions,
+    backwards: bool,
+    tx: Sender<Result<bool>>,
+  ) {
+    let controller = match find_controller(window) {
+      Ok(controller) => controller,
+      Err(e) => {
+        let _ = tx.send(Err(e));
+        return;
+      }
+    };
+    on_find_result(&controller, move |result| {
+      let _ = tx.send(Ok(result.found));
+    });
+    if backwards {
+      controller.search_previous();
+    } else {
+      controller.search_next();
+    }
+  }
+
+  pub fn clear_find(window: &Window) -> Result<()> {
+    find_controller(window)?.search_finish();
+    Ok(())
+  }
+
+  pub fn is_playing_audio(window: &Window) -> Result<bool> {
+    Ok(webview(window)?.is_playing_audio())
+  }
+
+  /// The `is-muted` property was added on WebKitGTK 2.30.
+  pub fn set_muted(window: &Window, muted: bool) -> Result<()> {
+    webview(window)?
+      .set_property("is-muted", &muted)
+      .map_err(|_| Error::Unsupported("muting the webview before WebKitGTK 2.30".into()))
+  }
+
+  pub fn zoom(window: &Window) -> Result<f64> {
+    Ok(webview(window)?.get_zoom_level())
+  }
+
+  pub fn set_zoom(window: &Window, zoom: f64) -> Result<()> {
+    webview(window)?.set_zoom_level(zoom);
+    Ok(())
+  }
+}
+
+#[cfg(windows)]
+mod platform {
+  use super::{
+    unsupported, unsupported_audio, DataTypes, FindOptions, FindResult, Result, Sender, Window,
+  };
+
+  pub fn clear_browsing_data(_window: &Window, _data_types: DataTypes, tx: Sender<Result<()>>) {
+    let _ = tx.send(Err(unsupported("clearing the browsing data")));
+  }
+
+  pub fn find(
+    _window: &Window,
+    _text: &str,
+    _options: &FindOptions,
+    tx: Sender<Result<FindResult>>,
+  ) {
+    let _ = tx.send(Err(unsupported("find in page")));
+  }
+
+  pub fn find_next(
+    _window: &Window,
+    _text: &str,
+    _options: &FindOptions,
+    _backwards: bool,
+    tx: Sender<Result<bool>>,
+  ) {
+    let _ = tx.send(Err(unsupported("find in page")));
+  }
+
+  pub fn clear_find(_window: &Window) -> Result<()> {
+    Err(unsupported("find in page"))
+  }
+
+  /// The user agent of WebView2 is set on its browser process when the first webview is created.
+  pub fn set_user_agent(_window: &Window, _user_agent: &str) -> Result<()> {
+    Err(unsupported("changing the user agent of a webview"))
+  }
+
+  pub fn is_playing_audio(_window: &Window) -> Result<bool> {
+    Err(unsupported_audio())
+  }
+
+  pub fn set_muted(_window: &Window, _muted: bool) -> Result<()> {
+    Err(unsupported_audio())
+  }
+
+  #[cfg(feature = "devtools")]
+  pub fn open_devtools(_window: &Window) -> Result<()> {
+    Err(unsupported("the devtools"))
+  }
+
+  #[cfg(feature = "devtools")]
+  pub fn close_devtools(_window: &Window) -> Result<()> {
+    Err(unsupported("the devtools"))
+  }
+
+  #[cfg(feature = "devtools")]
+  pub fn is_devtools_open(_window: &Window) -> Result<bool> {
+    Err(unsupported("the devtools"))
+  }
+
+  pub fn zoom(_window: &Window) -> Result<f64> {
+    Err(unsupported("the webview zoom"))
+  }
+
+  pub fn set_zoom(_window: &Window, _zoom: f64) -> Result<()> {
+    Err(unsupported("the webview zoom"))
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn clears_the_website_data_of_the_data_types() {
+    assert!(website_data_types(DataTypes::default()).is_empty());
+    assert_eq!(website_data_flags(DataTypes::default()), 0);
+
+    let cookies = DataTypes {
+      cookies: true,
+      ..Default::default()
+    };
+    assert_eq!(
+      website_data_types(cookies),
+      vec!["WKWebsiteDataTypeCookies"]
+    );
+    assert_eq!(website_data_flags(cookies), 1 << 8);
+
+    let storage = DataTypes {
+      local_storage: true,
+      indexed_db: true,
+      ..Default::default()
+    };
+    assert_eq!(
+      website_data_types(storage),
+      vec![
+        "WKWebsiteDataTypeLocalStorage",
+        "WKWebsiteDataTypeSessionStorage",
+        "WKWebsiteDataTypeIndexedDBDatabases",
+        "WKWebsiteDataTypeWebSQLDatabases",
+      ]
+    );
+    assert_eq!(website_data_flags(storage), 0b111_1000);
+
+    assert_eq!(website_data_types(DataTypes::all()).len(), 9);
+    assert_eq!(website_data_flags(DataTypes::all()), 0b1_0111_1111);
+  }
+}
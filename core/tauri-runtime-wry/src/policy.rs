@@ -0,0 +1,554 @@
+// Copyright 2019-2021 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+//! Native policy callbacks and settings of the webviews, so the navigation and permission handlers
+//! and the spellcheck settings can't be bypassed by the page.
+//!
+//! wry doesn't expose the webview, so it is looked up in the views of the window with [`crate::native::find_webview`].
+//! WebView2 isn't reachable from the window, so on Windows the URL of the webview is unknown and the navigation
+//! and permission handlers and the IPC origin allowlist are rejected when the webview is created.
+
+use crate::{NavigationHandler, PermissionHandler};
+use wry::application::window::Window;
+
+pub(crate) use platform::{
+  set_navigation_handler, set_permission_handler, set_spellcheck, webview_url,
+};
+
+#[cfg(target_os = "macos")]
+mod platform {
+  use super::{NavigationHandler, PermissionHandler, Window};
+  use crate::native::find_webview;
+
+  use cocoa::base::{id, nil, BOOL, NO, YES};
+  use objc::{
+    class,
+    declare::ClassDecl,
+    msg_send,
+    runtime::{Class, Object, Sel},
+    sel, sel_impl,
+  };
+  use tauri_runtime::webview::{PermissionKind, PermissionRequest};
+  use url::Url;
+  use wry::application::platform::macos::WindowExtMacOS;
+
+  use std::{
+    ffi::{c_void, CStr},
+    os::raw::c_char,
+  };
+
+  /// `WKNavigationActionPolicyCancel`.
+  const POLICY_CANCEL: isize = 0;
+  /// `WKNavigationActionPolicyAllow`.
+  const POLICY_ALLOW: isize = 1;
+  /// `WKPermissionDecisionGrant`.
+  const PERMISSION_GRANT: isize = 1;
+  /// `WKPermissionDecisionDeny`.
+  const PERMISSION_DENY: isize = 2;
+  /// `WKMediaCaptureTypeCamera`.
+  const MEDIA_CAPTURE_CAMERA: isize = 0;
+  /// `WKMediaCaptureTypeMicrophone`.
+  const MEDIA_CAPTURE_MICROPHONE: isize = 1;
+  /// `OBJC_ASSOCIATION_RETAIN_NONATOMIC`.
+  const ASSOCIATION_RETAIN: usize = 1;
+  /// The key of the navigation delegate associated to the webview, as it only keeps a weak reference to it.
+  static NAVIGATION_DELEGATE_KEY: u8 = 0;
+  /// The key of the UI delegate associated to the webview.
+  static UI_DELEGATE_KEY: u8 = 1;
+
+  extern "C" {
+    fn objc_setAssociatedObject(object: id, key: *const c_void, value: id, policy: usize);
+  }
+
+  unsafe fn ns_url(url: id) -> Option<Url> {
+    if url == nil {
+      return None;
+    }
+    let url: id = msg_send![url, absoluteString];
+    let url: *const c_char = msg_send![url, UTF8String];
+    if url.is_null() {
+      return None;
+    }
+    CStr::from_ptr(url)
+      .to_str()
+      .ok()
+      .and_then(|url| Url::parse(url).ok())
+  }
+
+  unsafe fn handler<T>(this: &Object) -> &T {
+    &*(*this.get_ivar::<*mut c_void>("handler") as *const T)
+  }
+
+  /// The delegate set by wry before the Tauri delegate, which receives the messages the Tauri delegate doesn't handle.
+  unsafe fn previous_delegate(this: &Object) -> id {
+    *this.get_ivar::<id>("previous")
+  }
+
+  extern "C" fn responds_to_selector(this: &Object, _cmd: Sel, selector: Sel) -> BOOL {
+    unsafe {
+      let responds: BOOL = msg_send![super(this, class!(NSObject)), respondsToSelector: selector];
+      let previous = previous_delegate(this);
+      if responds == YES || previous == nil {
+        return responds;
+      }
+      msg_send![previous, respondsToSelector: selector]
+    }
+  }
+
+  extern "C" fn forwarding_target_for_selector(this: &Object, _cmd: Sel, selector: Sel) -> id {
+    unsafe {
+      let previous = previous_delegate(this);
+      if previous == nil {
+        return nil;
+      }
+      let responds: BOOL = msg_send![previous, respondsToSelector: selector];
+      if responds == YES {
+        previous
+      } else {
+        nil
+      }
+    }
+  }
+
+  extern "C" fn decide_policy(
+    this: &Object,
+    _cmd: Sel,
+    webview: id,
+    action: id,
+    decision_handler: id,
+  ) {
+    unsafe {
+      let handler = handler::<NavigationHandler>(this);
+      let request: id = msg_send![action, request];
+      let allowed = ns_url(msg_send![request, URL])
+        .map(|url| (handler.0)(&url))
+        .unwrap_or(true);
+      // the navigations allowed by the handler are still checked by the previous delegate
+      let previous = previous_delegate(this);
+      if allowed && previous != nil {
+        let responds: BOOL = msg_send![
+          previous,
+          respondsToSelector: sel!(webView:decidePolicyForNavigationAction:decisionHandler:)
+        ];
+        if responds == YES {
+          let _: () = msg_send![previous, webView: webview decidePolicyForNavigationAction: action decisionHandler: decision_handler];
+          return;
+        }
+      }
+      let decision_handler = &*(decision_handler as *const block::Block<(isize,), ()>);
+      decision_handler.call((if allowed { POLICY_ALLOW } else { POLICY_CANCEL },));
+    }
+  }
+
+  extern "C" fn request_media_capture_permission(
+    this: &Object,
+    _cmd: Sel,
+    webview: id,
+    _origin: id,
+    _frame: id,
+    capture_type: isize,
+    decision_handler: id,
+  ) {
+    unsafe {
+      let handler = handler::<PermissionHandler>(this);
+      let kinds: &[PermissionKind] = match capture_type {
+        MEDIA_CAPTURE_CAMERA => &[PermissionKind::Camera],
+        MEDIA_CAPTURE_MICROPHONE => &[PermissionKind::Microphone],
+        _ => &[PermissionKind::Camera, PermissionKind::Microphone],
+      };
+      let granted = ns_url(msg_send![webview, URL])
+        .map(|url| {
+          kinds.iter().all(|kind| {
+            (handler.0)(&PermissionRequest {
+              kind: *kind,
+              url: url.clone(),
+            })
+          })
+        })
+        .unwrap_or(false);
+      let decision_handler = &*(decision_handler as *const block::Block<(isize,), ()>);
+      decision_handler.call((if granted {
+        PERMISSION_GRANT
+      } else {
+        PERMISSION_DENY
+      },));
+    }
+  }
+
+  extern "C" fn dealloc_navigation_delegate(this: &Object, _cmd: Sel) {
+    unsafe {
+      let handler = *this.get_ivar::<*mut c_void>("handler");
+      drop(Box::from_raw(handler as *mut NavigationHandler));
+      let _: () = msg_send![previous_delegate(this), release];
+      let _: () = msg_send![super(this, class!(NSObject)), dealloc];
+    }
+  }
+
+  extern "C" fn dealloc_ui_delegate(this: &Object, _cmd: Sel) {
+    unsafe {
+      let handler = *this.get_ivar::<*mut c_void>("handler");
+      drop(Box::from_raw(handler as *mut PermissionHandler));
+      let _: () = msg_send![previous_delegate(this), release];
+      let _: () = msg_send![super(this, class!(NSObject)), dealloc];
+    }
+  }
+
+  /// Declares the ivars of a delegate class and the methods forwarding the other messages to the previous delegate.
+  fn declare_delegate_class(name: &str) -> ClassDecl {
+    let mut decl = ClassDecl::new(name, class!(NSObject))
+      .unwrap_or_else(|| panic!("failed to declare the {} class", name));
+    decl.add_ivar::<*mut c_void>("handler");
+    decl.add_ivar::<id>("previous");
+    unsafe {
+      decl.add_method(
+        sel!(respondsToSelector:),
+        responds_to_selector as extern "C" fn(&Object, Sel, Sel) -> BOOL,
+      );
+      decl.add_method(
+        sel!(forwardingTargetForSelector:),
+        forwarding_target_for_selector as extern "C" fn(&Object, Sel, Sel) -> id,
+      );
+    }
+    decl
+  }
+
+  /// The `WKNavigationDelegate` of the webviews with a navigation handler.
+  fn navigation_delegate_class() -> &'static Class {
+    if let Some(class) = Class::get("TauriNavigationDelegate") {
+      return class;
+    }
+    let mut decl = declare_delegate_class("TauriNavigationDelegate");
+    unsafe {
+      decl.add_method(
+        sel!(webView:decidePolicyForNavigationAction:decisionHandler:),
+        decide_policy as extern "C" fn(&Object, Sel, id, id, id),
+      );
+      decl.add_method(
+        sel!(dealloc),
+        dealloc_navigation_delegate as extern "C" fn(&Object, Sel),
+      );
+    }
+    decl.register()
+  }
+
+  /// The `WKUIDelegate` of the webviews with a permission handler.
+  fn ui_delegate_class() -> &'static Class {
+    if let Some(class) = Class::get("TauriUIDelegate") {
+      return class;
+    }
+    let mut decl = declare_delegate_class("TauriUIDelegate");
+    unsafe {
+      decl.add_method(
+        sel!(webView:requestMediaCapturePermissionForOrigin:initiatedByFrame:type:decisionHandler:),
+        request_media_capture_permission as extern "C" fn(&Object, Sel, id, id, id, isize, id),
+      );
+      decl.add_method(
+        sel!(dealloc),
+        dealloc_ui_delegate as extern "C" fn(&Object, Sel),
+      );
+    }
+    decl.register()
+  }
+
+  /// Creates a delegate of the given class holding the handler and retaining the previous delegate.
+  unsafe fn new_delegate<T>(class: &Class, handler: T, previous: id) -> id {
+    let delegate: id = msg_send![class, new];
+    (*delegate).set_ivar("handler", Box::into_raw(Box::new(handler)) as *mut c_void);
+    let previous: id = msg_send![previous, retain];
+    (*delegate).set_ivar("previous", previous);
+    delegate
+  }
+
+  /// Creates a delegate of the given class holding the handler, retained by the webview of the window.
+  ///
+  /// The delegate replaces the one set by wry, `previous` returns it so the delegate can forward it
+  /// the messages it doesn't handle.
+  unsafe fn set_delegate<T>(
+    window: &Window,
+    class: &Class,
+    key: &'static u8,
+    handler: T,
+    previous: impl FnOnce(id) -> id,
+  ) -> Option<(id, id)> {
+    let content_view: id = msg_send![window.ns_window() as id, contentView];
+    let webview = find_webview(content_view)?;
+    let delegate = new_delegate(class, handler, previous(webview));
+    objc_setAssociatedObject(
+      webview,
+      key as *const u8 as *const c_void,
+      delegate,
+      ASSOCIATION_RETAIN,
+    );
+    let _: () = msg_send![delegate, release];
+    Some((webview, delegate))
+  }
+
+  /// Sets the continuous spellcheck and the autocorrection of the webview.
+  ///
+  /// The text checker state of WebKit is shared by all the webviews of the app.
+  /// The languages are rejected when the webview is created.
+  pub fn set_spellcheck(window: &Window, enabled: Option<bool>, _languages: &[String]) {
+    let enabled = match enabled {
+      Some(true) => YES,
+      Some(false) => NO,
+      None => return,
+    };
+    unsafe {
+      let content_view: id = msg_send![window.ns_window() as id, contentView];
+      let webview = match find_webview(content_view) {
+        Some(webview) => webview,
+        None => return,
+      };
+      let responds: BOOL = msg_send![
+        webview,
+        respondsToSelector: sel!(setContinuousSpellCheckingEnabled:)
+      ];
+      if responds == YES {
+        let _: () = msg_send![webview, setContinuousSpellCheckingEnabled: enabled];
+      }
+      let responds: BOOL = msg_send![
+        webview,
+        respondsToSelector: sel!(setAutomaticSpellingCorrectionEnabled:)
+      ];
+      if responds == YES {
+        let _: () = msg_send![webview, setAutomaticSpellingCorrectionEnabled: enabled];
+      }
+    }
+  }
+
+  /// The URL of the document loaded on the main frame of the webview.
+  pub fn webview_url(window: &Window) -> Option<Url> {
+    unsafe {
+      let content_view: id = msg_send![window.ns_window() as id, contentView];
+      let webview = find_webview(content_view)?;
+      let url: id = msg_send![webview, URL];
+      ns_url(url)
+    }
+  }
+
+  /// The other messages of the navigation delegate are still handled by wry,
+  /// and the navigations allowed by the handler are still checked by its delegate.
+  pub fn set_navigation_handler(window: &Window, handler: NavigationHandler) {
+    unsafe {
+      if let Some((webview, delegate)) = set_delegate(
+        window,
+        navigation_delegate_class(),
+        &NAVIGATION_DELEGATE_KEY,
+        handler,
+        |webview| msg_send![webview, navigationDelegate],
+      ) {
+        let _: () = msg_send![webview, setNavigationDelegate: delegate];
+      }
+    }
+  }
+
+  /// Only the camera and microphone requests are sent to the handler, on macOS 12 and newer.
+  /// The other messages of the UI delegate (file panels, alerts...) are still handled by wry.
+  pub fn set_permission_handler(window: &Window, handler: PermissionHandler) {
+    unsafe {
+      if let Some((webview, delegate)) = set_delegate(
+        window,
+        ui_delegate_class(),
+        &UI_DELEGATE_KEY,
+        handler,
+        |webview| msg_send![webview, UIDelegate],
+      ) {
+        let _: () = msg_send![webview, setUIDelegate: delegate];
+      }
+    }
+  }
+
+  #[cfg(test)]
+  mod tests {
+    use super::*;
+    use std::sync::Arc;
+
+    extern "C" fn did_finish_navigation(_this: &Object, _cmd: Sel, _webview: id, _navigation: id) {}
+
+    #[test]
+    fn forwards_the_unhandled_messages_to_the_previous_delegate() {
+      unsafe {
+        let mut decl = ClassDecl::new("TauriTestPreviousDelegate", class!(NSObject)).unwrap();
+        decl.add_method(
+          sel!(webView:didFinishNavigation:),
+          did_finish_navigation as extern "C" fn(&Object, Sel, id, id),
+        );
+        let previous: id = msg_send![decl.register(), new];
+        let delegate = new_delegate(
+          navigation_delegate_class(),
+          NavigationHandler(Arc::new(|_| true)),
+          previous,
+        );
+
+        let responds: BOOL = msg_send![
+          delegate,
+          respondsToSelector: sel!(webView:didFinishNavigation:)
+        ];
+        assert_eq!(responds, YES);
+        let target: id = msg_send![
+          delegate,
+          forwardingTargetForSelector: sel!(webView:didFinishNavigation:)
+        ];
+        assert_eq!(target, previous);
+
+        let responds: BOOL = msg_send![
+          delegate,
+          respondsToSelector: sel!(webView:decidePolicyForNavigationAction:decisionHandler:)
+        ];
+        assert_eq!(responds, YES);
+        let responds: BOOL = msg_send![
+          delegate,
+          respondsToSelector: sel!(webView:didFailNavigation:withError:)
+        ];
+        assert_eq!(responds, NO);
+        let target: id = msg_send![
+          delegate,
+          forwardingTargetForSelector: sel!(webView:didFailNavigation:withError:)
+        ];
+        assert_eq!(target, nil);
+
+        let _: () = msg_send![delegate, release];
+        let _: () = msg_send![previous, release];
+      }
+    }
+  }
+}
+
+#[cfg(target_os = "linux")]
+mod platform {
+  use super::{NavigationHandler, PermissionHandler, Window};
+  use crate::native::find_webview;
+
+  use gtk::prelude::*;
+  use tauri_runtime::webview::{PermissionKind, PermissionRequest};
+  use url::Url;
+  use webkit2gtk::{
+    GeolocationPermissionRequest, NavigationPolicyDecision, NavigationPolicyDecisionExt,
+    NotificationPermissionRequest, PermissionRequestExt, PolicyDecisionExt, PolicyDecisionType,
+    URIRequestExt, UserMediaPermissionRequest, UserMediaPermissionRequestExt, WebContextExt,
+    WebViewExt,
+  };
+  use wry::application::platform::unix::WindowExtUnix;
+
+  /// Sets the spellcheck of the web context of the webview, shared with the webviews using the same context.
+  ///
+  /// WebKitGTK doesn't autocorrect the text, and the dictionaries are named with underscores (`en_US`).
+  pub fn set_spellcheck(window: &Window, enabled: Option<bool>, languages: &[String]) {
+    let context = match find_webview(window.gtk_window().upcast_ref())
+      .and_then(|webview| webview.get_context())
+    {
+      Some(context) => context,
+      None => return,
+    };
+    if !languages.is_empty() {
+      let languages: Vec<String> = languages
+        .iter()
+        .map(|language| language.replace('-', "_"))
+        .collect();
+      let languages: Vec<&str> = languages.iter().map(String::as_str).collect();
+      context.set_spell_checking_languages(&languages);
+    }
+    // the spellcheck is disabled by default, so setting the languages enables it
+    match enabled {
+      Some(enabled) => context.set_spell_checking_enabled(enabled),
+      None if !languages.is_empty() => context.set_spell_checking_enabled(true),
+      None => {}
+    }
+  }
+
+  /// The URL of the document loaded on the main frame of the webview.
+  pub fn webview_url(window: &Window) -> Option<Url> {
+    find_webview(window.gtk_window().upcast_ref())?
+      .get_uri()
+      .and_then(|url| Url::parse(&url).ok())
+  }
+
+  pub fn set_navigation_handler(window: &Window, handler: NavigationHandler) {
+    if let Some(webview) = find_webview(window.gtk_window().upcast_ref()) {
+      webview.connect_decide_policy(move |_, decision, decision_type| {
+        if decision_type != PolicyDecisionType::NavigationAction
+          && decision_type != PolicyDecisionType::NewWindowAction
+        {
+          return false;
+        }
+        let url = decision
+          .downcast_ref::<NavigationPolicyDecision>()
+          .and_then(|decision| decision.get_navigation_action())
+          .and_then(|mut action| action.get_request())
+          .and_then(|request| request.get_uri())
+          .and_then(|url| Url::parse(&url).ok());
+        match url {
+          Some(url) if !(handler.0)(&url) => {
+            decision.ignore();
+            true
+          }
+          // the default policy is used for the allowed navigations
+          _ => false,
+        }
+      });
+    }
+  }
+
+  /// The clipboard read requests aren't sent to the handler, WebKitGTK doesn't ask for them.
+  pub fn set_permission_handler(window: &Window, handler: PermissionHandler) {
+    if let Some(webview) = find_webview(window.gtk_window().upcast_ref()) {
+      webview.connect_permission_request(move |webview, request| {
+        let kinds = if request.is::<GeolocationPermissionRequest>() {
+          vec![PermissionKind::Geolocation]
+        } else if request.is::<NotificationPermissionRequest>() {
+          vec![PermissionKind::Notifications]
+        } else if let Some(request) = request.downcast_ref::<UserMediaPermissionRequest>() {
+          let mut kinds = Vec::new();
+          if request.get_property_is_for_video_device() {
+            kinds.push(PermissionKind::Camera);
+          }
+          if request.get_property_is_for_audio_device() {
+            kinds.push(PermissionKind::Microphone);
+          }
+          kinds
+        } else {
+          // the default handler denies the other requests
+          return false;
+        };
+        let granted = webview
+          .get_uri()
+          .and_then(|url| Url::parse(&url).ok())
+          .map(|url| {
+            kinds.into_iter().all(|kind| {
+              (handler.0)(&PermissionRequest {
+                kind,
+                url: url.clone(),
+              })
+            })
+          })
+          .unwrap_or(false);
+        if granted {
+          request.allow();
+        } else {
+          request.deny();
+        }
+        true
+      });
+    }
+  }
+}
+
+#[cfg(windows)]
+mod platform {
+  use super::{NavigationHandler, PermissionHandler, Window};
+  use url::Url;
+
+  pub fn webview_url(_window: &Window) -> Option<Url> {
+    None
+  }
+
+  /// The spellcheck settings are rejected when the webview is created.
+  pub fn set_spellcheck(_window: &Window, _enabled: Option<bool>, _languages: &[String]) {}
+
+  /// The navigation handler is rejected when the webview is created.
+  pub fn set_navigation_handler(_window: &Window, _handler: NavigationHandler) {}
+
+  /// The permission handler is rejected when the webview is created.
+  pub fn set_permission_handler(_window: &Window, _handler: PermissionHandler) {}
+}
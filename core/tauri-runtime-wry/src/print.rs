@@ -0,0 +1,362 @@
+// Copyright 2019-2021 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+//! Native print operations of the webviews, applying the page setup of the print options.
+//!
+//! The PDF files are written by the print operations of the webview engines, without showing the print dialog.
+//! WebView2 isn't reachable from the window, so the print options and the PDF output are unsupported on Windows.
+
+use tauri_runtime::{
+  webview::{PageSize, PrintOptions},
+  Error, Result,
+};
+use wry::application::window::Window;
+
+use std::{path::Path, sync::mpsc::Sender};
+
+pub(crate) use platform::print_with_options;
+
+/// Prints the contents of the webview to the PDF file at `path`, sending whether it was written.
+pub(crate) fn print_to_pdf(
+  window: &Window,
+  path: &Path,
+  options: &PrintOptions,
+  tx: Sender<Result<()>>,
+) {
+  if path.is_absolute() {
+    platform::print_to_pdf(window, path, options, tx);
+  } else {
+    let _ = tx.send(Err(Error::Print(format!(
+      "the PDF path `{}` must be absolute",
+      path.display()
+    ))));
+  }
+}
+
+/// The width and height of the page size in portrait orientation, in millimeters.
+#[cfg_attr(windows, allow(dead_code))]
+pub(crate) fn paper_size(page_size: PageSize) -> (f64, f64) {
+  match page_size {
+    PageSize::A4 => (210.0, 297.0),
+    PageSize::Letter => (215.9, 279.4),
+    PageSize::Legal => (215.9, 355.6),
+    PageSize::Custom { width, height } => (width, height),
+  }
+}
+
+/// Converts millimeters to PostScript points, the unit of the AppKit page setup.
+#[cfg_attr(not(target_os = "macos"), allow(dead_code))]
+pub(crate) fn points(millimeters: f64) -> f64 {
+  millimeters * 72.0 / 25.4
+}
+
+/// The name of the GTK paper size of a standard page size.
+#[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+pub(crate) fn gtk_paper_name(page_size: PageSize) -> Option<&'static str> {
+  match page_size {
+    PageSize::A4 => Some("iso_a4"),
+    PageSize::Letter => Some("na_letter"),
+    PageSize::Legal => Some("na_legal"),
+    PageSize::Custom { .. } => None,
+  }
+}
+
+#[cfg(target_os = "macos")]
+mod platform {
+  use super::{paper_size, points, Error, Path, PrintOptions, Result, Sender, Window};
+  use crate::native::{ns_string, webview};
+
+  use cocoa::{
+    base::{id, nil, BOOL, NO, YES},
+    foundation::{NSRect, NSSize},
+  };
+  use objc::{
+    class,
+    declare::ClassDecl,
+    msg_send,
+    runtime::{Class, Object, Sel},
+    sel, sel_impl,
+  };
+
+  use std::{ffi::c_void, ptr};
+
+  /// `NSPaperOrientationPortrait`.
+  const PAPER_ORIENTATION_PORTRAIT: isize = 0;
+  /// `NSPaperOrientationLandscape`.
+  const PAPER_ORIENTATION_LANDSCAPE: isize = 1;
+
+  #[link(name = "AppKit", kind = "framework")]
+  extern "C" {
+    static NSPrintJobSavingURL: id;
+    static NSPrintSaveJob: id;
+  }
+
+  /// A copy of the shared print info with the page setup of the options.
+  unsafe fn print_info(options: &PrintOptions) -> id {
+    let shared: id = msg_send![class!(NSPrintInfo), sharedPrintInfo];
+    let print_info: id = msg_send![shared, copy];
+    let print_info: id = msg_send![print_info, autorelease];
+    // AppKit matches the orientation to the paper size, so the orientation is set last
+    if let Some(page_size) = options.page_size {
+      let (width, height) = paper_size(page_size);
+      let _: () = msg_send![
+        print_info,
+        setPaperSize: NSSize::new(points(width), points(height))
+      ];
+    }
+    if let Some(margins) = options.margins {
+      let _: () = msg_send![print_info, setTopMargin: points(margins.top)];
+      let _: () = msg_send![print_info, setRightMargin: points(margins.right)];
+      let _: () = msg_send![print_info, setBottomMargin: points(margins.bottom)];
+      let _: () = msg_send![print_info, setLeftMargin: points(margins.left)];
+    }
+    let orientation = if options.landscape {
+      PAPER_ORIENTATION_LANDSCAPE
+    } else {
+      PAPER_ORIENTATION_PORTRAIT
+    };
+    let _: () = msg_send![print_info, setOrientation: orientation];
+    print_info
+  }
+
+  /// The print operation of the webview, available on macOS 11 and later.
+  unsafe fn print_operation(webview: id, print_info: id) -> Result<id> {
+    let responds: BOOL = msg_send![
+      webview,
+      respondsToSelector: sel!(printOperationWithPrintInfo:)
+    ];
+    if responds == NO {
+      return Err(Error::Unsupported(
+        "the native print operation before macOS 11".into(),
+      ));
+    }
+    let operation: id = msg_send![webview, printOperationWithPrintInfo: print_info];
+    // the print view of the webview has an empty frame, which prints blank pages
+    let view: id = msg_send![operation, view];
+    let frame: NSRect = msg_send![webview, frame];
+    let _: () = msg_send![view, setFrame: frame];
+    Ok(operation)
+  }
+
+  extern "C" fn print_operation_did_run(
+    this: &Object,
+    _cmd: Sel,
+    _operation: id,
+    success: BOOL,
+    context: *mut c_void,
+  ) {
+    unsafe {
+      let tx = Box::from_raw(context as *mut Sender<Result<()>>);
+      let _ = tx.send(if success == YES {
+        Ok(())
+      } else {
+        Err(Error::Print("the print operation failed".into()))
+      });
+      let _: () = msg_send![this, release];
+    }
+  }
+
+  fn print_delegate_class() -> &'static Class {
+    if let Some(class) = Class::get("TauriPrintDelegate") {
+      return class;
+    }
+    let mut decl = ClassDecl::new("TauriPrintDelegate", class!(NSObject))
+      .expect("failed to declare the print delegate class");
+    unsafe {
+      decl.add_method(
+        sel!(printOperationDidRun:success:contextInfo:),
+        print_operation_did_run as extern "C" fn(&Object, Sel, id, BOOL, *mut c_void),
+      );
+    }
+    decl.register()
+  }
+
+  pub fn print_with_options(window: &Window, options: &PrintOptions) -> Result<()> {
+    unsafe {
+      let webview = webview(window)?;
+      let operation = print_operation(webview, print_info(options))?;
+      let ns_window: id = msg_send![webview, window];
+      let _: () = msg_send![operation, runOperationModalForWindow: ns_window delegate: nil didRunSelector: ptr::null::<c_void>() contextInfo: ptr::null_mut::<c_void>()];
+    }
+    Ok(())
+  }
+
+  pub fn print_to_pdf(
+    window: &Window,
+    path: &Path,
+    options: &PrintOptions,
+    tx: Sender<Result<()>>,
+  ) {
+    let result = unsafe {
+      webview(window).and_then(|webview| {
+        let print_info = print_info(options);
+        let url: id =
+          msg_send![class!(NSURL), fileURLWithPath: ns_string(&path.to_string_lossy())];
+        let dictionary: id = msg_send![print_info, dictionary];
+        let _: () = msg_send![dictionary, setObject: url forKey: NSPrintJobSavingURL];
+        let _: () = msg_send![print_info, setJobDisposition: NSPrintSaveJob];
+        let operation = print_operation(webview, print_info)?;
+        let _: () = msg_send![operation, setShowsPrintPanel: NO];
+        let _: () = msg_send![operation, setShowsProgressPanel: NO];
+        // released by the delegate once the operation ran
+        let delegate: id = msg_send![print_delegate_class(), new];
+        let context = Box::into_raw(Box::new(tx.clone())) as *mut c_void;
+        let ns_window: id = msg_send![webview, window];
+        let _: () = msg_send![operation, runOperationModalForWindow: ns_window delegate: delegate didRunSelector: sel!(printOperationDidRun:success:contextInfo:) contextInfo: context];
+        Ok(())
+      })
+    };
+    if let Err(e) = result {
+      let _ = tx.send(Err(e));
+    }
+  }
+}
+
+#[cfg(target_os = "linux")]
+mod platform {
+  use super::{gtk_paper_name, paper_size, Error, Path, PrintOptions, Result, Sender, Window};
+  use crate::native::webview;
+
+  use gtk::prelude::*;
+  use url::Url;
+  use webkit2gtk::{PrintOperation, PrintOperationExt};
+  use wry::application::platform::unix::WindowExtUnix;
+
+  /// The GTK printer writing the print to a file.
+  const PRINT_TO_FILE_PRINTER: &str = "Print to File";
+
+  fn page_setup(options: &PrintOptions) -> gtk::PageSetup {
+    let page_setup = gtk::PageSetup::new();
+    // setting the paper size resets the margins, so the margins are set after it
+    if let Some(page_size) = options.page_size {
+      let paper_size = match gtk_paper_name(page_size) {
+        Some(name) => gtk::PaperSize::new(Some(name)),
+        None => {
+          let (width, height) = paper_size(page_size);
+          gtk::PaperSize::new_custom("custom", "Custom", width, height, gtk::Unit::Mm)
+        }
+      };
+      page_setup.set_paper_size(&paper_size);
+    }
+    if let Some(margins) = options.margins {
+      page_setup.set_top_margin(margins.top, gtk::Unit::Mm);
+      page_setup.set_right_margin(margins.right, gtk::Unit::Mm);
+      page_setup.set_bottom_margin(margins.bottom, gtk::Unit::Mm);
+      page_setup.set_left_margin(margins.left, gtk::Unit::Mm);
+    }
+    page_setup.set_orientation(if options.landscape {
+      gtk::PageOrientation::Landscape
+    } else {
+      gtk::PageOrientation::Portrait
+    });
+    page_setup
+  }
+
+  pub fn print_with_options(window: &Window, options: &PrintOptions) -> Result<()> {
+    let operation = PrintOperation::new(&webview(window)?);
+    operation.set_page_setup(&page_setup(options));
+    operation.run_dialog(Some(window.gtk_window()));
+    Ok(())
+  }
+
+  pub fn print_to_pdf(
+    window: &Window,
+    path: &Path,
+    options: &PrintOptions,
+    tx: Sender<Result<()>>,
+  ) {
+    let webview = match webview(window) {
+      Ok(webview) => webview,
+      Err(e) => {
+        let _ = tx.send(Err(e));
+        return;
+      }
+    };
+    let uri = match Url::from_file_path(path) {
+      Ok(uri) => uri,
+      Err(()) => {
+        let _ = tx.send(Err(Error::Print(format!(
+          "invalid PDF path `{}`",
+          path.display()
+        ))));
+        return;
+      }
+    };
+
+    let settings = gtk::PrintSettings::new();
+    settings.set("printer", Some(PRINT_TO_FILE_PRINTER));
+    settings.set("output-file-format", Some("pdf"));
+    settings.set("output-uri", Some(uri.as_str()));
+
+    let operation = PrintOperation::new(&webview);
+    operation.set_page_setup(&page_setup(options));
+    operation.set_print_settings(&settings);
+    // `finished` is also emitted after `failed`, so only the first result is received
+    let failed_tx = tx.clone();
+    operation.connect_failed(move |_, error| {
+      let _ = failed_tx.send(Err(Error::Print(error.to_string())));
+    });
+    operation.connect_finished(move |_| {
+      let _ = tx.send(Ok(()));
+    });
+    operation.print();
+  }
+}
+
+#[cfg(windows)]
+mod platform {
+  use super::{Error, Path, PrintOptions, Result, Sender, Window};
+
+  pub fn print_with_options(_window: &Window, _options: &PrintOptions) -> Result<()> {
+    Err(Error::Unsupported("the print options on Windows".into()))
+  }
+
+  pub fn print_to_pdf(
+    _window: &Window,
+    _path: &Path,
+    _options: &PrintOptions,
+    tx: Sender<Result<()>>,
+  ) {
+    let _ = tx.send(Err(Error::Unsupported("printing to PDF on Windows".into())));
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn paper_sizes_are_in_portrait_millimeters() {
+    assert_eq!(paper_size(PageSize::A4), (210.0, 297.0));
+    assert_eq!(paper_size(PageSize::Letter), (215.9, 279.4));
+    assert_eq!(paper_size(PageSize::Legal), (215.9, 355.6));
+    assert_eq!(
+      paper_size(PageSize::Custom {
+        width: 100.0,
+        height: 50.0
+      }),
+      (100.0, 50.0)
+    );
+  }
+
+  #[test]
+  fn millimeters_are_converted_to_points() {
+    assert!((points(25.4) - 72.0).abs() < f64::EPSILON);
+    assert!((points(210.0) - 595.275_590_551_181_1).abs() < 1e-9);
+  }
+
+  #[test]
+  fn standard_page_sizes_have_gtk_paper_names() {
+    assert_eq!(gtk_paper_name(PageSize::A4), Some("iso_a4"));
+    assert_eq!(gtk_paper_name(PageSize::Letter), Some("na_letter"));
+    assert_eq!(gtk_paper_name(PageSize::Legal), Some("na_legal"));
+    assert_eq!(
+      gtk_paper_name(PageSize::Custom {
+        width: 100.0,
+        height: 50.0
+      }),
+      None
+    );
+  }
+}
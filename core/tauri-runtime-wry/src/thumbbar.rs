@@ -0,0 +1,387 @@
+// Copyright 2019-2021 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+//! The toolbar shown on the taskbar thumbnail of the windows, only available on Windows.
+//!
+//! The taskbar only allows adding the buttons once per window, so all the slots are added hidden
+//! and the next calls update them. The clicks are received as `WM_COMMAND` messages on a window subclass.
+
+use crate::{emit_window_event, icon_err, WindowEventListeners};
+use tauri_runtime::{
+  window::{ThumbbarButton, WindowEvent},
+  Icon, Result,
+};
+
+use winapi::{
+  shared::{
+    basetsd::{DWORD_PTR, UINT_PTR},
+    guiddef::GUID,
+    minwindef::{HIWORD, LOWORD, LPARAM, LRESULT, TRUE, UINT, WPARAM},
+    windef::{HICON, HWND},
+    winerror::SUCCEEDED,
+    wtypesbase::CLSCTX_INPROC_SERVER,
+  },
+  um::{
+    combaseapi::CoCreateInstance,
+    commctrl::{DefSubclassProc, RemoveWindowSubclass, SetWindowSubclass},
+    shobjidl_core::{
+      ITaskbarList3, THBF_DISABLED, THBF_ENABLED, THBF_HIDDEN, THB_FLAGS, THB_ICON, THB_TOOLTIP,
+      THUMBBUTTON,
+    },
+    wingdi::{
+      CreateBitmap, CreateDIBSection, DeleteObject, BITMAPINFO, BITMAPINFOHEADER, BI_RGB,
+      DIB_RGB_COLORS,
+    },
+    winuser::{
+      CreateIconIndirect, DestroyIcon, RegisterWindowMessageW, ICONINFO, WM_COMMAND, WM_NCDESTROY,
+    },
+  },
+  Interface,
+};
+use wry::application::{platform::windows::WindowExtWindows, window::Window};
+
+use std::{
+  cell::RefCell, collections::HashMap, ffi::OsStr, fs::read, iter::once,
+  os::windows::ffi::OsStrExt, ptr::null_mut,
+};
+
+/// `CLSID_TaskbarList`.
+const CLSID_TASKBAR_LIST: GUID = GUID {
+  Data1: 0x56fd_f344,
+  Data2: 0xfd6d,
+  Data3: 0x11d0,
+  Data4: [0x95, 0x8a, 0x00, 0x60, 0x97, 0xc9, 0xa0, 0x90],
+};
+/// `THBN_CLICKED`, the notification code of the button clicks.
+const THBN_CLICKED: u16 = 0x1800;
+/// The id of the window subclass, unique to this module.
+const SUBCLASS_ID: UINT_PTR = 0x5448_554d;
+
+/// A thumbnail toolbar button with its icon decoded, so it can be sent to the event loop.
+#[derive(Debug, Clone)]
+pub(crate) struct NativeButton {
+  id: String,
+  rgba: Vec<u8>,
+  width: u32,
+  height: u32,
+  tooltip: Option<String>,
+  enabled: bool,
+}
+
+impl NativeButton {
+  pub(crate) fn new(button: ThumbbarButton) -> Result<Self> {
+    let image_bytes = match button.icon {
+      Icon::File(path) => read(path).map_err(icon_err)?,
+      Icon::Raw(raw) => raw,
+      _ => return Err(invalid_icon("unsupported icon source")),
+    };
+    let (rgba, width, height) = match infer::get(&image_bytes).map(|kind| kind.extension()) {
+      Some("png") => decode_png(&image_bytes)?,
+      _ => decode_ico(&image_bytes)?,
+    };
+    Ok(Self {
+      id: button.id,
+      rgba,
+      width,
+      height,
+      tooltip: button.tooltip,
+      enabled: button.enabled,
+    })
+  }
+}
+
+fn invalid_icon(message: &str) -> tauri_runtime::Error {
+  icon_err(std::io::Error::new(
+    std::io::ErrorKind::InvalidData,
+    message,
+  ))
+}
+
+/// Decodes the image of the `.ico` file closest to the thumbnail toolbar icon size.
+fn decode_ico(bytes: &[u8]) -> Result<(Vec<u8>, u32, u32)> {
+  let icon_dir = ico::IconDir::read(std::io::Cursor::new(bytes)).map_err(icon_err)?;
+  let entry = icon_dir
+    .entries()
+    .iter()
+    // the thumbnail toolbar icons are 16x16 logical pixels
+    .min_by_key(|entry| (entry.width() as i32 - 16).abs())
+    .ok_or_else(|| invalid_icon("the icon has no image"))?;
+  let image = entry.decode().map_err(icon_err)?;
+  Ok((image.rgba_data().to_vec(), image.width(), image.height()))
+}
+
+/// Decodes a `.png` file to RGBA pixels.
+fn decode_png(bytes: &[u8]) -> Result<(Vec<u8>, u32, u32)> {
+  let mut decoder = png::Decoder::new(bytes);
+  // the palette and the low bit depths are expanded and the 16-bit channels stripped, so every channel is a byte
+  decoder.set_transformations(png::Transformations::EXPAND | png::Transformations::STRIP_16);
+  let (info, mut reader) = decoder.read_info().map_err(icon_err)?;
+  let mut buffer = vec![0; info.buffer_size()];
+  reader.next_frame(&mut buffer).map_err(icon_err)?;
+  let channels = match info.color_type {
+    png::ColorType::RGBA => return Ok((buffer, info.width, info.height)),
+    png::ColorType::RGB => 3,
+    png::ColorType::GrayscaleAlpha => 2,
+    png::ColorType::Grayscale => 1,
+    png::ColorType::Indexed => return Err(invalid_icon("the palette of the icon wasn't expanded")),
+  };
+  let mut rgba = Vec::with_capacity(buffer.len() / channels * 4);
+  for pixel in buffer.chunks(channels) {
+    match pixel {
+      [r, g, b] => rgba.extend_from_slice(&[*r, *g, *b, 255]),
+      [gray, alpha] => rgba.extend_from_slice(&[*gray, *gray, *gray, *alpha]),
+      [gray] => rgba.extend_from_slice(&[*gray, *gray, *gray, 255]),
+      _ => unreachable!(),
+    }
+  }
+  Ok((rgba, info.width, info.height))
+}
+
+/// The thumbnail toolbar of a window.
+struct Toolbar {
+  /// The ids of the visible buttons, by index.
+  ids: Vec<String>,
+  buttons: Vec<NativeButton>,
+  /// The icons of the buttons, destroyed when they are replaced.
+  icons: Vec<HICON>,
+  /// Whether the buttons were added to the taskbar button.
+  added: bool,
+  listeners: WindowEventListeners,
+}
+
+thread_local! {
+  /// The toolbars of the windows, only accessed on the event loop thread.
+  static TOOLBARS: RefCell<HashMap<usize, Toolbar>> = RefCell::new(HashMap::new());
+  /// The `TaskbarButtonCreated` message, sent when the taskbar button is (re)created.
+  static TASKBAR_BUTTON_CREATED: UINT = unsafe {
+    RegisterWindowMessageW(wide("TaskbarButtonCreated").as_ptr())
+  };
+}
+
+fn wide<S: AsRef<OsStr>>(value: S) -> Vec<u16> {
+  value.as_ref().encode_wide().chain(once(0)).collect()
+}
+
+/// Creates an icon from RGBA pixels with a 32-bpp BGRA bitmap, whose alpha channel is used over the mask.
+///
+/// The 1-bpp mask is left empty, as the rows of a `CreateIcon` mask must be word-aligned bit arrays.
+unsafe fn create_icon(button: &NativeButton) -> HICON {
+  let width = button.width as i32;
+  let height = button.height as i32;
+  let mut info: BITMAPINFO = std::mem::zeroed();
+  info.bmiHeader.biSize = std::mem::size_of::<BITMAPINFOHEADER>() as u32;
+  info.bmiHeader.biWidth = width;
+  // a negative height makes a top-down bitmap, with the rows in the order of the RGBA pixels
+  info.bmiHeader.biHeight = -height;
+  info.bmiHeader.biPlanes = 1;
+  info.bmiHeader.biBitCount = 32;
+  info.bmiHeader.biCompression = BI_RGB;
+
+  let mut bits = null_mut();
+  let color = CreateDIBSection(null_mut(), &info, DIB_RGB_COLORS, &mut bits, null_mut(), 0);
+  if color.is_null() {
+    return null_mut();
+  }
+  let bgra = std::slice::from_raw_parts_mut(bits as *mut u8, button.rgba.len());
+  for (target, pixel) in bgra.chunks_mut(4).zip(button.rgba.chunks(4)) {
+    target.copy_from_slice(&[pixel[2], pixel[1], pixel[0], pixel[3]]);
+  }
+  let mask_row_len = ((width as usize + 15) / 16) * 2;
+  let mask_bits = vec![0u8; mask_row_len * height as usize];
+  let mask = CreateBitmap(width, height, 1, 1, mask_bits.as_ptr() as *const _);
+
+  let mut icon_info = ICONINFO {
+    fIcon: TRUE,
+    xHotspot: 0,
+    yHotspot: 0,
+    hbmMask: mask,
+    hbmColor: color,
+  };
+  let icon = CreateIconIndirect(&mut icon_info);
+  // the icon holds copies of the bitmaps
+  DeleteObject(color as *mut _);
+  DeleteObject(mask as *mut _);
+  icon
+}
+
+/// Adds or updates the buttons of the taskbar button, returning whether it succeeded.
+unsafe fn apply(hwnd: HWND, toolbar: &mut Toolbar) -> bool {
+  let mut taskbar: *mut ITaskbarList3 = null_mut();
+  if !SUCCEEDED(CoCreateInstance(
+    &CLSID_TASKBAR_LIST,
+    null_mut(),
+    CLSCTX_INPROC_SERVER,
+    &ITaskbarList3::uuidof(),
+    &mut taskbar as *mut *mut ITaskbarList3 as *mut _,
+  )) {
+    return false;
+  }
+
+  let icons: Vec<HICON> = toolbar
+    .buttons
+    .iter()
+    .map(|button| create_icon(button))
+    .collect();
+  let mut native_buttons: Vec<THUMBBUTTON> = (0..ThumbbarButton::MAX)
+    .map(|index| {
+      let mut native_button: THUMBBUTTON = std::mem::zeroed();
+      native_button.dwMask = THB_ICON | THB_TOOLTIP | THB_FLAGS;
+      native_button.iId = index as UINT;
+      match toolbar.buttons.get(index) {
+        Some(button) => {
+          native_button.hIcon = icons[index];
+          native_button.dwFlags = if button.enabled {
+            THBF_ENABLED
+          } else {
+            THBF_DISABLED
+          };
+          if let Some(tooltip) = &button.tooltip {
+            for (tip, c) in native_button
+              .szTip
+              .iter_mut()
+              .zip(tooltip.encode_utf16().take(259))
+            {
+              *tip = c;
+            }
+          }
+        }
+        None => native_button.dwFlags = THBF_HIDDEN,
+      }
+      native_button
+    })
+    .collect();
+
+  let applied = SUCCEEDED((*taskbar).HrInit())
+    && SUCCEEDED(if toolbar.added {
+      (*taskbar).ThumbBarUpdateButtons(
+        hwnd,
+        native_buttons.len() as UINT,
+        native_buttons.as_mut_ptr(),
+      )
+    } else {
+      (*taskbar).ThumbBarAddButtons(
+        hwnd,
+        native_buttons.len() as UINT,
+        native_buttons.as_mut_ptr(),
+      )
+    });
+  (*taskbar).Release();
+
+  if applied {
+    toolbar.added = true;
+    for icon in std::mem::replace(&mut toolbar.icons, icons) {
+      DestroyIcon(icon);
+    }
+  } else {
+    for icon in icons {
+      DestroyIcon(icon);
+    }
+  }
+  applied
+}
+
+unsafe extern "system" fn subclass_proc(
+  hwnd: HWND,
+  msg: UINT,
+  wparam: WPARAM,
+  lparam: LPARAM,
+  _id: UINT_PTR,
+  _data: DWORD_PTR,
+) -> LRESULT {
+  if msg == TASKBAR_BUTTON_CREATED.with(|message| *message) {
+    // the taskbar button is recreated with the Explorer, without the buttons
+    TOOLBARS.with(|toolbars| {
+      if let Some(toolbar) = toolbars.borrow_mut().get_mut(&(hwnd as usize)) {
+        toolbar.added = false;
+        apply(hwnd, toolbar);
+      }
+    });
+  } else if msg == WM_COMMAND && HIWORD(wparam as u32) == THBN_CLICKED {
+    let index = LOWORD(wparam as u32) as usize;
+    let clicked = TOOLBARS.with(|toolbars| {
+      toolbars.borrow().get(&(hwnd as usize)).and_then(|toolbar| {
+        toolbar
+          .ids
+          .get(index)
+          .map(|id| (id.clone(), toolbar.listeners.clone()))
+      })
+    });
+    if let Some((id, listeners)) = clicked {
+      emit_window_event(&listeners, &WindowEvent::ThumbbarButtonClicked(id));
+      return 0;
+    }
+  } else if msg == WM_NCDESTROY {
+    TOOLBARS.with(|toolbars| {
+      if let Some(toolbar) = toolbars.borrow_mut().remove(&(hwnd as usize)) {
+        for icon in toolbar.icons {
+          DestroyIcon(icon);
+        }
+      }
+    });
+    RemoveWindowSubclass(hwnd, Some(subclass_proc), SUBCLASS_ID);
+  }
+  DefSubclassProc(hwnd, msg, wparam, lparam)
+}
+
+/// Sets the buttons of the window thumbnail toolbar.
+///
+/// If the taskbar button isn't created yet, they are added when it is.
+pub(crate) fn set_thumbbar_buttons(
+  window: &Window,
+  buttons: Vec<NativeButton>,
+  listeners: WindowEventListeners,
+) {
+  let hwnd = window.hwnd() as HWND;
+  TOOLBARS.with(|toolbars| {
+    let mut toolbars = toolbars.borrow_mut();
+    let toolbar = toolbars.entry(hwnd as usize).or_insert_with(|| {
+      unsafe { SetWindowSubclass(hwnd, Some(subclass_proc), SUBCLASS_ID, 0) };
+      Toolbar {
+        ids: Vec::new(),
+        buttons: Vec::new(),
+        icons: Vec::new(),
+        added: false,
+        listeners,
+      }
+    });
+    toolbar.ids = buttons.iter().map(|button| button.id.clone()).collect();
+    toolbar.buttons = buttons;
+    unsafe { apply(hwnd, toolbar) };
+  });
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn encode_png(color: png::ColorType, width: u32, height: u32, data: &[u8]) -> Vec<u8> {
+    let mut bytes = Vec::new();
+    {
+      let mut encoder = png::Encoder::new(&mut bytes, width, height);
+      encoder.set_color(color);
+      encoder.set_depth(png::BitDepth::Eight);
+      let mut writer = encoder.write_header().unwrap();
+      writer.write_image_data(data).unwrap();
+    }
+    bytes
+  }
+
+  #[test]
+  fn decodes_png_icons() {
+    let png = encode_png(png::ColorType::RGB, 2, 1, &[255, 0, 0, 0, 0, 255]);
+    let button = NativeButton::new(ThumbbarButton::new("play", Icon::Raw(png))).unwrap();
+    assert_eq!((button.width, button.height), (2, 1));
+    assert_eq!(button.rgba, vec![255, 0, 0, 255, 0, 0, 255, 255]);
+
+    let png = encode_png(png::ColorType::GrayscaleAlpha, 1, 1, &[10, 20]);
+    let button = NativeButton::new(ThumbbarButton::new("play", Icon::Raw(png))).unwrap();
+    assert_eq!(button.rgba, vec![10, 10, 10, 20]);
+  }
+
+  #[test]
+  fn rejects_invalid_icons() {
+    let result = NativeButton::new(ThumbbarButton::new("play", Icon::Raw(vec![1, 2, 3])));
+    assert!(matches!(result, Err(tauri_runtime::Error::InvalidIcon(_))));
+  }
+}
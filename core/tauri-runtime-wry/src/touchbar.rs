@@ -0,0 +1,320 @@
+// Copyright 2019-2021 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+//! The window Touch Bars, only available on macOS.
+
+pub use tauri_runtime::touchbar::{TouchBar, TouchBarEvent, TouchBarItem, TouchBarValue};
+
+use uuid::Uuid;
+use wry::application::window::WindowId;
+
+use std::{
+  collections::HashMap,
+  sync::{Arc, Mutex},
+};
+
+pub type TouchBarEventHandler = Box<dyn Fn(&TouchBarEvent) + Send>;
+/// The Touch Bar event listeners of each window.
+pub type TouchBarEventListeners =
+  Arc<Mutex<HashMap<WindowId, HashMap<Uuid, TouchBarEventHandler>>>>;
+
+#[cfg(target_os = "macos")]
+pub(crate) use platform::{set_touchbar, TouchBarHandler, TouchBarLayout};
+
+#[cfg(target_os = "macos")]
+mod platform {
+  use super::{TouchBar, TouchBarEvent, TouchBarItem, TouchBarValue};
+  use tauri_runtime::MenuId;
+
+  use cocoa::base::{id, nil};
+  use objc::{
+    class,
+    declare::ClassDecl,
+    msg_send,
+    runtime::{Class, Object, Sel},
+    sel, sel_impl,
+  };
+  use wry::application::{platform::macos::WindowExtMacOS, window::Window};
+
+  use std::{
+    ffi::{c_void, CString},
+    fmt,
+    sync::Arc,
+  };
+
+  /// The ivar of the action target holding its boxed [`TouchBarHandler`].
+  const HANDLER_IVAR: &str = "tauriHandler";
+  /// `NSSegmentSwitchTrackingSelectOne`.
+  const TRACKING_SELECT_ONE: usize = 0;
+  /// `OBJC_ASSOCIATION_RETAIN_NONATOMIC`.
+  const ASSOCIATION_RETAIN: usize = 1;
+
+  /// The key of the action target associated with its Touch Bar.
+  static TARGET_KEY: u8 = 0;
+
+  #[link(name = "AppKit", kind = "framework")]
+  extern "C" {
+    static NSTouchBarItemIdentifierFixedSpaceSmall: id;
+    static NSTouchBarItemIdentifierFlexibleSpace: id;
+  }
+
+  extern "C" {
+    fn objc_setAssociatedObject(object: id, key: *const c_void, value: id, policy: usize);
+  }
+
+  /// Sends the events of a window Touch Bar to its listeners.
+  #[derive(Clone)]
+  pub(crate) struct TouchBarHandler(pub(crate) Arc<dyn Fn(TouchBarEvent) + Send + Sync>);
+
+  impl fmt::Debug for TouchBarHandler {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+      f.debug_struct("TouchBarHandler").finish()
+    }
+  }
+
+  /// A Touch Bar with the item ids converted to their runtime values, so it can be sent to the event loop.
+  #[derive(Debug, Clone)]
+  pub(crate) struct TouchBarLayout(Vec<LayoutItem>);
+
+  #[derive(Debug, Clone)]
+  enum LayoutItem {
+    Button {
+      id: u32,
+      title: String,
+    },
+    Slider {
+      id: u32,
+      label: Option<String>,
+      min: f64,
+      max: f64,
+      value: f64,
+    },
+    SegmentedControl {
+      id: u32,
+      labels: Vec<String>,
+      selected: Option<usize>,
+    },
+    FixedSpace,
+    FlexibleSpace,
+  }
+
+  impl<I: MenuId> From<TouchBar<I>> for TouchBarLayout {
+    fn from(touchbar: TouchBar<I>) -> Self {
+      let items = touchbar
+        .items
+        .into_iter()
+        .filter_map(|item| match item {
+          TouchBarItem::Button(button) => Some(LayoutItem::Button {
+            id: button.id_value(),
+            title: button.title,
+          }),
+          TouchBarItem::Slider(slider) => Some(LayoutItem::Slider {
+            id: slider.id_value(),
+            label: slider.label,
+            min: slider.min,
+            max: slider.max,
+            value: slider.value,
+          }),
+          TouchBarItem::SegmentedControl(control) => Some(LayoutItem::SegmentedControl {
+            id: control.id_value(),
+            labels: control.labels,
+            selected: control.selected,
+          }),
+          TouchBarItem::FixedSpace => Some(LayoutItem::FixedSpace),
+          TouchBarItem::FlexibleSpace => Some(LayoutItem::FlexibleSpace),
+          _ => None,
+        })
+        .collect();
+      Self(items)
+    }
+  }
+
+  unsafe fn send(this: &Object, sender: id, value: TouchBarValue) {
+    let handler: *mut c_void = *this.get_ivar(HANDLER_IVAR);
+    let tag: isize = msg_send![sender, tag];
+    let handler = &*(handler as *const TouchBarHandler);
+    (handler.0)(TouchBarEvent::new(tag as u32, value));
+  }
+
+  extern "C" fn button_pressed(this: &Object, _cmd: Sel, sender: id) {
+    unsafe { send(this, sender, TouchBarValue::Pressed) }
+  }
+
+  extern "C" fn slider_changed(this: &Object, _cmd: Sel, sender: id) {
+    unsafe {
+      let value: f64 = msg_send![sender, doubleValue];
+      send(this, sender, TouchBarValue::Slider(value))
+    }
+  }
+
+  extern "C" fn segment_selected(this: &Object, _cmd: Sel, sender: id) {
+    unsafe {
+      let index: isize = msg_send![sender, selectedSegment];
+      if index >= 0 {
+        send(this, sender, TouchBarValue::Segment(index as usize))
+      }
+    }
+  }
+
+  extern "C" fn dealloc(this: &Object, _cmd: Sel) {
+    unsafe {
+      let handler: *mut c_void = *this.get_ivar(HANDLER_IVAR);
+      drop(Box::from_raw(handler as *mut TouchBarHandler));
+      let _: () = msg_send![super(this, class!(NSObject)), dealloc];
+    }
+  }
+
+  /// The target of the Touch Bar controls actions, owning the handler of its window.
+  fn target_class() -> &'static Class {
+    if let Some(class) = Class::get("TauriTouchBarTarget") {
+      return class;
+    }
+    let mut decl = ClassDecl::new("TauriTouchBarTarget", class!(NSObject))
+      .expect("failed to declare the Touch Bar target class");
+    decl.add_ivar::<*mut c_void>(HANDLER_IVAR);
+    unsafe {
+      decl.add_method(
+        sel!(buttonPressed:),
+        button_pressed as extern "C" fn(&Object, Sel, id),
+      );
+      decl.add_method(
+        sel!(sliderChanged:),
+        slider_changed as extern "C" fn(&Object, Sel, id),
+      );
+      decl.add_method(
+        sel!(segmentSelected:),
+        segment_selected as extern "C" fn(&Object, Sel, id),
+      );
+      decl.add_method(sel!(dealloc), dealloc as extern "C" fn(&Object, Sel));
+    }
+    decl.register()
+  }
+
+  unsafe fn ns_string(value: &str) -> id {
+    let value = CString::new(value.replace('\0', "")).expect("invalid string");
+    msg_send![class!(NSString), stringWithUTF8String: value.as_ptr()]
+  }
+
+  /// Creates a custom item showing `view`.
+  unsafe fn custom_item(identifier: id, view: id) -> id {
+    let item: id = msg_send![class!(NSCustomTouchBarItem), alloc];
+    let item: id = msg_send![item, initWithIdentifier: identifier];
+    let _: () = msg_send![item, setView: view];
+    item
+  }
+
+  /// Sets the Touch Bar of the window, or removes it if `layout` is `None`.
+  ///
+  /// The webview shows its own items instead while a text field is focused.
+  pub(crate) fn set_touchbar(
+    window: &Window,
+    layout: Option<TouchBarLayout>,
+    handler: TouchBarHandler,
+  ) {
+    unsafe {
+      let ns_window = window.ns_window() as id;
+      let layout = match layout {
+        Some(layout) => layout,
+        None => {
+          let _: () = msg_send![ns_window, setTouchBar: nil];
+          return;
+        }
+      };
+
+      let target: id = msg_send![target_class(), new];
+      (*target).set_ivar(
+        HANDLER_IVAR,
+        Box::into_raw(Box::new(handler)) as *mut c_void,
+      );
+
+      let identifiers: id = msg_send![class!(NSMutableArray), array];
+      let items: id = msg_send![class!(NSMutableSet), set];
+      for (index, item) in layout.0.into_iter().enumerate() {
+        let identifier = ns_string(&format!("studio.tauri.touchbar.{}", index));
+        let touchbar_item: id = match item {
+          LayoutItem::FixedSpace => {
+            let _: () = msg_send![
+              identifiers,
+              addObject: NSTouchBarItemIdentifierFixedSpaceSmall
+            ];
+            continue;
+          }
+          LayoutItem::FlexibleSpace => {
+            let _: () = msg_send![
+              identifiers,
+              addObject: NSTouchBarItemIdentifierFlexibleSpace
+            ];
+            continue;
+          }
+          LayoutItem::Button { id: tag, title } => {
+            let button: id = msg_send![class!(NSButton),
+              buttonWithTitle: ns_string(&title)
+              target: target
+              action: sel!(buttonPressed:)];
+            let _: () = msg_send![button, setTag: tag as isize];
+            custom_item(identifier, button)
+          }
+          LayoutItem::Slider {
+            id: tag,
+            label,
+            min,
+            max,
+            value,
+          } => {
+            let item: id = msg_send![class!(NSSliderTouchBarItem), alloc];
+            let item: id = msg_send![item, initWithIdentifier: identifier];
+            if let Some(label) = label {
+              let _: () = msg_send![item, setLabel: ns_string(&label)];
+            }
+            let slider: id = msg_send![item, slider];
+            let _: () = msg_send![slider, setMinValue: min];
+            let _: () = msg_send![slider, setMaxValue: max];
+            let _: () = msg_send![slider, setDoubleValue: value];
+            let _: () = msg_send![slider, setTag: tag as isize];
+            let _: () = msg_send![slider, setTarget: target];
+            let _: () = msg_send![slider, setAction: sel!(sliderChanged:)];
+            item
+          }
+          LayoutItem::SegmentedControl {
+            id: tag,
+            labels,
+            selected,
+          } => {
+            let ns_labels: id = msg_send![class!(NSMutableArray), array];
+            for label in &labels {
+              let _: () = msg_send![ns_labels, addObject: ns_string(label)];
+            }
+            let control: id = msg_send![class!(NSSegmentedControl),
+              segmentedControlWithLabels: ns_labels
+              trackingMode: TRACKING_SELECT_ONE
+              target: target
+              action: sel!(segmentSelected:)];
+            let _: () = msg_send![control, setTag: tag as isize];
+            if let Some(selected) = selected {
+              let _: () = msg_send![control, setSelectedSegment: selected as isize];
+            }
+            custom_item(identifier, control)
+          }
+        };
+        let _: () = msg_send![identifiers, addObject: identifier];
+        let _: () = msg_send![items, addObject: touchbar_item];
+        let _: () = msg_send![touchbar_item, release];
+      }
+
+      let touchbar: id = msg_send![class!(NSTouchBar), new];
+      let _: () = msg_send![touchbar, setDefaultItemIdentifiers: identifiers];
+      let _: () = msg_send![touchbar, setTemplateItems: items];
+      // the controls don't retain their target, so the Touch Bar keeps it alive until it's replaced
+      objc_setAssociatedObject(
+        touchbar,
+        &TARGET_KEY as *const u8 as *const c_void,
+        target,
+        ASSOCIATION_RETAIN,
+      );
+      let _: () = msg_send![target, release];
+      let _: () = msg_send![ns_window, setTouchBar: touchbar];
+      let _: () = msg_send![touchbar, release];
+    }
+  }
+}
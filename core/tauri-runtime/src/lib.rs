@@ -6,9 +6,10 @@
 
 #![cfg_attr(doc_cfg, feature(doc_cfg))]
 
-use std::{fmt::Debug, hash::Hash, path::PathBuf};
+use std::{any::Any, fmt::Debug, hash::Hash, path::PathBuf};
 
 use serde::Serialize;
+use serde_json::Value as JsonValue;
 use tauri_utils::assets::Assets;
 use uuid::Uuid;
 
@@ -19,6 +20,10 @@ pub mod menu;
 /// Types useful for interacting with a user's monitors.
 pub mod monitor;
 pub mod tag;
+/// Create window Touch Bars on macOS.
+#[cfg(feature = "touchbar")]
+#[cfg_attr(doc_cfg, doc(cfg(feature = "touchbar")))]
+pub mod touchbar;
 pub mod webview;
 pub mod window;
 
@@ -85,9 +90,42 @@ pub enum Error {
   /// Failed to create window.
   #[error("failed to create window")]
   CreateWindow,
+  /// A window or a system tray was requested from the main thread outside of the event loop,
+  /// while it waits for the request to be handled.
+  #[error("cannot create a window or a system tray with a runtime handle on the main thread outside of the event loop, use a separate thread or an async command")]
+  CreateOnMainThread,
+  /// A webview result was requested from the main thread, which must run the event loop for the webview to return it.
+  #[error(
+    "cannot wait for the webview on the main thread, use a separate thread or an async command"
+  )]
+  WaitOnMainThread,
+  /// The native webview of the window could not be found.
+  #[error("the webview of the window could not be found")]
+  WebviewNotFound,
+  /// The WebView2 runtime could not be found.
+  #[error("WebView2 runtime not found at {0}")]
+  WebviewRuntimeNotFound(std::path::PathBuf),
   /// Failed to send message to webview.
   #[error("failed to send message to the webview")]
   FailedToSendMessage,
+  /// Failed to receive a response from the webview.
+  #[error("failed to receive a response from the webview")]
+  FailedToReceiveMessage,
+  /// The operation is not supported by the runtime.
+  #[error("unsupported operation: {0}")]
+  Unsupported(String),
+  /// A cookie is invalid or the webview cookie store failed to read or write it.
+  #[error("cookie error: {0}")]
+  Cookie(String),
+  /// The webview data store failed to clear the browsing data.
+  #[error("failed to clear the browsing data: {0}")]
+  ClearBrowsingData(String),
+  /// The webview print operation failed.
+  #[error("print error: {0}")]
+  Print(String),
+  /// A script evaluated on the webview threw an error.
+  #[error("script error: {0}")]
+  Script(String),
   /// Failed to serialize/deserialize.
   #[error("JSON error: {0}")]
   Json(#[from] serde_json::Error),
@@ -102,6 +140,9 @@ pub enum Error {
   /// Failed to get monitor on window operation.
   #[error("failed to get monitor")]
   FailedToGetMonitor,
+  /// Failed to start dragging an item out of a window.
+  #[error("failed to start the drag: {0}")]
+  StartDrag(String),
 }
 
 /// Result type.
@@ -186,6 +227,18 @@ pub enum SystemTrayEvent {
   },
 }
 
+/// A type-erased application-defined event, sent to the event loop with [`RuntimeHandle::send_event`].
+pub type UserEvent = Box<dyn Any + Send>;
+
+/// Event triggered on the event loop run.
+#[non_exhaustive]
+pub enum RunEvent {
+  /// Event loop is exiting.
+  Exit,
+  /// An application-defined event sent with [`RuntimeHandle::send_event`].
+  UserEvent(UserEvent),
+}
+
 /// Metadata for a runtime event loop iteration on `run_iteration`.
 #[derive(Debug, Clone, Default)]
 pub struct RunIteration {
@@ -196,11 +249,29 @@ pub struct RunIteration {
 pub trait RuntimeHandle: Send + Sized + Clone + 'static {
   type Runtime: Runtime<Handle = Self>;
   /// Create a new webview window.
+  ///
+  /// The request is handled by the event loop, so this can be called from any thread,
+  /// and from the main thread while the event loop handles an event.
   fn create_window<P: Params<Runtime = Self::Runtime>>(
     &self,
     pending: PendingWindow<P>,
   ) -> crate::Result<DetachedWindow<P>>;
 
+  /// Adds the icon to the system tray with the specified menu items, replacing the existing tray.
+  ///
+  /// The request is handled by the event loop, so this can be called from any thread,
+  /// and from the main thread while the event loop handles an event.
+  #[cfg(feature = "system-tray")]
+  #[cfg_attr(doc_cfg, doc(cfg(feature = "system-tray")))]
+  fn system_tray<I: MenuId>(
+    &self,
+    system_tray: SystemTray<I>,
+  ) -> crate::Result<<Self::Runtime as Runtime>::TrayHandler>;
+
+  /// Sends an application-defined event to the event loop, waking it up.
+  /// The event is delivered to the [`Runtime::run`] callback as [`RunEvent::UserEvent`].
+  fn send_event(&self, event: UserEvent) -> crate::Result<()>;
+
   #[cfg(all(windows, feature = "system-tray"))]
   #[cfg_attr(doc_cfg, doc(cfg(all(windows, feature = "system-tray"))))]
   fn remove_system_tray(&self) -> crate::Result<()>;
@@ -243,7 +314,7 @@ pub trait Runtime: Sized + 'static {
   fn run_iteration(&mut self) -> RunIteration;
 
   /// Run the webview runtime.
-  fn run<F: Fn() + 'static>(self, callback: F);
+  fn run<F: FnMut(RunEvent) + 'static>(self, callback: F);
 }
 
 /// Webview dispatcher. A thread-safe handle to the webview API.
@@ -265,6 +336,11 @@ pub trait Dispatch: Clone + Send + Sized + 'static {
   #[cfg_attr(doc_cfg, doc(cfg(feature = "menu")))]
   fn on_menu_event<F: Fn(&window::MenuEvent) + Send + 'static>(&self, f: F) -> Uuid;
 
+  /// Registers a Touch Bar event handler.
+  #[cfg(feature = "touchbar")]
+  #[cfg_attr(doc_cfg, doc(cfg(feature = "touchbar")))]
+  fn on_touchbar_event<F: Fn(&touchbar::TouchBarEvent) + Send + 'static>(&self, f: F) -> Uuid;
+
   // GETTERS
 
   /// Returns the scale factor that can be used to map logical pixels to physical pixels, and vice versa.
@@ -314,6 +390,21 @@ pub trait Dispatch: Clone + Send + Sized + 'static {
   /// Returns the list of all the monitors available on the system.
   fn available_monitors(&self) -> crate::Result<Vec<Monitor>>;
 
+  /// Returns the current zoom factor of the webview.
+  ///
+  /// This blocks until the webview returns the value, so it fails with [`Error::WaitOnMainThread`] on the main thread.
+  fn zoom(&self) -> crate::Result<f64>;
+
+  /// Whether the webview is playing audio or not.
+  ///
+  /// This blocks until the webview returns the value, so it fails with [`Error::WaitOnMainThread`] on the main thread.
+  fn is_playing_audio(&self) -> crate::Result<bool>;
+
+  /// Whether the devtools are opened or not.
+  #[cfg(feature = "devtools")]
+  #[cfg_attr(doc_cfg, doc(cfg(feature = "devtools")))]
+  fn is_devtools_open(&self) -> crate::Result<bool>;
+
   /// Returns the native handle that is used by this window.
   #[cfg(windows)]
   fn hwnd(&self) -> crate::Result<*mut std::ffi::c_void>;
@@ -326,6 +417,70 @@ pub trait Dispatch: Clone + Send + Sized + 'static {
   /// Opens the dialog to prints the contents of the webview.
   fn print(&self) -> crate::Result<()>;
 
+  /// Opens the dialog to prints the contents of the webview with the given page setup.
+  fn print_with_options(&self, options: webview::PrintOptions) -> crate::Result<()>;
+
+  /// Prints the contents of the webview to a PDF file with the given page setup, without showing the print dialog.
+  fn print_to_pdf(&self, path: PathBuf, options: webview::PrintOptions) -> crate::Result<()>;
+
+  /// Opens the devtools of the webview.
+  #[cfg(feature = "devtools")]
+  #[cfg_attr(doc_cfg, doc(cfg(feature = "devtools")))]
+  fn open_devtools(&self) -> crate::Result<()>;
+
+  /// Closes the devtools of the webview.
+  #[cfg(feature = "devtools")]
+  #[cfg_attr(doc_cfg, doc(cfg(feature = "devtools")))]
+  fn close_devtools(&self) -> crate::Result<()>;
+
+  /// Searches the given text on the webview, highlighting the first match.
+  ///
+  /// This and the other find methods returning a result block until the webview returns it,
+  /// so they fail with [`Error::WaitOnMainThread`] on the main thread.
+  fn find(&self, text: String, options: webview::FindOptions)
+    -> crate::Result<webview::FindResult>;
+
+  /// Highlights the next match of the current search. Returns whether a match was found or not.
+  fn find_next(&self) -> crate::Result<bool>;
+
+  /// Highlights the previous match of the current search. Returns whether a match was found or not.
+  fn find_previous(&self) -> crate::Result<bool>;
+
+  /// Clears the current search and its highlight.
+  fn clear_find(&self) -> crate::Result<()>;
+
+  /// Injects a stylesheet on the current and future pages of the webview. Returns the stylesheet id.
+  fn inject_css(&self, css: String) -> crate::Result<Uuid>;
+
+  /// Removes a stylesheet added with [`Dispatch::inject_css`].
+  fn remove_css(&self, id: Uuid) -> crate::Result<()>;
+
+  /// Adds an initialization script that is executed on the next pages loaded on the webview.
+  fn add_init_script(&self, script: String) -> crate::Result<()>;
+
+  /// Mutes or unmutes the audio of the webview.
+  fn set_muted(&self, muted: bool) -> crate::Result<()>;
+
+  /// Overrides the user agent of the webview, used by the next requests and pages.
+  fn set_user_agent(&self, user_agent: String) -> crate::Result<()>;
+
+  /// Sets the zoom factor of the webview, where `1.0` is the default size.
+  fn set_zoom(&self, scale_factor: f64) -> crate::Result<()>;
+
+  /// Clears the specified browsing data of all the websites from the data store of the webview.
+  fn clear_browsing_data(&self, data_types: webview::DataTypes) -> crate::Result<()>;
+
+  /// Returns the cookies sent on the requests to the URL of the page loaded on the webview.
+  fn cookies(&self) -> crate::Result<Vec<webview::Cookie>>;
+
+  /// Sets a cookie on the page loaded on the webview.
+  ///
+  /// Invalid cookie names and values are rejected with [`Error::Cookie`].
+  fn set_cookie(&self, cookie: webview::Cookie) -> crate::Result<()>;
+
+  /// Deletes the cookie with the given name and path from the page loaded on the webview.
+  fn delete_cookie(&self, name: &str, path: Option<&str>) -> crate::Result<()>;
+
   /// Create a new webview window.
   fn create_window<P: Params<Runtime = Self::Runtime>>(
     &mut self,
@@ -392,9 +547,34 @@ pub trait Dispatch: Clone + Send + Sized + 'static {
   /// Starts dragging the window.
   fn start_dragging(&self) -> crate::Result<()>;
 
+  /// Starts an OS drag and drop operation of the item out of the window.
+  ///
+  /// The mouse button must still be pressed when the event loop starts the drag, otherwise an error is returned.
+  fn start_drag(&self, item: window::DragItem) -> crate::Result<()>;
+
+  /// Sets the buttons of the toolbar shown on the taskbar thumbnail of the window, replacing the current ones.
+  ///
+  /// At most [`window::ThumbbarButton::MAX`] buttons are supported.
+  /// Only supported on Windows, it's a no-op on the other platforms.
+  fn set_thumbbar_buttons(&self, buttons: Vec<window::ThumbbarButton>) -> crate::Result<()>;
+
+  /// Sets the Touch Bar of the window, or removes it if `touchbar` is `None`.
+  ///
+  /// Only supported on macOS, it's a no-op on the other platforms.
+  #[cfg(feature = "touchbar")]
+  #[cfg_attr(doc_cfg, doc(cfg(feature = "touchbar")))]
+  fn set_touchbar<I: MenuId>(&self, touchbar: Option<touchbar::TouchBar<I>>) -> crate::Result<()>;
+
   /// Executes javascript on the window this [`Dispatch`] represents.
   fn eval_script<S: Into<String>>(&self, script: S) -> crate::Result<()>;
 
+  /// Executes javascript on the window this [`Dispatch`] represents and returns its completion value.
+  /// If the value is a promise, its resolved value is returned instead.
+  ///
+  /// This blocks until the webview returns the value, so it fails with [`Error::WaitOnMainThread`] on the main thread.
+  /// There's no timeout: [`Error::FailedToReceiveMessage`] is returned if the page is unloaded or the window closed first.
+  fn eval_script_with_result<S: Into<String>>(&self, script: S) -> crate::Result<JsonValue>;
+
   /// Applies the specified `update` to the menu item associated with the given `id`.
   #[cfg(feature = "menu")]
   fn update_menu_item(&self, id: u32, update: menu::MenuUpdate) -> crate::Result<()>;
@@ -0,0 +1,209 @@
+// Copyright 2019-2021 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+use std::{
+  collections::hash_map::DefaultHasher,
+  hash::{Hash, Hasher},
+};
+
+use super::MenuId;
+
+fn hash_id<I: MenuId>(id: &I) -> u32 {
+  let mut s = DefaultHasher::new();
+  id.hash(&mut s);
+  s.finish() as u32
+}
+
+/// A window Touch Bar layout.
+#[derive(Debug, Clone)]
+#[non_exhaustive]
+pub struct TouchBar<I: MenuId> {
+  pub items: Vec<TouchBarItem<I>>,
+}
+
+impl<I: MenuId> Default for TouchBar<I> {
+  fn default() -> Self {
+    Self { items: Vec::new() }
+  }
+}
+
+impl<I: MenuId> TouchBar<I> {
+  /// Creates a new Touch Bar without items.
+  pub fn new() -> Self {
+    Default::default()
+  }
+
+  /// Adds an item to the end of the Touch Bar.
+  pub fn add_item(mut self, item: TouchBarItem<I>) -> Self {
+    self.items.push(item);
+    self
+  }
+
+  /// Adds a button to the end of the Touch Bar.
+  pub fn add_button(self, button: TouchBarButton<I>) -> Self {
+    self.add_item(TouchBarItem::Button(button))
+  }
+
+  /// Adds a slider to the end of the Touch Bar.
+  pub fn add_slider(self, slider: TouchBarSlider<I>) -> Self {
+    self.add_item(TouchBarItem::Slider(slider))
+  }
+
+  /// Adds a segmented control to the end of the Touch Bar.
+  pub fn add_segmented_control(self, control: TouchBarSegmentedControl<I>) -> Self {
+    self.add_item(TouchBarItem::SegmentedControl(control))
+  }
+}
+
+/// An item of the Touch Bar.
+#[derive(Debug, Clone)]
+#[non_exhaustive]
+pub enum TouchBarItem<I: MenuId> {
+  /// A button, sending [`TouchBarValue::Pressed`] events.
+  Button(TouchBarButton<I>),
+  /// A slider, sending [`TouchBarValue::Slider`] events.
+  Slider(TouchBarSlider<I>),
+  /// A segmented control, sending [`TouchBarValue::Segment`] events.
+  SegmentedControl(TouchBarSegmentedControl<I>),
+  /// A space with the standard item spacing.
+  FixedSpace,
+  /// A space taking the available width.
+  FlexibleSpace,
+}
+
+impl<I: MenuId> TouchBarItem<I> {
+  /// The item id, if it sends events.
+  pub fn id(&self) -> Option<&I> {
+    match self {
+      Self::Button(button) => Some(&button.id),
+      Self::Slider(slider) => Some(&slider.id),
+      Self::SegmentedControl(control) => Some(&control.id),
+      _ => None,
+    }
+  }
+
+  #[doc(hidden)]
+  pub fn id_value(&self) -> Option<u32> {
+    self.id().map(hash_id)
+  }
+}
+
+/// A Touch Bar button.
+#[derive(Debug, Clone)]
+pub struct TouchBarButton<I: MenuId> {
+  pub id: I,
+  pub title: String,
+}
+
+impl<I: MenuId> TouchBarButton<I> {
+  /// Creates a new button.
+  pub fn new<T: Into<String>>(id: I, title: T) -> Self {
+    Self {
+      id,
+      title: title.into(),
+    }
+  }
+
+  #[doc(hidden)]
+  pub fn id_value(&self) -> u32 {
+    hash_id(&self.id)
+  }
+}
+
+/// A Touch Bar slider.
+#[derive(Debug, Clone)]
+pub struct TouchBarSlider<I: MenuId> {
+  pub id: I,
+  pub label: Option<String>,
+  pub min: f64,
+  pub max: f64,
+  pub value: f64,
+}
+
+impl<I: MenuId> TouchBarSlider<I> {
+  /// Creates a new slider between `min` and `max`, starting on `min`.
+  pub fn new(id: I, min: f64, max: f64) -> Self {
+    Self {
+      id,
+      label: None,
+      min,
+      max,
+      value: min,
+    }
+  }
+
+  /// Sets the label shown before the slider.
+  pub fn label<T: Into<String>>(mut self, label: T) -> Self {
+    self.label.replace(label.into());
+    self
+  }
+
+  /// Sets the initial value of the slider.
+  pub fn value(mut self, value: f64) -> Self {
+    self.value = value;
+    self
+  }
+
+  #[doc(hidden)]
+  pub fn id_value(&self) -> u32 {
+    hash_id(&self.id)
+  }
+}
+
+/// A Touch Bar segmented control, with one selected segment.
+#[derive(Debug, Clone)]
+pub struct TouchBarSegmentedControl<I: MenuId> {
+  pub id: I,
+  pub labels: Vec<String>,
+  pub selected: Option<usize>,
+}
+
+impl<I: MenuId> TouchBarSegmentedControl<I> {
+  /// Creates a new segmented control with a segment for each label.
+  pub fn new<T: Into<String>>(id: I, labels: Vec<T>) -> Self {
+    Self {
+      id,
+      labels: labels.into_iter().map(Into::into).collect(),
+      selected: None,
+    }
+  }
+
+  /// Selects the segment at `index`.
+  pub fn selected(mut self, index: usize) -> Self {
+    self.selected.replace(index);
+    self
+  }
+
+  #[doc(hidden)]
+  pub fn id_value(&self) -> u32 {
+    hash_id(&self.id)
+  }
+}
+
+/// The value sent by a Touch Bar item.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[non_exhaustive]
+pub enum TouchBarValue {
+  /// The button was pressed.
+  Pressed,
+  /// The slider value changed.
+  Slider(f64),
+  /// The segment at this index was selected.
+  Segment(usize),
+}
+
+/// A Touch Bar event.
+#[derive(Debug, Clone)]
+#[non_exhaustive]
+pub struct TouchBarEvent {
+  pub item_id: u32,
+  pub value: TouchBarValue,
+}
+
+impl TouchBarEvent {
+  /// Creates a new Touch Bar event.
+  pub fn new(item_id: u32, value: TouchBarValue) -> Self {
+    Self { item_id, value }
+  }
+}
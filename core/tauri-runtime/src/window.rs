@@ -6,12 +6,15 @@
 
 use crate::{
   webview::{FileDropHandler, WebviewAttributes, WebviewRpcHandler},
-  Dispatch, Params, Runtime, WindowBuilder,
+  Dispatch, Icon, Params, Runtime, WindowBuilder,
 };
 use serde::Serialize;
 use tauri_utils::config::WindowConfig;
 
-use std::hash::{Hash, Hasher};
+use std::{
+  hash::{Hash, Hasher},
+  path::{Path, PathBuf},
+};
 
 /// UI scaling utilities.
 pub mod dpi;
@@ -45,6 +48,71 @@ pub enum WindowEvent {
     /// The window inner size.
     new_inner_size: dpi::PhysicalSize<u32>,
   },
+  /// The webview has navigated to a new page. Contains the page URL.
+  Navigated(url::Url),
+  /// A button of the taskbar thumbnail toolbar has been clicked. Contains the button id.
+  ThumbbarButtonClicked(String),
+}
+
+/// An item dragged out of a window with [`Dispatch::start_drag`](crate::Dispatch::start_drag).
+#[derive(Debug, Clone, PartialEq)]
+#[non_exhaustive]
+pub enum DragItem {
+  /// Files dragged to the file manager or to other apps, previewed with their icons.
+  Files(Vec<PathBuf>),
+  /// An image file, dragged like [`DragItem::Files`] but previewed with the image itself on macOS.
+  Image(PathBuf),
+}
+
+impl DragItem {
+  /// The paths of the dragged files.
+  pub fn paths(&self) -> Vec<&Path> {
+    match self {
+      Self::Files(paths) => paths.iter().map(PathBuf::as_path).collect(),
+      Self::Image(path) => vec![path.as_path()],
+    }
+  }
+}
+
+/// A button of the toolbar shown on the taskbar thumbnail of the window on Windows,
+/// set with [`Dispatch::set_thumbbar_buttons`](crate::Dispatch::set_thumbbar_buttons).
+#[derive(Debug, Clone)]
+pub struct ThumbbarButton {
+  /// The id sent on the [`WindowEvent::ThumbbarButtonClicked`] events.
+  pub id: String,
+  /// The button icon, an `.ico` or `.png` file.
+  pub icon: Icon,
+  /// The tooltip shown when the button is hovered.
+  pub tooltip: Option<String>,
+  /// Whether the button can be clicked.
+  pub enabled: bool,
+}
+
+impl ThumbbarButton {
+  /// The maximum number of buttons on a thumbnail toolbar.
+  pub const MAX: usize = 7;
+
+  /// Creates a new button.
+  pub fn new<I: Into<String>>(id: I, icon: Icon) -> Self {
+    Self {
+      id: id.into(),
+      icon,
+      tooltip: None,
+      enabled: true,
+    }
+  }
+
+  /// Sets the tooltip of the button.
+  pub fn tooltip<T: Into<String>>(mut self, tooltip: T) -> Self {
+    self.tooltip.replace(tooltip.into());
+    self
+  }
+
+  /// Disables the button, shown grayed out.
+  pub fn disabled(mut self) -> Self {
+    self.enabled = false;
+    self
+  }
 }
 
 /// A menu event.
@@ -55,6 +123,8 @@ pub struct MenuEvent {
 }
 
 /// A webview window that has yet to be built.
+///
+/// Each window hosts exactly one webview, since wry creates a single webview for the window it owns.
 pub struct PendingWindow<P: Params> {
   /// The label that the window will be named.
   pub label: P::Label,
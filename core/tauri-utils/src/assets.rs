@@ -8,9 +8,12 @@
 pub use phf;
 use std::{
   borrow::Cow,
+  io::Read,
   path::{Component, Path},
 };
 
+pub use crate::config::AssetCompression;
+
 /// Represent an asset file path in a normalized way.
 ///
 /// The following rules are enforced and added if needed:
@@ -77,28 +80,145 @@ impl<P: AsRef<Path>> From<P> for AssetKey {
 pub trait Assets: Send + Sync + 'static {
   /// Get the content of the passed [`AssetKey`].
   fn get<Key: Into<AssetKey>>(&self, key: Key) -> Option<Cow<'_, [u8]>>;
+
+  /// Iterates over the keys of the assets.
+  ///
+  /// Empty by default, for the containers that can't list their assets.
+  fn iter(&self) -> Box<dyn Iterator<Item = &str> + '_> {
+    Box::new(std::iter::empty())
+  }
 }
 
 /// [`Assets`] implementation that only contains compile-time compressed and embedded assets.
-pub struct EmbeddedAssets(phf::Map<&'static str, &'static [u8]>);
+///
+/// The assets are decompressed on demand, and the uncompressed ones are borrowed from the binary.
+pub struct EmbeddedAssets {
+  assets: phf::Map<&'static str, (AssetCompression, &'static [u8])>,
+  // only read on debug builds
+  #[cfg_attr(not(debug_assertions), allow(dead_code))]
+  dir: Option<&'static str>,
+  #[cfg_attr(not(debug_assertions), allow(dead_code))]
+  csp: Option<&'static str>,
+}
 
 impl EmbeddedAssets {
-  /// Wrap a [zstd] compressed [`phf::Map`].
+  /// Wrap a [`phf::Map`] of the assets and their compression.
+  pub const fn new(map: phf::Map<&'static str, (AssetCompression, &'static [u8])>) -> Self {
+    Self {
+      assets: map,
+      dir: None,
+      csp: None,
+    }
+  }
+
+  /// Reads the assets from the directory they were embedded from on debug builds,
+  /// so the frontend changes are served without recompiling the binary.
   ///
-  /// [zstd]: https://facebook.github.io/zstd/
-  pub const fn from_zstd(map: phf::Map<&'static str, &'static [u8]>) -> Self {
-    Self(map)
+  /// The files are read on every request, falling back to the embedded assets if they can't be read,
+  /// and the content security policy is injected in the HTML files.
+  /// The local scripts, stylesheets and images of the HTML files get their modification time as a query parameter,
+  /// so the webview doesn't serve them from its cache once they change.
+  pub fn with_dir(mut self, dir: &'static str, csp: Option<&'static str>) -> Self {
+    self.dir.replace(dir);
+    self.csp = csp;
+    self
   }
+
+  #[cfg(debug_assertions)]
+  fn read_from_dir(&self, key: &AssetKey) -> Option<Vec<u8>> {
+    let dir = Path::new(self.dir?);
+    let path = dir_path(dir, key.as_ref())?;
+    let bytes = std::fs::read(&path).ok()?;
+    if path.extension() != Some(std::ffi::OsStr::new("html")) {
+      return Some(bytes);
+    }
+
+    // the URLs are relative to the HTML file, or to the directory when they start with a `/`
+    let base = Path::new(key.as_ref())
+      .parent()
+      .map(|parent| parent.to_string_lossy().into_owned())
+      .unwrap_or_default();
+    let html =
+      crate::html::add_asset_versions(String::from_utf8_lossy(&bytes).into_owned(), |url| {
+        if url.contains("://") || url.starts_with("//") || url.starts_with("data:") {
+          return None;
+        }
+        let url = url.split(&['?', '#'][..]).next().unwrap_or_default();
+        let asset = if url.starts_with('/') {
+          url.to_string()
+        } else {
+          format!("{}/{}", base, url)
+        };
+        let modified = std::fs::metadata(dir_path(dir, &asset)?)
+          .and_then(|metadata| metadata.modified())
+          .ok()?;
+        modified
+          .duration_since(std::time::UNIX_EPOCH)
+          .ok()
+          .map(|modified| modified.as_millis().to_string())
+      });
+    Some(
+      match self.csp {
+        Some(csp) => crate::html::inject_csp(html, csp),
+        None => html,
+      }
+      .into_bytes(),
+    )
+  }
+}
+
+// The path of the asset in the directory, rejecting the keys reaching outside of it, e.g. with `..`
+#[cfg(debug_assertions)]
+fn dir_path(dir: &Path, key: &str) -> Option<std::path::PathBuf> {
+  let mut path = dir.to_path_buf();
+  for component in Path::new(key.trim_start_matches('/')).components() {
+    match component {
+      Component::Normal(name) => path.push(name),
+      _ => return None,
+    }
+  }
+  Some(path)
 }
 
 impl Assets for EmbeddedAssets {
   fn get<Key: Into<AssetKey>>(&self, key: Key) -> Option<Cow<'_, [u8]>> {
-    self
-      .0
-      .get(key.into().as_ref())
-      .copied()
-      .map(zstd::decode_all)
-      .and_then(Result::ok)
-      .map(Cow::Owned)
+    let key = key.into();
+    #[cfg(debug_assertions)]
+    if let Some(bytes) = self.read_from_dir(&key) {
+      return Some(Cow::Owned(bytes));
+    }
+    let (compression, bytes) = *self.assets.get(key.as_ref())?;
+    match compression {
+      AssetCompression::None => Some(Cow::Borrowed(bytes)),
+      AssetCompression::Zstd => zstd::decode_all(bytes).ok().map(Cow::Owned),
+      AssetCompression::Brotli => {
+        let mut decompressed = Vec::new();
+        brotli::Decompressor::new(bytes, 4096)
+          .read_to_end(&mut decompressed)
+          .ok()?;
+        Some(Cow::Owned(decompressed))
+      }
+    }
+  }
+
+  fn iter(&self) -> Box<dyn Iterator<Item = &str> + '_> {
+    Box::new(self.assets.keys().copied())
+  }
+}
+
+#[cfg(all(test, debug_assertions))]
+mod tests {
+  use std::path::Path;
+
+  #[test]
+  fn dir_path() {
+    let dir = Path::new("dist");
+    assert_eq!(
+      super::dir_path(dir, "/assets/app.js"),
+      Some(dir.join("assets").join("app.js"))
+    );
+    assert_eq!(super::dir_path(dir, "/../secret.txt"), None);
+    assert_eq!(super::dir_path(dir, "/assets/../../secret.txt"), None);
+    assert_eq!(super::dir_path(dir, "/./index.html"), None);
   }
 }
@@ -93,6 +93,16 @@ pub struct WindowConfig {
   /// Whether or not the window icon should be added to the taskbar.
   #[serde(default)]
   pub skip_taskbar: bool,
+  /// Whether the file drop is enabled or not on the webview. By default it is enabled.
+  ///
+  /// Disabling it is required to use drag and drop on the frontend on Windows.
+  #[serde(default = "default_file_drop_enabled")]
+  pub file_drop_enabled: bool,
+  /// Whether the Ctrl+scroll and Ctrl+=/Ctrl+-/Ctrl+0 hotkeys can be used to zoom the webview. WebView2 always enables them on Windows.
+  #[serde(default)]
+  pub zoom_hotkeys_enabled: bool,
+  /// The origins allowed to send IPC messages on this window. Overrides the `tauri > security > ipcAllowedOrigins` config.
+  pub ipc_allowed_origins: Option<Vec<String>>,
 }
 
 fn default_window_label() -> String {
@@ -123,6 +133,10 @@ fn default_title() -> String {
   "Tauri App".to_string()
 }
 
+fn default_file_drop_enabled() -> bool {
+  true
+}
+
 impl Default for WindowConfig {
   fn default() -> Self {
     Self {
@@ -147,6 +161,9 @@ impl Default for WindowConfig {
       decorations: default_decorations(),
       always_on_top: false,
       skip_taskbar: false,
+      file_drop_enabled: default_file_drop_enabled(),
+      zoom_hotkeys_enabled: false,
+      ipc_allowed_origins: None,
     }
   }
 }
@@ -167,6 +184,12 @@ pub struct UpdaterConfig {
   /// Optional pubkey.
   #[serde(default)]
   pub pubkey: Option<String>,
+  /// The release channel, e.g. `beta`. Defaults to `stable`.
+  #[serde(default)]
+  pub channel: Option<String>,
+  /// Whether an update to a version lower than the running one is allowed or not.
+  #[serde(default)]
+  pub allow_downgrades: bool,
 }
 
 fn default_updater_dialog() -> bool {
@@ -180,16 +203,146 @@ impl Default for UpdaterConfig {
       dialog: true,
       endpoints: None,
       pubkey: None,
+      channel: None,
+      allow_downgrades: false,
     }
   }
 }
 
 /// Security configuration.
-#[derive(PartialEq, Deserialize, Debug, Clone, Default)]
+#[derive(PartialEq, Deserialize, Debug, Clone)]
 #[serde(rename_all = "camelCase")]
 pub struct SecurityConfig {
   /// Content security policy to inject to HTML files with the custom protocol.
   pub csp: Option<String>,
+  /// The origins allowed to send IPC messages, e.g. `tauri://localhost` or `https://*.example.com`.
+  /// All origins are allowed if not set.
+  ///
+  /// The origin is read from the URL of the webview main frame,
+  /// it can't be read on Windows so the windows fail to be created there if set.
+  pub ipc_allowed_origins: Option<Vec<String>>,
+  /// Whether iframes can send IPC messages or not.
+  #[serde(default = "default_ipc_allow_iframes")]
+  pub ipc_allow_iframes: bool,
+  /// The scopes restricting the windows and commands allowed to use each plugin, mapped by plugin name.
+  /// The plugins without a scope accept all their commands on every window.
+  #[serde(default)]
+  pub plugin_scopes: HashMap<String, PluginScope>,
+}
+
+impl Default for SecurityConfig {
+  fn default() -> Self {
+    Self {
+      csp: None,
+      ipc_allowed_origins: None,
+      ipc_allow_iframes: default_ipc_allow_iframes(),
+      plugin_scopes: Default::default(),
+    }
+  }
+}
+
+/// The windows and commands allowed to use a plugin.
+#[derive(PartialEq, Deserialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct PluginScope {
+  /// The labels of the windows allowed to invoke the plugin commands. No window is allowed if not set, use `*` to allow all windows.
+  pub windows: Option<Vec<String>>,
+  /// The plugin commands allowed to be invoked. No command is allowed if not set, use `*` to allow all the commands declared by the plugin.
+  pub commands: Option<Vec<String>>,
+}
+
+impl PluginScope {
+  /// Checks if the window with the given label can invoke the plugin command.
+  ///
+  /// Nothing is allowed unless it is listed or the list has the `*` wildcard.
+  pub fn allows(&self, window: &str, command: &str) -> bool {
+    fn listed(list: &Option<Vec<String>>, value: &str) -> bool {
+      list
+        .as_ref()
+        .map(|list| list.iter().any(|v| v == "*" || v == value))
+        .unwrap_or(false)
+    }
+    listed(&self.windows, window) && listed(&self.commands, command)
+  }
+}
+
+fn default_ipc_allow_iframes() -> bool {
+  true
+}
+
+/// The application pattern.
+#[derive(PartialEq, Deserialize, Debug, Clone)]
+#[serde(rename_all = "lowercase", tag = "use", content = "options")]
+pub enum PatternKind {
+  /// Brownfield pattern: the webview sends the IPC messages directly to the Rust core.
+  Brownfield,
+  /// Isolation pattern: the IPC messages go through a sandboxed isolation application,
+  /// which can validate them before they are encrypted and sent to the Rust core.
+  Isolation {
+    /// The directory containing the `index.html` file of the isolation application.
+    dir: PathBuf,
+  },
+}
+
+impl Default for PatternKind {
+  fn default() -> Self {
+    Self::Brownfield
+  }
+}
+
+/// The WebView2 configuration, only used on Windows.
+#[derive(PartialEq, Deserialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct Webview2Config {
+  /// Additional command line arguments passed to the WebView2 browser process.
+  pub additional_browser_args: Option<String>,
+  /// Path to a fixed version WebView2 runtime folder to use instead of the installed runtime.
+  pub fixed_runtime_path: Option<PathBuf>,
+  /// Path to the WebView2 user data folder.
+  pub user_data_path: Option<PathBuf>,
+}
+
+/// The proxy configuration of the HTTP requests made by the HTTP API and the updater.
+#[derive(PartialEq, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ProxyConfig {
+  /// The proxy URL, e.g. `http://proxy.example.com:8080`. Overrides the system proxy.
+  pub url: Option<String>,
+  /// The hosts reached without the proxy, e.g. `localhost` or `.example.com`.
+  pub no_proxy: Option<Vec<String>>,
+  /// Whether the system proxy is used when `url` isn't set or not.
+  #[serde(default = "default_proxy_system")]
+  pub system: bool,
+}
+
+fn default_proxy_system() -> bool {
+  true
+}
+
+impl Default for ProxyConfig {
+  fn default() -> Self {
+    Self {
+      url: None,
+      no_proxy: None,
+      system: true,
+    }
+  }
+}
+
+/// A custom URI scheme protocol registered at startup.
+#[derive(PartialEq, Deserialize, Debug, Clone)]
+#[serde(untagged)]
+pub enum ProtocolConfig {
+  /// Serves the files of a folder, relative to the resource directory if the path is relative.
+  Dir {
+    /// The folder path.
+    dir: PathBuf,
+  },
+  /// Serves the responses of a handler registered with `tauri::Builder::protocol_handler`.
+  Handler {
+    /// The handler name.
+    handler: String,
+  },
 }
 
 /// Configuration for application system tray icon.
@@ -337,6 +490,101 @@ impl CliConfig {
   }
 }
 
+/// A sidecar binary bundled with the app.
+#[derive(PartialEq, Deserialize, Debug, Clone)]
+#[serde(untagged)]
+pub enum ExternalBin {
+  /// The sidecar path, without the target triple suffix. Bundled for every target.
+  Path(String),
+  /// A sidecar bundled for some targets only.
+  Targets {
+    /// The sidecar path, without the target triple suffix.
+    path: String,
+    /// The target triples, or `windows`, `macos` and `linux` to match every target of the OS.
+    targets: Vec<String>,
+  },
+}
+
+impl ExternalBin {
+  /// The sidecar path, without the target triple suffix.
+  pub fn path(&self) -> &str {
+    match self {
+      Self::Path(path) | Self::Targets { path, .. } => path,
+    }
+  }
+
+  /// The sidecar name, used to spawn it with `Command::new_sidecar`.
+  pub fn name(&self) -> &str {
+    let path = self.path();
+    path.rsplit(&['/', '\\'][..]).next().unwrap_or(path)
+  }
+
+  /// Whether the sidecar is bundled for the given target triple.
+  pub fn is_bundled_for(&self, target_triple: &str) -> bool {
+    match self {
+      Self::Path(_) => true,
+      Self::Targets { targets, .. } => targets.iter().any(|target| match target.as_str() {
+        "windows" => target_triple.contains("windows"),
+        "macos" => target_triple.contains("apple-darwin"),
+        "linux" => target_triple.contains("linux"),
+        triple => triple == target_triple,
+      }),
+    }
+  }
+}
+
+/// The role of the app for a file type, used on macOS.
+#[derive(PartialEq, Deserialize, Debug, Clone, Copy)]
+pub enum BundleTypeRole {
+  /// The app can read and write the files.
+  Editor,
+  /// The app can only read the files.
+  Viewer,
+  /// The app provides runtime services for the files.
+  Shell,
+  /// The app declares the file type without opening it.
+  None,
+}
+
+impl Default for BundleTypeRole {
+  fn default() -> Self {
+    Self::Editor
+  }
+}
+
+/// A file type opened by the app.
+#[derive(PartialEq, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct FileAssociation {
+  /// The file extensions, without the leading dot.
+  pub ext: Vec<String>,
+  /// The file type name. Defaults to the first extension.
+  pub name: Option<String>,
+  /// The file type description shown by the file manager.
+  pub description: Option<String>,
+  /// The file MIME type, e.g. `text/markdown`.
+  pub mime_type: Option<String>,
+  /// The role of the app for the file type on macOS.
+  #[serde(default)]
+  pub role: BundleTypeRole,
+}
+
+impl FileAssociation {
+  /// Whether the path has one of the associated extensions.
+  pub fn matches(&self, path: &std::path::Path) -> bool {
+    path
+      .extension()
+      .and_then(|extension| extension.to_str())
+      .map(|extension| {
+        self
+          .ext
+          .iter()
+          .any(|ext| ext.trim_start_matches('.').eq_ignore_ascii_case(extension))
+      })
+      .unwrap_or_default()
+  }
+}
+
 /// The bundler configuration object.
 #[derive(PartialEq, Deserialize, Debug)]
 #[serde(rename_all = "camelCase")]
@@ -346,6 +594,18 @@ pub struct BundleConfig {
   /// The bundle icons.
   #[serde(default)]
   pub icon: Vec<String>,
+  /// The sidecar binaries bundled with the app.
+  #[serde(default)]
+  pub external_bin: Vec<ExternalBin>,
+  /// Verifies the checksums of the sidecars when the app starts.
+  #[serde(default)]
+  pub verify_sidecar_checksums: bool,
+  /// The URL schemes registered for the app, e.g. `myapp` to open the `myapp://` links.
+  #[serde(default)]
+  pub url_schemes: Vec<String>,
+  /// The file types opened by the app.
+  #[serde(default)]
+  pub file_associations: Vec<FileAssociation>,
 }
 
 impl Default for BundleConfig {
@@ -353,6 +613,10 @@ impl Default for BundleConfig {
     Self {
       identifier: String::from(""),
       icon: Vec::default(),
+      external_bin: Vec::default(),
+      verify_sidecar_checksums: false,
+      url_schemes: Vec::default(),
+      file_associations: Vec::default(),
     }
   }
 }
@@ -380,8 +644,20 @@ pub struct TauriConfig {
   /// The security configuration.
   #[serde(default)]
   pub security: SecurityConfig,
+  /// The application pattern.
+  #[serde(default)]
+  pub pattern: PatternKind,
   /// System tray configuration.
   pub system_tray: Option<SystemTrayConfig>,
+  /// The WebView2 configuration.
+  #[serde(default)]
+  pub webview2: Webview2Config,
+  /// The proxy configuration.
+  #[serde(default)]
+  pub proxy: ProxyConfig,
+  /// The custom URI scheme protocols, mapped by scheme name.
+  #[serde(default)]
+  pub protocols: HashMap<String, ProtocolConfig>,
 }
 
 impl Default for TauriConfig {
@@ -392,7 +668,11 @@ impl Default for TauriConfig {
       bundle: BundleConfig::default(),
       updater: UpdaterConfig::default(),
       security: SecurityConfig::default(),
+      pattern: PatternKind::default(),
       system_tray: None,
+      webview2: Webview2Config::default(),
+      proxy: ProxyConfig::default(),
+      protocols: Default::default(),
     }
   }
 }
@@ -421,6 +701,30 @@ pub struct BuildConfig {
   /// Whether we should inject the Tauri API on `window.__TAURI__` or not.
   #[serde(default)]
   pub with_global_tauri: bool,
+  /// The compression of the embedded assets.
+  #[serde(default)]
+  pub asset_compression: AssetCompression,
+}
+
+/// The compression of the embedded assets.
+///
+/// The assets are decompressed when they're requested,
+/// and the formats that are already compressed (e.g. images, fonts and archives) are always embedded as is.
+#[derive(PartialEq, Eq, Deserialize, Debug, Clone, Copy)]
+#[serde(rename_all = "lowercase")]
+pub enum AssetCompression {
+  /// Embed the assets as is.
+  None,
+  /// Compress the assets with [zstd](https://facebook.github.io/zstd/).
+  Zstd,
+  /// Compress the assets with [brotli](https://github.com/google/brotli), usually smaller than zstd for text assets but slower to decompress.
+  Brotli,
+}
+
+impl Default for AssetCompression {
+  fn default() -> Self {
+    Self::Zstd
+  }
 }
 
 fn default_dev_path() -> AppUrl {
@@ -439,6 +743,7 @@ impl Default for BuildConfig {
       dev_path: default_dev_path(),
       dist_dir: default_dist_path(),
       with_global_tauri: false,
+      asset_compression: AssetCompression::default(),
     }
   }
 }
@@ -475,6 +780,89 @@ pub struct Config {
 #[derive(Debug, Clone, Default, PartialEq, Deserialize)]
 pub struct PluginConfig(pub HashMap<String, JsonValue>);
 
+/// The name of the Tauri configuration file.
+pub const CONFIG_FILE_NAME: &str = "tauri.conf.json";
+
+/// The names of the configuration overlays merged over `tauri.conf.json`, in the order they're applied.
+///
+/// The platform overlay (e.g. `tauri.linux.conf.json`, named after [`std::env::consts::OS`]) is applied first,
+/// then the profile overlay (`tauri.debug.conf.json` or `tauri.release.conf.json`),
+/// so the profile values take precedence over the platform ones.
+pub fn overlay_file_names(platform: &str, profile: Option<&str>) -> Vec<String> {
+  let mut names = vec![format!("tauri.{}.conf.json", platform)];
+  if let Some(profile) = profile {
+    names.push(format!("tauri.{}.conf.json", profile));
+  }
+  names
+}
+
+/// Merges a configuration overlay into the config, following the JSON Merge Patch rules (RFC 7396):
+/// objects are merged recursively, `null` removes the value and anything else replaces it.
+pub fn merge(config: &mut JsonValue, overlay: &JsonValue) {
+  match (config, overlay) {
+    (JsonValue::Object(config), JsonValue::Object(overlay)) => {
+      for (key, value) in overlay {
+        if value.is_null() {
+          config.remove(key);
+        } else {
+          merge(config.entry(key.clone()).or_insert(JsonValue::Null), value);
+        }
+      }
+    }
+    (config, overlay) => *config = overlay.clone(),
+  }
+}
+
+/// Resolves the `${VAR}` and `${VAR:-default}` placeholders of the value with the environment variables.
+///
+/// The default is used when the variable is unset or empty, and `$${` escapes a literal `${`.
+pub fn interpolate(value: &str) -> crate::Result<String> {
+  let mut output = String::with_capacity(value.len());
+  let mut rest = value;
+  while let Some(start) = rest.find("${") {
+    if rest[..start].ends_with('$') {
+      output.push_str(&rest[..start - 1]);
+      output.push_str("${");
+      rest = &rest[start + 2..];
+      continue;
+    }
+    output.push_str(&rest[..start]);
+    let end = rest[start..]
+      .find('}')
+      .ok_or_else(|| crate::Error::ConfigPlaceholder(value.into()))?;
+    let mut placeholder = rest[start + 2..start + end].splitn(2, ":-");
+    let name = placeholder.next().unwrap();
+    match (std::env::var(name), placeholder.next()) {
+      (Ok(var), _) if !var.is_empty() => output.push_str(&var),
+      (_, Some(default)) => output.push_str(default),
+      (Ok(var), None) => output.push_str(&var),
+      (Err(_), None) => return Err(crate::Error::ConfigEnvVar(name.into())),
+    }
+    rest = &rest[start + end + 1..];
+  }
+  output.push_str(rest);
+  Ok(output)
+}
+
+/// Resolves the environment variable placeholders of all the config strings. See [`interpolate`].
+pub fn interpolate_json(config: &mut JsonValue) -> crate::Result<()> {
+  match config {
+    JsonValue::String(value) => *value = interpolate(value)?,
+    JsonValue::Array(values) => {
+      for value in values {
+        interpolate_json(value)?;
+      }
+    }
+    JsonValue::Object(values) => {
+      for value in values.values_mut() {
+        interpolate_json(value)?;
+      }
+    }
+    _ => {}
+  }
+  Ok(())
+}
+
 /// Implement `ToTokens` for all config structs, allowing a literal `Config` to be built.
 ///
 /// This allows for a build script to output the values in a `Config` to a `TokenStream`, which can
@@ -672,6 +1060,9 @@ mod build {
       let decorations = self.decorations;
       let always_on_top = self.always_on_top;
       let skip_taskbar = self.skip_taskbar;
+      let file_drop_enabled = self.file_drop_enabled;
+      let zoom_hotkeys_enabled = self.zoom_hotkeys_enabled;
+      let ipc_allowed_origins = opt_vec_str_lit(self.ipc_allowed_origins.as_ref());
 
       literal_struct!(
         tokens,
@@ -696,7 +1087,10 @@ mod build {
         visible,
         decorations,
         always_on_top,
-        skip_taskbar
+        skip_taskbar,
+        file_drop_enabled,
+        zoom_hotkeys_enabled,
+        ipc_allowed_origins
       );
     }
   }
@@ -802,8 +1196,72 @@ mod build {
     fn to_tokens(&self, tokens: &mut TokenStream) {
       let identifier = str_lit(&self.identifier);
       let icon = vec_lit(&self.icon, str_lit);
+      let external_bin = vec_lit(&self.external_bin, identity);
+      let verify_sidecar_checksums = self.verify_sidecar_checksums;
+      let url_schemes = vec_lit(&self.url_schemes, str_lit);
+      let file_associations = vec_lit(&self.file_associations, identity);
+
+      literal_struct!(
+        tokens,
+        BundleConfig,
+        identifier,
+        icon,
+        external_bin,
+        verify_sidecar_checksums,
+        url_schemes,
+        file_associations
+      );
+    }
+  }
+
+  impl ToTokens for BundleTypeRole {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+      let prefix = quote! { ::tauri::api::config::BundleTypeRole };
+
+      tokens.append_all(match self {
+        Self::Editor => quote! { #prefix::Editor },
+        Self::Viewer => quote! { #prefix::Viewer },
+        Self::Shell => quote! { #prefix::Shell },
+        Self::None => quote! { #prefix::None },
+      })
+    }
+  }
 
-      literal_struct!(tokens, BundleConfig, identifier, icon);
+  impl ToTokens for FileAssociation {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+      let ext = vec_lit(&self.ext, str_lit);
+      let name = opt_str_lit(self.name.as_ref());
+      let description = opt_str_lit(self.description.as_ref());
+      let mime_type = opt_str_lit(self.mime_type.as_ref());
+      let role = &self.role;
+
+      literal_struct!(
+        tokens,
+        FileAssociation,
+        ext,
+        name,
+        description,
+        mime_type,
+        role
+      );
+    }
+  }
+
+  impl ToTokens for ExternalBin {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+      let prefix = quote! { ::tauri::api::config::ExternalBin };
+
+      tokens.append_all(match self {
+        Self::Path(path) => {
+          let path = str_lit(path);
+          quote! { #prefix::Path(#path) }
+        }
+        Self::Targets { path, targets } => {
+          let path = str_lit(path);
+          let targets = vec_lit(targets, str_lit);
+          quote! { #prefix::Targets { path: #path, targets: #targets } }
+        }
+      })
     }
   }
 
@@ -828,8 +1286,28 @@ mod build {
       let dev_path = &self.dev_path;
       let dist_dir = &self.dist_dir;
       let with_global_tauri = self.with_global_tauri;
+      let asset_compression = &self.asset_compression;
+
+      literal_struct!(
+        tokens,
+        BuildConfig,
+        dev_path,
+        dist_dir,
+        with_global_tauri,
+        asset_compression
+      );
+    }
+  }
 
-      literal_struct!(tokens, BuildConfig, dev_path, dist_dir, with_global_tauri);
+  impl ToTokens for AssetCompression {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+      let prefix = quote! { ::tauri::api::config::AssetCompression };
+
+      tokens.append_all(match self {
+        Self::None => quote! { #prefix::None },
+        Self::Zstd => quote! { #prefix::Zstd },
+        Self::Brotli => quote! { #prefix::Brotli },
+      })
     }
   }
 
@@ -839,16 +1317,81 @@ mod build {
       let dialog = self.dialog;
       let pubkey = opt_str_lit(self.pubkey.as_ref());
       let endpoints = opt_vec_str_lit(self.endpoints.as_ref());
+      let channel = opt_str_lit(self.channel.as_ref());
+      let allow_downgrades = self.allow_downgrades;
 
-      literal_struct!(tokens, UpdaterConfig, active, dialog, pubkey, endpoints);
+      literal_struct!(
+        tokens,
+        UpdaterConfig,
+        active,
+        dialog,
+        pubkey,
+        endpoints,
+        channel,
+        allow_downgrades
+      );
     }
   }
 
   impl ToTokens for SecurityConfig {
     fn to_tokens(&self, tokens: &mut TokenStream) {
       let csp = opt_str_lit(self.csp.as_ref());
+      let ipc_allowed_origins = opt_vec_str_lit(self.ipc_allowed_origins.as_ref());
+      let ipc_allow_iframes = self.ipc_allow_iframes;
+      let plugin_scopes = map_lit(
+        quote! { ::std::collections::HashMap },
+        &self.plugin_scopes,
+        str_lit,
+        identity,
+      );
 
-      literal_struct!(tokens, SecurityConfig, csp);
+      literal_struct!(
+        tokens,
+        SecurityConfig,
+        csp,
+        ipc_allowed_origins,
+        ipc_allow_iframes,
+        plugin_scopes
+      );
+    }
+  }
+
+  impl ToTokens for PluginScope {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+      let windows = opt_vec_str_lit(self.windows.as_ref());
+      let commands = opt_vec_str_lit(self.commands.as_ref());
+
+      literal_struct!(tokens, PluginScope, windows, commands);
+    }
+  }
+
+  impl ToTokens for PatternKind {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+      let prefix = quote! { ::tauri::api::config::PatternKind };
+
+      tokens.append_all(match self {
+        Self::Brownfield => quote! { #prefix::Brownfield },
+        Self::Isolation { dir } => {
+          let dir = path_buf_lit(dir);
+          quote! { #prefix::Isolation { dir: #dir } }
+        }
+      })
+    }
+  }
+
+  impl ToTokens for Webview2Config {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+      let additional_browser_args = opt_str_lit(self.additional_browser_args.as_ref());
+      let fixed_runtime_path = opt_lit(self.fixed_runtime_path.as_ref().map(path_buf_lit).as_ref());
+      let user_data_path = opt_lit(self.user_data_path.as_ref().map(path_buf_lit).as_ref());
+
+      literal_struct!(
+        tokens,
+        Webview2Config,
+        additional_browser_args,
+        fixed_runtime_path,
+        user_data_path
+      );
     }
   }
 
@@ -859,6 +1402,16 @@ mod build {
     }
   }
 
+  impl ToTokens for ProxyConfig {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+      let url = opt_str_lit(self.url.as_ref());
+      let no_proxy = opt_vec_str_lit(self.no_proxy.as_ref());
+      let system = self.system;
+
+      literal_struct!(tokens, ProxyConfig, url, no_proxy, system);
+    }
+  }
+
   impl ToTokens for TauriConfig {
     fn to_tokens(&self, tokens: &mut TokenStream) {
       let windows = vec_lit(&self.windows, identity);
@@ -866,7 +1419,16 @@ mod build {
       let bundle = &self.bundle;
       let updater = &self.updater;
       let security = &self.security;
+      let pattern = &self.pattern;
       let system_tray = opt_lit(self.system_tray.as_ref());
+      let webview2 = &self.webview2;
+      let proxy = &self.proxy;
+      let protocols = map_lit(
+        quote! { ::std::collections::HashMap },
+        &self.protocols,
+        str_lit,
+        identity,
+      );
 
       literal_struct!(
         tokens,
@@ -876,11 +1438,32 @@ mod build {
         bundle,
         updater,
         security,
-        system_tray
+        pattern,
+        system_tray,
+        webview2,
+        proxy,
+        protocols
       );
     }
   }
 
+  impl ToTokens for ProtocolConfig {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+      let prefix = quote! { ::tauri::api::config::ProtocolConfig };
+
+      tokens.append_all(match self {
+        Self::Dir { dir } => {
+          let dir = path_buf_lit(dir);
+          quote! { #prefix::Dir { dir: #dir } }
+        }
+        Self::Handler { handler } => {
+          let handler = str_lit(handler);
+          quote! { #prefix::Handler { handler: #handler } }
+        }
+      })
+    }
+  }
+
   impl ToTokens for PluginConfig {
     fn to_tokens(&self, tokens: &mut TokenStream) {
       let config = map_lit(
@@ -920,6 +1503,73 @@ mod test {
 
   // TODO: create a test that compares a config to a json config
 
+  #[test]
+  fn interpolate_env() {
+    std::env::set_var("TAURI_INTERPOLATE_TEST", "value");
+    std::env::remove_var("TAURI_INTERPOLATE_UNSET");
+    assert_eq!(
+      interpolate("a/${TAURI_INTERPOLATE_TEST}/b").unwrap(),
+      "a/value/b"
+    );
+    assert_eq!(
+      interpolate("${TAURI_INTERPOLATE_UNSET:-default}").unwrap(),
+      "default"
+    );
+    assert_eq!(
+      interpolate("$${TAURI_INTERPOLATE_TEST}").unwrap(),
+      "${TAURI_INTERPOLATE_TEST}"
+    );
+    assert!(matches!(
+      interpolate("${TAURI_INTERPOLATE_UNSET}"),
+      Err(crate::Error::ConfigEnvVar(_))
+    ));
+    assert!(matches!(
+      interpolate("${TAURI_INTERPOLATE_TEST"),
+      Err(crate::Error::ConfigPlaceholder(_))
+    ));
+
+    let mut config =
+      serde_json::json!({ "endpoints": ["${TAURI_INTERPOLATE_TEST}"], "active": true });
+    interpolate_json(&mut config).unwrap();
+    assert_eq!(
+      config,
+      serde_json::json!({ "endpoints": ["value"], "active": true })
+    );
+  }
+
+  #[test]
+  fn merge_overlays() {
+    assert_eq!(
+      overlay_file_names("linux", Some("release")),
+      vec!["tauri.linux.conf.json", "tauri.release.conf.json"]
+    );
+    assert_eq!(
+      overlay_file_names("macos", None),
+      vec!["tauri.macos.conf.json"]
+    );
+
+    let mut config = serde_json::json!({
+      "package": { "productName": "app", "version": "0.1.0" },
+      "tauri": { "bundle": { "identifier": "com.tauri.app", "targets": "all" } }
+    });
+    merge(
+      &mut config,
+      &serde_json::json!({
+        "package": { "version": null },
+        "tauri": { "bundle": { "targets": ["deb"], "active": true } }
+      }),
+    );
+    assert_eq!(
+      config,
+      serde_json::json!({
+        "package": { "productName": "app" },
+        "tauri": {
+          "bundle": { "identifier": "com.tauri.app", "targets": ["deb"], "active": true }
+        }
+      })
+    );
+  }
+
   #[test]
   // test all of the default functions
   fn test_defaults() {
@@ -962,10 +1612,17 @@ mod test {
         decorations: true,
         always_on_top: false,
         skip_taskbar: false,
+        file_drop_enabled: true,
+        zoom_hotkeys_enabled: false,
+        ipc_allowed_origins: None,
       }],
       bundle: BundleConfig {
         identifier: String::from(""),
         icon: Vec::new(),
+        external_bin: Vec::new(),
+        verify_sidecar_checksums: false,
+        url_schemes: Vec::new(),
+        file_associations: Vec::new(),
       },
       cli: None,
       updater: UpdaterConfig {
@@ -973,9 +1630,28 @@ mod test {
         dialog: true,
         pubkey: None,
         endpoints: None,
+        channel: None,
+        allow_downgrades: false,
       },
-      security: SecurityConfig { csp: None },
+      security: SecurityConfig {
+        csp: None,
+        ipc_allowed_origins: None,
+        ipc_allow_iframes: true,
+        plugin_scopes: Default::default(),
+      },
+      pattern: PatternKind::Brownfield,
       system_tray: None,
+      webview2: Webview2Config {
+        additional_browser_args: None,
+        fixed_runtime_path: None,
+        user_data_path: None,
+      },
+      proxy: ProxyConfig {
+        url: None,
+        no_proxy: None,
+        system: true,
+      },
+      protocols: Default::default(),
     };
 
     // create a build config
@@ -985,6 +1661,7 @@ mod test {
       )),
       dist_dir: AppUrl::Url(WindowUrl::App("../dist".into())),
       with_global_tauri: false,
+      asset_compression: AssetCompression::Zstd,
     };
 
     // test the configs
@@ -1001,4 +1678,51 @@ mod test {
     assert_eq!(d_title, tauri.windows[0].title);
     assert_eq!(d_windows, tauri.windows);
   }
+
+  #[test]
+  fn external_bin_targets() {
+    let all: ExternalBin =
+      serde_json::from_value(serde_json::json!("binaries/my-sidecar")).unwrap();
+    assert_eq!(all.name(), "my-sidecar");
+    assert!(all.is_bundled_for("x86_64-unknown-linux-gnu"));
+
+    let windows: ExternalBin = serde_json::from_value(serde_json::json!({
+      "path": "binaries/my-sidecar",
+      "targets": ["windows", "aarch64-apple-darwin"]
+    }))
+    .unwrap();
+    assert!(windows.is_bundled_for("x86_64-pc-windows-msvc"));
+    assert!(windows.is_bundled_for("aarch64-apple-darwin"));
+    assert!(!windows.is_bundled_for("x86_64-apple-darwin"));
+    assert!(!windows.is_bundled_for("x86_64-unknown-linux-gnu"));
+  }
+
+  #[test]
+  fn plugin_scope_allows() {
+    let unset = PluginScope::default();
+    assert!(!unset.allows("main", "ping"));
+
+    let scope: PluginScope = serde_json::from_value(serde_json::json!({
+      "windows": ["settings"],
+      "commands": ["ping"]
+    }))
+    .unwrap();
+    assert!(scope.allows("settings", "ping"));
+    assert!(!scope.allows("main", "ping"));
+    assert!(!scope.allows("settings", "pong"));
+
+    let windows_only: PluginScope = serde_json::from_value(serde_json::json!({
+      "windows": ["settings"]
+    }))
+    .unwrap();
+    assert!(!windows_only.allows("settings", "ping"));
+
+    let all: PluginScope = serde_json::from_value(serde_json::json!({
+      "windows": ["*"],
+      "commands": ["*"]
+    }))
+    .unwrap();
+    assert!(all.allows("main", "ping"));
+    assert!(all.allows("settings", "pong"));
+  }
 }
@@ -9,10 +9,21 @@ use html5ever::{
   LocalName,
 };
 use kuchiki::{traits::*, Attribute, ExpandedName, NodeRef};
+use sha2::{Digest, Sha256};
 
 /// Injects a content security policy to the HTML.
+///
+/// The hashes of the inline `<script>` and `<style>` tags are added to the `script-src` and `style-src` directives
+/// (created from `default-src` if needed), so the inline code is allowed with a strict policy.
+/// The directives allowing `'unsafe-inline'` are left as is, since the hashes would disable it.
 pub fn inject_csp<H: Into<Tendril<UTF8, NonAtomic>>>(html: H, csp: &str) -> String {
   let document = kuchiki::parse_html().one(html);
+  let csp = csp_with_hashes(
+    csp,
+    &inline_hashes(&document, "script:not([src])"),
+    &inline_hashes(&document, "style"),
+  );
+  let csp = csp.as_str();
   if let Ok(ref head) = document.select_first("head") {
     head.as_node().append(create_csp_meta_tag(csp));
   } else {
@@ -26,6 +37,119 @@ pub fn inject_csp<H: Into<Tendril<UTF8, NonAtomic>>>(html: H, csp: &str) -> Stri
   document.to_string()
 }
 
+/// Injects a script to the HTML, before any other script of the document.
+pub fn inject_script<H: Into<Tendril<UTF8, NonAtomic>>>(html: H, script: &str) -> String {
+  let document = kuchiki::parse_html().one(html);
+  let script_tag = NodeRef::new_element(
+    QualName::new(None, ns!(html), LocalName::from("script")),
+    None,
+  );
+  script_tag.append(NodeRef::new_text(script));
+  if let Ok(ref head) = document.select_first("head") {
+    head.as_node().prepend(script_tag);
+  } else {
+    let head = NodeRef::new_element(
+      QualName::new(None, ns!(html), LocalName::from("head")),
+      None,
+    );
+    head.append(script_tag);
+    document.prepend(head);
+  }
+  document.to_string()
+}
+
+/// Adds a version query parameter to the `src` and `href` URLs of the scripts, stylesheets and images of the HTML,
+/// so the webview loads them again when they change instead of serving them from its cache.
+///
+/// `version` returns the version of a URL, e.g. the modification time of the file, or `None` to leave it unchanged.
+pub fn add_asset_versions<H, F>(html: H, version: F) -> String
+where
+  H: Into<Tendril<UTF8, NonAtomic>>,
+  F: Fn(&str) -> Option<String>,
+{
+  let document = kuchiki::parse_html().one(html);
+  if let Ok(nodes) = document.select("script[src], link[href], img[src]") {
+    for node in nodes {
+      let mut attributes = node.attributes.borrow_mut();
+      for name in &["src", "href"] {
+        if let Some(url) = attributes.get_mut(*name) {
+          if let Some(version) = version(url) {
+            let (path, fragment) = match url.find('#') {
+              Some(index) => url.split_at(index),
+              None => (url.as_str(), ""),
+            };
+            let separator = if path.contains('?') { '&' } else { '?' };
+            *url = format!("{}{}v={}{}", path, separator, version, fragment);
+          }
+        }
+      }
+    }
+  }
+  document.to_string()
+}
+
+// The CSP sources of the content of the inline tags, e.g. `'sha256-...'`.
+fn inline_hashes(document: &NodeRef, selector: &str) -> Vec<String> {
+  let mut hashes: Vec<String> = document
+    .select(selector)
+    .map(|tags| {
+      tags
+        .map(|tag| {
+          let hash = Sha256::digest(tag.text_contents().as_bytes());
+          format!("'sha256-{}'", base64::encode(hash))
+        })
+        .collect()
+    })
+    .unwrap_or_default();
+  hashes.sort();
+  hashes.dedup();
+  hashes
+}
+
+// Adds the hashes to the `directive` (e.g. `script-src`) of the CSP.
+fn add_hashes(directives: &mut Vec<String>, directive: &str, hashes: &[String]) {
+  if hashes.is_empty() {
+    return;
+  }
+  let sources = |name: &str| {
+    directives
+      .iter()
+      .position(|d| d.split_whitespace().next() == Some(name))
+  };
+  match sources(directive).or_else(|| sources("default-src")) {
+    Some(index) if !directives[index].contains("'unsafe-inline'") => {
+      let mut sources = directives[index]
+        .split_whitespace()
+        .skip(1)
+        .collect::<Vec<_>>();
+      sources.extend(hashes.iter().map(String::as_str));
+      let updated = format!("{} {}", directive, sources.join(" "));
+      if directives[index].starts_with(directive) {
+        directives[index] = updated;
+      } else {
+        directives.push(updated);
+      }
+    }
+    // inline code is already allowed
+    _ => {}
+  }
+}
+
+fn csp_with_hashes(csp: &str, script_hashes: &[String], style_hashes: &[String]) -> String {
+  if script_hashes.is_empty() && style_hashes.is_empty() {
+    return csp.to_string();
+  }
+  let mut directives = csp
+    .split(';')
+    .map(str::trim)
+    .filter(|d| !d.is_empty())
+    .map(String::from)
+    .collect::<Vec<_>>();
+  add_hashes(&mut directives, "script-src", script_hashes);
+  add_hashes(&mut directives, "style-src", style_hashes);
+  directives.join("; ")
+}
+
 fn create_csp_meta_tag(csp: &str) -> NodeRef {
   NodeRef::new_element(
     QualName::new(None, ns!(html), LocalName::from("meta")),
@@ -68,4 +192,45 @@ mod tests {
       );
     }
   }
+
+  #[test]
+  fn csp_hashes() {
+    let html = "<html><head><script>window.value = 1</script><script src=\"app.js\"></script><style>body { margin: 0 }</style></head></html>";
+    let new = super::inject_csp(html, "default-src 'self'; style-src 'self' 'unsafe-inline'");
+    assert!(new.contains(
+      "default-src 'self'; style-src 'self' 'unsafe-inline'; script-src 'self' 'sha256-"
+    ));
+    // the script with a source isn't hashed
+    assert_eq!(new.matches("'sha256-").count(), 1);
+  }
+
+  #[test]
+  fn asset_versions() {
+    let html = r#"<html><head><script src="app.js"></script><link href="style.css?theme=dark#top" rel="stylesheet"><script src="https://cdn.example.com/lib.js"></script></head></html>"#;
+    let new = super::add_asset_versions(html, |url| {
+      if url.contains("://") {
+        None
+      } else {
+        Some("1".into())
+      }
+    });
+    assert!(new.contains(r#"src="app.js?v=1""#));
+    assert!(new.contains(r#"href="style.css?theme=dark&amp;v=1#top""#));
+    assert!(new.contains(r#"src="https://cdn.example.com/lib.js""#));
+  }
+
+  #[test]
+  fn script() {
+    let htmls = vec![
+      "<html><head><title>app</title></head></html>".to_string(),
+      "<html></html>".to_string(),
+    ];
+    let expected = vec![
+      "<html><head><script>window.value = 1</script><title>app</title></head><body></body></html>",
+      "<html><head><script>window.value = 1</script></head><body></body></html>",
+    ];
+    for (html, expected) in htmls.into_iter().zip(expected) {
+      assert_eq!(super::inject_script(html, "window.value = 1"), expected);
+    }
+  }
 }
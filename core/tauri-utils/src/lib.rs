@@ -68,4 +68,10 @@ pub enum Error {
   /// IO error.
   #[error("{0}")]
   Io(#[from] std::io::Error),
+  /// A config placeholder references an environment variable that isn't set and has no default value.
+  #[error("environment variable `{0}` referenced by the config is not set")]
+  ConfigEnvVar(String),
+  /// A config placeholder isn't closed.
+  #[error("unclosed placeholder in `{0}`")]
+  ConfigPlaceholder(String),
 }
@@ -4,9 +4,11 @@
 
 use std::{
   env,
-  path::{PathBuf, MAIN_SEPARATOR},
+  path::{Path, PathBuf, MAIN_SEPARATOR},
 };
 
+use sha2::{Digest, Sha256};
+
 use crate::PackageInfo;
 
 /// Try to determine the current target triple.
@@ -97,6 +99,18 @@ pub fn resource_dir(package_info: &PackageInfo) -> crate::Result<PathBuf> {
         appdir,
         package_info.package_name()
       )))
+    } else if env::var_os("FLATPAK_ID").is_some() {
+      // running the flatpak bundle, installed on /app
+      Ok(PathBuf::from(format!(
+        "/app/lib/{}",
+        package_info.package_name()
+      )))
+    } else if let Ok(snap) = env::var("SNAP") {
+      Ok(PathBuf::from(format!(
+        "{}/usr/lib/{}",
+        snap,
+        package_info.package_name()
+      )))
     } else {
       // running bundle
       Ok(PathBuf::from(format!(
@@ -110,3 +124,38 @@ pub fn resource_dir(package_info: &PackageInfo) -> crate::Result<PathBuf> {
     Err(crate::Error::UnsupportedPlatform)
   }
 }
+
+/// The environment variable the portable Windows executable sets with the app data directory.
+pub const PORTABLE_DATA_DIR_ENV: &str = "TAURI_PORTABLE_DATA_DIR";
+
+/// Gets the directory where the app data is stored when running from the portable Windows executable.
+///
+/// Returns `None` on other platforms or when the app isn't running from a portable executable.
+pub fn portable_data_dir() -> Option<PathBuf> {
+  if cfg!(target_os = "windows") {
+    env::var_os(PORTABLE_DATA_DIR_ENV).map(PathBuf::from)
+  } else {
+    None
+  }
+}
+
+/// Gets the file name of a sidecar built for the given target triple,
+/// e.g. `my-sidecar-x86_64-pc-windows-msvc.exe`.
+pub fn sidecar_file_name(name: &str, target_triple: &str) -> String {
+  format!(
+    "{}-{}{}",
+    name,
+    target_triple,
+    if target_triple.contains("windows") {
+      ".exe"
+    } else {
+      ""
+    }
+  )
+}
+
+/// Computes the hex encoded SHA-256 checksum of a file, used to verify the sidecars.
+pub fn file_checksum(path: &Path) -> crate::Result<String> {
+  let hash = Sha256::digest(&std::fs::read(path)?);
+  Ok(hash.iter().map(|byte| format!("{:02x}", byte)).collect())
+}
@@ -11,6 +11,9 @@ fn main() {
 
     api_all: { feature = "api-all" },
 
+    // app
+    app_all: { any(api_all, feature = "app-all") },
+
     // fs
     fs_all: { any(api_all, feature = "fs-all") },
     fs_read_text_file: { any(fs_all, feature = "fs-read-text-file") },
@@ -24,6 +27,8 @@ fn main() {
     fs_remove_file: { any(fs_all, feature = "fs-remove-file") },
     fs_rename_file: { any(fs_all, feature = "fs-rename-file") },
     fs_path: { any(fs_all, feature = "fs-path") },
+    fs_watch: { any(fs_all, feature = "fs-watch") },
+    fs_trash: { any(fs_all, feature = "fs-trash") },
 
     // window
     window_all: { any(api_all, feature = "window-all") },
@@ -38,10 +43,15 @@ fn main() {
     dialog_all: { any(api_all, feature = "dialog-all") },
     dialog_open: { any(dialog_all, feature = "dialog-open") },
     dialog_save: { any(dialog_all, feature = "dialog-save") },
+    dialog_message: { any(dialog_all, feature = "dialog-message") },
+    dialog_ask: { any(dialog_all, feature = "dialog-ask") },
+    // the dialogs are also used by the notification permission prompt and the updater
+    dialog: { any(dialog_open, dialog_save, dialog_message, dialog_ask, notification_all, feature = "updater") },
 
     // http
     http_all: { any(api_all, feature = "http-all") },
     http_request: { any(http_all, feature = "http-request") },
+    http_client: { any(http_request, feature = "updater") },
 
     // cli
     cli: { feature = "cli" },
@@ -51,5 +61,8 @@ fn main() {
 
     // global shortcut
     global_shortcut_all: { any(api_all, feature = "global_shortcut-all") },
+
+    // system
+    system_all: { any(api_all, feature = "system-all") },
   }
 }
@@ -92,10 +92,51 @@ if (!String.prototype.startsWith) {
     return identifier;
   };
 
+  // decoders of the IPC codecs, registered by the codec scripts
+  window.__TAURI__.__codecs = window.__TAURI__.__codecs || {};
+
+  // binary payloads are referenced by id and fetched as an ArrayBuffer from the tauri protocol
+  window.__TAURI__.__resolveBinary = function resolveBinary(value) {
+    if (
+      value &&
+      typeof value === "object" &&
+      typeof value.__tauriBinary === "string"
+    ) {
+      var codec = value.__tauriCodec && window.__TAURI__.__codecs[value.__tauriCodec];
+      var origin = navigator.userAgent.indexOf("Windows") !== -1
+        ? "https://tauri.localhost"
+        : "tauri://localhost";
+      return fetch(origin + "/__tauri_binary__/" + value.__tauriBinary).then(
+        function (response) {
+          return response.arrayBuffer().then(function (buffer) {
+            return codec ? codec(buffer) : buffer;
+          });
+        }
+      );
+    }
+    return Promise.resolve(value);
+  };
+
+  // the key of the window, only kept by the main frame so the Rust core can tell the iframe messages apart
+  var ipcKey = window.top === window ? __TAURI_IPC_KEY__ : null;
+
+  // sends the message to the Rust core, through the isolation application if the isolation pattern is used
+  window.__TAURI__.__sendIpcMessage = function sendIpcMessage(cmd, message) {
+    if (ipcKey) {
+      message.__tauriIpcKey = ipcKey;
+    }
+    if (window.__TAURI_ISOLATION__) {
+      window.__TAURI_ISOLATION__.send(cmd, message);
+    } else {
+      window.rpc.notify(cmd, message);
+    }
+  };
+
   window.__TAURI__.invoke = function invoke(cmd, args = {}) {
-    return new Promise(function (resolve, reject) {
+    var abort = function () {};
+    var promise = new Promise(function (resolve, reject) {
       var callback = window.__TAURI__.transformCallback(function (r) {
-        resolve(r);
+        window.__TAURI__.__resolveBinary(r).then(resolve, reject);
         delete window[error];
       }, true);
       var error = window.__TAURI__.transformCallback(function (e) {
@@ -111,8 +152,21 @@ if (!String.prototype.startsWith) {
         return reject(new Error("Invalid argument type."));
       }
 
+      abort = function () {
+        delete window[callback];
+        delete window[error];
+        window.__TAURI__.invoke("tauri", {
+          __tauriModule: "Internal",
+          message: {
+            cmd: "abortInvoke",
+            id: callback,
+          },
+        });
+        reject(new Error("aborted"));
+      };
+
       if (window.rpc) {
-        window.rpc.notify(
+        window.__TAURI__.__sendIpcMessage(
           cmd,
           _objectSpread(
             {
@@ -124,7 +178,7 @@ if (!String.prototype.startsWith) {
         );
       } else {
         window.addEventListener("DOMContentLoaded", function () {
-          window.rpc.notify(
+          window.__TAURI__.__sendIpcMessage(
             cmd,
             _objectSpread(
               {
@@ -137,6 +191,10 @@ if (!String.prototype.startsWith) {
         });
       }
     });
+    promise.abort = function () {
+      abort();
+    };
+    return promise;
   };
 
   // open <a href="..."> links with the Tauri API
@@ -0,0 +1,63 @@
+// Copyright 2019-2021 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+// encrypts the IPC messages on the isolation application, after they go through its hook
+(function () {
+  var keyPromise = window.crypto.subtle.importKey(
+    "raw",
+    new Uint8Array(__TAURI_ISOLATION_KEY__),
+    "AES-GCM",
+    false,
+    ["encrypt"]
+  );
+
+  function encrypt(data) {
+    var nonce = window.crypto.getRandomValues(new Uint8Array(12));
+    var encoded = new TextEncoder().encode(JSON.stringify(data));
+    return keyPromise
+      .then(function (key) {
+        return window.crypto.subtle.encrypt(
+          { name: "AES-GCM", iv: nonce },
+          key,
+          encoded
+        );
+      })
+      .then(function (payload) {
+        return {
+          nonce: Array.from(nonce),
+          payload: Array.from(new Uint8Array(payload)),
+        };
+      });
+  }
+
+  window.addEventListener("message", function (event) {
+    if (event.source !== window.parent || !event.data) {
+      return;
+    }
+    var cmd = event.data.cmd;
+    var hook =
+      window.__TAURI_ISOLATION_HOOK__ ||
+      function (payload) {
+        return payload;
+      };
+    Promise.resolve(hook(event.data.message, cmd))
+      .then(function (message) {
+        if (message) {
+          return encrypt({ cmd: cmd, message: message }).then(function (
+            encrypted
+          ) {
+            window.parent.postMessage({ cmd: cmd, message: encrypted }, "*");
+          });
+        }
+      })
+      .catch(function (e) {
+        console.error(e);
+      });
+  });
+
+  // wait for the isolation application to define its hook
+  window.addEventListener("load", function () {
+    window.parent.postMessage("__TAURI_ISOLATION_READY__", "*");
+  });
+})();
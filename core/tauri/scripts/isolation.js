@@ -0,0 +1,55 @@
+// Copyright 2019-2021 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+// sends the IPC messages through the isolation application, loaded on a sandboxed iframe
+(function () {
+  var origin = navigator.userAgent.indexOf("Windows") !== -1
+    ? "https://isolation.localhost"
+    : "isolation://localhost";
+  var frame = null;
+  var ready = false;
+  var queue = [];
+
+  function post(message) {
+    // the sandboxed iframe has an opaque origin
+    frame.contentWindow.postMessage(message, "*");
+  }
+
+  window.addEventListener("message", function (event) {
+    if (!frame || event.source !== frame.contentWindow) {
+      return;
+    }
+    if (event.data === "__TAURI_ISOLATION_READY__") {
+      ready = true;
+      queue.splice(0).forEach(post);
+    } else if (event.data && typeof event.data.cmd === "string") {
+      window.rpc.notify(event.data.cmd, event.data.message);
+    }
+  });
+
+  function createFrame() {
+    frame = document.createElement("iframe");
+    frame.setAttribute("sandbox", "allow-scripts");
+    frame.style.display = "none";
+    frame.src = origin + "/";
+    document.documentElement.appendChild(frame);
+  }
+
+  if (document.readyState === "loading") {
+    document.addEventListener("DOMContentLoaded", createFrame);
+  } else {
+    createFrame();
+  }
+
+  window.__TAURI_ISOLATION__ = {
+    send: function (cmd, message) {
+      var data = { cmd: cmd, message: message };
+      if (ready) {
+        post(data);
+      } else {
+        queue.push(data);
+      }
+    },
+  };
+})();
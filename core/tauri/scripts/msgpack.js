@@ -0,0 +1,143 @@
+// Copyright 2019-2021 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+// MessagePack decoder used by the `ipc-msgpack` codec
+(function () {
+  function decode(buffer) {
+    var view = new DataView(buffer);
+    var bytes = new Uint8Array(buffer);
+    var offset = 0;
+
+    function str(length) {
+      var value = new TextDecoder().decode(
+        bytes.subarray(offset, offset + length)
+      );
+      offset += length;
+      return value;
+    }
+
+    function bin(length) {
+      var value = buffer.slice(offset, offset + length);
+      offset += length;
+      return value;
+    }
+
+    function array(length) {
+      var value = new Array(length);
+      for (var i = 0; i < length; i++) {
+        value[i] = read();
+      }
+      return value;
+    }
+
+    function map(length) {
+      var value = {};
+      for (var i = 0; i < length; i++) {
+        var key = read();
+        value[key] = read();
+      }
+      return value;
+    }
+
+    function u8() {
+      return view.getUint8(offset++);
+    }
+
+    function u16() {
+      var value = view.getUint16(offset);
+      offset += 2;
+      return value;
+    }
+
+    function u32() {
+      var value = view.getUint32(offset);
+      offset += 4;
+      return value;
+    }
+
+    function u64() {
+      var value = view.getUint32(offset) * 4294967296 + view.getUint32(offset + 4);
+      offset += 8;
+      return value;
+    }
+
+    function i64() {
+      var value = view.getInt32(offset) * 4294967296 + view.getUint32(offset + 4);
+      offset += 8;
+      return value;
+    }
+
+    function read() {
+      var type = u8();
+      var value;
+      if (type <= 0x7f) return type;
+      if (type <= 0x8f) return map(type & 0x0f);
+      if (type <= 0x9f) return array(type & 0x0f);
+      if (type <= 0xbf) return str(type & 0x1f);
+      if (type >= 0xe0) return type - 0x100;
+      switch (type) {
+        case 0xc0:
+          return null;
+        case 0xc2:
+          return false;
+        case 0xc3:
+          return true;
+        case 0xc4:
+          return bin(u8());
+        case 0xc5:
+          return bin(u16());
+        case 0xc6:
+          return bin(u32());
+        case 0xca:
+          value = view.getFloat32(offset);
+          offset += 4;
+          return value;
+        case 0xcb:
+          value = view.getFloat64(offset);
+          offset += 8;
+          return value;
+        case 0xcc:
+          return u8();
+        case 0xcd:
+          return u16();
+        case 0xce:
+          return u32();
+        case 0xcf:
+          return u64();
+        case 0xd0:
+          return view.getInt8(offset++);
+        case 0xd1:
+          value = view.getInt16(offset);
+          offset += 2;
+          return value;
+        case 0xd2:
+          value = view.getInt32(offset);
+          offset += 4;
+          return value;
+        case 0xd3:
+          return i64();
+        case 0xd9:
+          return str(u8());
+        case 0xda:
+          return str(u16());
+        case 0xdb:
+          return str(u32());
+        case 0xdc:
+          return array(u16());
+        case 0xdd:
+          return array(u32());
+        case 0xde:
+          return map(u16());
+        case 0xdf:
+          return map(u32());
+        default:
+          throw new Error("unsupported MessagePack type " + type);
+      }
+    }
+
+    return read();
+  }
+
+  window.__TAURI__.__codecs["__CODEC_NAME__"] = decode;
+})();
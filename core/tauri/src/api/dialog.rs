@@ -72,14 +72,110 @@ pub enum AskResponse {
   No,
 }
 
+/// The kind of a message dialog, defining its icon.
+#[derive(Debug, Clone, Copy)]
+pub enum MessageDialogKind {
+  /// An information dialog.
+  Info,
+  /// A warning dialog.
+  Warning,
+  /// An error dialog.
+  Error,
+}
+
+impl Default for MessageDialogKind {
+  fn default() -> Self {
+    Self::Info
+  }
+}
+
+/// The buttons of a message dialog.
+#[derive(Debug, Clone, Copy)]
+pub enum MessageDialogButtons {
+  /// A single "ok" button.
+  Ok,
+  /// An "ok" and a "cancel" button.
+  OkCancel,
+  /// A "yes" and a "no" button.
+  YesNo,
+}
+
+impl Default for MessageDialogButtons {
+  fn default() -> Self {
+    Self::Ok
+  }
+}
+
+/// The message dialog builder.
+///
+/// The native dialogs only support the button sets of [`MessageDialogButtons`],
+/// so custom button labels and default buttons can't be set.
+pub struct MessageDialogBuilder {
+  title: String,
+  message: String,
+  kind: MessageDialogKind,
+  buttons: MessageDialogButtons,
+}
+
+impl MessageDialogBuilder {
+  /// Creates a new message dialog builder.
+  pub fn new(title: impl Into<String>, message: impl Into<String>) -> Self {
+    Self {
+      title: title.into(),
+      message: message.into(),
+      kind: Default::default(),
+      buttons: Default::default(),
+    }
+  }
+
+  /// Set the kind of the dialog.
+  pub fn kind(mut self, kind: MessageDialogKind) -> Self {
+    self.kind = kind;
+    self
+  }
+
+  /// Set the buttons of the dialog.
+  pub fn buttons(mut self, buttons: MessageDialogButtons) -> Self {
+    self.buttons = buttons;
+    self
+  }
+
+  /// Shows the dialog, blocking the current thread until it's closed.
+  ///
+  /// Returns `true` if the user pressed the "ok" or "yes" button.
+  pub fn show_blocking(self) -> bool {
+    rfd::MessageDialog::new()
+      .set_title(&self.title)
+      .set_description(&self.message)
+      .set_buttons(match self.buttons {
+        MessageDialogButtons::Ok => rfd::MessageButtons::Ok,
+        MessageDialogButtons::OkCancel => rfd::MessageButtons::OkCancel,
+        MessageDialogButtons::YesNo => rfd::MessageButtons::YesNo,
+      })
+      .set_level(match self.kind {
+        MessageDialogKind::Info => rfd::MessageLevel::Info,
+        MessageDialogKind::Warning => rfd::MessageLevel::Warning,
+        MessageDialogKind::Error => rfd::MessageLevel::Error,
+      })
+      .show()
+  }
+
+  /// Shows the dialog on the main thread of the given window's event loop without blocking the current thread,
+  /// calling `f` with `true` if the user pressed the "ok" or "yes" button.
+  pub fn show<P: crate::Params, F: FnOnce(bool) + Send + 'static>(
+    self,
+    window: &crate::Window<P>,
+    f: F,
+  ) -> crate::Result<()> {
+    window.run_on_main_thread(move || f(self.show_blocking()))
+  }
+}
+
 /// Displays a dialog with a message and an optional title with a "yes" and a "no" button
 pub fn ask(title: impl AsRef<str>, message: impl AsRef<str>) -> AskResponse {
-  match rfd::MessageDialog::new()
-    .set_title(title.as_ref())
-    .set_description(message.as_ref())
-    .set_buttons(rfd::MessageButtons::YesNo)
-    .set_level(rfd::MessageLevel::Info)
-    .show()
+  match MessageDialogBuilder::new(title.as_ref(), message.as_ref())
+    .buttons(MessageDialogButtons::YesNo)
+    .show_blocking()
   {
     true => AskResponse::Yes,
     false => AskResponse::No,
@@ -88,10 +184,5 @@ pub fn ask(title: impl AsRef<str>, message: impl AsRef<str>) -> AskResponse {
 
 /// Displays a message dialog
 pub fn message(title: impl AsRef<str>, message: impl AsRef<str>) {
-  rfd::MessageDialog::new()
-    .set_title(title.as_ref())
-    .set_description(message.as_ref())
-    .set_buttons(rfd::MessageButtons::Ok)
-    .set_level(rfd::MessageLevel::Info)
-    .show();
+  MessageDialogBuilder::new(title.as_ref(), message.as_ref()).show_blocking();
 }
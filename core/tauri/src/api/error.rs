@@ -25,7 +25,7 @@ pub enum Error {
   #[error("user cancelled the dialog")]
   DialogCancelled,
   /// The network error.
-  #[cfg(not(feature = "reqwest-client"))]
+  #[cfg(all(http_client, not(feature = "reqwest-client")))]
   #[error("Network Error: {0}")]
   Network(#[from] attohttpc::Error),
   /// The network error.
@@ -44,6 +44,12 @@ pub enum Error {
   /// HTTP form to must be an object.
   #[error("http form must be an object")]
   InvalidHttpForm,
+  /// The download request failed with the given status code.
+  #[error("download request failed with status: {0}")]
+  DownloadStatus(u16),
+  /// The proxy URL is invalid.
+  #[error("invalid proxy URL: {0}")]
+  InvalidProxy(String),
   /// Semver error.
   #[error("{0}")]
   Semver(#[from] semver::Error),
@@ -62,6 +68,14 @@ pub enum Error {
   /// ZIP error.
   #[error("{0}")]
   Zip(#[from] zip::result::ZipError),
+  /// File system watcher error.
+  #[cfg(fs_watch)]
+  #[error("failed to watch path: {0}")]
+  Watch(#[from] notify::Error),
+  /// Trash error.
+  #[cfg(fs_trash)]
+  #[error("trash error: {0}")]
+  Trash(String),
   /// Notification error.
   #[cfg(notification_all)]
   #[error("{0}")]
@@ -77,7 +91,21 @@ pub enum Error {
   #[cfg(global_shortcut_all)]
   #[error("shortcut error: {0}")]
   Shortcut(#[from] tauri_hotkey::Error),
+  /// The shortcut is already registered.
+  #[cfg(global_shortcut_all)]
+  #[error("shortcut `{0}` is already registered")]
+  ShortcutAlreadyRegistered(String),
+  /// The shortcut isn't registered.
+  #[cfg(global_shortcut_all)]
+  #[error("shortcut `{0}` is not registered")]
+  ShortcutNotRegistered(String),
   /// Shell error.
   #[error("shell error: {0}")]
   Shell(String),
+  /// The sidecar isn't next to the app executable.
+  #[error("sidecar `{0}` not found next to the app executable")]
+  SidecarNotFound(String),
+  /// The sidecar checksum doesn't match the one computed when the app was built.
+  #[error("the checksum of the `{0}` sidecar doesn't match the one computed at build time")]
+  SidecarChecksumMismatch(String),
 }
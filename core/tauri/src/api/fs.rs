@@ -0,0 +1,13 @@
+// Copyright 2019-2021 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+#[cfg(fs_trash)]
+mod trash;
+#[cfg(fs_watch)]
+mod watch;
+
+#[cfg(fs_trash)]
+pub use trash::*;
+#[cfg(fs_watch)]
+pub use watch::*;
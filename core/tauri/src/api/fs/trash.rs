@@ -0,0 +1,79 @@
+// Copyright 2019-2021 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+use serde::Serialize;
+
+use std::path::{Path, PathBuf};
+
+/// A file or directory on the trash.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TrashedItem {
+  /// The platform identifier of the item, used to restore it.
+  pub id: String,
+  /// The item name.
+  pub name: String,
+  /// The path of the directory the item was deleted from.
+  pub original_parent: PathBuf,
+  /// The deletion time, as seconds since the UNIX epoch.
+  pub time_deleted: i64,
+}
+
+fn trash_error(error: ::trash::Error) -> crate::api::Error {
+  crate::api::Error::Trash(error.to_string())
+}
+
+/// Moves the file or directory to the platform trash (or recycle bin) instead of deleting it permanently.
+///
+/// # Example
+/// ```rust,no_run
+/// tauri::api::fs::trash("/home/user/report.pdf").expect("failed to move the file to the trash");
+/// ```
+pub fn trash<P: AsRef<Path>>(path: P) -> crate::api::Result<()> {
+  ::trash::delete(path).map_err(trash_error)
+}
+
+/// Lists the items on the trash.
+///
+/// Not supported on macOS.
+pub fn list_trash() -> crate::api::Result<Vec<TrashedItem>> {
+  #[cfg(target_os = "macos")]
+  return Err(crate::api::Error::Trash(
+    "listing the trash is not supported on macOS".into(),
+  ));
+  #[cfg(not(target_os = "macos"))]
+  {
+    let items = ::trash::os_limited::list().map_err(trash_error)?;
+    Ok(
+      items
+        .into_iter()
+        .map(|item| TrashedItem {
+          id: item.id.to_string_lossy().into_owned(),
+          name: item.name,
+          original_parent: item.original_parent,
+          time_deleted: item.time_deleted,
+        })
+        .collect(),
+    )
+  }
+}
+
+/// Restores the trash items with the given identifiers to their original location.
+///
+/// Not supported on macOS.
+#[allow(unused_variables)]
+pub fn untrash(ids: &[String]) -> crate::api::Result<()> {
+  #[cfg(target_os = "macos")]
+  return Err(crate::api::Error::Trash(
+    "restoring items from the trash is not supported on macOS".into(),
+  ));
+  #[cfg(not(target_os = "macos"))]
+  {
+    let items = ::trash::os_limited::list()
+      .map_err(trash_error)?
+      .into_iter()
+      .filter(|item| ids.contains(&item.id.to_string_lossy().into_owned()));
+    ::trash::os_limited::restore_all(items).map_err(trash_error)
+  }
+}
@@ -0,0 +1,131 @@
+// Copyright 2019-2021 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+use notify::{DebouncedEvent, RecommendedWatcher, RecursiveMode, Watcher as _};
+use serde::{Deserialize, Serialize};
+
+use std::{path::PathBuf, sync::mpsc::channel, time::Duration};
+
+fn default_delay_ms() -> u64 {
+  500
+}
+
+/// The options of a file system watcher.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WatchOptions {
+  /// Whether the directories should be watched recursively or not.
+  #[serde(default)]
+  pub recursive: bool,
+  /// The delay in milliseconds used to debounce the events.
+  #[serde(default = "default_delay_ms")]
+  pub delay_ms: u64,
+}
+
+impl Default for WatchOptions {
+  fn default() -> Self {
+    Self {
+      recursive: false,
+      delay_ms: default_delay_ms(),
+    }
+  }
+}
+
+/// A debounced file system event.
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "type", content = "payload", rename_all = "camelCase")]
+#[non_exhaustive]
+pub enum WatchEvent {
+  /// A file or directory was created.
+  Create(PathBuf),
+  /// A file was written.
+  Write(PathBuf),
+  /// The attributes of a file or directory were changed.
+  Chmod(PathBuf),
+  /// A file or directory was removed.
+  Remove(PathBuf),
+  /// A file or directory was renamed.
+  Rename {
+    /// The old path.
+    from: PathBuf,
+    /// The new path.
+    to: PathBuf,
+  },
+  /// Some events may have been missed, so the watched paths should be rescanned.
+  Rescan,
+  /// An error occurred while watching the paths.
+  Error {
+    /// The path related to the error.
+    path: Option<PathBuf>,
+    /// The error message.
+    error: String,
+  },
+}
+
+impl WatchEvent {
+  fn from_debounced(event: DebouncedEvent) -> Option<Self> {
+    match event {
+      DebouncedEvent::NoticeWrite(_) | DebouncedEvent::NoticeRemove(_) => None,
+      DebouncedEvent::Create(path) => Some(Self::Create(path)),
+      DebouncedEvent::Write(path) => Some(Self::Write(path)),
+      DebouncedEvent::Chmod(path) => Some(Self::Chmod(path)),
+      DebouncedEvent::Remove(path) => Some(Self::Remove(path)),
+      DebouncedEvent::Rename(from, to) => Some(Self::Rename { from, to }),
+      DebouncedEvent::Rescan => Some(Self::Rescan),
+      DebouncedEvent::Error(error, path) => Some(Self::Error {
+        path,
+        error: error.to_string(),
+      }),
+    }
+  }
+}
+
+/// A file system watcher. The paths are watched until it is dropped or stopped.
+pub struct Watcher {
+  _watcher: RecommendedWatcher,
+}
+
+impl Watcher {
+  /// Stops watching the paths.
+  pub fn stop(self) {}
+}
+
+/// Watches the given paths, calling `on_event` with the debounced events on a separate thread.
+///
+/// # Example
+/// ```rust,no_run
+/// use tauri::api::fs::{watch, WatchOptions};
+/// let watcher = watch(vec!["/tmp".into()], WatchOptions::default(), |event| {
+///   println!("{:?}", event);
+/// }).expect("failed to watch /tmp");
+/// // ...
+/// watcher.stop();
+/// ```
+pub fn watch<F: Fn(WatchEvent) + Send + 'static>(
+  paths: Vec<PathBuf>,
+  options: WatchOptions,
+  on_event: F,
+) -> crate::api::Result<Watcher> {
+  let (tx, rx) = channel();
+  let mut watcher = notify::watcher(tx, Duration::from_millis(options.delay_ms))?;
+  let mode = if options.recursive {
+    RecursiveMode::Recursive
+  } else {
+    RecursiveMode::NonRecursive
+  };
+  for path in paths {
+    watcher.watch(path, mode)?;
+  }
+
+  // the channel is closed when the watcher is dropped, ending the thread
+  std::thread::spawn(move || {
+    for event in rx {
+      if let Some(event) = WatchEvent::from_debounced(event) {
+        on_event(event);
+      }
+    }
+  });
+
+  Ok(Watcher { _watcher: watcher })
+}
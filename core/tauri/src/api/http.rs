@@ -2,12 +2,27 @@
 // SPDX-License-Identifier: Apache-2.0
 // SPDX-License-Identifier: MIT
 
-use http::{header::HeaderName, Method};
+use either::Either;
+use http::{
+  header::{HeaderName, CONTENT_LENGTH, CONTENT_TYPE},
+  Method,
+};
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 use serde_repr::{Deserialize_repr, Serialize_repr};
 
-use std::{collections::HashMap, path::PathBuf, time::Duration};
+mod proxy;
+
+pub use proxy::Proxy;
+
+use std::{
+  collections::{HashMap, VecDeque},
+  fs::{File, OpenOptions},
+  io::{Cursor, Read, Write},
+  path::{Path, PathBuf},
+  sync::Arc,
+  time::Duration,
+};
 
 /// Client builder.
 #[derive(Clone, Default, Deserialize)]
@@ -17,6 +32,9 @@ pub struct ClientBuilder {
   pub max_redirections: Option<usize>,
   /// Connect timeout in seconds for the request
   pub connect_timeout: Option<u64>,
+  /// The proxy of the requests. Defaults to [`Proxy::system`].
+  #[serde(skip)]
+  pub proxy: Option<Proxy>,
 }
 
 impl ClientBuilder {
@@ -37,6 +55,12 @@ impl ClientBuilder {
     self
   }
 
+  /// Sets the proxy, overriding the system proxy.
+  pub fn proxy(mut self, proxy: Proxy) -> Self {
+    self.proxy = Some(proxy);
+    self
+  }
+
   /// Builds the Client.
   #[cfg(not(feature = "reqwest-client"))]
   pub fn build(self) -> crate::api::Result<Client> {
@@ -56,6 +80,14 @@ impl ClientBuilder {
       client_builder = client_builder.connect_timeout(Duration::from_secs(connect_timeout));
     }
 
+    // the system proxy is detected by us, not by reqwest
+    client_builder = client_builder.no_proxy();
+    if let Some(proxy) = self.proxy.or_else(Proxy::system) {
+      client_builder = client_builder.proxy(reqwest::Proxy::custom(move |url| {
+        proxy.url_for(url.as_str())
+      }));
+    }
+
     let client = client_builder.build()?;
     Ok(Client(client))
   }
@@ -78,6 +110,16 @@ impl Client {
   /// The response will be transformed to String,
   /// If reading the response as binary, the byte array will be serialized using serde_json.
   pub async fn send(&self, request: HttpRequestBuilder) -> crate::api::Result<Response> {
+    self.execute(request).await?.error_for_status()
+  }
+
+  /// Executes an HTTP request on the blocking thread pool, as attohttpc is synchronous.
+  async fn execute(&self, request: HttpRequestBuilder) -> crate::api::Result<Response> {
+    let client = self.clone();
+    crate::async_runtime::spawn_blocking(move || client.execute_blocking(request)).await
+  }
+
+  fn execute_blocking(&self, request: HttpRequestBuilder) -> crate::api::Result<Response> {
     let method = Method::from_bytes(request.method.to_uppercase().as_bytes())?;
 
     let mut request_builder = attohttpc::RequestBuilder::try_new(method, &request.url)?;
@@ -97,6 +139,23 @@ impl Client {
       request_builder = request_builder.timeout(Duration::from_secs(timeout));
     }
 
+    // the system proxy is detected by us, not by attohttpc
+    let mut proxy_settings = attohttpc::ProxySettings::builder();
+    if let Some(proxy_url) = self
+      .0
+      .proxy
+      .clone()
+      .or_else(Proxy::system)
+      .and_then(|proxy| proxy.url_for(&request.url))
+    {
+      let proxy_url =
+        url::Url::parse(&proxy_url).map_err(|_| crate::api::Error::InvalidProxy(proxy_url))?;
+      proxy_settings = proxy_settings
+        .http_proxy(proxy_url.clone())
+        .https_proxy(proxy_url);
+    }
+    request_builder = request_builder.proxy_settings(proxy_settings.build());
+
     let response = if let Some(body) = request.body {
       match body {
         Body::Bytes(data) => request_builder.body(attohttpc::body::Bytes(data)).send()?,
@@ -113,12 +172,18 @@ impl Client {
           }
           request_builder.form(&form)?.send()?
         }
+        Body::Multipart(multipart) => {
+          let reader = multipart.into_reader()?;
+          request_builder
+            .header(CONTENT_TYPE, reader.content_type())
+            .body(reader)
+            .send()?
+        }
       }
     } else {
       request_builder.send()?
     };
 
-    let response = response.error_for_status()?;
     Ok(Response(
       request.response_type.unwrap_or(ResponseType::Json),
       response,
@@ -134,6 +199,11 @@ impl Client {
   /// The response will be transformed to String,
   /// If reading the response as binary, the byte array will be serialized using serde_json.
   pub async fn send(&self, request: HttpRequestBuilder) -> crate::api::Result<Response> {
+    self.execute(request).await?.error_for_status()
+  }
+
+  /// Executes an HTTP request, without checking the response status.
+  async fn execute(&self, request: HttpRequestBuilder) -> crate::api::Result<Response> {
     let method = Method::from_bytes(request.method.to_uppercase().as_bytes())?;
 
     let mut request_builder = self.0.request(method, &request.url);
@@ -179,12 +249,40 @@ impl Client {
           }
           request_builder.form(&form).send().await?
         }
+        Body::Multipart(multipart) => {
+          let reader = multipart.into_reader()?;
+          let content_type = reader.content_type();
+          let content_length = reader.total;
+          // the chunks are read from disk on the blocking thread pool while the body is sent
+          let chunks = futures::stream::unfold(Some(reader), |reader| async move {
+            let mut reader = reader?;
+            let (reader, chunk) = crate::async_runtime::spawn_blocking(move || {
+              let mut chunk = vec![0; 64 * 1024];
+              let chunk = reader.read(&mut chunk).map(|read| {
+                chunk.truncate(read);
+                chunk
+              });
+              (reader, chunk)
+            })
+            .await;
+            match chunk {
+              Ok(chunk) if chunk.is_empty() => None,
+              Ok(chunk) => Some((Ok(chunk), Some(reader))),
+              Err(e) => Some((Err(e), None)),
+            }
+          });
+          request_builder
+            .header(CONTENT_TYPE, content_type)
+            .header(CONTENT_LENGTH, content_length)
+            .body(reqwest::Body::wrap_stream(chunks))
+            .send()
+            .await?
+        }
       }
     } else {
       request_builder.send().await?
     };
 
-    let response = response.error_for_status()?;
     Ok(Response(
       request.response_type.unwrap_or(ResponseType::Json),
       response,
@@ -192,6 +290,118 @@ impl Client {
   }
 }
 
+impl Client {
+  /// Downloads the response of an HTTP request to the given path, streaming it to disk.
+  ///
+  /// If a previous download to the same path was interrupted, it is resumed with a `Range` request,
+  /// sending the `ETag` or `Last-Modified` validator of the first response on the `If-Range` header.
+  /// The validator is kept on a `.download` file next to the file until the download is done.
+  /// When the server doesn't support it or the file changed, the file is downloaded again from the start.
+  ///
+  /// The file isn't touched if the response status isn't successful.
+  ///
+  /// The `on_progress` callback is invoked with the downloaded bytes after each chunk is written.
+  ///
+  /// # Examples
+  /// ```no_run
+  /// use tauri::api::http::{ClientBuilder, HttpRequestBuilder};
+  /// async fn run() {
+  ///   let client = ClientBuilder::new().build().unwrap();
+  ///   let request = HttpRequestBuilder::new("GET", "http://example.com/file.zip");
+  ///   client
+  ///     .download(request, "/tmp/file.zip", |progress| {
+  ///       println!("downloaded {} of {:?}", progress.downloaded, progress.total);
+  ///     })
+  ///     .await
+  ///     .unwrap();
+  /// }
+  /// ```
+  pub async fn download<F: FnMut(DownloadProgress)>(
+    &self,
+    mut request: HttpRequestBuilder,
+    path: impl AsRef<Path>,
+    mut on_progress: F,
+  ) -> crate::api::Result<()> {
+    let path = path.as_ref();
+    let mut validator_path = path.as_os_str().to_owned();
+    validator_path.push(".download");
+    let validator_path = PathBuf::from(validator_path);
+
+    // only the interrupted downloads are resumed, as the validator proves the file is a prefix of the response
+    let existing = match std::fs::read_to_string(&validator_path) {
+      Ok(validator) => {
+        let existing = std::fs::metadata(path).map(|m| m.len()).unwrap_or(0);
+        if existing > 0 {
+          let headers = request.headers.get_or_insert_with(HashMap::new);
+          headers.insert("Range".into(), format!("bytes={}-", existing));
+          headers.insert("If-Range".into(), validator);
+        }
+        existing
+      }
+      Err(_) => 0,
+    };
+
+    let response = self.execute(request).await?;
+    let status = response.1.status().as_u16();
+    let (range_start, complete_length) = response
+      .header(http::header::CONTENT_RANGE.as_str())
+      .map(parse_content_range)
+      .unwrap_or((None, None));
+
+    if status == 416 && existing > 0 && complete_length == Some(existing) {
+      // the interrupted download already had all the bytes
+      let _ = std::fs::remove_file(&validator_path);
+      on_progress(DownloadProgress {
+        downloaded: existing,
+        total: Some(existing),
+      });
+      return Ok(());
+    }
+    if !response.1.status().is_success() || (status == 206 && range_start != Some(existing)) {
+      return Err(crate::api::Error::DownloadStatus(status));
+    }
+
+    let resumed = status == 206;
+    if !resumed {
+      match response.validator() {
+        Some(validator) => std::fs::write(&validator_path, validator)?,
+        None => {
+          let _ = std::fs::remove_file(&validator_path);
+        }
+      }
+    }
+    response
+      .write_to(path, if resumed { existing } else { 0 }, on_progress)
+      .await?;
+    let _ = std::fs::remove_file(&validator_path);
+    Ok(())
+  }
+}
+
+/// The progress of a download.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+#[non_exhaustive]
+pub struct DownloadProgress {
+  /// The number of bytes downloaded, including the bytes of a resumed download.
+  pub downloaded: u64,
+  /// The total size of the file, if known.
+  pub total: Option<u64>,
+}
+
+/// Parses a `Content-Range` header, e.g. `bytes 100-199/200` or `bytes */200`,
+/// returning the position of the first byte and the complete length.
+fn parse_content_range(value: &str) -> (Option<u64>, Option<u64>) {
+  let range = value.trim().strip_prefix("bytes ").unwrap_or_default();
+  let mut parts = range.splitn(2, '/');
+  let start = parts
+    .next()
+    .and_then(|positions| positions.split('-').next())
+    .and_then(|start| start.parse().ok());
+  let complete_length = parts.next().and_then(|length| length.parse().ok());
+  (start, complete_length)
+}
+
 #[derive(Serialize_repr, Deserialize_repr, Clone, Debug)]
 #[repr(u16)]
 #[non_exhaustive]
@@ -229,11 +439,238 @@ impl FormBody {
   }
 }
 
+/// The progress of an upload.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+#[non_exhaustive]
+pub struct UploadProgress {
+  /// The number of bytes sent.
+  pub uploaded: u64,
+  /// The total size of the body.
+  pub total: u64,
+}
+
+type UploadProgressHandler = Arc<dyn Fn(UploadProgress) + Send + Sync>;
+
+/// A part of a [`MultipartBody`].
+#[derive(Deserialize)]
+#[serde(tag = "type", content = "payload")]
+#[non_exhaustive]
+pub enum MultipartPart {
+  /// A text value.
+  Text(String),
+  /// A byte array value, sent as an `application/octet-stream` file.
+  Bytes(Vec<u8>),
+  /// A file streamed from disk.
+  ///
+  /// Only available from Rust, so the webview can't upload any file.
+  #[serde(skip)]
+  File {
+    /// The file path.
+    path: PathBuf,
+    /// The MIME type of the file. Defaults to `application/octet-stream`.
+    mime: Option<String>,
+  },
+}
+
+/// A `multipart/form-data` body, streaming its file parts from disk.
+///
+/// # Examples
+/// ```no_run
+/// use tauri::api::http::{Body, ClientBuilder, HttpRequestBuilder, MultipartBody};
+/// async fn run() {
+///   let client = ClientBuilder::new().build().unwrap();
+///   let body = MultipartBody::new()
+///     .text("title", "Holidays")
+///     .file("attachment", "/tmp/photo.png", Some("image/png".into()))
+///     .on_progress(|progress| println!("sent {} of {}", progress.uploaded, progress.total));
+///   let request = HttpRequestBuilder::new("POST", "http://example.com/upload")
+///     .body(Body::Multipart(body));
+///   client.send(request).await.unwrap();
+/// }
+/// ```
+#[derive(Default, Deserialize)]
+pub struct MultipartBody {
+  parts: Vec<(String, MultipartPart)>,
+  #[serde(skip)]
+  on_progress: Option<UploadProgressHandler>,
+}
+
+impl MultipartBody {
+  /// Creates an empty multipart body.
+  pub fn new() -> Self {
+    Default::default()
+  }
+
+  /// Adds a part to the body.
+  pub fn part(mut self, name: impl Into<String>, part: MultipartPart) -> Self {
+    self.parts.push((name.into(), part));
+    self
+  }
+
+  /// Adds a text part to the body.
+  pub fn text(self, name: impl Into<String>, value: impl Into<String>) -> Self {
+    self.part(name, MultipartPart::Text(value.into()))
+  }
+
+  /// Adds a byte array part to the body.
+  pub fn bytes(self, name: impl Into<String>, bytes: Vec<u8>) -> Self {
+    self.part(name, MultipartPart::Bytes(bytes))
+  }
+
+  /// Adds a file part to the body. The file is only read when the request is sent.
+  pub fn file(
+    self,
+    name: impl Into<String>,
+    path: impl Into<PathBuf>,
+    mime: Option<String>,
+  ) -> Self {
+    self.part(
+      name,
+      MultipartPart::File {
+        path: path.into(),
+        mime,
+      },
+    )
+  }
+
+  /// Sets the callback invoked with the upload progress while the body is sent.
+  pub fn on_progress<F: Fn(UploadProgress) + Send + Sync + 'static>(mut self, f: F) -> Self {
+    self.on_progress.replace(Arc::new(f));
+    self
+  }
+
+  fn into_reader(self) -> crate::api::Result<MultipartReader> {
+    let boundary = format!("tauri-boundary-{:016x}", rand::random::<u64>());
+    let mut segments = VecDeque::new();
+
+    for (name, part) in self.parts {
+      let name = escape_disposition_value(&name);
+      let (header, content) = match part {
+        MultipartPart::Text(text) => (
+          format!("Content-Disposition: form-data; name=\"{}\"", name),
+          Either::Left(text.into_bytes()),
+        ),
+        MultipartPart::Bytes(bytes) => (
+          format!(
+            "Content-Disposition: form-data; name=\"{}\"; filename=\"{}\"\r\nContent-Type: application/octet-stream",
+            name, name
+          ),
+          Either::Left(bytes),
+        ),
+        MultipartPart::File { path, mime } => {
+          let file_name = path
+            .file_name()
+            .map(|n| escape_disposition_value(&n.to_string_lossy()))
+            .unwrap_or_else(|| name.clone());
+          (
+            format!(
+              "Content-Disposition: form-data; name=\"{}\"; filename=\"{}\"\r\nContent-Type: {}",
+              name,
+              file_name,
+              mime.as_deref().unwrap_or("application/octet-stream")
+            ),
+            Either::Right(path),
+          )
+        }
+      };
+      segments.push_back(Either::Left(
+        format!("--{}\r\n{}\r\n\r\n", boundary, header).into_bytes(),
+      ));
+      segments.push_back(content);
+      segments.push_back(Either::Left(b"\r\n".to_vec()));
+    }
+    segments.push_back(Either::Left(format!("--{}--\r\n", boundary).into_bytes()));
+
+    let mut total = 0;
+    for segment in &segments {
+      total += match segment {
+        Either::Left(bytes) => bytes.len() as u64,
+        Either::Right(path) => std::fs::metadata(path)?.len(),
+      };
+    }
+
+    Ok(MultipartReader {
+      boundary,
+      segments,
+      current: None,
+      total,
+      uploaded: 0,
+      on_progress: self.on_progress,
+    })
+  }
+}
+
+/// Reads the encoded multipart body, opening the files as they are reached.
+/// Escapes a quoted `Content-Disposition` parameter, percent-encoding the quotes and line breaks.
+fn escape_disposition_value(value: &str) -> String {
+  value
+    .replace('"', "%22")
+    .replace('\r', "%0D")
+    .replace('\n', "%0A")
+}
+
+struct MultipartReader {
+  boundary: String,
+  segments: VecDeque<Either<Vec<u8>, PathBuf>>,
+  current: Option<Either<Cursor<Vec<u8>>, File>>,
+  total: u64,
+  uploaded: u64,
+  on_progress: Option<UploadProgressHandler>,
+}
+
+impl MultipartReader {
+  fn content_type(&self) -> String {
+    format!("multipart/form-data; boundary={}", self.boundary)
+  }
+}
+
+impl Read for MultipartReader {
+  fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+    loop {
+      if let Some(current) = &mut self.current {
+        let read = current.read(buf)?;
+        if read == 0 {
+          self.current = None;
+          continue;
+        }
+        self.uploaded += read as u64;
+        if let Some(on_progress) = &self.on_progress {
+          on_progress(UploadProgress {
+            uploaded: self.uploaded,
+            total: self.total,
+          });
+        }
+        return Ok(read);
+      }
+      self.current = match self.segments.pop_front() {
+        Some(Either::Left(bytes)) => Some(Either::Left(Cursor::new(bytes))),
+        Some(Either::Right(path)) => Some(Either::Right(File::open(path)?)),
+        None => return Ok(0),
+      };
+    }
+  }
+}
+
+#[cfg(not(feature = "reqwest-client"))]
+impl attohttpc::body::Body for MultipartReader {
+  fn kind(&mut self) -> std::io::Result<attohttpc::body::BodyKind> {
+    Ok(attohttpc::body::BodyKind::KnownLength(self.total))
+  }
+
+  fn write<W: Write>(&mut self, mut writer: W) -> std::io::Result<()> {
+    std::io::copy(self, &mut writer)?;
+    Ok(())
+  }
+}
+
 /// A body for the request.
 #[derive(Deserialize)]
 #[serde(tag = "type", content = "payload")]
 #[non_exhaustive]
 pub enum Body {
+  /// A `multipart/form-data` body, streaming its file parts from disk.
+  Multipart(MultipartBody),
   /// A multipart formdata body.
   Form(FormBody),
   /// A JSON body.
@@ -336,13 +773,101 @@ pub struct Response(ResponseType, reqwest::Response);
 pub struct Response(ResponseType, attohttpc::Response, String);
 
 impl Response {
+  #[cfg(feature = "reqwest-client")]
+  fn error_for_status(self) -> crate::api::Result<Self> {
+    Ok(Self(self.0, self.1.error_for_status()?))
+  }
+
+  #[cfg(not(feature = "reqwest-client"))]
+  fn error_for_status(self) -> crate::api::Result<Self> {
+    Ok(Self(self.0, self.1.error_for_status()?, self.2))
+  }
+
+  fn header(&self, name: &str) -> Option<&str> {
+    self
+      .1
+      .headers()
+      .get(name)
+      .and_then(|value| value.to_str().ok())
+  }
+
+  /// The strong validator of the response, to resume the download with the `If-Range` header.
+  fn validator(&self) -> Option<String> {
+    self
+      .header(http::header::ETAG.as_str())
+      .filter(|etag| !etag.starts_with("W/"))
+      .or_else(|| self.header(http::header::LAST_MODIFIED.as_str()))
+      .map(Into::into)
+  }
+
+  /// Streams the response body to the file, appending to it if the download is resumed at the `offset`.
+  async fn write_to<F: FnMut(DownloadProgress)>(
+    self,
+    path: &Path,
+    offset: u64,
+    mut on_progress: F,
+  ) -> crate::api::Result<()> {
+    let content_length = self
+      .header(http::header::CONTENT_LENGTH.as_str())
+      .and_then(|length| length.parse::<u64>().ok());
+
+    let mut file = if offset > 0 {
+      OpenOptions::new().append(true).open(path)?
+    } else {
+      File::create(path)?
+    };
+    let mut downloaded = offset;
+    let total = content_length.map(|length| length + offset);
+
+    #[cfg(feature = "reqwest-client")]
+    {
+      let mut response = self.1;
+      while let Some(chunk) = response.chunk().await? {
+        file.write_all(&chunk)?;
+        downloaded += chunk.len() as u64;
+        on_progress(DownloadProgress { downloaded, total });
+      }
+      file.flush()?;
+    }
+
+    #[cfg(not(feature = "reqwest-client"))]
+    {
+      // the body is read on the blocking thread pool, sending the progress back to this task
+      let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel();
+      let (_, _, mut reader) = self.1.split();
+      let task = crate::async_runtime::spawn_blocking(move || -> crate::api::Result<()> {
+        let mut buffer = [0; 8192];
+        loop {
+          let read = reader.read(&mut buffer)?;
+          if read == 0 {
+            break;
+          }
+          file.write_all(&buffer[..read])?;
+          downloaded += read as u64;
+          let _ = tx.send(downloaded);
+        }
+        file.flush()?;
+        Ok(())
+      });
+      while let Some(downloaded) = rx.recv().await {
+        on_progress(DownloadProgress { downloaded, total });
+      }
+      task.await?;
+    }
+
+    Ok(())
+  }
+
   /// Reads the response as raw bytes.
   pub async fn bytes(self) -> crate::api::Result<RawResponse> {
     let status = self.1.status().as_u16();
     #[cfg(feature = "reqwest-client")]
     let data = self.1.bytes().await?.to_vec();
     #[cfg(not(feature = "reqwest-client"))]
-    let data = self.1.bytes()?;
+    let data = {
+      let response = self.1;
+      crate::async_runtime::spawn_blocking(move || response.bytes()).await?
+    };
     Ok(RawResponse { status, data })
   }
 
@@ -367,10 +892,16 @@ impl Response {
     };
 
     #[cfg(not(feature = "reqwest-client"))]
-    let data = match self.0 {
-      ResponseType::Json => self.1.json()?,
-      ResponseType::Text => Value::String(self.1.text()?),
-      ResponseType::Binary => Value::String(serde_json::to_string(&self.1.bytes()?)?),
+    let data = {
+      let (response_type, response) = (self.0, self.1);
+      crate::async_runtime::spawn_blocking(move || -> crate::api::Result<Value> {
+        Ok(match response_type {
+          ResponseType::Json => response.json()?,
+          ResponseType::Text => Value::String(response.text()?),
+          ResponseType::Binary => Value::String(serde_json::to_string(&response.bytes()?)?),
+        })
+      })
+      .await?
     };
 
     Ok(ResponseData {
@@ -405,3 +936,28 @@ pub struct ResponseData {
   /// Response data.
   pub data: Value,
 }
+
+#[cfg(test)]
+mod test {
+  use super::{escape_disposition_value, parse_content_range};
+
+  #[test]
+  fn content_range() {
+    assert_eq!(
+      parse_content_range("bytes 100-199/200"),
+      (Some(100), Some(200))
+    );
+    assert_eq!(parse_content_range("bytes 0-99/*"), (Some(0), None));
+    assert_eq!(parse_content_range("bytes */200"), (None, Some(200)));
+    assert_eq!(parse_content_range("items 0-1/2"), (None, None));
+  }
+
+  #[test]
+  fn disposition_value() {
+    assert_eq!(escape_disposition_value("photo.png"), "photo.png");
+    assert_eq!(
+      escape_disposition_value("a\"\r\nContent-Type: text/html"),
+      "a%22%0D%0AContent-Type: text/html"
+    );
+  }
+}
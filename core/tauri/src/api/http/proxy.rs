@@ -0,0 +1,635 @@
+// Copyright 2019-2021 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+use crate::api::config::ProxyConfig;
+use once_cell::sync::OnceCell;
+
+/// The proxy of the HTTP requests.
+///
+/// The clients built without a proxy use [`Proxy::system`].
+///
+/// # Examples
+/// ```no_run
+/// use tauri::api::http::{ClientBuilder, Proxy};
+/// let client = ClientBuilder::new()
+///   .proxy(Proxy::new("http://proxy.example.com:8080").no_proxy(vec!["localhost".into()]))
+///   .build()
+///   .unwrap();
+/// ```
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Proxy {
+  /// The proxy URL of the `http` requests.
+  pub http: Option<String>,
+  /// The proxy URL of the `https` requests.
+  pub https: Option<String>,
+  /// The hosts reached without the proxy.
+  ///
+  /// Supports domains, matching their subdomains too, `*.` and `.` domain prefixes,
+  /// `<local>` for the hosts without a dot and `*` for all hosts.
+  pub no_proxy: Vec<String>,
+  /// The URL of the proxy auto-config (PAC) script choosing the proxy of each request,
+  /// preferred over the `http` and `https` proxies.
+  ///
+  /// Only supported on Windows and macOS.
+  pub auto_config_url: Option<String>,
+  /// Whether the proxy auto-config script is discovered on the network (WPAD),
+  /// preferred over the `http` and `https` proxies.
+  ///
+  /// Only supported on Windows and macOS.
+  pub auto_detect: bool,
+}
+
+impl Proxy {
+  /// Creates a proxy used for both the `http` and `https` requests.
+  pub fn new(url: impl Into<String>) -> Self {
+    let url = url.into();
+    Self {
+      http: Some(url.clone()),
+      https: Some(url),
+      ..Default::default()
+    }
+  }
+
+  /// Connects directly, ignoring the system proxy.
+  pub fn direct() -> Self {
+    Self::default()
+  }
+
+  /// Sets the hosts reached without the proxy.
+  pub fn no_proxy(mut self, hosts: Vec<String>) -> Self {
+    self.no_proxy = hosts;
+    self
+  }
+
+  /// The proxy configured with the `tauri > proxy` object of `tauri.conf.json`.
+  ///
+  /// Returns `None` when the system proxy should be used.
+  pub fn from_config(config: &ProxyConfig) -> Option<Self> {
+    let proxy = match &config.url {
+      Some(url) => Self::new(url),
+      None if config.system => return None,
+      None => Self::direct(),
+    };
+    Some(proxy.no_proxy(config.no_proxy.clone().unwrap_or_default()))
+  }
+
+  /// The system proxy, if any.
+  ///
+  /// It's read from the `HTTP_PROXY`, `HTTPS_PROXY`, `ALL_PROXY` and `NO_PROXY` environment variables (or their lowercase version),
+  /// then from the Internet Options of the current user with WinHTTP on Windows
+  /// and from the network settings with SystemConfiguration on macOS,
+  /// including their auto-config script, auto-discovery and bypass settings.
+  ///
+  /// The system proxy is read once and cached.
+  pub fn system() -> Option<Self> {
+    static SYSTEM_PROXY: OnceCell<Option<Proxy>> = OnceCell::new();
+    SYSTEM_PROXY
+      .get_or_init(|| from_env().or_else(platform::from_system_settings))
+      .clone()
+  }
+
+  /// The proxy URL to use for the given request URL, or `None` if the request connects directly.
+  ///
+  /// When an auto-config script is set, it is downloaded and run to choose the proxy,
+  /// falling back to the `http` and `https` proxies if it fails.
+  pub fn url_for(&self, url: &str) -> Option<String> {
+    let parsed = url::Url::parse(url).ok()?;
+    if let Some(host) = parsed.host_str() {
+      if self.no_proxy.iter().any(|entry| bypasses(entry, host)) {
+        return None;
+      }
+    }
+    let auto_config = if self.auto_config_url.is_some() || self.auto_detect {
+      platform::resolve_auto_config(self, url)
+    } else {
+      None
+    };
+    let proxy = match auto_config {
+      Some(proxy) => proxy?,
+      None => match parsed.scheme() {
+        "https" => self.https.clone(),
+        _ => self.http.clone(),
+      }?,
+    };
+    // proxies are commonly set without the scheme, e.g. `proxy.example.com:8080`
+    if proxy.contains("://") {
+      Some(proxy)
+    } else {
+      Some(format!("http://{}", proxy))
+    }
+  }
+}
+
+// Whether the `no_proxy` entry matches the host
+fn bypasses(entry: &str, host: &str) -> bool {
+  let entry = entry.trim().to_lowercase();
+  let host = host.to_lowercase();
+  match entry.as_str() {
+    "" => false,
+    "*" => true,
+    "<local>" => !host.contains('.'),
+    _ => {
+      let domain = entry.trim_start_matches('*').trim_start_matches('.');
+      host == domain || host.ends_with(&format!(".{}", domain))
+    }
+  }
+}
+
+fn from_env() -> Option<Proxy> {
+  let var = |name: &str| {
+    std::env::var(name.to_uppercase())
+      .or_else(|_| std::env::var(name))
+      .ok()
+      .filter(|value| !value.trim().is_empty())
+  };
+  let all = var("all_proxy");
+  let proxy = Proxy {
+    http: var("http_proxy").or_else(|| all.clone()),
+    https: var("https_proxy").or(all),
+    no_proxy: var("no_proxy")
+      .map(|hosts| {
+        hosts
+          .split(',')
+          .map(|host| host.trim().to_string())
+          .collect()
+      })
+      .unwrap_or_default(),
+    ..Default::default()
+  };
+  if proxy.http.is_some() || proxy.https.is_some() {
+    Some(proxy)
+  } else {
+    None
+  }
+}
+
+// Parses a list of proxies, e.g. `http=proxy.example.com:8080;https=proxy.example.com:8443` or `proxy.example.com:8080`
+#[cfg(windows)]
+fn parse_proxy_server(server: &str) -> Proxy {
+  let mut proxy = Proxy::default();
+  for entry in server
+    .split(|c: char| c == ';' || c.is_whitespace())
+    .filter(|entry| !entry.is_empty())
+  {
+    let mut parts = entry.splitn(2, '=');
+    match (parts.next(), parts.next()) {
+      (Some("http"), Some(url)) => proxy.http = Some(url.into()),
+      (Some("https"), Some(url)) => proxy.https = Some(url.into()),
+      (Some(_), Some(_)) => {}
+      (Some(url), None) => {
+        proxy.http.get_or_insert_with(|| url.into());
+        proxy.https.get_or_insert_with(|| url.into());
+      }
+      (None, _) => {}
+    }
+  }
+  proxy
+}
+
+// The Windows proxy settings, read and resolved with WinHTTP
+#[cfg(windows)]
+mod platform {
+  use super::{parse_proxy_server, Proxy};
+  use once_cell::sync::OnceCell;
+  use winapi::{
+    shared::minwindef::{FALSE, TRUE},
+    um::{
+      winbase::GlobalFree,
+      winhttp::{
+        WinHttpGetIEProxyConfigForCurrentUser, WinHttpGetProxyForUrl, WinHttpOpen, HINTERNET,
+        WINHTTP_ACCESS_TYPE_NAMED_PROXY, WINHTTP_ACCESS_TYPE_NO_PROXY,
+        WINHTTP_AUTOPROXY_AUTO_DETECT, WINHTTP_AUTOPROXY_CONFIG_URL, WINHTTP_AUTOPROXY_OPTIONS,
+        WINHTTP_AUTO_DETECT_TYPE_DHCP, WINHTTP_AUTO_DETECT_TYPE_DNS_A,
+        WINHTTP_CURRENT_USER_IE_PROXY_CONFIG, WINHTTP_PROXY_INFO,
+      },
+      winnt::LPWSTR,
+    },
+  };
+
+  use std::{ffi::OsStr, iter::once, os::windows::ffi::OsStrExt, ptr::null};
+
+  // The WinHTTP session resolving the auto-config proxies, which caches the downloaded scripts
+  struct Session(HINTERNET);
+
+  // WinHTTP handles can be used from any thread
+  unsafe impl Send for Session {}
+  unsafe impl Sync for Session {}
+
+  fn wide(value: &str) -> Vec<u16> {
+    OsStr::new(value).encode_wide().chain(once(0)).collect()
+  }
+
+  // Reads and frees a string allocated by WinHTTP
+  unsafe fn take_string(ptr: LPWSTR) -> Option<String> {
+    if ptr.is_null() {
+      return None;
+    }
+    let mut len = 0;
+    while *ptr.add(len) != 0 {
+      len += 1;
+    }
+    let value = String::from_utf16_lossy(std::slice::from_raw_parts(ptr, len));
+    GlobalFree(ptr as _);
+    Some(value).filter(|value| !value.trim().is_empty())
+  }
+
+  /// Reads the Internet Options proxy of the current user, including the auto-config settings and the bypass list.
+  pub fn from_system_settings() -> Option<Proxy> {
+    let mut config: WINHTTP_CURRENT_USER_IE_PROXY_CONFIG = unsafe { std::mem::zeroed() };
+    if unsafe { WinHttpGetIEProxyConfigForCurrentUser(&mut config) } == FALSE {
+      return None;
+    }
+    let auto_config_url = unsafe { take_string(config.lpszAutoConfigUrl) };
+    let server = unsafe { take_string(config.lpszProxy) };
+    let bypass = unsafe { take_string(config.lpszProxyBypass) };
+
+    let mut proxy = server
+      .map(|server| parse_proxy_server(&server))
+      .unwrap_or_default();
+    proxy.no_proxy = bypass
+      .map(|hosts| {
+        hosts
+          .split(|c: char| c == ';' || c.is_whitespace())
+          .filter(|host| !host.is_empty())
+          .map(Into::into)
+          .collect()
+      })
+      .unwrap_or_default();
+    proxy.auto_config_url = auto_config_url;
+    proxy.auto_detect = config.fAutoDetect == TRUE;
+
+    if proxy.http.is_some()
+      || proxy.https.is_some()
+      || proxy.auto_config_url.is_some()
+      || proxy.auto_detect
+    {
+      Some(proxy)
+    } else {
+      None
+    }
+  }
+
+  /// Runs the auto-config script for the URL, returning `None` if it can't be resolved.
+  pub fn resolve_auto_config(proxy: &Proxy, url: &str) -> Option<Option<String>> {
+    static SESSION: OnceCell<Option<Session>> = OnceCell::new();
+    let session = SESSION
+      .get_or_init(|| {
+        let session = unsafe {
+          WinHttpOpen(
+            wide("tauri").as_ptr(),
+            WINHTTP_ACCESS_TYPE_NO_PROXY,
+            null(),
+            null(),
+            0,
+          )
+        };
+        if session.is_null() {
+          None
+        } else {
+          Some(Session(session))
+        }
+      })
+      .as_ref()?;
+
+    let auto_config_url = proxy.auto_config_url.as_deref().map(wide);
+    let mut options: WINHTTP_AUTOPROXY_OPTIONS = unsafe { std::mem::zeroed() };
+    if let Some(auto_config_url) = &auto_config_url {
+      options.dwFlags |= WINHTTP_AUTOPROXY_CONFIG_URL;
+      options.lpszAutoConfigUrl = auto_config_url.as_ptr();
+    }
+    if proxy.auto_detect {
+      options.dwFlags |= WINHTTP_AUTOPROXY_AUTO_DETECT;
+      options.dwAutoDetectFlags = WINHTTP_AUTO_DETECT_TYPE_DHCP | WINHTTP_AUTO_DETECT_TYPE_DNS_A;
+    }
+    options.fAutoLogonIfChallenged = TRUE;
+
+    let mut info: WINHTTP_PROXY_INFO = unsafe { std::mem::zeroed() };
+    let url = wide(url);
+    if unsafe { WinHttpGetProxyForUrl(session.0, url.as_ptr(), &mut options, &mut info) } == FALSE {
+      return None;
+    }
+    let server = unsafe { take_string(info.lpszProxy) };
+    let _ = unsafe { take_string(info.lpszProxyBypass) };
+    if info.dwAccessType == WINHTTP_ACCESS_TYPE_NAMED_PROXY {
+      // the script returns the proxies to try in order, we use the first one
+      Some(server.and_then(|server| parse_proxy_server(&server).http))
+    } else {
+      Some(None)
+    }
+  }
+}
+
+// The macOS proxy settings, read with SystemConfiguration and resolved with CFNetwork
+#[cfg(target_os = "macos")]
+mod platform {
+  use super::Proxy;
+  use core_foundation::{
+    array::{CFArray, CFArrayRef},
+    base::{CFIndex, CFType, TCFType},
+    dictionary::{CFDictionary, CFDictionaryRef},
+    error::CFErrorRef,
+    number::CFNumber,
+    runloop::{CFRunLoop, CFRunLoopSource, CFRunLoopSourceInvalidate, CFRunLoopSourceRef},
+    string::{CFString, CFStringRef},
+    url::{CFURLCreateWithString, CFURLRef, CFURL},
+  };
+
+  use std::{
+    os::raw::c_void,
+    ptr::null,
+    time::{Duration, Instant},
+  };
+
+  type ResultCallback = extern "C" fn(client: *mut c_void, proxies: CFArrayRef, error: CFErrorRef);
+
+  #[repr(C)]
+  struct StreamClientContext {
+    version: CFIndex,
+    info: *mut c_void,
+    retain: *const c_void,
+    release: *const c_void,
+    copy_description: *const c_void,
+  }
+
+  #[link(name = "SystemConfiguration", kind = "framework")]
+  extern "C" {
+    fn SCDynamicStoreCopyProxies(store: *const c_void) -> CFDictionaryRef;
+  }
+
+  #[link(name = "CFNetwork", kind = "framework")]
+  extern "C" {
+    static kCFProxyTypeKey: CFStringRef;
+    static kCFProxyTypeNone: CFStringRef;
+    static kCFProxyTypeHTTP: CFStringRef;
+    static kCFProxyTypeHTTPS: CFStringRef;
+    static kCFProxyHostNameKey: CFStringRef;
+    static kCFProxyPortNumberKey: CFStringRef;
+    fn CFNetworkExecuteProxyAutoConfigurationURL(
+      auto_config_url: CFURLRef,
+      target_url: CFURLRef,
+      callback: ResultCallback,
+      context: *mut StreamClientContext,
+    ) -> CFRunLoopSourceRef;
+  }
+
+  /// The time given to the auto-config script to download and run.
+  const AUTO_CONFIG_TIMEOUT: Duration = Duration::from_secs(10);
+  /// The WPAD script URL, discovered through the DNS.
+  const WPAD_URL: &str = "http://wpad/wpad.dat";
+
+  fn array(value: &CFType) -> Option<CFArray<CFType>> {
+    if value.instance_of::<CFArray>() {
+      Some(unsafe { CFArray::wrap_under_get_rule(value.as_CFTypeRef() as CFArrayRef) })
+    } else {
+      None
+    }
+  }
+
+  fn dictionary(value: &CFType) -> Option<CFDictionary<CFString, CFType>> {
+    if value.instance_of::<CFDictionary>() {
+      Some(unsafe { CFDictionary::wrap_under_get_rule(value.as_CFTypeRef() as CFDictionaryRef) })
+    } else {
+      None
+    }
+  }
+
+  fn string(settings: &CFDictionary<CFString, CFType>, key: &str) -> Option<String> {
+    settings
+      .find(CFString::new(key))
+      .and_then(|value| value.downcast::<CFString>())
+      .map(|value| value.to_string())
+  }
+
+  fn number(settings: &CFDictionary<CFString, CFType>, key: &str) -> Option<i64> {
+    settings
+      .find(CFString::new(key))
+      .and_then(|value| value.downcast::<CFNumber>())
+      .and_then(|value| value.to_i64())
+  }
+
+  /// Reads the proxy of the network settings, including the auto-config settings and the exceptions list.
+  pub fn from_system_settings() -> Option<Proxy> {
+    let settings = unsafe { SCDynamicStoreCopyProxies(null()) };
+    if settings.is_null() {
+      return None;
+    }
+    let settings: CFDictionary<CFString, CFType> =
+      unsafe { CFDictionary::wrap_under_create_rule(settings) };
+
+    let proxy_for = |protocol: &str| {
+      if number(&settings, &format!("{}Enable", protocol)) != Some(1) {
+        return None;
+      }
+      let host = string(&settings, &format!("{}Proxy", protocol))?;
+      Some(match number(&settings, &format!("{}Port", protocol)) {
+        Some(port) => format!("{}:{}", host, port),
+        None => host,
+      })
+    };
+    let mut no_proxy: Vec<String> = settings
+      .find(CFString::new("ExceptionsList"))
+      .and_then(|value| array(&value))
+      .map(|hosts| {
+        hosts
+          .iter()
+          .filter_map(|host| host.downcast::<CFString>())
+          .map(|host| host.to_string())
+          .collect()
+      })
+      .unwrap_or_default();
+    if number(&settings, "ExcludeSimpleHostnames") == Some(1) {
+      no_proxy.push("<local>".into());
+    }
+
+    let proxy = Proxy {
+      http: proxy_for("HTTP"),
+      https: proxy_for("HTTPS"),
+      no_proxy,
+      auto_config_url: if number(&settings, "ProxyAutoConfigEnable") == Some(1) {
+        string(&settings, "ProxyAutoConfigURLString")
+      } else {
+        None
+      },
+      auto_detect: number(&settings, "ProxyAutoDiscoveryEnable") == Some(1),
+    };
+    if proxy.http.is_some()
+      || proxy.https.is_some()
+      || proxy.auto_config_url.is_some()
+      || proxy.auto_detect
+    {
+      Some(proxy)
+    } else {
+      None
+    }
+  }
+
+  fn url(value: &str) -> Option<CFURL> {
+    let url =
+      unsafe { CFURLCreateWithString(null(), CFString::new(value).as_concrete_TypeRef(), null()) };
+    if url.is_null() {
+      None
+    } else {
+      Some(unsafe { CFURL::wrap_under_create_rule(url) })
+    }
+  }
+
+  extern "C" fn on_result(client: *mut c_void, proxies: CFArrayRef, _error: CFErrorRef) {
+    let result = unsafe { &mut *(client as *mut Option<Option<CFArray<CFType>>>) };
+    *result = Some(if proxies.is_null() {
+      None
+    } else {
+      Some(unsafe { CFArray::wrap_under_get_rule(proxies) })
+    });
+  }
+
+  /// Runs the auto-config script for the URL, returning `None` if it can't be resolved.
+  ///
+  /// CFNetwork runs the script asynchronously, so it is driven on the run loop of the current thread.
+  pub fn resolve_auto_config(proxy: &Proxy, target: &str) -> Option<Option<String>> {
+    let auto_config_url = url(
+      proxy
+        .auto_config_url
+        .as_deref()
+        .or_else(|| Some(WPAD_URL).filter(|_| proxy.auto_detect))?,
+    )?;
+    let target = url(target)?;
+
+    let mut result: Option<Option<CFArray<CFType>>> = None;
+    let mut context = StreamClientContext {
+      version: 0,
+      info: &mut result as *mut _ as *mut c_void,
+      retain: null(),
+      release: null(),
+      copy_description: null(),
+    };
+    let source = unsafe {
+      CFRunLoopSource::wrap_under_create_rule(CFNetworkExecuteProxyAutoConfigurationURL(
+        auto_config_url.as_concrete_TypeRef(),
+        target.as_concrete_TypeRef(),
+        on_result,
+        &mut context,
+      ))
+    };
+    // a private mode, so the other sources of the run loop aren't run
+    let mode = CFString::new("studio.tauri.proxy");
+    let run_loop = CFRunLoop::get_current();
+    run_loop.add_source(&source, mode.as_concrete_TypeRef());
+    let started = Instant::now();
+    while result.is_none() {
+      match AUTO_CONFIG_TIMEOUT.checked_sub(started.elapsed()) {
+        Some(remaining) => {
+          CFRunLoop::run_in_mode(mode.as_concrete_TypeRef(), remaining, true);
+        }
+        None => break,
+      }
+    }
+    // the callback must not run once `result` is dropped
+    unsafe { CFRunLoopSourceInvalidate(source.as_concrete_TypeRef()) };
+    run_loop.remove_source(&source, mode.as_concrete_TypeRef());
+
+    let proxies = result??;
+    let key = |key: CFStringRef| unsafe { CFString::wrap_under_get_rule(key) };
+    // the script returns the proxies to try in order, we use the first one we support
+    for entry in proxies.iter() {
+      let entry = match dictionary(&entry) {
+        Some(entry) => entry,
+        None => continue,
+      };
+      let kind = match entry
+        .find(key(unsafe { kCFProxyTypeKey }))
+        .and_then(|kind| kind.downcast::<CFString>())
+      {
+        Some(kind) => kind,
+        None => continue,
+      };
+      if kind == key(unsafe { kCFProxyTypeNone }) {
+        return Some(None);
+      }
+      if kind == key(unsafe { kCFProxyTypeHTTP }) || kind == key(unsafe { kCFProxyTypeHTTPS }) {
+        let host = entry
+          .find(key(unsafe { kCFProxyHostNameKey }))
+          .and_then(|host| host.downcast::<CFString>())?;
+        let port = entry
+          .find(key(unsafe { kCFProxyPortNumberKey }))
+          .and_then(|port| port.downcast::<CFNumber>())
+          .and_then(|port| port.to_i64());
+        return Some(Some(match port {
+          Some(port) => format!("{}:{}", host, port),
+          None => host.to_string(),
+        }));
+      }
+    }
+    None
+  }
+}
+
+#[cfg(not(any(windows, target_os = "macos")))]
+mod platform {
+  use super::Proxy;
+
+  pub fn from_system_settings() -> Option<Proxy> {
+    None
+  }
+
+  pub fn resolve_auto_config(_proxy: &Proxy, _url: &str) -> Option<Option<String>> {
+    None
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+
+  #[test]
+  fn url_for() {
+    let proxy = Proxy::new("proxy.example.com:8080").no_proxy(vec![
+      "localhost".into(),
+      "*.internal.com".into(),
+      ".corp.com".into(),
+    ]);
+    assert_eq!(
+      proxy.url_for("https://tauri.studio/releases"),
+      Some("http://proxy.example.com:8080".into())
+    );
+    assert_eq!(proxy.url_for("http://localhost:3000"), None);
+    assert_eq!(proxy.url_for("https://api.internal.com"), None);
+    assert_eq!(proxy.url_for("https://corp.com"), None);
+    assert_eq!(proxy.url_for("https://www.corp.com"), None);
+    assert!(proxy.url_for("https://notcorp.com").is_some());
+
+    assert_eq!(Proxy::direct().url_for("https://tauri.studio"), None);
+  }
+
+  #[cfg(windows)]
+  #[test]
+  fn parse_proxy_server() {
+    assert_eq!(
+      super::parse_proxy_server("http=proxy.example.com:8080;https=proxy.example.com:8443"),
+      Proxy {
+        http: Some("proxy.example.com:8080".into()),
+        https: Some("proxy.example.com:8443".into()),
+        ..Default::default()
+      }
+    );
+    assert_eq!(
+      super::parse_proxy_server("proxy.example.com:8080"),
+      Proxy::new("proxy.example.com:8080")
+    );
+  }
+
+  #[test]
+  fn from_config() {
+    let mut config = ProxyConfig::default();
+    assert_eq!(Proxy::from_config(&config), None);
+
+    config.system = false;
+    assert_eq!(Proxy::from_config(&config), Some(Proxy::direct()));
+
+    config.url = Some("http://proxy.example.com".into());
+    assert_eq!(
+      Proxy::from_config(&config),
+      Some(Proxy::new("http://proxy.example.com"))
+    );
+  }
+}
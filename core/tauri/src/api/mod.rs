@@ -6,13 +6,18 @@
 #![warn(missing_docs)]
 // #![feature(const_int_pow)]
 
-/// A module for working with processes.
+/// The native dialogs API.
+#[cfg(dialog)]
 pub mod dialog;
 /// The Dir module is a helper for file system directory management.
 pub mod dir;
 /// The File API module contains helpers to perform file operations.
 pub mod file;
+/// The file system watcher API.
+#[cfg(any(fs_watch, fs_trash))]
+pub mod fs;
 /// The HTTP request API.
+#[cfg(http_client)]
 pub mod http;
 /// The file system path operations API.
 pub mod path;
@@ -44,6 +49,10 @@ pub mod shortcuts;
 #[cfg(notification_all)]
 pub mod notification;
 
+/// The system information API module.
+#[cfg(system_all)]
+pub mod system;
+
 pub use tauri_utils::*;
 
 mod error;
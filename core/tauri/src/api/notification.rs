@@ -5,9 +5,49 @@
 #[cfg(windows)]
 use std::path::MAIN_SEPARATOR;
 
+use serde::{Deserialize, Serialize};
+
+/// An interaction of the user with a notification.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "type", content = "payload", rename_all = "camelCase")]
+#[non_exhaustive]
+pub enum NotificationEvent {
+  /// The notification was clicked.
+  Clicked,
+  /// An action button was clicked. Contains the action identifier.
+  Action(String),
+  /// The notification was closed or dismissed.
+  Closed,
+}
+
+/// The priority of a notification.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum NotificationPriority {
+  /// A low priority notification, which may be shown silently.
+  Low,
+  /// The default priority.
+  Normal,
+  /// A high priority notification, which should not expire automatically.
+  High,
+}
+
+impl Default for NotificationPriority {
+  fn default() -> Self {
+    Self::Normal
+  }
+}
+
+type ActionHandler = Box<dyn FnOnce(NotificationEvent) + Send>;
+
 /// The Notification definition.
 /// Allows you to construct a Notification data and send it.
 ///
+/// Action buttons and the [`Notification::on_action`] handler are only supported on Linux,
+/// and are ignored on the other platforms.
+/// The image and priority are mapped to the freedesktop hints,
+/// so they are also only supported on Linux.
+///
 /// # Example
 /// ```
 /// use tauri::api::notification::Notification;
@@ -28,6 +68,16 @@ pub struct Notification {
   icon: Option<String>,
   /// The notification identifier
   identifier: String,
+  /// The path of the notification image.
+  image: Option<String>,
+  /// The notification sound name.
+  sound: Option<String>,
+  /// The notification priority.
+  priority: NotificationPriority,
+  /// The notification action buttons.
+  actions: Vec<(String, String)>,
+  /// The handler called when the user interacts with the notification.
+  on_action: Option<ActionHandler>,
 }
 
 impl Notification {
@@ -57,6 +107,57 @@ impl Notification {
     self
   }
 
+  /// Sets the path of an image attached to the notification.
+  pub fn image(mut self, path: impl Into<String>) -> Self {
+    self.image = Some(path.into());
+    self
+  }
+
+  /// Sets the sound played when the notification is shown.
+  ///
+  /// On Linux this is a name of the freedesktop sound naming specification,
+  /// and on macOS the name of a system sound, e.g. `Ping`.
+  pub fn sound(mut self, name: impl Into<String>) -> Self {
+    self.sound = Some(name.into());
+    self
+  }
+
+  /// Sets the notification priority.
+  ///
+  /// On Linux it is sent as the `urgency` hint.
+  pub fn priority(mut self, priority: NotificationPriority) -> Self {
+    self.priority = priority;
+    self
+  }
+
+  /// Adds an action button with the given identifier and label.
+  pub fn action(mut self, id: impl Into<String>, label: impl Into<String>) -> Self {
+    self.actions.push((id.into(), label.into()));
+    self
+  }
+
+  /// Sets the handler called when the notification or one of its actions is clicked,
+  /// or when the notification is closed.
+  ///
+  /// # Example
+  /// ```rust,no_run
+  /// use tauri::api::notification::{Notification, NotificationEvent};
+  /// Notification::new("studio.tauri.example")
+  ///   .title("New message")
+  ///   .action("reply", "Reply")
+  ///   .on_action(|event| match event {
+  ///     NotificationEvent::Action(action) if action == "reply" => println!("reply"),
+  ///     NotificationEvent::Clicked => println!("open the conversation"),
+  ///     _ => {}
+  ///   })
+  ///   .show()
+  ///   .expect("failed to show notification");
+  /// ```
+  pub fn on_action<F: FnOnce(NotificationEvent) + Send + 'static>(mut self, handler: F) -> Self {
+    self.on_action.replace(Box::new(handler));
+    self
+  }
+
   /// Shows the notification.
   pub fn show(self) -> crate::api::Result<()> {
     let mut notification = notify_rust::Notification::new();
@@ -69,6 +170,27 @@ impl Notification {
     if let Some(icon) = self.icon {
       notification.icon(&icon);
     }
+    if let Some(sound) = &self.sound {
+      notification.sound_name(sound);
+    }
+    #[cfg(all(unix, not(target_os = "macos")))]
+    {
+      if let Some(image) = &self.image {
+        notification.image_path(image);
+      }
+      notification.urgency(match self.priority {
+        NotificationPriority::Low => notify_rust::Urgency::Low,
+        NotificationPriority::Normal => notify_rust::Urgency::Normal,
+        NotificationPriority::High => notify_rust::Urgency::Critical,
+      });
+    }
+    // the notification servers only report the clicks on the notification body through the `default` action
+    if self.on_action.is_some() {
+      notification.action("default", "");
+    }
+    for (id, label) in &self.actions {
+      notification.action(id, label);
+    }
     #[cfg(windows)]
     {
       let exe = std::env::current_exe()?;
@@ -81,6 +203,24 @@ impl Notification {
         notification.app_id(&self.identifier);
       }
     }
+    #[cfg(all(unix, not(target_os = "macos")))]
+    {
+      if let Some(on_action) = self.on_action {
+        // the notification handle can't be sent to another thread, so it is shown on the waiting thread
+        std::thread::spawn(move || {
+          if let Ok(handle) = notification.show() {
+            handle.wait_for_action(|action| {
+              on_action(match action {
+                "default" => NotificationEvent::Clicked,
+                "__closed" => NotificationEvent::Closed,
+                action => NotificationEvent::Action(action.to_string()),
+              })
+            });
+          }
+        });
+        return Ok(());
+      }
+    }
     notification.show()?;
     Ok(())
   }
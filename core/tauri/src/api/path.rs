@@ -55,7 +55,8 @@ pub enum BaseDirectory {
   /// The Resource directory.
   Resource,
   /// The default App config directory.
-  /// Resolves to ${BaseDirectory::Config}/${config.tauri.bundle.identifier}
+  /// Resolves to ${BaseDirectory::Config}/${config.tauri.bundle.identifier},
+  /// or the data directory of the portable executable on Windows.
   App,
   /// The current working directory.
   Current,
@@ -223,6 +224,9 @@ pub fn resource_dir(package_info: &PackageInfo) -> Option<PathBuf> {
 }
 
 /// Returns the path to the suggested directory for your app config files.
+///
+/// When running from the portable Windows executable, it's the portable data directory.
 pub fn app_dir(config: &Config) -> Option<PathBuf> {
-  dirs_next::config_dir().map(|dir| dir.join(&config.tauri.bundle.identifier))
+  crate::api::platform::portable_data_dir()
+    .or_else(|| dirs_next::config_dir().map(|dir| dir.join(&config.tauri.bundle.identifier)))
 }
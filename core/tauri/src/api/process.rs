@@ -3,16 +3,69 @@
 // SPDX-License-Identifier: MIT
 
 use std::{
+  collections::HashMap,
   env,
+  ffi::OsString,
   path::PathBuf,
   process::{exit, Command as StdCommand},
 };
 
+use tauri_utils::platform;
+
 #[cfg(shell_execute)]
 mod command;
 #[cfg(shell_execute)]
 pub use command::*;
 
+/// The sidecar binaries bundled with the app, configured on `tauri > bundle > externalBin`.
+pub struct SidecarCommand;
+
+impl SidecarCommand {
+  /// Resolves the path of the sidecar with the given name, next to the app executable.
+  ///
+  /// The sidecar file has the target triple suffix of the running platform,
+  /// or the `universal-apple-darwin` suffix on universal macOS builds.
+  ///
+  /// # Example
+  /// ```rust,no_run
+  /// use tauri::api::process::SidecarCommand;
+  /// // resolves e.g. `my-sidecar-x86_64-pc-windows-msvc.exe` next to the app executable
+  /// let path = SidecarCommand::resolve("my-sidecar").expect("sidecar not found");
+  /// ```
+  pub fn resolve(name: &str) -> crate::api::Result<PathBuf> {
+    let exe = env::current_exe()?;
+    let exe_dir = exe
+      .parent()
+      .ok_or_else(|| crate::api::Error::Command("Could not evaluate executable dir".into()))?;
+    let target_triple = platform::target_triple()
+      .map_err(|e| crate::api::Error::FailedToDetectPlatform(e.to_string()))?;
+
+    let mut file_names = vec![platform::sidecar_file_name(name, &target_triple)];
+    if cfg!(target_os = "macos") {
+      file_names.push(platform::sidecar_file_name(name, "universal-apple-darwin"));
+    }
+    file_names
+      .into_iter()
+      .map(|file_name| exe_dir.join(file_name))
+      .find(|path| path.exists())
+      .ok_or_else(|| crate::api::Error::SidecarNotFound(name.into()))
+  }
+
+  /// Verifies the sidecar checksums computed when the app was built.
+  pub(crate) fn verify_checksums(checksums: &HashMap<String, String>) -> crate::api::Result<()> {
+    for (name, checksum) in checksums {
+      let path = Self::resolve(name)?;
+      let actual = platform::file_checksum(&path).map_err(|e| {
+        crate::api::Error::Command(format!("failed to read the `{}` sidecar: {}", name, e))
+      })?;
+      if &actual != checksum {
+        return Err(crate::api::Error::SidecarChecksumMismatch(name.clone()));
+      }
+    }
+    Ok(())
+  }
+}
+
 /// Get the current binary
 pub fn current_binary() -> Option<PathBuf> {
   let mut current_binary = None;
@@ -34,12 +87,74 @@ pub fn current_binary() -> Option<PathBuf> {
   current_binary
 }
 
+/// Describes how the process is relaunched by [`restart_with`].
+///
+/// # Example
+/// ```rust,no_run
+/// use tauri::api::process::{restart_with, RestartRequest};
+/// restart_with(
+///   RestartRequest::new()
+///     .arg("--profile")
+///     .arg("work")
+///     .env("APP_SAFE_MODE", "1"),
+/// );
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct RestartRequest {
+  args: Vec<OsString>,
+  env: HashMap<OsString, OsString>,
+  cwd: Option<PathBuf>,
+}
+
+impl RestartRequest {
+  /// Creates a request relaunching the process without arguments.
+  pub fn new() -> Self {
+    Default::default()
+  }
+
+  /// Appends an argument to the relaunched process.
+  pub fn arg(mut self, arg: impl Into<OsString>) -> Self {
+    self.args.push(arg.into());
+    self
+  }
+
+  /// Appends arguments to the relaunched process.
+  pub fn args<I, S>(mut self, args: I) -> Self
+  where
+    I: IntoIterator<Item = S>,
+    S: Into<OsString>,
+  {
+    self.args.extend(args.into_iter().map(Into::into));
+    self
+  }
+
+  /// Sets an environment variable on the relaunched process, in addition to the inherited ones.
+  pub fn env(mut self, key: impl Into<OsString>, value: impl Into<OsString>) -> Self {
+    self.env.insert(key.into(), value.into());
+    self
+  }
+
+  /// Sets the working directory of the relaunched process.
+  pub fn current_dir(mut self, cwd: impl Into<PathBuf>) -> Self {
+    self.cwd.replace(cwd.into());
+    self
+  }
+}
+
 /// Restart the process.
 pub fn restart() {
+  restart_with(RestartRequest::default())
+}
+
+/// Restart the process with the arguments, environment variables and working directory of the request.
+pub fn restart_with(request: RestartRequest) {
   if let Some(path) = current_binary() {
-    StdCommand::new(path)
-      .spawn()
-      .expect("application failed to start");
+    let mut command = StdCommand::new(path);
+    command.args(request.args).envs(request.env);
+    if let Some(cwd) = request.cwd {
+      command.current_dir(cwd);
+    }
+    command.spawn().expect("application failed to start");
   }
 
   exit(0);
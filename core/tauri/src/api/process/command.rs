@@ -8,6 +8,7 @@ use std::{
   path::PathBuf,
   process::{Command as StdCommand, Stdio},
   sync::{Arc, Mutex},
+  time::Duration,
 };
 
 #[cfg(unix)]
@@ -22,7 +23,6 @@ use crate::async_runtime::{channel, spawn, Receiver, RwLock};
 use os_pipe::{pipe, PipeWriter};
 use serde::Serialize;
 use shared_child::SharedChild;
-use tauri_utils::platform;
 
 type ChildStore = Arc<Mutex<HashMap<u32, Arc<SharedChild>>>>;
 
@@ -32,12 +32,51 @@ fn commands() -> &'static ChildStore {
   &STORE
 }
 
+#[cfg(feature = "shell-pty")]
+type PtyChild = Arc<Mutex<Box<dyn portable_pty::Child + Send>>>;
+#[cfg(feature = "shell-pty")]
+type PtyStore = Arc<Mutex<HashMap<u32, PtyChild>>>;
+
+#[cfg(feature = "shell-pty")]
+fn pty_commands() -> &'static PtyStore {
+  use once_cell::sync::Lazy;
+  static STORE: Lazy<PtyStore> = Lazy::new(Default::default);
+  &STORE
+}
+
 /// Kill all child process created with [`Command`].
 /// By default it's called before the [`crate::App`] exits.
 pub fn kill_children() {
   for child in commands().lock().unwrap().values() {
     let _ = child.kill();
   }
+  #[cfg(feature = "shell-pty")]
+  for child in pty_commands().lock().unwrap().values() {
+    let _ = child.lock().unwrap().kill();
+  }
+}
+
+/// The size of a pseudo terminal.
+#[cfg(feature = "shell-pty")]
+#[cfg_attr(doc_cfg, doc(cfg(feature = "shell-pty")))]
+#[derive(Debug, Clone, Copy, serde::Deserialize)]
+pub struct PtySize {
+  /// The number of rows.
+  pub rows: u16,
+  /// The number of columns.
+  pub cols: u16,
+}
+
+#[cfg(feature = "shell-pty")]
+impl From<PtySize> for portable_pty::PtySize {
+  fn from(size: PtySize) -> Self {
+    Self {
+      rows: size.rows,
+      cols: size.cols,
+      pixel_width: 0,
+      pixel_height: 0,
+    }
+  }
 }
 
 /// Payload for the `Terminated` command event.
@@ -62,6 +101,10 @@ pub enum CommandEvent {
   Error(String),
   /// Command process terminated.
   Terminated(TerminatedPayload),
+  /// Raw output of a command spawned on a pseudo terminal.
+  #[cfg(feature = "shell-pty")]
+  #[cfg_attr(doc_cfg, doc(cfg(feature = "shell-pty")))]
+  Output(Vec<u8>),
 }
 
 macro_rules! get_std_command {
@@ -91,36 +134,105 @@ pub struct Command {
   env_clear: bool,
   env: HashMap<String, String>,
   current_dir: Option<PathBuf>,
+  #[cfg(feature = "shell-pty")]
+  pty: Option<PtySize>,
+}
+
+enum ChildHandle {
+  Pipe {
+    child: Arc<SharedChild>,
+    stdin_writer: PipeWriter,
+  },
+  #[cfg(feature = "shell-pty")]
+  Pty {
+    child: PtyChild,
+    master: Box<dyn portable_pty::MasterPty + Send>,
+    pid: u32,
+  },
 }
 
 /// Child spawned.
 pub struct CommandChild {
-  inner: Arc<SharedChild>,
-  stdin_writer: PipeWriter,
+  inner: ChildHandle,
 }
 
 impl CommandChild {
   /// Write to process stdin.
   pub fn write(&mut self, buf: &[u8]) -> crate::api::Result<()> {
-    self.stdin_writer.write_all(buf)?;
+    match &mut self.inner {
+      ChildHandle::Pipe { stdin_writer, .. } => stdin_writer.write_all(buf)?,
+      #[cfg(feature = "shell-pty")]
+      ChildHandle::Pty { master, .. } => master.write_all(buf)?,
+    }
     Ok(())
   }
 
   /// Send a kill signal to the child.
   pub fn kill(self) -> crate::api::Result<()> {
-    self.inner.kill()?;
+    match self.inner {
+      ChildHandle::Pipe { child, .. } => child.kill()?,
+      #[cfg(feature = "shell-pty")]
+      ChildHandle::Pty { child, .. } => child.lock().unwrap().kill()?,
+    }
     Ok(())
   }
 
+  /// Asks the child to terminate, killing it if it's still running after the `timeout`.
+  ///
+  /// On Unix a `SIGTERM` signal is sent so the process can exit gracefully.
+  /// On Windows there's no graceful termination, so the process is killed immediately.
+  /// Processes spawned on a pseudo terminal are always killed immediately.
+  ///
+  /// This function blocks the current thread until the child exits or is killed.
+  pub fn terminate(self, timeout: Duration) -> crate::api::Result<()> {
+    #[cfg(unix)]
+    if let ChildHandle::Pipe { child, .. } = &self.inner {
+      use shared_child::unix::SharedChildExt;
+      const SIGTERM: std::os::raw::c_int = 15;
+
+      child.send_signal(SIGTERM)?;
+      // the exit is sent by a waiter thread, which returns once the child exits or is killed
+      let (tx, rx) = std::sync::mpsc::channel();
+      let waiter = child.clone();
+      std::thread::spawn(move || {
+        let _ = tx.send(waiter.wait());
+      });
+      if let Ok(status) = rx.recv_timeout(timeout) {
+        status?;
+        return Ok(());
+      }
+    }
+    self.kill()
+  }
+
+  /// Resizes the pseudo terminal of a command spawned with [`Command::pty`].
+  #[cfg(feature = "shell-pty")]
+  #[cfg_attr(doc_cfg, doc(cfg(feature = "shell-pty")))]
+  pub fn resize(&self, size: PtySize) -> crate::api::Result<()> {
+    match &self.inner {
+      ChildHandle::Pty { master, .. } => master
+        .resize(size.into())
+        .map_err(|e| crate::api::Error::Command(e.to_string())),
+      ChildHandle::Pipe { .. } => Err(crate::api::Error::Command(
+        "the command was not spawned on a pseudo terminal".into(),
+      )),
+    }
+  }
+
   /// Returns the process pid.
   pub fn pid(&self) -> u32 {
-    self.inner.id()
+    match &self.inner {
+      ChildHandle::Pipe { child, .. } => child.id(),
+      #[cfg(feature = "shell-pty")]
+      ChildHandle::Pty { pid, .. } => *pid,
+    }
   }
 }
 
 /// Describes the result of a process after it has terminated.
 pub struct ExitStatus {
   code: Option<i32>,
+  signal: Option<i32>,
 }
 
 impl ExitStatus {
@@ -129,6 +241,11 @@ impl ExitStatus {
     self.code
   }
 
+  /// Returns the signal that terminated the process, if any. Always `None` on Windows.
+  pub fn signal(&self) -> Option<i32> {
+    self.signal
+  }
+
   /// Was termination successful? Signal termination is not considered a success, and success is defined as a zero exit status.
   pub fn success(&self) -> bool {
     self.code == Some(0)
@@ -145,30 +262,6 @@ pub struct Output {
   pub stderr: String,
 }
 
-#[cfg(not(windows))]
-fn relative_command_path(command: String) -> crate::Result<String> {
-  match std::env::current_exe()?.parent() {
-    Some(exe_dir) => Ok(format!(
-      "{}/{}",
-      exe_dir.to_string_lossy().to_string(),
-      command
-    )),
-    None => Err(crate::api::Error::Command("Could not evaluate executable dir".to_string()).into()),
-  }
-}
-
-#[cfg(windows)]
-fn relative_command_path(command: String) -> crate::Result<String> {
-  match std::env::current_exe()?.parent() {
-    Some(exe_dir) => Ok(format!(
-      "{}/{}.exe",
-      exe_dir.to_string_lossy().to_string(),
-      command
-    )),
-    None => Err(crate::api::Error::Command("Could not evaluate executable dir".to_string()).into()),
-  }
-}
-
 impl Command {
   /// Creates a new Command for launching the given program.
   pub fn new<S: Into<String>>(program: S) -> Self {
@@ -178,17 +271,17 @@ impl Command {
       env_clear: false,
       env: Default::default(),
       current_dir: None,
+      #[cfg(feature = "shell-pty")]
+      pty: None,
     }
   }
 
   /// Creates a new Command for launching the given sidecar program.
+  ///
+  /// The sidecar path is resolved with [`SidecarCommand::resolve`](super::SidecarCommand::resolve).
   pub fn new_sidecar<S: Into<String>>(program: S) -> crate::Result<Self> {
-    let program = format!(
-      "{}-{}",
-      program.into(),
-      platform::target_triple().expect("unsupported platform")
-    );
-    Ok(Self::new(relative_command_path(program)?))
+    let path = super::SidecarCommand::resolve(&program.into())?;
+    Ok(Self::new(path.to_string_lossy()))
   }
 
   /// Append args to the command.
@@ -221,8 +314,99 @@ impl Command {
     self
   }
 
+  /// Spawns the command on a pseudo terminal with the given size,
+  /// so programs that require a TTY can be used on embedded terminals.
+  ///
+  /// The terminal output is sent as [`CommandEvent::Output`] and stdout and stderr aren't split.
+  #[cfg(feature = "shell-pty")]
+  #[cfg_attr(doc_cfg, doc(cfg(feature = "shell-pty")))]
+  pub fn pty(mut self, size: PtySize) -> Self {
+    self.pty.replace(size);
+    self
+  }
+
+  #[cfg(feature = "shell-pty")]
+  fn spawn_pty(self, size: PtySize) -> crate::api::Result<(Receiver<CommandEvent>, CommandChild)> {
+    use portable_pty::{native_pty_system, CommandBuilder};
+    use std::io::Read;
+
+    let pair = native_pty_system()
+      .openpty(size.into())
+      .map_err(|e| crate::api::Error::Command(e.to_string()))?;
+    let mut builder = CommandBuilder::new(&self.program);
+    builder.args(&self.args);
+    if self.env_clear {
+      builder.env_clear();
+    }
+    for (key, value) in &self.env {
+      builder.env(key, value);
+    }
+    if let Some(current_dir) = &self.current_dir {
+      builder.cwd(current_dir);
+    }
+    let child = pair
+      .slave
+      .spawn_command(builder)
+      .map_err(|e| crate::api::Error::Command(e.to_string()))?;
+    // the reader only reaches EOF once every handle to the slave is closed
+    drop(pair.slave);
+    let mut reader = pair
+      .master
+      .try_clone_reader()
+      .map_err(|e| crate::api::Error::Command(e.to_string()))?;
+
+    let pid = child.process_id().unwrap_or_default();
+    let child: Box<dyn portable_pty::Child + Send> = child;
+    let child: PtyChild = Arc::new(Mutex::new(child));
+    pty_commands().lock().unwrap().insert(pid, child.clone());
+
+    let (tx, rx) = channel(1);
+    let child_ = child.clone();
+    std::thread::spawn(move || {
+      let mut buffer = [0; 4096];
+      loop {
+        match reader.read(&mut buffer) {
+          Ok(0) | Err(_) => break,
+          Ok(read) => {
+            if tx
+              .blocking_send(CommandEvent::Output(buffer[..read].to_vec()))
+              .is_err()
+            {
+              break;
+            }
+          }
+        }
+      }
+
+      let status = wait_pty_child(pid);
+      // the child already exited, so this only releases it
+      let _ = child_.lock().unwrap().wait();
+      pty_commands().lock().unwrap().remove(&pid);
+      let _ = tx.blocking_send(match status {
+        Ok((code, signal)) => CommandEvent::Terminated(TerminatedPayload { code, signal }),
+        Err(e) => CommandEvent::Error(e.to_string()),
+      });
+    });
+
+    Ok((
+      rx,
+      CommandChild {
+        inner: ChildHandle::Pty {
+          child,
+          master: pair.master,
+          pid,
+        },
+      },
+    ))
+  }
+
   /// Spawns the command.
   pub fn spawn(self) -> crate::api::Result<(Receiver<CommandEvent>, CommandChild)> {
+    #[cfg(feature = "shell-pty")]
+    if let Some(size) = self.pty {
+      return self.spawn_pty(size);
+    }
+
     let mut command = get_std_command!(self);
     let (stdout_reader, stdout_writer) = pipe()?;
     let (stderr_reader, stderr_writer) = pipe()?;
@@ -290,8 +474,10 @@ impl Command {
     Ok((
       rx,
       CommandChild {
-        inner: child,
-        stdin_writer,
+        inner: ChildHandle::Pipe {
+          child,
+          stdin_writer,
+        },
       },
     ))
   }
@@ -300,16 +486,22 @@ impl Command {
   /// Stdin, stdout and stderr are ignored.
   pub fn status(self) -> crate::api::Result<ExitStatus> {
     let (mut rx, _child) = self.spawn()?;
-    let code = crate::async_runtime::block_on(async move {
-      let mut code = None;
+    let status = crate::async_runtime::block_on(async move {
+      let mut status = ExitStatus {
+        code: None,
+        signal: None,
+      };
       while let Some(event) = rx.recv().await {
         if let CommandEvent::Terminated(payload) = event {
-          code = payload.code;
+          status = ExitStatus {
+            code: payload.code,
+            signal: payload.signal,
+          };
         }
       }
-      code
+      status
     });
-    Ok(ExitStatus { code })
+    Ok(status)
   }
 
   /// Executes the command as a child process, waiting for it to finish and collecting all of its output.
@@ -318,13 +510,19 @@ impl Command {
     let (mut rx, _child) = self.spawn()?;
 
     let output = crate::async_runtime::block_on(async move {
-      let mut code = None;
+      let mut status = ExitStatus {
+        code: None,
+        signal: None,
+      };
       let mut stdout = String::new();
       let mut stderr = String::new();
       while let Some(event) = rx.recv().await {
         match event {
           CommandEvent::Terminated(payload) => {
-            code = payload.code;
+            status = ExitStatus {
+              code: payload.code,
+              signal: payload.signal,
+            };
           }
           CommandEvent::Stdout(line) => {
             stdout.push_str(line.as_str());
@@ -334,11 +532,15 @@ impl Command {
             stderr.push_str(line.as_str());
             stderr.push('\n');
           }
+          #[cfg(feature = "shell-pty")]
+          CommandEvent::Output(data) => {
+            stdout.push_str(&String::from_utf8_lossy(&data));
+          }
           CommandEvent::Error(_) => {}
         }
       }
       Output {
-        status: ExitStatus { code },
+        status,
         stdout,
         stderr,
       }
@@ -348,6 +550,72 @@ impl Command {
   }
 }
 
+/// Waits for a pseudo terminal child to exit, returning its exit code and signal.
+///
+/// portable-pty only reports whether the child succeeded, so the status is read with `waitid`,
+/// leaving the child waitable so portable-pty can still release it.
+#[cfg(all(feature = "shell-pty", unix))]
+fn wait_pty_child(pid: u32) -> std::io::Result<(Option<i32>, Option<i32>)> {
+  let mut info: libc::siginfo_t = unsafe { std::mem::zeroed() };
+  loop {
+    let result = unsafe {
+      libc::waitid(
+        libc::P_PID,
+        pid as libc::id_t,
+        &mut info,
+        libc::WEXITED | libc::WNOWAIT,
+      )
+    };
+    if result == 0 {
+      break;
+    }
+    let error = std::io::Error::last_os_error();
+    if error.kind() != std::io::ErrorKind::Interrupted {
+      return Err(error);
+    }
+  }
+  #[cfg(target_os = "linux")]
+  let status = unsafe { info.si_status() };
+  #[cfg(not(target_os = "linux"))]
+  let status = info.si_status;
+  Ok(if info.si_code == libc::CLD_EXITED {
+    (Some(status), None)
+  } else {
+    (None, Some(status))
+  })
+}
+
+/// Waits for a pseudo terminal child to exit, returning its exit code.
+///
+/// portable-pty only reports whether the child succeeded, so the exit code is read from a new process handle.
+#[cfg(all(feature = "shell-pty", windows))]
+fn wait_pty_child(pid: u32) -> std::io::Result<(Option<i32>, Option<i32>)> {
+  use winapi::um::{
+    handleapi::CloseHandle,
+    processthreadsapi::{GetExitCodeProcess, OpenProcess},
+    synchapi::WaitForSingleObject,
+    winbase::{INFINITE, WAIT_OBJECT_0},
+    winnt::{PROCESS_QUERY_LIMITED_INFORMATION, SYNCHRONIZE},
+  };
+
+  unsafe {
+    let handle = OpenProcess(SYNCHRONIZE | PROCESS_QUERY_LIMITED_INFORMATION, 0, pid);
+    if handle.is_null() {
+      return Err(std::io::Error::last_os_error());
+    }
+    let mut code = 0;
+    let result = if WaitForSingleObject(handle, INFINITE) == WAIT_OBJECT_0
+      && GetExitCodeProcess(handle, &mut code) != 0
+    {
+      Ok((Some(code as i32), None))
+    } else {
+      Err(std::io::Error::last_os_error())
+    };
+    CloseHandle(handle);
+    result
+  }
+}
+
 // tests for the commands functions.
 #[cfg(test)]
 mod test {
@@ -2,7 +2,69 @@
 // SPDX-License-Identifier: Apache-2.0
 // SPDX-License-Identifier: MIT
 
-use tauri_hotkey::{parse_hotkey, HotkeyManager};
+use tauri_hotkey::{parse_hotkey, Hotkey, HotkeyManager};
+
+use std::{fmt, str::FromStr};
+
+pub use tauri_hotkey::{Key, Modifier};
+
+/// A global shortcut, made of a list of modifiers and a key.
+///
+/// It can be built from its parts or parsed from an accelerator string:
+///
+/// ```rust,no_run
+/// use tauri::api::shortcuts::{Key, Modifier, Shortcut};
+/// let shortcut = Shortcut::new(vec![Modifier::CTRL, Modifier::SHIFT], Key::K);
+/// let parsed: Shortcut = "CTRL+SHIFT+K".parse().expect("invalid shortcut");
+/// ```
+///
+/// The keys are the ones the `tauri-hotkey` backend can register,
+/// so the media keys (play/pause, next, previous, volume) aren't supported.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Shortcut(Hotkey);
+
+impl Shortcut {
+  /// Creates a shortcut with the given modifiers and key.
+  pub fn new(modifiers: Vec<Modifier>, key: Key) -> Self {
+    Self(Hotkey {
+      modifiers,
+      keys: vec![key],
+    })
+  }
+}
+
+impl FromStr for Shortcut {
+  type Err = crate::api::Error;
+
+  fn from_str(accelerator: &str) -> crate::api::Result<Self> {
+    parse_hotkey(accelerator).map(Self).map_err(Into::into)
+  }
+}
+
+impl fmt::Display for Shortcut {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    self.0.fmt(f)
+  }
+}
+
+/// The state of a shortcut when its handler is called.
+///
+/// The hotkey backend only reports key presses, so the handlers are only called with [`ShortcutState::Pressed`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum ShortcutState {
+  /// The shortcut was pressed.
+  Pressed,
+}
+
+/// The event sent to a shortcut handler.
+#[derive(Debug, Clone)]
+pub struct ShortcutEvent {
+  /// The shortcut that triggered the event.
+  pub shortcut: Shortcut,
+  /// The shortcut state.
+  pub state: ShortcutState,
+}
 
 /// The shortcut manager builder.
 #[derive(Default)]
@@ -14,27 +76,75 @@ impl ShortcutManager {
     Default::default()
   }
 
-  /// Determines whether the given hotkey is registered or not.
-  pub fn is_registered(&self, shortcut: String) -> crate::api::Result<bool> {
-    let hotkey = parse_hotkey(&shortcut)?;
-    Ok(self.0.is_registered(&hotkey))
+  /// Determines whether the given shortcut is registered by this application or not.
+  pub fn is_registered(&self, shortcut: &Shortcut) -> bool {
+    self.0.is_registered(&shortcut.0)
   }
 
   /// Registers a new shortcut handler.
-  pub fn register<H: FnMut() + Send + 'static>(
+  ///
+  /// Returns [`crate::api::Error::ShortcutAlreadyRegistered`] if the shortcut is already registered by this application.
+  pub fn register<H: FnMut(ShortcutEvent) + Send + 'static>(
+    &mut self,
+    shortcut: Shortcut,
+    mut handler: H,
+  ) -> crate::api::Result<()> {
+    if self.is_registered(&shortcut) {
+      return Err(crate::api::Error::ShortcutAlreadyRegistered(
+        shortcut.to_string(),
+      ));
+    }
+    let shortcut_ = shortcut.clone();
+    self.0.register(shortcut.0, move || {
+      handler(ShortcutEvent {
+        shortcut: shortcut_.clone(),
+        state: ShortcutState::Pressed,
+      })
+    })?;
+    Ok(())
+  }
+
+  /// Registers the handler on all the given shortcuts.
+  ///
+  /// If any of the shortcuts can't be registered, the ones registered by this call are unregistered.
+  pub fn register_all<H: FnMut(ShortcutEvent) + Clone + Send + 'static>(
     &mut self,
-    shortcut: String,
+    shortcuts: Vec<Shortcut>,
     handler: H,
   ) -> crate::api::Result<()> {
-    let hotkey = parse_hotkey(&shortcut)?;
-    self.0.register(hotkey, handler)?;
+    let mut registered = Vec::new();
+    for shortcut in shortcuts {
+      if let Err(e) = self.register(shortcut.clone(), handler.clone()) {
+        for shortcut in registered {
+          let _ = self.unregister(&shortcut);
+        }
+        return Err(e);
+      }
+      registered.push(shortcut);
+    }
     Ok(())
   }
 
   /// Unregister a previously registered shortcut handler.
-  pub fn unregister(&mut self, shortcut: String) -> crate::api::Result<()> {
-    let hotkey = parse_hotkey(&shortcut)?;
-    self.0.unregister(&hotkey)?;
+  ///
+  /// Returns [`crate::api::Error::ShortcutNotRegistered`] if the shortcut isn't registered by this application.
+  pub fn unregister(&mut self, shortcut: &Shortcut) -> crate::api::Result<()> {
+    if !self.is_registered(shortcut) {
+      return Err(crate::api::Error::ShortcutNotRegistered(
+        shortcut.to_string(),
+      ));
+    }
+    self.0.unregister(&shortcut.0)?;
+    Ok(())
+  }
+
+  /// Unregisters the given shortcuts, ignoring the ones that aren't registered.
+  pub fn unregister_many(&mut self, shortcuts: &[Shortcut]) -> crate::api::Result<()> {
+    for shortcut in shortcuts {
+      if self.is_registered(shortcut) {
+        self.0.unregister(&shortcut.0)?;
+      }
+    }
     Ok(())
   }
 
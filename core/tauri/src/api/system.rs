@@ -0,0 +1,59 @@
+// Copyright 2019-2021 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+use serde::Serialize;
+use sysinfo::{ProcessorExt, RefreshKind, System, SystemExt};
+
+/// Information about the operating system and the machine running the application.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SystemInfo {
+  /// The operating system name, e.g. `Ubuntu` or `Windows`.
+  pub os_name: Option<String>,
+  /// The operating system version.
+  pub os_version: Option<String>,
+  /// The kernel version.
+  pub kernel_version: Option<String>,
+  /// The machine host name.
+  pub hostname: Option<String>,
+  /// The total memory in bytes.
+  pub total_memory: u64,
+  /// The available memory in bytes.
+  pub available_memory: u64,
+  /// The CPU model name.
+  pub cpu_model: Option<String>,
+  /// The number of logical CPUs.
+  pub cpu_count: usize,
+  /// The number of physical CPU cores, if it could be determined.
+  pub physical_core_count: Option<usize>,
+  /// The system uptime in seconds.
+  pub uptime: u64,
+}
+
+/// Reads the system information.
+///
+/// # Example
+/// ```rust,no_run
+/// let info = tauri::api::system::info();
+/// println!("{:?} {:?}, {} CPUs", info.os_name, info.os_version, info.cpu_count);
+/// ```
+pub fn info() -> SystemInfo {
+  let system = System::new_with_specifics(RefreshKind::new().with_memory().with_cpu());
+  let processors = system.processors();
+  SystemInfo {
+    os_name: system.name(),
+    os_version: system.os_version(),
+    kernel_version: system.kernel_version(),
+    hostname: system.host_name(),
+    // sysinfo reports the memory in kilobytes
+    total_memory: system.total_memory() * 1024,
+    available_memory: system.available_memory() * 1024,
+    cpu_model: processors
+      .first()
+      .map(|processor| processor.brand().trim().to_string()),
+    cpu_count: processors.len(),
+    physical_core_count: system.physical_core_count(),
+    uptime: system.uptime(),
+  }
+}
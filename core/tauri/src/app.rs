@@ -8,36 +8,55 @@ pub(crate) mod tray;
 
 use crate::{
   api::assets::Assets,
-  api::config::WindowUrl,
-  hooks::{InvokeHandler, OnPageLoad, PageLoadPayload, SetupHook},
+  api::config::{Config, ProtocolConfig, WindowUrl},
+  api::PackageInfo,
+  asset_resolver::AssetResolver,
+  bus::BusSubscription,
+  deep_link,
+  hooks::{
+    InvokeHandler, InvokeMiddleware, InvokeObservation, OnInvokeObserved, OnPageLoad,
+    PageLoadPayload, SetupHook,
+  },
+  ipc::IpcCodec,
   manager::{Args, WindowManager},
+  open_file,
+  path_resolver::PathResolver,
   plugin::{Plugin, PluginStore},
   runtime::{
     tag::Tag,
-    webview::{CustomProtocol, WebviewAttributes, WindowBuilder},
+    webview::{CustomProtocol, FileDropEvent, WebviewAttributes, WindowBuilder},
     window::{PendingWindow, WindowEvent},
-    Dispatch, MenuId, Params, Runtime,
+    Dispatch, MenuId, Params, RunEvent as RuntimeRunEvent, Runtime, RuntimeHandle, UserEvent,
   },
   sealed::{ManagerBase, RuntimeOrDispatch},
   Context, Invoke, Manager, StateManager, Window,
 };
 
-use std::{collections::HashMap, sync::Arc};
+use std::{
+  any::Any,
+  collections::HashMap,
+  marker::PhantomData,
+  path::{Component, Path, PathBuf},
+  sync::{Arc, Mutex},
+};
 
 #[cfg(feature = "menu")]
 use crate::runtime::menu::Menu;
 
-#[cfg(all(windows, feature = "system-tray"))]
-use crate::runtime::RuntimeHandle;
 #[cfg(feature = "system-tray")]
 use crate::runtime::{Icon, SystemTrayEvent as RuntimeSystemTrayEvent};
 
+#[cfg(feature = "single-instance")]
+use crate::single_instance::{self, SingleInstanceHandler};
+#[cfg(feature = "touchbar")]
+use crate::touchbar::{GlobalTouchBarEventListener, WindowTouchBarEvent};
 #[cfg(feature = "updater")]
 use crate::updater;
 
 #[cfg(feature = "menu")]
 pub(crate) type GlobalMenuEventListener<P> = Box<dyn Fn(WindowMenuEvent<P>) + Send + Sync>;
 pub(crate) type GlobalWindowEventListener<P> = Box<dyn Fn(GlobalWindowEvent<P>) + Send + Sync>;
+pub(crate) type FileDropListener<P> = Box<dyn Fn(&Window<P>, &FileDropEvent) -> bool + Send + Sync>;
 #[cfg(feature = "system-tray")]
 type SystemTrayEventListener<P> =
   Box<dyn Fn(&AppHandle<P>, tray::SystemTrayEvent<<P as Params>::SystemTrayMenuId>) + Send + Sync>;
@@ -85,15 +104,52 @@ impl<P: Params> GlobalWindowEvent<P> {
   }
 }
 
+/// An application event, dispatched to the plugins and to the [`App::run`] callback.
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum RunEvent<L: Tag> {
+  /// An event of the window with the given label.
+  WindowEvent {
+    /// The window label.
+    label: L,
+    /// The window event.
+    event: WindowEvent,
+  },
+  /// The application is about to exit.
+  Exit,
+  /// An application-defined event sent with an [`EventLoopProxy`].
+  ///
+  /// It is only delivered to the [`App::run`] callback; use [`Box::downcast`] to get the event back.
+  UserEvent(Box<dyn Any + Send>),
+  /// An updater lifecycle event.
+  #[cfg(feature = "updater")]
+  #[cfg_attr(doc_cfg, doc(cfg(feature = "updater")))]
+  Updater(crate::updater::UpdaterEvent),
+  /// A change of the system power status.
+  #[cfg(feature = "power")]
+  #[cfg_attr(doc_cfg, doc(cfg(feature = "power")))]
+  Power(crate::power::PowerEvent),
+  /// A URL with one of the schemes registered on `tauri.conf.json > tauri > bundle > urlSchemes` was opened,
+  /// including the URL that launched the app.
+  ///
+  /// On Windows and Linux each URL opens a new instance of the app, so use [`Builder::single_instance`]
+  /// to receive them on the running instance.
+  OpenUrl(url::Url),
+  /// A file with one of the types registered on `tauri.conf.json > tauri > bundle > fileAssociations` was opened,
+  /// including the files that launched the app.
+  ///
+  /// On Windows and Linux each file opens a new instance of the app, so use [`Builder::single_instance`]
+  /// to receive them on the running instance.
+  OpenFile(PathBuf),
+}
+
 crate::manager::default_args! {
   /// A handle to the currently running application.
   ///
   /// This type implements [`Manager`] which allows for manipulation of global application items.
   pub struct AppHandle<P: Params> {
-    runtime_handle: <P::Runtime as Runtime>::Handle,
-    manager: WindowManager<P>,
-    #[cfg(feature = "system-tray")]
-    tray_handle: Option<tray::SystemTrayHandle<P>>,
+    pub(crate) runtime_handle: <P::Runtime as Runtime>::Handle,
+    pub(crate) manager: WindowManager<P>,
   }
 }
 
@@ -102,13 +158,55 @@ impl<P: Params> Clone for AppHandle<P> {
     Self {
       runtime_handle: self.runtime_handle.clone(),
       manager: self.manager.clone(),
-      #[cfg(feature = "system-tray")]
-      tray_handle: self.tray_handle.clone(),
     }
   }
 }
 
+/// A handle that wakes the event loop with application-defined events of type `T`,
+/// delivered to the [`App::run`] callback as [`RunEvent::UserEvent`].
+///
+/// It is created with [`AppHandle::create_proxy`] and can be cloned and moved to other threads.
+pub struct EventLoopProxy<T> {
+  sender: Arc<dyn Fn(UserEvent) -> crate::Result<()> + Send + Sync>,
+  _marker: PhantomData<fn(T)>,
+}
+
+impl<T> Clone for EventLoopProxy<T> {
+  fn clone(&self) -> Self {
+    Self {
+      sender: self.sender.clone(),
+      _marker: PhantomData,
+    }
+  }
+}
+
+impl<T: Send + 'static> EventLoopProxy<T> {
+  /// Sends the event to the event loop, waking it up.
+  ///
+  /// Returns an error if the event loop is no longer running.
+  pub fn send_event(&self, event: T) -> crate::Result<()> {
+    (self.sender)(Box::new(event))
+  }
+}
+
 impl<P: Params> AppHandle<P> {
+  /// Creates an [`EventLoopProxy`] to send events of type `T` to the event loop from any thread.
+  ///
+  /// The events are delivered to the [`App::run`] callback as [`RunEvent::UserEvent`].
+  pub fn create_proxy<T: Send + 'static>(&self) -> EventLoopProxy<T> {
+    let runtime_handle = Mutex::new(self.runtime_handle.clone());
+    EventLoopProxy {
+      sender: Arc::new(move |event| {
+        runtime_handle
+          .lock()
+          .expect("poisoned runtime handle")
+          .send_event(event)
+          .map_err(Into::into)
+      }),
+      _marker: PhantomData,
+    }
+  }
+
   /// Removes the system tray.
   #[cfg(all(windows, feature = "system-tray"))]
   #[cfg_attr(doc_cfg, doc(cfg(all(windows, feature = "system-tray"))))]
@@ -135,8 +233,6 @@ crate::manager::default_args! {
   pub struct App<P: Params> {
     runtime: Option<P::Runtime>,
     manager: WindowManager<P>,
-    #[cfg(feature = "system-tray")]
-    tray_handle: Option<tray::SystemTrayHandle<P>>,
     handle: AppHandle<P>,
   }
 }
@@ -178,14 +274,139 @@ macro_rules! shared_app_impl {
         Ok(())
       }
 
+      /// Adds an initialization script to every window created after this call.
+      ///
+      /// Use [`Window::add_init_script`](crate::Window::add_init_script) to add a script to an existing window.
+      pub fn add_initialization_script<S: Into<String>>(&self, script: S) {
+        self.manager().add_initialization_script(script.into());
+      }
+
+      /// Publishes a message on the message bus topic,
+      /// delivering it to the subscribers of the topic expecting a message of type `T`.
+      ///
+      /// The bus lets plugins communicate without the application wiring them together,
+      /// e.g. an `auth` plugin notifying a `sync` plugin of login events.
+      pub fn publish<T: Send + Sync + 'static>(&self, topic: &str, message: T) {
+        self.manager().bus().publish(topic, message)
+      }
+
+      /// Subscribes to the messages of type `T` published on the message bus topic.
+      pub fn subscribe<T, F>(&self, topic: impl Into<String>, handler: F) -> BusSubscription
+      where
+        T: 'static,
+        F: Fn(&T) + Send + Sync + 'static,
+      {
+        self.manager().bus().subscribe(topic.into(), handler)
+      }
+
+      /// Removes a message bus subscription.
+      pub fn unsubscribe(&self, subscription: BusSubscription) {
+        self.manager().bus().unsubscribe(subscription)
+      }
+
+      /// Gets the parsed `tauri.conf.json` configuration.
+      pub fn config(&self) -> Arc<Config> {
+        self.manager().config()
+      }
+
+      /// Gets the package information, read from the config or `Cargo.toml`.
+      pub fn package_info(&self) -> &PackageInfo {
+        self.manager().package_info()
+      }
+
+      /// Gets the [`AutoLaunch`](crate::autolaunch::AutoLaunch) registering the app to launch when the user logs in.
+      ///
+      /// The app is registered with its product name and the path of its bundle or executable.
+      #[cfg(feature = "autolaunch")]
+      #[cfg_attr(doc_cfg, doc(cfg(feature = "autolaunch")))]
+      pub fn autolaunch(&self) -> crate::Result<crate::autolaunch::AutoLaunch> {
+        crate::autolaunch::AutoLaunch::new(
+          self.package_info().name.clone(),
+          self.config().tauri.bundle.identifier.clone(),
+        )
+      }
+
+      /// Sets the jump list shown when the taskbar icon of the app is right clicked, replacing the current one.
+      ///
+      /// Only supported on Windows, it's a no-op on the other platforms.
+      #[cfg(feature = "jump-list")]
+      #[cfg_attr(doc_cfg, doc(cfg(feature = "jump-list")))]
+      pub fn set_jump_list(&self, jump_list: crate::jump_list::JumpList) -> crate::Result<()> {
+        crate::jump_list::set_jump_list(jump_list)
+      }
+
+      /// Gets a resolver of the assets embedded in the binary.
+      pub fn asset_resolver(&self) -> AssetResolver<P> {
+        AssetResolver {
+          manager: self.manager().clone(),
+        }
+      }
+
+      /// Gets a resolver of the app resources and directories.
+      pub fn path_resolver(&self) -> PathResolver {
+        PathResolver {
+          config: self.manager().config(),
+          package_info: self.manager().package_info().clone(),
+        }
+      }
+
+      #[cfg(feature = "updater")]
+      #[cfg_attr(doc_cfg, doc(cfg(feature = "updater")))]
+      /// Gets a handle to the updater, used to configure the update requests and to check, download and install the updates.
+      pub fn updater(&self) -> crate::updater::UpdaterHandle {
+        self.manager().updater().clone()
+      }
+
       #[cfg(feature = "system-tray")]
       #[cfg_attr(doc_cfg, doc(cfg(feature = "system-tray")))]
       /// Gets a handle handle to the system tray.
       pub fn tray_handle(&self) -> tray::SystemTrayHandle<P> {
-        self
-          .tray_handle
-          .clone()
-          .expect("tray not configured; use the `Builder#system_tray` API first.")
+        self.manager().tray_handle().expect(
+          "tray not configured; use the `Builder#system_tray` or `create_system_tray` APIs first.",
+        )
+      }
+
+      #[cfg(feature = "system-tray")]
+      #[cfg_attr(doc_cfg, doc(cfg(feature = "system-tray")))]
+      /// Adds the icon to the system tray with the specified menu items, replacing the existing tray.
+      ///
+      /// The tray defaults to the `tauri > systemTray > iconPath` icon.
+      /// The [`Builder#on_system_tray_event`](crate::Builder#method.on_system_tray_event) handlers receive its events.
+      ///
+      /// On the [`AppHandle`], the tray is created by the event loop so this can be called from any thread,
+      /// and from the main thread while the event loop handles an event.
+      pub fn create_system_tray(
+        &self,
+        system_tray: tray::SystemTray<P::SystemTrayMenuId>,
+      ) -> crate::Result<tray::SystemTrayHandle<P>> {
+        let mut ids = HashMap::new();
+        if let Some(menu) = system_tray.menu() {
+          tray::get_menu_ids(&mut ids, menu);
+        }
+        let icon = system_tray
+          .icon
+          .or_else(|| self.manager().system_tray_icon().cloned())
+          .ok_or_else(|| {
+            crate::Error::SystemTray(Box::new(std::io::Error::new(
+              std::io::ErrorKind::NotFound,
+              "tray icon not found; please configure it on tauri.conf.json",
+            )))
+          })?;
+        let mut tray = tray::SystemTray::new().with_icon(icon);
+        if let Some(menu) = system_tray.menu {
+          tray = tray.with_menu(menu);
+        }
+        let inner = match self.runtime() {
+          RuntimeOrDispatch::Runtime(runtime) => runtime.system_tray(tray),
+          RuntimeOrDispatch::RuntimeHandle(handle) => handle.system_tray(tray),
+          RuntimeOrDispatch::Dispatch(_) => unreachable!("the app has no window dispatcher"),
+        }?;
+        let tray_handle = tray::SystemTrayHandle {
+          ids: Arc::new(ids),
+          inner,
+        };
+        self.manager().set_tray_handle(tray_handle.clone());
+        Ok(tray_handle)
       }
     }
   };
@@ -223,6 +444,124 @@ impl<P: Params> App<P> {
   pub fn run_iteration(&mut self) -> crate::runtime::RunIteration {
     self.runtime.as_mut().unwrap().run_iteration()
   }
+
+  /// Runs the application event loop, calling the callback with the application events.
+  ///
+  /// The callback receives the [`RunEvent::UserEvent`]s sent with an [`EventLoopProxy`],
+  /// the [`RunEvent::Power`] events with the `power` feature, the [`RunEvent::OpenUrl`]
+  /// and [`RunEvent::OpenFile`] events and the [`RunEvent::Exit`] event, before the app cleanup is done.
+  ///
+  /// # Example
+  /// ```rust,ignore
+  /// struct Tick(u64);
+  ///
+  /// fn main() {
+  ///   let app = tauri::Builder::default()
+  ///     .build(tauri::generate_context!())
+  ///     .expect("error while building tauri application");
+  ///   let proxy = app.handle().create_proxy::<Tick>();
+  ///   std::thread::spawn(move || {
+  ///     for count in 0.. {
+  ///       std::thread::sleep(std::time::Duration::from_secs(1));
+  ///       if proxy.send_event(Tick(count)).is_err() {
+  ///         break;
+  ///       }
+  ///     }
+  ///   });
+  ///   app.run(|_app_handle, event| {
+  ///     if let tauri::RunEvent::UserEvent(event) = event {
+  ///       if let Ok(tick) = event.downcast::<Tick>() {
+  ///         println!("tick {}", tick.0);
+  ///       }
+  ///     }
+  ///   });
+  /// }
+  /// ```
+  pub fn run<F: FnMut(&AppHandle<P>, RunEvent<P::Label>) + 'static>(mut self, mut callback: F) {
+    let app_handle = self.handle();
+    let manager = self.manager.clone();
+    #[cfg(feature = "power")]
+    {
+      let proxy = app_handle.create_proxy();
+      crate::power::spawn_monitor(move |event| {
+        proxy
+          .send_event(crate::power::PowerMonitorEvent(event))
+          .is_ok()
+      });
+    }
+    {
+      let proxy = app_handle.create_proxy();
+      let config = self.manager.config();
+      for url in
+        deep_link::urls_from_args(std::env::args().skip(1), &config.tauri.bundle.url_schemes)
+      {
+        let _ = proxy.send_event(deep_link::OpenUrlEvent(url));
+      }
+      let cwd = std::env::current_dir().unwrap_or_default();
+      for path in open_file::files_from_args(
+        std::env::args().skip(1),
+        &cwd,
+        &config.tauri.bundle.file_associations,
+      ) {
+        let _ = proxy.send_event(open_file::OpenFileEvent(path));
+      }
+      #[cfg(target_os = "macos")]
+      {
+        let file_proxy = proxy.clone();
+        open_file::listen_apple_events(move |path| {
+          file_proxy
+            .send_event(open_file::OpenFileEvent(path))
+            .is_ok()
+        });
+        deep_link::listen_apple_events(move |url| {
+          proxy.send_event(deep_link::OpenUrlEvent(url)).is_ok()
+        });
+      }
+    }
+    self.runtime.take().unwrap().run(move |event| match event {
+      RuntimeRunEvent::Exit => {
+        callback(&app_handle, RunEvent::Exit);
+        manager.run_plugins_exit();
+        #[cfg(shell_execute)]
+        {
+          crate::api::process::kill_children();
+        }
+        #[cfg(all(windows, feature = "system-tray"))]
+        {
+          let _ = app_handle.remove_system_tray();
+        }
+      }
+      RuntimeRunEvent::UserEvent(event) => {
+        #[cfg(feature = "power")]
+        let event = match event.downcast::<crate::power::PowerMonitorEvent>() {
+          Ok(event) => {
+            manager.run_plugins_power_event(event.0);
+            callback(&app_handle, RunEvent::Power(event.0));
+            return;
+          }
+          Err(event) => event,
+        };
+        let event = match event.downcast::<deep_link::OpenUrlEvent>() {
+          Ok(event) => {
+            manager.run_plugins_open_url(&event.0);
+            callback(&app_handle, RunEvent::OpenUrl(event.0));
+            return;
+          }
+          Err(event) => event,
+        };
+        let event = match event.downcast::<open_file::OpenFileEvent>() {
+          Ok(event) => {
+            manager.run_plugins_open_file(&event.0);
+            callback(&app_handle, RunEvent::OpenFile(event.0));
+            return;
+          }
+          Err(event) => event,
+        };
+        callback(&app_handle, RunEvent::UserEvent(event))
+      }
+      _ => {}
+    });
+  }
 }
 
 #[cfg(feature = "updater")]
@@ -298,6 +637,15 @@ where
   /// The JS message handler.
   invoke_handler: Box<InvokeHandler<Args<E, L, MID, TID, A, R>>>,
 
+  /// The middlewares that wrap every invoke message.
+  invoke_middlewares: Vec<Box<InvokeMiddleware<Args<E, L, MID, TID, A, R>>>>,
+
+  /// The codec used to encode the command responses.
+  ipc_codec: Option<Arc<dyn IpcCodec>>,
+
+  /// The hooks that observe the resolved invokes.
+  invoke_observers: Vec<Box<OnInvokeObserved<Args<E, L, MID, TID, A, R>>>>,
+
   /// The setup hook.
   setup: SetupHook<Args<E, L, MID, TID, A, R>>,
 
@@ -313,6 +661,9 @@ where
   /// The webview protocols available to all windows.
   uri_scheme_protocols: HashMap<String, Arc<CustomProtocol>>,
 
+  /// The protocol handlers available to the `tauri > protocols` config.
+  protocol_handlers: HashMap<String, Arc<CustomProtocol>>,
+
   /// App state.
   state: StateManager,
 
@@ -324,9 +675,16 @@ where
   #[cfg(feature = "menu")]
   menu_event_listeners: Vec<GlobalMenuEventListener<Args<E, L, MID, TID, A, R>>>,
 
+  /// Touch Bar event handlers that listens to all windows.
+  #[cfg(feature = "touchbar")]
+  touchbar_event_listeners: Vec<GlobalTouchBarEventListener<Args<E, L, MID, TID, A, R>>>,
+
   /// Window event handlers that listens to all windows.
   window_event_listeners: Vec<GlobalWindowEventListener<Args<E, L, MID, TID, A, R>>>,
 
+  /// File drop handlers that listens to all windows.
+  file_drop_listeners: Vec<FileDropListener<Args<E, L, MID, TID, A, R>>>,
+
   /// The app system tray.
   #[cfg(feature = "system-tray")]
   system_tray: Option<tray::SystemTray<TID>>,
@@ -334,6 +692,10 @@ where
   /// System tray event handlers.
   #[cfg(feature = "system-tray")]
   system_tray_event_listeners: Vec<SystemTrayEventListener<Args<E, L, MID, TID, A, R>>>,
+
+  /// The handler of the invocations forwarded by the new instances of the app.
+  #[cfg(feature = "single-instance")]
+  single_instance: Option<SingleInstanceHandler<Args<E, L, MID, TID, A, R>>>,
 }
 
 impl<E, L, MID, TID, A, R> Builder<E, L, MID, TID, A, R>
@@ -350,20 +712,29 @@ where
     Self {
       setup: Box::new(|_| Ok(())),
       invoke_handler: Box::new(|_| ()),
+      invoke_middlewares: Vec::new(),
+      ipc_codec: None,
+      invoke_observers: Vec::new(),
       on_page_load: Box::new(|_, _| ()),
       pending_windows: Default::default(),
       plugins: PluginStore::default(),
       uri_scheme_protocols: Default::default(),
+      protocol_handlers: Default::default(),
       state: StateManager::new(),
       #[cfg(feature = "menu")]
       menu: None,
       #[cfg(feature = "menu")]
       menu_event_listeners: Vec::new(),
+      #[cfg(feature = "touchbar")]
+      touchbar_event_listeners: Vec::new(),
       window_event_listeners: Vec::new(),
+      file_drop_listeners: Vec::new(),
       #[cfg(feature = "system-tray")]
       system_tray: None,
       #[cfg(feature = "system-tray")]
       system_tray_event_listeners: Vec::new(),
+      #[cfg(feature = "single-instance")]
+      single_instance: None,
     }
   }
 
@@ -376,6 +747,82 @@ where
     self
   }
 
+  /// Adds a middleware that wraps every invoke message, including the core API and plugin commands.
+  ///
+  /// The middlewares run in the order they are added. Call `next` to continue the chain,
+  /// or reject the invoke to prevent the command from running.
+  ///
+  /// # Example
+  /// ```rust,no_run
+  /// tauri::Builder::default()
+  ///   .invoke_middleware(|invoke, next| {
+  ///     if invoke.message.command() == "delete_everything" {
+  ///       invoke.resolver.reject("unauthorized");
+  ///     } else {
+  ///       next(invoke);
+  ///     }
+  ///   });
+  /// ```
+  pub fn invoke_middleware<F>(mut self, middleware: F) -> Self
+  where
+    F: Fn(Invoke<Args<E, L, MID, TID, A, R>>, &dyn Fn(Invoke<Args<E, L, MID, TID, A, R>>))
+      + Send
+      + Sync
+      + 'static,
+  {
+    self.invoke_middlewares.push(Box::new(middleware));
+    self
+  }
+
+  /// Sets the codec used to encode the command responses instead of JSON.
+  ///
+  /// # Example
+  /// ```rust,ignore
+  /// tauri::Builder::default().ipc_codec(tauri::ipc::MessagePackCodec);
+  /// ```
+  pub fn ipc_codec<C: IpcCodec>(mut self, codec: C) -> Self {
+    self.ipc_codec.replace(Arc::new(codec));
+    self
+  }
+
+  /// Registers a hook that is called every time a command invocation is resolved,
+  /// reporting the command name, the window label, the arguments size, the duration and whether it succeeded.
+  ///
+  /// # Example
+  /// ```rust,ignore
+  /// tauri::Builder::default().on_invoke_observed(|observation| {
+  ///   if observation.duration() > std::time::Duration::from_millis(100) {
+  ///     println!("slow command {}: {:?}", observation.command(), observation.duration());
+  ///   }
+  /// });
+  /// ```
+  pub fn on_invoke_observed<F>(mut self, observer: F) -> Self
+  where
+    F: Fn(InvokeObservation<Args<E, L, MID, TID, A, R>>) + Send + Sync + 'static,
+  {
+    self.invoke_observers.push(Box::new(observer));
+    self
+  }
+
+  /// Restricts the given command to the windows with the given labels.
+  /// Calls from other windows reject the invoke promise.
+  ///
+  /// Commands can also be restricted with the `#[command(windows("main"))]` attribute.
+  pub fn command_windows<C: Into<String>>(self, command: C, windows: Vec<L>) -> Self {
+    let command = command.into();
+    self.invoke_middleware(move |invoke, next| {
+      let label = invoke.message.window_ref().label().clone();
+      if invoke.message.command() == command && !windows.contains(&label) {
+        invoke.resolver.reject(format!(
+          "command {} is not allowed on window {}",
+          command, label
+        ));
+      } else {
+        next(invoke);
+      }
+    })
+  }
+
   /// Defines the setup hook.
   pub fn setup<F>(mut self, setup: F) -> Self
   where
@@ -541,6 +988,19 @@ where
     self
   }
 
+  /// Registers a Touch Bar event handler for all windows.
+  #[cfg(feature = "touchbar")]
+  #[cfg_attr(doc_cfg, doc(cfg(feature = "touchbar")))]
+  pub fn on_touchbar_event<
+    F: Fn(WindowTouchBarEvent<Args<E, L, MID, TID, A, R>>) + Send + Sync + 'static,
+  >(
+    mut self,
+    handler: F,
+  ) -> Self {
+    self.touchbar_event_listeners.push(Box::new(handler));
+    self
+  }
+
   /// Registers a window event handler for all windows.
   pub fn on_window_event<
     F: Fn(GlobalWindowEvent<Args<E, L, MID, TID, A, R>>) + Send + Sync + 'static,
@@ -552,6 +1012,20 @@ where
     self
   }
 
+  /// Registers a file drop handler for all windows.
+  ///
+  /// The handler is called before the event is emitted to the webview.
+  /// Return `false` to deny the drop, so the frontend never receives the event.
+  pub fn on_file_drop<
+    F: Fn(&Window<Args<E, L, MID, TID, A, R>>, &FileDropEvent) -> bool + Send + Sync + 'static,
+  >(
+    mut self,
+    handler: F,
+  ) -> Self {
+    self.file_drop_listeners.push(Box::new(handler));
+    self
+  }
+
   /// Registers a system tray event handler.
   #[cfg(feature = "system-tray")]
   #[cfg_attr(doc_cfg, doc(cfg(feature = "system-tray")))]
@@ -565,6 +1039,36 @@ where
     self
   }
 
+  /// Enforces a single instance of the app.
+  ///
+  /// When the app is already running, [`Self::build`] forwards the new invocation's arguments
+  /// and working directory to the running instance and exits the process.
+  /// The running instance focuses its `main` window and calls the handler with them.
+  ///
+  /// The instances are identified by the `tauri > bundle > identifier` config.
+  ///
+  /// # Example
+  /// ```rust,ignore
+  /// tauri::Builder::default()
+  ///   .single_instance(|app, argv, cwd| {
+  ///     println!("{:?} opened from {}", argv, cwd);
+  ///     app.emit_all("open-args", argv).unwrap();
+  ///   })
+  ///   .run(tauri::generate_context!())
+  ///   .expect("error while running tauri application");
+  /// ```
+  #[cfg(feature = "single-instance")]
+  #[cfg_attr(doc_cfg, doc(cfg(feature = "single-instance")))]
+  pub fn single_instance<
+    F: Fn(&AppHandle<Args<E, L, MID, TID, A, R>>, Vec<String>, String) + Send + Sync + 'static,
+  >(
+    mut self,
+    handler: F,
+  ) -> Self {
+    self.single_instance.replace(Box::new(handler));
+    self
+  }
+
   /// Registers a URI scheme protocol available to all webviews.
   /// Leverages [setURLSchemeHandler](https://developer.apple.com/documentation/webkit/wkwebviewconfiguration/2875766-seturlschemehandler) on macOS,
   /// [AddWebResourceRequestedFilter](https://docs.microsoft.com/en-us/dotnet/api/microsoft.web.webview2.core.corewebview2.addwebresourcerequestedfilter?view=webview2-dotnet-1.0.774.44) on Windows
@@ -591,59 +1095,124 @@ where
     self
   }
 
+  /// Registers a protocol handler that the `tauri > protocols` config can map to URI schemes,
+  /// e.g. `"protocols": { "thumbnail": { "handler": "thumbnails" } }`.
+  ///
+  /// The handler takes an URL such as `thumbnail://localhost/photo.jpg`.
+  pub fn protocol_handler<
+    N: Into<String>,
+    H: Fn(&str) -> Result<Vec<u8>, Box<dyn std::error::Error>> + Send + Sync + 'static,
+  >(
+    mut self,
+    name: N,
+    handler: H,
+  ) -> Self {
+    self.protocol_handlers.insert(
+      name.into(),
+      Arc::new(CustomProtocol {
+        protocol: Box::new(handler),
+      }),
+    );
+    self
+  }
+
   /// Builds the application.
   #[allow(clippy::type_complexity)]
   pub fn build(mut self, context: Context<A>) -> crate::Result<App<Args<E, L, MID, TID, A, R>>> {
-    #[cfg(feature = "system-tray")]
-    let system_tray_icon = {
-      let icon = context.system_tray_icon.clone();
-
-      // check the icon format if the system tray is configured
-      if self.system_tray.is_some() {
-        use std::io::{Error, ErrorKind};
-        #[cfg(target_os = "linux")]
-        if let Some(Icon::Raw(_)) = icon {
-          return Err(crate::Error::InvalidIcon(Box::new(Error::new(
-            ErrorKind::InvalidInput,
-            "system tray icons on linux must be a file path",
-          ))));
+    #[cfg(not(feature = "isolation"))]
+    if let crate::Pattern::Isolation { .. } = context.pattern {
+      return Err(crate::Error::IsolationFeatureMissing);
+    }
+
+    // the socket is bound before anything else, so two instances started together can't both run
+    #[cfg(feature = "single-instance")]
+    let single_instance = match self.single_instance.take() {
+      Some(handler) => {
+        match single_instance::bind_or_forward(&context.config.tauri.bundle.identifier)? {
+          Some(listener) => Some((listener, handler)),
+          None => std::process::exit(0),
         }
+      }
+      None => None,
+    };
 
-        #[cfg(not(target_os = "linux"))]
-        if let Some(Icon::File(_)) = icon {
-          return Err(crate::Error::InvalidIcon(Box::new(Error::new(
-            ErrorKind::InvalidInput,
-            "system tray icons on non-linux platforms must be the raw bytes",
-          ))));
+    crate::api::process::SidecarCommand::verify_checksums(&context.sidecar_checksums)?;
+
+    // the protocols registered with `register_global_uri_scheme_protocol` take precedence
+    for (scheme, protocol) in &context.config.tauri.protocols {
+      if self.uri_scheme_protocols.contains_key(scheme) {
+        continue;
+      }
+      let protocol = match protocol {
+        ProtocolConfig::Dir { dir } => {
+          let dir = if dir.is_relative() {
+            crate::api::path::resource_dir(&context.package_info)
+              .ok_or_else(|| crate::Error::ProtocolDir(dir.clone()))?
+              .join(dir)
+          } else {
+            dir.clone()
+          };
+          Arc::new(dir_protocol(dir))
         }
+        ProtocolConfig::Handler { handler } => self
+          .protocol_handlers
+          .get(handler)
+          .cloned()
+          .ok_or_else(|| crate::Error::ProtocolHandlerNotFound(handler.clone()))?,
+      };
+      self.uri_scheme_protocols.insert(scheme.clone(), protocol);
+    }
+
+    // check the icon format if the system tray is configured
+    #[cfg(feature = "system-tray")]
+    if self.system_tray.is_some() {
+      use std::io::{Error, ErrorKind};
+      #[cfg(target_os = "linux")]
+      if let Some(Icon::Raw(_)) = &context.system_tray_icon {
+        return Err(crate::Error::InvalidIcon(Box::new(Error::new(
+          ErrorKind::InvalidInput,
+          "system tray icons on linux must be a file path",
+        ))));
       }
 
-      icon
-    };
+      #[cfg(not(target_os = "linux"))]
+      if let Some(Icon::File(_)) = &context.system_tray_icon {
+        return Err(crate::Error::InvalidIcon(Box::new(Error::new(
+          ErrorKind::InvalidInput,
+          "system tray icons on non-linux platforms must be the raw bytes",
+        ))));
+      }
+    }
 
     let manager = WindowManager::with_handlers(
       context,
       self.plugins,
       self.invoke_handler,
+      self.invoke_middlewares,
+      self.ipc_codec,
+      self.invoke_observers,
       self.on_page_load,
       self.uri_scheme_protocols,
       self.state,
       self.window_event_listeners,
+      self.file_drop_listeners,
       #[cfg(feature = "menu")]
       (self.menu, self.menu_event_listeners),
+      #[cfg(feature = "touchbar")]
+      self.touchbar_event_listeners,
     );
 
     // set up all the windows defined in the config
     for config in manager.config().tauri.windows.clone() {
-      let url = config.url.clone();
       let label = config
         .label
         .parse()
         .unwrap_or_else(|_| panic!("bad label found in config: {}", config.label));
 
+      let webview_attributes = WebviewAttributes::from(&config);
       self.pending_windows.push(PendingWindow::with_config(
         config,
-        WebviewAttributes::new(url),
+        webview_attributes,
         label,
       ));
     }
@@ -654,13 +1223,9 @@ where
     let mut app = App {
       runtime: Some(runtime),
       manager: manager.clone(),
-      #[cfg(feature = "system-tray")]
-      tray_handle: None,
       handle: AppHandle {
         runtime_handle,
         manager,
-        #[cfg(feature = "system-tray")]
-        tray_handle: None,
       },
     };
 
@@ -691,37 +1256,15 @@ where
     (self.setup)(&mut app).map_err(|e| crate::Error::Setup(e))?;
 
     #[cfg(feature = "system-tray")]
-    if let Some(system_tray) = self.system_tray {
-      let mut ids = HashMap::new();
-      if let Some(menu) = system_tray.menu() {
-        tray::get_menu_ids(&mut ids, menu);
-      }
-      let mut tray = tray::SystemTray::new();
-      if let Some(menu) = system_tray.menu {
-        tray = tray.with_menu(menu);
+    {
+      if let Some(system_tray) = self.system_tray {
+        app
+          .create_system_tray(system_tray)
+          .expect("failed to run tray");
       }
-      let tray_handler = app
-        .runtime
-        .as_ref()
-        .unwrap()
-        .system_tray(
-          tray.with_icon(
-            system_tray
-              .icon
-              .or(system_tray_icon)
-              .expect("tray icon not found; please configure it on tauri.conf.json"),
-          ),
-        )
-        .expect("failed to run tray");
-      let tray_handle = tray::SystemTrayHandle {
-        ids: Arc::new(ids.clone()),
-        inner: tray_handler,
-      };
-      app.tray_handle.replace(tray_handle.clone());
-      app.handle.tray_handle.replace(tray_handle);
+      // the listeners are registered even without a tray, which can be created later
       for listener in self.system_tray_event_listeners {
         let app_handle = app.handle();
-        let ids = ids.clone();
         let listener = Arc::new(std::sync::Mutex::new(listener));
         app
           .runtime
@@ -730,9 +1273,17 @@ where
           .on_system_tray_event(move |event| {
             let app_handle = app_handle.clone();
             let event = match event {
-              RuntimeSystemTrayEvent::MenuItemClick(id) => tray::SystemTrayEvent::MenuItemClick {
-                id: ids.get(id).unwrap().clone(),
-              },
+              RuntimeSystemTrayEvent::MenuItemClick(id) => {
+                let id = match app_handle
+                  .manager
+                  .tray_handle()
+                  .and_then(|tray_handle| tray_handle.ids.get(id).cloned())
+                {
+                  Some(id) => id,
+                  None => return,
+                };
+                tray::SystemTrayEvent::MenuItemClick { id }
+              }
               RuntimeSystemTrayEvent::LeftClick { position, size } => {
                 tray::SystemTrayEvent::LeftClick {
                   position: *position,
@@ -760,25 +1311,49 @@ where
       }
     }
 
+    app
+      .manager
+      .set_runtime_handle(app.handle.runtime_handle.clone());
+
+    #[cfg(feature = "single-instance")]
+    if let Some((listener, handler)) = single_instance {
+      single_instance::listen(listener, app.handle(), handler);
+    }
+
     Ok(app)
   }
 
   /// Runs the configured Tauri application.
+  ///
+  /// Use [`Self::build`] and [`App::run`] to handle the application events.
   pub fn run(self, context: Context<A>) -> crate::Result<()> {
-    let mut app = self.build(context)?;
-    #[cfg(all(windows, feature = "system-tray"))]
-    let app_handle = app.handle();
-    app.runtime.take().unwrap().run(move || {
-      #[cfg(shell_execute)]
-      {
-        crate::api::process::kill_children();
-      }
-      #[cfg(all(windows, feature = "system-tray"))]
+    self.build(context)?.run(|_, _| {});
+    Ok(())
+  }
+}
+
+/// Serves the files of the folder, e.g. `docs://localhost/guide/index.html` reads `guide/index.html`.
+fn dir_protocol(dir: PathBuf) -> CustomProtocol {
+  CustomProtocol {
+    protocol: Box::new(move |request| {
+      let url = url::Url::parse(request)?;
+      let path = percent_encoding::percent_decode(url.path().as_bytes())
+        .decode_utf8_lossy()
+        .to_string();
+      let path = match path.trim_matches('/') {
+        "" => "index.html",
+        path => path,
+      };
+      // don't serve the files outside of the folder
+      if Path::new(path)
+        .components()
+        .any(|c| !matches!(c, Component::Normal(_)))
       {
-        let _ = app_handle.remove_system_tray();
+        return Err(Box::new(crate::Error::ProtocolFileNotFound(path.into())));
       }
-    });
-    Ok(())
+      std::fs::read(dir.join(path))
+        .map_err(|_| Box::new(crate::Error::ProtocolFileNotFound(path.into())) as _)
+    }),
   }
 }
 
@@ -0,0 +1,101 @@
+// Copyright 2019-2021 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+use crate::{api::assets::Assets, manager::WindowManager, Params};
+
+use std::path::Path;
+
+/// An asset embedded in the binary.
+#[derive(Debug, Clone)]
+pub struct Asset {
+  /// The decompressed content.
+  pub bytes: Vec<u8>,
+  /// The MIME type, guessed from the file extension.
+  pub mime: String,
+}
+
+/// Reads the assets embedded in the binary, i.e. the `distDir` files.
+///
+/// When the app loads the `devPath` URL there are no embedded assets.
+///
+/// # Example
+/// ```rust,no_run
+/// tauri::Builder::default()
+///   .setup(|app| {
+///     if let Some(template) = app.asset_resolver().get("templates/report.html") {
+///       println!("{} bytes of {}", template.bytes.len(), template.mime);
+///     }
+///     Ok(())
+///   });
+/// ```
+pub struct AssetResolver<P: Params> {
+  pub(crate) manager: WindowManager<P>,
+}
+
+impl<P: Params> AssetResolver<P> {
+  /// Gets the asset at the given path, relative to the `distDir`.
+  pub fn get(&self, path: impl AsRef<str>) -> Option<Asset> {
+    let path = path.as_ref();
+    self.manager.assets().get(path).map(|bytes| Asset {
+      bytes: bytes.into_owned(),
+      mime: mime_type(path).into(),
+    })
+  }
+
+  /// Iterates over the paths of the assets, e.g. `/index.html`.
+  pub fn iter(&self) -> impl Iterator<Item = &str> + '_ {
+    self.manager.assets().iter()
+  }
+}
+
+/// The MIME type of the file extension, defaulting to `application/octet-stream`.
+pub(crate) fn mime_type(path: &str) -> &'static str {
+  let extension = Path::new(path)
+    .extension()
+    .and_then(|ext| ext.to_str())
+    .map(str::to_lowercase)
+    .unwrap_or_default();
+  match extension.as_str() {
+    "html" | "htm" => "text/html",
+    "css" => "text/css",
+    "js" | "mjs" => "text/javascript",
+    "json" | "map" => "application/json",
+    "wasm" => "application/wasm",
+    "txt" => "text/plain",
+    "csv" => "text/csv",
+    "xml" => "application/xml",
+    "svg" => "image/svg+xml",
+    "png" => "image/png",
+    "jpg" | "jpeg" => "image/jpeg",
+    "gif" => "image/gif",
+    "webp" => "image/webp",
+    "avif" => "image/avif",
+    "ico" => "image/vnd.microsoft.icon",
+    "woff" => "font/woff",
+    "woff2" => "font/woff2",
+    "ttf" => "font/ttf",
+    "otf" => "font/otf",
+    "mp3" => "audio/mpeg",
+    "ogg" => "audio/ogg",
+    "wav" => "audio/wav",
+    "mp4" => "video/mp4",
+    "webm" => "video/webm",
+    "pdf" => "application/pdf",
+    "zip" => "application/zip",
+    _ => "application/octet-stream",
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use super::mime_type;
+
+  #[test]
+  fn guesses_mime_type() {
+    assert_eq!(mime_type("/index.html"), "text/html");
+    assert_eq!(mime_type("assets/Logo.PNG"), "image/png");
+    assert_eq!(mime_type("fonts/inter.woff2"), "font/woff2");
+    assert_eq!(mime_type("LICENSE"), "application/octet-stream");
+  }
+}
@@ -33,3 +33,20 @@ where
   let runtime = RUNTIME.get_or_init(|| Runtime::new().unwrap());
   runtime.spawn(task);
 }
+
+/// Runs a blocking function on the runtime thread pool, returning a future resolving to its result.
+///
+/// The function starts running immediately, even if the future isn't polled.
+pub(crate) fn spawn_blocking<F, R>(f: F) -> impl Future<Output = R>
+where
+  F: FnOnce() -> R + Send + 'static,
+  R: Send + 'static,
+{
+  let runtime = RUNTIME.get_or_init(|| Runtime::new().unwrap());
+  let task = runtime.spawn_blocking(f);
+  async move {
+    task
+      .await
+      .unwrap_or_else(|e| std::panic::resume_unwind(e.into_panic()))
+  }
+}
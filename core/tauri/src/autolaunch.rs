@@ -0,0 +1,298 @@
+// Copyright 2019-2021 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+//! Launching the app at login, with the Login Items on macOS,
+//! the `Run` registry key on Windows and the XDG autostart directory on Linux.
+//!
+//! # Example
+//!
+//! ```rust,ignore
+//! fn main() {
+//!   tauri::Builder::default()
+//!     .setup(|app| {
+//!       let autolaunch = app.handle().autolaunch()?;
+//!       if !autolaunch.is_enabled()? {
+//!         autolaunch.enable(true)?;
+//!       }
+//!       if tauri::autolaunch::is_launched_hidden() {
+//!         app.get_window("main").unwrap().hide()?;
+//!       }
+//!       Ok(())
+//!     })
+//!     .run(tauri::generate_context!())
+//!     .expect("error while running tauri application");
+//! }
+//! ```
+
+use std::path::PathBuf;
+
+/// The argument passed to the app when it's launched hidden at login on Windows and Linux.
+pub const HIDDEN_ARG: &str = "--autolaunch-hidden";
+
+/// Whether the app was launched at login with [`AutoLaunch::enable`]`(true)`.
+///
+/// Always `false` on macOS, where the system hides the app itself.
+pub fn is_launched_hidden() -> bool {
+  std::env::args().skip(1).any(|arg| arg == HIDDEN_ARG)
+}
+
+/// Registers the app to launch when the user logs in, created with [`crate::AppHandle::autolaunch`].
+#[derive(Debug, Clone)]
+pub struct AutoLaunch {
+  /// The app name, used as the login item and registry value name.
+  name: String,
+  /// The bundle identifier, used as the autostart desktop file name.
+  #[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+  identifier: String,
+  /// The path launched at login: the app bundle on macOS, the AppImage or the executable.
+  path: PathBuf,
+}
+
+impl AutoLaunch {
+  pub(crate) fn new(name: String, identifier: String) -> crate::Result<Self> {
+    let path = launch_path()?;
+    Ok(Self {
+      name,
+      identifier,
+      path,
+    })
+  }
+
+  /// Launches the app at login, hidden if `hidden` is `true`.
+  ///
+  /// Calling it again replaces the previous registration.
+  pub fn enable(&self, hidden: bool) -> crate::Result<()> {
+    platform::enable(self, hidden)
+  }
+
+  /// Stops launching the app at login.
+  pub fn disable(&self) -> crate::Result<()> {
+    platform::disable(self)
+  }
+
+  /// Whether the app launches at login.
+  pub fn is_enabled(&self) -> crate::Result<bool> {
+    platform::is_enabled(self)
+  }
+}
+
+#[cfg(target_os = "macos")]
+fn launch_path() -> crate::Result<PathBuf> {
+  let exe = std::env::current_exe()?;
+  // `MyApp.app/Contents/MacOS/my-app`
+  let bundle = exe
+    .ancestors()
+    .nth(3)
+    .filter(|bundle| {
+      bundle
+        .extension()
+        .map(|ext| ext == "app")
+        .unwrap_or_default()
+    })
+    .map(PathBuf::from);
+  Ok(bundle.unwrap_or(exe))
+}
+
+#[cfg(target_os = "linux")]
+fn launch_path() -> crate::Result<PathBuf> {
+  // the AppImage is mounted on a temporary directory while running
+  match std::env::var_os("APPIMAGE") {
+    Some(appimage) => Ok(PathBuf::from(appimage)),
+    None => std::env::current_exe().map_err(Into::into),
+  }
+}
+
+#[cfg(windows)]
+fn launch_path() -> crate::Result<PathBuf> {
+  std::env::current_exe().map_err(Into::into)
+}
+
+#[cfg(target_os = "macos")]
+mod platform {
+  use super::AutoLaunch;
+
+  use std::process::Command;
+
+  fn escape(value: &str) -> String {
+    value.replace('\\', "\\\\").replace('"', "\\\"")
+  }
+
+  /// Runs an AppleScript command on `System Events`, returning its output.
+  fn system_events(command: &str) -> crate::Result<String> {
+    let output = Command::new("osascript")
+      .arg("-e")
+      .arg(format!("tell application \"System Events\" to {}", command))
+      .output()?;
+    if output.status.success() {
+      Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+    } else {
+      Err(crate::Error::AutoLaunch(
+        String::from_utf8_lossy(&output.stderr).trim().to_string(),
+      ))
+    }
+  }
+
+  pub fn enable(autolaunch: &AutoLaunch, hidden: bool) -> crate::Result<()> {
+    disable(autolaunch)?;
+    system_events(&format!(
+      "make login item at end with properties {{path:\"{}\", hidden:{}, name:\"{}\"}}",
+      escape(&autolaunch.path.display().to_string()),
+      hidden,
+      escape(&autolaunch.name)
+    ))
+    .map(|_| ())
+  }
+
+  pub fn disable(autolaunch: &AutoLaunch) -> crate::Result<()> {
+    system_events(&format!(
+      "delete every login item whose name is \"{}\"",
+      escape(&autolaunch.name)
+    ))
+    .map(|_| ())
+  }
+
+  pub fn is_enabled(autolaunch: &AutoLaunch) -> crate::Result<bool> {
+    // the names are listed separated by commas, which the names can contain
+    let exists = system_events(&format!(
+      "exists login item \"{}\"",
+      escape(&autolaunch.name)
+    ))?;
+    Ok(exists == "true")
+  }
+}
+
+#[cfg(windows)]
+mod platform {
+  use super::{AutoLaunch, HIDDEN_ARG};
+
+  use winreg::{
+    enums::{HKEY_CURRENT_USER, KEY_SET_VALUE},
+    RegKey,
+  };
+
+  const RUN_KEY: &str = "Software\\Microsoft\\Windows\\CurrentVersion\\Run";
+
+  pub fn enable(autolaunch: &AutoLaunch, hidden: bool) -> crate::Result<()> {
+    let (key, _) = RegKey::predef(HKEY_CURRENT_USER).create_subkey(RUN_KEY)?;
+    let mut command = format!("\"{}\"", autolaunch.path.display());
+    if hidden {
+      command.push(' ');
+      command.push_str(HIDDEN_ARG);
+    }
+    key.set_value(&autolaunch.name, &command)?;
+    Ok(())
+  }
+
+  pub fn disable(autolaunch: &AutoLaunch) -> crate::Result<()> {
+    let key = match RegKey::predef(HKEY_CURRENT_USER).open_subkey_with_flags(RUN_KEY, KEY_SET_VALUE)
+    {
+      Ok(key) => key,
+      Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(()),
+      Err(e) => return Err(e.into()),
+    };
+    match key.delete_value(&autolaunch.name) {
+      Err(e) if e.kind() != std::io::ErrorKind::NotFound => Err(e.into()),
+      _ => Ok(()),
+    }
+  }
+
+  pub fn is_enabled(autolaunch: &AutoLaunch) -> crate::Result<bool> {
+    Ok(
+      RegKey::predef(HKEY_CURRENT_USER)
+        .open_subkey(RUN_KEY)
+        .and_then(|key| key.get_value::<String, _>(&autolaunch.name))
+        .is_ok(),
+    )
+  }
+}
+
+#[cfg(target_os = "linux")]
+mod platform {
+  use super::{AutoLaunch, HIDDEN_ARG};
+
+  use std::path::PathBuf;
+
+  fn desktop_file_path(autolaunch: &AutoLaunch) -> crate::Result<PathBuf> {
+    let config_dir = crate::api::path::config_dir()
+      .ok_or_else(|| crate::Error::AutoLaunch("unable to resolve the config directory".into()))?;
+    Ok(
+      config_dir
+        .join("autostart")
+        .join(format!("{}.desktop", autolaunch.identifier)),
+    )
+  }
+
+  /// Escapes a value of the `string` and `localestring` types of the Desktop Entry specification.
+  pub(super) fn escape_value(value: &str) -> String {
+    value
+      .replace('\\', "\\\\")
+      .replace('\n', "\\n")
+      .replace('\t', "\\t")
+      .replace('\r', "\\r")
+  }
+
+  /// Quotes an argument of the `Exec` key, escaping the reserved characters and the field codes.
+  pub(super) fn quote_exec_arg(arg: &str) -> String {
+    let mut quoted = String::from("\"");
+    for c in arg.chars() {
+      match c {
+        '"' | '`' | '$' | '\\' => {
+          quoted.push('\\');
+          quoted.push(c);
+        }
+        '%' => quoted.push_str("%%"),
+        _ => quoted.push(c),
+      }
+    }
+    quoted.push('"');
+    quoted
+  }
+
+  pub fn enable(autolaunch: &AutoLaunch, hidden: bool) -> crate::Result<()> {
+    let path = desktop_file_path(autolaunch)?;
+    if let Some(parent) = path.parent() {
+      std::fs::create_dir_all(parent)?;
+    }
+    let mut exec = quote_exec_arg(&autolaunch.path.display().to_string());
+    if hidden {
+      exec.push(' ');
+      exec.push_str(HIDDEN_ARG);
+    }
+    std::fs::write(
+      path,
+      format!(
+        "[Desktop Entry]\nType=Application\nName={}\nExec={}\nTerminal=false\nX-GNOME-Autostart-enabled=true\n",
+        escape_value(&autolaunch.name),
+        escape_value(&exec)
+      ),
+    )?;
+    Ok(())
+  }
+
+  pub fn disable(autolaunch: &AutoLaunch) -> crate::Result<()> {
+    match std::fs::remove_file(desktop_file_path(autolaunch)?) {
+      Err(e) if e.kind() != std::io::ErrorKind::NotFound => Err(e.into()),
+      _ => Ok(()),
+    }
+  }
+
+  pub fn is_enabled(autolaunch: &AutoLaunch) -> crate::Result<bool> {
+    Ok(desktop_file_path(autolaunch)?.exists())
+  }
+}
+
+#[cfg(all(test, target_os = "linux"))]
+mod tests {
+  #[test]
+  fn escapes_the_desktop_entry() {
+    assert_eq!(
+      super::platform::quote_exec_arg("/opt/my \"app\"/$HOME/100%/a\\b"),
+      "\"/opt/my \\\"app\\\"/\\$HOME/100%%/a\\\\b\""
+    );
+    assert_eq!(
+      super::platform::escape_value("My\\App\nName"),
+      "My\\\\App\\nName"
+    );
+  }
+}
@@ -0,0 +1,108 @@
+// Copyright 2019-2021 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+use std::{
+  any::Any,
+  collections::HashMap,
+  fmt,
+  sync::{Arc, Mutex},
+};
+use uuid::Uuid;
+
+/// Represents a subscription to a message bus topic.
+#[derive(Debug, Clone, Copy, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct BusSubscription(Uuid);
+
+impl fmt::Display for BusSubscription {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    self.0.fmt(f)
+  }
+}
+
+type Handler = Arc<dyn Fn(&dyn Any) + Send + Sync>;
+
+/// An in-process bus where plugins publish and subscribe to typed messages by topic.
+#[derive(Clone, Default)]
+pub(crate) struct MessageBus {
+  topics: Arc<Mutex<HashMap<String, Vec<(BusSubscription, Handler)>>>>,
+}
+
+impl MessageBus {
+  /// Subscribes to the messages of type `T` published on the given topic.
+  pub(crate) fn subscribe<T, F>(&self, topic: String, handler: F) -> BusSubscription
+  where
+    T: 'static,
+    F: Fn(&T) + Send + Sync + 'static,
+  {
+    let id = BusSubscription(Uuid::new_v4());
+    let handler: Handler = Arc::new(move |message: &dyn Any| {
+      if let Some(message) = message.downcast_ref::<T>() {
+        handler(message)
+      }
+    });
+    self
+      .topics
+      .lock()
+      .expect("poisoned message bus")
+      .entry(topic)
+      .or_insert_with(Vec::new)
+      .push((id, handler));
+    id
+  }
+
+  /// Removes a subscription.
+  pub(crate) fn unsubscribe(&self, id: BusSubscription) {
+    let mut topics = self.topics.lock().expect("poisoned message bus");
+    for handlers in topics.values_mut() {
+      handlers.retain(|(handler_id, _)| *handler_id != id);
+    }
+    topics.retain(|_, handlers| !handlers.is_empty());
+  }
+
+  /// Delivers the message to the subscribers of the topic expecting its type.
+  pub(crate) fn publish<T: Send + Sync + 'static>(&self, topic: &str, message: T) {
+    // clone the handlers so they can (un)subscribe without a deadlock
+    let handlers: Vec<Handler> = self
+      .topics
+      .lock()
+      .expect("poisoned message bus")
+      .get(topic)
+      .map(|handlers| handlers.iter().map(|(_, h)| h.clone()).collect())
+      .unwrap_or_default();
+    for handler in handlers {
+      handler(&message);
+    }
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+  use std::sync::atomic::{AtomicUsize, Ordering};
+
+  #[derive(Debug, PartialEq)]
+  struct Login(&'static str);
+
+  #[test]
+  fn delivers_typed_messages() {
+    let bus = MessageBus::default();
+    let received = Arc::new(AtomicUsize::new(0));
+
+    let received_ = received.clone();
+    let id = bus.subscribe("auth".into(), move |login: &Login| {
+      assert_eq!(login, &Login("user"));
+      received_.fetch_add(1, Ordering::SeqCst);
+    });
+
+    bus.publish("auth", Login("user"));
+    // other types and topics are ignored
+    bus.publish("auth", "login");
+    bus.publish("sync", Login("user"));
+    assert_eq!(received.load(Ordering::SeqCst), 1);
+
+    bus.unsubscribe(id);
+    bus.publish("auth", Login("user"));
+    assert_eq!(received.load(Ordering::SeqCst), 1);
+  }
+}
@@ -51,6 +51,15 @@ impl<'de, D: Deserialize<'de>, P: Params> CommandArg<'de, P> for D {
   }
 }
 
+/// Whether the window that sent the message is on the given list of labels.
+///
+/// Used by commands restricted with `#[command(windows("main"))]`.
+#[doc(hidden)]
+pub fn is_window_allowed<P: Params>(message: &InvokeMessage<P>, windows: &[&str]) -> bool {
+  let label = message.window_ref().label().to_string();
+  windows.iter().any(|window| *window == label)
+}
+
 /// Pass the result of [`serde_json::Value::get`] into [`serde_json::Value`]'s deserializer.
 ///
 /// Returns an error if the [`CommandItem`]'s key does not exist in the value.
@@ -0,0 +1,126 @@
+// Copyright 2019-2021 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+//! Delivery of the URLs opened with the schemes registered on `tauri.conf.json > tauri > bundle > urlSchemes`.
+//!
+//! On Windows and Linux the URL is passed as an argument of a new process,
+//! so the arguments of the first instance and the ones forwarded by the next instances are scanned for URLs.
+//! On macOS the URL is sent as an Apple Event to the running process, including when it launches the app.
+
+use url::Url;
+
+/// A URL opened with one of the app schemes, sent to the event loop.
+///
+/// It is a distinct type so the events sent by the application with an [`crate::EventLoopProxy`] can't be mistaken for it.
+pub(crate) struct OpenUrlEvent(pub(crate) Url);
+
+/// The arguments that are URLs with one of the given schemes.
+pub(crate) fn urls_from_args<I: IntoIterator<Item = String>>(
+  args: I,
+  schemes: &[String],
+) -> Vec<Url> {
+  args
+    .into_iter()
+    .filter_map(|arg| Url::parse(&arg).ok())
+    .filter(|url| {
+      schemes
+        .iter()
+        .any(|scheme| scheme.eq_ignore_ascii_case(url.scheme()))
+    })
+    .collect()
+}
+
+#[cfg(target_os = "macos")]
+pub(crate) use macos::listen_apple_events;
+
+#[cfg(target_os = "macos")]
+mod macos {
+  use objc::{
+    class,
+    declare::ClassDecl,
+    msg_send,
+    runtime::{Object, Sel},
+    sel, sel_impl,
+  };
+  use once_cell::sync::OnceCell;
+  use url::Url;
+
+  use std::{ffi::CStr, os::raw::c_char, sync::Mutex};
+
+  /// `kInternetEventClass` and `kAEGetURL`, the `GURL` four-character code.
+  const GET_URL_EVENT: u32 = 0x4755_524c;
+  /// `keyDirectObject`, the `----` four-character code.
+  const KEY_DIRECT_OBJECT: u32 = 0x2d2d_2d2d;
+
+  type Sender = Box<dyn Fn(Url) -> bool + Send>;
+
+  static SENDER: OnceCell<Mutex<Sender>> = OnceCell::new();
+
+  extern "C" fn handle_get_url(_this: &Object, _cmd: Sel, event: *mut Object, _reply: *mut Object) {
+    let url = unsafe {
+      let descriptor: *mut Object = msg_send![event, paramDescriptorForKeyword: KEY_DIRECT_OBJECT];
+      if descriptor.is_null() {
+        return;
+      }
+      let string: *mut Object = msg_send![descriptor, stringValue];
+      if string.is_null() {
+        return;
+      }
+      let bytes: *const c_char = msg_send![string, UTF8String];
+      if bytes.is_null() {
+        return;
+      }
+      CStr::from_ptr(bytes).to_string_lossy().into_owned()
+    };
+    if let (Ok(url), Some(send)) = (Url::parse(&url), SENDER.get()) {
+      (send.lock().expect("poisoned deep link sender"))(url);
+    }
+  }
+
+  /// Registers the `GURL` Apple Event handler, calling `send` with the opened URLs.
+  ///
+  /// Must be called on the main thread before the event loop starts, so the URL that launched the app isn't missed.
+  pub(crate) fn listen_apple_events<F: Fn(Url) -> bool + Send + 'static>(send: F) {
+    if SENDER.set(Mutex::new(Box::new(send))).is_err() {
+      return;
+    }
+    let mut decl = match ClassDecl::new("TauriGetUrlHandler", class!(NSObject)) {
+      Some(decl) => decl,
+      None => return,
+    };
+    unsafe {
+      decl.add_method(
+        sel!(handleGetUrl:withReplyEvent:),
+        handle_get_url as extern "C" fn(&Object, Sel, *mut Object, *mut Object),
+      );
+      let handler_class = decl.register();
+      let handler: *mut Object = msg_send![handler_class, new];
+      let event_manager: *mut Object =
+        msg_send![class!(NSAppleEventManager), sharedAppleEventManager];
+      let _: () = msg_send![event_manager,
+        setEventHandler: handler
+        andSelector: sel!(handleGetUrl:withReplyEvent:)
+        forEventClass: GET_URL_EVENT
+        andEventID: GET_URL_EVENT];
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn finds_urls_with_the_app_schemes() {
+    let schemes = vec!["myapp".to_string()];
+    let args = vec![
+      "--verbose".to_string(),
+      "https://tauri.studio".to_string(),
+      "MyApp://auth/callback?code=42".to_string(),
+    ];
+    let urls = urls_from_args(args, &schemes);
+    assert_eq!(urls.len(), 1);
+    assert_eq!(urls[0].as_str(), "myapp://auth/callback?code=42");
+  }
+}
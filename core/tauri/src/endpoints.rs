@@ -19,13 +19,18 @@ mod event;
 #[allow(unused_imports)]
 mod file_system;
 mod global_shortcut;
+#[cfg(http_request)]
 mod http;
 mod internal;
 mod notification;
 mod process;
 mod shell;
+mod system;
 mod window;
 
+#[cfg(fs_watch)]
+pub(crate) use file_system::remove_window_watchers;
+
 /// The response for a JS `invoke` call.
 pub struct InvokeResponse {
   json: crate::Result<JsonValue>,
@@ -52,8 +57,13 @@ enum Module {
   Dialog(dialog::Cmd),
   Cli(cli::Cmd),
   Notification(notification::Cmd),
+  #[cfg(http_request)]
   Http(http::Cmd),
+  // the HTTP client isn't compiled without the allowlist flag
+  #[cfg(not(http_request))]
+  Http(JsonValue),
   GlobalShortcut(global_shortcut::Cmd),
+  System(system::Cmd),
 }
 
 impl Module {
@@ -67,7 +77,7 @@ impl Module {
     match self {
       Self::App(cmd) => resolver.respond_async(async move {
         cmd
-          .run(package_info)
+          .run(config, package_info)
           .and_then(|r| r.json)
           .map_err(InvokeError::from)
       }),
@@ -75,7 +85,7 @@ impl Module {
         .respond_async(async move { cmd.run().and_then(|r| r.json).map_err(InvokeError::from) }),
       Self::Fs(cmd) => resolver.respond_async(async move {
         cmd
-          .run(config, &package_info)
+          .run(window, config, &package_info)
           .and_then(|r| r.json)
           .map_err(InvokeError::from)
       }),
@@ -89,6 +99,7 @@ impl Module {
       Self::Shell(cmd) => resolver.respond_async(async move {
         cmd
           .run(window)
+          .await
           .and_then(|r| r.json)
           .map_err(InvokeError::from)
       }),
@@ -138,23 +149,30 @@ impl Module {
       }
       Self::Notification(cmd) => resolver.respond_closure(move || {
         cmd
-          .run(config, &package_info)
+          .run(window, config, &package_info)
           .and_then(|r| r.json)
           .map_err(InvokeError::from)
       }),
+      #[cfg(http_request)]
       Self::Http(cmd) => resolver.respond_async(async move {
         cmd
-          .run()
+          .run(window)
           .await
           .and_then(|r| r.json)
           .map_err(InvokeError::from)
       }),
+      #[cfg(not(http_request))]
+      Self::Http(_) => {
+        resolver.reject(crate::Error::ApiNotAllowlisted("http > request".to_string()).to_string())
+      }
       Self::GlobalShortcut(cmd) => resolver.respond_async(async move {
         cmd
           .run(window)
           .and_then(|r| r.json)
           .map_err(InvokeError::from)
       }),
+      Self::System(cmd) => resolver
+        .respond_async(async move { cmd.run().and_then(|r| r.json).map_err(InvokeError::from) }),
     }
   }
 }
@@ -3,8 +3,10 @@
 // SPDX-License-Identifier: MIT
 
 use super::InvokeResponse;
-use crate::api::PackageInfo;
-use serde::Deserialize;
+use crate::api::{config::Config, PackageInfo};
+use serde::{Deserialize, Serialize};
+
+use std::sync::Arc;
 
 /// The API descriptor.
 #[derive(Deserialize)]
@@ -17,14 +19,73 @@ pub enum Cmd {
   GetAppName,
   /// Get Tauri Version
   GetTauriVersion,
+  /// Get the application config values exposed to the frontend
+  GetAppConfig,
+}
+
+/// The `tauri.conf.json` values exposed to the frontend.
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+#[cfg_attr(not(app_all), allow(dead_code))]
+struct AppConfig {
+  product_name: Option<String>,
+  version: Option<String>,
+  identifier: String,
+  windows: Vec<WindowDefaults>,
+}
+
+/// The initial state of a window defined on `tauri.conf.json`.
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+#[cfg_attr(not(app_all), allow(dead_code))]
+struct WindowDefaults {
+  label: String,
+  title: String,
+  width: f64,
+  height: f64,
+  resizable: bool,
+  fullscreen: bool,
+}
+
+#[cfg(app_all)]
+impl AppConfig {
+  fn new(config: &Config) -> Self {
+    Self {
+      product_name: config.package.product_name.clone(),
+      version: config.package.version.clone(),
+      identifier: config.tauri.bundle.identifier.clone(),
+      windows: config
+        .tauri
+        .windows
+        .iter()
+        .map(|window| WindowDefaults {
+          label: window.label.clone(),
+          title: window.title.clone(),
+          width: window.width,
+          height: window.height,
+          resizable: window.resizable,
+          fullscreen: window.fullscreen,
+        })
+        .collect(),
+    }
+  }
 }
 
 impl Cmd {
-  pub fn run(self, package_info: PackageInfo) -> crate::Result<InvokeResponse> {
+  #[allow(unused_variables)]
+  pub fn run(
+    self,
+    config: Arc<Config>,
+    package_info: PackageInfo,
+  ) -> crate::Result<InvokeResponse> {
     match self {
       Self::GetAppVersion => Ok(package_info.version.into()),
       Self::GetAppName => Ok(package_info.name.into()),
       Self::GetTauriVersion => Ok(env!("CARGO_PKG_VERSION").into()),
+      #[cfg(app_all)]
+      Self::GetAppConfig => Ok(AppConfig::new(&config).into()),
+      #[cfg(not(app_all))]
+      Self::GetAppConfig => Err(crate::Error::ApiNotAllowlisted("app > all".to_string())),
     }
   }
 }
@@ -3,12 +3,13 @@
 // SPDX-License-Identifier: MIT
 
 use super::InvokeResponse;
+#[cfg(dialog_message)]
+use crate::api::dialog::message as message_dialog;
 #[cfg(any(dialog_open, dialog_save))]
 use crate::api::dialog::FileDialogBuilder;
-use crate::{
-  api::dialog::{ask as ask_dialog, message as message_dialog, AskResponse},
-  Params, Window,
-};
+#[cfg(dialog_ask)]
+use crate::api::dialog::{ask as ask_dialog, AskResponse};
+use crate::{Params, Window};
 use serde::Deserialize;
 
 use std::path::PathBuf;
@@ -47,6 +48,8 @@ pub struct SaveDialogOptions {
   pub filters: Vec<DialogFilter>,
   /// The initial path of the dialog.
   pub default_path: Option<PathBuf>,
+  /// The initial file name of the dialog.
+  pub file_name: Option<String>,
 }
 
 /// The API descriptor.
@@ -85,6 +88,7 @@ impl Cmd {
       #[cfg(not(dialog_save))]
       Self::SaveDialog { .. } => Err(crate::Error::ApiNotAllowlisted("dialog > save".to_string())),
 
+      #[cfg(dialog_message)]
       Self::MessageDialog { message } => {
         let exe = std::env::current_exe()?;
         let app_name = exe
@@ -95,6 +99,12 @@ impl Cmd {
         message_dialog(app_name, message);
         Ok(().into())
       }
+      #[cfg(not(dialog_message))]
+      Self::MessageDialog { .. } => Err(crate::Error::ApiNotAllowlisted(
+        "dialog > message".to_string(),
+      )),
+
+      #[cfg(dialog_ask)]
       Self::AskDialog { title, message } => {
         let exe = std::env::current_exe()?;
         let answer = ask(
@@ -109,6 +119,8 @@ impl Cmd {
         )?;
         Ok(answer)
       }
+      #[cfg(not(dialog_ask))]
+      Self::AskDialog { .. } => Err(crate::Error::ApiNotAllowlisted("dialog > ask".to_string())),
     }
   }
 }
@@ -215,6 +227,9 @@ pub fn save<P: Params>(
     }
     dialog_builder = set_default_path(dialog_builder, default_path);
   }
+  if let Some(file_name) = options.file_name {
+    dialog_builder = dialog_builder.set_file_name(&file_name);
+  }
   for filter in options.filters {
     let extensions: Vec<&str> = filter.extensions.iter().map(|s| &**s).collect();
     dialog_builder = dialog_builder.add_filter(filter.name, &extensions);
@@ -223,6 +238,7 @@ pub fn save<P: Params>(
 }
 
 /// Shows a dialog with a yes/no question.
+#[cfg(dialog_ask)]
 pub fn ask(title: String, message: String) -> crate::Result<InvokeResponse> {
   match ask_dialog(title, message) {
     AskResponse::Yes => Ok(true.into()),
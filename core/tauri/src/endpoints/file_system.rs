@@ -8,7 +8,7 @@ use crate::{
     dir, file,
     path::{resolve_path, BaseDirectory},
   },
-  Config, PackageInfo,
+  Config, PackageInfo, Params, Window,
 };
 
 use serde::{Deserialize, Serialize};
@@ -34,6 +34,42 @@ pub struct FileOperationOptions {
   pub dir: Option<BaseDirectory>,
 }
 
+/// The options for the watch function on the file system API.
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WatchOperationOptions {
+  /// Whether the directories should be watched recursively or not.
+  #[serde(default)]
+  pub recursive: bool,
+  /// The delay in milliseconds used to debounce the events.
+  pub delay_ms: Option<u64>,
+  /// The base directory of the operation.
+  /// The directory path of the BaseDirectory will be the prefix of the watched paths.
+  pub dir: Option<BaseDirectory>,
+}
+
+type WatcherId = u32;
+/// The watchers mapped by the label of the window that created them, so they're dropped with it.
+#[cfg(fs_watch)]
+type WatcherStore = std::sync::Mutex<
+  std::collections::HashMap<String, std::collections::HashMap<WatcherId, crate::api::fs::Watcher>>,
+>;
+
+#[cfg(fs_watch)]
+fn watchers() -> &'static WatcherStore {
+  use once_cell::sync::Lazy;
+  static STORE: Lazy<WatcherStore> = Lazy::new(Default::default);
+  &STORE
+}
+
+/// Stops the watchers created by the window.
+#[cfg(fs_watch)]
+pub(crate) fn remove_window_watchers(label: &str) {
+  // dropped after the store is unlocked, as stopping a watcher waits for its thread
+  let removed = watchers().lock().unwrap().remove(label);
+  drop(removed);
+}
+
 /// The API descriptor.
 #[derive(Deserialize)]
 #[serde(tag = "cmd", rename_all = "camelCase")]
@@ -98,12 +134,31 @@ pub enum Cmd {
     path: String,
     directory: Option<BaseDirectory>,
   },
+  /// The watch API.
+  #[serde(rename_all = "camelCase")]
+  Watch {
+    paths: Vec<PathBuf>,
+    options: Option<WatchOperationOptions>,
+    on_event_fn: String,
+  },
+  /// The unwatch API.
+  Unwatch { id: WatcherId },
+  /// The trash API.
+  Trash {
+    path: PathBuf,
+    options: Option<FileOperationOptions>,
+  },
+  /// The list trash API.
+  ListTrash,
+  /// The untrash API.
+  Untrash { ids: Vec<String> },
 }
 
 impl Cmd {
   #[allow(unused_variables)]
-  pub fn run(
+  pub fn run<P: Params>(
     self,
+    window: Window<P>,
     config: Arc<Config>,
     package_info: &PackageInfo,
   ) -> crate::Result<InvokeResponse> {
@@ -208,8 +263,89 @@ impl Cmd {
       }
       #[cfg(not(fs_path))]
       Self::ResolvePath { .. } => Err(crate::Error::ApiNotAllowlisted("fs > pathApi".to_string())),
+
+      #[cfg(fs_watch)]
+      Self::Watch {
+        paths,
+        options,
+        on_event_fn,
+      } => watch(window, &config, package_info, paths, options, on_event_fn).map(Into::into),
+      #[cfg(fs_watch)]
+      Self::Unwatch { id } => {
+        let removed = watchers()
+          .lock()
+          .unwrap()
+          .get_mut(&window.label().to_string())
+          .and_then(|window_watchers| window_watchers.remove(&id));
+        drop(removed);
+        Ok(().into())
+      }
+      #[cfg(not(fs_watch))]
+      Self::Watch { .. } | Self::Unwatch { .. } => {
+        Err(crate::Error::ApiNotAllowlisted("fs > watch".to_string()))
+      }
+
+      #[cfg(fs_trash)]
+      Self::Trash { path, options } => trash(&config, package_info, path, options).map(Into::into),
+      #[cfg(fs_trash)]
+      Self::ListTrash => crate::api::fs::list_trash()
+        .map(Into::into)
+        .map_err(crate::Error::FailedToExecuteApi),
+      #[cfg(fs_trash)]
+      Self::Untrash { ids } => crate::api::fs::untrash(&ids)
+        .map(Into::into)
+        .map_err(crate::Error::FailedToExecuteApi),
+      #[cfg(not(fs_trash))]
+      Self::Trash { .. } | Self::ListTrash | Self::Untrash { .. } => {
+        Err(crate::Error::ApiNotAllowlisted("fs > trash".to_string()))
+      }
+    }
+  }
+}
+
+/// Watches paths, sending the debounced events to the window callback.
+#[cfg(fs_watch)]
+pub fn watch<P: Params>(
+  window: Window<P>,
+  config: &Config,
+  package_info: &PackageInfo,
+  paths: Vec<PathBuf>,
+  options: Option<WatchOperationOptions>,
+  on_event_fn: String,
+) -> crate::Result<WatcherId> {
+  use crate::api::fs::WatchOptions;
+
+  let label = window.label().to_string();
+
+  let mut watch_options = WatchOptions::default();
+  let mut dir = None;
+  if let Some(options) = options {
+    watch_options.recursive = options.recursive;
+    if let Some(delay_ms) = options.delay_ms {
+      watch_options.delay_ms = delay_ms;
     }
+    dir = options.dir;
   }
+  let paths = paths
+    .into_iter()
+    .map(|path| resolve_path(config, package_info, path, dir.clone()))
+    .collect::<crate::api::Result<Vec<_>>>()?;
+
+  let watcher = crate::api::fs::watch(paths, watch_options, move |event| {
+    let js = crate::api::rpc::format_callback(on_event_fn.clone(), &event)
+      .expect("unable to serialize WatchEvent");
+    let _ = window.eval(js.as_str());
+  })
+  .map_err(crate::Error::FailedToExecuteApi)?;
+
+  let id = rand::random();
+  watchers()
+    .lock()
+    .unwrap()
+    .entry(label)
+    .or_default()
+    .insert(id, watcher);
+  Ok(id)
 }
 
 /// Reads a directory.
@@ -308,6 +444,18 @@ pub fn remove_file(
   Ok(())
 }
 
+/// Moves a file or directory to the trash.
+#[cfg(fs_trash)]
+pub fn trash(
+  config: &Config,
+  package_info: &PackageInfo,
+  path: PathBuf,
+  options: Option<FileOperationOptions>,
+) -> crate::Result<()> {
+  let resolved_path = resolve_path(config, package_info, path, options.and_then(|o| o.dir))?;
+  crate::api::fs::trash(resolved_path).map_err(crate::Error::FailedToExecuteApi)
+}
+
 /// Renames a file.
 #[cfg(fs_rename_file)]
 pub fn rename_file(
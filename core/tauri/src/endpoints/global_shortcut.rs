@@ -7,7 +7,13 @@ use crate::{Params, Window};
 use serde::Deserialize;
 
 #[cfg(global_shortcut_all)]
-use crate::{api::shortcuts::ShortcutManager, runtime::Dispatch};
+use crate::{
+  api::shortcuts::{Shortcut, ShortcutEvent, ShortcutManager},
+  runtime::Dispatch,
+};
+
+#[cfg(global_shortcut_all)]
+use std::{collections::HashMap, sync::Arc};
 
 #[cfg(global_shortcut_all)]
 type ShortcutManagerHandle = std::sync::Arc<std::sync::Mutex<ShortcutManager>>;
@@ -38,19 +44,23 @@ pub enum Cmd {
   IsRegistered { shortcut: String },
 }
 
+/// Creates the handler calling the JS callback with the accelerator the shortcut was registered with.
 #[cfg(global_shortcut_all)]
-fn register_shortcut<D: Dispatch>(
+fn shortcut_handler<D: Dispatch>(
   dispatcher: D,
-  manager: &mut ShortcutManager,
-  shortcut: String,
   handler: String,
-) -> crate::Result<()> {
-  manager.register(shortcut.clone(), move || {
-    let callback_string = crate::api::rpc::format_callback(handler.to_string(), &shortcut)
+  accelerators: HashMap<Shortcut, String>,
+) -> impl FnMut(ShortcutEvent) + Clone + Send + 'static {
+  let accelerators = Arc::new(accelerators);
+  move |event| {
+    let accelerator = accelerators
+      .get(&event.shortcut)
+      .cloned()
+      .unwrap_or_else(|| event.shortcut.to_string());
+    let callback_string = crate::api::rpc::format_callback(handler.to_string(), &accelerator)
       .expect("unable to serialize shortcut string to json");
     let _ = dispatcher.eval_script(callback_string.as_str());
-  })?;
-  Ok(())
+  }
 }
 
 #[cfg(not(global_shortcut_all))]
@@ -67,23 +77,33 @@ impl Cmd {
   pub fn run<P: Params>(self, window: Window<P>) -> crate::Result<InvokeResponse> {
     match self {
       Self::Register { shortcut, handler } => {
-        let dispatcher = window.dispatcher();
+        let parsed = shortcut.parse::<Shortcut>()?;
+        let mut accelerators = HashMap::new();
+        accelerators.insert(parsed.clone(), shortcut);
         let mut manager = manager_handle().lock().unwrap();
-        register_shortcut(dispatcher, &mut manager, shortcut, handler)?;
+        manager.register(
+          parsed,
+          shortcut_handler(window.dispatcher(), handler, accelerators),
+        )?;
         Ok(().into())
       }
       Self::RegisterAll { shortcuts, handler } => {
-        let dispatcher = window.dispatcher();
+        let parsed = shortcuts
+          .iter()
+          .map(|shortcut| shortcut.parse())
+          .collect::<crate::api::Result<Vec<Shortcut>>>()?;
+        let accelerators = parsed.iter().cloned().zip(shortcuts).collect();
         let mut manager = manager_handle().lock().unwrap();
-        for shortcut in shortcuts {
-          let dispatch = dispatcher.clone();
-          register_shortcut(dispatch, &mut manager, shortcut, handler.clone())?;
-        }
+        manager.register_all(
+          parsed,
+          shortcut_handler(window.dispatcher(), handler, accelerators),
+        )?;
         Ok(().into())
       }
       Self::Unregister { shortcut } => {
+        let shortcut = shortcut.parse::<Shortcut>()?;
         let mut manager = manager_handle().lock().unwrap();
-        manager.unregister(shortcut)?;
+        manager.unregister(&shortcut)?;
         Ok(().into())
       }
       Self::UnregisterAll => {
@@ -92,8 +112,9 @@ impl Cmd {
         Ok(().into())
       }
       Self::IsRegistered { shortcut } => {
+        let shortcut = shortcut.parse::<Shortcut>()?;
         let manager = manager_handle().lock().unwrap();
-        Ok(manager.is_registered(shortcut)?.into())
+        Ok(manager.is_registered(&shortcut).into())
       }
     }
   }
@@ -4,12 +4,20 @@
 
 use super::InvokeResponse;
 
-use crate::api::http::{Client, ClientBuilder, HttpRequestBuilder};
+use crate::{
+  api::{
+    http::{Client, ClientBuilder, HttpRequestBuilder, Proxy},
+    path::BaseDirectory,
+  },
+  sealed::ManagerBase,
+  Params, Window,
+};
 use once_cell::sync::Lazy;
 use serde::Deserialize;
 
 use std::{
   collections::HashMap,
+  path::PathBuf,
   sync::{Arc, Mutex},
 };
 
@@ -34,13 +42,25 @@ pub enum Cmd {
     client: ClientId,
     options: Box<HttpRequestBuilder>,
   },
+  /// The HTTP download API.
+  #[serde(rename_all = "camelCase")]
+  Download {
+    client: ClientId,
+    options: Box<HttpRequestBuilder>,
+    path: PathBuf,
+    dir: Option<BaseDirectory>,
+    on_progress_fn: Option<String>,
+  },
 }
 
 impl Cmd {
-  pub async fn run(self) -> crate::Result<InvokeResponse> {
+  #[allow(unused_variables)]
+  pub async fn run<P: Params>(self, window: Window<P>) -> crate::Result<InvokeResponse> {
     match self {
       Self::CreateClient { options } => {
-        let client = options.unwrap_or_default().build()?;
+        let mut options = options.unwrap_or_default();
+        options.proxy = Proxy::from_config(&window.manager().config().tauri.proxy);
+        let client = options.build()?;
         let mut store = clients().lock().unwrap();
         let id = rand::random::<ClientId>();
         store.insert(id, client);
@@ -59,10 +79,87 @@ impl Cmd {
       Self::HttpRequest { .. } => Err(crate::Error::ApiNotAllowlisted(
         "http > request".to_string(),
       )),
+      #[cfg(http_request)]
+      Self::Download {
+        client,
+        options,
+        path,
+        dir,
+        on_progress_fn,
+      } => download(window, client, *options, path, dir, on_progress_fn)
+        .await
+        .map(Into::into),
+      #[cfg(not(http_request))]
+      Self::Download { .. } => Err(crate::Error::ApiNotAllowlisted(
+        "http > request".to_string(),
+      )),
     }
   }
 }
 
+/// Resolves the path of a download, which must be relative to the download, app or cache directory
+/// so the webview can't overwrite any file. Defaults to the download directory.
+#[cfg(http_request)]
+fn download_path<P: Params>(
+  window: &Window<P>,
+  path: PathBuf,
+  dir: Option<BaseDirectory>,
+) -> crate::Result<PathBuf> {
+  let dir = dir.unwrap_or(BaseDirectory::Download);
+  let allowed_dir = matches!(
+    dir,
+    BaseDirectory::Download | BaseDirectory::App | BaseDirectory::Cache
+  );
+  let relative = path.components().next().is_some()
+    && path
+      .components()
+      .all(|component| matches!(component, std::path::Component::Normal(_)));
+  if !allowed_dir || !relative {
+    return Err(crate::Error::DownloadPathNotAllowed(path));
+  }
+  let manager = window.manager();
+  crate::api::path::resolve_path(&manager.config(), manager.package_info(), path, Some(dir))
+    .map_err(Into::into)
+}
+
+/// Downloads the response of an HTTP request to a file, sending the progress to the webview.
+#[cfg(http_request)]
+pub async fn download<P: Params>(
+  window: Window<P>,
+  client_id: ClientId,
+  options: HttpRequestBuilder,
+  path: PathBuf,
+  dir: Option<BaseDirectory>,
+  on_progress_fn: Option<String>,
+) -> crate::Result<()> {
+  let path = download_path(&window, path, dir)?;
+
+  // the progress is sent at most every 100ms so large downloads don't flood the webview
+  const PROGRESS_INTERVAL: std::time::Duration = std::time::Duration::from_millis(100);
+
+  let client = clients()
+    .lock()
+    .unwrap()
+    .get(&client_id)
+    .ok_or(crate::Error::HttpClientNotInitialized)?
+    .clone();
+  let mut last_progress: Option<std::time::Instant> = None;
+  client
+    .download(options, path, |progress| {
+      if let Some(on_progress_fn) = &on_progress_fn {
+        let done = progress.total == Some(progress.downloaded);
+        if done || last_progress.map_or(true, |last| last.elapsed() >= PROGRESS_INTERVAL) {
+          last_progress.replace(std::time::Instant::now());
+          if let Ok(js) = crate::api::rpc::format_callback(on_progress_fn.clone(), &progress) {
+            let _ = window.eval(js.as_str());
+          }
+        }
+      }
+    })
+    .await?;
+  Ok(())
+}
+
 /// Makes an HTTP request and resolves the response to the webview
 #[cfg(http_request)]
 pub async fn make_request(
@@ -3,7 +3,7 @@
 // SPDX-License-Identifier: MIT
 
 use super::InvokeResponse;
-use crate::{Params, Window};
+use crate::{sealed::ManagerBase, Params, Window};
 use serde::Deserialize;
 
 /// The API descriptor.
@@ -11,12 +11,17 @@ use serde::Deserialize;
 #[serde(tag = "cmd", rename_all = "camelCase")]
 pub enum Cmd {
   ValidateSalt { salt: String },
+  AbortInvoke { id: String },
 }
 
 impl Cmd {
   pub fn run<P: Params>(self, window: Window<P>) -> crate::Result<InvokeResponse> {
     match self {
       Self::ValidateSalt { salt } => Ok(window.verify_salt(salt).into()),
+      Self::AbortInvoke { id } => {
+        window.manager().cancel_invoke(window.label(), &id);
+        Ok(().into())
+      }
     }
   }
 }
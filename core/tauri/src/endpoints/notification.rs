@@ -6,8 +6,10 @@ use super::InvokeResponse;
 use serde::Deserialize;
 
 #[cfg(notification_all)]
-use crate::api::notification::Notification;
-use crate::{Config, PackageInfo};
+use crate::api::notification::{Notification, NotificationEvent, NotificationPriority};
+use crate::{Config, PackageInfo, Params, Window};
+#[cfg(notification_all)]
+use serde::Serialize;
 
 use std::sync::Arc;
 
@@ -16,6 +18,18 @@ use std::sync::Arc;
 const PERMISSION_GRANTED: &str = "granted";
 // `Denied` response from `request_permission`. Matches the Web API return value.
 const PERMISSION_DENIED: &str = "denied";
+// Event emitted to the window when the user interacts with a notification.
+#[cfg(notification_all)]
+const NOTIFICATION_ACTION_EVENT: &str = "tauri://notification-action";
+
+/// A notification action button. Matches the Web API `NotificationAction`.
+#[derive(Deserialize)]
+pub struct NotificationActionOptions {
+  /// The action identifier.
+  pub action: String,
+  /// The action button label.
+  pub title: String,
+}
 
 /// The options for the notification API.
 #[derive(Deserialize)]
@@ -26,6 +40,27 @@ pub struct NotificationOptions {
   pub body: Option<String>,
   /// The notification icon.
   pub icon: Option<String>,
+  /// The path of an image attached to the notification.
+  pub image: Option<String>,
+  /// The notification sound name.
+  pub sound: Option<String>,
+  /// The notification priority.
+  #[cfg(notification_all)]
+  #[serde(default)]
+  pub priority: NotificationPriority,
+  /// The notification tag, sent back on the notification action events.
+  pub tag: Option<String>,
+  /// The notification action buttons.
+  #[serde(default)]
+  pub actions: Vec<NotificationActionOptions>,
+}
+
+/// The payload of the notification action event.
+#[cfg(notification_all)]
+#[derive(Serialize)]
+struct NotificationActionPayload {
+  tag: Option<String>,
+  event: NotificationEvent,
 }
 
 /// The API descriptor.
@@ -42,14 +77,15 @@ pub enum Cmd {
 
 impl Cmd {
   #[allow(unused_variables)]
-  pub fn run(
+  pub fn run<P: Params>(
     self,
+    window: Window<P>,
     config: Arc<Config>,
     package_info: &PackageInfo,
   ) -> crate::Result<InvokeResponse> {
     match self {
       #[cfg(notification_all)]
-      Self::Notification { options } => send(options, &config).map(Into::into),
+      Self::Notification { options } => send(window, options, &config).map(Into::into),
       #[cfg(not(notification_all))]
       Self::Notification { .. } => Err(crate::Error::ApiNotAllowlisted("notification".to_string())),
       Self::IsNotificationPermissionGranted => {
@@ -69,7 +105,11 @@ impl Cmd {
 }
 
 #[cfg(notification_all)]
-pub fn send(options: NotificationOptions, config: &Config) -> crate::Result<InvokeResponse> {
+pub fn send<P: Params>(
+  window: Window<P>,
+  options: NotificationOptions,
+  config: &Config,
+) -> crate::Result<InvokeResponse> {
   let mut notification =
     Notification::new(config.tauri.bundle.identifier.clone()).title(options.title);
   if let Some(body) = options.body {
@@ -78,6 +118,23 @@ pub fn send(options: NotificationOptions, config: &Config) -> crate::Result<Invo
   if let Some(icon) = options.icon {
     notification = notification.icon(icon);
   }
+  if let Some(image) = options.image {
+    notification = notification.image(image);
+  }
+  if let Some(sound) = options.sound {
+    notification = notification.sound(sound);
+  }
+  notification = notification.priority(options.priority);
+  for action in options.actions {
+    notification = notification.action(action.action, action.title);
+  }
+  let tag = options.tag;
+  notification = notification.on_action(move |event| {
+    let _ = window.emit(
+      &crate::manager::tauri_event::<P::Event>(NOTIFICATION_ACTION_EVENT),
+      Some(NotificationActionPayload { tag, event }),
+    );
+  });
   notification.show()?;
   Ok(().into())
 }
@@ -43,6 +43,15 @@ pub struct CommandOptions {
   // but the env is an `Option` so when it's `None` we clear the env.
   #[serde(default = "default_env")]
   env: Option<HashMap<String, String>>,
+  /// Spawns the command on a pseudo terminal with the given size. Requires the `shell-pty` feature.
+  pty: Option<PtySize>,
+}
+
+#[allow(dead_code)]
+#[derive(Clone, Copy, Deserialize)]
+pub struct PtySize {
+  rows: u16,
+  cols: u16,
 }
 
 /// The API descriptor.
@@ -62,9 +71,18 @@ pub enum Cmd {
     pid: ChildId,
     buffer: Buffer,
   },
+  ResizeChild {
+    pid: ChildId,
+    size: PtySize,
+  },
   KillChild {
     pid: ChildId,
   },
+  #[serde(rename_all = "camelCase")]
+  TerminateChild {
+    pid: ChildId,
+    timeout_ms: u64,
+  },
   Open {
     path: String,
     with: Option<String>,
@@ -73,7 +91,7 @@ pub enum Cmd {
 
 impl Cmd {
   #[allow(unused_variables)]
-  pub fn run<P: Params>(self, window: Window<P>) -> crate::Result<InvokeResponse> {
+  pub async fn run<P: Params>(self, window: Window<P>) -> crate::Result<InvokeResponse> {
     match self {
       Self::Execute {
         program,
@@ -97,6 +115,19 @@ impl Cmd {
           } else {
             command = command.env_clear();
           }
+          if let Some(size) = options.pty {
+            #[cfg(feature = "shell-pty")]
+            {
+              command = command.pty(crate::api::process::PtySize {
+                rows: size.rows,
+                cols: size.cols,
+              });
+            }
+            #[cfg(not(feature = "shell-pty"))]
+            return Err(crate::Error::FailedToExecuteApi(
+              crate::api::Error::Command("the `shell-pty` feature is not enabled".into()),
+            ));
+          }
           let (mut rx, child) = command.spawn()?;
 
           let pid = child.pid();
@@ -135,6 +166,38 @@ impl Cmd {
           "shell > execute".to_string(),
         ))
       }
+      Self::TerminateChild { pid, timeout_ms } => {
+        #[cfg(shell_execute)]
+        {
+          // release the lock before waiting for the child to exit
+          let child = command_childs().lock().unwrap().remove(&pid);
+          if let Some(child) = child {
+            let timeout = std::time::Duration::from_millis(timeout_ms);
+            crate::async_runtime::spawn_blocking(move || child.terminate(timeout)).await?;
+          }
+          Ok(().into())
+        }
+        #[cfg(not(shell_execute))]
+        Err(crate::Error::ApiNotAllowlisted(
+          "shell > execute".to_string(),
+        ))
+      }
+      Self::ResizeChild { pid, size } => {
+        #[cfg(feature = "shell-pty")]
+        {
+          if let Some(child) = command_childs().lock().unwrap().get(&pid) {
+            child.resize(crate::api::process::PtySize {
+              rows: size.rows,
+              cols: size.cols,
+            })?;
+          }
+          Ok(().into())
+        }
+        #[cfg(not(feature = "shell-pty"))]
+        Err(crate::Error::FailedToExecuteApi(
+          crate::api::Error::Command("the `shell-pty` feature is not enabled".into()),
+        ))
+      }
       Self::StdinWrite { pid, buffer } => {
         #[cfg(shell_execute)]
         {
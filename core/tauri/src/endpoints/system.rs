@@ -0,0 +1,25 @@
+// Copyright 2019-2021 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+use super::InvokeResponse;
+use serde::Deserialize;
+
+/// The API descriptor.
+#[derive(Deserialize)]
+#[serde(tag = "cmd", rename_all = "camelCase")]
+pub enum Cmd {
+  /// Reads the system information.
+  GetInfo,
+}
+
+impl Cmd {
+  pub fn run(self) -> crate::Result<InvokeResponse> {
+    match self {
+      #[cfg(system_all)]
+      Self::GetInfo => Ok(crate::api::system::info().into()),
+      #[cfg(not(system_all))]
+      Self::GetInfo => Err(crate::Error::ApiNotAllowlisted("system > all".to_string())),
+    }
+  }
+}
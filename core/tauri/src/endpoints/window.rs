@@ -3,11 +3,17 @@
 // SPDX-License-Identifier: MIT
 
 #[cfg(window_create)]
-use crate::runtime::{webview::WindowBuilder, Dispatch, Runtime};
+use crate::runtime::{
+  webview::{WebviewAttributes, WindowBuilder},
+  Dispatch, Runtime,
+};
 use crate::{
   api::config::WindowConfig,
   endpoints::InvokeResponse,
-  runtime::window::dpi::{Position, Size},
+  runtime::{
+    webview::{DataTypes, PrintOptions},
+    window::dpi::{Position, Size},
+  },
   Params, Window,
 };
 use serde::Deserialize;
@@ -52,6 +58,7 @@ pub enum Cmd {
   CurrentMonitor,
   PrimaryMonitor,
   AvailableMonitors,
+  Zoom,
   // Setters
   Center,
   SetResizable(bool),
@@ -78,6 +85,9 @@ pub enum Cmd {
   SetSkipTaskbar(bool),
   StartDragging,
   Print,
+  PrintWithOptions(PrintOptions),
+  SetZoom(f64),
+  ClearBrowsingData(DataTypes),
 }
 
 #[cfg(window_create)]
@@ -111,8 +121,9 @@ impl Cmd {
           });
 
           let url = options.url.clone();
+          let webview_attributes = WebviewAttributes::from(&options);
           window
-            .create_window(label.clone(), url, |_, webview_attributes| {
+            .create_window(label.clone(), url, |_, _| {
               (
                 <<<P::Runtime as Runtime>::Dispatcher as Dispatch>::WindowBuilder>::with_config(
                   options,
@@ -141,6 +152,7 @@ impl Cmd {
         Self::CurrentMonitor => return Ok(window.current_monitor()?.into()),
         Self::PrimaryMonitor => return Ok(window.primary_monitor()?.into()),
         Self::AvailableMonitors => return Ok(window.available_monitors()?.into()),
+        Self::Zoom => return Ok(window.zoom()?.into()),
         // Setters
         Self::Center => window.center()?,
         Self::SetResizable(resizable) => window.set_resizable(resizable)?,
@@ -164,6 +176,9 @@ impl Cmd {
         Self::SetSkipTaskbar(skip) => window.set_skip_taskbar(skip)?,
         Self::StartDragging => window.start_dragging()?,
         Self::Print => window.print()?,
+        Self::PrintWithOptions(options) => window.print_with_options(options)?,
+        Self::SetZoom(scale_factor) => window.set_zoom(scale_factor)?,
+        Self::ClearBrowsingData(data_types) => window.clear_browsing_data(data_types)?,
       }
       Ok(().into())
     }
@@ -70,9 +70,44 @@ pub enum Error {
   /// `default_path` provided to dialog API doesn't exist.
   #[error("failed to setup dialog: provided default path `{0}` doesn't exist")]
   DialogDefaultPathNotExists(PathBuf),
+  /// Failed to read the battery status.
+  #[cfg(feature = "power")]
+  #[error("failed to read the battery status: {0}")]
+  Battery(#[from] battery::Error),
+  /// Failed to configure the launch at login.
+  #[cfg(feature = "autolaunch")]
+  #[error("failed to configure the launch at login: {0}")]
+  AutoLaunch(String),
+  /// Failed to set the Windows jump list.
+  #[cfg(feature = "jump-list")]
+  #[error("failed to set the jump list: {0}")]
+  JumpList(String),
   /// Encountered an error creating the app system tray,
   #[error("error encountered during tray setup: {0}")]
   SystemTray(Box<dyn std::error::Error + Send>),
+  /// The isolation pattern is used but the `isolation` feature is disabled.
+  #[error("the isolation pattern requires the `isolation` feature")]
+  IsolationFeatureMissing,
+  /// Failed to decrypt an IPC message sent by the isolation application.
+  #[error("failed to decrypt the IPC message sent by the isolation application")]
+  IsolationDecryption,
+  /// A protocol of the config uses a handler that isn't registered.
+  #[error("protocol handler `{0}` not registered, see `Builder::protocol_handler`")]
+  ProtocolHandlerNotFound(String),
+  /// The download path isn't a relative path on the download, app or cache directory.
+  #[error(
+    "download path `{0}` not allowed, it must be relative to the download, app or cache directory"
+  )]
+  DownloadPathNotAllowed(PathBuf),
+  /// The resource directory of a protocol folder couldn't be resolved.
+  #[error("unable to resolve the resource directory of the protocol folder `{0}`")]
+  ProtocolDir(PathBuf),
+  /// The file requested to a protocol isn't in its folder.
+  #[error("protocol file `{0}` not found")]
+  ProtocolFileNotFound(String),
+  /// A file dragged out of a window doesn't exist.
+  #[error("dragged file `{0}` not found")]
+  DragItemNotFound(PathBuf),
 }
 
 impl From<serde_json::Error> for Error {
@@ -42,6 +42,52 @@ impl Event {
   }
 }
 
+/// Removes the event listener when dropped.
+#[must_use = "the event listener is removed when the guard is dropped"]
+pub struct EventHandlerGuard<Event: Tag, Window: Tag> {
+  id: EventHandler,
+  listeners: Listeners<Event, Window>,
+}
+
+impl<Event: Tag, Window: Tag> EventHandlerGuard<Event, Window> {
+  /// The [`EventHandler`] of the listener.
+  pub fn id(&self) -> EventHandler {
+    self.id
+  }
+
+  /// Keeps the listener registered after the guard is dropped, returning its [`EventHandler`].
+  pub fn forget(self) -> EventHandler {
+    let id = self.id;
+    std::mem::forget(self);
+    id
+  }
+}
+
+impl<Event: Tag, Window: Tag> fmt::Debug for EventHandlerGuard<Event, Window> {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    f.debug_struct("EventHandlerGuard")
+      .field("id", &self.id)
+      .finish()
+  }
+}
+
+impl<Event: Tag, Window: Tag> Drop for EventHandlerGuard<Event, Window> {
+  fn drop(&mut self) {
+    self.listeners.unlisten(self.id);
+  }
+}
+
+/// Information about a registered event listener.
+#[derive(Debug, Clone)]
+pub struct EventListenerInfo<Event: Tag, Window: Tag> {
+  /// The [`EventHandler`] of the listener.
+  pub id: EventHandler,
+  /// The event the listener is registered to.
+  pub event: Event,
+  /// The label of the window the listener is registered to, or `None` for global listeners.
+  pub window: Option<Window>,
+}
+
 /// What to do with the pending handler when resolving it?
 enum Pending<Event: Tag, Window: Tag> {
   Unlisten(EventHandler),
@@ -181,6 +227,37 @@ impl<Event: Tag, Window: Tag> Listeners<Event, Window> {
     })
   }
 
+  /// Wraps the given listener on a guard that removes it when dropped.
+  pub(crate) fn guard(&self, id: EventHandler) -> EventHandlerGuard<Event, Window> {
+    EventHandlerGuard {
+      id,
+      listeners: self.clone(),
+    }
+  }
+
+  /// Removes the event listeners that match the filter.
+  pub(crate) fn unlisten_filter<F: Fn(&Event, Option<&Window>) -> bool>(&self, filter: F) {
+    let mut lock = self.inner.handlers.lock().expect("poisoned event handlers");
+    for (event, handlers) in lock.iter_mut() {
+      handlers.retain(|_, handler| !filter(event, handler.window.as_ref()));
+    }
+  }
+
+  /// The registered event listeners.
+  pub(crate) fn listeners(&self) -> Vec<EventListenerInfo<Event, Window>> {
+    let lock = self.inner.handlers.lock().expect("poisoned event handlers");
+    lock
+      .iter()
+      .flat_map(|(event, handlers)| {
+        handlers.iter().map(move |(id, handler)| EventListenerInfo {
+          id: *id,
+          event: event.clone(),
+          window: handler.window.clone(),
+        })
+      })
+      .collect()
+  }
+
   /// Removes an event listener.
   pub(crate) fn unlisten(&self, handler_id: EventHandler) {
     match self.inner.handlers.try_lock() {
@@ -5,11 +5,13 @@
 use crate::{
   api::rpc::{format_callback, format_callback_result},
   app::App,
+  ipc::{Binary, CodecValue, IpcCodec},
+  sealed::ManagerBase,
   Params, StateManager, Window,
 };
 use serde::{Deserialize, Serialize};
 use serde_json::Value as JsonValue;
-use std::{future::Future, sync::Arc};
+use std::{future::Future, sync::Arc, time::Duration};
 
 /// A closure that is run when the Tauri application is setting up.
 pub type SetupHook<P> =
@@ -18,6 +20,14 @@ pub type SetupHook<P> =
 /// A closure that is run everytime Tauri receives a message it doesn't explicitly handle.
 pub type InvokeHandler<P> = dyn Fn(Invoke<P>) + Send + Sync + 'static;
 
+/// A closure that wraps every invoke message, calling the next middleware or the handler with `next`.
+///
+/// The invoke can be rejected without calling `next` to prevent the handler from running.
+pub type InvokeMiddleware<P> = dyn Fn(Invoke<P>, &dyn Fn(Invoke<P>)) + Send + Sync + 'static;
+
+/// A closure that is run every time a command invocation is resolved.
+pub type OnInvokeObserved<P> = dyn Fn(InvokeObservation<P>) + Send + Sync + 'static;
+
 /// A closure that is run once every time a window is created and loaded.
 pub type OnPageLoad<P> = dyn Fn(Window<P>, PageLoadPayload) + Send + Sync + 'static;
 
@@ -45,6 +55,68 @@ crate::manager::default_args! {
   }
 }
 
+crate::manager::default_args! {
+  /// Information about a resolved command invocation, reported to the [`OnInvokeObserved`] hooks.
+  pub struct InvokeObservation<P: Params> {
+    pub(crate) command: String,
+    pub(crate) window: P::Label,
+    pub(crate) argument_size: usize,
+    pub(crate) duration: Duration,
+    pub(crate) success: bool,
+  }
+}
+
+impl<P: Params> Clone for InvokeObservation<P> {
+  fn clone(&self) -> Self {
+    Self {
+      command: self.command.clone(),
+      window: self.window.clone(),
+      argument_size: self.argument_size,
+      duration: self.duration,
+      success: self.success,
+    }
+  }
+}
+
+impl<P: Params> std::fmt::Debug for InvokeObservation<P> {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    f.debug_struct("InvokeObservation")
+      .field("command", &self.command)
+      .field("window", &self.window)
+      .field("argument_size", &self.argument_size)
+      .field("duration", &self.duration)
+      .field("success", &self.success)
+      .finish()
+  }
+}
+
+impl<P: Params> InvokeObservation<P> {
+  /// The invoked command.
+  pub fn command(&self) -> &str {
+    &self.command
+  }
+
+  /// The label of the window that invoked the command.
+  pub fn window(&self) -> &P::Label {
+    &self.window
+  }
+
+  /// The size in bytes of the JSON arguments.
+  pub fn argument_size(&self) -> usize {
+    self.argument_size
+  }
+
+  /// The time elapsed between receiving the invoke and resolving it.
+  pub fn duration(&self) -> Duration {
+    self.duration
+  }
+
+  /// Whether the invoke promise was resolved or rejected.
+  pub fn success(&self) -> bool {
+    self.success
+  }
+}
+
 /// Error response from an [`InvokeMessage`].
 #[derive(Debug)]
 pub struct InvokeError(JsonValue);
@@ -112,6 +184,20 @@ impl From<InvokeError> for InvokeResponse {
   }
 }
 
+/// Encodes a command response with an [`IpcCodec`], delivering it as a [`Binary`] payload.
+fn encode_response<T: Serialize>(
+  codec: &dyn IpcCodec,
+  result: Result<T, InvokeError>,
+) -> InvokeResponse {
+  match result {
+    Ok(value) => match codec.encode(CodecValue(&value)) {
+      Ok(bytes) => Result::<_, InvokeError>::Ok(Binary::encoded(bytes, codec.name())).into(),
+      Err(e) => InvokeResponse::Err(InvokeError(JsonValue::String(e.to_string()))),
+    },
+    Err(e) => InvokeResponse::Err(e),
+  }
+}
+
 crate::manager::default_args! {
   /// Resolver of a invoke message.
   pub struct InvokeResolver<P: Params> {
@@ -153,7 +239,7 @@ impl<P: Params> InvokeResolver<P> {
 
   /// Reply to the invoke promise with a serializable value.
   pub fn respond<T: Serialize>(self, value: Result<T, InvokeError>) {
-    Self::return_result(self.window, value.into(), self.callback, self.error)
+    Self::return_closure(self.window, || value, self.callback, self.error)
   }
 
   /// Reply to the invoke promise running the given closure.
@@ -167,7 +253,7 @@ impl<P: Params> InvokeResolver<P> {
 
   /// Resolve the invoke promise with a value.
   pub fn resolve<T: Serialize>(self, value: T) {
-    Self::return_result(self.window, Ok(value).into(), self.callback, self.error)
+    Self::return_closure(self.window, || Ok(value), self.callback, self.error)
   }
 
   /// Reject the invoke promise with a value.
@@ -209,7 +295,12 @@ impl<P: Params> InvokeResolver<P> {
     success_callback: String,
     error_callback: String,
   ) {
-    Self::return_result(window, f().into(), success_callback, error_callback)
+    // the value is encoded by the codec directly, without converting it to JSON first
+    let response = match Self::response_codec(&window, &success_callback) {
+      Some(codec) => encode_response(codec.as_ref(), f()),
+      None => f().into(),
+    };
+    Self::send_response(window, response, success_callback, error_callback)
   }
 
   pub(crate) fn return_result(
@@ -218,15 +309,64 @@ impl<P: Params> InvokeResolver<P> {
     success_callback: String,
     error_callback: String,
   ) {
-    let callback_string = match format_callback_result(
-      response.into_result(),
-      success_callback,
-      error_callback.clone(),
-    ) {
-      Ok(callback_string) => callback_string,
-      Err(e) => format_callback(error_callback, &e.to_string())
-        .expect("unable to serialize shortcut string to json"),
+    let response = match (response, Self::response_codec(&window, &success_callback)) {
+      (InvokeResponse::Ok(value), Some(codec)) => encode_response(codec.as_ref(), Ok(value)),
+      (response, _) => response,
     };
+    Self::send_response(window, response, success_callback, error_callback)
+  }
+
+  /// The codec used to encode the response of the invoke, if the app has one.
+  #[allow(unused_variables)]
+  fn response_codec(window: &Window<P>, success_callback: &str) -> Option<Arc<dyn IpcCodec>> {
+    // the test helpers receive the JSON responses
+    #[cfg(feature = "test")]
+    if window
+      .manager()
+      .test_hooks()
+      .has_responder(success_callback)
+    {
+      return None;
+    }
+    window.manager().ipc_codec().cloned()
+  }
+
+  fn send_response(
+    window: Window<P>,
+    response: InvokeResponse,
+    success_callback: String,
+    error_callback: String,
+  ) {
+    window
+      .manager()
+      .release_cancellation_token(&success_callback);
+    window
+      .manager()
+      .observe_invoke_resolved(&success_callback, matches!(response, InvokeResponse::Ok(_)));
+
+    #[cfg(feature = "test")]
+    if let Some(responder) = window
+      .manager()
+      .test_hooks()
+      .take_responder(&success_callback)
+    {
+      let _ = responder.send(response.into_result());
+      return;
+    }
+
+    let result = response.into_result();
+    crate::ipc::bind_binary(
+      match &result {
+        Ok(value) | Err(value) => value,
+      },
+      &window.label().to_string(),
+    );
+    let callback_string =
+      match format_callback_result(result, success_callback, error_callback.clone()) {
+        Ok(callback_string) => callback_string,
+        Err(e) => format_callback(error_callback, &e.to_string())
+          .expect("unable to serialize shortcut string to json"),
+      };
 
     let _ = window.eval(&callback_string);
   }
@@ -243,6 +383,8 @@ crate::manager::default_args! {
     pub(crate) command: String,
     /// The JSON argument passed on the invoke message.
     pub(crate) payload: JsonValue,
+    /// The invoke identifier, which is the name of its success callback.
+    pub(crate) id: String,
   }
 }
 
@@ -253,12 +395,14 @@ impl<P: Params> InvokeMessage<P> {
     state: Arc<StateManager>,
     command: String,
     payload: JsonValue,
+    id: String,
   ) -> Self {
     Self {
       window,
       state,
       command,
       payload,
+      id,
     }
   }
 
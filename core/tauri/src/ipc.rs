@@ -0,0 +1,460 @@
+// Copyright 2019-2021 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+//! Types and functions related to the communication between the webview and the Rust core.
+
+use crate::{
+  api::rpc::format_callback,
+  command::{CommandArg, CommandItem},
+  sealed::ManagerBase,
+  InvokeError, Params,
+};
+use once_cell::sync::Lazy;
+use serde::{ser::SerializeMap, Deserialize, Serialize, Serializer};
+use serde_json::Value as JsonValue;
+use tempfile::{NamedTempFile, TempPath};
+use tokio::sync::watch;
+use uuid::Uuid;
+
+use std::{
+  collections::HashMap,
+  fmt,
+  io::Write,
+  marker::PhantomData,
+  path::{Path, PathBuf},
+  sync::{Arc, Mutex, MutexGuard},
+  time::{Duration, Instant},
+};
+
+/// The key of the object that references a binary payload on the JSON sent to the webview.
+const BINARY_KEY: &str = "__tauriBinary";
+
+/// The key of the codec used to decode a binary payload on the webview.
+const CODEC_KEY: &str = "__tauriCodec";
+
+/// The path prefix of the URL that serves the binary payloads on the `tauri` protocol.
+pub(crate) const BINARY_PATH_PREFIX: &str = "__tauri_binary__/";
+
+/// The prefix of the value the webview sends as a [`Channel`] argument.
+const CHANNEL_PREFIX: &str = "__CHANNEL__:";
+
+/// A binary payload waiting to be fetched by the webview.
+enum StoredBinary {
+  Bytes(Vec<u8>),
+  File(PathBuf, Option<Arc<TempPath>>),
+}
+
+/// How long a binary payload waits to be fetched by the webview before it is dropped.
+const BINARY_PAYLOAD_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// A binary payload waiting to be fetched by the webview.
+struct PendingBinary {
+  binary: StoredBinary,
+  /// The labels of the windows the payload was sent to, the only ones allowed to fetch it.
+  windows: Vec<String>,
+  created_at: Instant,
+}
+
+/// The binary payloads waiting to be fetched by the webview.
+static BINARY_PAYLOADS: Lazy<Mutex<HashMap<Uuid, PendingBinary>>> = Lazy::new(Default::default);
+
+/// Locks the binary payloads, dropping the expired ones.
+fn binary_payloads() -> MutexGuard<'static, HashMap<Uuid, PendingBinary>> {
+  let mut payloads = BINARY_PAYLOADS.lock().expect("poisoned binary payloads");
+  payloads.retain(|_, payload| payload.created_at.elapsed() < BINARY_PAYLOAD_TIMEOUT);
+  payloads
+}
+
+/// Stores a binary payload and serializes its reference.
+fn serialize_binary<S: Serializer>(
+  serializer: S,
+  binary: StoredBinary,
+  codec: Option<&'static str>,
+) -> Result<S::Ok, S::Error> {
+  let id = Uuid::new_v4();
+  binary_payloads().insert(
+    id,
+    PendingBinary {
+      binary,
+      windows: Vec::new(),
+      created_at: Instant::now(),
+    },
+  );
+  let mut map = serializer.serialize_map(Some(if codec.is_some() { 2 } else { 1 }))?;
+  map.serialize_entry(BINARY_KEY, &id.to_string())?;
+  if let Some(codec) = codec {
+    map.serialize_entry(CODEC_KEY, codec)?;
+  }
+  map.end()
+}
+
+/// A binary payload that is delivered to the webview as an `ArrayBuffer` instead of a JSON array of numbers.
+///
+/// It can be returned from a command or used as an event payload:
+///
+/// ```rust,no_run
+/// #[tauri::command]
+/// fn read_image() -> tauri::ipc::Binary {
+///   std::fs::read("image.png").unwrap().into()
+/// }
+/// ```
+///
+/// The bytes aren't embedded on the JSON message: the webview fetches them from the `tauri` protocol.
+/// They can only be fetched by the windows the payload was sent to, and are dropped after a minute.
+/// Only a binary payload used as the whole command response or event payload is converted to an `ArrayBuffer`.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Binary {
+  bytes: Vec<u8>,
+  codec: Option<&'static str>,
+}
+
+impl Binary {
+  /// Creates a binary payload from the given bytes.
+  pub fn new(bytes: Vec<u8>) -> Self {
+    Self { bytes, codec: None }
+  }
+
+  /// A binary payload that is decoded on the webview by the [`IpcCodec`] with the given name.
+  pub(crate) fn encoded(bytes: Vec<u8>, codec: &'static str) -> Self {
+    Self {
+      bytes,
+      codec: Some(codec),
+    }
+  }
+
+  /// The bytes of the payload.
+  pub fn as_bytes(&self) -> &[u8] {
+    &self.bytes
+  }
+
+  /// Consumes the payload, returning its bytes.
+  pub fn into_inner(self) -> Vec<u8> {
+    self.bytes
+  }
+}
+
+impl From<Vec<u8>> for Binary {
+  fn from(bytes: Vec<u8>) -> Self {
+    Self::new(bytes)
+  }
+}
+
+impl From<&[u8]> for Binary {
+  fn from(bytes: &[u8]) -> Self {
+    Self::new(bytes.to_vec())
+  }
+}
+
+#[cfg(feature = "bytes")]
+impl From<bytes::Bytes> for Binary {
+  fn from(bytes: bytes::Bytes) -> Self {
+    Self::new(bytes.to_vec())
+  }
+}
+
+impl Serialize for Binary {
+  fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+    serialize_binary(
+      serializer,
+      StoredBinary::Bytes(self.bytes.clone()),
+      self.codec,
+    )
+  }
+}
+
+/// A large binary payload backed by a file, delivered to the webview as an `ArrayBuffer`.
+///
+/// Unlike [`Binary`], the bytes aren't kept in memory while the webview fetches them:
+/// the file is only read when the webview requests it from the `tauri` protocol.
+/// Useful for multi-megabyte command responses such as thumbnails or parsed datasets:
+///
+/// ```rust,no_run
+/// #[tauri::command]
+/// fn thumbnail() -> tauri::Result<tauri::ipc::FileBinary> {
+///   let bytes = vec![0; 10 * 1024 * 1024];
+///   tauri::ipc::FileBinary::temp(&bytes)
+/// }
+/// ```
+#[derive(Debug, Clone)]
+pub struct FileBinary {
+  path: PathBuf,
+  temp: Option<Arc<TempPath>>,
+}
+
+impl FileBinary {
+  /// Writes the bytes to a temporary file, which is deleted once the webview fetches it.
+  pub fn temp(bytes: &[u8]) -> crate::Result<Self> {
+    let mut file = NamedTempFile::new()?;
+    file.write_all(bytes)?;
+    let temp = file.into_temp_path();
+    Ok(Self {
+      path: temp.to_path_buf(),
+      temp: Some(Arc::new(temp)),
+    })
+  }
+
+  /// A payload read from the given file when the webview fetches it. The file isn't deleted.
+  pub fn from_path<P: Into<PathBuf>>(path: P) -> Self {
+    Self {
+      path: path.into(),
+      temp: None,
+    }
+  }
+
+  /// The path of the file.
+  pub fn path(&self) -> &Path {
+    &self.path
+  }
+}
+
+impl Serialize for FileBinary {
+  fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+    serialize_binary(
+      serializer,
+      StoredBinary::File(self.path.clone(), self.temp.clone()),
+      None,
+    )
+  }
+}
+
+/// The error type of an [`IpcCodec`].
+pub type CodecError = Box<dyn std::error::Error + Send + Sync>;
+
+/// A command response given to [`IpcCodec::encode`].
+///
+/// It serializes the value returned by the command directly, without converting it to JSON first.
+pub struct CodecValue<'a>(pub(crate) &'a dyn erased_serde::Serialize);
+
+impl Serialize for CodecValue<'_> {
+  fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+    erased_serde::serialize(self.0, serializer)
+  }
+}
+
+/// Encodes the command responses sent to the webview, replacing the JSON serialization.
+///
+/// The encoded responses are delivered as [`Binary`] payloads and decoded on the webview
+/// by the function registered on the [`IpcCodec::decoder_script`].
+/// The event payloads and the messages sent by the webview still use JSON.
+pub trait IpcCodec: Send + Sync + 'static {
+  /// The codec name, used to find its decoder on the webview.
+  fn name(&self) -> &'static str;
+
+  /// Encodes a command response.
+  fn encode(&self, value: CodecValue<'_>) -> Result<Vec<u8>, CodecError>;
+
+  /// A script that registers the decoder on the webview as
+  /// `window.__TAURI__.__codecs[name] = function (arrayBuffer) { return value }`.
+  fn decoder_script(&self) -> String;
+}
+
+/// An [`IpcCodec`] that encodes the command responses with [MessagePack](https://msgpack.org).
+#[cfg(feature = "ipc-msgpack")]
+#[cfg_attr(doc_cfg, doc(cfg(feature = "ipc-msgpack")))]
+#[derive(Debug, Default, Clone, Copy)]
+pub struct MessagePackCodec;
+
+#[cfg(feature = "ipc-msgpack")]
+impl IpcCodec for MessagePackCodec {
+  fn name(&self) -> &'static str {
+    "msgpack"
+  }
+
+  fn encode(&self, value: CodecValue<'_>) -> Result<Vec<u8>, CodecError> {
+    rmp_serde::to_vec_named(&value).map_err(Into::into)
+  }
+
+  fn decoder_script(&self) -> String {
+    include_str!("../scripts/msgpack.js").replace("__CODEC_NAME__", self.name())
+  }
+}
+
+/// Whether the value is a serialized [`Binary`] payload or not.
+pub(crate) fn is_binary(value: &JsonValue) -> bool {
+  value
+    .as_object()
+    .map(|object| object.contains_key(BINARY_KEY))
+    .unwrap_or(false)
+}
+
+/// Allows the window to fetch the binary payload referenced by the value, as it is sent to it.
+pub(crate) fn bind_binary(value: &JsonValue, window: &str) {
+  let id = value
+    .get(BINARY_KEY)
+    .and_then(|id| id.as_str())
+    .and_then(|id| Uuid::parse_str(id).ok());
+  if let Some(id) = id {
+    if let Some(payload) = binary_payloads().get_mut(&id) {
+      payload.windows.push(window.to_string());
+    }
+  }
+}
+
+/// Takes the binary payload with the given id, so it can only be fetched once by a window it was sent to.
+pub(crate) fn take_binary(id: &str, window: &str) -> Option<Vec<u8>> {
+  let id = Uuid::parse_str(id).ok()?;
+  let mut payloads = binary_payloads();
+  if !payloads.get(&id)?.windows.iter().any(|w| w == window) {
+    return None;
+  }
+  let binary = payloads.remove(&id)?.binary;
+  drop(payloads);
+  match binary {
+    StoredBinary::Bytes(bytes) => Some(bytes),
+    // the temporary file is deleted when the last reference is dropped
+    StoredBinary::File(path, _temp) => std::fs::read(path).ok(),
+  }
+}
+
+/// Drops the binary payloads that were only sent to the destroyed window.
+pub(crate) fn remove_window_binaries(window: &str) {
+  binary_payloads().retain(|_, payload| {
+    let sent = !payload.windows.is_empty();
+    payload.windows.retain(|w| w != window);
+    !sent || !payload.windows.is_empty()
+  });
+}
+
+type ChannelSender = dyn Fn(String) -> crate::Result<()> + Send + Sync;
+
+/// A channel that streams values from a command to the webview.
+///
+/// It is received as a command argument and the webview is notified every time a value is sent:
+///
+/// ```rust,no_run
+/// #[tauri::command]
+/// fn index_files(on_progress: tauri::ipc::Channel<usize>) {
+///   for indexed in 0..100 {
+///     on_progress.send(indexed).unwrap();
+///   }
+/// }
+/// ```
+///
+/// On the webview, the argument is a `Channel` instance from the `@tauri-apps/api/tauri` module.
+pub struct Channel<T> {
+  id: String,
+  sender: Arc<ChannelSender>,
+  _marker: PhantomData<fn(T)>,
+}
+
+impl<T> Clone for Channel<T> {
+  fn clone(&self) -> Self {
+    Self {
+      id: self.id.clone(),
+      sender: self.sender.clone(),
+      _marker: PhantomData,
+    }
+  }
+}
+
+impl<T> fmt::Debug for Channel<T> {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    f.debug_struct("Channel").field("id", &self.id).finish()
+  }
+}
+
+impl<T: Serialize> Channel<T> {
+  /// The identifier of the channel callback on the webview.
+  pub fn id(&self) -> &str {
+    &self.id
+  }
+
+  /// Sends a value to the webview.
+  pub fn send(&self, value: T) -> crate::Result<()> {
+    let js = format_callback(&self.id, &value)?;
+    (self.sender)(js)
+  }
+}
+
+impl<'de, T: Serialize, P: Params> CommandArg<'de, P> for Channel<T> {
+  /// Reads the channel identifier sent by the webview.
+  fn from_command(command: CommandItem<'de, P>) -> Result<Self, InvokeError> {
+    let name = command.name;
+    let arg = command.key;
+    let window = command.message.window();
+    let value = String::deserialize(command).map_err(|e| crate::Error::InvalidArgs(arg, e))?;
+    match value.strip_prefix(CHANNEL_PREFIX) {
+      Some(id) => Ok(Self {
+        id: id.to_string(),
+        sender: Arc::new(move |js| window.eval(&js)),
+        _marker: PhantomData,
+      }),
+      None => Err(
+        crate::Error::InvalidArgs(
+          arg,
+          serde::de::Error::custom(format!(
+            "command {} expected a channel on key {}",
+            name, arg
+          )),
+        )
+        .into(),
+      ),
+    }
+  }
+}
+
+/// A token that is cancelled when the invoking window is closed or when the webview aborts the invoke.
+///
+/// It is received as a command argument:
+///
+/// ```rust,no_run
+/// #[tauri::command]
+/// async fn index_files(token: tauri::ipc::CancellationToken) {
+///   while !token.is_cancelled() {
+///     // index the next file
+///   }
+/// }
+/// ```
+///
+/// On the webview, the promise returned by `invoke` has an `abort` function that cancels the token.
+#[derive(Debug, Clone)]
+pub struct CancellationToken {
+  sender: Arc<watch::Sender<bool>>,
+  receiver: watch::Receiver<bool>,
+}
+
+impl Default for CancellationToken {
+  fn default() -> Self {
+    let (sender, receiver) = watch::channel(false);
+    Self {
+      sender: Arc::new(sender),
+      receiver,
+    }
+  }
+}
+
+impl CancellationToken {
+  /// Cancels the token.
+  pub fn cancel(&self) {
+    let _ = self.sender.send(true);
+  }
+
+  /// Whether the token was cancelled or not.
+  pub fn is_cancelled(&self) -> bool {
+    *self.receiver.borrow()
+  }
+
+  /// Waits until the token is cancelled.
+  pub async fn cancelled(&self) {
+    let mut receiver = self.receiver.clone();
+    while !*receiver.borrow() {
+      if receiver.changed().await.is_err() {
+        break;
+      }
+    }
+  }
+}
+
+impl<'de, P: Params> CommandArg<'de, P> for CancellationToken {
+  /// Gets the cancellation token of the invoke. This will never fail.
+  fn from_command(command: CommandItem<'de, P>) -> Result<Self, InvokeError> {
+    Ok(
+      command
+        .message
+        .window_ref()
+        .manager()
+        .cancellation_token(&command.message.id),
+    )
+  }
+}
@@ -0,0 +1,458 @@
+// Copyright 2019-2021 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+//! The Windows jump list, shown when the taskbar icon of the app is right clicked.
+//!
+//! Each item launches the app executable with its arguments, so the running instance receives them
+//! on the [`crate::Builder::single_instance`] handler, or a new instance reads them from its command line.
+//! Setting a jump list is a no-op on the other platforms.
+//!
+//! # Example
+//!
+//! ```rust,ignore
+//! use tauri::jump_list::{JumpList, JumpListCategory, JumpListItem};
+//!
+//! fn main() {
+//!   tauri::Builder::default()
+//!     .single_instance(|app, argv, _cwd| {
+//!       if argv.iter().any(|arg| arg == "--new-window") {
+//!         app.emit_all("new-window", ()).unwrap();
+//!       }
+//!     })
+//!     .setup(|app| {
+//!       app.handle().set_jump_list(
+//!         JumpList::new()
+//!           .add_task(JumpListItem::new("New window", "--new-window"))
+//!           .add_category(
+//!             JumpListCategory::new("Projects")
+//!               .add_item(JumpListItem::new("Website", "--project website")),
+//!           )
+//!           .show_recent(true),
+//!       )?;
+//!       Ok(())
+//!     })
+//!     .run(tauri::generate_context!())
+//!     .expect("error while running tauri application");
+//! }
+//! ```
+
+use std::path::{Path, PathBuf};
+
+/// An item of the jump list, launching the app with its arguments.
+#[derive(Debug, Clone)]
+pub struct JumpListItem {
+  title: String,
+  arguments: String,
+  description: Option<String>,
+  icon: Option<(PathBuf, i32)>,
+}
+
+impl JumpListItem {
+  /// Creates an item launching the app with `arguments`, a command line string.
+  pub fn new<T: Into<String>, A: Into<String>>(title: T, arguments: A) -> Self {
+    Self {
+      title: title.into(),
+      arguments: arguments.into(),
+      description: None,
+      icon: None,
+    }
+  }
+
+  /// Sets the tooltip of the item.
+  pub fn description<D: Into<String>>(mut self, description: D) -> Self {
+    self.description.replace(description.into());
+    self
+  }
+
+  /// Sets the icon of the item, the icon resource at `index` of an `.ico`, `.exe` or `.dll` file.
+  ///
+  /// Defaults to the icon of the app executable.
+  pub fn icon<I: Into<PathBuf>>(mut self, path: I, index: i32) -> Self {
+    self.icon.replace((path.into(), index));
+    self
+  }
+}
+
+/// A named category of jump list items.
+#[derive(Debug, Clone)]
+pub struct JumpListCategory {
+  name: String,
+  items: Vec<JumpListItem>,
+}
+
+impl JumpListCategory {
+  /// Creates a category without items.
+  pub fn new<N: Into<String>>(name: N) -> Self {
+    Self {
+      name: name.into(),
+      items: Vec::new(),
+    }
+  }
+
+  /// Adds an item to the end of the category.
+  pub fn add_item(mut self, item: JumpListItem) -> Self {
+    self.items.push(item);
+    self
+  }
+}
+
+/// The jump list of the app, set with [`crate::AppHandle::set_jump_list`].
+#[derive(Debug, Clone, Default)]
+pub struct JumpList {
+  tasks: Vec<JumpListItem>,
+  categories: Vec<JumpListCategory>,
+  recent: bool,
+  frequent: bool,
+}
+
+impl JumpList {
+  /// Creates an empty jump list, removing the items of the current one when it's set.
+  pub fn new() -> Self {
+    Default::default()
+  }
+
+  /// Adds an item to the `Tasks` category, shown at the bottom of the jump list.
+  pub fn add_task(mut self, task: JumpListItem) -> Self {
+    self.tasks.push(task);
+    self
+  }
+
+  /// Adds a custom category, shown above the tasks.
+  pub fn add_category(mut self, category: JumpListCategory) -> Self {
+    self.categories.push(category);
+    self
+  }
+
+  /// Whether to show the `Recent` category, with the documents added with [`add_recent_document`].
+  pub fn show_recent(mut self, show: bool) -> Self {
+    self.recent = show;
+    self
+  }
+
+  /// Whether to show the `Frequent` category, with the documents added with [`add_recent_document`] the most times.
+  pub fn show_frequent(mut self, show: bool) -> Self {
+    self.frequent = show;
+    self
+  }
+}
+
+/// Adds the file to the recent documents of the app, listed on the `Recent` and `Frequent` categories of the jump list.
+///
+/// The file type must be associated with the app, e.g. with `tauri.conf.json > tauri > bundle > fileAssociations`.
+pub fn add_recent_document<P: AsRef<Path>>(path: P) {
+  #[cfg(windows)]
+  platform::add_recent_document(path.as_ref());
+  #[cfg(not(windows))]
+  let _ = path;
+}
+
+#[cfg(windows)]
+pub(crate) use platform::set_jump_list;
+
+#[cfg(not(windows))]
+pub(crate) fn set_jump_list(_jump_list: JumpList) -> crate::Result<()> {
+  Ok(())
+}
+
+#[cfg(windows)]
+mod platform {
+  use super::{JumpList, JumpListItem};
+
+  use winapi::{
+    shared::{
+      guiddef::GUID,
+      minwindef::UINT,
+      winerror::{FAILED, SUCCEEDED},
+      wtypes::{PROPERTYKEY, VT_LPWSTR},
+      wtypesbase::CLSCTX_INPROC_SERVER,
+    },
+    um::{
+      combaseapi::{CoCreateInstance, CoInitializeEx, CoUninitialize},
+      objbase::COINIT_APARTMENTTHREADED,
+      objectarray::{IObjectArray, IObjectCollection},
+      propidl::PROPVARIANT,
+      propsys::IPropertyStore,
+      shobjidl_core::IShellLinkW,
+      unknwnbase::IUnknown,
+      winnt::HRESULT,
+    },
+    Interface,
+  };
+
+  use std::{
+    ffi::{c_void, OsStr},
+    iter::once,
+    os::windows::ffi::OsStrExt,
+    path::Path,
+    ptr::null_mut,
+  };
+
+  use destination_list::ICustomDestinationList;
+
+  const CLSID_DESTINATION_LIST: GUID = GUID {
+    Data1: 0x77f1_0cf0,
+    Data2: 0x3db5,
+    Data3: 0x4966,
+    Data4: [0xb5, 0x20, 0xb7, 0xc5, 0x4f, 0xd3, 0x5e, 0xd6],
+  };
+  const CLSID_ENUMERABLE_OBJECT_COLLECTION: GUID = GUID {
+    Data1: 0x2d34_68c1,
+    Data2: 0x36a7,
+    Data3: 0x43b6,
+    Data4: [0xac, 0x24, 0xd3, 0xf0, 0x2f, 0xd9, 0x60, 0x7a],
+  };
+  const CLSID_SHELL_LINK: GUID = GUID {
+    Data1: 0x0002_1401,
+    Data2: 0x0000,
+    Data3: 0x0000,
+    Data4: [0xc0, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x46],
+  };
+  /// `PKEY_Title`, the property holding the title of the shell links.
+  const PKEY_TITLE: PROPERTYKEY = PROPERTYKEY {
+    fmtid: GUID {
+      Data1: 0xf29f_85e0,
+      Data2: 0x4ff9,
+      Data3: 0x1068,
+      Data4: [0xab, 0x91, 0x08, 0x00, 0x2b, 0x27, 0xb3, 0xd9],
+    },
+    pid: 2,
+  };
+  /// `KDC_FREQUENT` and `KDC_RECENT`.
+  const KNOWN_CATEGORY_FREQUENT: i32 = 1;
+  const KNOWN_CATEGORY_RECENT: i32 = 2;
+  /// `SHARD_PATHW`.
+  const SHARD_PATH: UINT = 3;
+
+  /// `ICustomDestinationList`, which isn't part of `winapi`.
+  #[allow(non_snake_case)]
+  mod destination_list {
+    use winapi::{
+      shared::{guiddef::REFIID, minwindef::UINT},
+      um::{
+        objectarray::IObjectArray,
+        unknwnbase::{IUnknown, IUnknownVtbl},
+        winnt::{HRESULT, LPCWSTR},
+      },
+      RIDL,
+    };
+
+    use std::ffi::c_void;
+
+    RIDL! {#[uuid(0x6332_debf, 0x87b5, 0x4670, 0x90, 0xc0, 0x5e, 0x57, 0xb4, 0x08, 0xa4, 0x9e)]
+    interface ICustomDestinationList(ICustomDestinationListVtbl): IUnknown(IUnknownVtbl) {
+      fn SetAppID(app_id: LPCWSTR,) -> HRESULT,
+      fn BeginList(min_slots: *mut UINT, riid: REFIID, removed: *mut *mut c_void,) -> HRESULT,
+      fn AppendCategory(category: LPCWSTR, items: *mut IObjectArray,) -> HRESULT,
+      fn AppendKnownCategory(category: i32,) -> HRESULT,
+      fn AddUserTasks(items: *mut IObjectArray,) -> HRESULT,
+      fn CommitList() -> HRESULT,
+      fn GetRemovedDestinations(riid: REFIID, removed: *mut *mut c_void,) -> HRESULT,
+      fn DeleteList(app_id: LPCWSTR,) -> HRESULT,
+      fn AbortList() -> HRESULT,
+    }}
+  }
+
+  #[link(name = "shell32")]
+  extern "system" {
+    fn SHAddToRecentDocs(flags: UINT, path: *const c_void);
+  }
+
+  fn wide<S: AsRef<OsStr>>(value: S) -> Vec<u16> {
+    value.as_ref().encode_wide().chain(once(0)).collect()
+  }
+
+  fn check(result: HRESULT, operation: &str) -> crate::Result<()> {
+    if FAILED(result) {
+      Err(crate::Error::JumpList(format!(
+        "{} failed with HRESULT {:#010x}",
+        operation, result
+      )))
+    } else {
+      Ok(())
+    }
+  }
+
+  /// Releases the COM object when dropped.
+  struct ComPtr<T: Interface>(*mut T);
+
+  impl<T: Interface> ComPtr<T> {
+    unsafe fn create(clsid: &GUID) -> crate::Result<Self> {
+      let mut object = null_mut();
+      check(
+        CoCreateInstance(
+          clsid,
+          null_mut(),
+          CLSCTX_INPROC_SERVER,
+          &T::uuidof(),
+          &mut object,
+        ),
+        "CoCreateInstance",
+      )?;
+      Ok(Self(object as *mut T))
+    }
+
+    fn as_unknown(&self) -> *mut IUnknown {
+      self.0 as *mut IUnknown
+    }
+  }
+
+  impl<T: Interface> std::ops::Deref for ComPtr<T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+      unsafe { &*self.0 }
+    }
+  }
+
+  impl<T: Interface> Drop for ComPtr<T> {
+    fn drop(&mut self) {
+      unsafe {
+        (*self.as_unknown()).Release();
+      }
+    }
+  }
+
+  /// A `PROPVARIANT` holding a string, whose layout matches the `VT_LPWSTR` variant.
+  #[repr(C)]
+  struct StringPropVariant {
+    vt: u16,
+    reserved: [u16; 3],
+    value: *const u16,
+    padding: usize,
+  }
+
+  /// Creates the shell link launching the app executable with the item arguments.
+  unsafe fn shell_link(exe: &[u16], item: &JumpListItem) -> crate::Result<ComPtr<IShellLinkW>> {
+    let link = ComPtr::<IShellLinkW>::create(&CLSID_SHELL_LINK)?;
+    check(link.SetPath(exe.as_ptr()), "IShellLinkW::SetPath")?;
+    check(
+      link.SetArguments(wide(&item.arguments).as_ptr()),
+      "IShellLinkW::SetArguments",
+    )?;
+    if let Some(description) = &item.description {
+      check(
+        link.SetDescription(wide(description).as_ptr()),
+        "IShellLinkW::SetDescription",
+      )?;
+    }
+    match &item.icon {
+      Some((path, index)) => check(
+        link.SetIconLocation(wide(path).as_ptr(), *index),
+        "IShellLinkW::SetIconLocation",
+      )?,
+      None => check(
+        link.SetIconLocation(exe.as_ptr(), 0),
+        "IShellLinkW::SetIconLocation",
+      )?,
+    }
+
+    let mut store: *mut c_void = null_mut();
+    check(
+      (*link.as_unknown()).QueryInterface(&IPropertyStore::uuidof(), &mut store),
+      "QueryInterface(IPropertyStore)",
+    )?;
+    let store = ComPtr(store as *mut IPropertyStore);
+    let title = wide(&item.title);
+    let value = StringPropVariant {
+      vt: VT_LPWSTR as u16,
+      reserved: [0; 3],
+      value: title.as_ptr(),
+      padding: 0,
+    };
+    // the store copies the value
+    check(
+      store.SetValue(
+        &PKEY_TITLE,
+        &value as *const StringPropVariant as *const PROPVARIANT,
+      ),
+      "IPropertyStore::SetValue",
+    )?;
+    check(store.Commit(), "IPropertyStore::Commit")?;
+    Ok(link)
+  }
+
+  unsafe fn collection(
+    exe: &[u16],
+    items: &[JumpListItem],
+  ) -> crate::Result<ComPtr<IObjectCollection>> {
+    let collection = ComPtr::<IObjectCollection>::create(&CLSID_ENUMERABLE_OBJECT_COLLECTION)?;
+    for item in items {
+      let link = shell_link(exe, item)?;
+      check(
+        collection.AddObject(link.as_unknown()),
+        "IObjectCollection::AddObject",
+      )?;
+    }
+    Ok(collection)
+  }
+
+  unsafe fn commit(jump_list: &JumpList) -> crate::Result<()> {
+    let exe = wide(std::env::current_exe()?);
+    let list = ComPtr::<ICustomDestinationList>::create(&CLSID_DESTINATION_LIST)?;
+    let mut min_slots = 0;
+    let mut removed: *mut c_void = null_mut();
+    check(
+      list.BeginList(&mut min_slots, &IObjectArray::uuidof(), &mut removed),
+      "ICustomDestinationList::BeginList",
+    )?;
+    drop(ComPtr(removed as *mut IObjectArray));
+
+    let result = (|| {
+      for category in &jump_list.categories {
+        let items = collection(&exe, &category.items)?;
+        check(
+          list.AppendCategory(
+            wide(&category.name).as_ptr(),
+            items.as_unknown() as *mut IObjectArray,
+          ),
+          "ICustomDestinationList::AppendCategory",
+        )?;
+      }
+      if jump_list.frequent {
+        check(
+          list.AppendKnownCategory(KNOWN_CATEGORY_FREQUENT),
+          "ICustomDestinationList::AppendKnownCategory",
+        )?;
+      }
+      if jump_list.recent {
+        check(
+          list.AppendKnownCategory(KNOWN_CATEGORY_RECENT),
+          "ICustomDestinationList::AppendKnownCategory",
+        )?;
+      }
+      if !jump_list.tasks.is_empty() {
+        let tasks = collection(&exe, &jump_list.tasks)?;
+        check(
+          list.AddUserTasks(tasks.as_unknown() as *mut IObjectArray),
+          "ICustomDestinationList::AddUserTasks",
+        )?;
+      }
+      check(list.CommitList(), "ICustomDestinationList::CommitList")
+    })();
+    if result.is_err() {
+      list.AbortList();
+    }
+    result
+  }
+
+  /// Commits the jump list on a new thread, which initializes COM
+  /// regardless of the threading model of the calling thread.
+  pub fn set_jump_list(jump_list: JumpList) -> crate::Result<()> {
+    std::thread::spawn(move || unsafe {
+      let initialized = SUCCEEDED(CoInitializeEx(null_mut(), COINIT_APARTMENTTHREADED));
+      let result = commit(&jump_list);
+      if initialized {
+        CoUninitialize();
+      }
+      result
+    })
+    .join()
+    .map_err(|_| crate::Error::JumpList("the jump list thread panicked".into()))?
+  }
+
+  pub fn add_recent_document(path: &Path) {
+    let path = wide(path);
+    unsafe { SHAddToRecentDocs(SHARD_PATH, path.as_ptr() as *const c_void) }
+  }
+}
@@ -16,6 +16,15 @@
 //! - **cli**: Enables usage of `clap` for CLI argument parsing. Enabled by default if the `cli` config is defined on the `tauri.conf.json` file.
 //! - **system-tray**: Enables application system tray API. Enabled by default if the `systemTray` config is defined on the `tauri.conf.json` file.
 //! - **updater**: Enables the application auto updater. Enabled by default if the `updater` config is defined on the `tauri.conf.json` file.
+//! - **ipc-msgpack**: Enables the [`ipc::MessagePackCodec`] to encode the command responses with MessagePack.
+//! - **isolation**: Enables the isolation [`Pattern`]. Enabled by default if the `pattern` config is set to `isolation` on the `tauri.conf.json` file.
+//! - **power**: Enables the [`power`] module, reading the battery status and dispatching the [`power::PowerEvent`]s.
+//! - **single-instance**: Enables [`Builder::single_instance`], forwarding the new invocations of the app to the running instance.
+//! - **autolaunch**: Enables the [`autolaunch`] module and [`AppHandle::autolaunch`], launching the app when the user logs in.
+//! - **jump-list**: Enables the [`jump_list`] module and [`AppHandle::set_jump_list`], setting the Windows taskbar jump list.
+//! - **touchbar**: Enables the [`touchbar`] module, setting Touch Bar layouts to the windows on macOS.
+//! - **tracing**: Emits [`tracing`](https://docs.rs/tracing) events for every command invocation and its response.
+//! - **test**: Enables the [`test`] module exposing unit test helpers, including a runtime that needs no display server.
 
 #![warn(missing_docs, rust_2018_idioms)]
 #![cfg_attr(doc_cfg, feature(doc_cfg))]
@@ -26,22 +35,46 @@ pub use tauri_macros::{command, generate_handler};
 
 pub mod api;
 pub(crate) mod app;
+mod asset_resolver;
 /// Async runtime.
 pub mod async_runtime;
+#[cfg(feature = "autolaunch")]
+#[cfg_attr(doc_cfg, doc(cfg(feature = "autolaunch")))]
+pub mod autolaunch;
+mod bus;
 pub mod command;
+mod deep_link;
 /// The Tauri API endpoints.
 mod endpoints;
 mod error;
 mod event;
 mod hooks;
+pub mod ipc;
+#[cfg(feature = "jump-list")]
+#[cfg_attr(doc_cfg, doc(cfg(feature = "jump-list")))]
+pub mod jump_list;
 mod manager;
+mod open_file;
+mod path_resolver;
+mod pattern;
 pub mod plugin;
+#[cfg(feature = "power")]
+#[cfg_attr(doc_cfg, doc(cfg(feature = "power")))]
+pub mod power;
 /// Tauri window.
 pub mod window;
 use tauri_runtime as runtime;
 /// The Tauri-specific settings for your runtime e.g. notification permission status.
 pub mod settings;
+#[cfg(feature = "single-instance")]
+mod single_instance;
 mod state;
+#[cfg(feature = "test")]
+#[cfg_attr(doc_cfg, doc(cfg(feature = "test")))]
+pub mod test;
+#[cfg(feature = "touchbar")]
+#[cfg_attr(doc_cfg, doc(cfg(feature = "touchbar")))]
+pub mod touchbar;
 #[cfg(feature = "updater")]
 pub mod updater;
 
@@ -54,10 +87,7 @@ pub type Result<T> = std::result::Result<T, Error>;
 /// A task to run on the main thread.
 pub type SyncTask = Box<dyn FnOnce() + Send>;
 
-use crate::{
-  event::{Event, EventHandler},
-  runtime::window::PendingWindow,
-};
+use crate::runtime::window::PendingWindow;
 use serde::Serialize;
 use std::{borrow::Borrow, collections::HashMap, sync::Arc};
 
@@ -79,22 +109,30 @@ pub use {
     config::{Config, WindowUrl},
     PackageInfo,
   },
-  self::app::{App, AppHandle, Builder, GlobalWindowEvent},
+  self::app::{App, AppHandle, Builder, EventLoopProxy, GlobalWindowEvent, RunEvent},
+  self::asset_resolver::{Asset, AssetResolver},
+  self::bus::BusSubscription,
+  self::event::{Event, EventHandler, EventHandlerGuard, EventListenerInfo},
   self::hooks::{
-    Invoke, InvokeError, InvokeHandler, InvokeMessage, InvokeResolver, InvokeResponse, OnPageLoad,
-    PageLoadPayload, SetupHook,
+    Invoke, InvokeError, InvokeHandler, InvokeMessage, InvokeMiddleware, InvokeObservation,
+    InvokeResolver, InvokeResponse, OnInvokeObserved, OnPageLoad, PageLoadPayload, SetupHook,
   },
+  self::path_resolver::PathResolver,
+  self::pattern::Pattern,
   self::runtime::{
     tag::{Tag, TagRef},
-    webview::{WebviewAttributes, WindowBuilder},
+    webview::{
+      Cookie, DataTypes, FileDropEvent, FileDropModifiers, PageMargins, PageSize, PermissionKind,
+      PermissionRequest, PrintOptions, ProxyConfig, ProxyKind, WebviewAttributes, WindowBuilder,
+    },
     window::{
       dpi::{LogicalPosition, LogicalSize, PhysicalPosition, PhysicalSize, Pixel, Position, Size},
-      WindowEvent,
+      DragItem, ThumbbarButton, WindowEvent,
     },
     Icon, MenuId, Params, RunIteration,
   },
   self::state::{State, StateManager},
-  self::window::{Monitor, Window},
+  self::window::{cookies::CookieManager, Monitor, StyleHandle, Window},
 };
 #[cfg(feature = "system-tray")]
 #[cfg_attr(doc_cfg, doc(cfg(feature = "system-tray")))]
@@ -158,6 +196,8 @@ pub struct Context<A: Assets> {
   pub(crate) default_window_icon: Option<Vec<u8>>,
   pub(crate) system_tray_icon: Option<Icon>,
   pub(crate) package_info: crate::api::PackageInfo,
+  pub(crate) pattern: Pattern,
+  pub(crate) sidecar_checksums: HashMap<String, String>,
 }
 
 impl<A: Assets> Context<A> {
@@ -221,6 +261,30 @@ impl<A: Assets> Context<A> {
     &mut self.package_info
   }
 
+  /// The application pattern.
+  #[inline(always)]
+  pub fn pattern(&self) -> &Pattern {
+    &self.pattern
+  }
+
+  /// A mutable reference to the application pattern.
+  #[inline(always)]
+  pub fn pattern_mut(&mut self) -> &mut Pattern {
+    &mut self.pattern
+  }
+
+  /// The sidecar checksums computed at build time, verified when the app starts.
+  #[inline(always)]
+  pub fn sidecar_checksums(&self) -> &HashMap<String, String> {
+    &self.sidecar_checksums
+  }
+
+  /// A mutable reference to the sidecar checksums computed at build time.
+  #[inline(always)]
+  pub fn sidecar_checksums_mut(&mut self) -> &mut HashMap<String, String> {
+    &mut self.sidecar_checksums
+  }
+
   /// Create a new [`Context`] from the minimal required items.
   #[inline(always)]
   pub fn new(
@@ -229,6 +293,8 @@ impl<A: Assets> Context<A> {
     default_window_icon: Option<Vec<u8>>,
     system_tray_icon: Option<Icon>,
     package_info: crate::api::PackageInfo,
+    pattern: Pattern,
+    sidecar_checksums: HashMap<String, String>,
   ) -> Self {
     Self {
       config,
@@ -236,6 +302,8 @@ impl<A: Assets> Context<A> {
       default_window_icon,
       system_tray_icon,
       package_info,
+      pattern,
+      sidecar_checksums,
     }
   }
 }
@@ -276,6 +344,37 @@ pub trait Manager<P: Params>: sealed::ManagerBase<P> {
       .emit_filter(event, payload, |w| label == w.label())
   }
 
+  /// Emits an event to the windows with the specified labels.
+  /// The payload is serialized only once.
+  fn emit_to_many<E: ?Sized, L: ?Sized, S: Serialize + Clone>(
+    &self,
+    labels: &[&L],
+    event: &E,
+    payload: S,
+  ) -> Result<()>
+  where
+    P::Label: Borrow<L>,
+    P::Event: Borrow<E>,
+    L: TagRef<P::Label>,
+    E: TagRef<P::Event>,
+  {
+    self.manager().emit_filter(event, payload, |w| {
+      labels.iter().any(|label| *label == w.label())
+    })
+  }
+
+  /// Emits an event to the windows that match the filter.
+  /// The payload is serialized only once.
+  fn emit_filter<E: ?Sized, S, F>(&self, event: &E, payload: S, filter: F) -> Result<()>
+  where
+    P::Event: Borrow<E>,
+    E: TagRef<P::Event>,
+    S: Serialize + Clone,
+    F: Fn(&Window<P>) -> bool,
+  {
+    self.manager().emit_filter(event, payload, filter)
+  }
+
   /// Listen to a global event.
   fn listen_global<E: Into<P::Event>, F>(&self, event: E, handler: F) -> EventHandler
   where
@@ -301,11 +400,38 @@ pub trait Manager<P: Params>: sealed::ManagerBase<P> {
     self.manager().trigger(event, None, data)
   }
 
+  /// Listen to a global event until the returned guard is dropped.
+  fn listen_global_guarded<E: Into<P::Event>, F>(
+    &self,
+    event: E,
+    handler: F,
+  ) -> EventHandlerGuard<P::Event, P::Label>
+  where
+    F: Fn(Event) + Send + 'static,
+  {
+    let id = self.manager().listen(event.into(), None, handler);
+    self.manager().event_handler_guard(id)
+  }
+
   /// Remove an event listener.
   fn unlisten(&self, handler_id: EventHandler) {
     self.manager().unlisten(handler_id)
   }
 
+  /// Removes the event listeners that match the filter, which receives the event
+  /// and the label of the window the listener is registered to, or `None` for global listeners.
+  fn unlisten_filter<F>(&self, filter: F)
+  where
+    F: Fn(&P::Event, Option<&P::Label>) -> bool,
+  {
+    self.manager().unlisten_filter(filter)
+  }
+
+  /// The registered event listeners. Useful for debugging.
+  fn event_listeners(&self) -> Vec<EventListenerInfo<P::Event, P::Label>> {
+    self.manager().event_listeners()
+  }
+
   /// Fetch a single window from the manager.
   fn get_window<L: ?Sized>(&self, label: &L) -> Option<Window<P>>
   where
@@ -336,6 +462,13 @@ pub trait Manager<P: Params>: sealed::ManagerBase<P> {
   {
     self.manager().inner.state.get()
   }
+
+  /// Gets a handle to the state managed by the plugin with the given name.
+  ///
+  /// Plugins usually get it on [`plugin::Plugin#method.initialize`] with `app.plugin_handle(self.name())`.
+  fn plugin_handle(&self, name: &'static str) -> plugin::PluginHandle {
+    plugin::PluginHandle::new(name, self.manager().plugin_state(name))
+  }
 }
 
 /// Prevent implementation details from leaking out of the [`Manager`] trait.
@@ -385,7 +518,7 @@ pub(crate) mod sealed {
 }
 
 #[cfg(test)]
-mod test {
+mod tests {
   use proptest::prelude::*;
 
   proptest! {
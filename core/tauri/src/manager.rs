@@ -12,26 +12,48 @@ use crate::{
     path::{resolve_path, BaseDirectory},
     PackageInfo,
   },
-  app::{GlobalWindowEvent, GlobalWindowEventListener},
-  event::{Event, EventHandler, Listeners},
-  hooks::{InvokeHandler, OnPageLoad, PageLoadPayload},
+  app::{FileDropListener, GlobalWindowEvent, GlobalWindowEventListener},
+  bus::MessageBus,
+  event::{Event, EventHandler, EventHandlerGuard, EventListenerInfo, Listeners},
+  hooks::{
+    InvokeHandler, InvokeMessage, InvokeMiddleware, InvokeObservation, OnInvokeObserved,
+    OnPageLoad, PageLoadPayload,
+  },
+  ipc::{CancellationToken, IpcCodec},
+  pattern::Pattern,
   plugin::PluginStore,
   runtime::{
     private::ParamsBase,
     tag::{tags_to_javascript_array, Tag, TagRef, ToJsString},
     webview::{
-      CustomProtocol, FileDropEvent, FileDropHandler, InvokePayload, WebviewRpcHandler,
-      WindowBuilder,
+      CustomProtocol, FileDropEvent, FileDropHandler, FileDropModifiers, InvokePayload,
+      WebviewRpcHandler, WindowBuilder,
+    },
+    window::{
+      dpi::{PhysicalPosition, PhysicalSize},
+      DetachedWindow, PendingWindow, WindowEvent,
     },
-    window::{dpi::PhysicalSize, DetachedWindow, PendingWindow, WindowEvent},
     Icon, MenuId, Params, Runtime,
   },
-  App, Context, Invoke, StateManager, Window,
+  sealed::ManagerBase,
+  App, AppHandle, Context, Invoke, RunEvent, StateManager, Window,
+};
+
+#[cfg(feature = "isolation")]
+use crate::{
+  api::{assets::EmbeddedAssets, html::inject_script},
+  pattern::{IsolationKey, IsolationMessage, IsolationPayload, ISOLATION_PROTOCOL},
 };
 
 #[cfg(feature = "menu")]
 use crate::app::{GlobalMenuEventListener, WindowMenuEvent};
 
+#[cfg(feature = "system-tray")]
+use crate::app::tray::SystemTrayHandle;
+
+#[cfg(feature = "touchbar")]
+use crate::touchbar::{GlobalTouchBarEventListener, TouchBar, WindowTouchBarEvent};
+
 #[cfg(feature = "menu")]
 use crate::{
   runtime::menu::{Menu, MenuEntry},
@@ -46,8 +68,11 @@ use std::{
   borrow::Cow,
   collections::{HashMap, HashSet},
   fs::create_dir_all,
+  path::PathBuf,
   sync::{Arc, Mutex, MutexGuard},
+  time::Instant,
 };
+use url::Url;
 use uuid::Uuid;
 
 const WINDOW_RESIZED_EVENT: &str = "tauri://resize";
@@ -57,6 +82,14 @@ const WINDOW_DESTROYED_EVENT: &str = "tauri://destroyed";
 const WINDOW_FOCUS_EVENT: &str = "tauri://focus";
 const WINDOW_BLUR_EVENT: &str = "tauri://blur";
 const WINDOW_SCALE_FACTOR_CHANGED_EVENT: &str = "tauri://scale-change";
+const WINDOW_NAVIGATED_EVENT: &str = "tauri://navigated";
+const WINDOW_THUMBBAR_BUTTON_CLICK_EVENT: &str = "tauri://thumbbar-button-click";
+const FILE_DROP_EVENT: &str = "tauri://file-drop";
+const FILE_DROP_HOVER_EVENT: &str = "tauri://file-drop-hover";
+const FILE_DROP_CANCELLED_EVENT: &str = "tauri://file-drop-cancelled";
+const DRAG_OVER_EVENT: &str = "tauri://drag-over";
+const DRAG_DROP_EVENT: &str = "tauri://drag-drop";
+const IPC_REJECTED_EVENT: &str = "tauri://ipc-rejected";
 #[cfg(feature = "menu")]
 const MENU_EVENT: &str = "tauri://menu";
 
@@ -78,18 +111,57 @@ crate::manager::default_args! {
   pub struct InnerWindowManager<P: Params> {
     windows: Mutex<HashMap<P::Label, Window<P>>>,
     plugins: Mutex<PluginStore<P>>,
+    /// The handle of the application runtime, set once it's built.
+    /// The [`AppHandle`] is created from it as storing it would keep the manager alive.
+    runtime_handle: Mutex<Option<<P::Runtime as Runtime>::Handle>>,
     listeners: Listeners<P::Event, P::Label>,
     pub(crate) state: Arc<StateManager>,
+    /// The state managed by each plugin, mapped by plugin name.
+    plugin_states: Mutex<HashMap<&'static str, Arc<StateManager>>>,
+    /// The message bus shared by the plugins.
+    bus: MessageBus,
+    /// The updater settings.
+    #[cfg(feature = "updater")]
+    updater: crate::updater::UpdaterHandle,
 
     /// The JS message handler.
     invoke_handler: Box<InvokeHandler<P>>,
 
+    /// The middlewares that wrap every invoke message.
+    invoke_middlewares: Vec<Box<InvokeMiddleware<P>>>,
+
+    /// The codec used to encode the command responses.
+    ipc_codec: Option<Arc<dyn IpcCodec>>,
+
+    /// The hooks that observe the resolved invokes.
+    invoke_observers: Vec<Box<OnInvokeObserved<P>>>,
+
+    /// The command, window label, arguments size and start time of the observed pending invokes, mapped by invoke id.
+    observed_invokes: Mutex<HashMap<String, (String, P::Label, usize, Instant)>>,
+
+    /// The cancellation tokens of the pending invokes, mapped by invoke id.
+    cancellation_tokens: Mutex<HashMap<String, (P::Label, CancellationToken)>>,
+
+    /// The origins allowed to send IPC messages, mapped by window label.
+    ipc_allowed_origins: Mutex<HashMap<P::Label, Vec<String>>>,
+
+    /// The keys only injected on the main frame of the windows, proving the IPC messages weren't sent by an iframe.
+    ipc_keys: Mutex<HashMap<P::Label, String>>,
+
+    /// The initialization scripts added at runtime, injected on the windows created afterwards.
+    initialization_scripts: Mutex<Vec<String>>,
+
     /// The page load hook, invoked when the webview performs a navigation.
     on_page_load: Box<OnPageLoad<P>>,
 
     config: Arc<Config>,
     assets: Arc<P::Assets>,
     default_window_icon: Option<Vec<u8>>,
+    /// The application pattern.
+    pattern: Pattern,
+    /// The key used to decrypt the messages sent by the isolation application.
+    #[cfg(feature = "isolation")]
+    isolation_key: IsolationKey,
 
     /// A list of salts that are valid for the current application.
     salts: Mutex<HashSet<Uuid>>,
@@ -105,8 +177,25 @@ crate::manager::default_args! {
     /// Menu event listeners to all windows.
     #[cfg(feature = "menu")]
     menu_event_listeners: Arc<Vec<GlobalMenuEventListener<P>>>,
+    /// Maps runtime id to a strongly typed id, for the items of the Touch Bars set to the windows.
+    #[cfg(feature = "touchbar")]
+    touchbar_ids: Mutex<HashMap<u32, P::MenuId>>,
+    /// Touch Bar event listeners to all windows.
+    #[cfg(feature = "touchbar")]
+    touchbar_event_listeners: Arc<Vec<GlobalTouchBarEventListener<P>>>,
     /// Window event listeners to all windows.
     window_event_listeners: Arc<Vec<GlobalWindowEventListener<P>>>,
+    /// File drop listeners to all windows.
+    file_drop_listeners: Arc<Vec<FileDropListener<P>>>,
+    /// The icon used by the system trays that don't set one.
+    #[cfg(feature = "system-tray")]
+    system_tray_icon: Option<Icon>,
+    /// The handle to the system tray, once it is created.
+    #[cfg(feature = "system-tray")]
+    tray_handle: Mutex<Option<SystemTrayHandle<P>>>,
+    /// The invoke responses and events captured by the [`crate::test`] helpers.
+    #[cfg(feature = "test")]
+    test_hooks: crate::test::TestHooks,
   }
 }
 
@@ -227,26 +316,51 @@ impl<P: Params> WindowManager<P> {
     context: Context<P::Assets>,
     plugins: PluginStore<P>,
     invoke_handler: Box<InvokeHandler<P>>,
+    invoke_middlewares: Vec<Box<InvokeMiddleware<P>>>,
+    ipc_codec: Option<Arc<dyn IpcCodec>>,
+    invoke_observers: Vec<Box<OnInvokeObserved<P>>>,
     on_page_load: Box<OnPageLoad<P>>,
     uri_scheme_protocols: HashMap<String, Arc<CustomProtocol>>,
     state: StateManager,
     window_event_listeners: Vec<GlobalWindowEventListener<P>>,
+    file_drop_listeners: Vec<FileDropListener<P>>,
     #[cfg(feature = "menu")] (menu, menu_event_listeners): (
       Option<Menu<P::MenuId>>,
       Vec<GlobalMenuEventListener<P>>,
     ),
+    #[cfg(feature = "touchbar")] touchbar_event_listeners: Vec<GlobalTouchBarEventListener<P>>,
   ) -> Self {
     Self {
       inner: Arc::new(InnerWindowManager {
         windows: Mutex::default(),
         plugins: Mutex::new(plugins),
+        runtime_handle: Mutex::default(),
         listeners: Listeners::default(),
         state: Arc::new(state),
+        plugin_states: Mutex::default(),
+        bus: MessageBus::default(),
+        #[cfg(feature = "updater")]
+        updater: crate::updater::UpdaterHandle::new(
+          context.config.tauri.updater.clone(),
+          context.package_info.clone(),
+          crate::api::http::Proxy::from_config(&context.config.tauri.proxy),
+        ),
         invoke_handler,
+        invoke_middlewares,
+        ipc_codec,
+        invoke_observers,
+        observed_invokes: Mutex::default(),
+        cancellation_tokens: Mutex::default(),
+        ipc_allowed_origins: Mutex::default(),
+        ipc_keys: Mutex::default(),
+        initialization_scripts: Mutex::default(),
         on_page_load,
         config: Arc::new(context.config),
         assets: context.assets,
         default_window_icon: context.default_window_icon,
+        pattern: context.pattern,
+        #[cfg(feature = "isolation")]
+        isolation_key: IsolationKey::generate(),
         salts: Mutex::default(),
         package_info: context.package_info,
         uri_scheme_protocols,
@@ -262,7 +376,18 @@ impl<P: Params> WindowManager<P> {
         menu,
         #[cfg(feature = "menu")]
         menu_event_listeners: Arc::new(menu_event_listeners),
+        #[cfg(feature = "touchbar")]
+        touchbar_ids: Mutex::default(),
+        #[cfg(feature = "touchbar")]
+        touchbar_event_listeners: Arc::new(touchbar_event_listeners),
         window_event_listeners: Arc::new(window_event_listeners),
+        file_drop_listeners: Arc::new(file_drop_listeners),
+        #[cfg(feature = "system-tray")]
+        system_tray_icon: context.system_tray_icon,
+        #[cfg(feature = "system-tray")]
+        tray_handle: Mutex::default(),
+        #[cfg(feature = "test")]
+        test_hooks: Default::default(),
       }),
       _marker: Args::default(),
     }
@@ -273,17 +398,82 @@ impl<P: Params> WindowManager<P> {
     self.inner.windows.lock().expect("poisoned window manager")
   }
 
+  /// Adds an initialization script to the windows created after this call.
+  pub(crate) fn add_initialization_script(&self, script: String) {
+    self
+      .inner
+      .initialization_scripts
+      .lock()
+      .expect("poisoned initialization scripts")
+      .push(script);
+  }
+
   /// State managed by the application.
   pub(crate) fn state(&self) -> Arc<StateManager> {
     self.inner.state.clone()
   }
 
+  /// State managed by the plugin with the given name, created on first access.
+  pub(crate) fn plugin_state(&self, name: &'static str) -> Arc<StateManager> {
+    self
+      .inner
+      .plugin_states
+      .lock()
+      .expect("poisoned plugin states")
+      .entry(name)
+      .or_insert_with(|| Arc::new(StateManager::new()))
+      .clone()
+  }
+
+  /// The assets embedded in the binary.
+  pub(crate) fn assets(&self) -> &P::Assets {
+    &self.inner.assets
+  }
+
+  /// The message bus shared by the plugins.
+  pub(crate) fn bus(&self) -> &MessageBus {
+    &self.inner.bus
+  }
+
+  /// The updater settings.
+  #[cfg(feature = "updater")]
+  pub(crate) fn updater(&self) -> &crate::updater::UpdaterHandle {
+    &self.inner.updater
+  }
+
   /// Get the menu ids mapper.
   #[cfg(feature = "menu")]
   pub(crate) fn menu_ids(&self) -> HashMap<u32, P::MenuId> {
     self.inner.menu_ids.clone()
   }
 
+  /// Registers the ids of the Touch Bar items, so their events can be mapped back to them.
+  #[cfg(feature = "touchbar")]
+  pub(crate) fn register_touchbar_ids(&self, touchbar: &TouchBar<P::MenuId>) {
+    let mut ids = self
+      .inner
+      .touchbar_ids
+      .lock()
+      .expect("poisoned touchbar ids");
+    for item in &touchbar.items {
+      if let (Some(id), Some(id_value)) = (item.id(), item.id_value()) {
+        ids.insert(id_value, id.clone());
+      }
+    }
+  }
+
+  /// The id of the Touch Bar item with the given runtime id.
+  #[cfg(feature = "touchbar")]
+  pub(crate) fn touchbar_item_id(&self, id: u32) -> Option<P::MenuId> {
+    self
+      .inner
+      .touchbar_ids
+      .lock()
+      .expect("poisoned touchbar ids")
+      .get(&id)
+      .cloned()
+  }
+
   // setup content for dev-server
   #[cfg(dev)]
   fn get_url(&self) -> String {
@@ -308,15 +498,18 @@ impl<P: Params> WindowManager<P> {
     pending_labels: &[P::Label],
   ) -> crate::Result<PendingWindow<P>> {
     let is_init_global = self.inner.config.build.with_global_tauri;
-    let plugin_init = self
+    let plugin_init = self.plugins().initialization_script();
+
+    let ipc_key = Uuid::new_v4().to_simple().to_string();
+    self
       .inner
-      .plugins
+      .ipc_keys
       .lock()
-      .expect("poisoned plugin store")
-      .initialization_script();
+      .expect("poisoned IPC keys")
+      .insert(label.clone(), ipc_key.clone());
 
     let mut webview_attributes = pending.webview_attributes
-      .initialization_script(&self.initialization_script(&plugin_init, is_init_global))
+      .initialization_script(&self.initialization_script(&plugin_init, is_init_global, &ipc_key))
       .initialization_script(&format!(
         r#"
               window.__TAURI__.__windows = {window_labels_array}.map(function (label) {{ return {{ label: label }} }});
@@ -326,6 +519,30 @@ impl<P: Params> WindowManager<P> {
         current_window_label = label.to_js_string()?,
       ));
 
+    if let Some(codec) = &self.inner.ipc_codec {
+      webview_attributes = webview_attributes.initialization_script(&codec.decoder_script());
+    }
+
+    #[cfg(feature = "isolation")]
+    if let Pattern::Isolation { assets } = &self.inner.pattern {
+      webview_attributes = webview_attributes
+        .initialization_script(include_str!("../scripts/isolation.js"))
+        .register_uri_scheme_protocol(
+          ISOLATION_PROTOCOL,
+          self.prepare_isolation_protocol(assets.clone()).protocol,
+        );
+    }
+
+    for script in self
+      .inner
+      .initialization_scripts
+      .lock()
+      .expect("poisoned initialization scripts")
+      .iter()
+    {
+      webview_attributes = webview_attributes.initialization_script(script);
+    }
+
     if !pending.window_builder.has_icon() {
       if let Some(default_window_icon) = &self.inner.default_window_icon {
         let icon = Icon::Raw(default_window_icon.clone());
@@ -350,19 +567,57 @@ impl<P: Params> WindowManager<P> {
 
     if !webview_attributes.has_uri_scheme_protocol("tauri") {
       webview_attributes = webview_attributes
-        .register_uri_scheme_protocol("tauri", self.prepare_uri_scheme_protocol().protocol);
+        .register_uri_scheme_protocol("tauri", self.prepare_uri_scheme_protocol(&label).protocol);
     }
 
-    let local_app_data = resolve_path(
-      &self.inner.config,
-      &self.inner.package_info,
-      &self.inner.config.tauri.bundle.identifier,
-      Some(BaseDirectory::LocalData),
-    );
-    if let Ok(user_data_dir) = local_app_data {
-      // Make sure the directory exist without panic
-      if create_dir_all(&user_data_dir).is_ok() {
-        webview_attributes = webview_attributes.data_directory(user_data_dir);
+    let ipc_allowed_origins = webview_attributes
+      .ipc_allowed_origins
+      .clone()
+      .or_else(|| self.inner.config.tauri.security.ipc_allowed_origins.clone());
+    // the runtime rejects the allowlist if it can't read the origin of the webview
+    webview_attributes.ipc_allowed_origins = ipc_allowed_origins.clone();
+    let mut ipc_allowed_origins_lock = self
+      .inner
+      .ipc_allowed_origins
+      .lock()
+      .expect("poisoned IPC allowed origins");
+    match ipc_allowed_origins {
+      Some(origins) => ipc_allowed_origins_lock.insert(label, origins),
+      None => ipc_allowed_origins_lock.remove(&label),
+    };
+    drop(ipc_allowed_origins_lock);
+
+    let webview2 = &self.inner.config.tauri.webview2;
+    if webview_attributes.additional_browser_args.is_none() {
+      if let Some(args) = &webview2.additional_browser_args {
+        webview_attributes = webview_attributes.additional_browser_args(args);
+      }
+    }
+    if webview_attributes.webview2_runtime_path.is_none() {
+      if let Some(path) = &webview2.fixed_runtime_path {
+        webview_attributes = webview_attributes.webview2_runtime_path(path.clone());
+      }
+    }
+
+    if webview_attributes.data_directory.is_none() {
+      let user_data_dir = match (
+        &webview2.user_data_path,
+        crate::api::platform::portable_data_dir(),
+      ) {
+        (Some(path), _) => Ok(path.clone()),
+        (None, Some(portable_data_dir)) => Ok(portable_data_dir),
+        (None, None) => resolve_path(
+          &self.inner.config,
+          &self.inner.package_info,
+          &self.inner.config.tauri.bundle.identifier,
+          Some(BaseDirectory::LocalData),
+        ),
+      };
+      if let Ok(user_data_dir) = user_data_dir {
+        // Make sure the directory exist without panic
+        if create_dir_all(&user_data_dir).is_ok() {
+          webview_attributes = webview_attributes.data_directory(user_data_dir);
+        }
       }
     }
 
@@ -376,6 +631,7 @@ impl<P: Params> WindowManager<P> {
     Box::new(move |window, request| {
       let window = Window::new(manager.clone(), window);
       let command = request.command.clone();
+      let url = request.url;
 
       let arg = request
         .params
@@ -385,8 +641,9 @@ impl<P: Params> WindowManager<P> {
         .first_mut()
         .unwrap_or(&mut JsonValue::Null)
         .take();
-      match serde_json::from_value::<InvokePayload>(arg) {
-        Ok(message) => {
+      match manager.parse_invoke_payload(command, arg) {
+        Ok((command, mut message)) => {
+          message.url = url;
           let _ = window.on_message(command, message);
         }
         Err(e) => {
@@ -400,8 +657,57 @@ impl<P: Params> WindowManager<P> {
     })
   }
 
-  fn prepare_uri_scheme_protocol(&self) -> CustomProtocol {
+  /// Parses the payload of an IPC message, decrypting it if the isolation pattern is used.
+  fn parse_invoke_payload(
+    &self,
+    command: String,
+    payload: JsonValue,
+  ) -> crate::Result<(String, InvokePayload)> {
+    match &self.inner.pattern {
+      Pattern::Brownfield => Ok((command, serde_json::from_value(payload)?)),
+      #[cfg(feature = "isolation")]
+      Pattern::Isolation { .. } => {
+        let message: IsolationMessage = serde_json::from_value(payload)?;
+        let IsolationPayload { cmd, message } = self.inner.isolation_key.decrypt(message)?;
+        Ok((cmd, serde_json::from_value(message)?))
+      }
+      #[cfg(not(feature = "isolation"))]
+      Pattern::Isolation { .. } => Err(crate::Error::IsolationFeatureMissing),
+    }
+  }
+
+  /// Serves the isolation application, injecting the script that encrypts the IPC messages.
+  #[cfg(feature = "isolation")]
+  fn prepare_isolation_protocol(&self, assets: Arc<EmbeddedAssets>) -> CustomProtocol {
+    let runtime_script = self.inner.isolation_key.runtime_script();
+    CustomProtocol {
+      protocol: Box::new(move |path| {
+        let path = path
+          .split(&['?', '#'][..])
+          .next()
+          .unwrap()
+          .replace("isolation://localhost", "")
+          .replace("https://isolation.localhost", "");
+        let path = path.trim_matches('/');
+        let path = if path.is_empty() { "index.html" } else { path };
+        match assets.get(path) {
+          Some(asset) if path.ends_with(".html") => Ok(
+            inject_script(
+              String::from_utf8_lossy(&asset).into_owned(),
+              &runtime_script,
+            )
+            .into_bytes(),
+          ),
+          Some(asset) => Ok(asset.into_owned()),
+          None => Err(Box::new(crate::Error::AssetNotFound(path.to_string()))),
+        }
+      }),
+    }
+  }
+
+  fn prepare_uri_scheme_protocol(&self, label: &P::Label) -> CustomProtocol {
     let assets = self.inner.assets.clone();
+    let label = label.to_string();
     CustomProtocol {
       protocol: Box::new(move |path| {
         let mut path = path
@@ -425,6 +731,13 @@ impl<P: Params> WindowManager<P> {
           path.chars().skip(1).collect::<String>()
         };
 
+        if let Some(id) = path.strip_prefix(crate::ipc::BINARY_PATH_PREFIX) {
+          return match crate::ipc::take_binary(id, &label) {
+            Some(bytes) => Ok(bytes),
+            None => Err(Box::new(crate::Error::AssetNotFound(path.clone()))),
+          };
+        }
+
         let asset_response = assets
           .get(&path)
           .ok_or(crate::Error::AssetNotFound(path))
@@ -447,16 +760,54 @@ impl<P: Params> WindowManager<P> {
       let manager = manager.clone();
       crate::async_runtime::block_on(async move {
         let window = Window::new(manager.clone(), window);
+        // every listener must allow the drop for it to reach the webview
+        let allowed = manager
+          .inner
+          .file_drop_listeners
+          .iter()
+          .fold(true, |allowed, handler| handler(&window, &event) && allowed);
+        if !allowed {
+          return;
+        }
+        // the `file-drop` events keep sending the paths only, the `drag` events include the cursor state
         let _ = match event {
-          FileDropEvent::Hovered(paths) => {
-            window.emit(&tauri_event::<P::Event>("tauri://file-drop"), Some(paths))
-          }
-          FileDropEvent::Dropped(paths) => window.emit(
-            &tauri_event::<P::Event>("tauri://file-drop-hover"),
-            Some(paths),
-          ),
+          FileDropEvent::Hovered {
+            paths,
+            position,
+            modifiers,
+          } => window
+            .emit(
+              &tauri_event::<P::Event>(FILE_DROP_HOVER_EVENT),
+              Some(&paths),
+            )
+            .and_then(|_| {
+              window.emit(
+                &tauri_event::<P::Event>(DRAG_OVER_EVENT),
+                Some(FileDropPayload {
+                  paths,
+                  position,
+                  modifiers,
+                }),
+              )
+            }),
+          FileDropEvent::Dropped {
+            paths,
+            position,
+            modifiers,
+          } => window
+            .emit(&tauri_event::<P::Event>(FILE_DROP_EVENT), Some(&paths))
+            .and_then(|_| {
+              window.emit(
+                &tauri_event::<P::Event>(DRAG_DROP_EVENT),
+                Some(FileDropPayload {
+                  paths,
+                  position,
+                  modifiers,
+                }),
+              )
+            }),
           FileDropEvent::Cancelled => window.emit(
-            &tauri_event::<P::Event>("tauri://file-drop-cancelled"),
+            &tauri_event::<P::Event>(FILE_DROP_CANCELLED_EVENT),
             Some(()),
           ),
           _ => unimplemented!(),
@@ -470,6 +821,7 @@ impl<P: Params> WindowManager<P> {
     &self,
     plugin_initialization_script: &str,
     with_global_tauri: bool,
+    ipc_key: &str,
   ) -> String {
     format!(
       r#"
@@ -485,7 +837,8 @@ impl<P: Params> WindowManager<P> {
       }}
       {plugin_initialization_script}
     "#,
-      core_script = include_str!("../scripts/core.js"),
+      core_script =
+        include_str!("../scripts/core.js").replace("__TAURI_IPC_KEY__", &format!("{:?}", ipc_key)),
       bundle_script = if with_global_tauri {
         include_str!("../scripts/bundle.js")
       } else {
@@ -518,11 +871,14 @@ impl<P: Params> WindowManager<P> {
           }}
         }}).then(function (flag) {{
           if (flag) {{
-            for (let i = listeners.length - 1; i >= 0; i--) {{
-              const listener = listeners[i]
-              eventData.id = listener.id
-              listener.handler(eventData)
-            }}
+            return window.__TAURI__.__resolveBinary(eventData.payload).then(function (payload) {{
+              eventData.payload = payload
+              for (let i = listeners.length - 1; i >= 0; i--) {{
+                const listener = listeners[i]
+                eventData.id = listener.id
+                listener.handler(eventData)
+              }}
+            }})
           }}
         }})
       }}
@@ -537,24 +893,38 @@ impl<P: Params> WindowManager<P> {
 
 #[cfg(test)]
 mod test {
-  use super::{Args, WindowManager};
-  use crate::{generate_context, plugin::PluginStore, StateManager, Wry};
+  use super::{origin_matches, url_origin, Args, WindowManager};
+  use crate::{
+    api::assets::EmbeddedAssets, generate_context, plugin::PluginStore, StateManager, Wry,
+  };
+
+  type TestManager = WindowManager<Args<String, String, String, String, EmbeddedAssets, Wry>>;
+
+  /// A manager of the test fixture app without handlers.
+  fn test_manager() -> TestManager {
+    let context = generate_context!("test/fixture/src-tauri/tauri.conf.json", crate);
+    WindowManager::with_handlers(
+      context,
+      PluginStore::default(),
+      Box::new(|_| ()),
+      Vec::new(),
+      None,
+      Vec::new(),
+      Box::new(|_, _| ()),
+      Default::default(),
+      StateManager::new(),
+      Default::default(),
+      Default::default(),
+      #[cfg(feature = "menu")]
+      Default::default(),
+      #[cfg(feature = "touchbar")]
+      Default::default(),
+    )
+  }
 
   #[test]
   fn check_get_url() {
-    let context = generate_context!("test/fixture/src-tauri/tauri.conf.json", crate);
-    let manager: WindowManager<Args<String, String, String, String, _, Wry>> =
-      WindowManager::with_handlers(
-        context,
-        PluginStore::default(),
-        Box::new(|_| ()),
-        Box::new(|_, _| ()),
-        Default::default(),
-        StateManager::new(),
-        Default::default(),
-        #[cfg(feature = "menu")]
-        Default::default(),
-      );
+    let manager = test_manager();
 
     #[cfg(custom_protocol)]
     assert_eq!(manager.get_url(), "tauri://localhost");
@@ -562,6 +932,56 @@ mod test {
     #[cfg(dev)]
     assert_eq!(manager.get_url(), "http://localhost:4000/");
   }
+
+  #[test]
+  fn check_origin_matches() {
+    assert!(origin_matches("tauri://localhost", "tauri://localhost"));
+    assert!(!origin_matches(
+      "tauri://localhost",
+      "https://tauri.localhost"
+    ));
+    assert!(origin_matches("*", "https://example.com"));
+    assert!(origin_matches(
+      "https://*.example.com",
+      "https://app.example.com"
+    ));
+    assert!(!origin_matches(
+      "https://*.example.com",
+      "https://example.com"
+    ));
+    assert!(!origin_matches(
+      "https://*.example.com",
+      "https://app.example.com.evil.net"
+    ));
+  }
+
+  #[test]
+  fn check_url_origin() {
+    let origin = |url: &str| url_origin(&url.parse().unwrap());
+    assert_eq!(origin("tauri://localhost/index.html"), "tauri://localhost");
+    assert_eq!(
+      origin("http://localhost:4000/path?query"),
+      "http://localhost:4000"
+    );
+    assert_eq!(origin("https://example.com:443/"), "https://example.com");
+    assert_eq!(origin("about:blank"), "null");
+  }
+
+  #[test]
+  fn check_invoke_cancellation() {
+    let manager = test_manager();
+
+    manager.register_invoke("main".into(), "invoke".into());
+    // another window can't abort the invoke
+    manager.cancel_invoke(&"other".into(), "invoke");
+    assert!(!manager.cancellation_token("invoke").is_cancelled());
+    // the abort is kept until the command asks for its token
+    manager.cancel_invoke(&"main".into(), "invoke");
+    assert!(manager.cancellation_token("invoke").is_cancelled());
+    // resolved invokes can't be aborted anymore
+    manager.release_cancellation_token("invoke");
+    assert!(manager.cancellation_token("invoke").is_cancelled());
+  }
 }
 
 impl<P: Params> WindowManager<P> {
@@ -569,32 +989,366 @@ impl<P: Params> WindowManager<P> {
     (self.inner.invoke_handler)(invoke);
   }
 
+  /// The codec used to encode the command responses.
+  pub(crate) fn ipc_codec(&self) -> Option<&Arc<dyn IpcCodec>> {
+    self.inner.ipc_codec.as_ref()
+  }
+
+  /// The invoke responses and events captured by the [`crate::test`] helpers.
+  #[cfg(feature = "test")]
+  pub(crate) fn test_hooks(&self) -> &crate::test::TestHooks {
+    &self.inner.test_hooks
+  }
+
+  /// Creates the cancellation token of an invoke received by the given window,
+  /// so it can be aborted before the command asks for its token.
+  pub(crate) fn register_invoke(&self, label: P::Label, id: String) {
+    self
+      .inner
+      .cancellation_tokens
+      .lock()
+      .expect("poisoned cancellation tokens")
+      .insert(id, (label, CancellationToken::default()));
+  }
+
+  /// Gets the cancellation token of the given invoke.
+  ///
+  /// The token is already cancelled if the invoke isn't pending anymore.
+  pub(crate) fn cancellation_token(&self, id: &str) -> CancellationToken {
+    self
+      .inner
+      .cancellation_tokens
+      .lock()
+      .expect("poisoned cancellation tokens")
+      .get(id)
+      .map(|(_, token)| token.clone())
+      .unwrap_or_else(|| {
+        let token = CancellationToken::default();
+        token.cancel();
+        token
+      })
+  }
+
+  /// Cancels the token of the given invoke, if it was received by the given window.
+  ///
+  /// The token is kept until the invoke is resolved, so the command can still get it.
+  pub(crate) fn cancel_invoke(&self, label: &P::Label, id: &str) {
+    if let Some((token_label, token)) = self
+      .inner
+      .cancellation_tokens
+      .lock()
+      .expect("poisoned cancellation tokens")
+      .get(id)
+    {
+      if token_label == label {
+        token.cancel();
+      }
+    }
+  }
+
+  /// Cancels the tokens of all pending invokes of the given window.
+  pub(crate) fn cancel_window_invokes(&self, label: &P::Label) {
+    self
+      .inner
+      .cancellation_tokens
+      .lock()
+      .expect("poisoned cancellation tokens")
+      .retain(|_, (token_label, token)| {
+        if token_label == label {
+          token.cancel();
+          false
+        } else {
+          true
+        }
+      });
+    self
+      .inner
+      .observed_invokes
+      .lock()
+      .expect("poisoned observed invokes")
+      .retain(|_, (_, invoke_label, _, _)| invoke_label != label);
+  }
+
+  /// Drops the token of the given invoke once it has been resolved.
+  pub(crate) fn release_cancellation_token(&self, id: &str) {
+    self
+      .inner
+      .cancellation_tokens
+      .lock()
+      .expect("poisoned cancellation tokens")
+      .remove(id);
+  }
+
+  /// Checks the origin and frame of an IPC message,
+  /// triggering the `tauri://ipc-rejected` event if it isn't allowed.
+  ///
+  /// The origin is read from the URL of the webview and the main frame is proven by the key injected on it,
+  /// so they can't be spoofed by the message content.
+  pub(crate) fn validate_ipc_message(
+    &self,
+    label: &P::Label,
+    command: &str,
+    payload: &InvokePayload,
+  ) -> bool {
+    let main_frame = payload.ipc_key.is_some()
+      && self
+        .inner
+        .ipc_keys
+        .lock()
+        .expect("poisoned IPC keys")
+        .get(label)
+        == payload.ipc_key.as_ref();
+    let origin = payload.url.as_ref().map(url_origin);
+
+    let reason = if !main_frame && !self.inner.config.tauri.security.ipc_allow_iframes {
+      Some("iframes are not allowed to send IPC messages")
+    } else {
+      let origins = self
+        .inner
+        .ipc_allowed_origins
+        .lock()
+        .expect("poisoned IPC allowed origins");
+      match (origins.get(label), &origin) {
+        (None, _) => None,
+        (Some(origins), Some(origin)) if origins.iter().any(|o| origin_matches(o, origin)) => None,
+        (Some(_), Some(_)) => Some("the origin is not allowed to send IPC messages"),
+        (Some(_), None) => Some("the origin of the webview couldn't be read"),
+      }
+    };
+
+    match reason {
+      Some(reason) => {
+        let payload = IpcRejectedPayload {
+          window: label.to_string(),
+          command,
+          origin: origin.as_deref(),
+          main_frame,
+          reason,
+        };
+        self.trigger(
+          &tauri_event::<P::Event>(IPC_REJECTED_EVENT),
+          None,
+          serde_json::to_string(&payload).ok(),
+        );
+        false
+      }
+      None => true,
+    }
+  }
+
+  /// Starts observing the given invoke if there's any [`OnInvokeObserved`] hook.
+  pub(crate) fn observe_invoke(&self, message: &InvokeMessage<P>) {
+    #[cfg(feature = "tracing")]
+    tracing::debug!(
+      command = %message.command(),
+      window = %message.window_ref().label(),
+      "ipc::request"
+    );
+    if self.inner.invoke_observers.is_empty() && !cfg!(feature = "tracing") {
+      return;
+    }
+    let argument_size = serde_json::to_vec(message.payload())
+      .map(|a| a.len())
+      .unwrap_or_default();
+    self
+      .inner
+      .observed_invokes
+      .lock()
+      .expect("poisoned observed invokes")
+      .insert(
+        message.id.clone(),
+        (
+          message.command().to_string(),
+          message.window_ref().label().clone(),
+          argument_size,
+          Instant::now(),
+        ),
+      );
+  }
+
+  /// Reports the resolved invoke to the [`OnInvokeObserved`] hooks.
+  pub(crate) fn observe_invoke_resolved(&self, id: &str, success: bool) {
+    let observed = self
+      .inner
+      .observed_invokes
+      .lock()
+      .expect("poisoned observed invokes")
+      .remove(id);
+    if let Some((command, window, argument_size, started)) = observed {
+      let observation = InvokeObservation {
+        command,
+        window,
+        argument_size,
+        duration: started.elapsed(),
+        success,
+      };
+      #[cfg(feature = "tracing")]
+      tracing::debug!(
+        command = %observation.command,
+        window = %observation.window,
+        argument_size = observation.argument_size,
+        duration_ms = observation.duration.as_millis() as u64,
+        success = observation.success,
+        "ipc::response"
+      );
+      for observer in &self.inner.invoke_observers {
+        observer(observation.clone());
+      }
+    }
+  }
+
+  /// Runs the invoke middlewares, calling `handler` if the invoke reaches the end of the chain.
+  pub fn run_invoke_middlewares(&self, invoke: Invoke<P>, handler: &dyn Fn(Invoke<P>)) {
+    fn run<P: Params>(
+      middlewares: &[Box<InvokeMiddleware<P>>],
+      invoke: Invoke<P>,
+      handler: &dyn Fn(Invoke<P>),
+    ) {
+      match middlewares.split_first() {
+        Some((middleware, next)) => middleware(invoke, &|invoke| run(next, invoke, handler)),
+        None => handler(invoke),
+      }
+    }
+    run(&self.inner.invoke_middlewares, invoke, handler);
+  }
+
   pub fn run_on_page_load(&self, window: Window<P>, payload: PageLoadPayload) {
     (self.inner.on_page_load)(window.clone(), payload.clone());
+    self.plugins().on_page_load(window, payload);
+  }
+
+  pub fn extend_api(&self, invoke: Invoke<P>) {
+    self
+      .plugins()
+      .extend_api(invoke, &self.inner.config.tauri.security.plugin_scopes);
+  }
+
+  /// Runs the plugin hooks for a window event.
+  pub(crate) fn run_plugins_window_event(&self, label: &P::Label, event: &WindowEvent) {
+    let app_handle = self.app_handle();
+    let plugins = self.plugins();
+    if let WindowEvent::Destroyed = event {
+      plugins.on_window_destroyed(label);
+    }
+    if let Some(app_handle) = app_handle {
+      plugins.on_event(
+        &app_handle,
+        &RunEvent::WindowEvent {
+          label: label.clone(),
+          event: event.clone(),
+        },
+      );
+    }
+  }
+
+  /// Runs the plugin hooks for an updater event.
+  #[cfg(feature = "updater")]
+  pub(crate) fn run_plugins_updater_event(&self, event: crate::updater::UpdaterEvent) {
+    if let Some(app_handle) = self.app_handle() {
+      self
+        .plugins()
+        .on_event(&app_handle, &RunEvent::Updater(event));
+    }
+  }
+
+  /// Runs the plugin hooks for a power event.
+  #[cfg(feature = "power")]
+  pub(crate) fn run_plugins_power_event(&self, event: crate::power::PowerEvent) {
+    if let Some(app_handle) = self.app_handle() {
+      self
+        .plugins()
+        .on_event(&app_handle, &RunEvent::Power(event));
+    }
+  }
+
+  /// Runs the plugin hooks for an opened URL.
+  pub(crate) fn run_plugins_open_url(&self, url: &url::Url) {
+    if let Some(app_handle) = self.app_handle() {
+      self
+        .plugins()
+        .on_event(&app_handle, &RunEvent::OpenUrl(url.clone()));
+    }
+  }
+
+  /// Runs the plugin hooks for an opened file.
+  pub(crate) fn run_plugins_open_file(&self, path: &std::path::Path) {
+    if let Some(app_handle) = self.app_handle() {
+      self
+        .plugins()
+        .on_event(&app_handle, &RunEvent::OpenFile(path.to_path_buf()));
+    }
+  }
+
+  /// Runs the plugin hooks when the application is about to exit.
+  pub(crate) fn run_plugins_exit(&self) {
+    let app_handle = self.app_handle();
+    let plugins = self.plugins();
+    if let Some(app_handle) = app_handle {
+      plugins.on_event(&app_handle, &RunEvent::Exit);
+    }
+    plugins.on_exit();
+  }
+
+  #[cfg(feature = "system-tray")]
+  pub(crate) fn system_tray_icon(&self) -> Option<&Icon> {
+    self.inner.system_tray_icon.as_ref()
+  }
+
+  #[cfg(feature = "system-tray")]
+  pub(crate) fn tray_handle(&self) -> Option<SystemTrayHandle<P>> {
     self
       .inner
-      .plugins
+      .tray_handle
       .lock()
-      .expect("poisoned plugin store")
-      .on_page_load(window, payload);
+      .expect("poisoned tray handle")
+      .clone()
   }
 
-  pub fn extend_api(&self, invoke: Invoke<P>) {
+  #[cfg(feature = "system-tray")]
+  pub(crate) fn set_tray_handle(&self, tray_handle: SystemTrayHandle<P>) {
     self
       .inner
-      .plugins
+      .tray_handle
       .lock()
-      .expect("poisoned plugin store")
-      .extend_api(invoke);
+      .expect("poisoned tray handle")
+      .replace(tray_handle);
   }
 
-  pub fn initialize_plugins(&self, app: &App<P>) -> crate::Result<()> {
+  pub(crate) fn set_runtime_handle(&self, runtime_handle: <P::Runtime as Runtime>::Handle) {
+    self
+      .inner
+      .runtime_handle
+      .lock()
+      .expect("poisoned runtime handle")
+      .replace(runtime_handle);
+  }
+
+  /// The handle of the running application, if it's built.
+  fn app_handle(&self) -> Option<AppHandle<P>> {
+    self
+      .inner
+      .runtime_handle
+      .lock()
+      .expect("poisoned runtime handle")
+      .clone()
+      .map(|runtime_handle| AppHandle {
+        runtime_handle,
+        manager: self.clone(),
+      })
+  }
+
+  /// The plugins, cloned out of the store lock so their hooks can use the manager.
+  fn plugins(&self) -> PluginStore<P> {
     self
       .inner
       .plugins
       .lock()
       .expect("poisoned plugin store")
-      .initialize(app, &self.inner.config.plugins)
+      .clone()
+  }
+
+  pub fn initialize_plugins(&self, app: &App<P>) -> crate::Result<()> {
+    self.plugins().initialize(app, &self.inner.config.plugins)
   }
 
   pub fn prepare_window(
@@ -625,7 +1379,9 @@ impl<P: Params> WindowManager<P> {
       pending.rpc_handler = Some(self.prepare_rpc_handler());
     }
 
-    pending.file_drop_handler = Some(self.prepare_file_drop());
+    if pending.webview_attributes.file_drop_handler_enabled {
+      pending.file_drop_handler = Some(self.prepare_file_drop());
+    }
     pending.url = url;
 
     Ok(pending)
@@ -636,8 +1392,10 @@ impl<P: Params> WindowManager<P> {
 
     let window_ = window.clone();
     let window_event_listeners = self.inner.window_event_listeners.clone();
+    let manager = self.clone();
     window.on_window_event(move |event| {
       let _ = on_window_event(&window_, event);
+      manager.run_plugins_window_event(window_.label(), event);
       for handler in window_event_listeners.iter() {
         handler(GlobalWindowEvent {
           window: window_.clone(),
@@ -659,6 +1417,19 @@ impl<P: Params> WindowManager<P> {
         }
       });
     }
+    #[cfg(feature = "touchbar")]
+    {
+      let window_ = window.clone();
+      let touchbar_event_listeners = self.inner.touchbar_event_listeners.clone();
+      window.on_touchbar_event(move |event| {
+        for handler in touchbar_event_listeners.iter() {
+          handler(WindowTouchBarEvent {
+            event: event.clone(),
+            window: window_.clone(),
+          });
+        }
+      });
+    }
 
     // insert the window into our manager
     {
@@ -669,12 +1440,7 @@ impl<P: Params> WindowManager<P> {
 
     // let plugins know that a new window has been added to the manager
     {
-      self
-        .inner
-        .plugins
-        .lock()
-        .expect("poisoned plugin store")
-        .created(window.clone());
+      self.plugins().on_window_created(window.clone());
     }
 
     window
@@ -687,11 +1453,16 @@ impl<P: Params> WindowManager<P> {
     S: Serialize + Clone,
     F: Fn(&Window<P>) -> bool,
   {
-    self
-      .windows_lock()
-      .values()
-      .filter(|&w| filter(w))
-      .try_for_each(|window| window.emit(event, payload.clone()))
+    let lock = self.windows_lock();
+    let mut windows = lock.values().filter(|&w| filter(w));
+    let value = serde_json::to_value(&payload)?;
+    // binary payloads can only be fetched once, so each window needs its own copy
+    if crate::ipc::is_binary(&value) {
+      windows.try_for_each(|window| window.emit(event, payload.clone()))
+    } else {
+      let event = event.to_js_string()?;
+      windows.try_for_each(|window| window.emit_serialized(&event, &value))
+    }
   }
 
   pub fn labels(&self) -> HashSet<P::Label> {
@@ -734,6 +1505,15 @@ impl<P: Params> WindowManager<P> {
   ) -> EventHandler {
     self.inner.listeners.once(event, window, handler)
   }
+  pub fn event_handler_guard(&self, id: EventHandler) -> EventHandlerGuard<P::Event, P::Label> {
+    self.inner.listeners.guard(id)
+  }
+  pub fn unlisten_filter<F: Fn(&P::Event, Option<&P::Label>) -> bool>(&self, filter: F) {
+    self.inner.listeners.unlisten_filter(filter)
+  }
+  pub fn event_listeners(&self) -> Vec<EventListenerInfo<P::Event, P::Label>> {
+    self.inner.listeners.listeners()
+  }
   pub fn event_listeners_object_name(&self) -> String {
     self.inner.listeners.listeners_object_name()
   }
@@ -796,18 +1576,27 @@ fn on_window_event<P: Params>(window: &Window<P>, event: &WindowEvent) -> crate:
         .unwrap_or_else(|_| panic!("unhandled event")),
       Some(position),
     )?,
-    WindowEvent::CloseRequested => window.emit(
-      &WINDOW_CLOSE_REQUESTED_EVENT
-        .parse()
-        .unwrap_or_else(|_| panic!("unhandled event")),
-      Some(()),
-    )?,
-    WindowEvent::Destroyed => window.emit(
-      &WINDOW_DESTROYED_EVENT
-        .parse()
-        .unwrap_or_else(|_| panic!("unhandled event")),
-      Some(()),
-    )?,
+    WindowEvent::CloseRequested => {
+      window.manager().cancel_window_invokes(window.label());
+      window.emit(
+        &WINDOW_CLOSE_REQUESTED_EVENT
+          .parse()
+          .unwrap_or_else(|_| panic!("unhandled event")),
+        Some(()),
+      )?
+    }
+    WindowEvent::Destroyed => {
+      window.manager().cancel_window_invokes(window.label());
+      crate::ipc::remove_window_binaries(&window.label().to_string());
+      #[cfg(fs_watch)]
+      crate::endpoints::remove_window_watchers(&window.label().to_string());
+      window.emit(
+        &WINDOW_DESTROYED_EVENT
+          .parse()
+          .unwrap_or_else(|_| panic!("unhandled event")),
+        Some(()),
+      )?
+    }
     WindowEvent::Focused(focused) => window.emit(
       &if *focused {
         WINDOW_FOCUS_EVENT
@@ -833,6 +1622,18 @@ fn on_window_event<P: Params>(window: &Window<P>, event: &WindowEvent) -> crate:
         size: *new_inner_size,
       }),
     )?,
+    WindowEvent::Navigated(url) => window.emit(
+      &WINDOW_NAVIGATED_EVENT
+        .parse()
+        .unwrap_or_else(|_| panic!("unhandled event")),
+      Some(url.to_string()),
+    )?,
+    WindowEvent::ThumbbarButtonClicked(id) => window.emit(
+      &WINDOW_THUMBBAR_BUTTON_CLICK_EVENT
+        .parse()
+        .unwrap_or_else(|_| panic!("unhandled event")),
+      Some(id.clone()),
+    )?,
     _ => unimplemented!(),
   }
   Ok(())
@@ -845,6 +1646,46 @@ struct ScaleFactorChanged {
   size: PhysicalSize<u32>,
 }
 
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct IpcRejectedPayload<'a> {
+  window: String,
+  command: &'a str,
+  origin: Option<&'a str>,
+  main_frame: bool,
+  reason: &'static str,
+}
+
+/// The origin of the URL, serialized as `window.location.origin` even for the custom protocols.
+fn url_origin(url: &Url) -> String {
+  match (url.host_str(), url.port()) {
+    (Some(host), Some(port)) => format!("{}://{}:{}", url.scheme(), host, port),
+    (Some(host), None) => format!("{}://{}", url.scheme(), host),
+    (None, _) => "null".into(),
+  }
+}
+
+/// Whether the origin matches the pattern, which can have a `*` wildcard.
+fn origin_matches(pattern: &str, origin: &str) -> bool {
+  match pattern.find('*') {
+    Some(index) => {
+      let (prefix, suffix) = (&pattern[..index], &pattern[index + 1..]);
+      origin.len() >= prefix.len() + suffix.len()
+        && origin.starts_with(prefix)
+        && origin.ends_with(suffix)
+    }
+    None => pattern == origin,
+  }
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct FileDropPayload {
+  paths: Vec<PathBuf>,
+  position: PhysicalPosition<f64>,
+  modifiers: FileDropModifiers,
+}
+
 #[cfg(feature = "menu")]
 fn on_menu_event<P: Params>(window: &Window<P>, event: &MenuEvent<P::MenuId>) -> crate::Result<()> {
   window.emit(
@@ -0,0 +1,212 @@
+// Copyright 2019-2021 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+//! Delivery of the files opened with the types registered on `tauri.conf.json > tauri > bundle > fileAssociations`.
+//!
+//! On Windows and Linux the file is passed as an argument of a new process,
+//! so the arguments of the first instance and the ones forwarded by the next instances are scanned for files.
+//! On macOS the files are sent as an Apple Event to the running process, including when they launch the app.
+
+use crate::api::config::FileAssociation;
+
+use url::Url;
+
+use std::path::{Path, PathBuf};
+
+/// A file opened with one of the app file types, sent to the event loop.
+///
+/// It is a distinct type so the events sent by the application with an [`crate::EventLoopProxy`] can't be mistaken for it.
+pub(crate) struct OpenFileEvent(pub(crate) PathBuf);
+
+/// The arguments that are existing files with one of the associated extensions,
+/// given as paths relative to `cwd` or as `file://` URLs.
+pub(crate) fn files_from_args<I: IntoIterator<Item = String>>(
+  args: I,
+  cwd: &Path,
+  associations: &[FileAssociation],
+) -> Vec<PathBuf> {
+  args
+    .into_iter()
+    .filter_map(|arg| match Url::parse(&arg) {
+      Ok(url) if url.scheme() == "file" => url.to_file_path().ok(),
+      _ => Some(cwd.join(arg)),
+    })
+    .filter(|path| {
+      path.is_file()
+        && associations
+          .iter()
+          .any(|association| association.matches(path))
+    })
+    .collect()
+}
+
+#[cfg(target_os = "macos")]
+pub(crate) use macos::listen_apple_events;
+
+#[cfg(target_os = "macos")]
+mod macos {
+  use objc::{
+    class,
+    declare::ClassDecl,
+    msg_send,
+    runtime::{Object, Sel},
+    sel, sel_impl,
+  };
+  use once_cell::sync::OnceCell;
+  use url::Url;
+
+  use std::{
+    ffi::{CStr, CString},
+    os::raw::c_char,
+    path::PathBuf,
+    sync::Mutex,
+  };
+
+  /// `kCoreEventClass`, the `aevt` four-character code.
+  const CORE_EVENT_CLASS: u32 = 0x6165_7674;
+  /// `kAEOpenDocuments`, the `odoc` four-character code.
+  const OPEN_DOCUMENTS_EVENT: u32 = 0x6f64_6f63;
+  /// `keyDirectObject`, the `----` four-character code.
+  const KEY_DIRECT_OBJECT: u32 = 0x2d2d_2d2d;
+  /// `typeFileURL`, the `furl` four-character code.
+  const TYPE_FILE_URL: u32 = 0x6675_726c;
+
+  type Sender = Box<dyn Fn(PathBuf) -> bool + Send>;
+
+  static SENDER: OnceCell<Mutex<Sender>> = OnceCell::new();
+
+  unsafe fn descriptor_string(descriptor: *mut Object) -> Option<String> {
+    let string: *mut Object = msg_send![descriptor, stringValue];
+    if string.is_null() {
+      return None;
+    }
+    let bytes: *const c_char = msg_send![string, UTF8String];
+    if bytes.is_null() {
+      return None;
+    }
+    Some(CStr::from_ptr(bytes).to_string_lossy().into_owned())
+  }
+
+  extern "C" fn handle_open_documents(
+    _this: &Object,
+    _cmd: Sel,
+    event: *mut Object,
+    _reply: *mut Object,
+  ) {
+    let mut paths = Vec::new();
+    unsafe {
+      let list: *mut Object = msg_send![event, paramDescriptorForKeyword: KEY_DIRECT_OBJECT];
+      if list.is_null() {
+        return;
+      }
+      let count: isize = msg_send![list, numberOfItems];
+      // the descriptor list is one-based
+      for index in 1..=count {
+        let item: *mut Object = msg_send![list, descriptorAtIndex: index];
+        if item.is_null() {
+          continue;
+        }
+        let file_url: *mut Object = msg_send![item, coerceToDescriptorType: TYPE_FILE_URL];
+        if file_url.is_null() {
+          continue;
+        }
+        if let Some(path) = descriptor_string(file_url)
+          .and_then(|url| Url::parse(&url).ok())
+          .and_then(|url| url.to_file_path().ok())
+        {
+          paths.push(path);
+        }
+      }
+    }
+    if let Some(send) = SENDER.get() {
+      let send = send.lock().expect("poisoned open file sender");
+      for path in paths {
+        send(path);
+      }
+    }
+  }
+
+  extern "C" fn will_finish_launching(this: &Object, _cmd: Sel, _notification: *mut Object) {
+    unsafe {
+      let event_manager: *mut Object =
+        msg_send![class!(NSAppleEventManager), sharedAppleEventManager];
+      let _: () = msg_send![event_manager,
+        setEventHandler: this
+        andSelector: sel!(handleOpenDocuments:withReplyEvent:)
+        forEventClass: CORE_EVENT_CLASS
+        andEventID: OPEN_DOCUMENTS_EVENT];
+    }
+  }
+
+  /// Registers the `odoc` Apple Event handler, calling `send` with the opened files.
+  ///
+  /// Cocoa installs its own handler when the app finishes launching, so ours is installed
+  /// on the `NSApplicationWillFinishLaunchingNotification`, which comes after it and before the files that launched the app.
+  /// Must be called on the main thread before the event loop starts.
+  pub(crate) fn listen_apple_events<F: Fn(PathBuf) -> bool + Send + 'static>(send: F) {
+    if SENDER.set(Mutex::new(Box::new(send))).is_err() {
+      return;
+    }
+    let mut decl = match ClassDecl::new("TauriOpenDocumentsHandler", class!(NSObject)) {
+      Some(decl) => decl,
+      None => return,
+    };
+    let notification_name =
+      CString::new("NSApplicationWillFinishLaunchingNotification").expect("invalid notification");
+    unsafe {
+      decl.add_method(
+        sel!(handleOpenDocuments:withReplyEvent:),
+        handle_open_documents as extern "C" fn(&Object, Sel, *mut Object, *mut Object),
+      );
+      decl.add_method(
+        sel!(willFinishLaunching:),
+        will_finish_launching as extern "C" fn(&Object, Sel, *mut Object),
+      );
+      let handler_class = decl.register();
+      let handler: *mut Object = msg_send![handler_class, new];
+      let name: *mut Object =
+        msg_send![class!(NSString), stringWithUTF8String: notification_name.as_ptr()];
+      let notification_center: *mut Object = msg_send![class!(NSNotificationCenter), defaultCenter];
+      let _: () = msg_send![notification_center,
+        addObserver: handler
+        selector: sel!(willFinishLaunching:)
+        name: name
+        object: std::ptr::null_mut::<Object>()];
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::api::config::BundleTypeRole;
+
+  #[test]
+  fn finds_files_with_the_associated_extensions() {
+    let dir = tempfile::tempdir().unwrap();
+    std::fs::write(dir.path().join("notes.md"), "# notes").unwrap();
+    std::fs::write(dir.path().join("notes.txt"), "notes").unwrap();
+    let associations = vec![FileAssociation {
+      ext: vec!["md".into(), "markdown".into()],
+      name: None,
+      description: None,
+      mime_type: Some("text/markdown".into()),
+      role: BundleTypeRole::Editor,
+    }];
+
+    let absolute_url = Url::from_file_path(dir.path().join("notes.md")).unwrap();
+    let args = vec![
+      "--verbose".to_string(),
+      "notes.txt".to_string(),
+      "notes.md".to_string(),
+      "missing.md".to_string(),
+      absolute_url.to_string(),
+    ];
+    let files = files_from_args(args, dir.path(), &associations);
+    assert_eq!(
+      files,
+      vec![dir.path().join("notes.md"), dir.path().join("notes.md")]
+    );
+  }
+}
@@ -0,0 +1,63 @@
+// Copyright 2019-2021 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+use crate::{
+  api::path::{app_dir, resolve_path, resource_dir, BaseDirectory},
+  Config, PackageInfo,
+};
+
+use std::{
+  path::{Path, PathBuf},
+  sync::Arc,
+};
+
+/// Resolves the paths of the app resources and directories.
+///
+/// The resources are bundled with the directory structure of their `tauri > bundle > resources` paths,
+/// and `tauri dev` copies them next to the app executable with the same structure,
+/// so the resource paths resolve identically in development and on the bundles.
+///
+/// # Example
+/// ```rust,no_run
+/// tauri::Builder::default()
+///   .setup(|app| {
+///     let template = app
+///       .path_resolver()
+///       .resolve_resource("templates/report.html")
+///       .expect("failed to resolve the template");
+///     println!("{}", std::fs::read_to_string(template)?);
+///     Ok(())
+///   });
+/// ```
+#[derive(Debug, Clone)]
+pub struct PathResolver {
+  pub(crate) config: Arc<Config>,
+  pub(crate) package_info: PackageInfo,
+}
+
+impl PathResolver {
+  /// Resolves the path of a resource, as listed on `tauri > bundle > resources`.
+  ///
+  /// Paths out of the `src-tauri` directory resolve like they're bundled,
+  /// e.g. `../assets/logo.png` resolves to `<resource_dir>/_up_/assets/logo.png`.
+  pub fn resolve_resource<P: AsRef<Path>>(&self, path: P) -> Option<PathBuf> {
+    resolve_path(
+      &self.config,
+      &self.package_info,
+      path,
+      Some(BaseDirectory::Resource),
+    )
+    .ok()
+  }
+
+  /// Returns the path to the resource directory of this app.
+  pub fn resource_dir(&self) -> Option<PathBuf> {
+    resource_dir(&self.package_info)
+  }
+
+  /// Returns the path to the suggested directory for your app config files.
+  pub fn app_dir(&self) -> Option<PathBuf> {
+    app_dir(&self.config)
+  }
+}
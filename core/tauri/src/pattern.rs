@@ -0,0 +1,99 @@
+// Copyright 2019-2021 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+//! The application patterns, defining how the IPC messages reach the Rust core.
+
+use crate::api::assets::EmbeddedAssets;
+
+use std::sync::Arc;
+
+#[cfg(feature = "isolation")]
+pub(crate) use isolation::{IsolationKey, IsolationMessage, IsolationPayload};
+
+/// The custom protocol that serves the isolation application.
+#[cfg(feature = "isolation")]
+pub(crate) const ISOLATION_PROTOCOL: &str = "isolation";
+
+/// The application pattern.
+#[derive(Clone)]
+pub enum Pattern {
+  /// The webview sends the IPC messages directly to the Rust core.
+  Brownfield,
+  /// The IPC messages go through the isolation application, loaded on a sandboxed iframe.
+  ///
+  /// The isolation application can validate or modify the messages
+  /// by defining a `window.__TAURI_ISOLATION_HOOK__(payload)` function returning the payload to send,
+  /// or a falsy value to drop it.
+  /// The messages are then encrypted with a key generated when the application starts,
+  /// so the Rust core rejects any message that didn't go through the isolation application.
+  ///
+  /// Requires the `isolation` feature.
+  Isolation {
+    /// The assets of the isolation application.
+    assets: Arc<EmbeddedAssets>,
+  },
+}
+
+impl Default for Pattern {
+  fn default() -> Self {
+    Self::Brownfield
+  }
+}
+
+#[cfg(feature = "isolation")]
+mod isolation {
+  use aes_gcm::{
+    aead::{Aead, NewAead},
+    Aes256Gcm, Key, Nonce,
+  };
+  use rand::RngCore;
+  use serde::Deserialize;
+  use serde_json::Value as JsonValue;
+
+  /// The length of the AES-GCM nonce used to encrypt the messages.
+  const NONCE_LENGTH: usize = 12;
+
+  /// An encrypted IPC message sent by the isolation application.
+  #[derive(Deserialize)]
+  pub(crate) struct IsolationMessage {
+    nonce: Vec<u8>,
+    payload: Vec<u8>,
+  }
+
+  /// The decrypted content of an [`IsolationMessage`].
+  #[derive(Deserialize)]
+  pub(crate) struct IsolationPayload {
+    pub(crate) cmd: String,
+    pub(crate) message: JsonValue,
+  }
+
+  /// The AES-GCM key shared with the isolation application.
+  pub(crate) struct IsolationKey([u8; 32]);
+
+  impl IsolationKey {
+    pub(crate) fn generate() -> Self {
+      let mut key = [0; 32];
+      rand::thread_rng().fill_bytes(&mut key);
+      Self(key)
+    }
+
+    /// The script that encrypts the messages, injected on the isolation application.
+    pub(crate) fn runtime_script(&self) -> String {
+      include_str!("../scripts/isolation-runtime.js")
+        .replace("__TAURI_ISOLATION_KEY__", &format!("{:?}", self.0))
+    }
+
+    /// Decrypts a message sent by the isolation application.
+    pub(crate) fn decrypt(&self, message: IsolationMessage) -> crate::Result<IsolationPayload> {
+      if message.nonce.len() != NONCE_LENGTH {
+        return Err(crate::Error::IsolationDecryption);
+      }
+      let cipher = Aes256Gcm::new(Key::from_slice(&self.0));
+      let payload = cipher
+        .decrypt(Nonce::from_slice(&message.nonce), message.payload.as_ref())
+        .map_err(|_| crate::Error::IsolationDecryption)?;
+      serde_json::from_slice(&payload).map_err(Into::into)
+    }
+  }
+}
@@ -4,9 +4,15 @@
 
 //! Extend Tauri functionality.
 
-use crate::{api::config::PluginConfig, App, Invoke, PageLoadPayload, Params, Window};
+use crate::{
+  api::config::{PluginConfig, PluginScope},
+  App, AppHandle, Invoke, PageLoadPayload, Params, RunEvent, State, StateManager, Window,
+};
 use serde_json::Value as JsonValue;
-use std::collections::HashMap;
+use std::{
+  collections::HashMap,
+  sync::{Arc, Mutex},
+};
 
 /// The plugin result type.
 pub type Result<T> = std::result::Result<T, Box<dyn std::error::Error>>;
@@ -27,14 +33,45 @@ pub trait Plugin<P: Params>: Send {
   /// so global variables must be assigned to `window` instead of implicity declared.
   ///
   /// It's guaranteed that this script is executed before the page is loaded.
+  ///
+  /// The plugin namespace `window.__TAURI_PLUGINS__[name]` is defined before this script runs,
+  /// so it can be extended with the plugin JS API.
   fn initialization_script(&self) -> Option<String> {
     None
   }
 
+  /// The commands exposed on the plugin JS namespace.
+  ///
+  /// Each command is available as a camelCase function on `window.__TAURI_PLUGINS__[name]`,
+  /// invoking `plugin:name|command` with the given arguments.
+  /// Use the [`crate::plugin_commands`] macro to define it.
+  ///
+  /// If the plugin has a `tauri > security > pluginScopes` config, only the declared commands can be invoked,
+  /// on the windows allowed by the scope.
+  fn commands(&self) -> &'static [&'static str] {
+    &[]
+  }
+
   /// Callback invoked when the webview is created.
+  #[deprecated(note = "use `on_window_created` instead")]
   #[allow(unused_variables)]
   fn created(&mut self, window: Window<P>) {}
 
+  /// Callback invoked when a window is created.
+  #[allow(unused_variables)]
+  fn on_window_created(&mut self, window: Window<P>) {}
+
+  /// Callback invoked when a window is destroyed.
+  #[allow(unused_variables)]
+  fn on_window_destroyed(&mut self, label: &P::Label) {}
+
+  /// Callback invoked on every application event.
+  #[allow(unused_variables)]
+  fn on_event(&mut self, app: &AppHandle<P>, event: &RunEvent<P::Label>) {}
+
+  /// Callback invoked when the application is about to exit.
+  fn on_exit(&mut self) {}
+
   /// Callback invoked when the webview performs a navigation.
   #[allow(unused_variables)]
   fn on_page_load(&mut self, window: Window<P>, payload: PageLoadPayload) {}
@@ -44,10 +81,65 @@ pub trait Plugin<P: Params>: Send {
   fn extend_api(&mut self, invoke: Invoke<P>) {}
 }
 
+/// A handle to the state managed by a plugin.
+///
+/// The state is namespaced by the plugin name, so it doesn't collide with
+/// the application state or with the state of other plugins using the same types.
+/// Get it with [`crate::Manager#method.plugin_handle`].
+#[derive(Clone)]
+pub struct PluginHandle {
+  name: &'static str,
+  state: Arc<StateManager>,
+}
+
+impl PluginHandle {
+  pub(crate) fn new(name: &'static str, state: Arc<StateManager>) -> Self {
+    Self { name, state }
+  }
+
+  /// The name of the plugin owning this handle.
+  pub fn name(&self) -> &'static str {
+    self.name
+  }
+
+  /// Add `state` to the state managed by the plugin.
+  ///
+  /// Returns `false` if the plugin already manages a state of the same type.
+  pub fn manage<T>(&self, state: T) -> bool
+  where
+    T: Send + Sync + 'static,
+  {
+    self.state.set(state)
+  }
+
+  /// Gets the state of type `T` managed by the plugin.
+  ///
+  /// # Panics
+  ///
+  /// Panics if the plugin doesn't manage a state of type `T`.
+  pub fn state<T>(&self) -> State<'_, T>
+  where
+    T: Send + Sync + 'static,
+  {
+    self.state.get()
+  }
+}
+
 crate::manager::default_args! {
   /// Plugin collection type.
+  ///
+  /// Each plugin has its own lock, so the store can be cloned out of the manager
+  /// and the hooks can use the manager without deadlocking.
   pub(crate) struct PluginStore<P: Params> {
-    store: HashMap<&'static str, Box<dyn Plugin<P>>>,
+    store: HashMap<&'static str, Arc<Mutex<Box<dyn Plugin<P>>>>>,
+  }
+}
+
+impl<P: Params> Clone for PluginStore<P> {
+  fn clone(&self) -> Self {
+    Self {
+      store: self.store.clone(),
+    }
   }
 }
 
@@ -64,12 +156,26 @@ impl<P: Params> PluginStore<P> {
   ///
   /// Returns `true` if a plugin with the same name is already in the store.
   pub fn register<Plug: Plugin<P> + 'static>(&mut self, plugin: Plug) -> bool {
-    self.store.insert(plugin.name(), Box::new(plugin)).is_some()
+    self
+      .store
+      .insert(
+        plugin.name(),
+        Arc::new(Mutex::new(Box::new(plugin) as Box<dyn Plugin<P>>)),
+      )
+      .is_some()
+  }
+
+  /// The plugins of the store, locked one at a time.
+  fn plugins(&self) -> impl Iterator<Item = std::sync::MutexGuard<'_, Box<dyn Plugin<P>>>> {
+    self
+      .store
+      .values()
+      .map(|plugin| plugin.lock().expect("poisoned plugin"))
   }
 
   /// Initializes all plugins in the store.
-  pub(crate) fn initialize(&mut self, app: &App<P>, config: &PluginConfig) -> crate::Result<()> {
-    self.store.values_mut().try_for_each(|plugin| {
+  pub(crate) fn initialize(&self, app: &App<P>, config: &PluginConfig) -> crate::Result<()> {
+    self.plugins().try_for_each(|mut plugin| {
       plugin
         .initialize(
           app,
@@ -81,43 +187,90 @@ impl<P: Params> PluginStore<P> {
 
   /// Generates an initialization script from all plugins in the store.
   pub(crate) fn initialization_script(&self) -> String {
+    self.plugins().fold(String::new(), |acc, plugin| {
+      let namespace = format!(
+        r#"
+        (function () {{
+          var plugins = window.__TAURI_PLUGINS__ = window.__TAURI_PLUGINS__ || {{}};
+          var name = {name};
+          var plugin = plugins[name] = plugins[name] || {{}};
+          {commands}.forEach(function (command) {{
+            var fn = command.replace(/_([a-z])/g, function (_, c) {{ return c.toUpperCase() }});
+            plugin[fn] = function (args) {{
+              return window.__TAURI__.invoke('plugin:' + name + '|' + command, args)
+            }}
+          }})
+        }})();"#,
+        name = JsonValue::from(plugin.name()),
+        commands = JsonValue::from(plugin.commands().to_vec()),
+      );
+      match plugin.initialization_script() {
+        Some(script) => format!("{}{}\n(function () {{ {} }})();", acc, namespace, script),
+        None => format!("{}{}", acc, namespace),
+      }
+    })
+  }
+
+  /// Runs the window created hooks for all plugins in the store.
+  #[allow(deprecated)]
+  pub(crate) fn on_window_created(&self, window: Window<P>) {
+    self.plugins().for_each(|mut plugin| {
+      plugin.created(window.clone());
+      plugin.on_window_created(window.clone());
+    })
+  }
+
+  /// Runs the on_window_destroyed hook for all plugins in the store.
+  pub(crate) fn on_window_destroyed(&self, label: &P::Label) {
     self
-      .store
-      .values()
-      .filter_map(|p| p.initialization_script())
-      .fold(String::new(), |acc, script| {
-        format!("{}\n(function () {{ {} }})();", acc, script)
-      })
+      .plugins()
+      .for_each(|mut plugin| plugin.on_window_destroyed(label))
   }
 
-  /// Runs the created hook for all plugins in the store.
-  pub(crate) fn created(&mut self, window: Window<P>) {
+  /// Runs the on_event hook for all plugins in the store.
+  pub(crate) fn on_event(&self, app: &AppHandle<P>, event: &RunEvent<P::Label>) {
     self
-      .store
-      .values_mut()
-      .for_each(|plugin| plugin.created(window.clone()))
+      .plugins()
+      .for_each(|mut plugin| plugin.on_event(app, event))
+  }
+
+  /// Runs the on_exit hook for all plugins in the store.
+  pub(crate) fn on_exit(&self) {
+    self.plugins().for_each(|mut plugin| plugin.on_exit())
   }
 
   /// Runs the on_page_load hook for all plugins in the store.
-  pub(crate) fn on_page_load(&mut self, window: Window<P>, payload: PageLoadPayload) {
+  pub(crate) fn on_page_load(&self, window: Window<P>, payload: PageLoadPayload) {
     self
-      .store
-      .values_mut()
-      .for_each(|plugin| plugin.on_page_load(window.clone(), payload.clone()))
+      .plugins()
+      .for_each(|mut plugin| plugin.on_page_load(window.clone(), payload.clone()))
   }
 
-  pub(crate) fn extend_api(&mut self, mut invoke: Invoke<P>) {
+  /// Runs the plugin command, if the plugin declares it and its scope allows it.
+  pub(crate) fn extend_api(&self, mut invoke: Invoke<P>, scopes: &HashMap<String, PluginScope>) {
     let command = invoke.message.command.replace("plugin:", "");
     let mut tokens = command.split('|');
     // safe to unwrap: split always has a least one item
     let target = tokens.next().unwrap();
 
-    if let Some(plugin) = self.store.get_mut(target) {
+    if let Some(plugin) = self.store.get(target) {
+      let mut plugin = plugin.lock().expect("poisoned plugin");
       invoke.message.command = tokens
         .next()
         .map(|c| c.to_string())
         .unwrap_or_else(String::new);
-      plugin.extend_api(invoke);
+
+      let window = invoke.message.window.label().to_string();
+      match check_command(
+        target,
+        plugin.commands(),
+        scopes,
+        &window,
+        &invoke.message.command,
+      ) {
+        Ok(()) => plugin.extend_api(invoke),
+        Err(error) => invoke.resolver.reject(error),
+      }
     } else {
       invoke
         .resolver
@@ -125,3 +278,89 @@ impl<P: Params> PluginStore<P> {
     }
   }
 }
+
+/// Checks if the window can invoke the plugin command.
+///
+/// The plugins without a scope accept all their commands, otherwise the command must be declared
+/// on [`Plugin::commands`] and allowed by the scope.
+fn check_command(
+  plugin: &str,
+  declared: &[&str],
+  scopes: &HashMap<String, PluginScope>,
+  window: &str,
+  command: &str,
+) -> std::result::Result<(), String> {
+  let scope = match scopes.get(plugin) {
+    Some(scope) => scope,
+    None => return Ok(()),
+  };
+  if !declared.contains(&command) {
+    return Err(format!(
+      "command {} not found on plugin {}",
+      command, plugin
+    ));
+  }
+  if scope.allows(window, command) {
+    Ok(())
+  } else {
+    Err(format!(
+      "command {} of plugin {} not allowed on window {}",
+      command, plugin, window
+    ))
+  }
+}
+
+/// Lists the commands exposed on the plugin JS namespace, to be returned by [`Plugin::commands`].
+///
+/// Pair it with [`crate::generate_handler`] on [`Plugin::extend_api`] using the same command names.
+///
+/// # Examples
+///
+/// ```rust,ignore
+/// fn commands(&self) -> &'static [&'static str] {
+///   tauri::plugin_commands![read_file, write_file]
+/// }
+///
+/// fn extend_api(&mut self, invoke: Invoke<P>) {
+///   (self.invoke_handler)(invoke)
+/// }
+/// ```
+///
+/// The commands are then available as `window.__TAURI_PLUGINS__.myplugin.readFile(args)`.
+#[macro_export]
+macro_rules! plugin_commands {
+  ($($command:ident),* $(,)?) => {
+    &[$(stringify!($command)),*]
+  };
+}
+
+#[cfg(test)]
+mod test {
+  use super::check_command;
+  use crate::api::config::PluginScope;
+  use std::collections::HashMap;
+
+  #[test]
+  fn check_command_scopes() {
+    let mut scopes = HashMap::new();
+    scopes.insert(
+      "fs-extra".to_string(),
+      PluginScope {
+        windows: Some(vec!["settings".into()]),
+        commands: Some(vec!["read".into()]),
+      },
+    );
+    let declared = &["read", "write"];
+
+    assert!(check_command("fs-extra", declared, &scopes, "settings", "read").is_ok());
+    // not allowed by the scope
+    assert!(check_command("fs-extra", declared, &scopes, "main", "read").is_err());
+    assert!(check_command("fs-extra", declared, &scopes, "settings", "write").is_err());
+    // not declared by the plugin
+    assert!(check_command("fs-extra", declared, &scopes, "settings", "remove").is_err());
+    assert!(check_command("fs-extra", &[], &scopes, "settings", "read").is_err());
+    // plugin without a scope
+    assert!(check_command("other", declared, &scopes, "main", "read").is_ok());
+    assert!(check_command("other", &[], &scopes, "main", "remove").is_ok());
+  }
+}
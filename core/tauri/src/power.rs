@@ -0,0 +1,556 @@
+// Copyright 2019-2021 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+//! Power monitoring: the battery status and the [`PowerEvent`]s.
+//!
+//! While the app runs, the power status is monitored and its changes are dispatched to the plugins
+//! and to the [`App::run`](crate::App::run) callback as [`RunEvent::Power`](crate::RunEvent::Power) events.
+//!
+//! The sleep events come from `WM_POWERBROADCAST` on Windows, the `NSWorkspace` sleep notifications on macOS
+//! and the logind `PrepareForSleep` signal on Linux. The battery status and the lid state are polled.
+//!
+//! # Example
+//!
+//! ```rust,ignore
+//! fn main() {
+//!   let app = tauri::Builder::default()
+//!     .build(tauri::generate_context!())
+//!     .expect("error while building tauri application");
+//!   app.run(|_app_handle, event| {
+//!     if let tauri::RunEvent::Power(event) = event {
+//!       match event {
+//!         tauri::power::PowerEvent::OnBattery => pause_sync(),
+//!         tauri::power::PowerEvent::OnAC => resume_sync(),
+//!         tauri::power::PowerEvent::Resume => reconnect(),
+//!         _ => {}
+//!       }
+//!     }
+//!   });
+//! }
+//! ```
+
+use std::{
+  sync::{Arc, Mutex},
+  thread::spawn,
+  time::Duration,
+};
+
+/// How often the battery status and the lid state are polled.
+const POLL_INTERVAL: Duration = Duration::from_secs(5);
+
+/// The battery level under which [`PowerEvent::LowBattery`] is emitted.
+pub const LOW_BATTERY_LEVEL: f32 = 0.1;
+
+/// A change of the system power status.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum PowerEvent {
+  /// The system is going to sleep.
+  Suspend,
+  /// The system woke up.
+  Resume,
+  /// The system is now running on battery.
+  OnBattery,
+  /// The system is now plugged to an AC power source.
+  OnAC,
+  /// The battery level dropped below [`LOW_BATTERY_LEVEL`] while discharging.
+  LowBattery,
+  /// The lid was closed. Only emitted on Windows, macOS and Linux.
+  LidClosed,
+  /// The lid was opened. Only emitted on Windows, macOS and Linux.
+  LidOpened,
+}
+
+/// The battery charge state.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum BatteryState {
+  /// The battery is charging.
+  Charging,
+  /// The battery is discharging, i.e. the system is running on battery.
+  Discharging,
+  /// The battery is fully charged.
+  Full,
+  /// The battery is fully discharged.
+  Empty,
+  /// The state could not be determined.
+  Unknown,
+}
+
+/// The status of the battery.
+#[derive(Debug, Clone)]
+pub struct BatteryStatus {
+  /// The charge level, from `0.0` to `1.0`.
+  pub level: f32,
+  /// The charge state.
+  pub state: BatteryState,
+  /// The estimated time until the battery is empty, while discharging.
+  pub time_to_empty: Option<Duration>,
+}
+
+impl BatteryStatus {
+  /// Whether the system is running on battery.
+  pub fn is_on_battery(&self) -> bool {
+    matches!(self.state, BatteryState::Discharging | BatteryState::Empty)
+  }
+}
+
+impl From<&::battery::Battery> for BatteryStatus {
+  fn from(battery: &::battery::Battery) -> Self {
+    use ::battery::units::{ratio::ratio, time::second};
+    Self {
+      level: battery.state_of_charge().get::<ratio>(),
+      state: match battery.state() {
+        ::battery::State::Charging => BatteryState::Charging,
+        ::battery::State::Discharging => BatteryState::Discharging,
+        ::battery::State::Full => BatteryState::Full,
+        ::battery::State::Empty => BatteryState::Empty,
+        _ => BatteryState::Unknown,
+      },
+      time_to_empty: battery
+        .time_to_empty()
+        .map(|time| Duration::from_secs_f32(time.get::<second>())),
+    }
+  }
+}
+
+/// Reads the status of the main battery, or `None` if the machine has no battery.
+///
+/// # Example
+/// ```rust,no_run
+/// if let Some(battery) = tauri::power::battery_status().unwrap() {
+///   println!("battery at {:.0}%", battery.level * 100.0);
+/// }
+/// ```
+pub fn battery_status() -> crate::Result<Option<BatteryStatus>> {
+  let manager = ::battery::Manager::new()?;
+  let battery = manager.batteries()?.next().transpose()?;
+  Ok(battery.as_ref().map(BatteryStatus::from))
+}
+
+/// A [`PowerEvent`] sent by the monitor to the event loop.
+///
+/// It is a distinct type so the events sent by the application with an [`crate::EventLoopProxy`] can't be mistaken for it.
+pub(crate) struct PowerMonitorEvent(pub(crate) PowerEvent);
+
+/// Sends the power events to the event loop, from the monitor thread and the system callbacks.
+#[derive(Clone)]
+struct EventSender(Arc<Mutex<Box<dyn Fn(PowerEvent) -> bool + Send>>>);
+
+impl EventSender {
+  /// Sends the events, returning `false` if the event loop is gone.
+  fn send(&self, events: Vec<PowerEvent>) -> bool {
+    let send = self.0.lock().unwrap();
+    events.into_iter().all(|event| send(event))
+  }
+}
+
+/// The power status between two polls, turned into events when it changes.
+struct Monitor {
+  on_battery: Option<bool>,
+  low_battery_sent: bool,
+  lid_closed: Option<bool>,
+}
+
+impl Monitor {
+  fn new() -> Self {
+    Self {
+      on_battery: battery_status()
+        .ok()
+        .flatten()
+        .map(|battery| battery.is_on_battery()),
+      low_battery_sent: false,
+      lid_closed: None,
+    }
+  }
+
+  fn poll_battery(&mut self) -> Vec<PowerEvent> {
+    let mut events = Vec::new();
+    if let Ok(Some(battery)) = battery_status() {
+      let is_on_battery = battery.is_on_battery();
+      if self.on_battery.is_some() && self.on_battery != Some(is_on_battery) {
+        events.push(if is_on_battery {
+          PowerEvent::OnBattery
+        } else {
+          PowerEvent::OnAC
+        });
+      }
+      self.on_battery = Some(is_on_battery);
+
+      if !is_on_battery {
+        self.low_battery_sent = false;
+      } else if battery.level < LOW_BATTERY_LEVEL && !self.low_battery_sent {
+        events.push(PowerEvent::LowBattery);
+        self.low_battery_sent = true;
+      }
+    }
+    events
+  }
+
+  // The first state read is the initial one, so it doesn't emit an event
+  #[cfg_attr(
+    not(any(windows, target_os = "macos", target_os = "linux")),
+    allow(dead_code)
+  )]
+  fn update_lid(&mut self, closed: bool) -> Vec<PowerEvent> {
+    match self.lid_closed.replace(closed) {
+      Some(previous) if previous != closed => vec![if closed {
+        PowerEvent::LidClosed
+      } else {
+        PowerEvent::LidOpened
+      }],
+      _ => Vec::new(),
+    }
+  }
+}
+
+/// Spawns a thread monitoring the power status, sending its changes until `send` returns `false`.
+pub(crate) fn spawn_monitor<F: Fn(PowerEvent) -> bool + Send + 'static>(send: F) {
+  let sender = EventSender(Arc::new(Mutex::new(Box::new(send))));
+  #[cfg(target_os = "macos")]
+  platform::observe_sleep(sender.clone());
+  spawn(move || platform::run(sender, Monitor::new()));
+}
+
+// The sleep and lid events are sent to a hidden window with `WM_POWERBROADCAST`,
+// which also polls the battery with a timer.
+#[cfg(windows)]
+mod platform {
+  use super::{EventSender, Monitor, PowerEvent, POLL_INTERVAL};
+  use winapi::{
+    shared::{
+      guiddef::{IsEqualGUID, GUID},
+      minwindef::{DWORD, LPARAM, LRESULT, TRUE, UINT, WPARAM},
+      windef::HWND,
+    },
+    um::{
+      libloaderapi::GetModuleHandleW,
+      winuser::{
+        CreateWindowExW, DefWindowProcW, DispatchMessageW, GetMessageW, PostQuitMessage,
+        RegisterClassW, RegisterPowerSettingNotification, SetTimer, TranslateMessage,
+        DEVICE_NOTIFY_WINDOW_HANDLE, MSG, PBT_APMPOWERSTATUSCHANGE, PBT_APMRESUMEAUTOMATIC,
+        PBT_APMSUSPEND, PBT_POWERSETTINGCHANGE, POWERBROADCAST_SETTING, WM_POWERBROADCAST,
+        WM_TIMER, WNDCLASSW,
+      },
+    },
+  };
+
+  use std::{cell::RefCell, ffi::OsStr, iter::once, os::windows::ffi::OsStrExt, ptr::null_mut};
+
+  /// `GUID_LIDSWITCH_STATE_CHANGE`, the power setting of the lid state.
+  const GUID_LIDSWITCH_STATE_CHANGE: GUID = GUID {
+    Data1: 0xba3e_0f4d,
+    Data2: 0xb817,
+    Data3: 0x4094,
+    Data4: [0xa2, 0xd1, 0xd5, 0x63, 0x79, 0xe6, 0xa0, 0xf3],
+  };
+  const POLL_TIMER_ID: usize = 1;
+
+  thread_local! {
+    /// The monitor of the window, only accessed on the monitor thread.
+    static MONITOR: RefCell<Option<(EventSender, Monitor)>> = RefCell::new(None);
+  }
+
+  fn wide(value: &str) -> Vec<u16> {
+    OsStr::new(value).encode_wide().chain(once(0)).collect()
+  }
+
+  unsafe extern "system" fn window_proc(
+    hwnd: HWND,
+    msg: UINT,
+    wparam: WPARAM,
+    lparam: LPARAM,
+  ) -> LRESULT {
+    if msg != WM_POWERBROADCAST && msg != WM_TIMER {
+      return DefWindowProcW(hwnd, msg, wparam, lparam);
+    }
+    MONITOR.with(|state| {
+      if let Some((sender, monitor)) = &mut *state.borrow_mut() {
+        let events = if msg == WM_TIMER || wparam == PBT_APMPOWERSTATUSCHANGE as WPARAM {
+          monitor.poll_battery()
+        } else if wparam == PBT_APMSUSPEND as WPARAM {
+          vec![PowerEvent::Suspend]
+        } else if wparam == PBT_APMRESUMEAUTOMATIC as WPARAM {
+          // sent on every resume, unlike `PBT_APMRESUMESUSPEND` which requires a user input
+          vec![PowerEvent::Resume]
+        } else if wparam == PBT_POWERSETTINGCHANGE as WPARAM {
+          let setting = &*(lparam as *const POWERBROADCAST_SETTING);
+          if IsEqualGUID(&setting.PowerSetting, &GUID_LIDSWITCH_STATE_CHANGE)
+            && setting.DataLength as usize >= std::mem::size_of::<DWORD>()
+          {
+            // 0 when the lid is closed, 1 when it's opened
+            let state = (setting.Data.as_ptr() as *const DWORD).read_unaligned();
+            monitor.update_lid(state == 0)
+          } else {
+            Vec::new()
+          }
+        } else {
+          Vec::new()
+        };
+        if !sender.send(events) {
+          PostQuitMessage(0);
+        }
+      }
+    });
+    TRUE as LRESULT
+  }
+
+  pub fn run(sender: EventSender, monitor: Monitor) {
+    MONITOR.with(|state| state.borrow_mut().replace((sender, monitor)));
+    unsafe {
+      let class_name = wide("TauriPowerMonitor");
+      let instance = GetModuleHandleW(null_mut());
+      let mut class: WNDCLASSW = std::mem::zeroed();
+      class.lpfnWndProc = Some(window_proc);
+      class.hInstance = instance;
+      class.lpszClassName = class_name.as_ptr();
+      RegisterClassW(&class);
+      // the power broadcasts are only sent to the top-level windows, not to the message-only ones
+      let hwnd = CreateWindowExW(
+        0,
+        class_name.as_ptr(),
+        class_name.as_ptr(),
+        0,
+        0,
+        0,
+        0,
+        0,
+        null_mut(),
+        null_mut(),
+        instance,
+        null_mut(),
+      );
+      if hwnd.is_null() {
+        return;
+      }
+      // the current lid state is sent right away, and read as the initial state
+      RegisterPowerSettingNotification(
+        hwnd as _,
+        &GUID_LIDSWITCH_STATE_CHANGE,
+        DEVICE_NOTIFY_WINDOW_HANDLE,
+      );
+      SetTimer(hwnd, POLL_TIMER_ID, POLL_INTERVAL.as_millis() as UINT, None);
+
+      let mut msg: MSG = std::mem::zeroed();
+      while GetMessageW(&mut msg, null_mut(), 0, 0) > 0 {
+        TranslateMessage(&msg);
+        DispatchMessageW(&msg);
+      }
+    }
+  }
+}
+
+// The sleep events are observed on the `NSWorkspace` notification center, on the main thread,
+// and the battery and the lid (the `AppleClamshellState` of the power management) are polled.
+#[cfg(target_os = "macos")]
+mod platform {
+  use super::{EventSender, Monitor, PowerEvent, POLL_INTERVAL};
+  use core_foundation::{
+    base::{kCFAllocatorDefault, CFAllocatorRef, CFType, CFTypeRef, TCFType},
+    boolean::CFBoolean,
+    dictionary::CFMutableDictionaryRef,
+    string::{CFString, CFStringRef},
+  };
+  use objc::{
+    class,
+    declare::ClassDecl,
+    msg_send,
+    runtime::{Class, Object, Sel},
+    sel, sel_impl,
+  };
+
+  use std::{ffi::c_void, os::raw::c_char, thread::sleep};
+
+  #[allow(non_camel_case_types)]
+  type id = *mut Object;
+  type IoObject = u32;
+
+  #[link(name = "AppKit", kind = "framework")]
+  extern "C" {
+    static NSWorkspaceWillSleepNotification: id;
+    static NSWorkspaceDidWakeNotification: id;
+  }
+
+  #[link(name = "IOKit", kind = "framework")]
+  extern "C" {
+    static kIOMasterPortDefault: u32;
+    fn IOServiceMatching(name: *const c_char) -> CFMutableDictionaryRef;
+    fn IOServiceGetMatchingService(master_port: u32, matching: CFMutableDictionaryRef) -> IoObject;
+    fn IORegistryEntryCreateCFProperty(
+      entry: IoObject,
+      key: CFStringRef,
+      allocator: CFAllocatorRef,
+      options: u32,
+    ) -> CFTypeRef;
+    fn IOObjectRelease(object: IoObject) -> i32;
+  }
+
+  fn send_from(this: &Object, event: PowerEvent) {
+    unsafe {
+      let sender = *this.get_ivar::<*mut c_void>("sender") as *const EventSender;
+      if !sender.is_null() {
+        (*sender).send(vec![event]);
+      }
+    }
+  }
+
+  extern "C" fn will_sleep(this: &Object, _: Sel, _notification: id) {
+    send_from(this, PowerEvent::Suspend);
+  }
+
+  extern "C" fn did_wake(this: &Object, _: Sel, _notification: id) {
+    send_from(this, PowerEvent::Resume);
+  }
+
+  fn observer_class() -> &'static Class {
+    Class::get("TauriPowerObserver").unwrap_or_else(|| {
+      let mut decl = ClassDecl::new("TauriPowerObserver", class!(NSObject))
+        .expect("failed to declare the power observer class");
+      decl.add_ivar::<*mut c_void>("sender");
+      unsafe {
+        decl.add_method(
+          sel!(willSleep:),
+          will_sleep as extern "C" fn(&Object, Sel, id),
+        );
+        decl.add_method(sel!(didWake:), did_wake as extern "C" fn(&Object, Sel, id));
+      }
+      decl.register()
+    })
+  }
+
+  /// Observes the sleep and wake notifications, for the lifetime of the app.
+  pub fn observe_sleep(sender: EventSender) {
+    unsafe {
+      let observer: id = msg_send![observer_class(), new];
+      (*observer).set_ivar("sender", Box::into_raw(Box::new(sender)) as *mut c_void);
+      let workspace: id = msg_send![class!(NSWorkspace), sharedWorkspace];
+      let center: id = msg_send![workspace, notificationCenter];
+      let nil: id = std::ptr::null_mut();
+      let _: () = msg_send![center, addObserver: observer selector: sel!(willSleep:) name: NSWorkspaceWillSleepNotification object: nil];
+      let _: () = msg_send![center, addObserver: observer selector: sel!(didWake:) name: NSWorkspaceDidWakeNotification object: nil];
+    }
+  }
+
+  /// Whether the lid is closed, or `None` if the machine has no lid.
+  fn lid_closed() -> Option<bool> {
+    unsafe {
+      // the matching dictionary is consumed by `IOServiceGetMatchingService`
+      let service = IOServiceGetMatchingService(
+        kIOMasterPortDefault,
+        IOServiceMatching(b"IOPMrootDomain\0".as_ptr() as *const c_char),
+      );
+      if service == 0 {
+        return None;
+      }
+      let key = CFString::from_static_string("AppleClamshellState");
+      let value =
+        IORegistryEntryCreateCFProperty(service, key.as_concrete_TypeRef(), kCFAllocatorDefault, 0);
+      IOObjectRelease(service);
+      if value.is_null() {
+        return None;
+      }
+      CFType::wrap_under_create_rule(value)
+        .downcast::<CFBoolean>()
+        .map(bool::from)
+    }
+  }
+
+  pub fn run(sender: EventSender, mut monitor: Monitor) {
+    loop {
+      let mut events = monitor.poll_battery();
+      if let Some(closed) = lid_closed() {
+        events.extend(monitor.update_lid(closed));
+      }
+      if !sender.send(events) {
+        return;
+      }
+      sleep(POLL_INTERVAL);
+    }
+  }
+}
+
+// The sleep events are the logind `PrepareForSleep` signals, and the battery and the logind `LidClosed` property are polled.
+#[cfg(target_os = "linux")]
+mod platform {
+  use super::{EventSender, Monitor, PowerEvent, POLL_INTERVAL};
+  use dbus::{
+    blocking::{stdintf::org_freedesktop_dbus::Properties, Connection},
+    message::MatchRule,
+  };
+
+  use std::{
+    sync::{Arc, Mutex},
+    thread::sleep,
+    time::{Duration, Instant},
+  };
+
+  const LOGIND_DESTINATION: &str = "org.freedesktop.login1";
+  const LOGIND_PATH: &str = "/org/freedesktop/login1";
+  const LOGIND_MANAGER: &str = "org.freedesktop.login1.Manager";
+
+  fn lid_closed(connection: &Connection) -> Option<bool> {
+    connection
+      .with_proxy(LOGIND_DESTINATION, LOGIND_PATH, Duration::from_secs(1))
+      .get(LOGIND_MANAGER, "LidClosed")
+      .ok()
+  }
+
+  pub fn run(sender: EventSender, mut monitor: Monitor) {
+    let connection = Connection::new_system().ok();
+    let sleep_events = Arc::new(Mutex::new(Vec::new()));
+    if let Some(connection) = &connection {
+      let sleep_events = sleep_events.clone();
+      // `true` before the system sleeps, `false` once it resumes
+      let _ = connection.add_match(
+        MatchRule::new_signal(LOGIND_MANAGER, "PrepareForSleep"),
+        move |(sleeping,): (bool,), _, _| {
+          sleep_events.lock().unwrap().push(if sleeping {
+            PowerEvent::Suspend
+          } else {
+            PowerEvent::Resume
+          });
+          true
+        },
+      );
+    }
+
+    let mut last_poll: Option<Instant> = None;
+    loop {
+      match &connection {
+        // returns as soon as a message is received, so the sleep events are sent right away
+        Some(connection) => {
+          let _ = connection.process(POLL_INTERVAL);
+        }
+        None => sleep(POLL_INTERVAL),
+      }
+      let mut events: Vec<PowerEvent> = std::mem::take(&mut *sleep_events.lock().unwrap());
+      if last_poll.map_or(true, |last_poll| last_poll.elapsed() >= POLL_INTERVAL) {
+        last_poll.replace(Instant::now());
+        events.extend(monitor.poll_battery());
+        if let Some(closed) = connection.as_ref().and_then(lid_closed) {
+          events.extend(monitor.update_lid(closed));
+        }
+      }
+      if !sender.send(events) {
+        return;
+      }
+    }
+  }
+}
+
+// Only the battery is monitored on the other platforms.
+#[cfg(not(any(windows, target_os = "macos", target_os = "linux")))]
+mod platform {
+  use super::{EventSender, Monitor, POLL_INTERVAL};
+  use std::thread::sleep;
+
+  pub fn run(sender: EventSender, mut monitor: Monitor) {
+    loop {
+      sleep(POLL_INTERVAL);
+      if !sender.send(monitor.poll_battery()) {
+        return;
+      }
+    }
+  }
+}
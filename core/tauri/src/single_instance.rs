@@ -0,0 +1,349 @@
+// Copyright 2019-2021 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+//! Single instance enforcement: the first instance listens on a local socket named after the bundle identifier,
+//! and the next instances forward their arguments and working directory to it before exiting.
+
+use crate::{deep_link, open_file, AppHandle, Manager, Params};
+
+use interprocess::local_socket::{LocalSocketListener, LocalSocketStream, NameTypeSupport};
+use serde::{Deserialize, Serialize};
+
+use std::{
+  io::{BufRead, BufReader, ErrorKind, Write},
+  path::Path,
+  sync::mpsc::channel,
+};
+
+/// The handler of the invocations forwarded by the new instances, called with their arguments and working directory.
+pub(crate) type SingleInstanceHandler<P> =
+  Box<dyn Fn(&AppHandle<P>, Vec<String>, String) + Send + Sync>;
+
+/// An invocation of the app forwarded to the running instance, sent as a JSON line.
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+struct Invocation {
+  argv: Vec<String>,
+  cwd: String,
+}
+
+/// A named pipe on Windows, an abstract socket on Linux and a socket file on the platforms without namespaced sockets.
+///
+/// The name includes the user, and the session on Windows, so the instances of the other users are not reached.
+fn socket_name(identifier: &str) -> String {
+  let name = format!("{}-{}.sock", identifier, user_id());
+  match NameTypeSupport::query() {
+    NameTypeSupport::OnlyPaths => std::env::temp_dir().join(name).display().to_string(),
+    NameTypeSupport::OnlyNamespaced | NameTypeSupport::Both => format!("@{}", name),
+  }
+}
+
+#[cfg(unix)]
+fn user_id() -> String {
+  unsafe { libc::geteuid() }.to_string()
+}
+
+#[cfg(windows)]
+fn user_id() -> String {
+  use winapi::um::processthreadsapi::{GetCurrentProcessId, ProcessIdToSessionId};
+  let mut session = 0;
+  unsafe { ProcessIdToSessionId(GetCurrentProcessId(), &mut session) };
+  format!(
+    "{}-{}",
+    session,
+    std::env::var("USERNAME").unwrap_or_default()
+  )
+}
+
+/// Whether the process on the other end of the stream runs as the current user.
+#[cfg(target_os = "linux")]
+fn is_peer_current_user(stream: &LocalSocketStream) -> bool {
+  use std::os::unix::io::AsRawFd;
+  let mut credentials: libc::ucred = unsafe { std::mem::zeroed() };
+  let mut len = std::mem::size_of::<libc::ucred>() as libc::socklen_t;
+  let result = unsafe {
+    libc::getsockopt(
+      stream.as_raw_fd(),
+      libc::SOL_SOCKET,
+      libc::SO_PEERCRED,
+      &mut credentials as *mut libc::ucred as *mut libc::c_void,
+      &mut len,
+    )
+  };
+  result == 0 && credentials.uid == unsafe { libc::geteuid() }
+}
+
+/// Whether the process on the other end of the stream runs as the current user.
+#[cfg(all(unix, not(target_os = "linux")))]
+fn is_peer_current_user(stream: &LocalSocketStream) -> bool {
+  use std::os::unix::io::AsRawFd;
+  let mut uid = 0;
+  let mut gid = 0;
+  let result = unsafe { libc::getpeereid(stream.as_raw_fd(), &mut uid, &mut gid) };
+  result == 0 && uid == unsafe { libc::geteuid() }
+}
+
+/// Whether the process on the other end of the pipe runs as the current user.
+#[cfg(windows)]
+fn is_peer_current_user(stream: &LocalSocketStream, server: bool) -> bool {
+  use std::os::windows::io::AsRawHandle;
+  use winapi::um::{
+    processthreadsapi::GetCurrentProcessId,
+    securitybaseapi::EqualSid,
+    winbase::{GetNamedPipeClientProcessId, GetNamedPipeServerProcessId},
+    winnt::TOKEN_USER,
+  };
+
+  let handle = stream.as_raw_handle() as _;
+  let mut peer = 0;
+  let found = unsafe {
+    if server {
+      GetNamedPipeClientProcessId(handle, &mut peer)
+    } else {
+      GetNamedPipeServerProcessId(handle, &mut peer)
+    }
+  };
+  if found == 0 {
+    return false;
+  }
+  match (
+    process_user(peer),
+    process_user(unsafe { GetCurrentProcessId() }),
+  ) {
+    (Some(peer), Some(current)) => unsafe {
+      let peer = &*(peer.as_ptr() as *const TOKEN_USER);
+      let current = &*(current.as_ptr() as *const TOKEN_USER);
+      EqualSid(peer.User.Sid, current.User.Sid) != 0
+    },
+    _ => false,
+  }
+}
+
+/// The `TOKEN_USER` of the process.
+#[cfg(windows)]
+fn process_user(pid: u32) -> Option<Vec<u64>> {
+  use winapi::um::{
+    handleapi::CloseHandle,
+    processthreadsapi::{OpenProcess, OpenProcessToken},
+    securitybaseapi::GetTokenInformation,
+    winnt::{TokenUser, PROCESS_QUERY_LIMITED_INFORMATION, TOKEN_QUERY},
+  };
+
+  unsafe {
+    let process = OpenProcess(PROCESS_QUERY_LIMITED_INFORMATION, 0, pid);
+    if process.is_null() {
+      return None;
+    }
+    let mut token = std::ptr::null_mut();
+    let opened = OpenProcessToken(process, TOKEN_QUERY, &mut token);
+    CloseHandle(process);
+    if opened == 0 {
+      return None;
+    }
+    let mut len = 0;
+    GetTokenInformation(token, TokenUser, std::ptr::null_mut(), 0, &mut len);
+    // the buffer holds the SID after the `TOKEN_USER`, and must be aligned for it
+    let mut buffer = vec![0u64; (len as usize + 7) / 8];
+    let read = GetTokenInformation(token, TokenUser, buffer.as_mut_ptr() as _, len, &mut len);
+    CloseHandle(token);
+    if read == 0 {
+      None
+    } else {
+      Some(buffer)
+    }
+  }
+}
+
+/// Binds the socket of the first instance, or forwards this invocation to the instance already bound to it.
+///
+/// Returns the listener of the socket, or `None` if the invocation was forwarded.
+pub(crate) fn bind_or_forward(identifier: &str) -> std::io::Result<Option<LocalSocketListener>> {
+  bind_or_send(identifier, &current_invocation())
+}
+
+fn bind_or_send(
+  identifier: &str,
+  invocation: &Invocation,
+) -> std::io::Result<Option<LocalSocketListener>> {
+  let name = socket_name(identifier);
+  let error = match LocalSocketListener::bind(name.clone()) {
+    Ok(listener) => return Ok(Some(listener)),
+    Err(e) if is_in_use(&e) => e,
+    Err(e) => return Err(e),
+  };
+  match send_invocation(identifier, invocation) {
+    Ok(true) => Ok(None),
+    // the socket is held by the process of another user
+    Ok(false) => Err(error),
+    // no instance answered, so the socket file was left by an instance that crashed
+    Err(_) if !name.starts_with('@') => {
+      let _ = std::fs::remove_file(&name);
+      LocalSocketListener::bind(name).map(Some)
+    }
+    Err(_) => Err(error),
+  }
+}
+
+/// Whether the socket failed to bind because it's already bound.
+fn is_in_use(error: &std::io::Error) -> bool {
+  match error.kind() {
+    ErrorKind::AddrInUse => true,
+    // the first instance of a named pipe denies the creation of the next ones
+    #[cfg(windows)]
+    ErrorKind::PermissionDenied => true,
+    _ => false,
+  }
+}
+
+/// Sends the invocation to the instance listening on the socket.
+///
+/// Returns an error if no instance is listening, and `false` if the instance doesn't run as the current user,
+/// in which case the invocation isn't sent.
+fn send_invocation(identifier: &str, invocation: &Invocation) -> std::io::Result<bool> {
+  let mut stream = LocalSocketStream::connect(socket_name(identifier))?;
+  #[cfg(windows)]
+  let is_current_user = is_peer_current_user(&stream, false);
+  #[cfg(not(windows))]
+  let is_current_user = is_peer_current_user(&stream);
+  if !is_current_user {
+    return Ok(false);
+  }
+  if let Ok(mut line) = serde_json::to_vec(invocation) {
+    line.push(b'\n');
+    let _ = stream.write_all(&line);
+    let _ = stream.flush();
+  }
+  Ok(true)
+}
+
+/// Accepts the invocations sent by the instances of the current user on a separate thread.
+///
+/// Each stream is read on its own thread, so a stream that never sends its line doesn't block the next ones,
+/// and the invocations are handled in order on another thread.
+fn accept_invocations<F: Fn(Invocation) + Send + 'static>(listener: LocalSocketListener, f: F) {
+  let (tx, rx) = channel();
+  std::thread::spawn(move || {
+    for invocation in rx {
+      f(invocation);
+    }
+  });
+  std::thread::spawn(move || {
+    for stream in listener.incoming().filter_map(Result::ok) {
+      #[cfg(windows)]
+      let is_current_user = is_peer_current_user(&stream, true);
+      #[cfg(not(windows))]
+      let is_current_user = is_peer_current_user(&stream);
+      if !is_current_user {
+        continue;
+      }
+      let tx = tx.clone();
+      std::thread::spawn(move || {
+        let mut line = String::new();
+        if BufReader::new(stream).read_line(&mut line).is_err() {
+          return;
+        }
+        if let Ok(invocation) = serde_json::from_str::<Invocation>(&line) {
+          let _ = tx.send(invocation);
+        }
+      });
+    }
+  });
+}
+
+/// The invocation of this instance.
+fn current_invocation() -> Invocation {
+  Invocation {
+    argv: std::env::args().collect(),
+    cwd: std::env::current_dir()
+      .map(|cwd| cwd.display().to_string())
+      .unwrap_or_default(),
+  }
+}
+
+/// Listens to the invocations forwarded by the new instances on a separate thread,
+/// focusing the main window before calling the handler and sending the opened URLs and files.
+pub(crate) fn listen<P: Params>(
+  listener: LocalSocketListener,
+  app_handle: AppHandle<P>,
+  handler: SingleInstanceHandler<P>,
+) {
+  accept_invocations(listener, move |invocation| {
+    focus_main_window(&app_handle);
+    let config = app_handle.config();
+    let urls = deep_link::urls_from_args(
+      invocation.argv.iter().skip(1).cloned(),
+      &config.tauri.bundle.url_schemes,
+    );
+    let files = open_file::files_from_args(
+      invocation.argv.iter().skip(1).cloned(),
+      Path::new(&invocation.cwd),
+      &config.tauri.bundle.file_associations,
+    );
+    handler(&app_handle, invocation.argv, invocation.cwd);
+    let proxy = app_handle.create_proxy();
+    for url in urls {
+      let _ = proxy.send_event(deep_link::OpenUrlEvent(url));
+    }
+    for path in files {
+      let _ = proxy.send_event(open_file::OpenFileEvent(path));
+    }
+  });
+}
+
+/// Focuses the `main` window, or any window if there's no `main` window.
+fn focus_main_window<P: Params>(app_handle: &AppHandle<P>) {
+  let window = "main"
+    .parse::<P::Label>()
+    .ok()
+    .and_then(|label| app_handle.get_window(&label))
+    .or_else(|| app_handle.windows().into_iter().map(|(_, w)| w).next());
+  if let Some(window) = window {
+    let _ = window.unminimize();
+    let _ = window.show();
+    let _ = window.set_focus();
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::{bind_or_send, Invocation};
+  use interprocess::local_socket::LocalSocketStream;
+  use std::{sync::mpsc::channel, time::Duration};
+
+  fn invocation(arg: &str) -> Invocation {
+    Invocation {
+      argv: vec!["app".into(), arg.into()],
+      cwd: "/home/user".into(),
+    }
+  }
+
+  #[test]
+  fn forwards_the_second_instance() {
+    let identifier = format!("studio.tauri.test.{}", std::process::id());
+    let listener = bind_or_send(&identifier, &invocation("--first"))
+      .expect("failed to bind the socket")
+      .expect("the first instance didn't bind the socket");
+    let (tx, rx) = channel();
+    super::accept_invocations(listener, move |invocation| {
+      let _ = tx.send(invocation);
+    });
+
+    // a stream that never sends its invocation doesn't block the next ones
+    let _silent = LocalSocketStream::connect(super::socket_name(&identifier))
+      .expect("failed to connect to the socket");
+
+    assert!(bind_or_send(&identifier, &invocation("--second"))
+      .expect("failed to forward the invocation")
+      .is_none());
+    assert_eq!(
+      rx.recv_timeout(Duration::from_secs(5))
+        .expect("the invocation wasn't received"),
+      invocation("--second")
+    );
+
+    // no instance is running with another identifier
+    assert!(
+      super::send_invocation(&format!("{}.other", identifier), &invocation("--other")).is_err()
+    );
+  }
+}
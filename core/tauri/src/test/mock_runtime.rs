@@ -0,0 +1,954 @@
+// Copyright 2019-2021 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+//! A [`Runtime`] that doesn't create any native window, keeping the window state in memory.
+
+#[cfg(any(feature = "menu", feature = "system-tray"))]
+use crate::runtime::menu::MenuUpdate;
+#[cfg(feature = "touchbar")]
+use crate::runtime::touchbar::{TouchBar, TouchBarEvent};
+#[cfg(any(feature = "menu", feature = "system-tray", feature = "touchbar"))]
+use crate::runtime::MenuId;
+#[cfg(feature = "menu")]
+use crate::runtime::{menu::Menu, window::MenuEvent};
+#[cfg(feature = "system-tray")]
+use crate::runtime::{menu::TrayHandle, SystemTray, SystemTrayEvent};
+use crate::{
+  api::config::WindowConfig,
+  runtime::{
+    monitor::Monitor,
+    webview::{
+      Cookie, DataTypes, FindOptions, FindResult, PermissionRequest, PrintOptions, ProxyConfig,
+      WindowBuilder, WindowBuilderBase,
+    },
+    window::{
+      dpi::{PhysicalPosition, PhysicalSize, Position, Size},
+      DetachedWindow, DragItem, PendingWindow, ThumbbarButton, WindowEvent,
+    },
+    Dispatch, Icon, Params, Result, RunEvent, Runtime, RuntimeHandle, UserEvent,
+  },
+};
+
+use serde_json::Value as JsonValue;
+use url::Url;
+use uuid::Uuid;
+
+use std::{
+  collections::HashMap,
+  fmt,
+  path::PathBuf,
+  sync::{Arc, Mutex},
+};
+
+type UserEvents = Arc<Mutex<Vec<UserEvent>>>;
+type WindowEventListeners = Arc<Mutex<HashMap<Uuid, Box<dyn Fn(&WindowEvent) + Send>>>>;
+#[cfg(feature = "menu")]
+type MenuEventListeners = Arc<Mutex<HashMap<Uuid, Box<dyn Fn(&MenuEvent) + Send>>>>;
+#[cfg(feature = "touchbar")]
+type TouchBarEventListeners = Arc<Mutex<HashMap<Uuid, Box<dyn Fn(&TouchBarEvent) + Send>>>>;
+
+/// An operation performed on a window of the [`MockRuntime`].
+#[derive(Debug, Clone, PartialEq)]
+#[non_exhaustive]
+pub enum WindowOperation {
+  /// [`Dispatch::center`].
+  Center,
+  /// [`Dispatch::print`].
+  Print,
+  /// [`Dispatch::print_with_options`].
+  PrintWithOptions(PrintOptions),
+  /// [`Dispatch::print_to_pdf`].
+  PrintToPdf(PathBuf, PrintOptions),
+  /// [`Dispatch::open_devtools`].
+  OpenDevtools,
+  /// [`Dispatch::close_devtools`].
+  CloseDevtools,
+  /// [`Dispatch::find`].
+  Find(String),
+  /// [`Dispatch::clear_find`].
+  ClearFind,
+  /// [`Dispatch::inject_css`].
+  InjectCss(String),
+  /// [`Dispatch::remove_css`].
+  RemoveCss(Uuid),
+  /// [`Dispatch::add_init_script`].
+  AddInitScript(String),
+  /// [`Dispatch::set_muted`].
+  SetMuted(bool),
+  /// [`Dispatch::set_user_agent`].
+  SetUserAgent(String),
+  /// [`Dispatch::set_zoom`].
+  SetZoom(f64),
+  /// [`Dispatch::clear_browsing_data`].
+  ClearBrowsingData(DataTypes),
+  /// [`Dispatch::set_resizable`].
+  SetResizable(bool),
+  /// [`Dispatch::set_title`].
+  SetTitle(String),
+  /// [`Dispatch::maximize`].
+  Maximize,
+  /// [`Dispatch::unmaximize`].
+  Unmaximize,
+  /// [`Dispatch::minimize`].
+  Minimize,
+  /// [`Dispatch::unminimize`].
+  Unminimize,
+  /// [`Dispatch::show`].
+  Show,
+  /// [`Dispatch::hide`].
+  Hide,
+  /// [`Dispatch::close`].
+  Close,
+  /// [`Dispatch::set_decorations`].
+  SetDecorations(bool),
+  /// [`Dispatch::set_always_on_top`].
+  SetAlwaysOnTop(bool),
+  /// [`Dispatch::set_size`].
+  SetSize(Size),
+  /// [`Dispatch::set_min_size`].
+  SetMinSize(Option<Size>),
+  /// [`Dispatch::set_max_size`].
+  SetMaxSize(Option<Size>),
+  /// [`Dispatch::set_position`].
+  SetPosition(Position),
+  /// [`Dispatch::set_fullscreen`].
+  SetFullscreen(bool),
+  /// [`Dispatch::set_focus`].
+  SetFocus,
+  /// [`Dispatch::set_icon`].
+  SetIcon,
+  /// [`Dispatch::set_skip_taskbar`].
+  SetSkipTaskbar(bool),
+  /// [`Dispatch::start_dragging`].
+  StartDragging,
+  /// [`Dispatch::start_drag`].
+  StartDrag(DragItem),
+  /// [`Dispatch::set_thumbbar_buttons`], with the button ids.
+  SetThumbbarButtons(Vec<String>),
+  /// [`Dispatch::set_touchbar`], with the runtime ids of the Touch Bar items.
+  #[cfg(feature = "touchbar")]
+  SetTouchBar(Option<Vec<u32>>),
+  /// [`Dispatch::eval_script`] and [`Dispatch::eval_script_with_result`].
+  EvalScript(String),
+}
+
+/// The in-memory state of a [`MockRuntime`] window.
+#[derive(Debug, Clone)]
+struct WindowState {
+  url: String,
+  title: String,
+  visible: bool,
+  maximized: bool,
+  fullscreen: bool,
+  decorated: bool,
+  resizable: bool,
+  #[cfg(feature = "devtools")]
+  devtools_open: bool,
+  zoom: f64,
+  size: PhysicalSize<u32>,
+  position: PhysicalPosition<i32>,
+  cookies: Vec<Cookie>,
+  operations: Vec<WindowOperation>,
+}
+
+/// A [`WindowBuilder`] that stores the initial state of the [`MockRuntime`] windows.
+#[derive(Debug, Clone)]
+pub struct MockWindowBuilder {
+  title: String,
+  visible: bool,
+  maximized: bool,
+  fullscreen: bool,
+  decorated: bool,
+  resizable: bool,
+  size: PhysicalSize<u32>,
+  position: PhysicalPosition<i32>,
+  has_icon: bool,
+  #[cfg(feature = "menu")]
+  has_menu: bool,
+}
+
+impl WindowBuilderBase for MockWindowBuilder {}
+impl WindowBuilder for MockWindowBuilder {
+  fn new() -> Self {
+    Self {
+      title: "Tauri App".into(),
+      visible: true,
+      maximized: false,
+      fullscreen: false,
+      decorated: true,
+      resizable: true,
+      size: PhysicalSize {
+        width: 800,
+        height: 600,
+      },
+      position: Default::default(),
+      has_icon: false,
+      #[cfg(feature = "menu")]
+      has_menu: false,
+    }
+  }
+
+  fn with_config(config: WindowConfig) -> Self {
+    let mut builder = Self::new()
+      .title(config.title)
+      .inner_size(config.width, config.height)
+      .visible(config.visible)
+      .maximized(config.maximized)
+      .fullscreen(config.fullscreen)
+      .decorations(config.decorations)
+      .resizable(config.resizable);
+    if let (Some(x), Some(y)) = (config.x, config.y) {
+      builder = builder.position(x, y);
+    }
+    builder
+  }
+
+  #[cfg(feature = "menu")]
+  fn menu<I: MenuId>(mut self, _menu: Menu<I>) -> Self {
+    self.has_menu = true;
+    self
+  }
+
+  fn center(self) -> Self {
+    self
+  }
+
+  fn position(mut self, x: f64, y: f64) -> Self {
+    self.position = PhysicalPosition {
+      x: x as i32,
+      y: y as i32,
+    };
+    self
+  }
+
+  fn inner_size(mut self, width: f64, height: f64) -> Self {
+    self.size = PhysicalSize {
+      width: width as u32,
+      height: height as u32,
+    };
+    self
+  }
+
+  fn min_inner_size(self, _min_width: f64, _min_height: f64) -> Self {
+    self
+  }
+
+  fn max_inner_size(self, _max_width: f64, _max_height: f64) -> Self {
+    self
+  }
+
+  fn resizable(mut self, resizable: bool) -> Self {
+    self.resizable = resizable;
+    self
+  }
+
+  fn title<S: Into<String>>(mut self, title: S) -> Self {
+    self.title = title.into();
+    self
+  }
+
+  fn fullscreen(mut self, fullscreen: bool) -> Self {
+    self.fullscreen = fullscreen;
+    self
+  }
+
+  fn focus(self) -> Self {
+    self
+  }
+
+  fn maximized(mut self, maximized: bool) -> Self {
+    self.maximized = maximized;
+    self
+  }
+
+  fn visible(mut self, visible: bool) -> Self {
+    self.visible = visible;
+    self
+  }
+
+  fn transparent(self, _transparent: bool) -> Self {
+    self
+  }
+
+  fn decorations(mut self, decorations: bool) -> Self {
+    self.decorated = decorations;
+    self
+  }
+
+  fn always_on_top(self, _always_on_top: bool) -> Self {
+    self
+  }
+
+  fn icon(mut self, _icon: Icon) -> Result<Self> {
+    self.has_icon = true;
+    Ok(self)
+  }
+
+  fn skip_taskbar(self, _skip: bool) -> Self {
+    self
+  }
+
+  fn proxy(self, _proxy: ProxyConfig) -> Self {
+    self
+  }
+
+  fn user_agent<S: Into<String>>(self, _user_agent: S) -> Self {
+    self
+  }
+
+  fn incognito(self, _incognito: bool) -> Self {
+    self
+  }
+
+  fn spellcheck(self, _enabled: bool) -> Self {
+    self
+  }
+
+  fn spellcheck_languages(self, _languages: Vec<String>) -> Self {
+    self
+  }
+
+  fn on_navigation<F: Fn(&Url) -> bool + Send + Sync + 'static>(self, _handler: F) -> Self {
+    self
+  }
+
+  fn on_permission_request<F: Fn(&PermissionRequest) -> bool + Send + Sync + 'static>(
+    self,
+    _handler: F,
+  ) -> Self {
+    self
+  }
+
+  #[cfg(windows)]
+  fn parent_window(self, _parent: crate::runtime::webview::HWND) -> Self {
+    self
+  }
+
+  #[cfg(windows)]
+  fn owner_window(self, _owner: crate::runtime::webview::HWND) -> Self {
+    self
+  }
+
+  fn has_icon(&self) -> bool {
+    self.has_icon
+  }
+
+  #[cfg(feature = "menu")]
+  fn has_menu(&self) -> bool {
+    self.has_menu
+  }
+}
+
+/// The [`Dispatch`] of the [`MockRuntime`] windows, recording the window operations.
+#[derive(Clone)]
+pub struct MockDispatcher {
+  state: Arc<Mutex<WindowState>>,
+  window_event_listeners: WindowEventListeners,
+  #[cfg(feature = "menu")]
+  menu_event_listeners: MenuEventListeners,
+  #[cfg(feature = "touchbar")]
+  touchbar_event_listeners: TouchBarEventListeners,
+}
+
+impl fmt::Debug for MockDispatcher {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    f.debug_struct("MockDispatcher")
+      .field("state", &self.state)
+      .finish()
+  }
+}
+
+impl MockDispatcher {
+  fn new(builder: MockWindowBuilder, url: String) -> Self {
+    Self {
+      state: Arc::new(Mutex::new(WindowState {
+        url,
+        title: builder.title,
+        visible: builder.visible,
+        maximized: builder.maximized,
+        fullscreen: builder.fullscreen,
+        decorated: builder.decorated,
+        resizable: builder.resizable,
+        #[cfg(feature = "devtools")]
+        devtools_open: false,
+        zoom: 1.0,
+        size: builder.size,
+        position: builder.position,
+        cookies: Vec::new(),
+        operations: Vec::new(),
+      })),
+      window_event_listeners: Default::default(),
+      #[cfg(feature = "menu")]
+      menu_event_listeners: Default::default(),
+      #[cfg(feature = "touchbar")]
+      touchbar_event_listeners: Default::default(),
+    }
+  }
+
+  fn state(&self) -> std::sync::MutexGuard<'_, WindowState> {
+    self.state.lock().expect("poisoned mock window state")
+  }
+
+  fn record<F: FnOnce(&mut WindowState)>(
+    &self,
+    operation: WindowOperation,
+    update: F,
+  ) -> Result<()> {
+    let mut state = self.state();
+    update(&mut state);
+    state.operations.push(operation);
+    Ok(())
+  }
+
+  /// The operations performed on the window, in order.
+  pub fn operations(&self) -> Vec<WindowOperation> {
+    self.state().operations.clone()
+  }
+
+  /// Clears the recorded window operations.
+  pub fn clear_operations(&self) {
+    self.state().operations.clear();
+  }
+
+  /// The URL loaded on the window.
+  pub fn url(&self) -> String {
+    self.state().url.clone()
+  }
+
+  /// The window title.
+  pub fn title(&self) -> String {
+    self.state().title.clone()
+  }
+
+  /// Sends an event to the window event listeners, as if it came from the window system.
+  pub fn trigger_window_event(&self, event: &WindowEvent) {
+    for listener in self
+      .window_event_listeners
+      .lock()
+      .expect("poisoned window event listeners")
+      .values()
+    {
+      listener(event);
+    }
+  }
+
+  /// Sends a menu event to the window menu event listeners, as if the menu item was clicked.
+  #[cfg(feature = "menu")]
+  #[cfg_attr(doc_cfg, doc(cfg(feature = "menu")))]
+  pub fn trigger_menu_event(&self, event: &MenuEvent) {
+    for listener in self
+      .menu_event_listeners
+      .lock()
+      .expect("poisoned menu event listeners")
+      .values()
+    {
+      listener(event);
+    }
+  }
+
+  /// Sends a Touch Bar event to the window Touch Bar event listeners, as if the item was used.
+  #[cfg(feature = "touchbar")]
+  #[cfg_attr(doc_cfg, doc(cfg(feature = "touchbar")))]
+  pub fn trigger_touchbar_event(&self, event: &TouchBarEvent) {
+    for listener in self
+      .touchbar_event_listeners
+      .lock()
+      .expect("poisoned touchbar event listeners")
+      .values()
+    {
+      listener(event);
+    }
+  }
+}
+
+impl Dispatch for MockDispatcher {
+  type Runtime = MockRuntime;
+  type WindowBuilder = MockWindowBuilder;
+
+  fn run_on_main_thread<F: FnOnce() + Send + 'static>(&self, f: F) -> Result<()> {
+    f();
+    Ok(())
+  }
+
+  fn on_window_event<F: Fn(&WindowEvent) + Send + 'static>(&self, f: F) -> Uuid {
+    let id = Uuid::new_v4();
+    self
+      .window_event_listeners
+      .lock()
+      .expect("poisoned window event listeners")
+      .insert(id, Box::new(f));
+    id
+  }
+
+  #[cfg(feature = "menu")]
+  fn on_menu_event<F: Fn(&MenuEvent) + Send + 'static>(&self, f: F) -> Uuid {
+    let id = Uuid::new_v4();
+    self
+      .menu_event_listeners
+      .lock()
+      .expect("poisoned menu event listeners")
+      .insert(id, Box::new(f));
+    id
+  }
+
+  #[cfg(feature = "touchbar")]
+  fn on_touchbar_event<F: Fn(&TouchBarEvent) + Send + 'static>(&self, f: F) -> Uuid {
+    let id = Uuid::new_v4();
+    self
+      .touchbar_event_listeners
+      .lock()
+      .expect("poisoned touchbar event listeners")
+      .insert(id, Box::new(f));
+    id
+  }
+
+  // Getters
+
+  fn scale_factor(&self) -> Result<f64> {
+    Ok(1.0)
+  }
+
+  fn inner_position(&self) -> Result<PhysicalPosition<i32>> {
+    Ok(self.state().position)
+  }
+
+  fn outer_position(&self) -> Result<PhysicalPosition<i32>> {
+    Ok(self.state().position)
+  }
+
+  fn inner_size(&self) -> Result<PhysicalSize<u32>> {
+    Ok(self.state().size)
+  }
+
+  fn outer_size(&self) -> Result<PhysicalSize<u32>> {
+    Ok(self.state().size)
+  }
+
+  fn is_fullscreen(&self) -> Result<bool> {
+    Ok(self.state().fullscreen)
+  }
+
+  fn is_maximized(&self) -> Result<bool> {
+    Ok(self.state().maximized)
+  }
+
+  fn is_decorated(&self) -> Result<bool> {
+    Ok(self.state().decorated)
+  }
+
+  fn is_resizable(&self) -> Result<bool> {
+    Ok(self.state().resizable)
+  }
+
+  fn is_visible(&self) -> Result<bool> {
+    Ok(self.state().visible)
+  }
+
+  fn current_monitor(&self) -> Result<Option<Monitor>> {
+    Ok(None)
+  }
+
+  fn primary_monitor(&self) -> Result<Option<Monitor>> {
+    Ok(None)
+  }
+
+  fn available_monitors(&self) -> Result<Vec<Monitor>> {
+    Ok(Vec::new())
+  }
+
+  fn zoom(&self) -> Result<f64> {
+    Ok(self.state().zoom)
+  }
+
+  fn is_playing_audio(&self) -> Result<bool> {
+    Ok(false)
+  }
+
+  #[cfg(feature = "devtools")]
+  fn is_devtools_open(&self) -> Result<bool> {
+    Ok(self.state().devtools_open)
+  }
+
+  #[cfg(windows)]
+  fn hwnd(&self) -> Result<*mut std::ffi::c_void> {
+    Ok(std::ptr::null_mut())
+  }
+
+  // Setters
+
+  fn center(&self) -> Result<()> {
+    self.record(WindowOperation::Center, |_| {})
+  }
+
+  fn print(&self) -> Result<()> {
+    self.record(WindowOperation::Print, |_| {})
+  }
+
+  fn print_with_options(&self, options: PrintOptions) -> Result<()> {
+    self.record(WindowOperation::PrintWithOptions(options), |_| {})
+  }
+
+  fn print_to_pdf(&self, path: PathBuf, options: PrintOptions) -> Result<()> {
+    self.record(WindowOperation::PrintToPdf(path, options), |_| {})
+  }
+
+  #[cfg(feature = "devtools")]
+  fn open_devtools(&self) -> Result<()> {
+    self.record(WindowOperation::OpenDevtools, |state| {
+      state.devtools_open = true
+    })
+  }
+
+  #[cfg(feature = "devtools")]
+  fn close_devtools(&self) -> Result<()> {
+    self.record(WindowOperation::CloseDevtools, |state| {
+      state.devtools_open = false
+    })
+  }
+
+  fn find(&self, text: String, _options: FindOptions) -> Result<FindResult> {
+    self.record(WindowOperation::Find(text), |_| {})?;
+    Ok(FindResult::default())
+  }
+
+  fn find_next(&self) -> Result<bool> {
+    Ok(false)
+  }
+
+  fn find_previous(&self) -> Result<bool> {
+    Ok(false)
+  }
+
+  fn clear_find(&self) -> Result<()> {
+    self.record(WindowOperation::ClearFind, |_| {})
+  }
+
+  fn inject_css(&self, css: String) -> Result<Uuid> {
+    self.record(WindowOperation::InjectCss(css), |_| {})?;
+    Ok(Uuid::new_v4())
+  }
+
+  fn remove_css(&self, id: Uuid) -> Result<()> {
+    self.record(WindowOperation::RemoveCss(id), |_| {})
+  }
+
+  fn add_init_script(&self, script: String) -> Result<()> {
+    self.record(WindowOperation::AddInitScript(script), |_| {})
+  }
+
+  fn set_muted(&self, muted: bool) -> Result<()> {
+    self.record(WindowOperation::SetMuted(muted), |_| {})
+  }
+
+  fn set_user_agent(&self, user_agent: String) -> Result<()> {
+    self.record(WindowOperation::SetUserAgent(user_agent), |_| {})
+  }
+
+  fn set_zoom(&self, scale_factor: f64) -> Result<()> {
+    self.record(WindowOperation::SetZoom(scale_factor), |state| {
+      state.zoom = scale_factor
+    })
+  }
+
+  fn clear_browsing_data(&self, data_types: DataTypes) -> Result<()> {
+    self.record(WindowOperation::ClearBrowsingData(data_types), |state| {
+      if data_types.cookies {
+        state.cookies.clear();
+      }
+    })
+  }
+
+  fn cookies(&self) -> Result<Vec<Cookie>> {
+    Ok(self.state().cookies.clone())
+  }
+
+  fn set_cookie(&self, cookie: Cookie) -> Result<()> {
+    let mut state = self.state();
+    state
+      .cookies
+      .retain(|c| c.name != cookie.name || c.path != cookie.path);
+    state.cookies.push(cookie);
+    Ok(())
+  }
+
+  fn delete_cookie(&self, name: &str, path: Option<&str>) -> Result<()> {
+    self
+      .state()
+      .cookies
+      .retain(|c| c.name != name || c.path.as_deref() != path);
+    Ok(())
+  }
+
+  fn create_window<P: Params<Runtime = Self::Runtime>>(
+    &mut self,
+    pending: PendingWindow<P>,
+  ) -> Result<DetachedWindow<P>> {
+    Ok(detach(pending))
+  }
+
+  fn set_resizable(&self, resizable: bool) -> Result<()> {
+    self.record(WindowOperation::SetResizable(resizable), |state| {
+      state.resizable = resizable
+    })
+  }
+
+  fn set_title<S: Into<String>>(&self, title: S) -> Result<()> {
+    let title = title.into();
+    self.record(WindowOperation::SetTitle(title.clone()), |state| {
+      state.title = title
+    })
+  }
+
+  fn maximize(&self) -> Result<()> {
+    self.record(WindowOperation::Maximize, |state| state.maximized = true)
+  }
+
+  fn unmaximize(&self) -> Result<()> {
+    self.record(WindowOperation::Unmaximize, |state| state.maximized = false)
+  }
+
+  fn minimize(&self) -> Result<()> {
+    self.record(WindowOperation::Minimize, |_| {})
+  }
+
+  fn unminimize(&self) -> Result<()> {
+    self.record(WindowOperation::Unminimize, |_| {})
+  }
+
+  fn show(&self) -> Result<()> {
+    self.record(WindowOperation::Show, |state| state.visible = true)
+  }
+
+  fn hide(&self) -> Result<()> {
+    self.record(WindowOperation::Hide, |state| state.visible = false)
+  }
+
+  fn close(&self) -> Result<()> {
+    self.record(WindowOperation::Close, |state| state.visible = false)?;
+    self.trigger_window_event(&WindowEvent::Destroyed);
+    Ok(())
+  }
+
+  fn set_decorations(&self, decorations: bool) -> Result<()> {
+    self.record(WindowOperation::SetDecorations(decorations), |state| {
+      state.decorated = decorations
+    })
+  }
+
+  fn set_always_on_top(&self, always_on_top: bool) -> Result<()> {
+    self.record(WindowOperation::SetAlwaysOnTop(always_on_top), |_| {})
+  }
+
+  fn set_size(&self, size: Size) -> Result<()> {
+    self.record(WindowOperation::SetSize(size), |state| {
+      state.size = match size {
+        Size::Physical(size) => size,
+        Size::Logical(size) => PhysicalSize {
+          width: size.width as u32,
+          height: size.height as u32,
+        },
+      }
+    })
+  }
+
+  fn set_min_size(&self, size: Option<Size>) -> Result<()> {
+    self.record(WindowOperation::SetMinSize(size), |_| {})
+  }
+
+  fn set_max_size(&self, size: Option<Size>) -> Result<()> {
+    self.record(WindowOperation::SetMaxSize(size), |_| {})
+  }
+
+  fn set_position(&self, position: Position) -> Result<()> {
+    self.record(WindowOperation::SetPosition(position), |state| {
+      state.position = match position {
+        Position::Physical(position) => position,
+        Position::Logical(position) => PhysicalPosition {
+          x: position.x as i32,
+          y: position.y as i32,
+        },
+      }
+    })
+  }
+
+  fn set_fullscreen(&self, fullscreen: bool) -> Result<()> {
+    self.record(WindowOperation::SetFullscreen(fullscreen), |state| {
+      state.fullscreen = fullscreen
+    })
+  }
+
+  fn set_focus(&self) -> Result<()> {
+    self.record(WindowOperation::SetFocus, |_| {})
+  }
+
+  fn set_icon(&self, _icon: Icon) -> Result<()> {
+    self.record(WindowOperation::SetIcon, |_| {})
+  }
+
+  fn set_skip_taskbar(&self, skip: bool) -> Result<()> {
+    self.record(WindowOperation::SetSkipTaskbar(skip), |_| {})
+  }
+
+  fn start_dragging(&self) -> Result<()> {
+    self.record(WindowOperation::StartDragging, |_| {})
+  }
+
+  fn start_drag(&self, item: DragItem) -> Result<()> {
+    self.record(WindowOperation::StartDrag(item), |_| {})
+  }
+
+  fn set_thumbbar_buttons(&self, buttons: Vec<ThumbbarButton>) -> Result<()> {
+    self.record(
+      WindowOperation::SetThumbbarButtons(buttons.into_iter().map(|button| button.id).collect()),
+      |_| {},
+    )
+  }
+
+  #[cfg(feature = "touchbar")]
+  fn set_touchbar<I: MenuId>(&self, touchbar: Option<TouchBar<I>>) -> Result<()> {
+    let ids = touchbar.map(|touchbar| {
+      touchbar
+        .items
+        .iter()
+        .filter_map(|item| item.id_value())
+        .collect()
+    });
+    self.record(WindowOperation::SetTouchBar(ids), |_| {})
+  }
+
+  fn eval_script<S: Into<String>>(&self, script: S) -> Result<()> {
+    self.record(WindowOperation::EvalScript(script.into()), |_| {})
+  }
+
+  fn eval_script_with_result<S: Into<String>>(&self, script: S) -> Result<JsonValue> {
+    self.record(WindowOperation::EvalScript(script.into()), |_| {})?;
+    Ok(JsonValue::Null)
+  }
+
+  #[cfg(feature = "menu")]
+  fn update_menu_item(&self, _id: u32, _update: MenuUpdate) -> Result<()> {
+    Ok(())
+  }
+}
+
+/// The system tray handle of the [`MockRuntime`].
+#[cfg(feature = "system-tray")]
+#[cfg_attr(doc_cfg, doc(cfg(feature = "system-tray")))]
+#[derive(Debug, Clone)]
+pub struct MockTrayHandler;
+
+#[cfg(feature = "system-tray")]
+impl TrayHandle for MockTrayHandler {
+  fn set_icon(&self, _icon: Icon) -> Result<()> {
+    Ok(())
+  }
+
+  fn update_item(&self, _id: u32, _update: MenuUpdate) -> Result<()> {
+    Ok(())
+  }
+}
+
+/// The [`RuntimeHandle`] of the [`MockRuntime`].
+#[derive(Debug, Clone)]
+pub struct MockRuntimeHandle {
+  user_events: UserEvents,
+}
+
+impl RuntimeHandle for MockRuntimeHandle {
+  type Runtime = MockRuntime;
+
+  fn create_window<P: Params<Runtime = Self::Runtime>>(
+    &self,
+    pending: PendingWindow<P>,
+  ) -> Result<DetachedWindow<P>> {
+    Ok(detach(pending))
+  }
+
+  #[cfg(feature = "system-tray")]
+  fn system_tray<I: MenuId>(&self, _system_tray: SystemTray<I>) -> Result<MockTrayHandler> {
+    Ok(MockTrayHandler)
+  }
+
+  /// The events are queued and delivered when the runtime runs.
+  fn send_event(&self, event: UserEvent) -> Result<()> {
+    self
+      .user_events
+      .lock()
+      .expect("poisoned user events")
+      .push(event);
+    Ok(())
+  }
+
+  #[cfg(all(windows, feature = "system-tray"))]
+  fn remove_system_tray(&self) -> Result<()> {
+    Ok(())
+  }
+}
+
+/// A [`Runtime`] that needs no display server, used to test Tauri applications.
+///
+/// The windows are kept in memory: their state is updated by the window setters,
+/// and every operation is recorded to be asserted with [`MockDispatcher::operations`].
+#[derive(Debug)]
+pub struct MockRuntime {
+  user_events: UserEvents,
+}
+
+impl Runtime for MockRuntime {
+  type Dispatcher = MockDispatcher;
+  type Handle = MockRuntimeHandle;
+  #[cfg(feature = "system-tray")]
+  type TrayHandler = MockTrayHandler;
+
+  fn new() -> Result<Self> {
+    Ok(Self {
+      user_events: Default::default(),
+    })
+  }
+
+  fn handle(&self) -> Self::Handle {
+    MockRuntimeHandle {
+      user_events: self.user_events.clone(),
+    }
+  }
+
+  fn create_window<P: Params<Runtime = Self>>(
+    &self,
+    pending: PendingWindow<P>,
+  ) -> Result<DetachedWindow<P>> {
+    Ok(detach(pending))
+  }
+
+  #[cfg(feature = "system-tray")]
+  fn system_tray<I: MenuId>(&self, _system_tray: SystemTray<I>) -> Result<Self::TrayHandler> {
+    Ok(MockTrayHandler)
+  }
+
+  #[cfg(feature = "system-tray")]
+  fn on_system_tray_event<F: Fn(&SystemTrayEvent) + Send + 'static>(&mut self, _f: F) -> Uuid {
+    Uuid::new_v4()
+  }
+
+  #[cfg(any(target_os = "windows", target_os = "macos"))]
+  fn run_iteration(&mut self) -> crate::runtime::RunIteration {
+    Default::default()
+  }
+
+  /// There's no event loop to run, so the callback is called right away
+  /// with the user events sent so far, then with [`RunEvent::Exit`].
+  fn run<F: FnMut(RunEvent) + 'static>(self, mut callback: F) {
+    let user_events = std::mem::take(&mut *self.user_events.lock().expect("poisoned user events"));
+    for event in user_events {
+      callback(RunEvent::UserEvent(event));
+    }
+    callback(RunEvent::Exit)
+  }
+}
+
+fn detach<P: Params<Runtime = MockRuntime>>(pending: PendingWindow<P>) -> DetachedWindow<P> {
+  DetachedWindow {
+    label: pending.label,
+    dispatcher: MockDispatcher::new(pending.window_builder, pending.url),
+  }
+}
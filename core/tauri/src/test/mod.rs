@@ -0,0 +1,417 @@
+// Copyright 2019-2021 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+//! Utilities to unit test Tauri applications and plugins without a display server.
+//!
+//! The [`MockRuntime`] keeps the windows in memory, so the app can be built on CI,
+//! the commands invoked with [`invoke`] and the emitted events and window operations asserted.
+//!
+//! # Example
+//!
+//! ```
+//! use tauri::{test::{invoke, mock_builder, mock_context, noop_assets}, Manager};
+//!
+//! #[tauri::command]
+//! fn greet(name: String) -> String {
+//!   format!("Hello, {}!", name)
+//! }
+//!
+//! let app = mock_builder()
+//!   .invoke_handler(tauri::generate_handler![greet])
+//!   .build(mock_context(noop_assets()))
+//!   .unwrap();
+//! let window = app.get_window("main").unwrap();
+//!
+//! assert_eq!(
+//!   invoke(&window, "greet", serde_json::json!({ "name": "Tauri" })),
+//!   Ok("Hello, Tauri!".into())
+//! );
+//! ```
+
+mod mock_runtime;
+
+pub use mock_runtime::*;
+
+use crate::{
+  api::{assets::AssetKey, config::Config, PackageInfo},
+  manager::Args,
+  runtime::{webview::InvokePayload, Params},
+  sealed::ManagerBase,
+  App, Assets, Builder, Context, Pattern, Window,
+};
+
+use serde_json::Value as JsonValue;
+use uuid::Uuid;
+
+use std::{
+  borrow::Cow,
+  collections::HashMap,
+  sync::{
+    mpsc::{channel, Sender},
+    Arc, Mutex,
+  },
+};
+
+/// The [`Params`] of the applications built with [`mock_builder`].
+pub type MockParams<A = NoopAsset> = Args<String, String, String, String, A, MockRuntime>;
+
+/// An [`Assets`] implementation without any asset.
+#[derive(Debug, Default)]
+pub struct NoopAsset;
+
+impl Assets for NoopAsset {
+  fn get<Key: Into<AssetKey>>(&self, _key: Key) -> Option<Cow<'_, [u8]>> {
+    None
+  }
+}
+
+/// Creates an [`Assets`] implementation without any asset.
+pub fn noop_assets() -> NoopAsset {
+  NoopAsset
+}
+
+/// Creates a [`Context`] with the default config, which defines the `main` window.
+pub fn mock_context<A: Assets>(assets: A) -> Context<A> {
+  Context {
+    config: Config::default(),
+    assets: Arc::new(assets),
+    default_window_icon: None,
+    system_tray_icon: None,
+    package_info: PackageInfo {
+      name: "test".into(),
+      version: "0.1.0".into(),
+    },
+    pattern: Pattern::Brownfield,
+    sidecar_checksums: HashMap::new(),
+  }
+}
+
+/// Creates a [`Builder`] using the [`MockRuntime`].
+pub fn mock_builder() -> Builder<String, String, String, String, NoopAsset, MockRuntime> {
+  Builder::new()
+}
+
+/// Builds an [`App`] with the [`MockRuntime`] and the [`mock_context`].
+pub fn mock_app() -> App<MockParams> {
+  mock_builder()
+    .build(mock_context(noop_assets()))
+    .expect("failed to build the mock app")
+}
+
+/// Invokes the command on the window, as the webview does, and waits for its response.
+///
+/// The `payload` is the object with the command arguments.
+/// Note that this blocks forever if the command never resolves its invoke.
+pub fn invoke<P: Params>(
+  window: &Window<P>,
+  command: &str,
+  payload: JsonValue,
+) -> Result<JsonValue, JsonValue> {
+  let mut payload = match payload {
+    JsonValue::Object(payload) => payload,
+    JsonValue::Null => Default::default(),
+    _ => return Err("the invoke payload must be an object".into()),
+  };
+  let callback = format!("_{}", Uuid::new_v4().to_simple());
+  payload.insert("callback".into(), callback.clone().into());
+  payload.insert(
+    "error".into(),
+    format!("_{}", Uuid::new_v4().to_simple()).into(),
+  );
+  let payload: InvokePayload =
+    serde_json::from_value(payload.into()).map_err(|e| JsonValue::String(e.to_string()))?;
+
+  let (tx, rx) = channel();
+  window
+    .manager()
+    .test_hooks()
+    .responders
+    .lock()
+    .expect("poisoned test responders")
+    .insert(callback.clone(), tx);
+  if let Err(e) = window.clone().on_message(command.into(), payload) {
+    window.manager().test_hooks().take_responder(&callback);
+    return Err(e.to_string().into());
+  }
+  rx.recv()
+    .expect("the invoke was dropped without a response")
+}
+
+/// Gets the [`MockDispatcher`] of the window, to assert on its state and operations
+/// or to trigger window events.
+pub fn mock_dispatcher<P: Params<Runtime = MockRuntime>>(window: &Window<P>) -> MockDispatcher {
+  window.dispatcher()
+}
+
+/// The events emitted to the windows of the app, in order.
+pub fn emitted_events<P: Params>(app: &App<P>) -> Vec<EmittedEvent> {
+  app
+    .manager()
+    .test_hooks()
+    .events
+    .lock()
+    .expect("poisoned test events")
+    .clone()
+}
+
+/// An event emitted to a window.
+#[derive(Debug, Clone, PartialEq)]
+pub struct EmittedEvent {
+  window: String,
+  event: String,
+  payload: JsonValue,
+}
+
+impl EmittedEvent {
+  /// The label of the window that received the event.
+  pub fn window(&self) -> &str {
+    &self.window
+  }
+
+  /// The event name.
+  pub fn event(&self) -> &str {
+    &self.event
+  }
+
+  /// The event payload.
+  pub fn payload(&self) -> &JsonValue {
+    &self.payload
+  }
+}
+
+/// Captures the invoke responses and the events emitted by the application.
+#[derive(Default)]
+pub(crate) struct TestHooks {
+  /// The senders of the invoke responses, mapped by callback id.
+  responders: Mutex<HashMap<String, Sender<Result<JsonValue, JsonValue>>>>,
+  events: Mutex<Vec<EmittedEvent>>,
+}
+
+impl TestHooks {
+  pub(crate) fn has_responder(&self, callback: &str) -> bool {
+    self
+      .responders
+      .lock()
+      .expect("poisoned test responders")
+      .contains_key(callback)
+  }
+
+  pub(crate) fn take_responder(
+    &self,
+    callback: &str,
+  ) -> Option<Sender<Result<JsonValue, JsonValue>>> {
+    self
+      .responders
+      .lock()
+      .expect("poisoned test responders")
+      .remove(callback)
+  }
+
+  /// Records an event, whose name is serialized as a JS string.
+  pub(crate) fn record_event(&self, window: String, event: &str, payload: &JsonValue) {
+    self
+      .events
+      .lock()
+      .expect("poisoned test events")
+      .push(EmittedEvent {
+        window,
+        event: serde_json::from_str(event).unwrap_or_else(|_| event.to_string()),
+        payload: payload.clone(),
+      });
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::{Manager, RunEvent};
+
+  #[test]
+  fn invokes_commands() {
+    let app = mock_builder()
+      .invoke_handler(|invoke| {
+        let payload = invoke.message.payload();
+        match (payload["a"].as_u64(), payload["b"].as_u64()) {
+          (Some(a), Some(b)) => invoke.resolver.resolve(a + b),
+          _ => invoke.resolver.reject("missing arguments"),
+        }
+      })
+      .build(mock_context(noop_assets()))
+      .unwrap();
+    let window = app.get_window("main").unwrap();
+
+    assert_eq!(
+      invoke(&window, "add", serde_json::json!({ "a": 1, "b": 2 })),
+      Ok(3.into())
+    );
+    assert!(invoke(&window, "add", serde_json::json!({ "a": 1 })).is_err());
+  }
+
+  #[test]
+  fn records_events_and_window_operations() {
+    let app = mock_app();
+    let window = app.get_window("main").unwrap();
+
+    window.emit("ready", "payload").unwrap();
+    window.set_title("Mock").unwrap();
+    window.hide().unwrap();
+
+    let events = emitted_events(&app);
+    assert_eq!(events.len(), 1);
+    assert_eq!(events[0].window(), "main");
+    assert_eq!(events[0].event(), "ready");
+    assert_eq!(events[0].payload(), "payload");
+
+    let dispatcher = mock_dispatcher(&window);
+    assert_eq!(dispatcher.title(), "Mock");
+    assert!(!window.is_visible().unwrap());
+    assert!(dispatcher
+      .operations()
+      .contains(&WindowOperation::SetTitle("Mock".into())));
+  }
+
+  #[test]
+  fn records_the_webview_operations() {
+    use crate::{Icon, ThumbbarButton};
+
+    let app = mock_app();
+    let window = app.get_window("main").unwrap();
+
+    window.set_zoom(1.5).unwrap();
+    window.set_user_agent("Mock/1.0").unwrap();
+    window.set_muted(true).unwrap();
+    window.find("needle", Default::default()).unwrap();
+    window.clear_find().unwrap();
+    window.eval("document.title").unwrap();
+    window
+      .set_thumbbar_buttons(vec![ThumbbarButton::new("play", Icon::Raw(Vec::new()))])
+      .unwrap();
+
+    assert_eq!(window.zoom().unwrap(), 1.5);
+    let operations = mock_dispatcher(&window).operations();
+    for operation in vec![
+      WindowOperation::SetZoom(1.5),
+      WindowOperation::SetUserAgent("Mock/1.0".into()),
+      WindowOperation::SetMuted(true),
+      WindowOperation::Find("needle".into()),
+      WindowOperation::ClearFind,
+      WindowOperation::EvalScript("document.title".into()),
+      WindowOperation::SetThumbbarButtons(vec!["play".into()]),
+    ] {
+      assert!(
+        operations.contains(&operation),
+        "{:?} not recorded",
+        operation
+      );
+    }
+  }
+
+  #[test]
+  fn clears_the_cookies_with_the_browsing_data() {
+    use crate::{Cookie, DataTypes};
+
+    let app = mock_app();
+    let window = app.get_window("main").unwrap();
+    let cookies = window.cookies();
+
+    cookies.set(Cookie::new("session", "1")).unwrap();
+    cookies.set(Cookie::new("theme", "dark")).unwrap();
+    cookies.delete("theme", None).unwrap();
+    assert_eq!(cookies.get("session").unwrap().unwrap().value, "1");
+    assert!(cookies.get("theme").unwrap().is_none());
+
+    // clearing the cache keeps the cookies
+    window
+      .clear_browsing_data(DataTypes {
+        cache: true,
+        ..Default::default()
+      })
+      .unwrap();
+    assert_eq!(cookies.list().unwrap().len(), 1);
+
+    window
+      .clear_browsing_data(DataTypes {
+        cookies: true,
+        ..Default::default()
+      })
+      .unwrap();
+    assert!(cookies.list().unwrap().is_empty());
+  }
+
+  #[cfg(feature = "devtools")]
+  #[test]
+  fn opens_and_closes_the_devtools() {
+    let app = mock_app();
+    let window = app.get_window("main").unwrap();
+
+    window.open_devtools().unwrap();
+    assert!(window.is_devtools_open().unwrap());
+    window.close_devtools().unwrap();
+    assert!(!window.is_devtools_open().unwrap());
+  }
+
+  #[test]
+  fn creates_windows_from_other_threads() {
+    let app = mock_app();
+    let handle = app.handle();
+    std::thread::spawn(move || {
+      handle.create_window("other".into(), Default::default(), |window, webview| {
+        (window, webview)
+      })
+    })
+    .join()
+    .unwrap()
+    .unwrap();
+
+    assert!(app.get_window("other").is_some());
+  }
+
+  #[test]
+  fn records_the_print_operations() {
+    use crate::{PageSize, PrintOptions};
+
+    let app = mock_app();
+    let window = app.get_window("main").unwrap();
+    let options = PrintOptions {
+      page_size: Some(PageSize::Letter),
+      landscape: true,
+      ..Default::default()
+    };
+
+    window.print_with_options(options.clone()).unwrap();
+    window
+      .print_to_pdf("/tmp/page.pdf", options.clone())
+      .unwrap();
+
+    let operations = mock_dispatcher(&window).operations();
+    assert!(operations.contains(&WindowOperation::PrintWithOptions(options.clone())));
+    assert!(operations.contains(&WindowOperation::PrintToPdf(
+      "/tmp/page.pdf".into(),
+      options
+    )));
+  }
+
+  #[test]
+  fn delivers_user_events() {
+    struct Ping(u8);
+
+    let app = mock_app();
+    let proxy = app.handle().create_proxy::<Ping>();
+    std::thread::spawn(move || proxy.send_event(Ping(1)))
+      .join()
+      .unwrap()
+      .unwrap();
+
+    let (tx, rx) = channel();
+    app.run(move |_, event| {
+      let event = match event {
+        RunEvent::UserEvent(event) => event.downcast::<Ping>().ok().map(|ping| ping.0),
+        RunEvent::Exit => None,
+        _ => return,
+      };
+      tx.send(event).unwrap();
+    });
+    assert_eq!(rx.try_iter().collect::<Vec<_>>(), vec![Some(1), None]);
+  }
+}
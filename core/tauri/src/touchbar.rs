@@ -0,0 +1,137 @@
+// Copyright 2019-2021 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+//! Touch Bar layouts of the windows on macOS, set with [`Window::set_touchbar`].
+//!
+//! The items use the menu id type, and their events are sent to the handlers registered with
+//! [`crate::Builder::on_touchbar_event`] and [`Window::on_touchbar_event`].
+//! Setting a Touch Bar is a no-op on the other platforms.
+//!
+//! # Example
+//!
+//! ```rust,ignore
+//! use tauri::touchbar::{TouchBar, TouchBarButton, TouchBarSlider, TouchBarValue};
+//!
+//! fn main() {
+//!   tauri::Builder::default()
+//!     .setup(|app| {
+//!       app.get_window("main").unwrap().set_touchbar(
+//!         TouchBar::new()
+//!           .add_button(TouchBarButton::new("play".into(), "Play"))
+//!           .add_slider(TouchBarSlider::new("volume".into(), 0.0, 1.0).label("Volume")),
+//!       )?;
+//!       Ok(())
+//!     })
+//!     .on_touchbar_event(|event| match (event.item_id().as_str(), event.value()) {
+//!       ("play", _) => event.window().emit("play", ()).unwrap(),
+//!       ("volume", TouchBarValue::Slider(volume)) => event.window().emit("volume", volume).unwrap(),
+//!       _ => {}
+//!     })
+//!     .run(tauri::generate_context!())
+//!     .expect("error while running tauri application");
+//! }
+//! ```
+
+pub use crate::runtime::touchbar::{
+  TouchBar, TouchBarButton, TouchBarItem, TouchBarSegmentedControl, TouchBarSlider, TouchBarValue,
+};
+
+use crate::{runtime::MenuId, Params, Window};
+
+pub(crate) type GlobalTouchBarEventListener<P> = Box<dyn Fn(WindowTouchBarEvent<P>) + Send + Sync>;
+
+/// The window Touch Bar event.
+#[derive(Debug, Clone)]
+pub struct TouchBarEvent<I: MenuId> {
+  pub(crate) item_id: I,
+  pub(crate) value: TouchBarValue,
+}
+
+impl<I: MenuId> TouchBarEvent<I> {
+  /// The Touch Bar item id.
+  pub fn item_id(&self) -> &I {
+    &self.item_id
+  }
+
+  /// The value sent by the item.
+  pub fn value(&self) -> TouchBarValue {
+    self.value
+  }
+}
+
+crate::manager::default_args! {
+  /// A Touch Bar event that was triggered on a window.
+  pub struct WindowTouchBarEvent<P: Params> {
+    pub(crate) event: TouchBarEvent<P::MenuId>,
+    pub(crate) window: Window<P>,
+  }
+}
+
+impl<P: Params> WindowTouchBarEvent<P> {
+  /// The Touch Bar item id.
+  pub fn item_id(&self) -> &P::MenuId {
+    &self.event.item_id
+  }
+
+  /// The value sent by the item.
+  pub fn value(&self) -> TouchBarValue {
+    self.event.value
+  }
+
+  /// The window that the Touch Bar belongs to.
+  pub fn window(&self) -> &Window<P> {
+    &self.window
+  }
+}
+
+#[cfg(all(test, feature = "test"))]
+mod tests {
+  use super::*;
+  use crate::{
+    runtime::touchbar::TouchBarEvent as RuntimeTouchBarEvent,
+    test::{mock_builder, mock_context, mock_dispatcher, noop_assets},
+    Manager,
+  };
+
+  use std::sync::mpsc::channel;
+
+  #[test]
+  fn routes_events_with_the_item_ids() {
+    let (tx, rx) = channel();
+    let tx = std::sync::Mutex::new(tx);
+    let app = mock_builder()
+      .on_touchbar_event(move |event| {
+        tx.lock()
+          .unwrap()
+          .send((
+            event.window().label().clone(),
+            event.item_id().clone(),
+            event.value(),
+          ))
+          .unwrap();
+      })
+      .build(mock_context(noop_assets()))
+      .unwrap();
+    let window = app.get_window("main").unwrap();
+
+    let slider = TouchBarSlider::new("volume".to_string(), 0.0, 1.0);
+    let slider_id = slider.id_value();
+    window
+      .set_touchbar(TouchBar::new().add_slider(slider))
+      .unwrap();
+    mock_dispatcher(&window).trigger_touchbar_event(&RuntimeTouchBarEvent::new(
+      slider_id,
+      TouchBarValue::Slider(0.5),
+    ));
+
+    assert_eq!(
+      rx.try_iter().collect::<Vec<_>>(),
+      vec![(
+        "main".to_string(),
+        "volume".to_string(),
+        TouchBarValue::Slider(0.5)
+      )]
+    );
+  }
+}
@@ -2,20 +2,24 @@
 // SPDX-License-Identifier: Apache-2.0
 // SPDX-License-Identifier: MIT
 
-use super::error::{Error, Result};
-use crate::api::{file::Extract, version};
+use super::{
+  error::{Error, Result},
+  UpdaterEvent,
+};
+use crate::api::{file::Extract, http::DownloadProgress, version};
 use base64::decode;
 use http::StatusCode;
 use minisign_verify::{PublicKey, Signature};
+use serde::{Deserialize, Serialize};
 use std::{
   collections::HashMap,
   env,
   ffi::OsStr,
-  fs::{read_dir, remove_file, File, OpenOptions},
+  fs::{create_dir_all, read_dir, remove_dir_all, remove_file, File, OpenOptions},
   io::{prelude::*, BufReader, Read},
   path::{Path, PathBuf},
   str::from_utf8,
-  time::{SystemTime, UNIX_EPOCH},
+  time::{Duration, Instant},
 };
 
 #[cfg(not(target_os = "macos"))]
@@ -24,11 +28,56 @@ use std::process::Command;
 #[cfg(target_os = "macos")]
 use crate::api::file::Move;
 
-use crate::api::http::{ClientBuilder, HttpRequestBuilder};
+use crate::api::http::{ClientBuilder, HttpRequestBuilder, Proxy};
 
 #[cfg(target_os = "windows")]
 use std::process::exit;
 
+/// The release channel used when none is configured.
+pub const DEFAULT_CHANNEL: &str = "stable";
+
+/// A binary patch from a previous version to the announced one.
+#[derive(Debug, Clone)]
+pub struct RemoteDelta {
+  /// The version the patch applies to
+  pub from: String,
+  /// Download URL of the patch
+  pub url: String,
+  /// Optional signature of the patched result
+  pub signature: Option<String>,
+}
+
+impl RemoteDelta {
+  // Read the `deltas` array of the release or platform JSON
+  fn from_deltas(deltas: Option<&serde_json::Value>) -> Result<Vec<RemoteDelta>> {
+    let deltas = match deltas {
+      Some(deltas) => deltas
+        .as_array()
+        .ok_or_else(|| Error::RemoteMetadata("`deltas` must be an array".into()))?,
+      None => return Ok(Vec::new()),
+    };
+    deltas
+      .iter()
+      .map(|delta| {
+        let field = |name: &str| {
+          delta
+            .get(name)
+            .and_then(|value| value.as_str())
+            .map(|value| value.to_string())
+        };
+        Ok(RemoteDelta {
+          from: field("from")
+            .ok_or_else(|| Error::RemoteMetadata("Delta missing `from`".into()))?
+            .trim_start_matches('v')
+            .to_string(),
+          url: field("url").ok_or_else(|| Error::RemoteMetadata("Delta missing `url`".into()))?,
+          signature: field("signature"),
+        })
+      })
+      .collect()
+  }
+}
+
 #[derive(Debug)]
 pub struct RemoteRelease {
   /// Version to install
@@ -41,11 +90,26 @@ pub struct RemoteRelease {
   pub body: Option<String>,
   /// Optional signature for the current platform
   pub signature: Option<String>,
+  /// Binary patches from previous versions for the current platform
+  pub deltas: Vec<RemoteDelta>,
 }
 
 impl RemoteRelease {
   // Read JSON and confirm this is a valid Schema
-  fn from_release(release: &serde_json::Value, target: &str) -> Result<RemoteRelease> {
+  fn from_release(
+    release: &serde_json::Value,
+    target: &str,
+    channel: &str,
+  ) -> Result<RemoteRelease> {
+    // A manifest announcing multiple channels has the release of each channel
+    // under `channels`, otherwise the root is the release.
+    let release = match release.get("channels") {
+      Some(channels) => channels
+        .get(channel)
+        .ok_or_else(|| Error::RemoteMetadata(format!("Channel `{}` not available", channel)))?,
+      None => release,
+    };
+
     // Version or name is required for static and dynamic JSON
     // if `version` is not announced, we fallback to `name` (can be the tag name example v1.0.0)
     let version = match release.get("version") {
@@ -84,6 +148,7 @@ impl RemoteRelease {
       .map(|signature| signature.as_str().unwrap_or("").to_string());
 
     let download_url;
+    let deltas;
 
     match release.get("platforms") {
       //
@@ -116,6 +181,7 @@ impl RemoteRelease {
               Error::RemoteMetadata("Unable to extract `url` from remote server`".into())
             })?
             .to_string();
+          deltas = RemoteDelta::from_deltas(current_target_data.get("deltas"))?;
         } else {
           // make sure we have an available platform from the static
           return Err(Error::RemoteMetadata("Platform not available".into()));
@@ -132,6 +198,7 @@ impl RemoteRelease {
             Error::RemoteMetadata("Unable to extract `url` from remote server`".into())
           })?
           .to_string();
+        deltas = RemoteDelta::from_deltas(release.get("deltas"))?;
       }
     }
     // Return our formatted release
@@ -141,6 +208,7 @@ impl RemoteRelease {
       download_url,
       body,
       signature,
+      deltas,
     })
   }
 }
@@ -154,6 +222,16 @@ pub struct UpdateBuilder<'a> {
   pub target: Option<String>,
   /// The current executable path. Default is automatically extracted.
   pub executable_path: Option<PathBuf>,
+  /// Headers added to the update check and download requests.
+  pub headers: HashMap<String, String>,
+  /// Query parameters added to the update check requests.
+  pub query: HashMap<String, String>,
+  /// The release channel. Default is `stable`.
+  pub channel: Option<String>,
+  /// Whether an update to a lower version is allowed. Default is `false`.
+  pub allow_downgrades: bool,
+  /// The proxy of the update check and download requests. Default is the system proxy.
+  pub proxy: Option<Proxy>,
 }
 
 impl<'a> Default for UpdateBuilder<'a> {
@@ -162,6 +240,11 @@ impl<'a> Default for UpdateBuilder<'a> {
       urls: Vec::new(),
       target: None,
       executable_path: None,
+      headers: HashMap::new(),
+      query: HashMap::new(),
+      channel: None,
+      allow_downgrades: false,
+      proxy: None,
       current_version: env!("CARGO_PKG_VERSION"),
     }
   }
@@ -211,6 +294,38 @@ impl<'a> UpdateBuilder<'a> {
     self
   }
 
+  /// Add headers to the update check requests.
+  ///
+  /// They are only sent to the downloads served from the origin of the endpoint that announced the update.
+  pub fn headers(mut self, headers: HashMap<String, String>) -> Self {
+    self.headers.extend(headers);
+    self
+  }
+
+  /// Add query parameters to the update check requests
+  pub fn query(mut self, query: HashMap<String, String>) -> Self {
+    self.query.extend(query);
+    self
+  }
+
+  /// Set the release channel, e.g. `beta` or `nightly`
+  pub fn channel(mut self, channel: impl Into<String>) -> Self {
+    self.channel = Some(channel.into());
+    self
+  }
+
+  /// Allow updates to a version lower than the current one, e.g. to revert a botched release
+  pub fn allow_downgrades(mut self, allow_downgrades: bool) -> Self {
+    self.allow_downgrades = allow_downgrades;
+    self
+  }
+
+  /// Set the proxy of the update check and download requests, overriding the system proxy
+  pub fn proxy(mut self, proxy: Proxy) -> Self {
+    self.proxy = Some(proxy);
+    self
+  }
+
   pub async fn build(self) -> Result<Update> {
     let mut remote_release: Option<RemoteRelease> = None;
 
@@ -241,6 +356,11 @@ impl<'a> UpdateBuilder<'a> {
       get_updater_target().ok_or(Error::UnsupportedPlatform)?
     };
 
+    let channel = self
+      .channel
+      .clone()
+      .unwrap_or_else(|| DEFAULT_CHANNEL.to_string());
+
     // Get the extract_path from the provided executable_path
     let extract_path = extract_path_from_executable(&executable_path);
 
@@ -257,35 +377,57 @@ impl<'a> UpdateBuilder<'a> {
       }
     }
 
+    let mut client = ClientBuilder::new();
+    if let Some(proxy) = self.proxy.clone() {
+      client = client.proxy(proxy);
+    }
+
     // Allow fallback if more than 1 urls is provided
     let mut last_error: Option<Error> = None;
+    // the origin of the endpoint that announced the update
+    let mut endpoint_origin = None;
     for url in &self.urls {
-      // replace {{current_version}} and {{target}} in the provided URL
+      // replace {{current_version}}, {{target}} and {{channel}} in the provided URL
       // this is usefull if we need to query example
       // https://releases.myapp.com/update/{{target}}/{{current_version}}
       // will be transleted into ->
       // https://releases.myapp.com/update/darwin/1.0.0
       // The main objective is if the update URL is defined via the Cargo.toml
       // the URL will be generated dynamicly
-      let fixed_link = str::replace(
-        &str::replace(url, "{{current_version}}", current_version),
-        "{{target}}",
-        &target,
-      );
+      // the `${VAR}` placeholders escaped in the config are resolved at runtime too
+      let url = match tauri_utils::config::interpolate(url) {
+        Ok(url) => url,
+        Err(e) => {
+          last_error = Some(e.into());
+          continue;
+        }
+      };
+      let fixed_link = url
+        .replace("{{current_version}}", current_version)
+        .replace("{{target}}", &target)
+        .replace("{{channel}}", &channel);
 
       // we want JSON only
       let mut headers = HashMap::new();
       headers.insert("Accept".into(), "application/json".into());
+      headers.extend(self.headers.clone());
+
+      let mut request = HttpRequestBuilder::new("GET", &fixed_link)
+        .headers(headers)
+        // wait 20sec for the firewall
+        .timeout(20);
+      let mut query = self.query.clone();
+      // let the server know which channel we follow when it was explicitly set
+      if self.channel.is_some() {
+        query
+          .entry("channel".into())
+          .or_insert_with(|| channel.clone());
+      }
+      if !query.is_empty() {
+        request = request.query(query);
+      }
 
-      let resp = ClientBuilder::new()
-        .build()?
-        .send(
-          HttpRequestBuilder::new("GET", &fixed_link)
-            .headers(headers)
-            // wait 20sec for the firewall
-            .timeout(20),
-        )
-        .await;
+      let resp = client.clone().build()?.send(request).await;
 
       // If we got a success, we stop the loop
       // and we set our remote_release variable
@@ -300,12 +442,13 @@ impl<'a> UpdateBuilder<'a> {
             return Err(Error::UpToDate);
           };
           // Convert the remote result to our local struct
-          let built_release = RemoteRelease::from_release(&res.data, &target);
+          let built_release = RemoteRelease::from_release(&res.data, &target, &channel);
           // make sure all went well and the remote data is compatible
           // with what we need locally
           match built_release {
             Ok(release) => {
               last_error = None;
+              endpoint_origin = url::Url::parse(&fixed_link).ok().map(|url| url.origin());
               remote_release = Some(release);
               break;
             }
@@ -327,8 +470,26 @@ impl<'a> UpdateBuilder<'a> {
     })?;
 
     // did the announced version is greated than our current one?
-    let should_update =
-      version::is_greater(current_version, &final_release.version).unwrap_or(false);
+    // when downgrades are allowed, any other version is installed
+    let should_update = if self.allow_downgrades {
+      version::compare(current_version, &final_release.version)
+        .map(|ordering| ordering != 0)
+        .unwrap_or(false)
+    } else {
+      version::is_greater(current_version, &final_release.version).unwrap_or(false)
+    };
+
+    // the update downloaded on a previous launch must not be installed over the newer one
+    if should_update {
+      remove_outdated_pending_update(&final_release.version);
+    }
+
+    // find a patch from the running version, if any
+    let delta = final_release.deltas.into_iter().find(|delta| {
+      version::compare(&delta.from, current_version)
+        .map(|ordering| ordering == 0)
+        .unwrap_or(false)
+    });
 
     // create our new updater
     Ok(Update {
@@ -337,10 +498,15 @@ impl<'a> UpdateBuilder<'a> {
       should_update,
       version: final_release.version,
       date: final_release.date,
+      channel,
       current_version: self.current_version.to_owned(),
       download_url: final_release.download_url,
       body: final_release.body,
       signature: final_release.signature,
+      delta,
+      headers: self.headers,
+      endpoint_origin,
+      client,
     })
   }
 }
@@ -361,6 +527,8 @@ pub struct Update {
   pub current_version: String,
   /// Update publish date
   pub date: String,
+  /// Release channel the update was found on
+  pub channel: String,
   /// Target
   target: String,
   /// Extract path
@@ -369,17 +537,37 @@ pub struct Update {
   download_url: String,
   /// Signature announced
   signature: Option<String>,
+  /// Binary patch from the running version announced
+  delta: Option<RemoteDelta>,
+  /// Headers added to the download requests served from the endpoint origin
+  headers: HashMap<String, String>,
+  /// Origin of the endpoint that announced the update
+  endpoint_origin: Option<url::Origin>,
+  /// Client options of the download requests
+  client: ClientBuilder,
 }
 
 impl Update {
   // Download and install our update
-  // @todo(lemarier): Split into download and install (two step) but need to be thread safe
-  pub async fn download_and_install(&self, pub_key: Option<String>) -> Result {
-    // download url for selected release
-    let url = self.download_url.clone();
-    // extract path
-    let extract_path = self.extract_path.clone();
+  pub async fn download_and_install<F: FnMut(UpdaterEvent) + Send>(
+    &self,
+    pub_key: Option<String>,
+    mut on_event: F,
+  ) -> Result {
+    self
+      .download(pub_key, &mut on_event)
+      .await?
+      .install(on_event)
+  }
 
+  /// Downloads the update and caches it on disk, ready to be installed.
+  ///
+  /// The download replaces any update previously cached, and is found on the next launches with [`pending_update`].
+  pub async fn download<F: FnMut(UpdaterEvent) + Send>(
+    &self,
+    pub_key: Option<String>,
+    mut on_event: F,
+  ) -> Result<DownloadedUpdate> {
     // make sure we can install the update on linux
     // We fail here because later we can add more linux support
     // actually if we use APPIMAGE, our extract path should already
@@ -390,85 +578,404 @@ impl Update {
       return Err(Error::UnsupportedPlatform);
     }
 
-    // used  for temp file name
-    // if we cant extract app name, we use unix epoch duration
-    let current_time = SystemTime::now()
-      .duration_since(UNIX_EPOCH)
-      .expect("Unable to get Unix Epoch")
-      .subsec_nanos()
-      .to_string();
-
-    // get the current app name
-    let bin_name = std::env::current_exe()
-      .ok()
-      .and_then(|pb| pb.file_name().map(|s| s.to_os_string()))
-      .and_then(|s| s.into_string().ok())
-      .unwrap_or_else(|| current_time.clone());
-
-    // tmp dir for extraction
-    let tmp_dir = tempfile::Builder::new()
-      .prefix(&format!("{}_{}_download", bin_name, current_time))
-      .tempdir()?;
-
-    // tmp directories are used to create backup of current application
-    // if something goes wrong, we can restore to previous state
-    let tmp_archive_path = tmp_dir.path().join(detect_archive_in_url(&url));
-    let mut tmp_archive = File::create(&tmp_archive_path)?;
-
-    // set our headers
-    let mut headers = HashMap::new();
-    headers.insert("Accept".into(), "application/octet-stream".into());
-    headers.insert("User-Agent".into(), "tauri/updater".into());
-
-    // Create our request
-    let resp = ClientBuilder::new()
-      .build()?
-      .send(
-        HttpRequestBuilder::new("GET", &url)
-          .headers(headers)
-          // wait 20sec for the firewall
-          .timeout(20),
-      )
-      .await?
-      .bytes()
-      .await?;
-
-    // make sure it's success
-    if !StatusCode::from_u16(resp.status).unwrap().is_success() {
-      return Err(Error::Network(format!(
-        "Download request failed with status: {}",
-        resp.status
-      )));
+    let dir = updates_dir();
+    if dir.exists() {
+      remove_dir_all(&dir)?;
+    }
+    create_dir_all(&dir)?;
+
+    match self.download_into(&dir, &pub_key, &mut on_event).await {
+      Ok((file, signature, archive)) => {
+        let downloaded = DownloadedUpdate {
+          version: self.version.clone(),
+          date: self.date.clone(),
+          body: self.body.clone(),
+          channel: self.channel.clone(),
+          dir,
+          extract_path: self.extract_path.clone(),
+          current_version: self.current_version.clone(),
+          file,
+          signature,
+          archive,
+          pub_key,
+        };
+        serde_json::to_writer(
+          File::create(downloaded.dir.join(DOWNLOAD_MANIFEST))?,
+          &downloaded,
+        )?;
+        Ok(downloaded)
+      }
+      Err(e) => {
+        let _ = remove_dir_all(&dir);
+        Err(e)
+      }
+    }
+  }
+
+  // The custom headers may hold credentials, so they are only sent back to the endpoint origin
+  fn download_headers(&self, url: &str) -> HashMap<String, String> {
+    let origin = url::Url::parse(url).ok().map(|url| url.origin());
+    if origin.is_some() && origin == self.endpoint_origin {
+      self.headers.clone()
+    } else {
+      HashMap::new()
     }
+  }
 
-    tmp_archive.write_all(&resp.data)?;
-
-    // Validate signature ONLY if pubkey is available in tauri.conf.json
-    if let Some(pub_key) = pub_key {
-      // We need an announced signature by the server
-      // if there is no signature, bail out.
-      if let Some(signature) = self.signature.clone() {
-        // we make sure the archive is valid and signed with the private key linked with the publickey
-        verify_signature(&tmp_archive_path, signature, &pub_key)?;
-      } else {
-        // We have a public key inside our source file, but not announced by the server,
-        // we assume this update is NOT valid.
-        return Err(Error::PubkeyButNoSignature);
+  // Downloads and verifies the update in the directory,
+  // returning the name of the signed file, its signature and whether it's an archive to extract
+  async fn download_into<F: FnMut(UpdaterEvent) + Send>(
+    &self,
+    dir: &Path,
+    pub_key: &Option<String>,
+    on_event: &mut F,
+  ) -> Result<(PathBuf, Option<String>, bool)> {
+    // try to patch the running AppImage first, falling back to the full download if anything fails
+    #[cfg(target_os = "linux")]
+    if let Some(delta) = &self.delta {
+      if let Ok(file) = download_and_apply_delta(
+        delta,
+        &self.client,
+        &self.download_headers(&delta.url),
+        &self.extract_path,
+        dir,
+        pub_key,
+        on_event,
+      )
+      .await
+      {
+        return Ok((file, delta.signature.clone(), false));
       }
     }
-    // extract using tauri api inside a tmp path
-    Extract::from_source(&tmp_archive_path).extract_into(tmp_dir.path())?;
-    // Remove archive (not needed anymore)
-    remove_file(&tmp_archive_path)?;
+
+    let url = self.download_url.clone();
+    let archive = PathBuf::from(detect_archive_in_url(&url));
+    let archive_path = dir.join(&archive);
+
+    download(
+      &url,
+      &self.client,
+      &self.download_headers(&url),
+      &archive_path,
+      |progress| on_event(progress_event(progress)),
+    )
+    .await?;
+    on_event(UpdaterEvent::Downloaded);
+
+    // the archive is verified now to fail early, and again before it's extracted on install
+    verify_update_file(&archive_path, &self.signature, pub_key)?;
+    Ok((archive, self.signature.clone(), true))
+  }
+}
+
+// The name of the file describing the update cached on disk
+const DOWNLOAD_MANIFEST: &str = "update.json";
+
+/// An update downloaded and verified by [`Update::download`], cached on disk until it's installed.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DownloadedUpdate {
+  /// Update description
+  pub body: Option<String>,
+  /// Version downloaded
+  pub version: String,
+  /// Update publish date
+  pub date: String,
+  /// Release channel the update was found on
+  pub channel: String,
+  /// Directory with the extracted update
+  #[serde(skip)]
+  dir: PathBuf,
+  /// Path of the installed application
+  #[serde(skip)]
+  extract_path: PathBuf,
+  /// Running version
+  #[serde(skip)]
+  current_version: String,
+  /// File signed by the release, relative to the update directory
+  file: PathBuf,
+  /// Signature of the file announced by the server
+  signature: Option<String>,
+  /// Whether the file is an archive to extract on install
+  archive: bool,
+  /// Public key verifying the file on install
+  #[serde(skip)]
+  pub_key: Option<String>,
+}
+
+impl DownloadedUpdate {
+  /// Installs the update.
+  ///
+  /// The cached update is verified again with the public key, since it may have been changed on disk
+  /// since it was downloaded, and it's deleted if the verification fails.
+  ///
+  /// On Linux and macOS, the running version is kept to be restored by [`rollback`].
+  /// On Windows, the installer is launched and the application exits.
+  pub fn install<F: FnMut(UpdaterEvent)>(self, mut on_event: F) -> Result {
+    on_event(UpdaterEvent::Installing);
+    let file = self.dir.join(&self.file);
+    if let Err(e) = verify_update_file(&file, &self.signature, &self.pub_key) {
+      let _ = remove_dir_all(&self.dir);
+      return Err(e);
+    }
+    if self.archive {
+      // extract using tauri api next to the archive
+      Extract::from_source(&file).extract_into(&self.dir)?;
+      remove_file(&file)?;
+    }
+    // keeping the previous version is best effort, it doesn't prevent the update
+    #[cfg(any(target_os = "linux", target_os = "macos"))]
+    let _ = backup(&self.extract_path, &self.current_version);
     // we copy the files depending of the operating system
     // we run the setup, appimage re-install or overwrite the
     // macos .app
-    copy_files_and_run(tmp_dir, extract_path)?;
+    copy_files_and_run(&self.dir, self.extract_path.clone())?;
     // We are done!
+    let _ = remove_dir_all(&self.dir);
+    Ok(())
+  }
+
+  /// Deletes the update from the disk without installing it.
+  pub fn discard(self) -> Result {
+    remove_dir_all(&self.dir)?;
     Ok(())
   }
 }
 
+/// Gets the update downloaded by a previous [`Update::download`], if it's newer than the given version,
+/// or only different when downgrades are allowed.
+///
+/// Outdated downloads, e.g. of an update already installed, are removed from the disk.
+/// The public key verifies the update when it's installed.
+pub fn pending_update(
+  current_version: &str,
+  allow_downgrades: bool,
+  pub_key: Option<String>,
+) -> Option<DownloadedUpdate> {
+  let dir = updates_dir();
+  let manifest = File::open(dir.join(DOWNLOAD_MANIFEST)).ok()?;
+  match serde_json::from_reader::<_, DownloadedUpdate>(BufReader::new(manifest)) {
+    // an older download is only kept when downgrades are allowed
+    Ok(downloaded)
+      if version::compare(current_version, &downloaded.version)
+        .map(|ordering| ordering > 0 || (allow_downgrades && ordering != 0))
+        .unwrap_or(false) =>
+    {
+      Some(DownloadedUpdate {
+        dir,
+        extract_path: extract_path_from_executable(&env::current_exe().ok()?),
+        current_version: current_version.into(),
+        pub_key,
+        ..downloaded
+      })
+    }
+    _ => {
+      let _ = remove_dir_all(&dir);
+      None
+    }
+  }
+}
+
+// Removes the update downloaded on a previous launch if it's older than the announced version
+fn remove_outdated_pending_update(announced_version: &str) {
+  let dir = updates_dir();
+  let downloaded = File::open(dir.join(DOWNLOAD_MANIFEST))
+    .ok()
+    .and_then(|manifest| {
+      serde_json::from_reader::<_, DownloadedUpdate>(BufReader::new(manifest)).ok()
+    });
+  if let Some(downloaded) = downloaded {
+    if version::is_greater(&downloaded.version, announced_version).unwrap_or(false) {
+      let _ = remove_dir_all(&dir);
+    }
+  }
+}
+
+// The directory where the downloaded update is cached until it's installed
+fn updates_dir() -> PathBuf {
+  cache_dir("updates")
+}
+
+// The directory where the previous version is kept after an update
+fn backup_dir() -> PathBuf {
+  cache_dir("backup")
+}
+
+fn cache_dir(name: &str) -> PathBuf {
+  // get the current app name
+  let bin_name = env::current_exe()
+    .ok()
+    .and_then(|pb| pb.file_stem().map(|s| s.to_os_string()))
+    .and_then(|s| s.into_string().ok())
+    .unwrap_or_else(|| "tauri".into());
+  crate::api::path::cache_dir()
+    .unwrap_or_else(env::temp_dir)
+    .join(format!("{}_{}", bin_name, name))
+}
+
+// The name of the file describing the version kept in the backup dir
+const BACKUP_MANIFEST: &str = "backup.json";
+
+#[derive(Serialize, Deserialize)]
+struct BackupManifest {
+  version: String,
+}
+
+// Copies the installed application to the backup dir, replacing the previous backup
+#[cfg(any(target_os = "linux", target_os = "macos"))]
+fn backup(extract_path: &Path, current_version: &str) -> Result {
+  let dir = backup_dir();
+  if dir.exists() {
+    remove_dir_all(&dir)?;
+  }
+  create_dir_all(&dir)?;
+
+  let file_name = extract_path
+    .file_name()
+    .ok_or_else(|| Error::Builder("Unable to find the application name".into()))?;
+  // the AppImage on Linux, the .app on macOS
+  #[cfg(target_os = "linux")]
+  std::fs::copy(extract_path, dir.join(file_name))?;
+  #[cfg(target_os = "macos")]
+  Move::from_source(extract_path).walk_to_dest(&dir.join(file_name))?;
+
+  serde_json::to_writer(
+    File::create(dir.join(BACKUP_MANIFEST))?,
+    &BackupManifest {
+      version: current_version.into(),
+    },
+  )?;
+  Ok(())
+}
+
+/// Gets the version kept by the last update, restored by [`rollback`].
+pub fn previous_version() -> Option<String> {
+  let manifest = File::open(backup_dir().join(BACKUP_MANIFEST)).ok()?;
+  serde_json::from_reader::<_, BackupManifest>(BufReader::new(manifest))
+    .ok()
+    .map(|backup| backup.version)
+}
+
+/// Restores the version kept by the last update.
+///
+/// Returns [`Error::NoBackup`] if no version was kept.
+#[cfg(not(windows))]
+pub fn rollback(executable_path: &Path) -> Result {
+  if previous_version().is_none() {
+    return Err(Error::NoBackup);
+  }
+  let dir = backup_dir();
+  remove_file(dir.join(BACKUP_MANIFEST))?;
+  // the backup has the same layout as an extracted update
+  copy_files_and_run(&dir, extract_path_from_executable(executable_path))?;
+  let _ = remove_dir_all(&dir);
+  Ok(())
+}
+
+/// Restores the version kept by the last update.
+///
+/// Returns [`Error::UnsupportedPlatform`] on Windows, since the updates are installed by the MSI or EXE installers
+/// and no version is kept.
+#[cfg(windows)]
+pub fn rollback(_executable_path: &Path) -> Result {
+  Err(Error::UnsupportedPlatform)
+}
+
+fn progress_event(progress: DownloadProgress) -> UpdaterEvent {
+  UpdaterEvent::Progress {
+    received: progress.downloaded,
+    total: progress.total,
+  }
+}
+
+// Downloads the file at the given URL to the path, reporting the progress at most every 100ms
+async fn download<F: FnMut(DownloadProgress)>(
+  url: &str,
+  client: &ClientBuilder,
+  extra_headers: &HashMap<String, String>,
+  path: &Path,
+  mut on_progress: F,
+) -> Result {
+  // set our headers
+  let mut headers = HashMap::new();
+  headers.insert("Accept".into(), "application/octet-stream".into());
+  headers.insert("User-Agent".into(), "tauri/updater".into());
+  headers.extend(extra_headers.clone());
+
+  let mut last_progress = Instant::now();
+  // Stream the response to the file
+  client
+    .clone()
+    .build()?
+    .download(
+      HttpRequestBuilder::new("GET", url)
+        .headers(headers)
+        // wait 20sec for the firewall
+        .timeout(20),
+      path,
+      |progress| {
+        let done = Some(progress.downloaded) == progress.total;
+        if done || last_progress.elapsed() >= Duration::from_millis(100) {
+          last_progress = Instant::now();
+          on_progress(progress);
+        }
+      },
+    )
+    .await?;
+
+  Ok(())
+}
+
+// Delta updates (Linux AppImage only)
+
+// The delta is a bsdiff patch from the running AppImage to the new one.
+// The patched AppImage is written to the updates dir, where `copy_files_and_run` picks it up.
+// The announced delta signature must match the patched AppImage, not the patch itself.
+// Returns the name of the patched AppImage in the updates dir.
+#[cfg(target_os = "linux")]
+async fn download_and_apply_delta<F: FnMut(UpdaterEvent)>(
+  delta: &RemoteDelta,
+  client: &ClientBuilder,
+  headers: &HashMap<String, String>,
+  extract_path: &Path,
+  dir: &Path,
+  pub_key: &Option<String>,
+  on_event: &mut F,
+) -> Result<PathBuf> {
+  let patch_path = dir.join("update.patch");
+  download(&delta.url, client, headers, &patch_path, |progress| {
+    on_event(progress_event(progress))
+  })
+  .await?;
+  on_event(UpdaterEvent::Downloaded);
+
+  let patch = std::fs::read(&patch_path)?;
+  remove_file(&patch_path)?;
+  let source = std::fs::read(extract_path)?;
+
+  let mut target = Vec::new();
+  qbsdiff::Bspatch::new(&patch)?.apply(&source, &mut target)?;
+
+  let file_name = extract_path
+    .file_name()
+    .map(|name| name.to_os_string())
+    .unwrap_or_else(|| "update.AppImage".into());
+  let patched_file = PathBuf::from(file_name).with_extension("AppImage");
+  let patched_path = dir.join(&patched_file);
+  std::fs::write(&patched_path, target)?;
+  // the AppImage must stay executable
+  std::fs::set_permissions(
+    &patched_path,
+    std::fs::metadata(extract_path)?.permissions(),
+  )?;
+
+  if let Err(e) = verify_update_file(&patched_path, &delta.signature, pub_key) {
+    remove_file(&patched_path)?;
+    return Err(e);
+  }
+
+  Ok(patched_file)
+}
+
 // Linux (AppImage)
 
 // ### Expected structure:
@@ -478,15 +985,15 @@ impl Update {
 
 // We should have an AppImage already installed to be able to copy and install
 // the extract_path is the current AppImage path
-// tmp_dir is where our new AppImage is found
+// dir is where our new AppImage is found
 
 #[cfg(target_os = "linux")]
-fn copy_files_and_run(tmp_dir: tempfile::TempDir, extract_path: PathBuf) -> Result {
+fn copy_files_and_run(dir: &Path, extract_path: PathBuf) -> Result {
   // we delete our current AppImage (we'll create a new one later)
   remove_file(&extract_path)?;
 
-  // In our tempdir we expect 1 directory (should be the <app>.app)
-  let paths = read_dir(&tmp_dir).unwrap();
+  // In our updates dir we expect 1 AppImage
+  let paths = read_dir(dir)?;
 
   for path in paths {
     let found_path = path.expect("Unable to extract").path();
@@ -525,11 +1032,9 @@ fn copy_files_and_run(tmp_dir: tempfile::TempDir, extract_path: PathBuf) -> Resu
 
 #[cfg(target_os = "windows")]
 #[allow(clippy::unnecessary_wraps)]
-fn copy_files_and_run(tmp_dir: tempfile::TempDir, _extract_path: PathBuf) -> Result {
-  let paths = read_dir(&tmp_dir).unwrap();
-  // This consumes the TempDir without deleting directory on the filesystem,
-  // meaning that the directory will no longer be automatically deleted.
-  tmp_dir.into_path();
+fn copy_files_and_run(dir: &Path, _extract_path: PathBuf) -> Result {
+  // The installer is kept in the updates dir, it's removed on the next launch
+  let paths = read_dir(dir)?;
   for path in paths {
     let found_path = path.expect("Unable to extract").path();
     // we support 2 type of files exe & msi for now
@@ -568,9 +1073,9 @@ fn copy_files_and_run(tmp_dir: tempfile::TempDir, _extract_path: PathBuf) -> Res
 // └── ...
 
 #[cfg(target_os = "macos")]
-fn copy_files_and_run(tmp_dir: tempfile::TempDir, extract_path: PathBuf) -> Result {
-  // In our tempdir we expect 1 directory (should be the <app>.app)
-  let paths = read_dir(&tmp_dir).unwrap();
+fn copy_files_and_run(dir: &Path, extract_path: PathBuf) -> Result {
+  // In our updates dir we expect 1 directory (should be the <app>.app)
+  let paths = read_dir(dir)?;
 
   for path in paths {
     let found_path = path.expect("Unable to extract").path();
@@ -678,6 +1183,19 @@ fn base64_to_string(base64_string: &str) -> Result<String> {
   Ok(result)
 }
 
+// Verifies the signature of the update file, required if the public key is set
+fn verify_update_file(path: &Path, signature: &Option<String>, pub_key: &Option<String>) -> Result {
+  // Validate signature ONLY if pubkey is available in tauri.conf.json
+  if let Some(pub_key) = pub_key {
+    // We need an announced signature by the server
+    // if there is no signature, bail out.
+    let signature = signature.clone().ok_or(Error::PubkeyButNoSignature)?;
+    // we make sure the file is valid and signed with the private key linked with the publickey
+    verify_signature(path, signature, pub_key)?;
+  }
+  Ok(())
+}
+
 // Validate signature
 // need to be public because its been used
 // by our tests in the bundler
@@ -695,18 +1213,13 @@ pub fn verify_signature(
     Signature::decode(&signature_base64_decoded).expect("Something wrong with the signature");
 
   // We need to open the file and extract the datas to make sure its not corrupted
-  let file_open = OpenOptions::new()
-    .read(true)
-    .open(&archive_path)
-    .expect("Can't open our archive to validate signature");
+  let file_open = OpenOptions::new().read(true).open(&archive_path)?;
 
   let mut file_buff: BufReader<File> = BufReader::new(file_open);
 
   // read all bytes since EOF in the buffer
   let mut data = vec![];
-  file_buff
-    .read_to_end(&mut data)
-    .expect("Can't read buffer to validate signature");
+  file_buff.read_to_end(&mut data)?;
 
   // Validate signature or bail out
   public_key.verify(&data, &signature)?;
@@ -862,6 +1375,118 @@ mod test {
     assert!(!updater.should_update);
   }
 
+  #[test]
+  fn http_updater_delta_for_current_version() {
+    let _m = mockito::mock("GET", "/")
+      .with_status(200)
+      .with_header("content-type", "application/json")
+      .with_body(
+        r#"{
+          "version": "v2.0.0",
+          "platforms": {
+            "linux": {
+              "url": "https://tauri.studio/app.AppImage.tar.gz",
+              "deltas": [
+                { "from": "v1.0.0", "url": "https://tauri.studio/1.0.0.patch", "signature": "SampleTauriKey" },
+                { "from": "1.1.0", "url": "https://tauri.studio/1.1.0.patch" }
+              ]
+            }
+          }
+        }"#,
+      )
+      .create();
+
+    let check_update = block!(builder()
+      .current_version("1.1.0")
+      .target("linux")
+      .url(mockito::server_url())
+      .build());
+
+    let updater = check_update.expect("Can't check update");
+    let delta = updater.delta.expect("Can't find the delta");
+    assert_eq!(delta.url, "https://tauri.studio/1.1.0.patch");
+    assert!(delta.signature.is_none());
+
+    let check_update = block!(builder()
+      .current_version("0.9.0")
+      .target("linux")
+      .url(mockito::server_url())
+      .build());
+
+    assert!(check_update.expect("Can't check update").delta.is_none());
+  }
+
+  #[test]
+  fn simple_http_updater_raw_json_downgrade() {
+    let _m = mockito::mock("GET", "/")
+      .with_status(200)
+      .with_header("content-type", "application/json")
+      .with_body(generate_sample_raw_json())
+      .create();
+
+    let check_update = block!(builder()
+      .current_version("10.0.0")
+      .url(mockito::server_url())
+      .build());
+    assert!(!check_update.expect("Can't check update").should_update);
+
+    let check_update = block!(builder()
+      .current_version("10.0.0")
+      .allow_downgrades(true)
+      .url(mockito::server_url())
+      .build());
+    assert!(check_update.expect("Can't check update").should_update);
+
+    let check_update = block!(builder()
+      .current_version("2.0.0")
+      .allow_downgrades(true)
+      .url(mockito::server_url())
+      .build());
+    assert!(!check_update.expect("Can't check update").should_update);
+  }
+
+  #[test]
+  fn http_updater_channels() {
+    let _m = mockito::mock("GET", "/beta")
+      .match_query(mockito::Matcher::UrlEncoded(
+        "channel".into(),
+        "beta".into(),
+      ))
+      .with_status(200)
+      .with_header("content-type", "application/json")
+      .with_body(
+        r#"{
+          "channels": {
+            "stable": { "version": "v1.0.0", "url": "https://tauri.studio/stable.tar.gz" },
+            "beta": { "version": "v1.1.0-beta.1", "url": "https://tauri.studio/beta.tar.gz" }
+          }
+        }"#,
+      )
+      .create();
+
+    let check_update = block!(builder()
+      .current_version("1.0.0")
+      .target("linux")
+      .channel("beta")
+      .url(format!("{}/{{{{channel}}}}", mockito::server_url()))
+      .build());
+
+    let updater = check_update.expect("Can't check update");
+    assert!(updater.should_update);
+    assert_eq!(updater.channel, "beta");
+    assert_eq!(updater.version, "1.1.0-beta.1");
+    assert_eq!(updater.download_url, "https://tauri.studio/beta.tar.gz");
+
+    let check_update = block!(builder()
+      .current_version("1.0.0")
+      .target("linux")
+      .channel("nightly")
+      .url(format!("{}/beta", mockito::server_url()))
+      .build());
+
+    assert!(check_update.is_err());
+  }
+
   #[test]
   fn simple_http_updater_without_version() {
     let _m = mockito::mock("GET", "/darwin/1.0.0")
@@ -969,6 +1594,46 @@ mod test {
     assert!(check_update.is_err());
   }
 
+  #[test]
+  fn pending_update_is_verified_on_install() {
+    let mut signature = String::new();
+    File::open("./test/updater/fixture/archives/archive.tar.gz.sig")
+      .and_then(|mut file| file.read_to_string(&mut signature))
+      .expect("Unable to read signature as string");
+    let mut pubkey = String::new();
+    File::open("./test/updater/fixture/good_signature/update.key.pub")
+      .and_then(|mut file| file.read_to_string(&mut pubkey))
+      .expect("Unable to read pubkey as string");
+
+    let dir = tempfile::tempdir().expect("Can't create the updates dir");
+    let archive = dir.path().join("archive.tar.gz");
+    std::fs::copy("./test/updater/fixture/archives/archive.tar.gz", &archive)
+      .expect("Can't copy the archive");
+    // the cached archive is changed after it was verified by the download
+    OpenOptions::new()
+      .append(true)
+      .open(&archive)
+      .and_then(|mut file| file.write_all(b"tampered"))
+      .expect("Can't change the archive");
+
+    let downloaded = DownloadedUpdate {
+      body: None,
+      version: "2.0.1".into(),
+      date: String::new(),
+      channel: "stable".into(),
+      dir: dir.path().to_path_buf(),
+      extract_path: dir.path().join("my_app.exe"),
+      current_version: "1.0.0".into(),
+      file: "archive.tar.gz".into(),
+      signature: Some(signature),
+      archive: true,
+      pub_key: Some(pubkey),
+    };
+    assert!(downloaded.install(|_| {}).is_err());
+    // the rejected update is removed from the disk
+    assert!(!archive.exists());
+  }
+
   // run complete process on mac only for now as we don't have
   // server (api) that we can use to test
   #[cfg(target_os = "macos")]
@@ -1046,7 +1711,7 @@ mod test {
     assert_eq!(updater.version, "2.0.1");
 
     // download, install and validate signature
-    let install_process = block!(updater.download_and_install(Some(pubkey)));
+    let install_process = block!(updater.download_and_install(Some(pubkey), |_| {}));
     assert!(install_process.is_ok());
 
     // make sure the extraction went well (it should have skipped the main app.app folder)
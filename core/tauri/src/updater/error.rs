@@ -48,6 +48,12 @@ pub enum Error {
   /// On client side, it's important to catch this error.
   #[error("No updates available")]
   UpToDate,
+  /// No previous version to roll back to.
+  #[error("No previous version available")]
+  NoBackup,
+  /// The environment variable placeholders of an endpoint couldn't be resolved.
+  #[error("Unable to resolve the endpoint: {0}")]
+  Endpoint(#[from] tauri_utils::Error),
 }
 
 pub type Result<T = ()> = std::result::Result<T, Error>;
@@ -34,12 +34,18 @@
 //!
 //! "active" must be a boolean. By default, it's set to false.
 //!
-//! "endpoints" must be an array. The string `{{target}}` and `{{current_version}}` are automatically replaced in the URL allowing you determine [server-side](#update-server-json-format) if an update is available. If multiple endpoints are specified, the updater will fallback if a server is not responding within the pre-defined timeout.
+//! "endpoints" must be an array. The strings `{{target}}`, `{{current_version}}` and `{{channel}}` are automatically replaced in the URL allowing you determine [server-side](#update-server-json-format) if an update is available. If multiple endpoints are specified, the updater will fallback if a server is not responding within the pre-defined timeout.
 //!
 //! "dialog" if present must be a boolean. By default, it's set to true. If enabled, [events](#events) are turned-off as the updater will handle everything. If you need the custom events, you MUST turn off the built-in dialog.
 //!
 //! "pubkey" if present must be a valid public-key generated with Tauri cli. See [Signing updates](#signing-updates).
 //!
+//! "channel" if present must be a string naming the release channel to follow, e.g. `beta` or `nightly`. By default, it's set to `stable`.
+//! It can be overridden at runtime with [`UpdaterHandle::channel`], and the update server can announce a release per channel with a `channels` object.
+//!
+//! "allowDowngrades" if present must be a boolean. By default, it's set to false and only the versions greater than the running one are installed.
+//! If enabled, any version other than the running one is installed, so a release can be reverted by announcing the previous version.
+//!
 //! ## Update Requests
 //!
 //! Tauri is indifferent to the request the client application provides for update checking.
@@ -157,11 +163,11 @@
 //!
 //! Emitted data:
 //! ```text
-//! status    [ERROR/PENDING/DONE]
+//! status    [ERROR/PENDING/DOWNLOADED/INSTALLING/DONE/UPTODATE]
 //! error     String/null
 //! ```
 //!
-//! PENDING is emitted when the download is started and DONE when the install is complete. You can then ask to restart the application.
+//! PENDING is emitted when the download is started, DOWNLOADED when the update is downloaded, INSTALLING when the installation starts and DONE when the install is complete. You can then ask to restart the application.
 //!
 //! ERROR is emitted when there is an error with the updater. We suggest to listen to this event even if the dialog is enabled.
 //!
@@ -180,6 +186,28 @@
 //! });
 //! ```
 //!
+//! ### Listen Download Progress
+//!
+//! Event : `tauri://update-download-progress`
+//!
+//! Emitted data:
+//! ```text
+//! received  Number of bytes downloaded
+//! total     Total number of bytes of the download, or null if unknown
+//! ```
+//!
+//! ### Javascript
+//! ```js
+//! import { listen } from "@tauri-apps/api/event";
+//! listen("tauri://update-download-progress", function (res) {
+//!     console.log("Downloaded ", res.payload.received, " of ", res.payload.total);
+//! });
+//! ```
+//!
+//! ### Plugins
+//!
+//! The updater lifecycle is also dispatched to the plugins as [`crate::RunEvent::Updater`] events.
+//!
 //! # Server Support
 //!
 //! Your server should determine whether an update is required based on the [Update Request](#update-requests) your client issues.
@@ -330,6 +358,7 @@
 mod core;
 mod error;
 
+pub use self::core::{DownloadedUpdate, Update};
 pub use self::error::Error;
 
 use crate::manager::tauri_event;
@@ -337,17 +366,28 @@ use crate::{
   api::{
     config::UpdaterConfig,
     dialog::{ask, AskResponse},
+    http::Proxy,
     process::restart,
+    PackageInfo,
   },
+  sealed::ManagerBase,
   Params, Window,
 };
 
+use std::{
+  collections::HashMap,
+  sync::{Arc, Mutex},
+};
+
 /// Check for new updates
 pub const EVENT_CHECK_UPDATE: &str = "tauri://update";
 /// New update available
 pub const EVENT_UPDATE_AVAILABLE: &str = "tauri://update-available";
 /// Used to initialize an update *should run check-update first (once you received the update available event)*
+/// If the update was downloaded with [`EVENT_DOWNLOAD_UPDATE`], it's installed without downloading it again.
 pub const EVENT_INSTALL_UPDATE: &str = "tauri://update-install";
+/// Used to download an update without installing it *should run check-update first (once you received the update available event)*
+pub const EVENT_DOWNLOAD_UPDATE: &str = "tauri://update-download";
 /// Send updater status or error even if dialog is enabled, you should
 /// always listen for this event. It'll send you the install progress
 /// and any error triggered during update check and install
@@ -361,6 +401,240 @@ pub const EVENT_STATUS_ERROR: &str = "ERROR";
 pub const EVENT_STATUS_SUCCESS: &str = "DONE";
 /// When you receive this status, this is because the application is running last version
 pub const EVENT_STATUS_UPTODATE: &str = "UPTODATE";
+/// This is the status emitted when the download is complete
+pub const EVENT_STATUS_DOWNLOADED: &str = "DOWNLOADED";
+/// This is the status emitted when the installation starts
+pub const EVENT_STATUS_INSTALLING: &str = "INSTALLING";
+/// The download progress of the update
+pub const EVENT_DOWNLOAD_PROGRESS: &str = "tauri://update-download-progress";
+
+/// An updater lifecycle event, dispatched to the plugins with [`crate::RunEvent::Updater`].
+#[derive(Debug, Clone)]
+#[non_exhaustive]
+pub enum UpdaterEvent {
+  /// A new version is available.
+  UpdateAvailable {
+    /// The update notes.
+    body: String,
+    /// The update publish date.
+    date: String,
+    /// The announced version.
+    version: String,
+  },
+  /// The update download started.
+  Pending,
+  /// The update download progressed.
+  Progress {
+    /// The number of bytes downloaded.
+    received: u64,
+    /// The total number of bytes of the download, if known.
+    total: Option<u64>,
+  },
+  /// The update was downloaded.
+  Downloaded,
+  /// The update installation started.
+  Installing,
+  /// The update was installed.
+  Updated,
+  /// The application is running the latest version.
+  AlreadyUpToDate,
+  /// The update check or installation failed.
+  Error(String),
+}
+
+#[derive(Default)]
+struct UpdaterRequest {
+  headers: HashMap<String, String>,
+  query: HashMap<String, String>,
+  channel: Option<String>,
+}
+
+/// A handle to the updater, shared by the application.
+/// Get it with [`crate::AppHandle::updater`].
+///
+/// The settings are applied to the next update checks, e.g. to authenticate on a private release server:
+///
+/// ```rust,no_run
+/// tauri::Builder::default()
+///   .setup(|app| {
+///     app
+///       .updater()
+///       .header("Authorization", "Bearer my-api-token")
+///       .query("license", "my-license-key");
+///     Ok(())
+///   });
+/// ```
+///
+/// The handle also checks, downloads and installs the updates as separate steps,
+/// e.g. to download an update in the background and install it on the next launch:
+///
+/// ```rust,no_run
+/// tauri::Builder::default()
+///   .setup(|app| {
+///     let updater = app.updater();
+///     if let Some(update) = updater.pending_update() {
+///       // downloaded on a previous launch
+///       update.install(|_event| {})?;
+///     } else {
+///       tauri::async_runtime::spawn(async move {
+///         if let Ok(update) = updater.check().await {
+///           if update.should_update {
+///             let _ = updater.download(&update, |_event| {}).await;
+///           }
+///         }
+///       });
+///     }
+///     Ok(())
+///   });
+/// ```
+#[derive(Clone)]
+pub struct UpdaterHandle {
+  config: UpdaterConfig,
+  package_info: PackageInfo,
+  proxy: Option<Proxy>,
+  request: Arc<Mutex<UpdaterRequest>>,
+}
+
+impl UpdaterHandle {
+  pub(crate) fn new(
+    config: UpdaterConfig,
+    package_info: PackageInfo,
+    proxy: Option<Proxy>,
+  ) -> Self {
+    Self {
+      config,
+      package_info,
+      proxy,
+      request: Default::default(),
+    }
+  }
+
+  fn request(&self) -> std::sync::MutexGuard<'_, UpdaterRequest> {
+    self.request.lock().expect("poisoned updater request")
+  }
+
+  /// Sets a header sent on the update check requests, and on the download requests served from the same origin.
+  pub fn header(&self, key: impl Into<String>, value: impl Into<String>) -> &Self {
+    self.request().headers.insert(key.into(), value.into());
+    self
+  }
+
+  /// Removes a header previously set with [`Self::header`] or [`Self::basic_auth`].
+  pub fn remove_header(&self, key: &str) -> &Self {
+    self.request().headers.remove(key);
+    self
+  }
+
+  /// Sets the HTTP basic authentication credentials sent on the update check requests,
+  /// and on the download requests served from the same origin.
+  pub fn basic_auth(&self, username: &str, password: &str) -> &Self {
+    self.header(
+      "Authorization",
+      format!(
+        "Basic {}",
+        base64::encode(format!("{}:{}", username, password))
+      ),
+    )
+  }
+
+  /// Sets a query parameter sent on the update check requests.
+  pub fn query(&self, key: impl Into<String>, value: impl Into<String>) -> &Self {
+    self.request().query.insert(key.into(), value.into());
+    self
+  }
+
+  /// Removes a query parameter previously set with [`Self::query`].
+  pub fn remove_query(&self, key: &str) -> &Self {
+    self.request().query.remove(key);
+    self
+  }
+
+  /// Sets the release channel of the next update checks, overriding the `channel` configured in `tauri.conf.json`.
+  pub fn channel(&self, channel: impl Into<String>) -> &Self {
+    self.request().channel.replace(channel.into());
+    self
+  }
+
+  /// Removes the channel set with [`Self::channel`], going back to the configured one.
+  pub fn clear_channel(&self) -> &Self {
+    self.request().channel.take();
+    self
+  }
+
+  // Creates an update builder with the current settings.
+  fn builder<'a>(&self, updater_config: &UpdaterConfig) -> self::core::UpdateBuilder<'a> {
+    let request = self.request();
+    let mut builder = self::core::builder()
+      .headers(request.headers.clone())
+      .query(request.query.clone())
+      .allow_downgrades(updater_config.allow_downgrades);
+    if let Some(proxy) = self.proxy.clone() {
+      builder = builder.proxy(proxy);
+    }
+    match request
+      .channel
+      .as_ref()
+      .or_else(|| updater_config.channel.as_ref())
+    {
+      Some(channel) => builder.channel(channel.clone()),
+      None => builder,
+    }
+  }
+
+  /// Checks the configured endpoints for an update.
+  pub async fn check(&self) -> self::error::Result<Update> {
+    self
+      .builder(&self.config)
+      .urls(self.config.endpoints.as_deref().unwrap_or_default())
+      .current_version(&self.package_info.version)
+      .build()
+      .await
+  }
+
+  /// Downloads the update and caches it on disk, verifying its signature with the configured `pubkey`.
+  ///
+  /// The downloaded update is installed with [`DownloadedUpdate::install`],
+  /// or found on the next launches with [`Self::pending_update`].
+  pub async fn download<F: FnMut(UpdaterEvent) + Send>(
+    &self,
+    update: &Update,
+    on_event: F,
+  ) -> self::error::Result<DownloadedUpdate> {
+    update.download(self.config.pubkey.clone(), on_event).await
+  }
+
+  /// Gets the update downloaded by a previous [`Self::download`], if it wasn't installed yet.
+  ///
+  /// It is discarded when [`Self::check`] finds a newer update,
+  /// and its signature is verified again with the configured `pubkey` when it's installed.
+  pub fn pending_update(&self) -> Option<DownloadedUpdate> {
+    self::core::pending_update(
+      &self.package_info.version,
+      self.config.allow_downgrades,
+      self.config.pubkey.clone(),
+    )
+  }
+
+  /// Gets the version kept by the last update installed on Linux or macOS, restored by [`Self::rollback`].
+  pub fn previous_version(&self) -> Option<String> {
+    self::core::previous_version()
+  }
+
+  /// Restores the version kept by the last update, e.g. to revert a botched release.
+  /// The application must be restarted to run the restored version.
+  ///
+  /// Returns [`Error::NoBackup`] if there's no version to restore.
+  /// Returns [`Error::UnsupportedPlatform`] on Windows, where the installers don't keep the previous version.
+  pub fn rollback(&self) -> self::error::Result<()> {
+    self::core::rollback(&std::env::current_exe()?)
+  }
+}
+
+#[derive(Clone, serde::Serialize)]
+struct ProgressEvent {
+  received: u64,
+  total: Option<u64>,
+}
 
 #[derive(Clone, serde::Serialize)]
 struct StatusEvent {
@@ -383,7 +657,10 @@ pub(crate) async fn check_update_with_dialog<P: Params>(
 ) {
   if let Some(endpoints) = updater_config.endpoints.clone() {
     // check updates
-    match self::core::builder()
+    match window
+      .manager()
+      .updater()
+      .builder(&updater_config)
       .urls(&endpoints[..])
       .current_version(&package_info.version)
       .build()
@@ -395,14 +672,19 @@ pub(crate) async fn check_update_with_dialog<P: Params>(
         // if dialog enabled only
         if updater.should_update && updater_config.dialog {
           let body = updater.body.clone().unwrap_or_else(|| String::from(""));
-          let dialog =
-            prompt_for_install(&updater.clone(), &package_info.name, &body.clone(), pubkey).await;
+          let dialog = prompt_for_install(
+            window.clone(),
+            &updater.clone(),
+            &package_info.name,
+            &body.clone(),
+            pubkey,
+          )
+          .await;
 
           if dialog.is_err() {
-            send_status_update(
-              window.clone(),
-              EVENT_STATUS_ERROR,
-              Some(dialog.err().unwrap().to_string()),
+            emit_event(
+              &window,
+              UpdaterEvent::Error(dialog.err().unwrap().to_string()),
             );
 
             return;
@@ -410,7 +692,7 @@ pub(crate) async fn check_update_with_dialog<P: Params>(
         }
       }
       Err(e) => {
-        send_status_update(window.clone(), EVENT_STATUS_ERROR, Some(e.to_string()));
+        emit_event(&window, UpdaterEvent::Error(e.to_string()));
       }
     }
   }
@@ -423,8 +705,12 @@ pub(crate) fn listener<P: Params>(
   package_info: crate::api::PackageInfo,
   window: &Window<P>,
 ) {
-  let isolated_window = window.clone();
+  // the update found by the last check, downloaded or installed on request
+  let available_update: Arc<Mutex<Option<Update>>> = Default::default();
 
+  let isolated_window = window.clone();
+  let available_update_ = available_update.clone();
+  let config = updater_config.clone();
   // Wait to receive the event `"tauri://update"`
   window.listen(
     EVENT_CHECK_UPDATE
@@ -433,23 +719,23 @@ pub(crate) fn listener<P: Params>(
     move |_msg| {
       let window = isolated_window.clone();
       let package_info = package_info.clone();
+      let available_update = available_update_.clone();
 
       // prepare our endpoints
-      let endpoints = updater_config
+      let endpoints = config
         .endpoints
         .as_ref()
         .expect("Something wrong with endpoints")
         .clone();
 
-      let pubkey = updater_config.pubkey.clone();
+      let updater_config = config.clone();
 
       // check updates
       crate::async_runtime::spawn(async move {
-        let window = window.clone();
-        let window_isolation = window.clone();
-        let pubkey = pubkey.clone();
-
-        match self::core::builder()
+        match window
+          .manager()
+          .updater()
+          .builder(&updater_config)
           .urls(&endpoints[..])
           .current_version(&package_info.version)
           .build()
@@ -461,59 +747,157 @@ pub(crate) fn listener<P: Params>(
               let body = updater.body.clone().unwrap_or_else(|| String::from(""));
 
               // Emit `tauri://update-available`
-              let _ = window.emit(
-                &tauri_event::<P::Event>(EVENT_UPDATE_AVAILABLE),
-                Some(UpdateManifest {
+              emit_event(
+                &window,
+                UpdaterEvent::UpdateAvailable {
                   body,
                   date: updater.date.clone(),
                   version: updater.version.clone(),
-                }),
-              );
-
-              // Listen for `tauri://update-install`
-              window.once(
-                EVENT_INSTALL_UPDATE
-                  .parse::<P::Event>()
-                  .unwrap_or_else(|_| panic!("bad label")),
-                move |_msg| {
-                  let window = window_isolation.clone();
-                  let updater = updater.clone();
-                  let pubkey = pubkey.clone();
-
-                  // Start installation
-                  crate::async_runtime::spawn(async move {
-                    // emit {"status": "PENDING"}
-                    send_status_update(window.clone(), EVENT_STATUS_PENDING, None);
-
-                    // Launch updater download process
-                    // macOS we display the `Ready to restart dialog` asking to restart
-                    // Windows is closing the current App and launch the downloaded MSI when ready (the process stop here)
-                    // Linux we replace the AppImage by launching a new install, it start a new AppImage instance, so we're closing the previous. (the process stop here)
-                    let update_result = updater.clone().download_and_install(pubkey.clone()).await;
-
-                    if let Err(err) = update_result {
-                      // emit {"status": "ERROR", "error": "The error message"}
-                      send_status_update(window.clone(), EVENT_STATUS_ERROR, Some(err.to_string()));
-                    } else {
-                      // emit {"status": "DONE"}
-                      send_status_update(window.clone(), EVENT_STATUS_SUCCESS, None);
-                    }
-                  })
                 },
               );
+
+              // downloaded on `tauri://update-download` or installed on `tauri://update-install`
+              available_update
+                .lock()
+                .expect("poisoned updater state")
+                .replace(updater);
             } else {
-              send_status_update(window.clone(), EVENT_STATUS_UPTODATE, None);
+              emit_event(&window, UpdaterEvent::AlreadyUpToDate);
             }
           }
           Err(e) => {
-            send_status_update(window.clone(), EVENT_STATUS_ERROR, Some(e.to_string()));
+            emit_event(&window, UpdaterEvent::Error(e.to_string()));
+          }
+        }
+      });
+    },
+  );
+
+  let isolated_window = window.clone();
+  let available_update_ = available_update.clone();
+  let pubkey = updater_config.pubkey.clone();
+  // Listen for `tauri://update-download`
+  window.listen(
+    EVENT_DOWNLOAD_UPDATE
+      .parse::<P::Event>()
+      .unwrap_or_else(|_| panic!("bad label")),
+    move |_msg| {
+      let window = isolated_window.clone();
+      let update = available_update_
+        .lock()
+        .expect("poisoned updater state")
+        .clone();
+      let pubkey = pubkey.clone();
+
+      crate::async_runtime::spawn(async move {
+        // emit {"status": "PENDING"}
+        emit_event(&window, UpdaterEvent::Pending);
+
+        let window_ = window.clone();
+        let download_result = match update {
+          Some(update) => update
+            .download(pubkey, move |event| emit_event(&window_, event))
+            .await
+            .map(|_| ()),
+          None => Err(Error::UpToDate),
+        };
+
+        if let Err(err) = download_result {
+          // emit {"status": "ERROR", "error": "The error message"}
+          emit_event(&window, UpdaterEvent::Error(err.to_string()));
+        }
+      });
+    },
+  );
+
+  let isolated_window = window.clone();
+  let pubkey = updater_config.pubkey;
+  // Listen for `tauri://update-install`
+  window.listen(
+    EVENT_INSTALL_UPDATE
+      .parse::<P::Event>()
+      .unwrap_or_else(|_| panic!("bad label")),
+    move |_msg| {
+      let window = isolated_window.clone();
+      let update = available_update
+        .lock()
+        .expect("poisoned updater state")
+        .clone();
+      let pubkey = pubkey.clone();
+
+      // Start installation
+      crate::async_runtime::spawn(async move {
+        // emit {"status": "PENDING"}
+        emit_event(&window, UpdaterEvent::Pending);
+
+        // Install the downloaded update if there's one, otherwise download it first
+        // macOS we display the `Ready to restart dialog` asking to restart
+        // Windows is closing the current App and launch the downloaded MSI when ready (the process stop here)
+        // Linux we replace the AppImage by launching a new install, it start a new AppImage instance, so we're closing the previous. (the process stop here)
+        let window_ = window.clone();
+        let on_event = move |event: UpdaterEvent| emit_event(&window_, event);
+        // the checks discard the downloaded updates older than the update they found
+        let update_result = match (window.manager().updater().pending_update(), update) {
+          (Some(downloaded), Some(update))
+            if crate::api::version::is_greater(&downloaded.version, &update.version)
+              .unwrap_or(false) =>
+          {
+            update.download_and_install(pubkey, on_event).await
           }
+          (Some(downloaded), _) => downloaded.install(on_event),
+          (None, Some(update)) => update.download_and_install(pubkey, on_event).await,
+          (None, None) => Err(Error::UpToDate),
+        };
+
+        if let Err(err) = update_result {
+          // emit {"status": "ERROR", "error": "The error message"}
+          emit_event(&window, UpdaterEvent::Error(err.to_string()));
+        } else {
+          // emit {"status": "DONE"}
+          emit_event(&window, UpdaterEvent::Updated);
         }
-      })
+      });
     },
   );
 }
 
+// Dispatch the event to the plugins and emit its `tauri://update-*` equivalent to the window.
+fn emit_event<P: Params>(window: &Window<P>, event: UpdaterEvent) {
+  window.manager().run_plugins_updater_event(event.clone());
+  match event {
+    UpdaterEvent::UpdateAvailable {
+      body,
+      date,
+      version,
+    } => {
+      let _ = window.emit(
+        &tauri_event::<P::Event>(EVENT_UPDATE_AVAILABLE),
+        Some(UpdateManifest {
+          body,
+          date,
+          version,
+        }),
+      );
+    }
+    UpdaterEvent::Pending => send_status_update(window.clone(), EVENT_STATUS_PENDING, None),
+    UpdaterEvent::Progress { received, total } => {
+      let _ = window.emit(
+        &tauri_event::<P::Event>(EVENT_DOWNLOAD_PROGRESS),
+        Some(ProgressEvent { received, total }),
+      );
+    }
+    UpdaterEvent::Downloaded => send_status_update(window.clone(), EVENT_STATUS_DOWNLOADED, None),
+    UpdaterEvent::Installing => send_status_update(window.clone(), EVENT_STATUS_INSTALLING, None),
+    UpdaterEvent::Updated => send_status_update(window.clone(), EVENT_STATUS_SUCCESS, None),
+    UpdaterEvent::AlreadyUpToDate => {
+      send_status_update(window.clone(), EVENT_STATUS_UPTODATE, None)
+    }
+    UpdaterEvent::Error(error) => {
+      send_status_update(window.clone(), EVENT_STATUS_ERROR, Some(error))
+    }
+  }
+}
+
 // Send a status update via `tauri://update-status` event.
 fn send_status_update<P: Params>(window: Window<P>, status: &str, error: Option<String>) {
   let _ = window.emit(
@@ -527,7 +911,8 @@ fn send_status_update<P: Params>(window: Window<P>, status: &str, error: Option<
 
 // Prompt a dialog asking if the user want to install the new version
 // Maybe we should add an option to customize it in future versions.
-async fn prompt_for_install(
+async fn prompt_for_install<P: Params>(
+  window: Window<P>,
   updater: &self::core::Update,
   app_name: &str,
   body: &str,
@@ -557,7 +942,12 @@ Release Notes:
       // macOS we display the `Ready to restart dialog` asking to restart
       // Windows is closing the current App and launch the downloaded MSI when ready (the process stop here)
       // Linux we replace the AppImage by launching a new install, it start a new AppImage instance, so we're closing the previous. (the process stop here)
-      updater.download_and_install(pubkey.clone()).await?;
+      emit_event(&window, UpdaterEvent::Pending);
+      let window_ = window.clone();
+      updater
+        .download_and_install(pubkey.clone(), move |event| emit_event(&window_, event))
+        .await?;
+      emit_event(&window, UpdaterEvent::Updated);
 
       // Ask user if we need to restart the application
       let should_exit = ask(
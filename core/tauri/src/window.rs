@@ -2,6 +2,7 @@
 // SPDX-License-Identifier: Apache-2.0
 // SPDX-License-Identifier: MIT
 
+pub(crate) mod cookies;
 #[cfg(feature = "menu")]
 #[cfg_attr(doc_cfg, doc(cfg(feature = "menu")))]
 pub(crate) mod menu;
@@ -9,15 +10,18 @@ pub(crate) mod menu;
 use crate::{
   api::config::WindowUrl,
   command::{CommandArg, CommandItem},
-  event::{Event, EventHandler},
+  event::{Event, EventHandler, EventHandlerGuard},
   manager::WindowManager,
   runtime::{
     monitor::Monitor as RuntimeMonitor,
     tag::{TagRef, ToJsString},
-    webview::{InvokePayload, WebviewAttributes, WindowBuilder},
+    webview::{
+      DataTypes, FindOptions, FindResult, InvokePayload, PrintOptions, WebviewAttributes,
+      WindowBuilder,
+    },
     window::{
       dpi::{PhysicalPosition, PhysicalSize, Position, Size},
-      DetachedWindow, PendingWindow, WindowEvent,
+      DetachedWindow, DragItem, PendingWindow, ThumbbarButton, WindowEvent,
     },
     Dispatch, Icon, Params, Runtime,
   },
@@ -26,13 +30,43 @@ use crate::{
   Invoke, InvokeError, InvokeMessage, InvokeResolver, Manager, PageLoadPayload,
 };
 
-use serde::Serialize;
+#[cfg(feature = "touchbar")]
+use crate::touchbar::{TouchBar, TouchBarEvent};
+
+use serde::{de::DeserializeOwned, Serialize};
+use serde_json::Value as JsonValue;
+use uuid::Uuid;
 
 use std::{
   borrow::Borrow,
   hash::{Hash, Hasher},
+  path::{Path, PathBuf},
 };
 
+crate::manager::default_args! {
+  /// A handle to a stylesheet injected with [`Window::inject_css`].
+  pub struct StyleHandle<P: Params> {
+    id: Uuid,
+    dispatcher: <P::Runtime as Runtime>::Dispatcher,
+  }
+}
+
+impl<P: Params> Clone for StyleHandle<P> {
+  fn clone(&self) -> Self {
+    Self {
+      id: self.id,
+      dispatcher: self.dispatcher.clone(),
+    }
+  }
+}
+
+impl<P: Params> StyleHandle<P> {
+  /// Removes the stylesheet from the webview.
+  pub fn remove(&self) -> crate::Result<()> {
+    self.dispatcher.remove_css(self.id).map_err(Into::into)
+  }
+}
+
 /// Monitor descriptor.
 #[derive(Debug, Clone, Serialize)]
 #[serde(rename_all = "camelCase")]
@@ -185,6 +219,11 @@ impl<P: Params> Window<P> {
   /// How to handle this window receiving an [`InvokeMessage`].
   pub(crate) fn on_message(self, command: String, payload: InvokePayload) -> crate::Result<()> {
     let manager = self.manager.clone();
+    if !manager.validate_ipc_message(self.label(), &command, &payload) {
+      InvokeResolver::new(self, payload.callback, payload.error)
+        .reject("the IPC message was rejected");
+      return Ok(());
+    }
     match command.as_str() {
       "__initialized" => {
         let payload: PageLoadPayload = serde_json::from_value(payload.inner)?;
@@ -196,17 +235,23 @@ impl<P: Params> Window<P> {
           manager.state(),
           command.to_string(),
           payload.inner,
+          payload.callback.clone(),
         );
+        manager.register_invoke(self.label().clone(), payload.callback.clone());
+        manager.observe_invoke(&message);
         let resolver = InvokeResolver::new(self, payload.callback, payload.error);
         let invoke = Invoke { message, resolver };
-        if let Some(module) = &payload.tauri_module {
-          let module = module.to_string();
-          crate::endpoints::handle(module, invoke, manager.config(), manager.package_info());
-        } else if command.starts_with("plugin:") {
-          manager.extend_api(invoke);
-        } else {
-          manager.run_invoke_handler(invoke);
-        }
+        let tauri_module = payload.tauri_module;
+        manager.run_invoke_middlewares(invoke, &|invoke| {
+          if let Some(module) = &tauri_module {
+            let module = module.to_string();
+            crate::endpoints::handle(module, invoke, manager.config(), manager.package_info());
+          } else if command.starts_with("plugin:") {
+            manager.extend_api(invoke);
+          } else {
+            manager.run_invoke_handler(invoke);
+          }
+        });
       }
     }
 
@@ -225,15 +270,24 @@ impl<P: Params> Window<P> {
     E: TagRef<P::Event>,
     S: Serialize,
   {
+    self.emit_serialized(&event.to_js_string()?, &serde_json::to_value(payload)?)
+  }
+
+  /// Emits an event whose name and payload are already serialized.
+  pub(crate) fn emit_serialized(&self, event: &str, payload: &JsonValue) -> crate::Result<()> {
+    #[cfg(feature = "test")]
+    self
+      .manager
+      .test_hooks()
+      .record_event(self.label().to_string(), event, payload);
+    crate::ipc::bind_binary(payload, &self.label().to_string());
     self.eval(&format!(
       "window['{}']({{event: {}, payload: {}}}, '{}')",
       self.manager.event_emit_function_name(),
-      event.to_js_string()?,
-      serde_json::to_value(payload)?,
+      event,
+      payload,
       self.manager.generate_salt(),
-    ))?;
-
-    Ok(())
+    ))
   }
 
   /// Emits an event on all windows except this one.
@@ -255,6 +309,19 @@ impl<P: Params> Window<P> {
     self.manager.listen(event.into(), Some(label), handler)
   }
 
+  /// Listen to an event on this window until the returned guard is dropped.
+  pub fn listen_guarded<E: Into<P::Event>, F>(
+    &self,
+    event: E,
+    handler: F,
+  ) -> EventHandlerGuard<P::Event, P::Label>
+  where
+    F: Fn(Event) + Send + 'static,
+  {
+    let id = self.listen(event, handler);
+    self.manager.event_handler_guard(id)
+  }
+
   /// Listen to a an event on this window a single time.
   pub fn once<E: Into<P::Event>, F>(&self, event: E, handler: F) -> EventHandler
   where
@@ -279,6 +346,16 @@ impl<P: Params> Window<P> {
     self.window.dispatcher.eval_script(js).map_err(Into::into)
   }
 
+  /// Evaluates JavaScript on this window and deserializes its completion value.
+  /// If the script evaluates to a promise, its resolved value is used instead and a rejection is returned as an error.
+  ///
+  /// This method blocks until the webview returns the value, so it returns an error on the main thread.
+  /// It waits until the promise settles, the page is unloaded or the window is closed.
+  pub fn eval_with_result<T: DeserializeOwned>(&self, js: &str) -> crate::Result<T> {
+    let value = self.window.dispatcher.eval_script_with_result(js)?;
+    serde_json::from_value(value).map_err(Into::into)
+  }
+
   /// Registers a window event listener.
   pub fn on_window_event<F: Fn(&WindowEvent) + Send + 'static>(&self, f: F) {
     self.window.dispatcher.on_window_event(f);
@@ -296,6 +373,21 @@ impl<P: Params> Window<P> {
     });
   }
 
+  /// Registers a Touch Bar event listener.
+  #[cfg(feature = "touchbar")]
+  #[cfg_attr(doc_cfg, doc(cfg(feature = "touchbar")))]
+  pub fn on_touchbar_event<F: Fn(TouchBarEvent<P::MenuId>) + Send + 'static>(&self, f: F) {
+    let manager = self.manager.clone();
+    self.window.dispatcher.on_touchbar_event(move |event| {
+      if let Some(item_id) = manager.touchbar_item_id(event.item_id) {
+        f(TouchBarEvent {
+          item_id,
+          value: event.value,
+        })
+      }
+    });
+  }
+
   // Getters
 
   /// Gets a handle to the window menu.
@@ -395,6 +487,47 @@ impl<P: Params> Window<P> {
       .map_err(Into::into)
   }
 
+  /// Returns the current zoom factor of the webview, where `1.0` is the default size.
+  ///
+  /// This method must not be called on the main thread, since it blocks until the webview returns the value.
+  /// Unsupported on Windows on `wry`.
+  pub fn zoom(&self) -> crate::Result<f64> {
+    self.window.dispatcher.zoom().map_err(Into::into)
+  }
+
+  /// Returns a handle to manage the cookies of the page loaded on this window.
+  pub fn cookies(&self) -> cookies::CookieManager<P> {
+    cookies::CookieManager {
+      dispatcher: self.dispatcher(),
+    }
+  }
+
+  /// Whether the webview is playing audio or not.
+  ///
+  /// This method must not be called on the main thread, since it blocks until the webview returns the value.
+  /// Only supported on Linux on `wry`.
+  pub fn is_playing_audio(&self) -> crate::Result<bool> {
+    self
+      .window
+      .dispatcher
+      .is_playing_audio()
+      .map_err(Into::into)
+  }
+
+  /// Whether the devtools are opened or not.
+  ///
+  /// This method must not be called on the main thread, since it blocks until the webview returns the value.
+  /// On `wry` it's unsupported on Windows and requires macOS 10.14.4 on macOS.
+  #[cfg(feature = "devtools")]
+  #[cfg_attr(doc_cfg, doc(cfg(feature = "devtools")))]
+  pub fn is_devtools_open(&self) -> crate::Result<bool> {
+    self
+      .window
+      .dispatcher
+      .is_devtools_open()
+      .map_err(Into::into)
+  }
+
   /// Returns the native handle that is used by this window.
   #[cfg(windows)]
   pub fn hwnd(&self) -> crate::Result<*mut std::ffi::c_void> {
@@ -415,6 +548,147 @@ impl<P: Params> Window<P> {
     self.window.dispatcher.print().map_err(Into::into)
   }
 
+  /// Opens the dialog to prints the contents of the webview with the given page setup.
+  /// The options are set on the page setup of the native print operation, which requires macOS 11 on macOS.
+  /// Unsupported on Windows on `wry`.
+  pub fn print_with_options(&self, options: PrintOptions) -> crate::Result<()> {
+    self
+      .window
+      .dispatcher
+      .print_with_options(options)
+      .map_err(Into::into)
+  }
+
+  /// Prints the contents of the webview to the PDF file at the given absolute path with the given page setup,
+  /// without showing the print dialog.
+  ///
+  /// This method must not be called on the main thread, since it blocks until the file is written.
+  /// Unsupported on Windows on `wry`.
+  pub fn print_to_pdf<P: AsRef<Path>>(&self, path: P, options: PrintOptions) -> crate::Result<()> {
+    self
+      .window
+      .dispatcher
+      .print_to_pdf(path.as_ref().to_path_buf(), options)
+      .map_err(Into::into)
+  }
+
+  /// Opens the devtools of the webview.
+  ///
+  /// On `wry` it's unsupported on Windows, and it uses private WebKit APIs on macOS,
+  /// so apps enabling the `devtools` feature can't be published on the Mac App Store.
+  /// The devtools aren't opened before macOS 10.14.4.
+  #[cfg(feature = "devtools")]
+  #[cfg_attr(doc_cfg, doc(cfg(feature = "devtools")))]
+  pub fn open_devtools(&self) -> crate::Result<()> {
+    self.window.dispatcher.open_devtools().map_err(Into::into)
+  }
+
+  /// Closes the devtools of the webview.
+  ///
+  /// Unsupported on Windows on `wry`.
+  #[cfg(feature = "devtools")]
+  #[cfg_attr(doc_cfg, doc(cfg(feature = "devtools")))]
+  pub fn close_devtools(&self) -> crate::Result<()> {
+    self.window.dispatcher.close_devtools().map_err(Into::into)
+  }
+
+  /// Searches the given text on the webview and highlights the first match.
+  ///
+  /// This method must not be called on the main thread, since it blocks until the webview returns the result.
+  /// On `wry` it's unsupported on Windows and requires macOS 11 on macOS, where the matches aren't counted.
+  pub fn find<S: Into<String>>(&self, text: S, options: FindOptions) -> crate::Result<FindResult> {
+    self
+      .window
+      .dispatcher
+      .find(text.into(), options)
+      .map_err(Into::into)
+  }
+
+  /// Highlights the next match of the current search. Returns whether a match was found or not.
+  ///
+  /// This method must not be called on the main thread, since it blocks until the webview returns the result.
+  pub fn find_next(&self) -> crate::Result<bool> {
+    self.window.dispatcher.find_next().map_err(Into::into)
+  }
+
+  /// Highlights the previous match of the current search. Returns whether a match was found or not.
+  ///
+  /// This method must not be called on the main thread, since it blocks until the webview returns the result.
+  pub fn find_previous(&self) -> crate::Result<bool> {
+    self.window.dispatcher.find_previous().map_err(Into::into)
+  }
+
+  /// Clears the current search and its highlight.
+  pub fn clear_find(&self) -> crate::Result<()> {
+    self.window.dispatcher.clear_find().map_err(Into::into)
+  }
+
+  /// Injects a stylesheet on the current and future pages of the webview.
+  pub fn inject_css<S: Into<String>>(&self, css: S) -> crate::Result<StyleHandle<P>> {
+    let id = self.window.dispatcher.inject_css(css.into())?;
+    Ok(StyleHandle {
+      id,
+      dispatcher: self.dispatcher(),
+    })
+  }
+
+  /// Adds an initialization script that is executed on the next pages loaded on the webview.
+  ///
+  /// On `wry` the script runs when the page content is loaded instead of before the page scripts.
+  pub fn add_init_script<S: Into<String>>(&self, script: S) -> crate::Result<()> {
+    self
+      .window
+      .dispatcher
+      .add_init_script(script.into())
+      .map_err(Into::into)
+  }
+
+  /// Mutes or unmutes the audio of the webview.
+  ///
+  /// Only supported on Linux with WebKitGTK 2.30 or newer on `wry`.
+  pub fn set_muted(&self, muted: bool) -> crate::Result<()> {
+    self.window.dispatcher.set_muted(muted).map_err(Into::into)
+  }
+
+  /// Overrides the user agent of the webview, used by the next requests and pages.
+  ///
+  /// Unsupported on Windows on `wry`, where [`WindowBuilder::user_agent`](crate::WindowBuilder::user_agent)
+  /// sets the user agent of all the webviews when the first window is created.
+  pub fn set_user_agent<S: Into<String>>(&self, user_agent: S) -> crate::Result<()> {
+    self
+      .window
+      .dispatcher
+      .set_user_agent(user_agent.into())
+      .map_err(Into::into)
+  }
+
+  /// Sets the zoom factor of the webview, where `1.0` is the default size.
+  ///
+  /// The zoom factor is clamped between `0.2` and `5.0` on `wry`. Unsupported on Windows,
+  /// and macOS 10.15 and older magnify the webview instead of zooming the page.
+  pub fn set_zoom(&self, scale_factor: f64) -> crate::Result<()> {
+    self
+      .window
+      .dispatcher
+      .set_zoom(scale_factor)
+      .map_err(Into::into)
+  }
+
+  /// Clears the specified browsing data of the webview, e.g. on logout.
+  ///
+  /// The data of all the websites is removed from the data store of the webview,
+  /// which is shared with the windows using the same data directory.
+  ///
+  /// This method must not be called on the main thread, since it blocks until the data is cleared.
+  /// Unsupported on Windows on `wry`.
+  pub fn clear_browsing_data(&self, data_types: DataTypes) -> crate::Result<()> {
+    self
+      .window
+      .dispatcher
+      .clear_browsing_data(data_types)
+      .map_err(Into::into)
+  }
+
   /// Determines if this window should be resizable.
   pub fn set_resizable(&self, resizable: bool) -> crate::Result<()> {
     self
@@ -557,6 +831,108 @@ impl<P: Params> Window<P> {
     self.window.dispatcher.start_dragging().map_err(Into::into)
   }
 
+  /// Starts an OS drag and drop operation of the files out of the window,
+  /// so they can be dropped on the file manager or on other apps.
+  ///
+  /// The mouse button must still be pressed when the drag starts, otherwise an error is returned:
+  /// invoke the command on the `dragstart` event of the element, preventing its default behavior,
+  /// rather than on `mousedown`, which is usually followed by the release before the command runs.
+  /// On Windows it returns when the drag started, while the item is dragged on a modal loop.
+  /// The relative paths are resolved against the current directory.
+  ///
+  /// # Example
+  /// ```rust,no_run
+  /// #[tauri::command]
+  /// fn drag_attachment(window: tauri::Window, path: std::path::PathBuf) -> Result<(), String> {
+  ///   window
+  ///     .start_drag(tauri::DragItem::Files(vec![path]))
+  ///     .map_err(|e| e.to_string())
+  /// }
+  /// ```
+  pub fn start_drag(&self, item: DragItem) -> crate::Result<()> {
+    let cwd = std::env::current_dir()?;
+    let resolve = |path: PathBuf| {
+      let path = cwd.join(path);
+      if path.exists() {
+        Ok(path)
+      } else {
+        Err(crate::Error::DragItemNotFound(path))
+      }
+    };
+    let item = match item {
+      DragItem::Files(paths) => DragItem::Files(
+        paths
+          .into_iter()
+          .map(resolve)
+          .collect::<crate::Result<_>>()?,
+      ),
+      DragItem::Image(path) => DragItem::Image(resolve(path)?),
+      item => item,
+    };
+    self.window.dispatcher.start_drag(item).map_err(Into::into)
+  }
+
+  /// Sets the buttons of the toolbar shown on the window thumbnail in the taskbar, replacing the current ones.
+  ///
+  /// At most [`ThumbbarButton::MAX`] buttons are supported, and their icons must be `.ico` or `.png` files.
+  /// The clicks are sent as [`WindowEvent::ThumbbarButtonClicked`] and emitted to the window as `tauri://thumbbar-button-click`.
+  /// Only supported on Windows, it's a no-op on the other platforms.
+  ///
+  /// # Example
+  /// ```rust,no_run
+  /// use tauri::{Icon, Manager, ThumbbarButton, WindowEvent};
+  ///
+  /// tauri::Builder::default()
+  ///   .setup(|app| {
+  ///     let window = app.get_window("main").unwrap();
+  ///     window.set_thumbbar_buttons(vec![
+  ///       ThumbbarButton::new("previous", Icon::File("icons/previous.ico".into())).tooltip("Previous"),
+  ///       ThumbbarButton::new("play", Icon::File("icons/play.ico".into())).tooltip("Play"),
+  ///       ThumbbarButton::new("next", Icon::File("icons/next.ico".into())).tooltip("Next"),
+  ///     ])?;
+  ///     let window_ = window.clone();
+  ///     window.on_window_event(move |event| {
+  ///       if let WindowEvent::ThumbbarButtonClicked(id) = event {
+  ///         window_.emit("media-control", id.clone()).unwrap();
+  ///       }
+  ///     });
+  ///     Ok(())
+  ///   });
+  /// ```
+  pub fn set_thumbbar_buttons(&self, buttons: Vec<ThumbbarButton>) -> crate::Result<()> {
+    self
+      .window
+      .dispatcher
+      .set_thumbbar_buttons(buttons)
+      .map_err(Into::into)
+  }
+
+  /// Sets the Touch Bar of the window, replacing the current one.
+  ///
+  /// Only supported on macOS, it's a no-op on the other platforms.
+  /// The webview shows its own items instead while a text field is focused.
+  #[cfg(feature = "touchbar")]
+  #[cfg_attr(doc_cfg, doc(cfg(feature = "touchbar")))]
+  pub fn set_touchbar(&self, touchbar: TouchBar<P::MenuId>) -> crate::Result<()> {
+    self.manager.register_touchbar_ids(&touchbar);
+    self
+      .window
+      .dispatcher
+      .set_touchbar(Some(touchbar))
+      .map_err(Into::into)
+  }
+
+  /// Removes the Touch Bar of the window.
+  #[cfg(feature = "touchbar")]
+  #[cfg_attr(doc_cfg, doc(cfg(feature = "touchbar")))]
+  pub fn remove_touchbar(&self) -> crate::Result<()> {
+    self
+      .window
+      .dispatcher
+      .set_touchbar::<P::MenuId>(None)
+      .map_err(Into::into)
+  }
+
   pub(crate) fn verify_salt(&self, salt: String) -> bool {
     self.manager.verify_salt(salt)
   }
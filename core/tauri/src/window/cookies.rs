@@ -0,0 +1,55 @@
+// Copyright 2019-2021 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+use crate::{
+  runtime::{webview::Cookie, Dispatch, Runtime},
+  Params,
+};
+
+crate::manager::default_args! {
+  /// A handle to the cookies of the page loaded on a window.
+  ///
+  /// On `wry` the cookies are managed with the cookie store of the webview, which is unsupported on Windows.
+  /// The methods wait for the cookie store, so they must not be called on the main thread.
+  pub struct CookieManager<P: Params> {
+    pub(crate) dispatcher: <P::Runtime as Runtime>::Dispatcher,
+  }
+}
+
+impl<P: Params> Clone for CookieManager<P> {
+  fn clone(&self) -> Self {
+    Self {
+      dispatcher: self.dispatcher.clone(),
+    }
+  }
+}
+
+impl<P: Params> CookieManager<P> {
+  /// Lists the cookies sent on the requests to the URL of the page, including the `HttpOnly` cookies.
+  pub fn list(&self) -> crate::Result<Vec<Cookie>> {
+    self.dispatcher.cookies().map_err(Into::into)
+  }
+
+  /// Gets the cookie with the given name.
+  pub fn get(&self, name: &str) -> crate::Result<Option<Cookie>> {
+    self
+      .list()
+      .map(|cookies| cookies.into_iter().find(|cookie| cookie.name == name))
+  }
+
+  /// Sets a cookie, overwriting any existing cookie with the same name, domain and path.
+  ///
+  /// The name and value must be valid cookie tokens, they are not encoded.
+  pub fn set(&self, cookie: Cookie) -> crate::Result<()> {
+    self.dispatcher.set_cookie(cookie).map_err(Into::into)
+  }
+
+  /// Deletes the cookie with the given name and path. The path defaults to `/`.
+  pub fn delete(&self, name: &str, path: Option<&str>) -> crate::Result<()> {
+    self
+      .dispatcher
+      .delete_cookie(name, path)
+      .map_err(Into::into)
+  }
+}
@@ -53,4 +53,47 @@ async function getTauriVersion(): Promise<string> {
   })
 }
 
-export { getName, getVersion, getTauriVersion }
+interface WindowDefaults {
+  /** The window label. */
+  label: string
+  /** The window title. */
+  title: string
+  /** The initial window width. */
+  width: number
+  /** The initial window height. */
+  height: number
+  /** Whether the window is resizable. */
+  resizable: boolean
+  /** Whether the window starts as fullscreen. */
+  fullscreen: boolean
+}
+
+interface AppConfig {
+  /** The `package > productName` value. */
+  productName: string | null
+  /** The `package > version` value. */
+  version: string | null
+  /** The `tauri > bundle > identifier` value. */
+  identifier: string
+  /** The windows defined on `tauri > windows`. */
+  windows: WindowDefaults[]
+}
+
+/**
+ * Gets the application config values exposed to the frontend.
+ * Requires the `tauri > allowlist > app > all` flag.
+ *
+ * @returns A promise resolving to the application config values.
+ */
+async function getConfig(): Promise<AppConfig> {
+  return invokeTauriCommand<AppConfig>({
+    __tauriModule: 'App',
+    message: {
+      cmd: 'getAppConfig'
+    }
+  })
+}
+
+export type { AppConfig, WindowDefaults }
+
+export { getName, getVersion, getTauriVersion, getConfig }
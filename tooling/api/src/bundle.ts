@@ -16,6 +16,7 @@ import * as notification from './notification'
 import * as path from './path'
 import * as process from './process'
 import * as shell from './shell'
+import * as system from './system'
 import * as tauri from './tauri'
 import * as updater from './updater'
 import * as window from './window'
@@ -32,6 +33,7 @@ export {
   path,
   process,
   shell,
+  system,
   tauri,
   updater,
   window
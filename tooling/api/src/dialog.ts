@@ -15,7 +15,9 @@
  *       "dialog": {
  *         "all": true, // enable all dialog APIs
  *         "open": true, // enable file open API
- *         "save": true // enable file save API
+ *         "save": true, // enable file save API
+ *         "message": true, // enable message dialog API
+ *         "ask": true // enable ask dialog API
  *       }
  *     }
  *   }
@@ -47,7 +49,10 @@ interface OpenDialogOptions {
   filters?: DialogFilter[]
   /** Initial directory or file path. It must exist. */
   defaultPath?: string
-  /** Whether the dialog allows multiple selection or not. */
+  /**
+   * Whether the dialog allows multiple selection or not.
+   * Multiple selection is not supported on directory selection dialogs.
+   */
   multiple?: boolean
   /** Whether the dialog is a directory selection or not. */
   directory?: boolean
@@ -59,6 +64,8 @@ interface SaveDialogOptions {
   filters?: DialogFilter[]
   /** Initial directory or file path. It must exist. */
   defaultPath?: string
+  /** Initial file name of the dialog. Overrides the file name of `defaultPath`. */
+  fileName?: string
 }
 
 /**
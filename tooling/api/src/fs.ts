@@ -23,7 +23,9 @@
  *         "createDir": true,
  *         "removeDir": true,
  *         "removeFile": true,
- *         "renameFile": true
+ *         "renameFile": true,
+ *         "watch": true,
+ *         "trash": true
  *       }
  *     }
  *   }
@@ -34,6 +36,7 @@
  */
 
 import { invokeTauriCommand } from './helpers/tauri'
+import { transformCallback } from './tauri'
 
 export enum BaseDirectory {
   Audio = 1,
@@ -76,6 +79,20 @@ interface FsBinaryFileOption {
   contents: ArrayBuffer
 }
 
+interface FsWatchOptions {
+  dir?: BaseDirectory
+  /** Whether the directories should be watched recursively or not. */
+  recursive?: boolean
+  /** The delay in milliseconds used to debounce the events. Defaults to 500. */
+  delayMs?: number
+}
+
+type WatchEvent =
+  | { type: 'create' | 'write' | 'chmod' | 'remove'; payload: string }
+  | { type: 'rename'; payload: { from: string; to: string } }
+  | { type: 'rescan' }
+  | { type: 'error'; payload: { path?: string; error: string } }
+
 interface FileEntry {
   path: string
   /**
@@ -358,9 +375,110 @@ async function renameFile(
   })
 }
 
+/**
+ * Watches the given paths for changes.
+ *
+ * @param paths The paths to watch.
+ * @param cb The callback invoked with the debounced change events.
+ * @param options Configuration object.
+ * @returns A promise resolving to a function that stops watching the paths.
+ */
+async function watch(
+  paths: string | string[],
+  cb: (event: WatchEvent) => void,
+  options: FsWatchOptions = {}
+): Promise<() => Promise<void>> {
+  const id = await invokeTauriCommand<number>({
+    __tauriModule: 'Fs',
+    message: {
+      cmd: 'watch',
+      paths: typeof paths === 'string' ? [paths] : paths,
+      options,
+      onEventFn: transformCallback(cb)
+    }
+  })
+
+  return async () => {
+    return invokeTauriCommand({
+      __tauriModule: 'Fs',
+      message: {
+        cmd: 'unwatch',
+        id
+      }
+    })
+  }
+}
+
+/**
+ * A file or directory on the trash.
+ */
+interface TrashedItem {
+  /** The platform identifier of the item, used to restore it. */
+  id: string
+  /** The item name. */
+  name: string
+  /** The path of the directory the item was deleted from. */
+  originalParent: string
+  /** The deletion time, as seconds since the UNIX epoch. */
+  timeDeleted: number
+}
+
+/**
+ * Moves a file or directory to the trash (or recycle bin) instead of deleting it permanently.
+ *
+ * @param path Path to the file or directory to move.
+ * @param options Configuration object.
+ * @returns A promise indicating the success or failure of the operation.
+ */
+async function trash(path: string, options: FsOptions = {}): Promise<void> {
+  return invokeTauriCommand({
+    __tauriModule: 'Fs',
+    message: {
+      cmd: 'trash',
+      path,
+      options
+    }
+  })
+}
+
+/**
+ * Lists the items on the trash.
+ * Not supported on macOS.
+ *
+ * @returns A promise resolving to the trashed items.
+ */
+async function listTrash(): Promise<TrashedItem[]> {
+  return invokeTauriCommand<TrashedItem[]>({
+    __tauriModule: 'Fs',
+    message: {
+      cmd: 'listTrash'
+    }
+  })
+}
+
+/**
+ * Restores the trash items with the given identifiers to their original location.
+ * Not supported on macOS.
+ *
+ * @param ids The identifiers of the items to restore, from {@link listTrash}.
+ * @returns A promise indicating the success or failure of the operation.
+ */
+async function untrash(ids: string[]): Promise<void> {
+  return invokeTauriCommand({
+    __tauriModule: 'Fs',
+    message: {
+      cmd: 'untrash',
+      ids
+    }
+  })
+}
+
 export type {
   FsOptions,
   FsDirOptions,
+  FsWatchOptions,
+  WatchEvent,
+  TrashedItem,
   FsTextFileOption,
   FsBinaryFileOption,
   FileEntry
@@ -377,5 +495,9 @@ export {
   removeDir,
   copyFile,
   removeFile,
-  renameFile
+  renameFile,
+  watch,
+  trash,
+  listTrash,
+  untrash
 }
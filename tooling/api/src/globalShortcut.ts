@@ -7,6 +7,8 @@
  *
  * This package is also accessible with `window.__TAURI__.globalShortcut` when `tauri.conf.json > build > withGlobalTauri` is set to true.
  *
+ * The media keys (play/pause, next, previous, volume) can't be registered.
+ *
  * The APIs must be allowlisted on `tauri.conf.json`:
  * ```json
  * {
@@ -25,6 +25,15 @@
  */
 
 import { invokeTauriCommand } from './helpers/tauri'
+import { transformCallback } from './tauri'
+import { BaseDirectory } from './fs'
+
+interface DownloadProgress {
+  /** The number of bytes downloaded, including the bytes of a resumed download. */
+  downloaded: number
+  /** The total size of the file, if known. */
+  total?: number
+}
 
 interface ClientOptions {
   maxRedirections: number
@@ -39,6 +48,9 @@ enum ResponseType {
 
 type Part = 'string' | number[]
 
+/** A part of a multipart body. The byte arrays are sent as `application/octet-stream` files. */
+type MultipartPart = string | number[]
+
 /** The body object to be used on POST and PUT requests. */
 class Body {
   type: string
@@ -61,6 +73,25 @@ class Body {
     return new Body('Form', data)
   }
 
+  /**
+   * Creates a new `multipart/form-data` body.
+   *
+   * @param data The body data.
+   *
+   * @return The body object ready to be used on the POST and PUT requests.
+   */
+  static multipart(data: Record<string, MultipartPart>): Body {
+    return new Body('Multipart', {
+      parts: Object.entries(data).map(([name, part]) => {
+        if (typeof part === 'string') {
+          return [name, { type: 'Text', payload: part }]
+        } else {
+          return [name, { type: 'Bytes', payload: part }]
+        }
+      })
+    })
+  }
+
   /**
    * Creates a new JSON body.
    *
@@ -174,6 +205,41 @@ class Client {
     })
   }
 
+  /**
+   * Downloads a file, streaming it to disk.
+   * If a previous download to the same path was interrupted, it is resumed.
+   *
+   * @param url The request URL.
+   * @param path The path of the downloaded file, relative to the `dir` directory.
+   * @param onProgress The callback invoked with the download progress.
+   * @param options The request options.
+   * @param dir The base directory of the file, one of `Download`, `App` or `Cache`. Defaults to `Download`.
+   * @returns A promise resolving when the download is finished.
+   */
+  async download(
+    url: string,
+    path: string,
+    onProgress?: (progress: DownloadProgress) => void,
+    options?: RequestOptions,
+    dir?: BaseDirectory
+  ): Promise<void> {
+    return invokeTauriCommand({
+      __tauriModule: 'Http',
+      message: {
+        cmd: 'download',
+        client: this.id,
+        options: {
+          method: 'GET',
+          url,
+          ...options
+        },
+        path,
+        dir,
+        onProgressFn: onProgress ? transformCallback(onProgress) : undefined
+      }
+    })
+  }
+
   /**
    * Makes a GET request.
    *
@@ -307,11 +373,13 @@ export type {
   ClientOptions,
   ResponseType,
   Part,
+  MultipartPart,
   HttpVerb,
   HttpOptions,
   RequestOptions,
   FetchOptions,
-  Response
+  Response,
+  DownloadProgress
 }
 
 export { getClient, fetch, Body, Client }
@@ -24,6 +24,17 @@
  */
 
 import { invokeTauriCommand } from './helpers/tauri'
+import { listen, UnlistenFn } from './event'
+
+/**
+ * An action button of a notification.
+ */
+interface Action {
+  /** Action identifier, sent back on the {@link onAction} events. */
+  action: string
+  /** Action button label. */
+  title: string
+}
 
 /**
  * Options to send a notification.
@@ -35,8 +46,47 @@ interface Options {
   body?: string
   /** Optional notification icon. */
   icon?: string
+  /**
+   * Optional path of an image attached to the notification.
+   * Only supported on Linux.
+   */
+  image?: string
+  /**
+   * Optional name of the sound played when the notification is shown.
+   * On Linux this is a name of the freedesktop sound naming specification, and on macOS the name of a system sound.
+   */
+  sound?: string
+  /**
+   * Optional notification priority. Defaults to `normal`.
+   * Only supported on Linux, where it is sent as the `urgency` hint.
+   */
+  priority?: Priority
+  /** Optional notification tag, sent back on the {@link onAction} events. */
+  tag?: string
+  /**
+   * Optional notification action buttons.
+   * Only supported on Linux.
+   */
+  actions?: Action[]
 }
 
+/** An interaction of the user with a notification. */
+type NotificationEvent =
+  | { type: 'clicked' }
+  | { type: 'action'; payload: string }
+  | { type: 'closed' }
+
+/** The payload of the notification action events. */
+interface ActionEvent {
+  /** The tag of the notification. */
+  tag: string | null
+  /** The user interaction. */
+  event: NotificationEvent
+}
+
+/** The priority of a notification. */
+type Priority = 'low' | 'normal' | 'high'
+
 /** Possible permission values. */
 type Permission = 'granted' | 'denied' | 'default'
 
@@ -81,6 +131,38 @@ function sendNotification(options: Options | string): void {
   }
 }
 
-export type { Options, Permission }
+/**
+ * Listens to the user interactions with the notifications, such as clicks on the notification or its actions.
+ * Only supported on Linux.
+ * Use the notification `tag` to find out which view should be opened.
+ *
+ * @example
+ * ```typescript
+ * import { sendNotification, onAction } from '@tauri-apps/api/notification'
+ * sendNotification({ title: 'New message', tag: 'chat-1', actions: [{ action: 'reply', title: 'Reply' }] })
+ * const unlisten = await onAction(({ tag, event }) => {
+ *   if (event.type === 'clicked') router.push(`/chat/${tag}`)
+ * })
+ * ```
+ *
+ * @param handler The handler called with the notification tag and the user interaction.
+ * @returns A promise resolving to a function to stop listening.
+ */
+async function onAction(
+  handler: (event: ActionEvent) => void
+): Promise<UnlistenFn> {
+  return listen<ActionEvent>('tauri://notification-action', (event) =>
+    handler(event.payload)
+  )
+}
+
+export type {
+  Action,
+  ActionEvent,
+  NotificationEvent,
+  Options,
+  Permission,
+  Priority
+}
 
-export { sendNotification, requestPermission, isPermissionGranted }
+export { sendNotification, requestPermission, isPermissionGranted, onAction }
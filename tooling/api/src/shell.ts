@@ -34,6 +34,19 @@ interface SpawnOptions {
   cwd?: string
   /** Environment variables. set to `null` to clear the process env. */
   env?: { [name: string]: string }
+  /**
+   * Spawns the process on a pseudo terminal with the given size, for programs that require a TTY.
+   * The terminal output is emitted as byte arrays on the `stdout` `data` event.
+   * Requires the `shell-pty` Cargo feature.
+   */
+  pty?: PtySize
+}
+
+interface PtySize {
+  /** The number of rows. */
+  rows: number
+  /** The number of columns. */
+  cols: number
 }
 
 /** @ignore */
@@ -161,6 +174,24 @@ class Child {
     })
   }
 
+  /**
+   * Resizes the pseudo terminal of a process spawned with the `pty` option.
+   *
+   * @param size The new terminal size.
+   *
+   * @return A promise indicating the success or failure of the operation.
+   */
+  async resize(size: PtySize): Promise<void> {
+    return invokeTauriCommand({
+      __tauriModule: 'Shell',
+      message: {
+        cmd: 'resizeChild',
+        pid: this.pid,
+        size
+      }
+    })
+  }
+
   /**
    * Kills the child process.
    *
@@ -175,6 +206,25 @@ class Child {
       }
     })
   }
+
+  /**
+   * Asks the child process to terminate, killing it if it's still running after the timeout.
+   * On Windows the process is killed immediately.
+   *
+   * @param timeoutMs The time in milliseconds to wait for the process to exit before killing it.
+   *
+   * @return A promise indicating the success or failure of the operation.
+   */
+  async terminate(timeoutMs = 5000): Promise<void> {
+    return invokeTauriCommand({
+      __tauriModule: 'Shell',
+      message: {
+        cmd: 'terminateChild',
+        pid: this.pid,
+        timeoutMs
+      }
+    })
+  }
 }
 
 /**
@@ -263,6 +313,7 @@ class Command extends EventEmitter<'close' | 'error'> {
             this._emit('close', event.payload)
             break
           case 'Stdout':
+          case 'Output':
             this.stdout._emit('data', event.payload)
             break
           case 'Stderr':
@@ -337,6 +388,7 @@ type CommandEvent =
   | Event<'Stderr', string>
   | Event<'Terminated', TerminatedPayload>
   | Event<'Error', string>
+  | Event<'Output', number[]>
 
 /**
  * Opens a path or URL with the system's default app,
@@ -367,4 +419,4 @@ async function open(path: string, openWith?: string): Promise<void> {
 }
 
 export { Command, Child, open }
-export type { ChildProcess, SpawnOptions }
+export type { ChildProcess, SpawnOptions, PtySize }
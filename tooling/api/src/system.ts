@@ -0,0 +1,72 @@
+// Copyright 2019-2021 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+/**
+ * Read information about the operating system and the machine.
+ *
+ * This package is also accessible with `window.__TAURI__.system` when `tauri.conf.json > build > withGlobalTauri` is set to true.
+ *
+ * The APIs must be allowlisted on `tauri.conf.json`:
+ * ```json
+ * {
+ *   "tauri": {
+ *     "allowlist": {
+ *       "system": {
+ *         "all": true // enable the system information API
+ *       }
+ *     }
+ *   }
+ * }
+ * ```
+ * @packageDocumentation
+ */
+
+import { invokeTauriCommand } from './helpers/tauri'
+
+/** Information about the operating system and the machine running the application. */
+interface SystemInfo {
+  /** The operating system name, e.g. `Ubuntu` or `Windows`. */
+  osName: string | null
+  /** The operating system version. */
+  osVersion: string | null
+  /** The kernel version. */
+  kernelVersion: string | null
+  /** The machine host name. */
+  hostname: string | null
+  /** The total memory in bytes. */
+  totalMemory: number
+  /** The available memory in bytes. */
+  availableMemory: number
+  /** The CPU model name. */
+  cpuModel: string | null
+  /** The number of logical CPUs. */
+  cpuCount: number
+  /** The number of physical CPU cores, if it could be determined. */
+  physicalCoreCount: number | null
+  /** The system uptime in seconds. */
+  uptime: number
+}
+
+/**
+ * Reads the system information.
+ * @example
+ * ```typescript
+ * import { getInfo } from '@tauri-apps/api/system'
+ * const { osName, osVersion, totalMemory } = await getInfo()
+ * ```
+ *
+ * @returns A promise resolving to the system information.
+ */
+async function getInfo(): Promise<SystemInfo> {
+  return invokeTauriCommand<SystemInfo>({
+    __tauriModule: 'System',
+    message: {
+      cmd: 'getInfo'
+    }
+  })
+}
+
+export type { SystemInfo }
+
+export { getInfo }
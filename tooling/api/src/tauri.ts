@@ -16,6 +16,13 @@ declare global {
     rpc: {
       notify: (command: string, args?: { [key: string]: unknown }) => void
     }
+    __TAURI__: {
+      __resolveBinary: (value: unknown) => Promise<unknown>
+      __sendIpcMessage: (
+        command: string,
+        args?: { [key: string]: unknown }
+      ) => void
+    }
   }
 }
 
@@ -55,11 +62,50 @@ function transformCallback(
   return identifier
 }
 
+/**
+ * A channel that receives the values streamed by a command.
+ * Pass it as a command argument and set the `onmessage` handler.
+ *
+ * @example
+ * ```typescript
+ * import { invoke, Channel } from '@tauri-apps/api/tauri'
+ * const onProgress = new Channel<number>()
+ * onProgress.onmessage = (indexed) => console.log(indexed)
+ * await invoke('index_files', { onProgress })
+ * ```
+ */
+class Channel<T = unknown> {
+  /** The identifier of the channel callback. */
+  id: string
+  /** The handler called for every value sent by the backend. */
+  onmessage: (response: T) => void = () => {}
+
+  constructor() {
+    this.id = transformCallback((response) => {
+      void window.__TAURI__
+        .__resolveBinary(response)
+        .then((value) => this.onmessage(value as T))
+    })
+  }
+
+  toJSON(): string {
+    return `__CHANNEL__:${this.id}`
+  }
+}
+
 /** Command arguments. */
 interface InvokeArgs {
   [key: string]: unknown
 }
 
+/** The promise returned by {@link invoke}. */
+interface InvokePromise<T> extends Promise<T> {
+  /**
+   * Rejects the promise and cancels the `CancellationToken` of the command.
+   */
+  abort: () => void
+}
+
 /**
  * Sends a message to the backend.
  *
@@ -67,10 +113,14 @@ interface InvokeArgs {
  * @param args The optional arguments to pass to the command.
  * @return A promise resolving or rejecting to the backend response.
  */
-async function invoke<T>(cmd: string, args: InvokeArgs = {}): Promise<T> {
-  return new Promise((resolve, reject) => {
+function invoke<T>(cmd: string, args: InvokeArgs = {}): InvokePromise<T> {
+  let abort = (): void => {}
+  const promise = new Promise<T>((resolve, reject) => {
     const callback = transformCallback((e) => {
-      resolve(e)
+      window.__TAURI__.__resolveBinary(e).then(
+        (value) => resolve(value as T),
+        reject
+      )
       Reflect.deleteProperty(window, error)
     }, true)
     const error = transformCallback((e) => {
@@ -78,14 +128,31 @@ async function invoke<T>(cmd: string, args: InvokeArgs = {}): Promise<T> {
       Reflect.deleteProperty(window, callback)
     }, true)
 
-    window.rpc.notify(cmd, {
+    abort = () => {
+      Reflect.deleteProperty(window, callback)
+      Reflect.deleteProperty(window, error)
+      window.__TAURI__.__sendIpcMessage('tauri', {
+        __tauriModule: 'Internal',
+        callback: transformCallback(undefined, true),
+        error: transformCallback(undefined, true),
+        message: {
+          cmd: 'abortInvoke',
+          id: callback
+        }
+      })
+      reject(new Error('aborted'))
+    }
+
+    window.__TAURI__.__sendIpcMessage(cmd, {
       callback,
       error,
       ...args
     })
-  })
+  }) as InvokePromise<T>
+  promise.abort = () => abort()
+  return promise
 }
 
-export type { InvokeArgs }
+export type { InvokeArgs, InvokePromise }
 
-export { transformCallback, invoke }
+export { transformCallback, invoke, Channel }
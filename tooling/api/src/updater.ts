@@ -11,7 +11,13 @@
 
 import { once, listen, emit, UnlistenFn } from './event'
 
-type UpdateStatus = 'PENDING' | 'ERROR' | 'DONE' | 'UPTODATE'
+type UpdateStatus =
+  | 'PENDING'
+  | 'DOWNLOADED'
+  | 'INSTALLING'
+  | 'ERROR'
+  | 'DONE'
+  | 'UPTODATE'
 
 interface UpdateStatusResult {
   error?: string
@@ -24,13 +30,72 @@ interface UpdateManifest {
   body: string
 }
 
+interface DownloadProgress {
+  /** Number of bytes downloaded. */
+  received: number
+  /** Total number of bytes of the download, or null if unknown. */
+  total: number | null
+}
+
 interface UpdateResult {
   manifest?: UpdateManifest
   shouldUpdate: boolean
 }
 
+/**
+ * Downloads the update found by {@link checkUpdate} without installing it.
+ * The update is cached on disk, and installed by {@link installUpdate} or on the next launches.
+ *
+ * @return A promise indicating the success or failure of the download.
+ */
+async function downloadUpdate(): Promise<void> {
+  let unlistenerFn: UnlistenFn | undefined
+
+  function cleanListener(): void {
+    if (unlistenerFn) {
+      unlistenerFn()
+    }
+    unlistenerFn = undefined
+  }
+
+  return new Promise((resolve, reject) => {
+    function onStatusChange(statusResult: UpdateStatusResult): void {
+      if (statusResult.error) {
+        cleanListener()
+        return reject(statusResult.error)
+      }
+
+      // download complete
+      if (statusResult.status === 'DOWNLOADED') {
+        cleanListener()
+        return resolve()
+      }
+    }
+
+    // listen status change
+    listen('tauri://update-status', (data: { payload: any }) => {
+      onStatusChange(data?.payload as UpdateStatusResult)
+    })
+      .then((fn) => {
+        unlistenerFn = fn
+      })
+      .catch((e) => {
+        cleanListener()
+        // dispatch the error to our downloadUpdate
+        throw e
+      })
+
+    emit('tauri://update-download').catch((e) => {
+      cleanListener()
+      // dispatch the error to our downloadUpdate
+      throw e
+    })
+  })
+}
+
 /**
  * Install the update if there's one available.
+ * The update downloaded by {@link downloadUpdate}, on this launch or a previous one, is installed without being downloaded again.
  *
  * @return A promise indicating the success or failure of the operation.
  */
@@ -150,6 +215,26 @@ async function checkUpdate(): Promise<UpdateResult> {
   })
 }
 
-export type { UpdateStatus, UpdateStatusResult, UpdateManifest, UpdateResult }
+/**
+ * Listens to the download progress of the update installed with {@link installUpdate}.
+ *
+ * @param handler The handler called with the download progress.
+ * @return Promise resolving to a function to stop listening.
+ */
+async function onDownloadProgress(
+  handler: (progress: DownloadProgress) => void
+): Promise<UnlistenFn> {
+  return listen('tauri://update-download-progress', (data: { payload: any }) => {
+    handler(data.payload as DownloadProgress)
+  })
+}
+
+export type {
+  DownloadProgress,
+  UpdateStatus,
+  UpdateStatusResult,
+  UpdateManifest,
+  UpdateResult
+}
 
-export { installUpdate, checkUpdate }
+export { installUpdate, downloadUpdate, checkUpdate, onDownloadProgress }
@@ -40,6 +40,18 @@ interface Monitor {
   scaleFactor: number
 }
 
+/** The types of browsing data to clear. */
+interface DataTypes {
+  /** Whether the cookies should be cleared or not. */
+  cookies?: boolean
+  /** Whether the cache should be cleared or not. */
+  cache?: boolean
+  /** Whether the local and session storage should be cleared or not. */
+  localStorage?: boolean
+  /** Whether the IndexedDB databases should be cleared or not. */
+  indexedDb?: boolean
+}
+
 /** A size represented in logical pixels. */
 class LogicalSize {
   type = 'Logical'
@@ -392,6 +404,16 @@ class WindowManager {
     })
   }
 
+  /** Gets the webview's current zoom factor, where `1` is the default size. Unsupported on Windows. */
+  async zoom(): Promise<number> {
+    return invokeTauriCommand({
+      __tauriModule: 'Window',
+      message: {
+        cmd: 'zoom'
+      }
+    })
+  }
+
   // Setters
 
   /**
@@ -789,6 +811,50 @@ class WindowManager {
       }
     })
   }
+
+  /**
+   * Sets the webview zoom factor. Unsupported on Windows.
+   *
+   * @param scaleFactor The new zoom factor, where `1` is the default size.
+   * @returns A promise indicating the success or failure of the operation.
+   */
+  async setZoom(scaleFactor: number): Promise<void> {
+    return invokeTauriCommand({
+      __tauriModule: 'Window',
+      message: {
+        cmd: 'setZoom',
+        data: scaleFactor
+      }
+    })
+  }
+
+  /**
+   * Clears the specified browsing data of the webview, for all the websites. Unsupported on Windows.
+   * @example
+   * ```typescript
+   * import { appWindow } from '@tauri-apps/api/window'
+   * await appWindow.clearBrowsingData({ cookies: true, localStorage: true })
+   * ```
+   *
+   * @param dataTypes The types of data to clear. Defaults to all types.
+   * @returns A promise indicating the success or failure of the operation.
+   */
+  async clearBrowsingData(
+    dataTypes: DataTypes = {
+      cookies: true,
+      cache: true,
+      localStorage: true,
+      indexedDb: true
+    }
+  ): Promise<void> {
+    return invokeTauriCommand({
+      __tauriModule: 'Window',
+      message: {
+        cmd: 'clearBrowsingData',
+        data: dataTypes
+      }
+    })
+  }
 }
 
 /** The manager for the current window. Allows you to manipulate the window object. */
@@ -838,6 +904,14 @@ interface WindowOptions {
   alwaysOnTop?: boolean
   /** Whether or not the window icon should be added to the taskbar. */
   skipTaskbar?: boolean
+  /**
+   * Whether the file drop is enabled or not on the webview. By default it is enabled.
+   *
+   * Disabling it is required to use drag and drop on the frontend on Windows.
+   */
+  fileDropEnabled?: boolean
+  /** Whether the Ctrl+scroll and Ctrl+=/Ctrl+-/Ctrl+0 hotkeys can be used to zoom the webview. WebView2 always enables them on Windows. */
+  zoomHotkeysEnabled?: boolean
 }
 
 /**
@@ -892,4 +966,4 @@ export {
   availableMonitors
 }
 
-export type { Monitor, WindowOptions }
+export type { DataTypes, Monitor, WindowOptions }
@@ -18,11 +18,16 @@ mod windows;
 pub use self::{
   category::AppCategory,
   settings::{
-    BundleBinary, BundleSettings, DebianSettings, MacOsSettings, PackageSettings, PackageType,
-    Settings, SettingsBuilder, UpdaterSettings,
+    AppImageSettings, BundleBinary, BundleResource, BundleSettings, BundleTypeRole, DebianSettings,
+    DesktopEntrySettings, ExternalBin, FileAssociation, FlatpakSettings, MacOsSettings,
+    PackageSettings, PackageType, Settings, SettingsBuilder, SnapSettings, UpdaterSettings,
   },
 };
-pub use settings::{WindowsSettings, WixSettings};
+pub use platform::target_os;
+pub use settings::{
+  NsisSettings, PortableSettings, WindowsSettings, WixFileAssociation, WixInstallScope,
+  WixRegistryKey, WixSettings,
+};
 
 use common::{print_finished, print_info};
 
@@ -50,12 +55,20 @@ pub fn bundle_project(settings: Settings) -> crate::Result<Vec<Bundle>> {
       PackageType::IosBundle => macos::ios::bundle_project(&settings)?,
       #[cfg(target_os = "windows")]
       PackageType::WindowsMsi => windows::msi::bundle_project(&settings)?,
+      #[cfg(target_os = "windows")]
+      PackageType::Nsis => windows::nsis::bundle_project(&settings)?,
+      #[cfg(target_os = "windows")]
+      PackageType::Portable => windows::portable::bundle_project(&settings)?,
       #[cfg(target_os = "linux")]
       PackageType::Deb => linux::debian::bundle_project(&settings)?,
       #[cfg(target_os = "linux")]
       PackageType::Rpm => linux::rpm::bundle_project(&settings)?,
       #[cfg(target_os = "linux")]
       PackageType::AppImage => linux::appimage::bundle_project(&settings)?,
+      #[cfg(target_os = "linux")]
+      PackageType::Flatpak => linux::flatpak::bundle_project(&settings)?,
+      #[cfg(target_os = "linux")]
+      PackageType::Snap => linux::snap::bundle_project(&settings)?,
       // dmg is dependant of MacOsBundle, we send our bundles to prevent rebuilding
       #[cfg(target_os = "macos")]
       PackageType::Dmg => macos::dmg::bundle_project(&settings, &bundles)?,
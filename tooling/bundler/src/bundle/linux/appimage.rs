@@ -8,15 +8,40 @@ use super::{
 };
 use crate::Settings;
 
+use anyhow::Context;
 use handlebars::Handlebars;
 
 use std::{
   collections::BTreeMap,
   fs::{remove_dir_all, write},
-  path::PathBuf,
+  path::{Path, PathBuf},
   process::{Command, Stdio},
 };
 
+/// The prefixes of the libraries expected on every system, never bundled with the dependencies:
+/// glibc, the dynamic linker and the graphics stack tied to the drivers.
+const SYSTEM_LIBRARIES: &[&str] = &[
+  "ld-linux",
+  "libc.so",
+  "libdl.so",
+  "libm.so",
+  "libpthread.so",
+  "libresolv.so",
+  "librt.so",
+  "libutil.so",
+  "libGL.so",
+  "libGLX",
+  "libGLdispatch",
+  "libEGL",
+  "libdrm",
+  "libgbm",
+  "libX11.so",
+  "libX11-xcb",
+  "libxcb.so",
+  "libfontconfig",
+  "libfreetype",
+];
+
 /// Bundles the project.
 /// Returns a vector of PathBuf that shows where the AppImage was created.
 pub fn bundle_project(settings: &Settings) -> crate::Result<Vec<PathBuf>> {
@@ -37,8 +62,10 @@ pub fn bundle_project(settings: &Settings) -> crate::Result<Vec<PathBuf>> {
   let package_dir = settings.project_out_directory().join("bundle/appimage_deb");
 
   // generate deb_folder structure
-  let (_, icons) = debian::generate_data(settings, &package_dir)?;
+  let (data_dir, icons) = debian::generate_data(settings, &package_dir)?;
   let icons: Vec<debian::DebIcon> = icons.into_iter().collect();
+  copy_libraries(settings, &data_dir.join("usr/lib"))
+    .with_context(|| "Failed to copy the shared libraries")?;
 
   let output_path = settings.project_out_directory().join("bundle/appimage");
   if output_path.exists() {
@@ -74,6 +101,21 @@ pub fn bundle_project(settings: &Settings) -> crate::Result<Vec<PathBuf>> {
     .to_string_lossy()
     .to_string();
   sh_map.insert("icon_path", &larger_icon_path);
+  let app_run = match &settings.appimage().app_run {
+    Some(app_run) => app_run
+      .canonicalize()
+      .with_context(|| format!("Failed to find the AppRun script {:?}", app_run))?
+      .to_string_lossy()
+      .to_string(),
+    None => String::new(),
+  };
+  sh_map.insert("app_run", &app_run);
+  let update_information = settings
+    .appimage()
+    .update_information
+    .clone()
+    .unwrap_or_default();
+  sh_map.insert("update_information", &update_information);
 
   // initialize shell script template.
   let mut handlebars = Handlebars::new();
@@ -113,5 +155,106 @@ pub fn bundle_project(settings: &Settings) -> crate::Result<Vec<PathBuf>> {
   })?;
 
   remove_dir_all(&package_dir)?;
-  Ok(vec![appimage_path])
+  let mut paths = vec![appimage_path.clone()];
+  // appimagetool generates the zsync file when the update information is set
+  let zsync_path = appimage_path.with_extension("AppImage.zsync");
+  if zsync_path.exists() {
+    paths.push(zsync_path);
+  }
+  Ok(paths)
+}
+
+/// Copies the shared libraries to bundle to the `lib_dir`.
+fn copy_libraries(settings: &Settings, lib_dir: &Path) -> crate::Result<()> {
+  let appimage = settings.appimage();
+  let mut libraries = Vec::new();
+  if appimage.bundle_dependencies {
+    for bin in settings.binaries() {
+      for library in linked_libraries(&settings.binary_path(bin))? {
+        let file_name = library
+          .file_name()
+          .map(|name| name.to_string_lossy().to_string())
+          .unwrap_or_default();
+        let excluded = SYSTEM_LIBRARIES
+          .iter()
+          .map(|prefix| prefix.to_string())
+          .chain(appimage.exclude_libraries.iter().cloned())
+          .any(|prefix| file_name.starts_with(&prefix));
+        if !excluded {
+          libraries.push(library);
+        }
+      }
+    }
+  }
+  for library in &appimage.libraries {
+    libraries.push(resolve_library(library)?);
+  }
+
+  for library in libraries {
+    let file_name = library.file_name().expect("invalid library path");
+    let dest_path = lib_dir.join(file_name);
+    if !dest_path.exists() {
+      // the library symlinks are resolved, copying the actual file
+      common::copy_file(library.canonicalize()?, &dest_path)
+        .with_context(|| format!("Failed to copy library {:?}", library))?;
+    }
+  }
+  Ok(())
+}
+
+// Lists the libraries the binary links to, reading the `ldd` output, e.g.
+//   libgtk-3.so.0 => /lib/x86_64-linux-gnu/libgtk-3.so.0 (0x00007f...)
+fn linked_libraries(binary: &Path) -> crate::Result<Vec<PathBuf>> {
+  let output = Command::new("ldd")
+    .arg(binary)
+    .output()
+    .with_context(|| "Failed to run ldd")?;
+  if !output.status.success() {
+    return Err(crate::Error::GenericError(format!(
+      "Failed to list the libraries of {:?}",
+      binary
+    )));
+  }
+  Ok(
+    String::from_utf8_lossy(&output.stdout)
+      .lines()
+      .filter_map(|line| {
+        let path = line.split("=>").nth(1)?.trim().split(" (").next()?.trim();
+        if path.starts_with('/') {
+          Some(PathBuf::from(path))
+        } else {
+          None
+        }
+      })
+      .collect(),
+  )
+}
+
+// Finds the library path, reading the `ldconfig -p` output for library names, e.g.
+//   libwebkit2gtk-4.0.so.37 (libc6,x86-64) => /lib/x86_64-linux-gnu/libwebkit2gtk-4.0.so.37
+fn resolve_library(library: &str) -> crate::Result<PathBuf> {
+  if library.contains('/') {
+    return Ok(PathBuf::from(library));
+  }
+  let output = Command::new("ldconfig")
+    .arg("-p")
+    .output()
+    .with_context(|| "Failed to run ldconfig")?;
+  String::from_utf8_lossy(&output.stdout)
+    .lines()
+    .find_map(|line| {
+      let mut parts = line.trim().splitn(2, " => ");
+      let name = parts.next()?.split(' ').next()?;
+      if name == library {
+        parts.next().map(|path| PathBuf::from(path.trim()))
+      } else {
+        None
+      }
+    })
+    .ok_or_else(|| {
+      crate::Error::GenericError(format!(
+        "Failed to find the {} library, install it or use its path",
+        library
+      ))
+    })
 }
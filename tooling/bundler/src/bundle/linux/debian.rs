@@ -19,8 +19,8 @@
 //
 // For cargo-bundle, we put bundle resource files under /usr/lib/package_name/,
 // and then generate the desktop file and control file from the bundle
-// metadata, as well as generating the md5sums file.  Currently we do not
-// generate postinst or prerm files.
+// metadata, as well as generating the md5sums file. The maintainer scripts
+// (preinst, postinst, prerm and postrm) are copied from the deb settings.
 
 use super::super::common;
 use crate::Settings;
@@ -36,6 +36,7 @@ use std::{
   ffi::OsStr,
   fs::{self, File},
   io::{self, Write},
+  os::unix::fs::PermissionsExt,
   path::{Path, PathBuf},
 };
 
@@ -82,6 +83,9 @@ pub fn bundle_project(settings: &Settings) -> crate::Result<Vec<PathBuf>> {
   generate_control_file(settings, arch, &control_dir, &data_dir)
     .with_context(|| "Failed to create control file")?;
   generate_md5sums(&control_dir, &data_dir).with_context(|| "Failed to create md5sums file")?;
+  generate_conffiles(&control_dir, &data_dir).with_context(|| "Failed to create conffiles file")?;
+  copy_maintainer_scripts(settings, &control_dir)
+    .with_context(|| "Failed to copy maintainer scripts")?;
 
   // Generate `debian-binary` file; see
   // http://www.tldp.org/HOWTO/Debian-Binary-Package-Building-HOWTO/x60.html#AEN66
@@ -212,7 +216,10 @@ fn generate_desktop_file(settings: &Settings, data_dir: &Path) -> crate::Result<
   // For more information about the format of this file, see
   // https://developer.gnome.org/integration-guide/stable/desktop-files.html.en
   writeln!(file, "[Desktop Entry]")?;
-  if let Some(category) = settings.app_category() {
+  let desktop_entry = &settings.deb().desktop_entry;
+  if let Some(categories) = &desktop_entry.categories {
+    writeln!(file, "Categories={}", desktop_list(categories))?;
+  } else if let Some(category) = settings.app_category() {
     writeln!(file, "Categories={}", category.gnome_desktop_categories())?;
   } else {
     writeln!(file, "Categories=")?;
@@ -223,20 +230,67 @@ fn generate_desktop_file(settings: &Settings, data_dir: &Path) -> crate::Result<
   let use_bootstrapper = settings.deb().use_bootstrapper.unwrap_or_default();
   writeln!(
     file,
-    "Exec={}",
+    "Exec={}{}",
     if use_bootstrapper {
       format!("__{}-bootstrapper", bin_name)
     } else {
       bin_name.to_string()
+    },
+    // the opened URL or file is passed as argument
+    if settings.url_schemes().is_empty() && settings.file_associations().is_empty() {
+      ""
+    } else {
+      " %u"
     }
   )?;
   writeln!(file, "Icon={}", bin_name)?;
+  if let Some(keywords) = &desktop_entry.keywords {
+    writeln!(file, "Keywords={}", desktop_list(keywords))?;
+  }
+  let mut mime_types = desktop_entry.mime_types.clone().unwrap_or_default();
+  mime_types.extend(
+    settings
+      .url_schemes()
+      .iter()
+      .map(|scheme| format!("x-scheme-handler/{}", scheme)),
+  );
+  mime_types.extend(
+    settings
+      .file_associations()
+      .iter()
+      .filter_map(|association| association.mime_type.clone()),
+  );
+  if !mime_types.is_empty() {
+    writeln!(file, "MimeType={}", desktop_list(&mime_types))?;
+  }
   writeln!(file, "Name={}", settings.product_name())?;
   writeln!(file, "Terminal=false")?;
   writeln!(file, "Type=Application")?;
   Ok(())
 }
 
+/// Points the `Icon` of the desktop file to the icon name or path.
+pub fn set_desktop_icon(desktop_file_path: &Path, icon: &str) -> crate::Result<()> {
+  let desktop_file = fs::read_to_string(desktop_file_path)?
+    .lines()
+    .map(|line| {
+      if line.starts_with("Icon=") {
+        format!("Icon={}", icon)
+      } else {
+        line.to_string()
+      }
+    })
+    .collect::<Vec<_>>()
+    .join("\n");
+  fs::write(desktop_file_path, desktop_file + "\n")?;
+  Ok(())
+}
+
+/// Formats a desktop entry list value, where each item is terminated by a semicolon.
+fn desktop_list(items: &[String]) -> String {
+  items.iter().map(|item| format!("{};", item)).collect()
+}
+
 /// Generates the debian control file and stores it under the `control_dir`.
 fn generate_control_file(
   settings: &Settings,
@@ -268,9 +322,17 @@ fn generate_control_file(
   if !settings.homepage_url().is_empty() {
     writeln!(&mut file, "Homepage: {}", settings.homepage_url())?;
   }
-  let dependencies = settings.deb().depends.as_ref().cloned().unwrap_or_default();
-  if !dependencies.is_empty() {
-    writeln!(&mut file, "Depends: {}", dependencies.join(", "))?;
+  let deb = settings.deb();
+  for (field, packages) in vec![
+    ("Depends", &deb.depends),
+    ("Recommends", &deb.recommends),
+    ("Conflicts", &deb.conflicts),
+  ] {
+    if let Some(packages) = packages {
+      if !packages.is_empty() {
+        writeln!(&mut file, "{}: {}", field, packages.join(", "))?;
+      }
+    }
   }
   let mut short_description = settings.short_description().trim();
   if short_description.is_empty() {
@@ -320,6 +382,44 @@ fn generate_md5sums(control_dir: &Path, data_dir: &Path) -> crate::Result<()> {
   Ok(())
 }
 
+/// Creates a `conffiles` file in the `control_dir` listing the files installed under `/etc`,
+/// so dpkg keeps the user changes on upgrades.
+fn generate_conffiles(control_dir: &Path, data_dir: &Path) -> crate::Result<()> {
+  let etc_dir = data_dir.join("etc");
+  if !etc_dir.exists() {
+    return Ok(());
+  }
+  let mut conffiles = common::create_file(&control_dir.join("conffiles"))?;
+  for entry in WalkDir::new(&etc_dir) {
+    let entry = entry?;
+    if entry.file_type().is_file() {
+      let rel_path = entry.path().strip_prefix(data_dir)?;
+      writeln!(conffiles, "/{}", rel_path.display())?;
+    }
+  }
+  conffiles.flush()?;
+  Ok(())
+}
+
+/// Copies the maintainer scripts to the `control_dir`, making them executable.
+fn copy_maintainer_scripts(settings: &Settings, control_dir: &Path) -> crate::Result<()> {
+  let deb = settings.deb();
+  for (name, script) in &[
+    ("preinst", &deb.pre_install_script),
+    ("postinst", &deb.post_install_script),
+    ("prerm", &deb.pre_remove_script),
+    ("postrm", &deb.post_remove_script),
+  ] {
+    if let Some(script) = script {
+      let dest_path = control_dir.join(name);
+      common::copy_file(script, &dest_path)
+        .with_context(|| format!("Failed to copy {} script from {:?}", name, script))?;
+      fs::set_permissions(&dest_path, fs::Permissions::from_mode(0o755))?;
+    }
+  }
+  Ok(())
+}
+
 /// Copy the bundle's resource files into an appropriate directory under the
 /// `data_dir`.
 fn copy_resource_files(settings: &Settings, data_dir: &Path) -> crate::Result<()> {
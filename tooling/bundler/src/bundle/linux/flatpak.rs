@@ -0,0 +1,149 @@
+// Copyright 2019-2021 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+// The Flatpak bundle is built from a manifest like:
+//
+// bundle/flatpak/
+//     com.example.app.json    # The flatpak-builder manifest
+//     data/usr/               # The files copied to /app, as laid out by the Debian package
+//     foobar_1.2.3_x86_64.flatpak
+//
+// The desktop file and the icons are renamed after the app identifier, as Flatpak requires.
+
+use super::{super::common, debian, has_command};
+use crate::Settings;
+
+use anyhow::Context;
+use serde_json::json;
+
+use std::{
+  fs::{remove_dir_all, rename, write},
+  path::{Path, PathBuf},
+  process::Command,
+};
+
+const DEFAULT_RUNTIME_VERSION: &str = "41";
+
+/// Bundles the project.
+/// Returns a vector of PathBuf that shows where the Flatpak bundle and its manifest were created.
+pub fn bundle_project(settings: &Settings) -> crate::Result<Vec<PathBuf>> {
+  let app_id = settings.bundle_identifier();
+  if app_id.split('.').count() < 3 {
+    return Err(crate::Error::GenericError(format!(
+      "the Flatpak app id must have at least 3 segments, e.g. `com.example.app`, got `{}`; set `tauri > bundle > identifier`",
+      app_id
+    )));
+  }
+  let arch = match settings.binary_arch() {
+    "x86" => "i386",
+    "armv7" => "arm",
+    other => other,
+  };
+
+  let output_path = settings.project_out_directory().join("bundle/flatpak");
+  if output_path.exists() {
+    remove_dir_all(&output_path)?;
+  }
+  let (data_dir, icons) = debian::generate_data(settings, &output_path)?;
+  rename_after_app_id(settings, &data_dir, icons.iter().map(|icon| &icon.path))
+    .with_context(|| "Failed to rename the desktop file and icons")?;
+
+  let flatpak = settings.flatpak();
+  let mut finish_args = vec![
+    "--share=ipc".to_string(),
+    "--share=network".to_string(),
+    "--socket=x11".to_string(),
+    "--socket=wayland".to_string(),
+    "--device=dri".to_string(),
+  ];
+  finish_args.extend(flatpak.finish_args.iter().cloned());
+  let runtime_version = flatpak
+    .runtime_version
+    .as_deref()
+    .unwrap_or(DEFAULT_RUNTIME_VERSION);
+  let manifest = json!({
+    "app-id": app_id,
+    "runtime": "org.gnome.Platform",
+    "runtime-version": runtime_version,
+    "sdk": "org.gnome.Sdk",
+    "command": settings.main_binary_name(),
+    "finish-args": finish_args,
+    "modules": [{
+      "name": settings.main_binary_name(),
+      "buildsystem": "simple",
+      "build-commands": ["cp -a usr/. /app/"],
+      "sources": [{ "type": "dir", "path": "data" }]
+    }]
+  });
+  let manifest_path = output_path.join(format!("{}.json", app_id));
+  write(&manifest_path, serde_json::to_string_pretty(&manifest)?)?;
+
+  if !has_command("flatpak-builder") {
+    common::print_warning(
+      "flatpak-builder not found, skipping the Flatpak build; install it to build the bundle from the generated manifest",
+    )?;
+    return Ok(vec![manifest_path]);
+  }
+
+  let bundle_path = output_path.join(format!(
+    "{}_{}_{}.flatpak",
+    settings.main_binary_name(),
+    settings.version_string(),
+    arch
+  ));
+  common::print_bundling(bundle_path.file_name().unwrap().to_str().unwrap())?;
+
+  let mut builder = Command::new("flatpak-builder");
+  builder
+    .args(&["--force-clean", "--repo=repo", "build"])
+    .arg(&manifest_path)
+    .current_dir(&output_path);
+  run(&mut builder, "flatpak-builder", settings)?;
+
+  let mut bundle = Command::new("flatpak");
+  bundle
+    .args(&["build-bundle", "repo"])
+    .arg(&bundle_path)
+    .arg(app_id)
+    .current_dir(&output_path);
+  run(&mut bundle, "flatpak build-bundle", settings)?;
+
+  Ok(vec![bundle_path, manifest_path])
+}
+
+// Renames the desktop file and the icons to `<app_id>.desktop` and `<app_id>.png`,
+// pointing the desktop file `Icon` to the app id.
+fn rename_after_app_id<'a>(
+  settings: &Settings,
+  data_dir: &Path,
+  icons: impl Iterator<Item = &'a PathBuf>,
+) -> crate::Result<()> {
+  let app_id = settings.bundle_identifier();
+  let applications_dir = data_dir.join("usr/share/applications");
+  let desktop_file_path = applications_dir.join(format!("{}.desktop", settings.main_binary_name()));
+  debian::set_desktop_icon(&desktop_file_path, app_id)?;
+  rename(
+    &desktop_file_path,
+    applications_dir.join(format!("{}.desktop", app_id)),
+  )?;
+
+  for icon in icons {
+    rename(icon, icon.with_file_name(format!("{}.png", app_id)))?;
+  }
+  Ok(())
+}
+
+fn run(cmd: &mut Command, name: &str, settings: &Settings) -> crate::Result<()> {
+  common::execute_with_verbosity(cmd, settings).map_err(|_| {
+    crate::Error::ShellScriptError(format!(
+      "error running {}{}",
+      name,
+      if settings.is_verbose() {
+        ""
+      } else {
+        ", try running with --verbose to see command output"
+      }
+    ))
+  })
+}
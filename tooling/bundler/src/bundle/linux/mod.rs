@@ -1,3 +1,17 @@
 pub mod appimage;
 pub mod debian;
+pub mod flatpak;
 pub mod rpm;
+pub mod snap;
+
+use std::process::{Command, Stdio};
+
+/// Whether the command is installed, used to skip the optional package builds.
+fn has_command(name: &str) -> bool {
+  Command::new(name)
+    .arg("--version")
+    .stdout(Stdio::null())
+    .stderr(Stdio::null())
+    .status()
+    .is_ok()
+}
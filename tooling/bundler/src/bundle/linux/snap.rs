@@ -0,0 +1,161 @@
+// Copyright 2019-2021 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+// The Snap package is built from a project like:
+//
+// bundle/snap/
+//     snapcraft.yaml          # The snapcraft project, dumping the data directory on the snap
+//     data/usr/               # The files of the snap, as laid out by the Debian package
+//     foobar_1.2.3_amd64.snap
+
+use super::{super::common, debian, has_command};
+use crate::Settings;
+
+use anyhow::Context;
+
+use std::{
+  fs::{remove_dir_all, write},
+  path::PathBuf,
+  process::Command,
+};
+
+/// The maximum length of the snap summary.
+const SUMMARY_MAX_LENGTH: usize = 78;
+
+/// Bundles the project.
+/// Returns a vector of PathBuf that shows where the snap and its snapcraft.yaml were created.
+pub fn bundle_project(settings: &Settings) -> crate::Result<Vec<PathBuf>> {
+  let name = snap_name(settings.main_binary_name());
+
+  let output_path = settings.project_out_directory().join("bundle/snap");
+  if output_path.exists() {
+    remove_dir_all(&output_path)?;
+  }
+  let (data_dir, icons) = debian::generate_data(settings, &output_path)?;
+
+  // the snap desktop file references the icon by its path on the snap
+  if let Some(icon) = icons
+    .iter()
+    .filter(|icon| icon.width == icon.height && !icon.is_high_density)
+    .max_by_key(|icon| icon.width)
+  {
+    let icon_path = icon
+      .path
+      .strip_prefix(&data_dir)
+      .expect("the icon isn't on the data directory");
+    debian::set_desktop_icon(
+      &data_dir.join(format!(
+        "usr/share/applications/{}.desktop",
+        settings.main_binary_name()
+      )),
+      &format!("${{SNAP}}/{}", icon_path.display()),
+    )
+    .with_context(|| "Failed to update the desktop file")?;
+  }
+
+  let snapcraft_path = output_path.join("snapcraft.yaml");
+  write(&snapcraft_path, snapcraft_yaml(settings, &name)?)?;
+
+  if !has_command("snapcraft") {
+    common::print_warning(
+      "snapcraft not found, skipping the snap build; install it to build the package from the generated snapcraft.yaml",
+    )?;
+    return Ok(vec![snapcraft_path]);
+  }
+
+  common::print_bundling(&format!("{}_{}.snap", name, settings.version_string()))?;
+  let mut cmd = Command::new("snapcraft");
+  cmd.current_dir(&output_path);
+  common::execute_with_verbosity(&mut cmd, settings).map_err(|_| {
+    crate::Error::ShellScriptError(format!(
+      "error running snapcraft{}",
+      if settings.is_verbose() {
+        ""
+      } else {
+        ", try running with --verbose to see command output"
+      }
+    ))
+  })?;
+
+  let mut paths = Vec::new();
+  for snap in glob::glob(&output_path.join("*.snap").to_string_lossy())? {
+    paths.push(snap?);
+  }
+  paths.push(snapcraft_path);
+  Ok(paths)
+}
+
+/// Converts the binary name to a snap name: lowercase letters, digits and dashes.
+fn snap_name(binary_name: &str) -> String {
+  binary_name
+    .to_lowercase()
+    .chars()
+    .map(|c| if c.is_ascii_alphanumeric() { c } else { '-' })
+    .collect::<String>()
+    .trim_matches('-')
+    .to_string()
+}
+
+fn snapcraft_yaml(settings: &Settings, name: &str) -> crate::Result<String> {
+  let snap = settings.snap();
+  let mut summary = settings.short_description().to_string();
+  if summary.is_empty() {
+    summary = settings.product_name().to_string();
+  }
+  if summary.chars().count() > SUMMARY_MAX_LENGTH {
+    summary = summary.chars().take(SUMMARY_MAX_LENGTH).collect();
+  }
+  let description = settings.long_description().unwrap_or(summary.as_str());
+
+  let mut plugs = vec!["network".to_string(), "browser-support".to_string()];
+  plugs.extend(snap.plugs.iter().cloned());
+  let mut stage_packages = vec!["libwebkit2gtk-4.0-37".to_string()];
+  stage_packages.extend(snap.stage_packages.iter().cloned());
+
+  // JSON strings are valid YAML scalars, quoting the values safely
+  Ok(format!(
+    r#"name: {name}
+base: core20
+version: {version}
+summary: {summary}
+description: {description}
+grade: {grade}
+confinement: {confinement}
+
+apps:
+  {name}:
+    command: usr/bin/{binary}
+    desktop: usr/share/applications/{binary}.desktop
+    extensions: [gnome-3-38]
+    plugs: {plugs}
+
+parts:
+  {name}:
+    plugin: dump
+    source: data
+    stage-packages: {stage_packages}
+"#,
+    name = name,
+    version = serde_json::to_string(settings.version_string())?,
+    summary = serde_json::to_string(&summary)?,
+    description = serde_json::to_string(description)?,
+    grade = snap.grade.as_deref().unwrap_or("stable"),
+    confinement = snap.confinement.as_deref().unwrap_or("strict"),
+    binary = settings.main_binary_name(),
+    plugs = serde_json::to_string(&plugs)?,
+    stage_packages = serde_json::to_string(&stage_packages)?,
+  ))
+}
+
+#[cfg(test)]
+mod tests {
+  use super::snap_name;
+
+  #[test]
+  fn converts_binary_name() {
+    assert_eq!(snap_name("my_app"), "my-app");
+    assert_eq!(snap_name("MyApp2"), "myapp2");
+    assert_eq!(snap_name("_app_"), "app");
+  }
+}
@@ -90,12 +90,32 @@ pub fn bundle_project(settings: &Settings) -> crate::Result<Vec<PathBuf>> {
     // setup keychain allow you to import your certificate
     // for CI build
     setup_keychain_if_needed()?;
+    // sign the binaries and sidecars before the bundle, since the hardened runtime applies to each of them
+    // the sidecars with verified checksums must be signed beforehand, since signing changes them
+    let mut skip = vec![std::ffi::OsString::from("__bootstrapper")];
+    if settings.verify_sidecar_checksums() {
+      for src in settings.external_binaries() {
+        if let Some(file_name) = src?.file_name() {
+          skip.push(file_name.to_os_string());
+        }
+      }
+    }
+    for entry in std::fs::read_dir(&bin_dir)? {
+      let path = entry?.path();
+      let skipped = path
+        .file_name()
+        .map(|file_name| skip.iter().any(|s| s == file_name))
+        .unwrap_or_default();
+      if path.is_file() && !skipped {
+        sign(path, identity, &settings, true)?;
+      }
+    }
     // sign application
     sign(app_bundle_path.clone(), identity, &settings, true)?;
     // notarization is required for distribution
     match notarize_auth_args() {
       Ok(args) => {
-        notarize(app_bundle_path.clone(), args, settings)?;
+        notarize(app_bundle_path.clone(), args)?;
       }
       Err(e) => {
         common::print_info(format!("skipping app notarization, {}", e.to_string()).as_str())?;
@@ -201,6 +221,38 @@ fn create_info_plist(
     "  <key>CFBundleDisplayName</key>\n  <string>{}</string>\n",
     settings.product_name()
   )?;
+  if !settings.file_associations().is_empty() {
+    write!(file, "  <key>CFBundleDocumentTypes</key>\n  <array>\n")?;
+    for association in settings.file_associations() {
+      write!(
+        file,
+        "    <dict>\n      \
+         <key>CFBundleTypeExtensions</key>\n      \
+         <array>\n"
+      )?;
+      for ext in association.extensions() {
+        write!(file, "        <string>{}</string>\n", ext)?;
+      }
+      write!(file, "      </array>\n")?;
+      if let Some(mime_type) = &association.mime_type {
+        write!(
+          file,
+          "      <key>CFBundleTypeMIMETypes</key>\n      \
+           <array>\n        <string>{}</string>\n      </array>\n",
+          mime_type
+        )?;
+      }
+      write!(
+        file,
+        "      <key>CFBundleTypeName</key>\n      <string>{}</string>\n      \
+         <key>CFBundleTypeRole</key>\n      <string>{}</string>\n    \
+         </dict>\n",
+        association.name(),
+        association.role.as_str()
+      )?;
+    }
+    write!(file, "  </array>\n")?;
+  }
   write!(
     file,
     "  <key>CFBundleExecutable</key>\n  <string>{}</string>\n",
@@ -241,6 +293,23 @@ fn create_info_plist(
     "  <key>CFBundleShortVersionString</key>\n  <string>{}</string>\n",
     settings.version_string()
   )?;
+  if !settings.url_schemes().is_empty() {
+    write!(
+      file,
+      "  <key>CFBundleURLTypes</key>\n  \
+       <array>\n    \
+       <dict>\n      \
+       <key>CFBundleURLName</key>\n      \
+       <string>{}</string>\n      \
+       <key>CFBundleURLSchemes</key>\n      \
+       <array>\n",
+      settings.bundle_identifier()
+    )?;
+    for scheme in settings.url_schemes() {
+      write!(file, "        <string>{}</string>\n", scheme)?;
+    }
+    write!(file, "      </array>\n    </dict>\n  </array>\n")?;
+  }
   write!(
     file,
     "  <key>CFBundleVersion</key>\n  <string>{}</string>\n",
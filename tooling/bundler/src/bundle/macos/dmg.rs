@@ -153,6 +153,10 @@ pub fn bundle_project(settings: &Settings, bundles: &[Bundle]) -> crate::Result<
   // Sign DMG if needed
   if let Some(identity) = &settings.macos().signing_identity {
     super::sign::sign(dmg_path.clone(), identity, &settings, false)?;
+    // the app inside is already notarized, the DMG gets its own ticket so Gatekeeper accepts it offline
+    if let Ok(args) = super::sign::notarize_auth_args() {
+      super::sign::notarize(dmg_path.clone(), args)?;
+    }
   }
   Ok(vec![dmg_path])
 }
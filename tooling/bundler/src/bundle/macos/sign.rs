@@ -6,7 +6,7 @@ use std::{
 };
 
 use crate::{bundle::common, Settings};
-use regex::Regex;
+use serde::Deserialize;
 
 // Import certificate from ENV variables.
 // APPLE_CERTIFICATE is the p12 certificate base64 encoded.
@@ -182,7 +182,7 @@ pub fn sign(
   is_an_executable: bool,
 ) -> crate::Result<()> {
   common::print_info(format!(r#"signing with identity "{}""#, identity).as_str())?;
-  let mut args = vec!["--force", "-s", identity];
+  let mut args = vec!["--force", "--timestamp", "-s", identity];
   if let Some(entitlements_path) = &settings.macos().entitlements {
     common::print_info(format!("using entitlements file at {}", entitlements_path).as_str())?;
     args.push("--entitlements");
@@ -194,7 +194,8 @@ pub fn sign(
     args.push("runtime");
   }
 
-  if path_to_sign.is_dir() {
+  // `--deep` would sign the sidecars again, changing the checksums verified by the app
+  if path_to_sign.is_dir() && !settings.verify_sidecar_checksums() {
     args.push("--deep");
   }
 
@@ -210,119 +211,104 @@ pub fn sign(
   Ok(())
 }
 
-pub fn notarize(
-  app_bundle_path: PathBuf,
-  auth_args: Vec<String>,
-  settings: &Settings,
-) -> crate::Result<()> {
-  let identifier = settings.bundle_identifier();
-
-  let bundle_stem = app_bundle_path
-    .file_stem()
-    .expect("failed to get bundle filename");
-
+/// Submits the app bundle or DMG to Apple's notary service with `notarytool`, waiting for the result,
+/// and staples the notarization ticket to it.
+pub fn notarize(path: PathBuf, auth_args: Vec<String>) -> crate::Result<()> {
   let tmp_dir = tempfile::tempdir()?;
-  let zip_path = tmp_dir
-    .path()
-    .join(format!("{}.zip", bundle_stem.to_string_lossy()));
-  let zip_args = vec![
-    "-c",
-    "-k",
-    "--keepParent",
-    "--sequesterRsrc",
-    app_bundle_path
-      .to_str()
-      .expect("failed to convert bundle_path to string"),
+  let submission_path = if path.is_dir() {
+    let bundle_stem = path.file_stem().expect("failed to get bundle filename");
+    let zip_path = tmp_dir
+      .path()
+      .join(format!("{}.zip", bundle_stem.to_string_lossy()));
+    let zip_args = vec![
+      "-c",
+      "-k",
+      "--keepParent",
+      "--sequesterRsrc",
+      path
+        .to_str()
+        .expect("failed to convert bundle_path to string"),
+      zip_path
+        .to_str()
+        .expect("failed to convert zip_path to string"),
+    ];
+
+    // use ditto to create a PKZip almost identical to Finder
+    // this remove almost 99% of false alarm in notarization
+    let zip_app = Command::new("ditto")
+      .args(zip_args)
+      .stderr(Stdio::inherit())
+      .status()?;
+
+    if !zip_app.success() {
+      return Err(anyhow::anyhow!("failed to zip app with ditto").into());
+    }
     zip_path
-      .to_str()
-      .expect("failed to convert zip_path to string"),
-  ];
-
-  // use ditto to create a PKZip almost identical to Finder
-  // this remove almost 99% of false alarm in notarization
-  let zip_app = Command::new("ditto")
-    .args(zip_args)
-    .stderr(Stdio::inherit())
-    .status()?;
-
-  if !zip_app.success() {
-    return Err(anyhow::anyhow!("failed to zip app with ditto").into());
-  }
-
-  // sign the zip file
-  if let Some(identity) = &settings.macos().signing_identity {
-    sign(zip_path.clone(), identity, &settings, false)?;
+  } else {
+    path.clone()
   };
 
-  let notarize_args = vec![
-    "altool",
-    "--notarize-app",
-    "-f",
-    zip_path
-      .to_str()
-      .expect("failed to convert zip_path to string"),
-    "--primary-bundle-id",
-    identifier,
-  ];
-  common::print_info("notarizing app")?;
+  common::print_info(
+    format!(
+      "notarizing {}; waiting for Apple response...",
+      path.display()
+    )
+    .as_str(),
+  )?;
   let output = Command::new("xcrun")
-    .args(notarize_args)
-    .args(auth_args.clone())
+    .args(&["notarytool", "submit"])
+    .arg(&submission_path)
+    .args(&["--wait", "--output-format", "json"])
+    .args(&auth_args)
     .stderr(Stdio::inherit())
     .output()?;
 
-  if !output.status.success() {
-    return Err(
-      anyhow::anyhow!(format!(
-        "failed to upload app to Apple's notarization servers. {}",
-        std::str::from_utf8(&output.stdout)?
-      ))
-      .into(),
-    );
-  }
-
   let stdout = std::str::from_utf8(&output.stdout)?;
-  if let Some(uuid) = Regex::new(r"\nRequestUUID = (.+?)\n")?
-    .captures_iter(stdout)
-    .next()
-  {
-    common::print_info("notarization started; waiting for Apple response...")?;
-    let uuid = uuid[1].to_string();
-    get_notarization_status(uuid, auth_args)?;
-    staple_app(app_bundle_path.clone())?;
-  } else {
+  let submission: NotarySubmission = serde_json::from_str(stdout).map_err(|_| {
+    anyhow::anyhow!(format!(
+      "failed to upload app to Apple's notarization servers. {}",
+      stdout
+    ))
+  })?;
+
+  if submission.status != "Accepted" {
+    let log = Command::new("xcrun")
+      .args(&["notarytool", "log", &submission.id])
+      .args(&auth_args)
+      .output()
+      .map(|output| String::from_utf8_lossy(&output.stdout).to_string())
+      .unwrap_or_default();
     return Err(
       anyhow::anyhow!(format!(
-        "failed to parse RequestUUID from upload output. {}",
-        stdout
+        "Apple failed to notarize your app ({}). {}",
+        submission.status, log
       ))
       .into(),
     );
   }
 
-  Ok(())
+  staple(path)
 }
 
-fn staple_app(mut app_bundle_path: PathBuf) -> crate::Result<()> {
-  let app_bundle_path_clone = app_bundle_path.clone();
-  let filename = app_bundle_path_clone
-    .file_name()
-    .expect("failed to get bundle filename")
-    .to_str()
-    .expect("failed to convert bundle filename to string");
-
-  app_bundle_path.pop();
+/// The `notarytool submit` JSON output.
+#[derive(Deserialize)]
+struct NotarySubmission {
+  id: String,
+  status: String,
+}
 
+fn staple(path: PathBuf) -> crate::Result<()> {
   let output = Command::new("xcrun")
-    .args(vec!["stapler", "staple", "-v", filename])
-    .current_dir(app_bundle_path)
+    .args(vec!["stapler", "staple", "-v"])
+    .arg(&path)
     .stderr(Stdio::inherit())
     .output()?;
 
   if !output.status.success() {
     Err(
       anyhow::anyhow!(format!(
-        "failed to staple app. {}",
+        "failed to staple {}. {}",
+        path.display(),
         std::str::from_utf8(&output.stdout)?
       ))
       .into(),
@@ -332,81 +318,72 @@ fn staple_app(mut app_bundle_path: PathBuf) -> crate::Result<()> {
   }
 }
 
-fn get_notarization_status(uuid: String, auth_args: Vec<String>) -> crate::Result<()> {
-  std::thread::sleep(std::time::Duration::from_secs(10));
-  let output = Command::new("xcrun")
-    .args(vec!["altool", "--notarization-info", &uuid])
-    .args(auth_args.clone())
-    .stderr(Stdio::inherit())
-    .output()?;
-
-  if !output.status.success() {
-    get_notarization_status(uuid, auth_args)
-  } else {
-    let stdout = std::str::from_utf8(&output.stdout)?;
-    if let Some(status) = Regex::new(r"\n *Status: (.+?)\n")?
-      .captures_iter(stdout)
-      .next()
-    {
-      let status = status[1].to_string();
-      if status == "in progress" {
-        get_notarization_status(uuid, auth_args)
-      } else if status == "invalid" {
-        Err(
-          anyhow::anyhow!(format!(
-            "Apple failed to notarize your app. {}",
-            std::str::from_utf8(&output.stdout)?
-          ))
-          .into(),
-        )
-      } else if status != "success" {
-        Err(
-          anyhow::anyhow!(format!(
-            "Unknown notarize status {}. {}",
-            status,
-            std::str::from_utf8(&output.stdout)?
-          ))
-          .into(),
-        )
-      } else {
-        Ok(())
-      }
-    } else {
-      get_notarization_status(uuid, auth_args)
-    }
-  }
-}
-
+/// The `notarytool` credentials, read from the `APPLE_ID`, `APPLE_PASSWORD` and `APPLE_TEAM_ID`
+/// or the `APPLE_API_KEY`, `APPLE_API_ISSUER` and `APPLE_API_KEY_PATH` environment variables.
 pub fn notarize_auth_args() -> crate::Result<Vec<String>> {
-  match (
-    std::env::var_os("APPLE_ID"),
-    std::env::var_os("APPLE_PASSWORD"),
-  ) {
-    (Some(apple_id), Some(apple_password)) => {
-      let apple_id = apple_id
-        .to_str()
-        .expect("failed to convert APPLE_ID to string")
-        .to_string();
-      let apple_password = apple_password
+  let var = |name: &str| {
+    std::env::var_os(name).map(|value| {
+      value
         .to_str()
-        .expect("failed to convert APPLE_PASSWORD to string")
-        .to_string();
+        .unwrap_or_else(|| panic!("failed to convert {} to string", name))
+        .to_string()
+    })
+  };
+  match (var("APPLE_ID"), var("APPLE_PASSWORD")) {
+    (Some(apple_id), Some(apple_password)) => {
+      let team_id = var("APPLE_TEAM_ID").ok_or_else(|| {
+        anyhow::anyhow!("the APPLE_TEAM_ID environment variable is required with APPLE_ID")
+      })?;
       Ok(vec![
-        "-u".to_string(),
+        "--apple-id".to_string(),
         apple_id,
-        "-p".to_string(),
+        "--password".to_string(),
         apple_password,
+        "--team-id".to_string(),
+        team_id,
       ])
     }
-    _ => {
-      match (std::env::var_os("APPLE_API_KEY"), std::env::var_os("APPLE_API_ISSUER")) {
-        (Some(api_key), Some(api_issuer)) => {
-          let api_key = api_key.to_str().expect("failed to convert APPLE_API_KEY to string").to_string();
-          let api_issuer = api_issuer.to_str().expect("failed to convert APPLE_API_ISSUER to string").to_string();
-          Ok(vec!["--apiKey".to_string(), api_key, "--apiIssuer".to_string(), api_issuer])
-        },
-        _ => Err(anyhow::anyhow!("no APPLE_ID & APPLE_PASSWORD or APPLE_API_KEY & APPLE_API_ISSUER environment variables found").into())
+    _ => match (var("APPLE_API_KEY"), var("APPLE_API_ISSUER")) {
+      (Some(api_key), Some(api_issuer)) => {
+        let key_path = match var("APPLE_API_KEY_PATH") {
+          Some(key_path) => PathBuf::from(key_path),
+          None => find_api_key(&api_key)?,
+        };
+        Ok(vec![
+          "--key".to_string(),
+          key_path.to_string_lossy().to_string(),
+          "--key-id".to_string(),
+          api_key,
+          "--issuer".to_string(),
+          api_issuer,
+        ])
       }
-    }
+      _ => Err(
+        anyhow::anyhow!("no APPLE_ID & APPLE_PASSWORD or APPLE_API_KEY & APPLE_API_ISSUER environment variables found")
+          .into(),
+      ),
+    },
   }
 }
+
+// Looks for the `AuthKey_<id>.p8` file on the directories searched by `altool`.
+fn find_api_key(api_key: &str) -> crate::Result<PathBuf> {
+  let file_name = format!("AuthKey_{}.p8", api_key);
+  let mut directories = vec![PathBuf::from("./private_keys")];
+  if let Some(home) = dirs_next::home_dir() {
+    directories.push(home.join("private_keys"));
+    directories.push(home.join(".private_keys"));
+    directories.push(home.join(".appstoreconnect/private_keys"));
+  }
+  directories
+    .into_iter()
+    .map(|directory| directory.join(&file_name))
+    .find(|path| path.exists())
+    .ok_or_else(|| {
+      anyhow::anyhow!(format!(
+        "{} not found, set the APPLE_API_KEY_PATH environment variable",
+        file_name
+      ))
+      .into()
+    })
+}
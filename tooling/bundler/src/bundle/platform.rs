@@ -2,6 +2,24 @@
 // SPDX-License-Identifier: Apache-2.0
 // SPDX-License-Identifier: MIT
 
+/// The operating system of the target triple, e.g. `windows` for `x86_64-pc-windows-msvc`,
+/// using the [`std::env::consts::OS`] names.
+pub fn target_os(target: &str) -> Option<&'static str> {
+  if target.contains("windows") {
+    Some("windows")
+  } else if target.contains("apple-darwin") {
+    Some("macos")
+  } else if target.contains("apple-ios") {
+    Some("ios")
+  } else if target.contains("linux") {
+    Some("linux")
+  } else if target.contains("freebsd") {
+    Some("freebsd")
+  } else {
+    None
+  }
+}
+
 /// Try to determine the current target triple.
 ///
 /// Returns a target triple (e.g. `x86_64-unknown-linux-gnu` or `i686-pc-windows-msvc`) or an
@@ -3,7 +3,10 @@
 // SPDX-License-Identifier: MIT
 
 use super::category::AppCategory;
-use crate::bundle::{common, platform::target_triple};
+use crate::bundle::{
+  common,
+  platform::{target_os, target_triple},
+};
 
 use std::{
   collections::HashMap,
@@ -20,12 +23,20 @@ pub enum PackageType {
   IosBundle,
   /// The Windows bundle (.msi).
   WindowsMsi,
+  /// The Windows NSIS installer (.exe).
+  Nsis,
+  /// The Windows portable executable (.exe).
+  Portable,
   /// The Linux Debian package bundle (.deb).
   Deb,
   /// The Linux RPM bundle (.rpm).
   Rpm,
   /// The Linux AppImage bundle (.AppImage).
   AppImage,
+  /// The Linux Flatpak bundle (.flatpak).
+  Flatpak,
+  /// The Linux Snap package (.snap).
+  Snap,
   /// The macOS DMG bundle (.dmg).
   Dmg,
   /// The Updater bundle.
@@ -34,16 +45,20 @@ pub enum PackageType {
 
 impl PackageType {
   /// Maps a short name to a PackageType.
-  /// Possible values are "deb", "ios", "msi", "app", "rpm", "appimage", "dmg", "updater".
+  /// Possible values are "deb", "ios", "msi", "nsis", "portable", "app", "rpm", "appimage", "flatpak", "snap", "dmg", "updater".
   pub fn from_short_name(name: &str) -> Option<PackageType> {
     // Other types we may eventually want to support: apk.
     match name {
       "deb" => Some(PackageType::Deb),
       "ios" => Some(PackageType::IosBundle),
       "msi" => Some(PackageType::WindowsMsi),
+      "nsis" => Some(PackageType::Nsis),
+      "portable" => Some(PackageType::Portable),
       "app" => Some(PackageType::MacOsBundle),
       "rpm" => Some(PackageType::Rpm),
       "appimage" => Some(PackageType::AppImage),
+      "flatpak" => Some(PackageType::Flatpak),
+      "snap" => Some(PackageType::Snap),
       "dmg" => Some(PackageType::Dmg),
       "updater" => Some(PackageType::Updater),
       _ => None,
@@ -57,9 +72,13 @@ impl PackageType {
       PackageType::Deb => "deb",
       PackageType::IosBundle => "ios",
       PackageType::WindowsMsi => "msi",
+      PackageType::Nsis => "nsis",
+      PackageType::Portable => "portable",
       PackageType::MacOsBundle => "app",
       PackageType::Rpm => "rpm",
       PackageType::AppImage => "appimage",
+      PackageType::Flatpak => "flatpak",
+      PackageType::Snap => "snap",
       PackageType::Dmg => "dmg",
       PackageType::Updater => "updater",
     }
@@ -78,6 +97,10 @@ const ALL_PACKAGE_TYPES: &[PackageType] = &[
   PackageType::IosBundle,
   #[cfg(target_os = "windows")]
   PackageType::WindowsMsi,
+  #[cfg(target_os = "windows")]
+  PackageType::Nsis,
+  #[cfg(target_os = "windows")]
+  PackageType::Portable,
   #[cfg(target_os = "macos")]
   PackageType::MacOsBundle,
   #[cfg(target_os = "linux")]
@@ -86,6 +109,10 @@ const ALL_PACKAGE_TYPES: &[PackageType] = &[
   PackageType::Dmg,
   #[cfg(target_os = "linux")]
   PackageType::AppImage,
+  #[cfg(target_os = "linux")]
+  PackageType::Flatpak,
+  #[cfg(target_os = "linux")]
+  PackageType::Snap,
   PackageType::Updater,
 ];
 
@@ -133,7 +160,81 @@ pub struct DebianSettings {
   pub use_bootstrapper: Option<bool>,
   /// List of custom files to add to the deb package.
   /// Maps the path on the debian package to the path of the file to include (relative to the current working directory).
+  ///
+  /// The files placed under `/etc` are marked as configuration files, kept on upgrades if the user changed them.
   pub files: HashMap<PathBuf, PathBuf>,
+  /// the list of debian packages recommended by the app.
+  pub recommends: Option<Vec<String>>,
+  /// the list of debian packages conflicting with the app.
+  pub conflicts: Option<Vec<String>>,
+  /// the desktop entry settings.
+  pub desktop_entry: DesktopEntrySettings,
+  /// Path to the script run before the package is installed (`preinst`).
+  pub pre_install_script: Option<PathBuf>,
+  /// Path to the script run after the package is installed (`postinst`).
+  pub post_install_script: Option<PathBuf>,
+  /// Path to the script run before the package is removed (`prerm`).
+  pub pre_remove_script: Option<PathBuf>,
+  /// Path to the script run after the package is removed (`postrm`).
+  pub post_remove_script: Option<PathBuf>,
+}
+
+/// The Linux AppImage bundle settings.
+#[derive(Clone, Debug, Default)]
+pub struct AppImageSettings {
+  /// Whether the shared libraries the binaries link to are bundled or not,
+  /// except the ones expected on every system, e.g. glibc and the graphics drivers.
+  pub bundle_dependencies: bool,
+  /// The shared libraries to bundle, as library names resolved with `ldconfig` (e.g. `libwebkit2gtk-4.0.so.37`) or paths.
+  pub libraries: Vec<String>,
+  /// The shared libraries that aren't bundled with [`AppImageSettings::bundle_dependencies`],
+  /// matching the library file names starting with each entry, e.g. `libgtk-3`.
+  pub exclude_libraries: Vec<String>,
+  /// The update information embedded on the AppImage for AppImageUpdate, e.g. `gh-releases-zsync|user|repo|latest|*.AppImage.zsync`.
+  ///
+  /// The `.zsync` file is generated next to the AppImage.
+  ///
+  /// See <https://github.com/AppImage/AppImageSpec/blob/master/draft.md#update-information>.
+  pub update_information: Option<String>,
+  /// Path to a custom `AppRun` script, replacing the AppImageKit `AppRun`.
+  pub app_run: Option<PathBuf>,
+}
+
+/// The Linux Flatpak bundle settings.
+#[derive(Clone, Debug, Default)]
+pub struct FlatpakSettings {
+  /// The `org.gnome.Platform` runtime version. Defaults to `41`.
+  pub runtime_version: Option<String>,
+  /// The sandbox permissions added to the default ones (IPC, X11, Wayland, DRI and network access),
+  /// e.g. `--filesystem=home`.
+  pub finish_args: Vec<String>,
+}
+
+/// The Linux Snap package settings.
+#[derive(Clone, Debug, Default)]
+pub struct SnapSettings {
+  /// The snap confinement, `strict`, `classic` or `devmode`. Defaults to `strict`.
+  pub confinement: Option<String>,
+  /// The snap grade, `stable` or `devel`. Defaults to `stable`.
+  pub grade: Option<String>,
+  /// The interfaces the app plugs, added to `network`, `browser-support` and the ones of the GNOME extension.
+  pub plugs: Vec<String>,
+  /// The Ubuntu packages bundled with the snap, added to WebKitGTK.
+  pub stage_packages: Vec<String>,
+}
+
+/// The settings of the app's `.desktop` file.
+///
+/// See <https://specifications.freedesktop.org/desktop-entry-spec/latest/ar01s06.html>.
+#[derive(Clone, Debug, Default)]
+pub struct DesktopEntrySettings {
+  /// The desktop entry categories, e.g. `Development` or `Utility`.
+  /// Defaults to the categories of the app category.
+  pub categories: Option<Vec<String>>,
+  /// The MIME types the app can open, e.g. `text/markdown`.
+  pub mime_types: Option<Vec<String>>,
+  /// The keywords used to search the app, in addition to its name.
+  pub keywords: Option<Vec<String>>,
 }
 
 /// The macOS bundle settings.
@@ -192,6 +293,82 @@ pub struct WixSettings {
   pub merge_refs: Vec<String>,
   /// Disables the Webview2 runtime installation after app install.
   pub skip_webview_install: bool,
+  /// Whether the app is installed for all users or only for the current user.
+  pub install_scope: WixInstallScope,
+  /// The UpgradeCode GUID identifying the app across versions.
+  /// Defaults to a GUID derived from the main binary name.
+  pub upgrade_code: Option<String>,
+  /// The installer languages, as culture names e.g. `en-US`. One MSI is built per language.
+  pub languages: Vec<String>,
+  /// The registry values written on install and removed on uninstall.
+  pub registry_keys: Vec<WixRegistryKey>,
+  /// The file extensions opened by the app.
+  pub file_associations: Vec<WixFileAssociation>,
+}
+
+/// The WiX install scope.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum WixInstallScope {
+  /// Installs the app on `Program Files` for all users, requiring elevation.
+  PerMachine,
+  /// Installs the app on `%LOCALAPPDATA%\Programs` for the current user, without elevation.
+  PerUser,
+}
+
+impl Default for WixInstallScope {
+  fn default() -> Self {
+    Self::PerMachine
+  }
+}
+
+/// A registry value written by the MSI installer.
+#[derive(Clone, Debug)]
+pub struct WixRegistryKey {
+  /// The registry root, e.g. `HKCU` or `HKLM`.
+  pub root: String,
+  /// The key path, e.g. `Software\MyCompany\MyApp`.
+  pub key: String,
+  /// The value name. Defaults to the key default value.
+  pub name: Option<String>,
+  /// The value type, `string` or `integer`.
+  pub value_type: String,
+  /// The value, which can reference installer properties such as `[INSTALLDIR]`.
+  pub value: String,
+}
+
+/// A file extension opened by the app.
+#[derive(Clone, Debug)]
+pub struct WixFileAssociation {
+  /// The file extension, without the leading dot.
+  pub extension: String,
+  /// The file type description shown on the Explorer.
+  pub description: Option<String>,
+  /// The file MIME type.
+  pub mime_type: Option<String>,
+}
+
+/// Settings specific to the NSIS installer.
+#[derive(Clone, Debug, Default)]
+pub struct NsisSettings {
+  /// Path to a license file shown on the installer license page.
+  pub license: Option<PathBuf>,
+  /// The default install directory, which can use the NSIS constants e.g. `$LOCALAPPDATA\Programs\MyApp`.
+  /// Defaults to `$PROGRAMFILES64\<productName>` (`$PROGRAMFILES` on 32-bit, `$LOCALAPPDATA\Programs` on per-user installs).
+  pub install_directory: Option<String>,
+  /// Installs the app for the current user only, without elevation.
+  pub per_user: bool,
+  /// Builds an installer that never shows its pages, as if run with `/S`.
+  pub silent: bool,
+  /// Disables the Webview2 runtime web installation when it's not installed.
+  pub skip_webview_install: bool,
+}
+
+/// Settings specific to the portable executable.
+#[derive(Clone, Debug, Default)]
+pub struct PortableSettings {
+  /// The directory where the app stores its data, relative to the executable directory or absolute.
+  /// Defaults to `<productName> Data` next to the executable.
+  pub data_directory: Option<String>,
 }
 
 /// The Windows bundle settings.
@@ -205,6 +382,10 @@ pub struct WindowsSettings {
   pub timestamp_url: Option<String>,
   /// WiX configuration.
   pub wix: Option<WixSettings>,
+  /// NSIS configuration.
+  pub nsis: Option<NsisSettings>,
+  /// Portable executable configuration.
+  pub portable: Option<PortableSettings>,
   /// The path to the application icon. Defaults to `./icons/icon.ico`.
   pub icon_path: PathBuf,
 }
@@ -216,6 +397,8 @@ impl Default for WindowsSettings {
       certificate_thumbprint: None,
       timestamp_url: None,
       wix: None,
+      nsis: None,
+      portable: None,
       icon_path: PathBuf::from("icons/icon.ico"),
     }
   }
@@ -232,8 +415,8 @@ pub struct BundleSettings {
   ///
   /// each item can be a path to a file or a path to a folder.
   ///
-  /// supports glob patterns.
-  pub resources: Option<Vec<String>>,
+  /// supports glob patterns, and the patterns prefixed with `!` exclude the matched files.
+  pub resources: Option<Vec<BundleResource>>,
   /// the app's copyright.
   pub copyright: Option<String>,
   /// the app's category.
@@ -253,9 +436,22 @@ pub struct BundleSettings {
   /// and `sqlite3-x86_64-pc-windows-gnu.exe` on windows.
   ///
   /// The possible target triples can be seen by running `$ rustup target list`.
-  pub external_bin: Option<Vec<String>>,
+  pub external_bin: Option<Vec<ExternalBin>>,
+  /// Whether the app verifies the sidecar checksums at startup.
+  /// The bundler doesn't sign the sidecars in this case, since it would change their checksums.
+  pub verify_sidecar_checksums: bool,
+  /// The URL schemes registered for the app, e.g. `myapp` to open the `myapp://` links with the app.
+  pub url_schemes: Vec<String>,
+  /// The file types opened by the app.
+  pub file_associations: Vec<FileAssociation>,
   /// Debian-specific settings.
   pub deb: DebianSettings,
+  /// AppImage-specific settings.
+  pub appimage: AppImageSettings,
+  /// Flatpak-specific settings.
+  pub flatpak: FlatpakSettings,
+  /// Snap-specific settings.
+  pub snap: SnapSettings,
   /// MacOS-specific settings.
   pub macos: MacOsSettings,
   /// Updater configuration.
@@ -264,6 +460,113 @@ pub struct BundleSettings {
   pub windows: WindowsSettings,
 }
 
+/// An external binary (sidecar) to bundle.
+#[derive(Clone, Debug)]
+pub struct ExternalBin {
+  /// The binary path, without the target triple suffix.
+  pub path: String,
+  /// The target triples the binary is bundled for, or `windows`, `macos` and `linux`
+  /// to match every target of the OS. The binary is bundled for every target if empty.
+  pub targets: Vec<String>,
+}
+
+impl ExternalBin {
+  /// Whether the binary is bundled for the given target triple.
+  pub fn is_bundled_for(&self, target_triple: &str) -> bool {
+    matches_target(&self.targets, target_triple)
+  }
+}
+
+/// The role of the app for a file type, written to the macOS `CFBundleTypeRole`.
+#[derive(Clone, Copy, Debug)]
+pub enum BundleTypeRole {
+  /// The app can read and write the files.
+  Editor,
+  /// The app can only read the files.
+  Viewer,
+  /// The app provides runtime services for the files.
+  Shell,
+  /// The app declares the file type without opening it.
+  None,
+}
+
+impl Default for BundleTypeRole {
+  fn default() -> Self {
+    Self::Editor
+  }
+}
+
+impl BundleTypeRole {
+  /// The `CFBundleTypeRole` value.
+  pub fn as_str(&self) -> &'static str {
+    match self {
+      Self::Editor => "Editor",
+      Self::Viewer => "Viewer",
+      Self::Shell => "Shell",
+      Self::None => "None",
+    }
+  }
+}
+
+/// A file type opened by the app.
+#[derive(Clone, Debug)]
+pub struct FileAssociation {
+  /// The file extensions, without the leading dot.
+  pub ext: Vec<String>,
+  /// The file type name. Defaults to the first extension.
+  pub name: Option<String>,
+  /// The file type description shown by the file manager.
+  pub description: Option<String>,
+  /// The file MIME type.
+  pub mime_type: Option<String>,
+  /// The role of the app for the file type on macOS.
+  pub role: BundleTypeRole,
+}
+
+impl FileAssociation {
+  /// The file extensions, without the leading dot.
+  pub fn extensions(&self) -> impl Iterator<Item = &str> {
+    self.ext.iter().map(|ext| ext.trim_start_matches('.'))
+  }
+
+  /// The file type name, defaulting to the first extension.
+  pub fn name(&self) -> String {
+    self
+      .name
+      .clone()
+      .or_else(|| self.extensions().next().map(ToString::to_string))
+      .unwrap_or_default()
+  }
+}
+
+/// A resource to bundle.
+#[derive(Clone, Debug)]
+pub struct BundleResource {
+  /// The path to a file or a folder, or a glob pattern. Excludes the matched files when prefixed with `!`.
+  pub path: String,
+  /// The target triples the resource is bundled for, or `windows`, `macos` and `linux`
+  /// to match every target of the OS. The resource is bundled for every target if empty.
+  pub targets: Vec<String>,
+}
+
+impl BundleResource {
+  /// Whether the resource is bundled for the given target triple.
+  pub fn is_bundled_for(&self, target_triple: &str) -> bool {
+    matches_target(&self.targets, target_triple)
+  }
+}
+
+// Whether the target triple matches one of the targets, or the targets list is empty.
+fn matches_target(targets: &[String], target_triple: &str) -> bool {
+  targets.is_empty()
+    || targets.iter().any(|target| match target.as_str() {
+      "windows" => target_triple.contains("windows"),
+      "macos" => target_triple.contains("apple-darwin"),
+      "linux" => target_triple.contains("linux"),
+      triple => triple == target_triple,
+    })
+}
+
 /// A binary to bundle.
 #[derive(Clone, Debug)]
 pub struct BundleBinary {
@@ -335,6 +638,12 @@ pub struct Settings {
   bundle_settings: BundleSettings,
   /// the binaries to bundle.
   binaries: Vec<BundleBinary>,
+  /// the target triple of the binaries.
+  target: String,
+  /// the external binaries to bundle, with the target triple suffix.
+  external_bin: Vec<String>,
+  /// the resource patterns of the target.
+  resources: Vec<String>,
 }
 
 /// A builder for [`Settings`].
@@ -346,6 +655,7 @@ pub struct SettingsBuilder {
   package_settings: Option<PackageSettings>,
   bundle_settings: BundleSettings,
   binaries: Vec<BundleBinary>,
+  target: Option<String>,
 }
 
 impl SettingsBuilder {
@@ -392,13 +702,24 @@ impl SettingsBuilder {
     self
   }
 
+  /// Sets the target triple of the binaries. Defaults to the host triple.
+  pub fn target(mut self, target: String) -> Self {
+    self.target.replace(target);
+    self
+  }
+
   /// Builds a Settings from the CLI args.
   ///
   /// Package settings will be read from Cargo.toml.
   ///
   /// Bundle settings will be read from from $TAURI_DIR/tauri.conf.json if it exists and fallback to Cargo.toml's [package.metadata.bundle].
   pub fn build(self) -> crate::Result<Settings> {
-    let bundle_settings = parse_external_bin(self.bundle_settings)?;
+    let target = match self.target {
+      Some(target) => target,
+      None => target_triple()?,
+    };
+    let external_bin = parse_external_bin(&self.bundle_settings, &target)?;
+    let resources = parse_resources(&self.bundle_settings, &target)?;
 
     Ok(Settings {
       package: self.package_settings.expect("package settings is required"),
@@ -408,7 +729,10 @@ impl SettingsBuilder {
         .project_out_directory
         .expect("out directory is required"),
       binaries: self.binaries,
-      bundle_settings,
+      bundle_settings: self.bundle_settings,
+      target,
+      external_bin,
+      resources,
     })
   }
 }
@@ -419,9 +743,25 @@ impl Settings {
     &self.project_out_directory
   }
 
+  /// Returns the target triple of the binaries being bundled.
+  pub fn target(&self) -> &str {
+    &self.target
+  }
+
   /// Returns the architecture for the binary being bundled (e.g. "arm", "x86" or "x86_64").
+  ///
+  /// The `universal-apple-darwin` target bundles the Apple Silicon and Intel binaries merged with `lipo`, returning "universal".
   pub fn binary_arch(&self) -> &str {
-    std::env::consts::ARCH
+    let arch = self.target.split('-').next().unwrap_or_default();
+    if arch.starts_with("arm") {
+      "arm"
+    } else if arch == "i686" || arch == "i586" {
+      "x86"
+    } else if arch == "x86_64" || arch == "aarch64" || arch == "universal" {
+      arch
+    } else {
+      std::env::consts::ARCH
+    }
   }
 
   /// Returns the file name of the binary being bundled.
@@ -457,7 +797,21 @@ impl Settings {
   ///
   /// Fails if the host/target's native package type is not supported.
   pub fn package_types(&self) -> crate::Result<Vec<PackageType>> {
-    let target_os = std::env::consts::OS;
+    let target_os = target_os(&self.target).unwrap_or(std::env::consts::OS);
+    // the bundles are created with the tools of the target platform
+    let bundle_os = if target_os == "ios" {
+      "macos"
+    } else {
+      target_os
+    };
+    if bundle_os != std::env::consts::OS {
+      return Err(crate::Error::GenericError(format!(
+        "{} bundles can't be created on {}, build the bundles on {} or skip them with `--bundles none`",
+        self.target,
+        std::env::consts::OS,
+        bundle_os
+      )));
+    }
     let mut platform_types = match target_os {
       "macos" => vec![PackageType::MacOsBundle, PackageType::Dmg],
       "ios" => vec![PackageType::IosBundle],
@@ -471,6 +825,13 @@ impl Settings {
       }
     };
 
+    // the bundles built only when requested, since they need extra tooling
+    let opt_in_types = match target_os {
+      "windows" => vec![PackageType::Nsis, PackageType::Portable],
+      "linux" => vec![PackageType::Flatpak, PackageType::Snap],
+      _ => vec![],
+    };
+
     // add updater if needed
     if self.is_update_enabled() {
       platform_types.push(PackageType::Updater)
@@ -481,9 +842,9 @@ impl Settings {
       for package_type in package_types {
         let package_type = *package_type;
         if platform_types
-          .clone()
-          .into_iter()
-          .any(|t| t == package_type)
+          .iter()
+          .chain(&opt_in_types)
+          .any(|t| *t == package_type)
         {
           types.push(package_type);
         }
@@ -520,19 +881,28 @@ impl Settings {
   /// Returns an iterator over the resource files to be included in this
   /// bundle.
   pub fn resource_files(&self) -> ResourcePaths<'_> {
-    match self.bundle_settings.resources {
-      Some(ref paths) => ResourcePaths::new(paths.as_slice(), true),
-      None => ResourcePaths::new(&[], true),
-    }
+    ResourcePaths::new(self.resources.as_slice(), true)
   }
 
   /// Returns an iterator over the external binaries to be included in this
   /// bundle.
   pub fn external_binaries(&self) -> ResourcePaths<'_> {
-    match self.bundle_settings.external_bin {
-      Some(ref paths) => ResourcePaths::new(paths.as_slice(), true),
-      None => ResourcePaths::new(&[], true),
-    }
+    ResourcePaths::new(self.external_bin.as_slice(), true)
+  }
+
+  /// Whether the app verifies the sidecar checksums at startup, so the sidecars must not be signed.
+  pub fn verify_sidecar_checksums(&self) -> bool {
+    self.bundle_settings.verify_sidecar_checksums
+  }
+
+  /// Returns the URL schemes registered for the app.
+  pub fn url_schemes(&self) -> &[String] {
+    &self.bundle_settings.url_schemes
+  }
+
+  /// Returns the file types opened by the app.
+  pub fn file_associations(&self) -> &[FileAssociation] {
+    &self.bundle_settings.file_associations
   }
 
   /// Copies external binaries to a path.
@@ -616,6 +986,21 @@ impl Settings {
     &self.bundle_settings.deb
   }
 
+  /// Returns the AppImage settings.
+  pub fn appimage(&self) -> &AppImageSettings {
+    &self.bundle_settings.appimage
+  }
+
+  /// Returns the Flatpak settings.
+  pub fn flatpak(&self) -> &FlatpakSettings {
+    &self.bundle_settings.flatpak
+  }
+
+  /// Returns the Snap settings.
+  pub fn snap(&self) -> &SnapSettings {
+    &self.bundle_settings.snap
+  }
+
   /// Returns the MacOS settings.
   pub fn macos(&self) -> &MacOsSettings {
     &self.bundle_settings.macos
@@ -655,35 +1040,61 @@ impl Settings {
   }
 }
 
-/// Parses the external binaries to bundle, adding the target triple suffix to each of them.
-fn parse_external_bin(bundle_settings: BundleSettings) -> crate::Result<BundleSettings> {
-  let target_triple = target_triple()?;
-  let mut win_paths = Vec::new();
-  let external_bin = match bundle_settings.external_bin {
-    Some(paths) => {
-      for curr_path in paths.iter() {
-        win_paths.push(format!(
-          "{}-{}{}",
-          curr_path,
-          target_triple,
-          if cfg!(windows) { ".exe" } else { "" }
-        ));
+/// Parses the external binaries to bundle for the target, adding the target triple suffix to each of them.
+fn parse_external_bin(
+  bundle_settings: &BundleSettings,
+  target_triple: &str,
+) -> crate::Result<Vec<String>> {
+  let mut paths = Vec::new();
+  for bin in bundle_settings.external_bin.iter().flatten() {
+    if !bin.is_bundled_for(target_triple) {
+      continue;
+    }
+    let path = format!(
+      "{}-{}{}",
+      bin.path,
+      target_triple,
+      if target_triple.contains("windows") {
+        ".exe"
+      } else {
+        ""
       }
-      Some(win_paths)
+    );
+    if !Path::new(&path).exists() {
+      return Err(crate::Error::GenericError(format!(
+        "the `{}` sidecar is missing for the `{}` target, expected the `{}` binary",
+        bin.path, target_triple, path
+      )));
     }
-    None => Some(vec![]),
-  };
+    paths.push(path);
+  }
+  Ok(paths)
+}
 
-  Ok(BundleSettings {
-    external_bin,
-    ..bundle_settings
-  })
+/// Parses the resource patterns bundled for the target, validating the exclude patterns.
+fn parse_resources(
+  bundle_settings: &BundleSettings,
+  target_triple: &str,
+) -> crate::Result<Vec<String>> {
+  let mut patterns = Vec::new();
+  for resource in bundle_settings.resources.iter().flatten() {
+    if !resource.is_bundled_for(target_triple) {
+      continue;
+    }
+    if let Some(exclude) = resource.path.strip_prefix('!') {
+      glob::Pattern::new(exclude)?;
+    }
+    patterns.push(resource.path.clone());
+  }
+  Ok(patterns)
 }
 
 /// A helper to iterate through resources.
 pub struct ResourcePaths<'a> {
   /// the patterns to iterate.
   pattern_iter: std::slice::Iter<'a, String>,
+  /// the patterns of the paths to skip, from the patterns prefixed with `!`.
+  excludes: Vec<glob::Pattern>,
   /// the glob iterator if the path from the current iteration is a glob pattern.
   glob_iter: Option<glob::Paths>,
   /// the walkdir iterator if the path from the current iteration is a directory.
@@ -701,6 +1112,11 @@ impl<'a> ResourcePaths<'a> {
   fn new(patterns: &'a [String], allow_walk: bool) -> ResourcePaths<'a> {
     ResourcePaths {
       pattern_iter: patterns.iter(),
+      excludes: patterns
+        .iter()
+        .filter_map(|pattern| pattern.strip_prefix('!'))
+        .filter_map(|pattern| glob::Pattern::new(pattern).ok())
+        .collect(),
       glob_iter: None,
       walk_iter: None,
       allow_walk,
@@ -708,6 +1124,14 @@ impl<'a> ResourcePaths<'a> {
       current_pattern_is_valid: false,
     }
   }
+
+  /// Whether the path matches one of the exclude patterns.
+  fn is_excluded(&self, path: &Path) -> bool {
+    self
+      .excludes
+      .iter()
+      .any(|exclude| exclude.matches_path(path))
+  }
 }
 
 impl<'a> Iterator for ResourcePaths<'a> {
@@ -726,6 +1150,9 @@ impl<'a> Iterator for ResourcePaths<'a> {
             continue;
           }
           self.current_pattern_is_valid = true;
+          if self.is_excluded(path) {
+            continue;
+          }
           return Some(Ok(path.to_path_buf()));
         }
       }
@@ -747,6 +1174,9 @@ impl<'a> Iterator for ResourcePaths<'a> {
             }
           }
           self.current_pattern_is_valid = true;
+          if self.is_excluded(&path) {
+            continue;
+          }
           return Some(Ok(path));
         } else if let Some(current_path) = &self.current_pattern {
           if !self.current_pattern_is_valid {
@@ -759,6 +1189,9 @@ impl<'a> Iterator for ResourcePaths<'a> {
       }
       self.glob_iter = None;
       if let Some(pattern) = self.pattern_iter.next() {
+        if pattern.starts_with('!') {
+          continue;
+        }
         self.current_pattern = Some(pattern.to_string());
         self.current_pattern_is_valid = false;
         let glob = match glob::glob(pattern) {
@@ -772,3 +1205,29 @@ impl<'a> Iterator for ResourcePaths<'a> {
     }
   }
 }
+
+#[cfg(test)]
+mod tests {
+  use super::ResourcePaths;
+  use std::fs::{create_dir_all, write};
+
+  #[test]
+  fn resource_paths_excludes() {
+    let tmp = tempfile::tempdir().expect("unable to create tempdir");
+    let dir = tmp.path().join("assets");
+    create_dir_all(dir.join("sub")).expect("unable to create dir");
+    for file in &["a.txt", "sub/b.psd", "sub/c.txt"] {
+      write(dir.join(file), "").expect("unable to write file");
+    }
+
+    let patterns = vec![
+      dir.to_string_lossy().to_string(),
+      format!("!{}/**/*.psd", dir.display()),
+    ];
+    let mut paths = ResourcePaths::new(&patterns, true)
+      .collect::<crate::Result<Vec<_>>>()
+      .expect("failed to iterate the resources");
+    paths.sort();
+    assert_eq!(paths, vec![dir.join("a.txt"), dir.join("sub/c.txt")]);
+  }
+}
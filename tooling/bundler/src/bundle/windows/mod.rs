@@ -1,2 +1,4 @@
 pub mod msi;
+pub mod nsis;
+pub mod portable;
 pub mod sign;
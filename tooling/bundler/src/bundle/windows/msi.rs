@@ -9,7 +9,7 @@ use crate::Settings;
 use std::{self, path::PathBuf};
 
 /// Runs all of the commands to build the MSI installer.
-/// Returns a vector of PathBuf that shows where the MSI was created.
+/// Returns a vector of PathBuf that shows where the MSIs were created, one per language.
 pub fn bundle_project(settings: &Settings) -> crate::Result<Vec<PathBuf>> {
   let wix_path = PathBuf::from("./WixTools");
 
@@ -17,7 +17,5 @@ pub fn bundle_project(settings: &Settings) -> crate::Result<Vec<PathBuf>> {
     wix::get_and_extract_wix(&wix_path)?;
   }
 
-  let msi_path = wix::build_wix_app_installer(settings, &wix_path)?;
-
-  Ok(vec![msi_path])
+  wix::build_wix_app_installer(settings, &wix_path)
 }
@@ -2,11 +2,11 @@
 // SPDX-License-Identifier: Apache-2.0
 // SPDX-License-Identifier: MIT
 
-use super::super::sign::{sign, SignParams};
+use super::super::sign::{sign_app_binaries, sign_with_settings};
 use crate::bundle::{
   common,
   path_utils::{copy_file, FileOpts},
-  settings::Settings,
+  settings::{Settings, WixInstallScope},
 };
 
 use handlebars::{to_json, Handlebars};
@@ -49,8 +49,31 @@ const UUID_NAMESPACE: [u8; 16] = [
   0xfd, 0x85, 0x95, 0xa8, 0x17, 0xa3, 0x47, 0x4e, 0xa6, 0x16, 0x76, 0x14, 0x8d, 0xfa, 0x0c, 0x7b,
 ];
 
-/// Mapper between a resource directory name and its ResourceDirectory descriptor.
-type ResourceMap = BTreeMap<String, ResourceDirectory>;
+/// The installer languages supported by the WixUI dialogs, as (culture, LCID, codepage) tuples.
+const WIX_LANGUAGES: &[(&str, u32, u32)] = &[
+  ("cs-CZ", 1029, 1250),
+  ("da-DK", 1030, 1252),
+  ("de-DE", 1031, 1252),
+  ("en-US", 1033, 1252),
+  ("es-ES", 3082, 1252),
+  ("fi-FI", 1035, 1252),
+  ("fr-FR", 1036, 1252),
+  ("hu-HU", 1038, 1250),
+  ("it-IT", 1040, 1252),
+  ("ja-JP", 1041, 932),
+  ("ko-KR", 1042, 949),
+  ("nb-NO", 1044, 1252),
+  ("nl-NL", 1043, 1252),
+  ("pl-PL", 1045, 1250),
+  ("pt-BR", 1046, 1252),
+  ("pt-PT", 2070, 1252),
+  ("ru-RU", 1049, 1251),
+  ("sv-SE", 1053, 1252),
+  ("tr-TR", 1055, 1254),
+  ("uk-UA", 1058, 1251),
+  ("zh-CN", 2052, 936),
+  ("zh-TW", 1028, 950),
+];
 
 /// A binary to bundle with WIX.
 /// External binaries or additional project binaries are represented with this data structure.
@@ -65,6 +88,31 @@ struct Binary {
   path: String,
 }
 
+/// A registry value to write with WIX.
+#[derive(Serialize)]
+struct RegistryValue {
+  /// the GUID of the component holding the value.
+  guid: String,
+  /// the id of the component holding the value.
+  id: String,
+  root: String,
+  key: String,
+  name: Option<String>,
+  #[serde(rename = "type")]
+  value_type: String,
+  value: String,
+}
+
+/// A file extension to associate with the app on the WIX XML.
+#[derive(Serialize)]
+struct FileAssociation {
+  /// the ProgId of the file type.
+  prog_id: String,
+  extension: String,
+  description: String,
+  mime_type: Option<String>,
+}
+
 /// A Resource file to bundle with WIX.
 /// This data structure is needed because WIX requires each path to have its own `id` and `guid`.
 #[derive(Serialize, Clone)]
@@ -97,6 +145,32 @@ impl ResourceDirectory {
     self.files.push(file);
   }
 
+  /// Adds a file to the directory at the given path relative to this one, creating the missing directories.
+  fn add_file_at(&mut self, directories: &[String], file: ResourceFile) {
+    match directories.split_first() {
+      None => self.add_file(file),
+      Some((name, rest)) => {
+        let index = match self.directories.iter().position(|d| &d.name == name) {
+          Some(index) => index,
+          None => {
+            self.directories.push(ResourceDirectory {
+              path: if self.path.is_empty() {
+                name.clone()
+              } else {
+                format!("{}\\{}", self.path, name)
+              },
+              name: name.clone(),
+              directories: vec![],
+              files: vec![],
+            });
+            self.directories.len() - 1
+          }
+        };
+        self.directories[index].add_file_at(rest, file);
+      }
+    }
+  }
+
   /// Generates the wix XML string to bundle this directory resources recursively
   fn get_wix_data(self) -> crate::Result<(String, Vec<String>)> {
     let mut files = String::from("");
@@ -315,6 +389,7 @@ fn run_light(
   build_path: &Path,
   wixobjs: &[&str],
   output_path: &Path,
+  culture: &str,
   settings: &Settings,
 ) -> crate::Result<PathBuf> {
   let light_exe = wix_toolset_path.join("light.exe");
@@ -322,10 +397,18 @@ fn run_light(
   let mut args: Vec<String> = vec![
     "-ext".to_string(),
     "WixUIExtension".to_string(),
+    format!("-cultures:{}", culture.to_lowercase()),
     "-o".to_string(),
     output_path.display().to_string(),
   ];
 
+  if is_per_user(settings) {
+    // per-user components live on the user profile, which these ICEs expect to be keyed by HKCU values
+    for ice in &["ICE38", "ICE64", "ICE91"] {
+      args.push(format!("-sice:{}", ice));
+    }
+  }
+
   for p in wixobjs {
     args.push((*p).to_string());
   }
@@ -355,11 +438,38 @@ fn run_light(
 //   Ok(())
 // }
 
-// Entry point for bundling and creating the MSI installer. For now the only supported platform is Windows x64.
+fn is_per_user(settings: &Settings) -> bool {
+  settings
+    .windows()
+    .wix
+    .as_ref()
+    .map(|wix| wix.install_scope == WixInstallScope::PerUser)
+    .unwrap_or_default()
+}
+
+// Finds the LCID and codepage of the installer language.
+fn language_data(culture: &str) -> crate::Result<(u32, u32)> {
+  WIX_LANGUAGES
+    .iter()
+    .find(|(name, _, _)| name.eq_ignore_ascii_case(culture))
+    .map(|(_, lcid, codepage)| (*lcid, *codepage))
+    .ok_or_else(|| {
+      crate::Error::GenericError(format!(
+        "unsupported WiX language `{}`, expected one of {:?}",
+        culture,
+        WIX_LANGUAGES
+          .iter()
+          .map(|(name, _, _)| *name)
+          .collect::<Vec<_>>()
+      ))
+    })
+}
+
+// Entry point for bundling and creating the MSI installers, one per language. For now the only supported platform is Windows x64.
 pub fn build_wix_app_installer(
   settings: &Settings,
   wix_toolset_path: &Path,
-) -> crate::Result<PathBuf> {
+) -> crate::Result<Vec<PathBuf>> {
   let arch = match settings.binary_arch() {
     "x86_64" => "x64",
     "x86" => "x86",
@@ -381,26 +491,7 @@ pub fn build_wix_app_installer(
     .ok_or_else(|| anyhow::anyhow!("Failed to get main binary"))?;
   let app_exe_source = settings.binary_path(main_binary);
 
-  if let Some(certificate_thumbprint) = &settings.windows().certificate_thumbprint {
-    common::print_info("signing app")?;
-    sign(
-      &app_exe_source,
-      &SignParams {
-        digest_algorithm: settings
-          .windows()
-          .digest_algorithm
-          .as_ref()
-          .map(|algorithm| algorithm.to_string())
-          .unwrap_or_else(|| "sha256".to_string()),
-        certificate_thumbprint: certificate_thumbprint.to_string(),
-        timestamp_url: settings
-          .windows()
-          .timestamp_url
-          .as_ref()
-          .map(|url| url.to_string()),
-      },
-    )?;
-  }
+  sign_app_binaries(settings)?;
 
   let output_path = settings.project_out_directory().join("wix").join(arch);
 
@@ -410,11 +501,26 @@ pub fn build_wix_app_installer(
   data.insert("version", to_json(settings.version_string()));
   let manufacturer = settings.bundle_identifier().to_string();
   data.insert("manufacturer", to_json(manufacturer.as_str()));
-  let upgrade_code = Uuid::new_v5(
-    &Uuid::NAMESPACE_DNS,
-    format!("{}.app.x64", &settings.main_binary_name()).as_bytes(),
-  )
-  .to_string();
+  let upgrade_code = match settings
+    .windows()
+    .wix
+    .as_ref()
+    .and_then(|wix| wix.upgrade_code.as_ref())
+  {
+    Some(upgrade_code) => Uuid::parse_str(upgrade_code)
+      .map_err(|e| {
+        crate::Error::GenericError(format!(
+          "invalid WiX upgrade code `{}`: {}",
+          upgrade_code, e
+        ))
+      })?
+      .to_string(),
+    None => Uuid::new_v5(
+      &Uuid::NAMESPACE_DNS,
+      format!("{}.app.x64", &settings.main_binary_name()).as_bytes(),
+    )
+    .to_string(),
+  };
 
   data.insert("upgrade_code", to_json(&upgrade_code.as_str()));
 
@@ -426,22 +532,14 @@ pub fn build_wix_app_installer(
 
   let app_exe_name = settings.main_binary_name().to_string();
   data.insert("app_exe_name", to_json(&app_exe_name));
+  data.insert("url_schemes", to_json(settings.url_schemes()));
 
   let binaries = generate_binaries_data(settings)?;
 
   let binaries_json = to_json(&binaries);
   data.insert("binaries", binaries_json);
 
-  let resources = generate_resource_data(settings)?;
-  let mut resources_wix_string = String::from("");
-  let mut files_ids = Vec::new();
-  for (_, dir) in resources {
-    let (wix_string, ids) = dir.get_wix_data()?;
-    resources_wix_string.push_str(wix_string.as_str());
-    for id in ids {
-      files_ids.push(id);
-    }
-  }
+  let (resources_wix_string, files_ids) = generate_resource_data(settings)?.get_wix_data()?;
 
   data.insert("resources", to_json(resources_wix_string));
   data.insert("resource_file_ids", to_json(files_ids));
@@ -460,6 +558,33 @@ pub fn build_wix_app_installer(
   let mut handlebars = Handlebars::new();
   let mut has_custom_template = false;
   let mut install_webview = true;
+  let mut languages = vec!["en-US".to_string()];
+
+  let file_association =
+    |extension: &str, description: Option<&String>, mime_type: Option<&String>| {
+      let extension = extension.trim_start_matches('.').to_string();
+      FileAssociation {
+        prog_id: format!("{}.{}", settings.main_binary_name(), extension),
+        description: description
+          .cloned()
+          .unwrap_or_else(|| format!("{} file", settings.product_name())),
+        mime_type: mime_type.cloned(),
+        extension,
+      }
+    };
+  let mut file_associations: Vec<FileAssociation> = settings
+    .file_associations()
+    .iter()
+    .flat_map(|association| {
+      association.extensions().map(move |extension| {
+        file_association(
+          extension,
+          association.description.as_ref(),
+          association.mime_type.as_ref(),
+        )
+      })
+    })
+    .collect();
 
   if let Some(wix) = &settings.windows().wix {
     data.insert("component_group_refs", to_json(&wix.component_group_refs));
@@ -469,6 +594,37 @@ pub fn build_wix_app_installer(
     data.insert("merge_refs", to_json(&wix.merge_refs));
     fragment_paths = wix.fragment_paths.clone();
     install_webview = !wix.skip_webview_install;
+    if !wix.languages.is_empty() {
+      languages = wix.languages.clone();
+    }
+
+    data.insert(
+      "per_user",
+      to_json(wix.install_scope == WixInstallScope::PerUser),
+    );
+
+    let registry_keys: Vec<RegistryValue> = wix
+      .registry_keys
+      .iter()
+      .map(|registry_key| RegistryValue {
+        guid: Uuid::new_v4().to_string(),
+        id: format!("_{}", Uuid::new_v4().to_simple()),
+        root: registry_key.root.clone(),
+        key: registry_key.key.clone(),
+        name: registry_key.name.clone(),
+        value_type: registry_key.value_type.clone(),
+        value: registry_key.value.clone(),
+      })
+      .collect();
+    data.insert("registry_keys", to_json(registry_keys));
+
+    file_associations.extend(wix.file_associations.iter().map(|association| {
+      file_association(
+        &association.extension,
+        association.description.as_ref(),
+        association.mime_type.as_ref(),
+      )
+    }));
 
     if let Some(temp_path) = &wix.template {
       let template = std::fs::read_to_string(temp_path)?;
@@ -479,6 +635,7 @@ pub fn build_wix_app_installer(
       has_custom_template = true;
     }
   }
+  data.insert("file_associations", to_json(file_associations));
 
   if !has_custom_template {
     handlebars
@@ -497,9 +654,6 @@ pub fn build_wix_app_installer(
 
   create_dir_all(&output_path)?;
 
-  let main_wxs_path = output_path.join("main.wxs");
-  write(&main_wxs_path, handlebars.render("main.wxs", &data)?)?;
-
   let mut candle_inputs = vec!["main.wxs".into()];
 
   let current_dir = std::env::current_dir()?;
@@ -507,20 +661,42 @@ pub fn build_wix_app_installer(
     candle_inputs.push(current_dir.join(fragment_path));
   }
 
-  for wxs in &candle_inputs {
-    run_candle(settings, wix_toolset_path, &output_path, wxs)?;
-  }
+  let main_wxs_path = output_path.join("main.wxs");
+  let mut targets = Vec::new();
+  for language in &languages {
+    let (lcid, codepage) = language_data(language)?;
+    data.insert("language", to_json(lcid));
+    data.insert("codepage", to_json(codepage));
+    write(&main_wxs_path, handlebars.render("main.wxs", &data)?)?;
+
+    for wxs in &candle_inputs {
+      run_candle(settings, wix_toolset_path, &output_path, wxs)?;
+    }
 
-  let wixobjs = vec!["*.wixobj"];
-  let target = run_light(
-    wix_toolset_path,
-    &output_path,
-    &wixobjs,
-    &app_installer_dir(settings)?,
-    settings,
-  )?;
+    let mut installer_path = app_installer_dir(settings)?;
+    if languages.len() > 1 {
+      let file_stem = installer_path
+        .file_stem()
+        .expect("failed to get the MSI file name")
+        .to_string_lossy()
+        .to_string();
+      installer_path.set_file_name(format!("{}_{}.msi", file_stem, language));
+    }
 
-  Ok(target)
+    let wixobjs = vec!["*.wixobj"];
+    let target = run_light(
+      wix_toolset_path,
+      &output_path,
+      &wixobjs,
+      &installer_path,
+      language,
+      settings,
+    )?;
+    sign_with_settings(&target, settings)?;
+    targets.push(target);
+  }
+
+  Ok(targets)
 }
 
 /// Generates the data required for the external binaries and extra binaries bundling.
@@ -590,9 +766,9 @@ fn get_merge_modules(settings: &Settings) -> crate::Result<Vec<MergeModule>> {
   Ok(merge_modules)
 }
 
-/// Generates the data required for the resource bundling on wix
-fn generate_resource_data(settings: &Settings) -> crate::Result<ResourceMap> {
-  let mut resources = ResourceMap::new();
+/// Generates the data required for the resource bundling on wix,
+/// laying out the resources like [`common::resource_relpath`] does on the other bundles.
+fn generate_resource_data(settings: &Settings) -> crate::Result<ResourceDirectory> {
   let cwd = std::env::current_dir()?;
 
   let mut dlls = vec![];
@@ -612,17 +788,12 @@ fn generate_resource_data(settings: &Settings) -> crate::Result<ResourceMap> {
       path: resource_path,
     });
   }
-  if !dlls.is_empty() {
-    resources.insert(
-      "".to_string(),
-      ResourceDirectory {
-        path: "".to_string(),
-        name: "".to_string(),
-        directories: vec![],
-        files: dlls,
-      },
-    );
-  }
+  let mut resources = ResourceDirectory {
+    path: "".to_string(),
+    name: "".to_string(),
+    directories: vec![],
+    files: dlls,
+  };
 
   for src in settings.resource_files() {
     let src = src?;
@@ -639,82 +810,17 @@ fn generate_resource_data(settings: &Settings) -> crate::Result<ResourceMap> {
       path: resource_path,
     };
 
-    // split the resource path directories
-    let mut directories = src
-      .components()
-      .filter(|component| {
-        let comp = component.as_os_str();
-        comp != "." && comp != ".."
+    let relpath = common::resource_relpath(&src);
+    let directories = relpath
+      .parent()
+      .map(|parent| {
+        parent
+          .components()
+          .map(|component| component.as_os_str().to_string_lossy().into_owned())
+          .collect::<Vec<_>>()
       })
-      .collect::<Vec<_>>();
-    directories.truncate(directories.len() - 1);
-    // transform the directory structure to a chained vec structure
-    let first_directory = directories
-      .first()
-      .map(|d| d.as_os_str().to_string_lossy().into_owned())
-      .unwrap_or_else(String::new);
-    let last_index = directories.len() - 1;
-    let mut path = String::new();
-    for (i, directory) in directories.into_iter().enumerate() {
-      let directory_name = directory
-        .as_os_str()
-        .to_os_string()
-        .into_string()
-        .expect("failed to read resource folder name");
-      path.push_str(directory_name.as_str());
-
-      // if the directory is already on the map
-      if resources.contains_key(&first_directory) {
-        let directory_entry = &mut resources
-          .get_mut(&first_directory)
-          .expect("Unable to handle resources");
-        if last_index == 0 {
-          // the directory entry is the root of the chain
-          directory_entry.add_file(resource_entry.clone());
-        } else {
-          let index = directory_entry
-            .directories
-            .iter()
-            .position(|f| f.path == path);
-          if let Some(index) = index {
-            // the directory entry is already a part of the chain
-            if i == last_index {
-              let dir = directory_entry
-                .directories
-                .get_mut(index)
-                .expect("Unable to get directory");
-              dir.add_file(resource_entry.clone());
-            }
-          } else {
-            // push it to the chain
-            directory_entry.directories.push(ResourceDirectory {
-              path: path.clone(),
-              name: directory_name.clone(),
-              directories: vec![],
-              files: if i == last_index {
-                vec![resource_entry.clone()]
-              } else {
-                vec![]
-              },
-            });
-          }
-        }
-      } else {
-        resources.insert(
-          directory_name.clone(),
-          ResourceDirectory {
-            path: path.clone(),
-            name: directory_name.clone(),
-            directories: vec![],
-            files: if i == last_index {
-              vec![resource_entry.clone()]
-            } else {
-              vec![]
-            },
-          },
-        );
-      }
-    }
+      .unwrap_or_default();
+    resources.add_file_at(&directories, resource_entry);
   }
 
   Ok(resources)
@@ -0,0 +1,278 @@
+// Copyright 2019-2021 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+use super::sign::{sign_app_binaries, sign_with_settings};
+use crate::bundle::{
+  common,
+  settings::{NsisSettings, Settings},
+};
+
+use handlebars::{to_json, Handlebars};
+use serde::Serialize;
+
+use std::{
+  collections::{BTreeMap, BTreeSet},
+  env::var_os,
+  fs::{create_dir_all, remove_dir_all, write},
+  path::{Path, PathBuf},
+  process::{Command, Stdio},
+};
+
+/// A file copied to the install directory.
+#[derive(Serialize)]
+pub(super) struct InstallFile {
+  /// the directory relative to `$INSTDIR`, with Windows separators.
+  directory: String,
+  /// the path relative to `$INSTDIR`, with Windows separators.
+  target: String,
+  /// the source file path.
+  source: String,
+}
+
+/// A file extension associated with the app, written to the registry.
+#[derive(Serialize)]
+struct FileAssociation {
+  /// the ProgId of the file type.
+  prog_id: String,
+  extension: String,
+  description: String,
+  mime_type: Option<String>,
+}
+
+/// Runs all of the commands to build the NSIS installer.
+/// Returns a vector of PathBuf that shows where the installer was created.
+pub fn bundle_project(settings: &Settings) -> crate::Result<Vec<PathBuf>> {
+  let arch = match settings.binary_arch() {
+    "x86_64" => "x64",
+    "x86" => "x86",
+    target => {
+      return Err(crate::Error::ArchError(format!(
+        "unsupported target: {}",
+        target
+      )))
+    }
+  };
+  common::print_info(format!("Target: {}", arch).as_str())?;
+
+  let makensis = find_makensis()?;
+
+  let main_binary = settings
+    .binaries()
+    .iter()
+    .find(|bin| bin.main())
+    .ok_or_else(|| anyhow::anyhow!("Failed to get main binary"))?;
+  let app_exe_source = settings.binary_path(main_binary);
+  sign_app_binaries(settings)?;
+
+  let output_path = settings.project_out_directory().join("nsis").join(arch);
+  if output_path.exists() {
+    remove_dir_all(&output_path)?;
+  }
+  create_dir_all(&output_path)?;
+
+  let installer_path = settings.project_out_directory().join(format!(
+    "bundle/nsis/{}_{}_{}-setup.exe",
+    settings.main_binary_name().replace(".exe", ""),
+    settings.version_string(),
+    arch
+  ));
+  create_dir_all(installer_path.parent().expect("invalid installer path"))?;
+
+  let default_settings = NsisSettings::default();
+  let nsis = settings
+    .windows()
+    .nsis
+    .as_ref()
+    .unwrap_or(&default_settings);
+
+  let main_binary_name = app_exe_source
+    .file_name()
+    .expect("failed to get the main binary name")
+    .to_string_lossy()
+    .to_string();
+  let files = install_files(settings)?;
+  // the directories to remove on uninstall, the deepest ones first
+  let mut directories: Vec<String> = files
+    .iter()
+    .flat_map(|file| Path::new(&file.target).ancestors().skip(1))
+    .map(|directory| directory.to_string_lossy().replace('/', "\\"))
+    .filter(|directory| !directory.is_empty())
+    .collect::<BTreeSet<_>>()
+    .into_iter()
+    .collect();
+  directories.sort_by_key(|directory| std::cmp::Reverse(directory.matches('\\').count()));
+
+  let install_directory = nsis.install_directory.clone().unwrap_or_else(|| {
+    let base = if nsis.per_user {
+      "$LOCALAPPDATA\\Programs"
+    } else if arch == "x64" {
+      "$PROGRAMFILES64"
+    } else {
+      "$PROGRAMFILES"
+    };
+    format!("{}\\{}", base, settings.product_name())
+  });
+
+  let mut data = BTreeMap::new();
+  data.insert("product_name", to_json(settings.product_name()));
+  data.insert("version", to_json(settings.version_string()));
+  data.insert("manufacturer", to_json(settings.bundle_identifier()));
+  data.insert("x64", to_json(arch == "x64"));
+  data.insert("out_file", to_json(&installer_path));
+  data.insert("install_directory", to_json(install_directory));
+  data.insert("per_user", to_json(nsis.per_user));
+  data.insert("silent", to_json(nsis.silent));
+  data.insert("install_webview", to_json(!nsis.skip_webview_install));
+  data.insert("main_binary_name", to_json(&main_binary_name));
+  data.insert("main_binary_path", to_json(&app_exe_source));
+  data.insert("files", to_json(&files));
+  data.insert("directories", to_json(&directories));
+  data.insert("url_schemes", to_json(settings.url_schemes()));
+  let file_associations: Vec<FileAssociation> = settings
+    .file_associations()
+    .iter()
+    .flat_map(|association| {
+      association
+        .extensions()
+        .map(move |extension| FileAssociation {
+          prog_id: format!("{}.{}", settings.main_binary_name(), extension),
+          extension: extension.to_string(),
+          description: association
+            .description
+            .clone()
+            .unwrap_or_else(|| format!("{} file", settings.product_name())),
+          mime_type: association.mime_type.clone(),
+        })
+    })
+    .collect();
+  data.insert("file_associations", to_json(file_associations));
+  data.insert(
+    "icon_path",
+    to_json(std::env::current_dir()?.join(&settings.windows().icon_path)),
+  );
+  if let Some(license) = &nsis.license {
+    data.insert("license", to_json(std::env::current_dir()?.join(license)));
+  }
+
+  let mut handlebars = Handlebars::new();
+  // NSIS scripts aren't HTML, the values are quoted by the template
+  handlebars.register_escape_fn(handlebars::no_escape);
+  handlebars
+    .register_template_string("installer.nsi", include_str!("templates/installer.nsi"))
+    .map_err(|e| e.to_string())
+    .expect("Failed to setup handlebar template");
+  let script_path = output_path.join("installer.nsi");
+  // makensis reads the script as UTF-8 with a BOM
+  let mut script = vec![0xEF, 0xBB, 0xBF];
+  script.extend(handlebars.render("installer.nsi", &data)?.into_bytes());
+  write(&script_path, script)?;
+
+  run_makensis(&makensis, &script_path, settings)?;
+  sign_with_settings(&installer_path, settings)?;
+
+  Ok(vec![installer_path])
+}
+
+// Finds the NSIS compiler on the PATH or on the default install directories.
+pub(super) fn find_makensis() -> crate::Result<PathBuf> {
+  let on_path = Command::new("makensis")
+    .arg("/VERSION")
+    .stdout(Stdio::null())
+    .status()
+    .map(|status| status.success())
+    .unwrap_or_default();
+  if on_path {
+    return Ok(PathBuf::from("makensis"));
+  }
+  ["ProgramFiles(x86)", "ProgramFiles"]
+    .iter()
+    .filter_map(var_os)
+    .map(|program_files| {
+      PathBuf::from(program_files)
+        .join("NSIS")
+        .join("makensis.exe")
+    })
+    .find(|path| path.exists())
+    .ok_or_else(|| {
+      crate::Error::GenericError(
+        "makensis not found, install NSIS from https://nsis.sourceforge.io/Download".into(),
+      )
+    })
+}
+
+/// Generates the list of the files to install besides the main binary: the external binaries,
+/// the additional project binaries, the DLLs next to the main binary and the resources.
+pub(super) fn install_files(settings: &Settings) -> crate::Result<Vec<InstallFile>> {
+  let cwd = std::env::current_dir()?;
+  let mut files = Vec::new();
+  let mut push = |target: &Path, source: PathBuf| {
+    let directory = target
+      .parent()
+      .map(|parent| parent.to_string_lossy().replace('/', "\\"))
+      .unwrap_or_default();
+    files.push(InstallFile {
+      directory,
+      target: target.to_string_lossy().replace('/', "\\"),
+      source: source.to_string_lossy().to_string(),
+    });
+  };
+
+  for src in settings.external_binaries() {
+    let src = src?;
+    let file_name = src.file_name().expect("failed to get the binary name");
+    push(Path::new(file_name), cwd.join(&src));
+  }
+
+  for bin in settings.binaries() {
+    if !bin.main() {
+      let path = settings.binary_path(bin);
+      let file_name = path.file_name().expect("failed to get the binary name");
+      push(Path::new(file_name), path.clone());
+    }
+  }
+
+  for dll in glob::glob(
+    settings
+      .project_out_directory()
+      .join("*.dll")
+      .to_string_lossy()
+      .to_string()
+      .as_str(),
+  )? {
+    let dll = dll?;
+    let file_name = dll.file_name().expect("failed to get the DLL name");
+    push(Path::new(file_name), dll.clone());
+  }
+
+  for src in settings.resource_files() {
+    let src = src?;
+    push(&common::resource_relpath(&src), cwd.join(&src));
+  }
+
+  Ok(files)
+}
+
+pub(super) fn run_makensis(
+  makensis: &Path,
+  script_path: &Path,
+  settings: &Settings,
+) -> crate::Result<()> {
+  let mut cmd = Command::new(makensis);
+  cmd
+    .arg(if settings.is_verbose() { "/V4" } else { "/V2" })
+    .arg(script_path)
+    .stdout(Stdio::piped());
+
+  common::print_info("running makensis")?;
+  common::execute_with_verbosity(&mut cmd, settings).map_err(|_| {
+    crate::Error::ShellScriptError(format!(
+      "error running makensis{}",
+      if settings.is_verbose() {
+        ""
+      } else {
+        ", try running with --verbose to see command output"
+      }
+    ))
+  })
+}
@@ -0,0 +1,121 @@
+// Copyright 2019-2021 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+// The portable executable is a NSIS launcher that extracts the app and its resources
+// to a temporary directory, runs it and removes it on exit.
+// The app data is stored on the directory set on the `TAURI_PORTABLE_DATA_DIR` environment variable.
+
+use super::{
+  nsis::{find_makensis, install_files, run_makensis},
+  sign::{sign_app_binaries, sign_with_settings},
+};
+use crate::bundle::{
+  common,
+  settings::{PortableSettings, Settings},
+};
+
+use handlebars::{to_json, Handlebars};
+
+use std::{
+  collections::BTreeMap,
+  fs::{create_dir_all, remove_dir_all, write},
+  path::{Path, PathBuf},
+};
+
+/// The environment variable read by the app to find its data directory.
+const DATA_DIRECTORY_ENV: &str = "TAURI_PORTABLE_DATA_DIR";
+
+/// Runs all of the commands to build the portable executable.
+/// Returns a vector of PathBuf that shows where the executable was created.
+pub fn bundle_project(settings: &Settings) -> crate::Result<Vec<PathBuf>> {
+  let arch = match settings.binary_arch() {
+    "x86_64" => "x64",
+    "x86" => "x86",
+    target => {
+      return Err(crate::Error::ArchError(format!(
+        "unsupported target: {}",
+        target
+      )))
+    }
+  };
+  common::print_info(format!("Target: {}", arch).as_str())?;
+
+  let makensis = find_makensis()?;
+
+  let main_binary = settings
+    .binaries()
+    .iter()
+    .find(|bin| bin.main())
+    .ok_or_else(|| anyhow::anyhow!("Failed to get main binary"))?;
+  let app_exe_source = settings.binary_path(main_binary);
+  sign_app_binaries(settings)?;
+
+  let output_path = settings.project_out_directory().join("portable").join(arch);
+  if output_path.exists() {
+    remove_dir_all(&output_path)?;
+  }
+  create_dir_all(&output_path)?;
+
+  let executable_path = settings.project_out_directory().join(format!(
+    "bundle/portable/{}_{}_{}-portable.exe",
+    settings.main_binary_name().replace(".exe", ""),
+    settings.version_string(),
+    arch
+  ));
+  create_dir_all(executable_path.parent().expect("invalid executable path"))?;
+  common::print_bundling(executable_path.file_name().unwrap().to_str().unwrap())?;
+
+  let default_settings = PortableSettings::default();
+  let portable = settings
+    .windows()
+    .portable
+    .as_ref()
+    .unwrap_or(&default_settings);
+  let data_directory = portable
+    .data_directory
+    .clone()
+    .unwrap_or_else(|| format!("{} Data", settings.product_name()));
+  let data_directory = if Path::new(&data_directory).is_absolute() {
+    data_directory
+  } else {
+    format!("$EXEDIR\\{}", data_directory.replace('/', "\\"))
+  };
+
+  let main_binary_name = app_exe_source
+    .file_name()
+    .expect("failed to get the main binary name")
+    .to_string_lossy()
+    .to_string();
+
+  let mut data = BTreeMap::new();
+  data.insert("product_name", to_json(settings.product_name()));
+  data.insert("out_file", to_json(&executable_path));
+  data.insert("main_binary_name", to_json(&main_binary_name));
+  data.insert("main_binary_path", to_json(&app_exe_source));
+  data.insert("files", to_json(install_files(settings)?));
+  data.insert("data_directory_env", to_json(DATA_DIRECTORY_ENV));
+  data.insert("data_directory", to_json(data_directory));
+  data.insert(
+    "icon_path",
+    to_json(std::env::current_dir()?.join(&settings.windows().icon_path)),
+  );
+
+  let mut handlebars = Handlebars::new();
+  // NSIS scripts aren't HTML, the values are quoted by the template
+  handlebars.register_escape_fn(handlebars::no_escape);
+  handlebars
+    .register_template_string("portable.nsi", include_str!("templates/portable.nsi"))
+    .map_err(|e| e.to_string())
+    .expect("Failed to setup handlebar template");
+  let script_path = output_path.join("portable.nsi");
+  // makensis reads the script as UTF-8 with a BOM
+  let mut script = vec![0xEF, 0xBB, 0xBF];
+  script.extend(handlebars.render("portable.nsi", &data)?.into_bytes());
+  write(&script_path, script)?;
+
+  run_makensis(&makensis, &script_path, settings)?;
+  sign_with_settings(&executable_path, settings)?;
+
+  Ok(vec![executable_path])
+}
@@ -3,7 +3,7 @@ use std::{
   process::Command,
 };
 
-use crate::bundle::common;
+use crate::{bundle::common, Settings};
 
 use bitness::{self, Bitness};
 use winreg::{
@@ -115,3 +115,107 @@ pub fn sign<P: AsRef<Path>>(path: P, params: &SignParams) -> crate::Result<()> {
 
   Ok(())
 }
+
+/// The AzureSignTool parameters, read from the `AZURE_KEY_VAULT_URI`, `AZURE_CLIENT_ID`, `AZURE_TENANT_ID`,
+/// `AZURE_CLIENT_SECRET` and `AZURE_CERTIFICATE_NAME` environment variables.
+struct AzureSignParams {
+  key_vault_uri: String,
+  client_id: String,
+  tenant_id: String,
+  client_secret: String,
+  certificate_name: String,
+}
+
+impl AzureSignParams {
+  fn from_env() -> Option<Self> {
+    let var = |name: &str| std::env::var(name).ok().filter(|value| !value.is_empty());
+    Some(Self {
+      key_vault_uri: var("AZURE_KEY_VAULT_URI")?,
+      client_id: var("AZURE_CLIENT_ID")?,
+      tenant_id: var("AZURE_TENANT_ID")?,
+      client_secret: var("AZURE_CLIENT_SECRET")?,
+      certificate_name: var("AZURE_CERTIFICATE_NAME")?,
+    })
+  }
+}
+
+// Signs the file with a certificate stored on Azure Key Vault.
+fn azure_sign(path: &Path, params: &AzureSignParams, settings: &Settings) -> crate::Result<()> {
+  let digest_algorithm = settings
+    .windows()
+    .digest_algorithm
+    .clone()
+    .unwrap_or_else(|| "sha256".to_string());
+  let mut cmd = Command::new("AzureSignTool");
+  cmd
+    .arg("sign")
+    .args(&["-kvu", &params.key_vault_uri])
+    .args(&["-kvi", &params.client_id])
+    .args(&["-kvt", &params.tenant_id])
+    .args(&["-kvs", &params.client_secret])
+    .args(&["-kvc", &params.certificate_name])
+    .args(&["-fd", &digest_algorithm]);
+  if let Some(timestamp_url) = &settings.windows().timestamp_url {
+    cmd.args(&["-tr", timestamp_url, "-td", &digest_algorithm]);
+  }
+  cmd.arg(path);
+
+  let output = cmd.output().map_err(|_| {
+    crate::Error::Sign(
+      "AzureSignTool not found, install it with `dotnet tool install --global AzureSignTool`"
+        .into(),
+    )
+  })?;
+  if !output.status.success() {
+    return Err(crate::Error::Sign(
+      String::from_utf8_lossy(&output.stdout).into_owned(),
+    ));
+  }
+  Ok(())
+}
+
+/// Signs the file with AzureSignTool when its environment variables are set,
+/// or with signtool and the `certificateThumbprint` of the Windows settings, if any.
+pub fn sign_with_settings<P: AsRef<Path>>(path: P, settings: &Settings) -> crate::Result<()> {
+  if let Some(params) = AzureSignParams::from_env() {
+    common::print_info(format!("signing {} with AzureSignTool", path.as_ref().display()).as_str())?;
+    azure_sign(path.as_ref(), &params, settings)?;
+  } else if let Some(certificate_thumbprint) = &settings.windows().certificate_thumbprint {
+    common::print_info(format!("signing {}", path.as_ref().display()).as_str())?;
+    sign(
+      path,
+      &SignParams {
+        digest_algorithm: settings
+          .windows()
+          .digest_algorithm
+          .as_ref()
+          .map(|algorithm| algorithm.to_string())
+          .unwrap_or_else(|| "sha256".to_string()),
+        certificate_thumbprint: certificate_thumbprint.to_string(),
+        timestamp_url: settings
+          .windows()
+          .timestamp_url
+          .as_ref()
+          .map(|url| url.to_string()),
+      },
+    )?;
+  }
+  Ok(())
+}
+
+/// Signs the main binary, the additional project binaries and the sidecars.
+///
+/// The sidecars aren't signed when the app verifies their checksums, since it would change them.
+pub fn sign_app_binaries(settings: &Settings) -> crate::Result<()> {
+  for bin in settings.binaries() {
+    sign_with_settings(settings.binary_path(bin), settings)?;
+  }
+  if settings.verify_sidecar_checksums() {
+    return Ok(());
+  }
+  let cwd = std::env::current_dir()?;
+  for src in settings.external_binaries() {
+    sign_with_settings(cwd.join(src?), settings)?;
+  }
+  Ok(())
+}
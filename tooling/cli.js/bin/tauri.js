@@ -7,8 +7,8 @@ const chalk = require('chalk')
 const pkg = require('../package.json')
 const updateNotifier = require('update-notifier')
 
-const cmds = ['icon', 'deps']
-const rustCliCmds = ['dev', 'build', 'init', 'info', 'sign']
+const cmds = ['deps']
+const rustCliCmds = ['dev', 'build', 'init', 'info', 'sign', 'plugin', 'icon']
 
 const cmd = process.argv[2]
 /**
@@ -12,6 +12,7 @@
  * It will retain transparency and can make special file
  * types. You can control the settings.
  *
+ * @deprecated The `tauri icon` command now runs on the Rust CLI.
  * @module tauricon
  * @exports tauricon
  * @author Daniel Thompson-Yvetot
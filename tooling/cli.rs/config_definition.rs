@@ -37,6 +37,58 @@ pub struct DebConfig {
   pub use_bootstrapper: bool,
   #[serde(default)]
   pub files: HashMap<PathBuf, PathBuf>,
+  pub recommends: Option<Vec<String>>,
+  pub conflicts: Option<Vec<String>>,
+  #[serde(default)]
+  pub desktop_entry: DesktopEntryConfig,
+  pub pre_install_script: Option<PathBuf>,
+  pub post_install_script: Option<PathBuf>,
+  pub pre_remove_script: Option<PathBuf>,
+  pub post_remove_script: Option<PathBuf>,
+}
+
+#[skip_serializing_none]
+#[derive(Debug, Default, PartialEq, Clone, Deserialize, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct AppImageConfig {
+  #[serde(default)]
+  pub bundle_dependencies: bool,
+  #[serde(default)]
+  pub libraries: Vec<String>,
+  #[serde(default)]
+  pub exclude_libraries: Vec<String>,
+  pub update_information: Option<String>,
+  pub app_run: Option<PathBuf>,
+}
+
+#[skip_serializing_none]
+#[derive(Debug, Default, PartialEq, Clone, Deserialize, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct FlatpakConfig {
+  pub runtime_version: Option<String>,
+  #[serde(default)]
+  pub finish_args: Vec<String>,
+}
+
+#[skip_serializing_none]
+#[derive(Debug, Default, PartialEq, Clone, Deserialize, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct SnapConfig {
+  pub confinement: Option<String>,
+  pub grade: Option<String>,
+  #[serde(default)]
+  pub plugs: Vec<String>,
+  #[serde(default)]
+  pub stage_packages: Vec<String>,
+}
+
+#[skip_serializing_none]
+#[derive(Debug, Default, PartialEq, Clone, Deserialize, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct DesktopEntryConfig {
+  pub categories: Option<Vec<String>>,
+  pub mime_types: Option<Vec<String>>,
+  pub keywords: Option<Vec<String>>,
 }
 
 #[skip_serializing_none]
@@ -71,6 +123,53 @@ pub struct WixConfig {
   pub merge_refs: Vec<String>,
   #[serde(default)]
   pub skip_webview_install: bool,
+  #[serde(default)]
+  pub install_scope: WixInstallScope,
+  pub upgrade_code: Option<String>,
+  #[serde(default)]
+  pub languages: Vec<String>,
+  #[serde(default)]
+  pub registry_keys: Vec<WixRegistryKeyConfig>,
+  #[serde(default)]
+  pub file_associations: Vec<WixFileAssociationConfig>,
+}
+
+#[derive(Debug, PartialEq, Clone, Copy, Deserialize, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub enum WixInstallScope {
+  PerMachine,
+  PerUser,
+}
+
+impl Default for WixInstallScope {
+  fn default() -> Self {
+    Self::PerMachine
+  }
+}
+
+#[skip_serializing_none]
+#[derive(Debug, PartialEq, Clone, Deserialize, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct WixRegistryKeyConfig {
+  pub root: String,
+  pub key: String,
+  pub name: Option<String>,
+  #[serde(rename = "type", default = "default_registry_value_type")]
+  pub value_type: String,
+  pub value: String,
+}
+
+fn default_registry_value_type() -> String {
+  "string".into()
+}
+
+#[skip_serializing_none]
+#[derive(Debug, PartialEq, Clone, Deserialize, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct WixFileAssociationConfig {
+  pub extension: String,
+  pub description: Option<String>,
+  pub mime_type: Option<String>,
 }
 
 #[derive(Debug, Default, PartialEq, Clone, Deserialize, Serialize, JsonSchema)]
@@ -80,6 +179,29 @@ pub struct WindowsConfig {
   pub certificate_thumbprint: Option<String>,
   pub timestamp_url: Option<String>,
   pub wix: Option<WixConfig>,
+  pub nsis: Option<NsisConfig>,
+  pub portable: Option<PortableConfig>,
+}
+
+#[skip_serializing_none]
+#[derive(Debug, Default, PartialEq, Clone, Deserialize, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct NsisConfig {
+  pub license: Option<PathBuf>,
+  pub install_directory: Option<String>,
+  #[serde(default)]
+  pub per_user: bool,
+  #[serde(default)]
+  pub silent: bool,
+  #[serde(default)]
+  pub skip_webview_install: bool,
+}
+
+#[skip_serializing_none]
+#[derive(Debug, Default, PartialEq, Clone, Deserialize, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct PortableConfig {
+  pub data_directory: Option<String>,
 }
 
 #[skip_serializing_none]
@@ -99,7 +221,7 @@ pub struct BundleConfig {
   /// Whether we should build your app with tauri-bundler or plain `cargo build`
   #[serde(default)]
   pub active: bool,
-  /// The bundle targets, currently supports ["deb", "app", "msi", "appimage", "dmg"] or "all"
+  /// The bundle targets, currently supports ["deb", "app", "msi", "nsis", "portable", "appimage", "flatpak", "snap", "dmg"] or "all"
   pub targets: Option<BundleTarget>,
   /// The app's identifier
   pub identifier: Option<String>,
@@ -107,21 +229,103 @@ pub struct BundleConfig {
   pub icon: Option<Vec<String>>,
   /// App resources to bundle.
   /// Each resource is a path to a file or directory.
-  /// Glob patterns are supported.
-  pub resources: Option<Vec<String>>,
+  /// Glob patterns are supported, and the patterns prefixed with `!` exclude the matched files.
+  pub resources: Option<Vec<BundleResourceConfig>>,
   pub copyright: Option<String>,
   pub category: Option<String>,
   pub short_description: Option<String>,
   pub long_description: Option<String>,
   #[serde(default)]
   pub deb: DebConfig,
+  #[serde(default)]
+  pub appimage: AppImageConfig,
+  #[serde(default)]
+  pub flatpak: FlatpakConfig,
+  #[serde(default)]
+  pub snap: SnapConfig,
   #[serde(rename = "macOS", default)]
   pub macos: MacConfig,
-  pub external_bin: Option<Vec<String>>,
+  /// The sidecar binaries to bundle, looked up with the target triple suffix.
+  pub external_bin: Option<Vec<ExternalBinConfig>>,
+  /// Verifies the sidecar checksums computed at build time when the app starts.
+  /// The bundler doesn't sign the sidecars in this case, they must be signed beforehand.
+  #[serde(default)]
+  pub verify_sidecar_checksums: bool,
+  /// The URL schemes registered for the app, e.g. `myapp` to open the `myapp://` links with the app.
+  pub url_schemes: Option<Vec<String>>,
+  /// The file types opened by the app, registered on the Info.plist, the Windows registry and the .desktop file.
+  pub file_associations: Option<Vec<FileAssociationConfig>>,
   #[serde(default)]
   pub windows: WindowsConfig,
 }
 
+/// The role of the app for a file type on macOS.
+#[derive(Debug, PartialEq, Clone, Copy, Deserialize, Serialize, JsonSchema)]
+pub enum BundleTypeRole {
+  /// The app can read and write the files.
+  Editor,
+  /// The app can only read the files.
+  Viewer,
+  /// The app provides runtime services for the files.
+  Shell,
+  /// The app declares the file type without opening it.
+  None,
+}
+
+impl Default for BundleTypeRole {
+  fn default() -> Self {
+    Self::Editor
+  }
+}
+
+/// A file type opened by the app.
+#[skip_serializing_none]
+#[derive(Debug, PartialEq, Clone, Deserialize, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct FileAssociationConfig {
+  /// The file extensions, without the leading dot.
+  pub ext: Vec<String>,
+  /// The file type name. Defaults to the first extension.
+  pub name: Option<String>,
+  /// The file type description shown by the file manager.
+  pub description: Option<String>,
+  /// The file MIME type, e.g. `text/markdown`. Required to register the file type on Linux.
+  pub mime_type: Option<String>,
+  /// The role of the app for the file type on macOS.
+  #[serde(default)]
+  pub role: BundleTypeRole,
+}
+
+/// A resource to bundle.
+#[derive(Debug, PartialEq, Clone, Deserialize, Serialize, JsonSchema)]
+#[serde(untagged, deny_unknown_fields)]
+pub enum BundleResourceConfig {
+  /// The resource path or glob pattern. Bundled for every target.
+  Path(String),
+  /// A resource bundled for some targets only.
+  Targets {
+    /// The resource path or glob pattern.
+    path: String,
+    /// The target triples, or `windows`, `macos` and `linux` to match every target of the OS.
+    targets: Vec<String>,
+  },
+}
+
+/// A sidecar binary to bundle.
+#[derive(Debug, PartialEq, Clone, Deserialize, Serialize, JsonSchema)]
+#[serde(untagged, deny_unknown_fields)]
+pub enum ExternalBinConfig {
+  /// The sidecar path, without the target triple suffix. Bundled for every target.
+  Path(String),
+  /// A sidecar bundled for some targets only.
+  Targets {
+    /// The sidecar path, without the target triple suffix.
+    path: String,
+    /// The target triples, or `windows`, `macos` and `linux` to match every target of the OS.
+    targets: Vec<String>,
+  },
+}
+
 /// A CLI argument definition
 #[skip_serializing_none]
 #[derive(Debug, PartialEq, Clone, Deserialize, Serialize, JsonSchema)]
@@ -293,21 +497,83 @@ pub struct WindowConfig {
   /// Whether the window should always be on top of other windows.
   #[serde(default)]
   pub always_on_top: bool,
+  /// Whether the file drop is enabled or not on the webview. By default it is enabled.
+  ///
+  /// Disabling it is required to use drag and drop on the frontend on Windows.
+  #[serde(default = "default_file_drop_enabled")]
+  pub file_drop_enabled: bool,
+  /// Whether the Ctrl+scroll and Ctrl+=/Ctrl+-/Ctrl+0 hotkeys can be used to zoom the webview. WebView2 always enables them on Windows.
+  #[serde(default)]
+  pub zoom_hotkeys_enabled: bool,
+  /// The origins allowed to send IPC messages on this window. Overrides the `tauri > security > ipcAllowedOrigins` config.
+  pub ipc_allowed_origins: Option<Vec<String>>,
 }
 
 fn default_visible() -> bool {
   true
 }
 
+fn default_file_drop_enabled() -> bool {
+  true
+}
+
 fn default_decorations() -> bool {
   true
 }
 
 #[skip_serializing_none]
-#[derive(Debug, Default, PartialEq, Clone, Deserialize, Serialize, JsonSchema)]
+#[derive(Debug, PartialEq, Clone, Deserialize, Serialize, JsonSchema)]
 #[serde(rename_all = "camelCase", deny_unknown_fields)]
 pub struct SecurityConfig {
   pub csp: Option<String>,
+  /// The origins allowed to send IPC messages, e.g. `tauri://localhost` or `https://*.example.com`.
+  /// All origins are allowed if not set.
+  ///
+  /// The origin is read from the URL of the webview main frame,
+  /// it can't be read on Windows so the windows fail to be created there if set.
+  pub ipc_allowed_origins: Option<Vec<String>>,
+  /// Whether iframes can send IPC messages or not.
+  #[serde(default = "default_ipc_allow_iframes")]
+  pub ipc_allow_iframes: bool,
+  /// The scopes restricting the windows and commands allowed to use each plugin, mapped by plugin name.
+  /// The plugins without a scope accept all their commands on every window.
+  #[serde(default)]
+  pub plugin_scopes: HashMap<String, PluginScope>,
+}
+
+fn default_ipc_allow_iframes() -> bool {
+  true
+}
+
+/// The windows and commands allowed to use a plugin.
+#[skip_serializing_none]
+#[derive(Debug, Default, PartialEq, Clone, Deserialize, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct PluginScope {
+  /// The labels of the windows allowed to invoke the plugin commands. No window is allowed if not set, use `*` to allow all windows.
+  pub windows: Option<Vec<String>>,
+  /// The plugin commands allowed to be invoked. No command is allowed if not set, use `*` to allow all the commands declared by the plugin.
+  pub commands: Option<Vec<String>>,
+}
+
+/// The application pattern.
+#[derive(Debug, PartialEq, Clone, Deserialize, Serialize, JsonSchema)]
+#[serde(rename_all = "lowercase", tag = "use", content = "options")]
+pub enum PatternKind {
+  /// Brownfield pattern.
+  Brownfield,
+  /// Isolation pattern. Recommended for security purposes.
+  #[serde(rename_all = "camelCase")]
+  Isolation {
+    /// The dir containing the index.html file that contains the secure isolation application.
+    dir: PathBuf,
+  },
+}
+
+impl Default for PatternKind {
+  fn default() -> Self {
+    Self::Brownfield
+  }
 }
 
 pub trait Allowlist {
@@ -349,6 +615,10 @@ pub struct FsAllowlistConfig {
   pub rename_file: bool,
   #[serde(default)]
   pub path: bool,
+  #[serde(default)]
+  pub watch: bool,
+  #[serde(default)]
+  pub trash: bool,
 }
 
 impl Allowlist for FsAllowlistConfig {
@@ -368,6 +638,8 @@ impl Allowlist for FsAllowlistConfig {
       check_feature!(self, features, remove_file, "fs-remove-file");
       check_feature!(self, features, rename_file, "fs-rename-file");
       check_feature!(self, features, path, "fs-path");
+      check_feature!(self, features, watch, "fs-watch");
+      check_feature!(self, features, trash, "fs-trash");
       features
     }
   }
@@ -427,6 +699,10 @@ pub struct DialogAllowlistConfig {
   pub open: bool,
   #[serde(default)]
   pub save: bool,
+  #[serde(default)]
+  pub message: bool,
+  #[serde(default)]
+  pub ask: bool,
 }
 
 impl Allowlist for DialogAllowlistConfig {
@@ -437,6 +713,8 @@ impl Allowlist for DialogAllowlistConfig {
       let mut features = Vec::new();
       check_feature!(self, features, open, "dialog-open");
       check_feature!(self, features, save, "dialog-save");
+      check_feature!(self, features, message, "dialog-message");
+      check_feature!(self, features, ask, "dialog-ask");
       features
     }
   }
@@ -497,6 +775,40 @@ impl Allowlist for GlobalShortcutAllowlistConfig {
   }
 }
 
+#[derive(Debug, Default, PartialEq, Clone, Deserialize, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct AppAllowlistConfig {
+  #[serde(default)]
+  pub all: bool,
+}
+
+impl Allowlist for AppAllowlistConfig {
+  fn to_features(&self) -> Vec<&str> {
+    if self.all {
+      vec!["app-all"]
+    } else {
+      vec![]
+    }
+  }
+}
+
+#[derive(Debug, Default, PartialEq, Clone, Deserialize, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct SystemAllowlistConfig {
+  #[serde(default)]
+  pub all: bool,
+}
+
+impl Allowlist for SystemAllowlistConfig {
+  fn to_features(&self) -> Vec<&str> {
+    if self.all {
+      vec!["system-all"]
+    } else {
+      vec![]
+    }
+  }
+}
+
 #[derive(Debug, Default, PartialEq, Clone, Deserialize, Serialize, JsonSchema)]
 #[serde(rename_all = "camelCase", deny_unknown_fields)]
 pub struct AllowlistConfig {
@@ -516,6 +828,10 @@ pub struct AllowlistConfig {
   pub notification: NotificationAllowlistConfig,
   #[serde(default)]
   pub global_shortcut: GlobalShortcutAllowlistConfig,
+  #[serde(default)]
+  pub system: SystemAllowlistConfig,
+  #[serde(default)]
+  pub app: AppAllowlistConfig,
 }
 
 impl Allowlist for AllowlistConfig {
@@ -531,6 +847,8 @@ impl Allowlist for AllowlistConfig {
       features.extend(self.http.to_features());
       features.extend(self.notification.to_features());
       features.extend(self.global_shortcut.to_features());
+      features.extend(self.system.to_features());
+      features.extend(self.app.to_features());
       features
     }
   }
@@ -552,11 +870,39 @@ pub struct TauriConfig {
   #[serde(default)]
   allowlist: AllowlistConfig,
   pub security: Option<SecurityConfig>,
+  /// The application pattern.
+  #[serde(default)]
+  pub pattern: PatternKind,
   /// The updater configuration.
   #[serde(default = "default_updater")]
   pub updater: UpdaterConfig,
   /// Configuration for app system tray.
   pub system_tray: Option<SystemTrayConfig>,
+  /// The WebView2 configuration, only used on Windows.
+  #[serde(default)]
+  pub webview2: Webview2Config,
+  /// The proxy configuration of the HTTP requests made by the HTTP API and the updater.
+  #[serde(default)]
+  pub proxy: ProxyConfig,
+  /// The custom URI scheme protocols registered at startup, mapped by scheme name.
+  #[serde(default)]
+  pub protocols: HashMap<String, ProtocolConfig>,
+}
+
+/// A custom URI scheme protocol registered at startup.
+#[derive(Debug, PartialEq, Clone, Deserialize, Serialize, JsonSchema)]
+#[serde(untagged, deny_unknown_fields)]
+pub enum ProtocolConfig {
+  /// Serves the files of a folder.
+  Dir {
+    /// The folder path, relative to the resource directory if the path is relative.
+    dir: PathBuf,
+  },
+  /// Serves the responses of a handler registered with `tauri::Builder::protocol_handler`.
+  Handler {
+    /// The handler name.
+    handler: String,
+  },
 }
 
 impl TauriConfig {
@@ -580,6 +926,40 @@ pub struct UpdaterConfig {
   pub endpoints: Option<Vec<String>>,
   /// Optional pubkey.
   pub pubkey: Option<String>,
+  /// The release channel, e.g. `beta`. Defaults to `stable`.
+  pub channel: Option<String>,
+  /// Whether an update to a version lower than the running one is allowed or not.
+  #[serde(default)]
+  pub allow_downgrades: bool,
+}
+
+#[skip_serializing_none]
+#[derive(Debug, Default, PartialEq, Clone, Deserialize, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct Webview2Config {
+  /// Additional command line arguments passed to the WebView2 browser process.
+  pub additional_browser_args: Option<String>,
+  /// Path to a fixed version WebView2 runtime folder to use instead of the installed runtime.
+  pub fixed_runtime_path: Option<PathBuf>,
+  /// Path to the WebView2 user data folder.
+  pub user_data_path: Option<PathBuf>,
+}
+
+#[skip_serializing_none]
+#[derive(Debug, Default, PartialEq, Clone, Deserialize, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct ProxyConfig {
+  /// The proxy URL, e.g. `http://proxy.example.com:8080`. Overrides the system proxy.
+  pub url: Option<String>,
+  /// The hosts reached without the proxy, e.g. `localhost` or `.example.com`.
+  pub no_proxy: Option<Vec<String>>,
+  /// Whether the system proxy is used when `url` isn't set or not.
+  #[serde(default = "default_proxy_system")]
+  pub system: Option<bool>,
+}
+
+fn default_proxy_system() -> Option<bool> {
+  Some(true)
 }
 
 #[skip_serializing_none]
@@ -641,6 +1021,59 @@ pub struct BuildConfig {
   /// Whether we should inject the Tauri API on `window.__TAURI__` or not.
   #[serde(default)]
   pub with_global_tauri: bool,
+  /// The compression of the embedded assets.
+  #[serde(default)]
+  pub asset_compression: AssetCompression,
+  /// The `tauri dev` file watcher configuration.
+  #[serde(default)]
+  pub dev_watcher: DevWatcherConfig,
+}
+
+/// The `tauri dev` file watcher configuration.
+#[derive(Debug, PartialEq, Clone, Deserialize, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct DevWatcherConfig {
+  /// Additional paths to watch, relative to the `src-tauri` folder, e.g. the path dependencies outside of the workspace.
+  #[serde(default)]
+  pub paths: Vec<PathBuf>,
+  /// Glob patterns of the paths ignored by the watcher, relative to the `src-tauri` folder. The patterns without a `/` match the file names.
+  #[serde(default)]
+  pub ignore: Vec<String>,
+  /// The milliseconds the watcher waits for more changes before restarting the app.
+  #[serde(default = "default_debounce")]
+  pub debounce: u64,
+}
+
+impl Default for DevWatcherConfig {
+  fn default() -> Self {
+    Self {
+      paths: Vec::new(),
+      ignore: Vec::new(),
+      debounce: default_debounce(),
+    }
+  }
+}
+
+fn default_debounce() -> u64 {
+  1000
+}
+
+/// The compression of the embedded assets.
+#[derive(Debug, PartialEq, Clone, Copy, Deserialize, Serialize, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum AssetCompression {
+  /// Embed the assets as is.
+  None,
+  /// Compress the assets with zstd.
+  Zstd,
+  /// Compress the assets with brotli, usually smaller than zstd for text assets but slower to decompress.
+  Brotli,
+}
+
+impl Default for AssetCompression {
+  fn default() -> Self {
+    Self::Zstd
+  }
 }
 
 fn default_dev_path() -> AppUrl {
@@ -681,6 +1114,8 @@ fn default_build() -> BuildConfig {
     before_build_command: None,
     features: None,
     with_global_tauri: false,
+    asset_compression: AssetCompression::Zstd,
+    dev_watcher: DevWatcherConfig::default(),
   }
 }
 
@@ -690,5 +1125,7 @@ fn default_updater() -> UpdaterConfig {
     dialog: Some(true),
     endpoints: None,
     pubkey: None,
+    channel: None,
+    allow_downgrades: false,
   }
 }
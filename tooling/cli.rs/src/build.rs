@@ -5,16 +5,26 @@
 use anyhow::Context;
 use tauri_bundler::bundle::{bundle_project, PackageType};
 
-use crate::helpers::{
-  app_paths::{app_dir, tauri_dir},
-  config::{get as get_config, AppUrl},
-  execute_with_output,
-  manifest::rewrite_manifest,
-  updater_signature::sign_file_from_env_variables,
-  Logger,
+use crate::{
+  helpers::{
+    app_paths::{app_dir, tauri_dir},
+    config::{get as get_config, AppUrl},
+    execute_with_output,
+    manifest::rewrite_manifest,
+    updater_signature::sign_file_from_env_variables,
+    Logger,
+  },
+  interface::rust::{
+    build_project, lipo, validate_target, UNIVERSAL_MACOS_ARCH_TARGETS, UNIVERSAL_MACOS_TARGET,
+  },
 };
 
-use std::{env::set_current_dir, fs::rename, path::PathBuf, process::Command};
+use std::{
+  env::set_current_dir,
+  fs::{create_dir_all, rename},
+  path::PathBuf,
+  process::Command,
+};
 
 #[derive(Default)]
 pub struct Build {
@@ -69,7 +79,8 @@ impl Build {
 
   pub fn run(self) -> crate::Result<()> {
     let logger = Logger::new("tauri:build");
-    let config = get_config(self.config.as_deref())?;
+    let profile = if self.debug { "debug" } else { "release" };
+    let config = get_config(self.config.as_deref(), Some(profile))?;
 
     let tauri_path = tauri_dir();
     set_current_dir(&tauri_path).with_context(|| "failed to change current working directory")?;
@@ -122,30 +133,111 @@ impl Build {
       cargo_features.extend(features);
     }
 
-    crate::interface::rust::build_project(runner, &self.target, cargo_features, self.debug)
-      .with_context(|| "failed to build app")?;
+    let universal = self.target.as_deref() == Some(UNIVERSAL_MACOS_TARGET);
+    if universal {
+      for target in UNIVERSAL_MACOS_ARCH_TARGETS {
+        validate_target(&runner, target)?;
+      }
+      for target in UNIVERSAL_MACOS_ARCH_TARGETS {
+        build_project(
+          runner.clone(),
+          &Some(target.to_string()),
+          cargo_features.clone(),
+          self.debug,
+        )
+        .with_context(|| format!("failed to build app for {}", target))?;
+      }
+    } else {
+      if let Some(target) = &self.target {
+        validate_target(&runner, target)?;
+      }
+      build_project(runner, &self.target, cargo_features, self.debug)
+        .with_context(|| "failed to build app")?;
+    }
 
     let app_settings = crate::interface::rust::AppSettings::new(config_)?;
 
     let out_dir = app_settings
-      .get_out_dir(self.debug)
+      .get_out_dir(self.target.clone(), self.debug)
       .with_context(|| "failed to get project out directory")?;
+    if universal {
+      let bin_name = &app_settings.cargo_package_settings().name;
+      let inputs = UNIVERSAL_MACOS_ARCH_TARGETS
+        .iter()
+        .map(|target| {
+          app_settings
+            .get_out_dir(Some(target.to_string()), self.debug)
+            .map(|dir| dir.join(bin_name))
+        })
+        .collect::<crate::Result<Vec<_>>>()?;
+      create_dir_all(&out_dir)?;
+      lipo(&inputs, &out_dir.join(bin_name))?;
+      if config_.tauri.bundle.verify_sidecar_checksums {
+        return Err(anyhow::anyhow!(
+          "`tauri > bundle > verifySidecarChecksums` isn't supported on universal macOS builds, since the sidecars are merged after the app is built"
+        ));
+      }
+      // the sidecars are looked up with the target triple suffix
+      for sidecar in config_.tauri.bundle.external_bin.iter().flatten() {
+        let sidecar = tauri_bundler::ExternalBin::from(sidecar.clone());
+        if !sidecar.is_bundled_for(UNIVERSAL_MACOS_TARGET) {
+          continue;
+        }
+        let sidecar = &sidecar.path;
+        let universal_sidecar = PathBuf::from(format!("{}-{}", sidecar, UNIVERSAL_MACOS_TARGET));
+        let inputs: Vec<PathBuf> = UNIVERSAL_MACOS_ARCH_TARGETS
+          .iter()
+          .map(|target| PathBuf::from(format!("{}-{}", sidecar, target)))
+          .collect();
+        if inputs.iter().all(|input| input.exists()) {
+          lipo(&inputs, &universal_sidecar)?;
+        } else if !universal_sidecar.exists() {
+          return Err(anyhow::anyhow!(
+            "the `{}` sidecar needs the {:?} or {:?} binaries",
+            sidecar,
+            inputs,
+            universal_sidecar
+          ));
+        }
+      }
+      logger.log(format!(
+        "Merged the {} binaries into {:?}",
+        UNIVERSAL_MACOS_ARCH_TARGETS.join(" and "),
+        out_dir
+      ));
+    }
+    let target_os = self
+      .target
+      .as_deref()
+      .and_then(tauri_bundler::bundle::target_os)
+      .unwrap_or(std::env::consts::OS);
     if let Some(product_name) = config_.package.product_name.clone() {
       let bin_name = app_settings.cargo_package_settings().name.clone();
-      #[cfg(windows)]
+      let extension = if target_os == "windows" { ".exe" } else { "" };
       rename(
-        out_dir.join(format!("{}.exe", bin_name)),
-        out_dir.join(format!("{}.exe", product_name)),
+        out_dir.join(format!("{}{}", bin_name, extension)),
+        out_dir.join(format!("{}{}", product_name, extension)),
       )?;
-      #[cfg(not(windows))]
-      rename(out_dir.join(bin_name), out_dir.join(product_name))?;
     }
 
-    if config_.tauri.bundle.active {
+    // the bundles are created with the tools of the target platform
+    let bundle_on_host =
+      target_os == std::env::consts::OS || (target_os == "ios" && std::env::consts::OS == "macos");
+    if config_.tauri.bundle.active && !bundle_on_host && self.bundles.is_none() {
+      logger.warn(format!(
+        "Skipping the bundles: {} bundles can't be created on {}.",
+        target_os,
+        std::env::consts::OS
+      ));
+    } else if config_.tauri.bundle.active {
       // move merge modules to the out dir so the bundler can load it
       #[cfg(windows)]
       {
-        let (filename, vcruntime_msm) = if cfg!(target_arch = "x86") {
+        let x86 = match &self.target {
+          Some(target) => target.starts_with("i686") || target.starts_with("i586"),
+          None => cfg!(target_arch = "x86"),
+        };
+        let (filename, vcruntime_msm) = if x86 {
           let _ = std::fs::remove_file(out_dir.join("Microsoft_VC142_CRT_x64.msm"));
           (
             "Microsoft_VC142_CRT_x86.msm",
@@ -212,6 +304,7 @@ impl Build {
         &out_dir,
         self.verbose,
         package_types,
+        self.target.clone(),
       )
       .with_context(|| "failed to build bundler settings")?;
 
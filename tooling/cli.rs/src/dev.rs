@@ -4,12 +4,13 @@
 
 use crate::helpers::{
   app_paths::{app_dir, tauri_dir},
-  config::{get as get_config, reload as reload_config},
+  config::{get as get_config, overlay_file_names, reload as reload_config},
   manifest::{get_workspace_members, rewrite_manifest},
   Logger,
 };
 
 use anyhow::Context;
+use glob::Pattern;
 use notify::{watcher, DebouncedEvent, RecursiveMode, Watcher};
 use once_cell::sync::OnceCell;
 use shared_child::SharedChild;
@@ -17,6 +18,7 @@ use shared_child::SharedChild;
 use std::{
   env::set_current_dir,
   ffi::OsStr,
+  path::{Path, PathBuf},
   process::{exit, Child, Command},
   sync::{
     mpsc::{channel, Receiver},
@@ -26,6 +28,8 @@ use std::{
 };
 
 static BEFORE_DEV: OnceCell<Mutex<Child>> = OnceCell::new();
+// the profile of the config overlay, matching the cargo profile of the dev build
+const PROFILE: &str = "debug";
 
 fn kill_before_dev_process() {
   if let Some(child) = BEFORE_DEV.get() {
@@ -52,6 +56,9 @@ pub struct Dev {
   exit_on_panic: bool,
   config: Option<String>,
   args: Vec<String>,
+  watch: Vec<PathBuf>,
+  ignore: Vec<String>,
+  debounce: Option<u64>,
 }
 
 impl Dev {
@@ -89,12 +96,27 @@ impl Dev {
     self
   }
 
+  pub fn watch(mut self, paths: Vec<PathBuf>) -> Self {
+    self.watch = paths;
+    self
+  }
+
+  pub fn ignore(mut self, patterns: Vec<String>) -> Self {
+    self.ignore = patterns;
+    self
+  }
+
+  pub fn debounce(mut self, millis: u64) -> Self {
+    self.debounce.replace(millis);
+    self
+  }
+
   pub fn run(self) -> crate::Result<()> {
     let logger = Logger::new("tauri:dev");
     let tauri_path = tauri_dir();
     set_current_dir(&tauri_path).with_context(|| "failed to change current working directory")?;
     let merge_config = self.config.clone();
-    let config = get_config(merge_config.as_deref())?;
+    let config = get_config(merge_config.as_deref(), Some(PROFILE))?;
     let mut process: Arc<SharedChild>;
 
     let (settings, out_dir) = {
@@ -102,7 +124,7 @@ impl Dev {
       let config_ = config_guard.as_ref().unwrap();
       let app_settings = crate::interface::rust::AppSettings::new(config_)?;
       let out_dir = app_settings
-        .get_out_dir(true)
+        .get_out_dir(self.target.clone(), true)
         .with_context(|| "failed to get project out directory")?;
       let settings = crate::interface::get_bundler_settings(
         app_settings,
@@ -111,6 +133,7 @@ impl Dev {
         &out_dir,
         false,
         None,
+        self.target.clone(),
       )
       .with_context(|| "failed to build bundler settings")?;
       (settings, out_dir)
@@ -190,12 +213,39 @@ impl Dev {
 
     process = self.start_app(&runner, &cargo_features, child_wait_rx.clone());
 
+    let watcher_config = config
+      .lock()
+      .unwrap()
+      .as_ref()
+      .unwrap()
+      .build
+      .dev_watcher
+      .clone();
+    let ignore = watcher_config
+      .ignore
+      .iter()
+      .chain(&self.ignore)
+      .map(|pattern| {
+        Pattern::new(pattern).with_context(|| format!("invalid ignore pattern `{}`", pattern))
+      })
+      .collect::<crate::Result<Vec<_>>>()?;
+    let debounce = Duration::from_millis(self.debounce.unwrap_or(watcher_config.debounce));
+    // the event paths are canonicalized
+    let watch_root = tauri_path.canonicalize()?;
+
     let (tx, rx) = channel();
 
-    let mut watcher = watcher(tx, Duration::from_secs(1)).unwrap();
+    let mut watcher = watcher(tx, debounce).unwrap();
     watcher.watch(tauri_path.join("src"), RecursiveMode::Recursive)?;
     watcher.watch(tauri_path.join("Cargo.toml"), RecursiveMode::Recursive)?;
-    watcher.watch(tauri_path.join("tauri.conf.json"), RecursiveMode::Recursive)?;
+    let mut config_files = vec!["tauri.conf.json".to_string()];
+    config_files.extend(overlay_file_names(Some(PROFILE)));
+    for name in &config_files {
+      let path = tauri_path.join(name);
+      if path.exists() {
+        watcher.watch(path, RecursiveMode::Recursive)?;
+      }
+    }
 
     for member in get_workspace_members()? {
       let workspace_path = tauri_path.join(member);
@@ -203,6 +253,12 @@ impl Dev {
       watcher.watch(workspace_path.join("Cargo.toml"), RecursiveMode::Recursive)?;
     }
 
+    for path in watcher_config.paths.iter().chain(&self.watch) {
+      watcher
+        .watch(tauri_path.join(path), RecursiveMode::Recursive)
+        .with_context(|| format!("failed to watch {:?}", path))?;
+    }
+
     loop {
       if let Ok(event) = rx.recv() {
         let event_path = match event {
@@ -213,9 +269,13 @@ impl Dev {
           _ => None,
         };
 
-        if let Some(event_path) = event_path {
-          if event_path.file_name() == Some(OsStr::new("tauri.conf.json")) {
-            reload_config(merge_config.as_deref())?;
+        if let Some(event_path) = event_path.filter(|path| !is_ignored(&ignore, &watch_root, path))
+        {
+          if config_files
+            .iter()
+            .any(|name| event_path.file_name() == Some(OsStr::new(name)))
+          {
+            reload_config(merge_config.as_deref(), Some(PROFILE))?;
             rewrite_manifest(config.clone())?;
           } else {
             // When tauri.conf.json is changed, rewrite_manifest will be called
@@ -291,3 +351,41 @@ impl Dev {
     child_arc
   }
 }
+
+// Whether the path matches one of the ignore patterns, relative to the `src-tauri` folder.
+// The patterns without a `/` match any component of the path, e.g. `generated` or `*.snap`.
+fn is_ignored(patterns: &[Pattern], root: &Path, path: &Path) -> bool {
+  let relative = path.strip_prefix(root).unwrap_or(path);
+  patterns.iter().any(|pattern| {
+    if pattern.as_str().contains('/') {
+      pattern.matches_path(relative)
+    } else {
+      relative
+        .components()
+        .any(|component| pattern.matches(&component.as_os_str().to_string_lossy()))
+    }
+  })
+}
+
+#[cfg(test)]
+mod test {
+  use super::is_ignored;
+  use glob::Pattern;
+  use std::path::Path;
+
+  #[test]
+  fn ignores_matching_paths() {
+    let patterns = vec![
+      Pattern::new("*.snap").unwrap(),
+      Pattern::new("generated").unwrap(),
+      Pattern::new("src/bindings/**").unwrap(),
+    ];
+    let root = Path::new("/app/src-tauri");
+    let ignored = |path: &str| is_ignored(&patterns, root, Path::new(path));
+    assert!(ignored("/app/src-tauri/src/tests/main.snap"));
+    assert!(ignored("/app/src-tauri/src/generated/schema.rs"));
+    assert!(ignored("/app/src-tauri/src/bindings/api.rs"));
+    assert!(!ignored("/app/src-tauri/src/main.rs"));
+    assert!(!ignored("/app/src-tauri/bindings/api.rs"));
+  }
+}
@@ -9,6 +9,8 @@ use json_patch::merge;
 use once_cell::sync::Lazy;
 use serde_json::Value as JsonValue;
 
+use super::validation::validate;
+
 #[path = "../../config_definition.rs"]
 mod config_definition;
 pub use config_definition::*;
@@ -24,6 +26,95 @@ impl From<WixConfig> for tauri_bundler::WixSettings {
       feature_refs: config.feature_refs,
       merge_refs: config.merge_refs,
       skip_webview_install: config.skip_webview_install,
+      install_scope: match config.install_scope {
+        WixInstallScope::PerMachine => tauri_bundler::WixInstallScope::PerMachine,
+        WixInstallScope::PerUser => tauri_bundler::WixInstallScope::PerUser,
+      },
+      upgrade_code: config.upgrade_code,
+      languages: config.languages,
+      registry_keys: config
+        .registry_keys
+        .into_iter()
+        .map(|registry_key| tauri_bundler::WixRegistryKey {
+          root: registry_key.root,
+          key: registry_key.key,
+          name: registry_key.name,
+          value_type: registry_key.value_type,
+          value: registry_key.value,
+        })
+        .collect(),
+      file_associations: config
+        .file_associations
+        .into_iter()
+        .map(|association| tauri_bundler::WixFileAssociation {
+          extension: association.extension,
+          description: association.description,
+          mime_type: association.mime_type,
+        })
+        .collect(),
+    }
+  }
+}
+
+impl From<NsisConfig> for tauri_bundler::NsisSettings {
+  fn from(config: NsisConfig) -> tauri_bundler::NsisSettings {
+    tauri_bundler::NsisSettings {
+      license: config.license,
+      install_directory: config.install_directory,
+      per_user: config.per_user,
+      silent: config.silent,
+      skip_webview_install: config.skip_webview_install,
+    }
+  }
+}
+
+impl From<BundleResourceConfig> for tauri_bundler::BundleResource {
+  fn from(config: BundleResourceConfig) -> tauri_bundler::BundleResource {
+    match config {
+      BundleResourceConfig::Path(path) => tauri_bundler::BundleResource {
+        path,
+        targets: Vec::new(),
+      },
+      BundleResourceConfig::Targets { path, targets } => {
+        tauri_bundler::BundleResource { path, targets }
+      }
+    }
+  }
+}
+
+impl From<ExternalBinConfig> for tauri_bundler::ExternalBin {
+  fn from(config: ExternalBinConfig) -> tauri_bundler::ExternalBin {
+    match config {
+      ExternalBinConfig::Path(path) => tauri_bundler::ExternalBin {
+        path,
+        targets: Vec::new(),
+      },
+      ExternalBinConfig::Targets { path, targets } => tauri_bundler::ExternalBin { path, targets },
+    }
+  }
+}
+
+impl From<FileAssociationConfig> for tauri_bundler::FileAssociation {
+  fn from(config: FileAssociationConfig) -> tauri_bundler::FileAssociation {
+    tauri_bundler::FileAssociation {
+      ext: config.ext,
+      name: config.name,
+      description: config.description,
+      mime_type: config.mime_type,
+      role: match config.role {
+        BundleTypeRole::Editor => tauri_bundler::BundleTypeRole::Editor,
+        BundleTypeRole::Viewer => tauri_bundler::BundleTypeRole::Viewer,
+        BundleTypeRole::Shell => tauri_bundler::BundleTypeRole::Shell,
+        BundleTypeRole::None => tauri_bundler::BundleTypeRole::None,
+      },
+    }
+  }
+}
+
+impl From<PortableConfig> for tauri_bundler::PortableSettings {
+  fn from(config: PortableConfig) -> tauri_bundler::PortableSettings {
+    tauri_bundler::PortableSettings {
+      data_directory: config.data_directory,
     }
   }
 }
@@ -32,6 +123,7 @@ use std::{
   env::set_var,
   fs::File,
   io::BufReader,
+  path::Path,
   process::exit,
   sync::{Arc, Mutex},
 };
@@ -43,42 +135,125 @@ fn config_handle() -> &'static ConfigHandle {
   &CONFING_HANDLE
 }
 
-/// Gets the static parsed config from `tauri.conf.json`.
-fn get_internal(merge_config: Option<&str>, reload: bool) -> crate::Result<ConfigHandle> {
+/// The names of the configuration overlays merged over `tauri.conf.json`, in the order they're applied:
+/// the platform overlay (e.g. `tauri.linux.conf.json`) and then the profile overlay (e.g. `tauri.release.conf.json`).
+pub fn overlay_file_names(profile: Option<&str>) -> Vec<String> {
+  let mut names = vec![format!("tauri.{}.conf.json", std::env::consts::OS)];
+  if let Some(profile) = profile {
+    names.push(format!("tauri.{}.conf.json", profile));
+  }
+  names
+}
+
+/// Resolves the `${VAR}` and `${VAR:-default}` placeholders of the value with the environment variables.
+/// The default is used when the variable is unset or empty, and `$${` escapes a literal `${`.
+fn interpolate(value: &str) -> crate::Result<String> {
+  let mut output = String::with_capacity(value.len());
+  let mut rest = value;
+  while let Some(start) = rest.find("${") {
+    if rest[..start].ends_with('$') {
+      output.push_str(&rest[..start - 1]);
+      output.push_str("${");
+      rest = &rest[start + 2..];
+      continue;
+    }
+    output.push_str(&rest[..start]);
+    let end = rest[start..]
+      .find('}')
+      .with_context(|| format!("unclosed placeholder in `{}`", value))?;
+    let mut placeholder = rest[start + 2..start + end].splitn(2, ":-");
+    let name = placeholder.next().unwrap();
+    match (std::env::var(name), placeholder.next()) {
+      (Ok(var), _) if !var.is_empty() => output.push_str(&var),
+      (_, Some(default)) => output.push_str(default),
+      (Ok(var), None) => output.push_str(&var),
+      (Err(_), None) => {
+        return Err(anyhow::anyhow!(
+          "environment variable `{}` referenced by the config is not set",
+          name
+        ))
+      }
+    }
+    rest = &rest[start + end + 1..];
+  }
+  output.push_str(rest);
+  Ok(output)
+}
+
+fn interpolate_json(config: &mut JsonValue) -> crate::Result<()> {
+  match config {
+    JsonValue::String(value) => *value = interpolate(value)?,
+    JsonValue::Array(values) => {
+      for value in values {
+        interpolate_json(value)?;
+      }
+    }
+    JsonValue::Object(values) => {
+      for value in values.values_mut() {
+        interpolate_json(value)?;
+      }
+    }
+    _ => {}
+  }
+  Ok(())
+}
+
+fn read_json(path: &Path) -> crate::Result<JsonValue> {
+  let file_name = path.file_name().unwrap().to_string_lossy();
+  let file = File::open(path).with_context(|| format!("failed to read `{}`", file_name))?;
+  let config = serde_json::from_reader(BufReader::new(file))
+    .with_context(|| format!("failed to parse `{}`", file_name))?;
+  Ok(config)
+}
+
+/// Gets the static parsed config from `tauri.conf.json`,
+/// merged with the platform and profile overlays and the `merge_config` JSON, in this order of precedence.
+/// The `${VAR}` placeholders are resolved with the environment variables.
+fn get_internal(
+  merge_config: Option<&str>,
+  profile: Option<&str>,
+  reload: bool,
+) -> crate::Result<ConfigHandle> {
   if !reload && config_handle().lock().unwrap().is_some() {
     return Ok(config_handle().clone());
   }
 
-  let path = super::app_paths::tauri_dir().join("tauri.conf.json");
-  let file = File::open(path)?;
-  let buf = BufReader::new(file);
-  let mut config: JsonValue =
-    serde_json::from_reader(buf).with_context(|| "failed to parse `tauri.conf.json`")?;
-
-  let schema: JsonValue = serde_json::from_str(include_str!("../../schema.json"))?;
-  let mut scope = valico::json_schema::Scope::new();
-  let schema = scope.compile_and_return(schema, false).unwrap();
-  let state = schema.validate(&config);
-  if !state.errors.is_empty() {
-    for error in state.errors {
-      eprintln!(
-        "`tauri.conf.json` error on `{}`: {}",
-        error
-          .get_path()
-          .chars()
-          .skip(1)
-          .collect::<String>()
-          .replace("/", " > "),
-        error.get_detail().unwrap_or_else(|| error.get_title()),
-      );
+  let tauri_dir = super::app_paths::tauri_dir();
+  let mut config = read_json(&tauri_dir.join("tauri.conf.json"))?;
+  let mut config_files = vec!["tauri.conf.json".to_string()];
+  for name in overlay_file_names(profile) {
+    let path = tauri_dir.join(&name);
+    if path.exists() {
+      let overlay = read_json(&path)?;
+      if !overlay.is_object() {
+        return Err(anyhow::anyhow!("`{}` must be a JSON object", name));
+      }
+      merge(&mut config, &overlay);
+      config_files.push(name);
     }
-    exit(1);
   }
+  interpolate_json(&mut config)?;
 
   if let Some(merge_config) = merge_config {
-    let merge_config: JsonValue =
+    let mut merge_config: JsonValue =
       serde_json::from_str(merge_config).with_context(|| "failed to parse config to merge")?;
+    interpolate_json(&mut merge_config)?;
     merge(&mut config, &merge_config);
+    config_files.push("--config".into());
+  }
+
+  let schema: JsonValue = serde_json::from_str(include_str!("../../schema.json"))?;
+  let errors = validate(&schema, &config);
+  if !errors.is_empty() {
+    let config_files = config_files
+      .iter()
+      .map(|name| format!("`{}`", name))
+      .collect::<Vec<_>>()
+      .join(" + ");
+    for error in errors {
+      eprintln!("{} error on {}", config_files, error);
+    }
+    exit(1);
   }
 
   #[allow(unused_mut)]
@@ -93,12 +268,12 @@ fn get_internal(merge_config: Option<&str>, reload: bool) -> crate::Result<Confi
   Ok(config_handle().clone())
 }
 
-pub fn get(merge_config: Option<&str>) -> crate::Result<ConfigHandle> {
-  get_internal(merge_config, false)
+pub fn get(merge_config: Option<&str>, profile: Option<&str>) -> crate::Result<ConfigHandle> {
+  get_internal(merge_config, profile, false)
 }
 
-pub fn reload(merge_config: Option<&str>) -> crate::Result<()> {
-  get_internal(merge_config, true)?;
+pub fn reload(merge_config: Option<&str>, profile: Option<&str>) -> crate::Result<()> {
+  get_internal(merge_config, profile, true)?;
   Ok(())
 }
 
@@ -118,6 +293,8 @@ pub fn all_allowlist_features() -> Vec<&'static str> {
       remove_file: true,
       rename_file: true,
       path: true,
+      watch: true,
+      trash: true,
     },
     window: WindowAllowlistConfig {
       all: true,
@@ -132,6 +309,8 @@ pub fn all_allowlist_features() -> Vec<&'static str> {
       all: true,
       open: true,
       save: true,
+      message: true,
+      ask: true,
     },
     http: HttpAllowlistConfig {
       all: true,
@@ -139,6 +318,8 @@ pub fn all_allowlist_features() -> Vec<&'static str> {
     },
     notification: NotificationAllowlistConfig { all: true },
     global_shortcut: GlobalShortcutAllowlistConfig { all: true },
+    system: SystemAllowlistConfig { all: true },
+    app: AppAllowlistConfig { all: true },
   }
   .to_features()
 }
@@ -4,7 +4,7 @@
 
 use super::{
   app_paths::tauri_dir,
-  config::{all_allowlist_features, ConfigHandle},
+  config::{all_allowlist_features, Config, ConfigHandle, PatternKind},
 };
 
 use anyhow::Context;
@@ -46,6 +46,63 @@ fn toml_array(features: &HashSet<String>) -> Array {
   f
 }
 
+/// The tauri features enabled by the config, e.g. the allowlist features.
+pub fn config_features(config: &Config) -> HashSet<String> {
+  let mut features: HashSet<String> = config
+    .tauri
+    .features()
+    .into_iter()
+    .map(|feature| feature.to_string())
+    .collect();
+  if config.tauri.cli.is_some() {
+    features.insert("cli".to_string());
+  }
+  if config.tauri.updater.active {
+    features.insert("updater".to_string());
+  }
+  if config.tauri.system_tray.is_some() {
+    features.insert("system-tray".to_string());
+  }
+  if let PatternKind::Isolation { .. } = config.tauri.pattern {
+    features.insert("isolation".to_string());
+  }
+  features
+}
+
+/// The tauri features managed by the CLI, rewritten on the manifest according to the config.
+pub fn cli_managed_features() -> Vec<&'static str> {
+  let mut features = all_allowlist_features();
+  features.extend(vec!["cli", "updater", "system-tray", "isolation"]);
+  features
+}
+
+/// The features of the tauri dependency on the manifest.
+pub fn manifest_tauri_features() -> crate::Result<Vec<String>> {
+  let mut manifest = read_manifest(&tauri_dir().join("Cargo.toml"))?;
+  let tauri = manifest
+    .as_table_mut()
+    .entry("dependencies")
+    .as_table_mut()
+    .map(|dependencies| dependencies.entry("tauri").clone());
+  let features = match tauri {
+    Some(Item::Table(table)) => table.get("features").and_then(|f| f.as_array()).cloned(),
+    Some(Item::Value(Value::InlineTable(table))) => {
+      table.get("features").and_then(|f| f.as_array()).cloned()
+    }
+    _ => None,
+  };
+  Ok(
+    features
+      .map(|features| {
+        features
+          .iter()
+          .filter_map(|feature| feature.as_str().map(|f| f.to_string()))
+          .collect()
+      })
+      .unwrap_or_default(),
+  )
+}
+
 pub fn rewrite_manifest(config: ConfigHandle) -> crate::Result<Manifest> {
   let manifest_path = tauri_dir().join("Cargo.toml");
   let mut manifest = read_manifest(&manifest_path)?;
@@ -60,23 +117,8 @@ pub fn rewrite_manifest(config: ConfigHandle) -> crate::Result<Manifest> {
   let config_guard = config.lock().unwrap();
   let config = config_guard.as_ref().unwrap();
 
-  let allowlist_features = config.tauri.features();
-  let mut features = HashSet::new();
-  for feature in allowlist_features {
-    features.insert(feature.to_string());
-  }
-  if config.tauri.cli.is_some() {
-    features.insert("cli".to_string());
-  }
-  if config.tauri.updater.active {
-    features.insert("updater".to_string());
-  }
-  if config.tauri.system_tray.is_some() {
-    features.insert("system-tray".to_string());
-  }
-
-  let mut cli_managed_features = all_allowlist_features();
-  cli_managed_features.extend(vec!["cli", "updater", "system-tray"]);
+  let mut features = config_features(config);
+  let cli_managed_features = cli_managed_features();
 
   if let Some(tauri) = tauri_entry.as_table_mut() {
     let manifest_features = tauri.entry("features");
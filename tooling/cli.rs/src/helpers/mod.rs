@@ -8,6 +8,7 @@ pub mod framework;
 mod logger;
 pub mod manifest;
 pub mod updater_signature;
+mod validation;
 
 pub use logger::Logger;
 
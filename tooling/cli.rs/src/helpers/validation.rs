@@ -0,0 +1,216 @@
+// Copyright 2019-2021 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+use serde_json::{Map, Value as JsonValue};
+
+use std::fmt;
+
+/// A `tauri.conf.json` validation error.
+#[derive(Debug, PartialEq)]
+pub struct ValidationError {
+  /// The JSON pointer of the invalid value, e.g. `/tauri/windows/0/width`.
+  pub pointer: String,
+  /// The error description.
+  pub message: String,
+}
+
+impl fmt::Display for ValidationError {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    write!(f, "`{}`: {}", self.pointer, self.message)
+  }
+}
+
+/// Validates the config against the JSON schema, reporting the unknown keys (with the closest known key),
+/// the type mismatches and the allowlist values ignored because a parent enables the whole API.
+pub fn validate(schema: &JsonValue, config: &JsonValue) -> Vec<ValidationError> {
+  let mut errors = Vec::new();
+  unknown_keys(schema, schema, config, "", &mut errors);
+
+  // the unknown keys are already reported with a suggestion
+  let mut permissive_schema = schema.clone();
+  allow_additional_properties(&mut permissive_schema);
+  let mut scope = valico::json_schema::Scope::new();
+  let compiled = scope
+    .compile_and_return(permissive_schema, false)
+    .expect("invalid config schema");
+  for error in compiled.validate(config).errors {
+    errors.push(ValidationError {
+      pointer: error.get_path().to_string(),
+      message: error
+        .get_detail()
+        .unwrap_or_else(|| error.get_title())
+        .into(),
+    });
+  }
+
+  if let Some(allowlist) = config.pointer("/tauri/allowlist") {
+    ignored_allowlist_values(allowlist, "/tauri/allowlist", &mut errors);
+  }
+
+  errors
+}
+
+// Follows the `$ref`, `allOf` and `anyOf` of the schema to the object definitions.
+fn definitions<'a>(root: &'a JsonValue, schema: &'a JsonValue) -> Vec<&'a JsonValue> {
+  if let Some(reference) = schema.get("$ref").and_then(JsonValue::as_str) {
+    return root
+      .pointer(reference.trim_start_matches('#'))
+      .map(|schema| definitions(root, schema))
+      .unwrap_or_default();
+  }
+  let mut schemas = vec![schema];
+  for key in &["allOf", "anyOf", "oneOf"] {
+    if let Some(JsonValue::Array(variants)) = schema.get(*key) {
+      for variant in variants {
+        schemas.extend(definitions(root, variant));
+      }
+    }
+  }
+  schemas
+}
+
+fn unknown_keys(
+  root: &JsonValue,
+  schema: &JsonValue,
+  value: &JsonValue,
+  pointer: &str,
+  errors: &mut Vec<ValidationError>,
+) {
+  let schemas = definitions(root, schema);
+  match value {
+    JsonValue::Object(object) => {
+      let properties: Vec<&Map<String, JsonValue>> = schemas
+        .iter()
+        .filter_map(|schema| schema.get("properties").and_then(JsonValue::as_object))
+        .collect();
+      let additional: Vec<&JsonValue> = schemas
+        .iter()
+        .filter_map(|schema| schema.get("additionalProperties"))
+        .collect();
+      let strict =
+        !properties.is_empty() && additional.iter().any(|a| **a == JsonValue::Bool(false));
+      for (key, value) in object {
+        let key_pointer = format!("{}/{}", pointer, key);
+        if let Some(schema) = properties.iter().find_map(|properties| properties.get(key)) {
+          unknown_keys(root, schema, value, &key_pointer, errors);
+        } else if let Some(schema) = additional.iter().find(|a| a.is_object()) {
+          unknown_keys(root, schema, value, &key_pointer, errors);
+        } else if strict {
+          let known_keys = properties.iter().flat_map(|properties| properties.keys());
+          let message = match suggestion(key, known_keys) {
+            Some(suggestion) => format!("unknown key `{}`, did you mean `{}`?", key, suggestion),
+            None => format!("unknown key `{}`", key),
+          };
+          errors.push(ValidationError {
+            pointer: key_pointer,
+            message,
+          });
+        }
+      }
+    }
+    JsonValue::Array(items) => {
+      if let Some(schema) = schemas.iter().find_map(|schema| schema.get("items")) {
+        for (i, item) in items.iter().enumerate() {
+          unknown_keys(root, schema, item, &format!("{}/{}", pointer, i), errors);
+        }
+      }
+    }
+    _ => {}
+  }
+}
+
+// The known key closest to the unknown one, if it's similar enough.
+fn suggestion<'a>(key: &str, known_keys: impl Iterator<Item = &'a String>) -> Option<&'a String> {
+  known_keys
+    .map(|known| (strsim::jaro_winkler(key, known), known))
+    .filter(|(similarity, _)| *similarity > 0.8)
+    .max_by(|(a, _), (b, _)| a.partial_cmp(b).unwrap())
+    .map(|(_, known)| known)
+}
+
+fn allow_additional_properties(schema: &mut JsonValue) {
+  match schema {
+    JsonValue::Object(object) => {
+      if object.get("additionalProperties") == Some(&JsonValue::Bool(false)) {
+        object.remove("additionalProperties");
+      }
+      object.values_mut().for_each(allow_additional_properties);
+    }
+    JsonValue::Array(values) => values.iter_mut().for_each(allow_additional_properties),
+    _ => {}
+  }
+}
+
+// Reports the `false` flags of an allowlist object with `all` enabled, since `all` enables every API regardless.
+fn ignored_allowlist_values(
+  allowlist: &JsonValue,
+  pointer: &str,
+  errors: &mut Vec<ValidationError>,
+) {
+  let object = match allowlist.as_object() {
+    Some(object) => object,
+    None => return,
+  };
+  let all = object.get("all") == Some(&JsonValue::Bool(true));
+  for (key, value) in object {
+    let key_pointer = format!("{}/{}", pointer, key);
+    if all && value.as_bool() == Some(false) {
+      errors.push(ValidationError {
+        pointer: key_pointer,
+        message: format!("ignored because `{}/all` enables every API", pointer),
+      });
+    } else if all && value.is_object() {
+      disabled_flags(value, &key_pointer, pointer, errors);
+    } else if value.is_object() {
+      ignored_allowlist_values(value, &key_pointer, errors);
+    }
+  }
+}
+
+fn disabled_flags(
+  value: &JsonValue,
+  pointer: &str,
+  all_pointer: &str,
+  errors: &mut Vec<ValidationError>,
+) {
+  for (key, value) in value.as_object().into_iter().flatten() {
+    if value.as_bool() == Some(false) {
+      errors.push(ValidationError {
+        pointer: format!("{}/{}", pointer, key),
+        message: format!("ignored because `{}/all` enables every API", all_pointer),
+      });
+    }
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+  use serde_json::json;
+
+  #[test]
+  fn reports_precise_errors() {
+    let schema: JsonValue = serde_json::from_str(include_str!("../../schema.json")).unwrap();
+    let config = json!({
+      "build": { "distDir": "../dist", "devPath": "http://localhost:4000" },
+      "tauri": {
+        "windws": [],
+        "windows": [{ "width": "800" }],
+        "allowlist": { "fs": { "all": true, "readDir": false } }
+      }
+    });
+    let errors = validate(&schema, &config);
+    assert!(errors.contains(&ValidationError {
+      pointer: "/tauri/windws".into(),
+      message: "unknown key `windws`, did you mean `windows`?".into(),
+    }));
+    assert!(errors
+      .iter()
+      .any(|error| error.pointer == "/tauri/windows/0/width"));
+    assert!(errors.contains(&ValidationError {
+      pointer: "/tauri/allowlist/fs/readDir".into(),
+      message: "ignored because `/tauri/allowlist/fs/all` enables every API".into(),
+    }));
+  }
+}
@@ -0,0 +1,265 @@
+// Copyright 2019-2021 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+use crate::helpers::{
+  app_paths::{app_dir, tauri_dir},
+  Logger,
+};
+use anyhow::Context;
+use image::{imageops::FilterType, DynamicImage, GenericImageView, ImageOutputFormat};
+
+use std::{
+  fs::{create_dir_all, read, read_to_string, write, File},
+  io::{BufWriter, Write},
+  path::{Path, PathBuf},
+};
+
+/// The PNG icons, as (file name, size) pairs.
+const PNG_ICONS: &[(&str, u32)] = &[
+  ("32x32.png", 32),
+  ("128x128.png", 128),
+  ("128x128@2x.png", 256),
+  ("icon.png", 512),
+  // Windows Store logos
+  ("StoreLogo.png", 50),
+  ("Square30x30Logo.png", 30),
+  ("Square44x44Logo.png", 44),
+  ("Square71x71Logo.png", 71),
+  ("Square89x89Logo.png", 89),
+  ("Square107x107Logo.png", 107),
+  ("Square142x142Logo.png", 142),
+  ("Square150x150Logo.png", 150),
+  ("Square284x284Logo.png", 284),
+  ("Square310x310Logo.png", 310),
+];
+
+/// The sizes packed in the `icon.ico` file.
+const ICO_SIZES: &[u32] = &[16, 24, 32, 48, 64, 256];
+
+/// The sizes packed in the `icon.icns` file, as (pixel size, density) pairs.
+const ICNS_SIZES: &[(u32, u32)] = &[
+  (16, 1),
+  (32, 1),
+  (32, 2),
+  (64, 2),
+  (128, 1),
+  (256, 1),
+  (256, 2),
+  (512, 1),
+  (512, 2),
+  (1024, 2),
+];
+
+/// The `tauri > bundle > icon` value referencing the generated icons.
+const BUNDLE_ICONS: &[&str] = &[
+  "32x32.png",
+  "128x128.png",
+  "128x128@2x.png",
+  "icon.icns",
+  "icon.ico",
+];
+
+/// The size the SVG sources are rendered to.
+const SVG_SIZE: u32 = 1024;
+
+pub struct Icon {
+  source: Option<PathBuf>,
+  target: Option<PathBuf>,
+  update_config: bool,
+}
+
+impl Default for Icon {
+  fn default() -> Self {
+    Self {
+      source: None,
+      target: None,
+      update_config: true,
+    }
+  }
+}
+
+impl Icon {
+  pub fn new() -> Self {
+    Default::default()
+  }
+
+  pub fn source(mut self, source: impl Into<PathBuf>) -> Self {
+    self.source = Some(source.into());
+    self
+  }
+
+  pub fn target(mut self, target: impl Into<PathBuf>) -> Self {
+    self.target = Some(target.into());
+    self
+  }
+
+  pub fn skip_config(mut self) -> Self {
+    self.update_config = false;
+    self
+  }
+
+  pub fn run(self) -> crate::Result<()> {
+    let logger = Logger::new("tauri:icon");
+    let source = self
+      .source
+      .unwrap_or_else(|| app_dir().join("app-icon.png"));
+    let target = self.target.unwrap_or_else(|| tauri_dir().join("icons"));
+
+    let image = load(&source).with_context(|| format!("failed to read icon {:?}", source))?;
+    let (width, height) = image.dimensions();
+    if width != height {
+      logger.warn(format!(
+        "The source icon is {}x{}, the icons will be stretched to a square.",
+        width, height
+      ));
+    }
+    if width.min(height) < 1024 {
+      logger.warn("Use a source icon of at least 1024x1024 for the best results.");
+    }
+
+    create_dir_all(&target)?;
+    for (file_name, size) in PNG_ICONS {
+      resize(&image, *size)
+        .save_with_format(target.join(file_name), image::ImageFormat::Png)
+        .with_context(|| format!("failed to write {}", file_name))?;
+    }
+    write_ico(&image, &target.join("icon.ico")).with_context(|| "failed to write icon.ico")?;
+    write_icns(&image, &target.join("icon.icns")).with_context(|| "failed to write icon.icns")?;
+    logger.log(format!("Generated the icons on {:?}", target));
+
+    if self.update_config {
+      update_config(&logger, &target)?;
+    }
+
+    Ok(())
+  }
+}
+
+fn load(source: &Path) -> crate::Result<DynamicImage> {
+  let is_svg = source
+    .extension()
+    .map(|ext| ext.eq_ignore_ascii_case("svg"))
+    .unwrap_or_default();
+  if is_svg {
+    let tree = usvg::Tree::from_data(&read(source)?, &usvg::Options::default())?;
+    let mut pixmap =
+      tiny_skia::Pixmap::new(SVG_SIZE, SVG_SIZE).expect("failed to allocate the SVG canvas");
+    resvg::render(
+      &tree,
+      usvg::FitTo::Size(SVG_SIZE, SVG_SIZE),
+      pixmap.as_mut(),
+    )
+    .ok_or_else(|| anyhow::anyhow!("failed to render the SVG icon"))?;
+    Ok(image::load_from_memory_with_format(
+      &pixmap.encode_png()?,
+      image::ImageFormat::Png,
+    )?)
+  } else {
+    Ok(image::open(source)?)
+  }
+}
+
+fn resize(image: &DynamicImage, size: u32) -> DynamicImage {
+  image.resize_exact(size, size, FilterType::Lanczos3)
+}
+
+fn png_bytes(image: &DynamicImage) -> crate::Result<Vec<u8>> {
+  let mut bytes = Vec::new();
+  image.write_to(&mut bytes, ImageOutputFormat::Png)?;
+  Ok(bytes)
+}
+
+// Writes an ICO file with PNG compressed entries, supported since Windows Vista.
+fn write_ico(image: &DynamicImage, path: &Path) -> crate::Result<()> {
+  let entries = ICO_SIZES
+    .iter()
+    .map(|size| png_bytes(&resize(image, *size)).map(|png| (*size, png)))
+    .collect::<crate::Result<Vec<_>>>()?;
+
+  let mut file = BufWriter::new(File::create(path)?);
+  // ICONDIR: reserved, type (1 = icon) and image count
+  file.write_all(&0u16.to_le_bytes())?;
+  file.write_all(&1u16.to_le_bytes())?;
+  file.write_all(&(entries.len() as u16).to_le_bytes())?;
+
+  let mut offset = 6 + 16 * entries.len() as u32;
+  for (size, png) in &entries {
+    // ICONDIRENTRY: a 0 width and height means 256
+    let dimension = if *size >= 256 { 0 } else { *size as u8 };
+    file.write_all(&[dimension, dimension, 0, 0])?;
+    // color planes and bits per pixel
+    file.write_all(&1u16.to_le_bytes())?;
+    file.write_all(&32u16.to_le_bytes())?;
+    file.write_all(&(png.len() as u32).to_le_bytes())?;
+    file.write_all(&offset.to_le_bytes())?;
+    offset += png.len() as u32;
+  }
+  for (_, png) in &entries {
+    file.write_all(png)?;
+  }
+  file.flush()?;
+  Ok(())
+}
+
+fn write_icns(image: &DynamicImage, path: &Path) -> crate::Result<()> {
+  let mut family = icns::IconFamily::new();
+  for (size, density) in ICNS_SIZES {
+    let icon_type = icns::IconType::from_pixel_size_and_density(*size, *size, *density)
+      .expect("invalid ICNS icon size");
+    if !family.has_icon_with_type(icon_type) {
+      let icon = resize(image, *size).to_rgba8();
+      let icon = icns::Image::from_data(icns::PixelFormat::RGBA, *size, *size, icon.into_raw())?;
+      family.add_icon_with_type(&icon, icon_type)?;
+    }
+  }
+  family.write(BufWriter::new(File::create(path)?))?;
+  Ok(())
+}
+
+// Points `tauri > bundle > icon` to the generated icons, editing the config text so its formatting is kept.
+fn update_config(logger: &Logger, target: &Path) -> crate::Result<()> {
+  let tauri_dir = tauri_dir().canonicalize()?;
+  let config_path = tauri_dir.join("tauri.conf.json");
+  let target = target.canonicalize()?;
+  let icons_dir = target
+    .strip_prefix(&tauri_dir)
+    .map(Path::to_path_buf)
+    .unwrap_or(target);
+  let icons: Vec<String> = BUNDLE_ICONS
+    .iter()
+    .map(|icon| icons_dir.join(icon).to_string_lossy().replace('\\', "/"))
+    .collect();
+
+  let config = read_to_string(&config_path)?;
+  let current_icons = serde_json::from_str::<serde_json::Value>(&config)?
+    .pointer("/tauri/bundle/icon")
+    .cloned();
+  if current_icons == Some(serde_json::json!(icons)) {
+    return Ok(());
+  }
+
+  let icon_array = regex::Regex::new(r#"(?m)^([ \t]*)"icon"\s*:\s*\[[^\]]*\]"#).unwrap();
+  let matches: Vec<_> = icon_array.captures_iter(&config).collect();
+  if current_icons.is_none() || matches.len() != 1 {
+    logger.warn(format!(
+      "Couldn't update the icons on tauri.conf.json, set `tauri > bundle > icon` to {:?}.",
+      icons
+    ));
+    return Ok(());
+  }
+  let indent = &matches[0][1];
+  let items: Vec<String> = icons
+    .iter()
+    .map(|icon| format!("{}  {:?}", indent, icon))
+    .collect();
+  let replacement = format!("{}\"icon\": [\n{}\n{}]", indent, items.join(",\n"), indent);
+  write(
+    &config_path,
+    icon_array
+      .replace(&config, regex::NoExpand(&replacement))
+      .as_ref(),
+  )?;
+  logger.log("Updated `tauri > bundle > icon` on tauri.conf.json");
+  Ok(())
+}
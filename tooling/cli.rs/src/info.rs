@@ -72,8 +72,12 @@ struct CargoManifest {
   dependencies: HashMap<String, CargoManifestDependency>,
 }
 
+mod doctor;
+
 #[derive(Default)]
-pub struct Info;
+pub struct Info {
+  doctor: bool,
+}
 
 fn crate_latest_version(name: &str) -> Option<String> {
   let url = format!("https://docs.rs/crate/{}/", name);
@@ -335,6 +339,12 @@ impl Info {
     Default::default()
   }
 
+  /// Also checks the system dependencies, the environment variables and the app config.
+  pub fn doctor(mut self) -> Self {
+    self.doctor = true;
+    self
+  }
+
   pub fn run(self) -> crate::Result<()> {
     let os_info = os_info::get();
     InfoBlock {
@@ -360,6 +370,7 @@ impl Info {
     let app_dir = panic::catch_unwind(app_dir).map(Some).unwrap_or_default();
     panic::set_hook(hook);
 
+    let has_app = app_dir.is_some();
     let use_yarn = app_dir
       .map(|dir| dir.join("yarn.lock").exists())
       .unwrap_or_default();
@@ -532,7 +543,7 @@ impl Info {
         .suffix(suffix)
         .display();
 
-      if let Ok(config) = get_config(None) {
+      if let Ok(config) = get_config(None, None) {
         let config_guard = config.lock().unwrap();
         let config = config_guard.as_ref().unwrap();
         InfoBlock::new("  build-type")
@@ -573,6 +584,10 @@ impl Info {
       }
     }
 
+    if self.doctor {
+      doctor::run(has_app)?;
+    }
+
     Ok(())
   }
 }
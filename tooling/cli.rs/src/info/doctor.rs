@@ -0,0 +1,397 @@
+// Copyright 2019-2021 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+use crate::helpers::{
+  config::{get as get_config, Config},
+  manifest::{cli_managed_features, config_features, manifest_tauri_features},
+};
+use colored::Colorize;
+
+use std::{env::var_os, process::Command};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Status {
+  Ok,
+  Warning,
+  Error,
+}
+
+/// The result of a doctor check, with the steps to fix it.
+struct Diagnostic {
+  status: Status,
+  check: String,
+  message: String,
+  fix: Option<String>,
+}
+
+impl Diagnostic {
+  fn ok(check: impl Into<String>, message: impl Into<String>) -> Self {
+    Self {
+      status: Status::Ok,
+      check: check.into(),
+      message: message.into(),
+      fix: None,
+    }
+  }
+
+  fn warning(check: impl Into<String>, message: impl Into<String>, fix: impl Into<String>) -> Self {
+    Self {
+      status: Status::Warning,
+      check: check.into(),
+      message: message.into(),
+      fix: Some(fix.into()),
+    }
+  }
+
+  fn error(check: impl Into<String>, message: impl Into<String>, fix: impl Into<String>) -> Self {
+    Self {
+      status: Status::Error,
+      check: check.into(),
+      message: message.into(),
+      fix: Some(fix.into()),
+    }
+  }
+
+  fn display(&self) {
+    let icon = match self.status {
+      Status::Ok => "✔".green(),
+      Status::Warning => "⚠".yellow(),
+      Status::Error => "✖".red(),
+    };
+    println!("  {} {} - {}", icon, self.check, self.message);
+    if let Some(fix) = &self.fix {
+      println!("      {} {}", "fix:".bold(), fix);
+    }
+  }
+}
+
+/// Checks the system dependencies, the environment variables and the app config,
+/// printing the fixes of the problems found.
+pub fn run(has_app: bool) -> crate::Result<()> {
+  let config = if has_app {
+    get_config(None, None).ok()
+  } else {
+    None
+  };
+  let config_guard = config.as_ref().map(|config| config.lock().unwrap());
+  let config = config_guard.as_ref().and_then(|guard| guard.as_ref());
+
+  let mut diagnostics = system_dependencies(config);
+  diagnostics.extend(environment_variables(config));
+  if let Some(config) = config {
+    diagnostics.extend(signing(config));
+    diagnostics.push(tauri_features(config));
+  }
+
+  println!();
+  println!("Doctor");
+  for diagnostic in &diagnostics {
+    diagnostic.display();
+  }
+
+  let count = |status| {
+    diagnostics
+      .iter()
+      .filter(|diagnostic| diagnostic.status == status)
+      .count()
+  };
+  let (errors, warnings) = (count(Status::Error), count(Status::Warning));
+  println!();
+  if errors == 0 && warnings == 0 {
+    println!("{}", "No issues found.".green());
+  } else {
+    println!(
+      "{}",
+      format!("Found {} error(s) and {} warning(s).", errors, warnings).yellow()
+    );
+  }
+
+  Ok(())
+}
+
+fn system_dependencies(_config: Option<&Config>) -> Vec<Diagnostic> {
+  #[allow(unused_mut)]
+  let mut diagnostics = Vec::new();
+  #[cfg(windows)]
+  diagnostics.push(webview2());
+  #[cfg(target_os = "linux")]
+  diagnostics.extend(linux_packages(_config));
+  #[cfg(target_os = "macos")]
+  diagnostics.push(xcode_command_line_tools());
+  diagnostics
+}
+
+#[cfg(windows)]
+fn webview2() -> Diagnostic {
+  match super::webview2_version()
+    .unwrap_or_default()
+    .map(|version| version.trim().to_string())
+    .filter(|version| !version.is_empty())
+  {
+    Some(version) => Diagnostic::ok("WebView2 Runtime", version),
+    None => Diagnostic::error(
+      "WebView2 Runtime",
+      "not installed",
+      "install the Evergreen WebView2 Runtime from https://developer.microsoft.com/microsoft-edge/webview2/",
+    ),
+  }
+}
+
+#[cfg(target_os = "linux")]
+fn linux_packages(config: Option<&Config>) -> Vec<Diagnostic> {
+  if Command::new("pkg-config")
+    .arg("--version")
+    .output()
+    .is_err()
+  {
+    return vec![Diagnostic::error(
+      "pkg-config",
+      "not installed",
+      "install it, e.g. `sudo apt install pkg-config`",
+    )];
+  }
+  // the pkg-config names of the libraries, with their Debian packages
+  let mut libraries = vec![
+    (vec!["webkit2gtk-4.0"], "libwebkit2gtk-4.0-dev"),
+    (vec!["gtk+-3.0"], "libgtk-3-dev"),
+    (vec!["openssl"], "libssl-dev"),
+    (vec!["librsvg-2.0"], "librsvg2-dev"),
+  ];
+  if config
+    .map(|config| config.tauri.system_tray.is_some())
+    .unwrap_or_default()
+  {
+    libraries.push((
+      vec!["ayatana-appindicator3-0.1", "appindicator3-0.1"],
+      "libayatana-appindicator3-dev",
+    ));
+  }
+
+  libraries
+    .into_iter()
+    .map(|(names, package)| {
+      let version = names.iter().find_map(|name| {
+        Command::new("pkg-config")
+          .args(&["--modversion", name])
+          .output()
+          .ok()
+          .filter(|output| output.status.success())
+          .map(|output| {
+            format!(
+              "{} {}",
+              name,
+              String::from_utf8_lossy(&output.stdout).trim()
+            )
+          })
+      });
+      match version {
+        Some(version) => Diagnostic::ok(package, version),
+        None => Diagnostic::error(
+          package,
+          "not installed",
+          format!(
+            "install it, e.g. `sudo apt install {}` (or the equivalent package of your distribution)",
+            package
+          ),
+        ),
+      }
+    })
+    .collect()
+}
+
+#[cfg(target_os = "macos")]
+fn xcode_command_line_tools() -> Diagnostic {
+  match Command::new("xcode-select")
+    .arg("-p")
+    .output()
+    .ok()
+    .filter(|output| output.status.success())
+  {
+    Some(output) => Diagnostic::ok(
+      "Xcode Command Line Tools",
+      String::from_utf8_lossy(&output.stdout).trim(),
+    ),
+    None => Diagnostic::error(
+      "Xcode Command Line Tools",
+      "not installed",
+      "run `xcode-select --install`",
+    ),
+  }
+}
+
+fn environment_variables(config: Option<&Config>) -> Vec<Diagnostic> {
+  let mut diagnostics = Vec::new();
+  let is_set = |name: &str| var_os(name).map(|v| !v.is_empty()).unwrap_or_default();
+
+  if let Some(target) = var_os("CARGO_BUILD_TARGET") {
+    diagnostics.push(Diagnostic::warning(
+      "CARGO_BUILD_TARGET",
+      format!(
+        "set to {:?}, but `tauri build` and `tauri dev` look for the binary of the host target",
+        target
+      ),
+      format!(
+        "unset it and use `tauri build --target {}`",
+        target.to_string_lossy()
+      ),
+    ));
+  }
+
+  // the variables that only work when set together
+  for (variable, required) in &[
+    ("APPLE_CERTIFICATE", "APPLE_CERTIFICATE_PASSWORD"),
+    ("APPLE_CERTIFICATE_PASSWORD", "APPLE_CERTIFICATE"),
+    ("APPLE_ID", "APPLE_PASSWORD"),
+    ("APPLE_ID", "APPLE_TEAM_ID"),
+    ("APPLE_PASSWORD", "APPLE_ID"),
+    ("APPLE_API_KEY", "APPLE_API_ISSUER"),
+    ("APPLE_API_ISSUER", "APPLE_API_KEY"),
+    ("APPLE_API_KEY_PATH", "APPLE_API_KEY"),
+    ("AZURE_KEY_VAULT_URI", "AZURE_CERTIFICATE_NAME"),
+    ("AZURE_CERTIFICATE_NAME", "AZURE_KEY_VAULT_URI"),
+    ("TAURI_KEY_PASSWORD", "TAURI_PRIVATE_KEY"),
+  ] {
+    if is_set(variable) && !is_set(required) {
+      diagnostics.push(Diagnostic::warning(
+        *variable,
+        format!("ignored without `{}`", required),
+        format!("set `{}` or unset `{}`", required, variable),
+      ));
+    }
+  }
+
+  if let Some(config) = config {
+    let signs_updates = config.tauri.updater.active && config.tauri.updater.pubkey.is_some();
+    if is_set("TAURI_PRIVATE_KEY") && !signs_updates {
+      diagnostics.push(Diagnostic::warning(
+        "TAURI_PRIVATE_KEY",
+        "ignored since the updater isn't active with a `pubkey`",
+        "set `tauri > updater > active` and `tauri > updater > pubkey` or unset `TAURI_PRIVATE_KEY`",
+      ));
+    }
+  }
+
+  if diagnostics.is_empty() {
+    diagnostics.push(Diagnostic::ok("Environment variables", "no conflicts"));
+  }
+  diagnostics
+}
+
+fn signing(config: &Config) -> Vec<Diagnostic> {
+  let mut diagnostics = Vec::new();
+
+  #[cfg(target_os = "macos")]
+  if let Some(identity) = &var_os("APPLE_SIGNING_IDENTITY")
+    .map(|identity| identity.to_string_lossy().to_string())
+    .or_else(|| config.tauri.bundle.macos.signing_identity.clone())
+  {
+    let check = "Signing identity";
+    if var_os("APPLE_CERTIFICATE").is_some() {
+      diagnostics.push(Diagnostic::ok(
+        check,
+        "imported from the `APPLE_CERTIFICATE` environment variable",
+      ));
+    } else {
+      let identities = Command::new("security")
+        .args(&["find-identity", "-v", "-p", "codesigning"])
+        .output()
+        .map(|output| String::from_utf8_lossy(&output.stdout).to_string())
+        .unwrap_or_default();
+      if identities
+        .lines()
+        .any(|line| line.contains(identity.as_str()))
+      {
+        diagnostics.push(Diagnostic::ok(check, identity.clone()));
+      } else {
+        diagnostics.push(Diagnostic::error(
+          check,
+          format!("`{}` not found on the keychain", identity),
+          "import the certificate on the keychain or set the `APPLE_CERTIFICATE` and `APPLE_CERTIFICATE_PASSWORD` environment variables, see `security find-identity -v -p codesigning` for the available identities",
+        ));
+      }
+    }
+  }
+
+  #[cfg(windows)]
+  if let Some(thumbprint) = &config.tauri.bundle.windows.certificate_thumbprint {
+    let check = "Signing certificate";
+    let found = Command::new("powershell")
+      .args(&["-NoProfile", "-Command"])
+      .arg(format!("Test-Path Cert:\\CurrentUser\\My\\{}", thumbprint))
+      .output()
+      .map(|output| String::from_utf8_lossy(&output.stdout).trim() == "True")
+      .unwrap_or_default();
+    if found {
+      diagnostics.push(Diagnostic::ok(check, thumbprint.clone()));
+    } else {
+      diagnostics.push(Diagnostic::error(
+        check,
+        format!("`{}` not found on the personal certificate store", thumbprint),
+        "import the certificate on the `Cert:\\CurrentUser\\My` store or fix `tauri > bundle > windows > certificateThumbprint`",
+      ));
+    }
+  }
+
+  if config.tauri.updater.active
+    && config.tauri.updater.pubkey.is_some()
+    && var_os("TAURI_PRIVATE_KEY").is_none()
+  {
+    diagnostics.push(Diagnostic::warning(
+      "Updater signature",
+      "`TAURI_PRIVATE_KEY` isn't set, `tauri build` can't sign the update bundles",
+      "set the `TAURI_PRIVATE_KEY` and `TAURI_KEY_PASSWORD` environment variables, generating the keys with `tauri sign --generate`",
+    ));
+  }
+
+  diagnostics
+}
+
+// Compares the tauri features of Cargo.toml with the ones enabled by the config.
+fn tauri_features(config: &Config) -> Diagnostic {
+  let check = "tauri features";
+  let manifest_features = match manifest_tauri_features() {
+    Ok(features) => features,
+    Err(e) => {
+      return Diagnostic::error(
+        check,
+        format!("failed to read Cargo.toml: {}", e),
+        "fix the `src-tauri/Cargo.toml` syntax",
+      )
+    }
+  };
+  let expected = config_features(config);
+  let managed = cli_managed_features();
+
+  let mut missing: Vec<&String> = expected
+    .iter()
+    .filter(|feature| !manifest_features.contains(feature))
+    .collect();
+  missing.sort();
+  let unused: Vec<&String> = manifest_features
+    .iter()
+    .filter(|feature| managed.contains(&feature.as_str()) && !expected.contains(*feature))
+    .collect();
+
+  if missing.is_empty() && unused.is_empty() {
+    return Diagnostic::ok(check, "match the tauri.conf.json allowlist");
+  }
+  let mut problems = Vec::new();
+  if !missing.is_empty() {
+    problems.push(format!(
+      "{:?} enabled on tauri.conf.json but missing on Cargo.toml",
+      missing
+    ));
+  }
+  if !unused.is_empty() {
+    problems.push(format!(
+      "{:?} enabled on Cargo.toml but not on tauri.conf.json",
+      unused
+    ));
+  }
+  Diagnostic::warning(
+    check,
+    problems.join(", "),
+    "run `tauri dev` or `tauri build` to update Cargo.toml, `cargo build` uses its features as is",
+  )
+}
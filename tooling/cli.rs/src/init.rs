@@ -16,13 +16,13 @@ use handlebars::{to_json, Handlebars};
 use include_dir::{include_dir, Dir};
 use serde::Deserialize;
 
-const TEMPLATE_DIR: Dir = include_dir!("templates");
+const TEMPLATE_DIR: Dir = include_dir!("templates/app");
 
 #[derive(Deserialize)]
-struct VersionMetadata {
-  tauri: String,
+pub struct VersionMetadata {
+  pub tauri: String,
   #[serde(rename = "tauri-build")]
-  tauri_build: String,
+  pub tauri_build: String,
 }
 
 pub struct Init {
@@ -152,7 +152,7 @@ impl Init {
   }
 }
 
-fn render_template<P: AsRef<Path>>(
+pub fn render_template<P: AsRef<Path>>(
   handlebars: &Handlebars,
   data: &BTreeMap<&str, serde_json::Value>,
   dir: &Dir,
@@ -183,7 +183,7 @@ fn render_template<P: AsRef<Path>>(
   Ok(())
 }
 
-fn resolve_tauri_path<P: AsRef<Path>>(path: P, crate_name: &str) -> PathBuf {
+pub fn resolve_tauri_path<P: AsRef<Path>>(path: P, crate_name: &str) -> PathBuf {
   let path = path.as_ref();
   if path.is_absolute() {
     path.join(crate_name)
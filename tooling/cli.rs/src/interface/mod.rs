@@ -16,6 +16,7 @@ pub fn get_bundler_settings(
   out_dir: &Path,
   verbose: bool,
   package_types: Option<Vec<PackageType>>,
+  target: Option<String>,
 ) -> crate::Result<Settings> {
   let mut settings_builder = SettingsBuilder::new()
     .package_settings(app_settings.get_package_settings())
@@ -31,5 +32,9 @@ pub fn get_bundler_settings(
     settings_builder = settings_builder.package_types(types);
   }
 
+  if let Some(target) = target {
+    settings_builder = settings_builder.target(target);
+  }
+
   settings_builder.build().map_err(Into::into)
 }
@@ -15,7 +15,8 @@ use serde::Deserialize;
 
 use crate::helpers::{app_paths::tauri_dir, config::Config, manifest::Manifest};
 use tauri_bundler::{
-  AppCategory, BundleBinary, BundleSettings, DebianSettings, MacOsSettings, PackageSettings,
+  AppCategory, AppImageSettings, BundleBinary, BundleResource, BundleSettings, DebianSettings,
+  DesktopEntrySettings, FlatpakSettings, MacOsSettings, PackageSettings, SnapSettings,
   UpdaterSettings, WindowsSettings,
 };
 
@@ -126,6 +127,98 @@ pub fn build_project(
   Ok(())
 }
 
+/// The target of the macOS binaries running on both Apple Silicon and Intel.
+pub const UNIVERSAL_MACOS_TARGET: &str = "universal-apple-darwin";
+/// The targets merged into the [`UNIVERSAL_MACOS_TARGET`] binaries.
+pub const UNIVERSAL_MACOS_ARCH_TARGETS: &[&str] = &["aarch64-apple-darwin", "x86_64-apple-darwin"];
+
+/// Merges the binaries built for each architecture into a universal binary with `lipo`.
+pub fn lipo(inputs: &[PathBuf], output: &Path) -> crate::Result<()> {
+  let status = Command::new("lipo")
+    .arg("-create")
+    .arg("-output")
+    .arg(output)
+    .args(inputs)
+    .status()
+    .with_context(|| "failed to run lipo, install the Xcode command line tools")?;
+  if status.success() {
+    Ok(())
+  } else {
+    Err(anyhow::anyhow!(
+      "failed to merge {:?} into the universal binary {:?}",
+      inputs,
+      output
+    ))
+  }
+}
+
+/// Checks the toolchain needed to build for the target triple,
+/// failing with the steps to install it.
+pub fn validate_target(runner: &str, target: &str) -> crate::Result<()> {
+  // `cross` and the custom runners bring their own toolchains
+  if runner != "cargo" {
+    return Ok(());
+  }
+
+  if let Ok(output) = Command::new("rustup")
+    .args(&["target", "list", "--installed"])
+    .output()
+  {
+    let installed = String::from_utf8_lossy(&output.stdout);
+    if output.status.success() && !installed.lines().any(|t| t.trim() == target) {
+      return Err(anyhow::anyhow!(
+        "the `{0}` target is not installed, run `rustup target add {0}`",
+        target
+      ));
+    }
+  }
+
+  let host_os = std::env::consts::OS;
+  let target_os = tauri_bundler::bundle::target_os(target).unwrap_or(host_os);
+  if target_os == host_os {
+    return Ok(());
+  }
+  // the linker configured with the `CARGO_TARGET_<TRIPLE>_LINKER` environment variable
+  let linker_var = format!(
+    "CARGO_TARGET_{}_LINKER",
+    target.to_uppercase().replace('-', "_")
+  );
+  if std::env::var_os(linker_var).is_some() {
+    return Ok(());
+  }
+  match target_os {
+    "windows" if target.ends_with("-msvc") => Err(anyhow::anyhow!(
+      "the `{}` target can only be built on Windows, use the `-pc-windows-gnu` target with the mingw-w64 or llvm-mingw toolchains or `--runner cross`",
+      target
+    )),
+    "windows" => {
+      let arch = match target.split('-').next() {
+        Some("i586") | Some("i686") => "i686",
+        Some(arch) => arch,
+        None => "x86_64",
+      };
+      let linker = format!("{}-w64-mingw32-gcc", arch);
+      if Command::new(&linker).arg("--version").output().is_ok() {
+        Ok(())
+      } else {
+        Err(anyhow::anyhow!(
+          "the `{}` linker was not found, install the mingw-w64 (e.g. `sudo apt install mingw-w64`) or llvm-mingw toolchain or use `--runner cross`",
+          linker
+        ))
+      }
+    }
+    "macos" | "ios" => Err(anyhow::anyhow!(
+      "the `{}` target can only be built on macOS",
+      target
+    )),
+    _ => Err(anyhow::anyhow!(
+      "building the `{}` target on {} requires a cross toolchain, set the `CARGO_TARGET_<TRIPLE>_LINKER` environment variable or use `--runner cross`",
+      target,
+      host_os
+    )),
+  }
+}
+
 pub struct AppSettings {
   cargo_settings: CargoSettings,
   cargo_package_settings: CargoPackageSettings,
@@ -186,10 +279,10 @@ impl AppSettings {
     )
   }
 
-  pub fn get_out_dir(&self, debug: bool) -> crate::Result<PathBuf> {
+  pub fn get_out_dir(&self, target: Option<String>, debug: bool) -> crate::Result<PathBuf> {
     let tauri_dir = tauri_dir();
     let workspace_dir = get_workspace_dir(&tauri_dir);
-    get_target_dir(&workspace_dir, None, !debug)
+    get_target_dir(&workspace_dir, target, !debug)
   }
 
   pub fn get_package_settings(&self) -> PackageSettings {
@@ -369,7 +462,12 @@ fn tauri_config_to_bundle_settings(
   let windows_icon_path = PathBuf::from("");
 
   #[allow(unused_mut)]
-  let mut resources = config.resources.unwrap_or_default();
+  let mut resources: Vec<BundleResource> = config
+    .resources
+    .unwrap_or_default()
+    .into_iter()
+    .map(Into::into)
+    .collect();
   #[allow(unused_mut)]
   let mut depends = config.deb.depends.unwrap_or_default();
 
@@ -378,7 +476,10 @@ fn tauri_config_to_bundle_settings(
     if let Some(system_tray_config) = &system_tray_config {
       let mut icon_path = system_tray_config.icon_path.clone();
       icon_path.set_extension("png");
-      resources.push(icon_path.display().to_string());
+      resources.push(BundleResource {
+        path: icon_path.display().to_string(),
+        targets: Vec::new(),
+      });
       depends.push("libappindicator3-1".to_string());
     }
 
@@ -408,7 +509,15 @@ fn tauri_config_to_bundle_settings(
     },
     short_description: config.short_description,
     long_description: config.long_description,
-    external_bin: config.external_bin,
+    external_bin: config
+      .external_bin
+      .map(|bins| bins.into_iter().map(Into::into).collect()),
+    verify_sidecar_checksums: config.verify_sidecar_checksums,
+    url_schemes: config.url_schemes.unwrap_or_default(),
+    file_associations: config
+      .file_associations
+      .map(|associations| associations.into_iter().map(Into::into).collect())
+      .unwrap_or_default(),
     deb: DebianSettings {
       depends: if depends.is_empty() {
         None
@@ -417,6 +526,34 @@ fn tauri_config_to_bundle_settings(
       },
       use_bootstrapper: Some(config.deb.use_bootstrapper),
       files: config.deb.files,
+      recommends: config.deb.recommends,
+      conflicts: config.deb.conflicts,
+      desktop_entry: DesktopEntrySettings {
+        categories: config.deb.desktop_entry.categories,
+        mime_types: config.deb.desktop_entry.mime_types,
+        keywords: config.deb.desktop_entry.keywords,
+      },
+      pre_install_script: config.deb.pre_install_script,
+      post_install_script: config.deb.post_install_script,
+      pre_remove_script: config.deb.pre_remove_script,
+      post_remove_script: config.deb.post_remove_script,
+    },
+    appimage: AppImageSettings {
+      bundle_dependencies: config.appimage.bundle_dependencies,
+      libraries: config.appimage.libraries,
+      exclude_libraries: config.appimage.exclude_libraries,
+      update_information: config.appimage.update_information,
+      app_run: config.appimage.app_run,
+    },
+    flatpak: FlatpakSettings {
+      runtime_version: config.flatpak.runtime_version,
+      finish_args: config.flatpak.finish_args,
+    },
+    snap: SnapSettings {
+      confinement: config.snap.confinement,
+      grade: config.snap.grade,
+      plugs: config.snap.plugs,
+      stage_packages: config.snap.stage_packages,
     },
     macos: MacOsSettings {
       frameworks: config.macos.frameworks,
@@ -424,7 +561,10 @@ fn tauri_config_to_bundle_settings(
       license: config.macos.license,
       use_bootstrapper: Some(config.macos.use_bootstrapper),
       exception_domain: config.macos.exception_domain,
-      signing_identity: config.macos.signing_identity,
+      signing_identity: std::env::var("APPLE_SIGNING_IDENTITY")
+        .ok()
+        .filter(|identity| !identity.is_empty())
+        .or(config.macos.signing_identity),
       entitlements: config.macos.entitlements,
     },
     windows: WindowsSettings {
@@ -432,6 +572,8 @@ fn tauri_config_to_bundle_settings(
       digest_algorithm: config.windows.digest_algorithm,
       certificate_thumbprint: config.windows.certificate_thumbprint,
       wix: config.windows.wix.map(|w| w.into()),
+      nsis: config.windows.nsis.map(|n| n.into()),
+      portable: config.windows.portable.map(|p| p.into()),
       icon_path: windows_icon_path,
     },
     updater: Some(UpdaterSettings {
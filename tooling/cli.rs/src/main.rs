@@ -10,9 +10,11 @@ use serde::Deserialize;
 mod build;
 mod dev;
 mod helpers;
+mod icon;
 mod info;
 mod init;
 mod interface;
+mod plugin;
 mod sign;
 
 // temporary fork from https://github.com/mitsuhiko/console until 0.14.1+ release
@@ -142,11 +144,22 @@ fn dev_command(matches: &ArgMatches) -> Result<()> {
     .values_of("args")
     .map(|a| a.into_iter().map(|v| v.to_string()).collect())
     .unwrap_or_default();
+  let watch: Vec<PathBuf> = matches
+    .values_of("watch")
+    .map(|a| a.into_iter().map(PathBuf::from).collect())
+    .unwrap_or_default();
+  let ignore: Vec<String> = matches
+    .values_of("ignore")
+    .map(|a| a.into_iter().map(|v| v.to_string()).collect())
+    .unwrap_or_default();
+  let debounce = matches.value_of("debounce");
 
   let mut dev_runner = dev::Dev::new()
     .exit_on_panic(exit_on_panic)
     .args(args)
-    .features(features);
+    .features(features)
+    .watch(watch)
+    .ignore(ignore);
 
   if let Some(runner) = runner {
     dev_runner = dev_runner.runner(runner.to_string());
@@ -157,6 +170,13 @@ fn dev_command(matches: &ArgMatches) -> Result<()> {
   if let Some(config) = config {
     dev_runner = dev_runner.config(config.to_string());
   }
+  if let Some(debounce) = debounce {
+    dev_runner = dev_runner.debounce(
+      debounce
+        .parse()
+        .map_err(|_| anyhow::anyhow!("invalid debounce `{}`, expected milliseconds", debounce))?,
+    );
+  }
 
   dev_runner.run()
 }
@@ -196,8 +216,12 @@ fn build_command(matches: &ArgMatches) -> Result<()> {
   build_runner.run()
 }
 
-fn info_command() -> Result<()> {
-  info::Info::new().run()
+fn info_command(matches: &ArgMatches) -> Result<()> {
+  let mut info_runner = info::Info::new();
+  if matches.is_present("doctor") {
+    info_runner = info_runner.doctor();
+  }
+  info_runner.run()
 }
 
 fn sign_command(matches: &ArgMatches) -> Result<()> {
@@ -257,6 +281,59 @@ fn sign_command(matches: &ArgMatches) -> Result<()> {
   sign_runner.run()
 }
 
+fn icon_command(matches: &ArgMatches) -> Result<()> {
+  let source = matches.value_of("source");
+  let target = matches.value_of("target");
+  let skip_config = matches.is_present("skip-config");
+
+  let mut icon_runner = icon::Icon::new();
+  if let Some(source) = source {
+    icon_runner = icon_runner.source(source);
+  }
+  if let Some(target) = target {
+    icon_runner = icon_runner.target(target);
+  }
+  if skip_config {
+    icon_runner = icon_runner.skip_config();
+  }
+
+  icon_runner.run()
+}
+
+fn plugin_init_command(matches: &ArgMatches) -> Result<()> {
+  let force = matches.is_present("force");
+  let directory = matches.value_of("directory");
+  let tauri_path = matches.value_of("tauri-path");
+  let name = matches.value_of("name");
+  let no_api = matches.is_present("no-api");
+  let ci = matches.is_present("ci") || std::env::var("CI").is_ok();
+
+  let mut init_runner = plugin::Init::new();
+  if force {
+    init_runner = init_runner.force();
+  }
+  if let Some(directory) = directory {
+    init_runner = init_runner.directory(directory);
+  }
+  if let Some(tauri_path) = tauri_path {
+    init_runner = init_runner.tauri_path(tauri_path);
+  }
+  if no_api {
+    init_runner = init_runner.no_api();
+  }
+  let default_name: Option<String> = None;
+  init_runner = value_or_prompt!(
+    init_runner,
+    plugin_name,
+    name,
+    ci,
+    "What is your plugin name?",
+    default_name
+  );
+
+  init_runner.run()
+}
+
 fn main() -> Result<()> {
   let yaml = load_yaml!("cli.yml");
   let app = App::from(yaml)
@@ -273,10 +350,16 @@ fn main() -> Result<()> {
     dev_command(matches)?;
   } else if let Some(matches) = matches.subcommand_matches("build") {
     build_command(matches)?;
-  } else if matches.subcommand_matches("info").is_some() {
-    info_command()?;
+  } else if let Some(matches) = matches.subcommand_matches("info") {
+    info_command(matches)?;
   } else if let Some(matches) = matches.subcommand_matches("sign") {
     sign_command(matches)?;
+  } else if let Some(matches) = matches.subcommand_matches("icon") {
+    icon_command(matches)?;
+  } else if let Some(matches) = matches.subcommand_matches("plugin") {
+    if let Some(matches) = matches.subcommand_matches("init") {
+      plugin_init_command(matches)?;
+    }
   }
 
   Ok(())
@@ -0,0 +1,170 @@
+// Copyright 2019-2021 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+use crate::{
+  helpers::Logger,
+  init::{render_template, resolve_tauri_path, VersionMetadata},
+};
+use anyhow::Context;
+use handlebars::{to_json, Handlebars};
+use include_dir::{include_dir, Dir};
+
+use std::{
+  collections::BTreeMap,
+  env::current_dir,
+  fs::{remove_dir_all, remove_file},
+  path::PathBuf,
+};
+
+const TEMPLATE_DIR: Dir = include_dir!("templates/plugin");
+const CRATE_NAME_PREFIX: &str = "tauri-plugin-";
+
+pub struct Init {
+  force: bool,
+  directory: PathBuf,
+  tauri_path: Option<PathBuf>,
+  plugin_name: Option<String>,
+  api: bool,
+}
+
+impl Default for Init {
+  fn default() -> Self {
+    Self {
+      force: false,
+      directory: current_dir().expect("failed to read cwd"),
+      tauri_path: None,
+      plugin_name: None,
+      api: true,
+    }
+  }
+}
+
+impl Init {
+  pub fn new() -> Self {
+    Default::default()
+  }
+
+  pub fn force(mut self) -> Self {
+    self.force = true;
+    self
+  }
+
+  pub fn directory(mut self, directory: impl Into<PathBuf>) -> Self {
+    self.directory = directory.into();
+    self
+  }
+
+  pub fn tauri_path(mut self, tauri_path: impl Into<PathBuf>) -> Self {
+    self.tauri_path = Some(tauri_path.into());
+    self
+  }
+
+  pub fn plugin_name(mut self, plugin_name: impl Into<String>) -> Self {
+    self.plugin_name = Some(plugin_name.into());
+    self
+  }
+
+  pub fn no_api(mut self) -> Self {
+    self.api = false;
+    self
+  }
+
+  pub fn run(self) -> crate::Result<()> {
+    let logger = Logger::new("tauri:plugin");
+    let plugin_name = plugin_name(
+      self
+        .plugin_name
+        .as_deref()
+        .ok_or_else(|| anyhow::anyhow!("the plugin name is required"))?,
+    )?;
+    let crate_name = format!("{}{}", CRATE_NAME_PREFIX, plugin_name);
+    let template_target_path = self.directory.join(&crate_name);
+    let metadata = serde_json::from_str::<VersionMetadata>(include_str!("../metadata.json"))?;
+    if template_target_path.exists() && !self.force {
+      logger.warn(format!(
+        "Plugin dir ({:?}) not empty. Run `plugin init --force` to overwrite.",
+        template_target_path
+      ));
+      return Ok(());
+    }
+
+    let tauri_dep = if let Some(tauri_path) = self.tauri_path {
+      format!(
+        "{{  path = {:?} }}",
+        resolve_tauri_path(&tauri_path, "core/tauri")
+      )
+    } else {
+      format!(r#"{{ version = "{}" }}"#, metadata.tauri)
+    };
+
+    let _ = remove_dir_all(&template_target_path);
+    let handlebars = Handlebars::new();
+
+    let mut data = BTreeMap::new();
+    data.insert("tauri_dep", to_json(tauri_dep));
+    data.insert("plugin_name", to_json(&plugin_name));
+    data.insert("plugin_name_snake", to_json(plugin_name.replace('-', "_")));
+    data.insert("plugin_struct", to_json(pascal_case(&plugin_name)));
+    data.insert("crate_name", to_json(&crate_name));
+    data.insert("api", to_json(self.api));
+
+    render_template(&handlebars, &data, &TEMPLATE_DIR, &template_target_path)
+      .with_context(|| "failed to render the plugin template")?;
+    if !self.api {
+      remove_dir_all(template_target_path.join("webview-src"))?;
+      for file in &["package.json", "tsconfig.json"] {
+        remove_file(template_target_path.join(file))?;
+      }
+    }
+    logger.log(format!("Created the plugin at {:?}", template_target_path));
+
+    Ok(())
+  }
+}
+
+// Normalizes the plugin name, accepting the crate name too, e.g. `tauri-plugin-awesome`.
+fn plugin_name(name: &str) -> crate::Result<String> {
+  let name = name.trim().to_lowercase().replace('_', "-");
+  let name = name.trim_start_matches(CRATE_NAME_PREFIX);
+  let valid = !name.is_empty()
+    && name.starts_with(|c: char| c.is_ascii_lowercase())
+    && name
+      .chars()
+      .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-');
+  if valid {
+    Ok(name.to_string())
+  } else {
+    Err(anyhow::anyhow!(
+      "invalid plugin name `{}`: it must start with a letter and contain only letters, digits and dashes",
+      name
+    ))
+  }
+}
+
+fn pascal_case(name: &str) -> String {
+  name
+    .split('-')
+    .map(|word| {
+      let mut chars = word.chars();
+      match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => String::new(),
+      }
+    })
+    .collect()
+}
+
+#[cfg(test)]
+mod test {
+  use super::{pascal_case, plugin_name};
+
+  #[test]
+  fn normalizes_plugin_name() {
+    assert_eq!(plugin_name("awesome").unwrap(), "awesome");
+    assert_eq!(plugin_name("tauri-plugin-file_sync").unwrap(), "file-sync");
+    assert!(plugin_name("9lives").is_err());
+    assert!(plugin_name("tauri-plugin-").is_err());
+    assert_eq!(pascal_case("file-sync"), "FileSync");
+  }
+}
@@ -0,0 +1,10 @@
+use crate::Config;
+use tauri::{Manager, Params, Window};
+
+/// Invoked with `window.__TAURI_PLUGINS__['{{ plugin_name }}'].ping({ value })`
+/// or `invoke('plugin:{{ plugin_name }}|ping', { value })`.
+#[tauri::command]
+pub fn ping<P: Params>(window: Window<P>, value: String) -> String {
+  let config = window.plugin_handle("{{ plugin_name }}").state::<Config>();
+  format!("{}{}", config.greeting, value)
+}
@@ -0,0 +1,101 @@
+use serde::Deserialize;
+use tauri::{
+  plugin::{Plugin, Result as PluginResult},
+  App, Invoke, Manager, Params,
+};
+
+mod commands;
+
+/// The plugin configuration, read from `tauri.conf.json > plugins > {{ plugin_name }}`.
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct Config {
+  /// The prefix of the `ping` command response.
+  #[serde(default = "default_greeting")]
+  pub greeting: String,
+}
+
+fn default_greeting() -> String {
+  "pong: ".into()
+}
+
+impl Default for Config {
+  fn default() -> Self {
+    Self {
+      greeting: default_greeting(),
+    }
+  }
+}
+
+impl Config {
+  /// Reads the plugin configuration, which is `null` if the user didn't configure the plugin.
+  fn from_value(value: serde_json::Value) -> serde_json::Result<Self> {
+    if value.is_null() {
+      Ok(Default::default())
+    } else {
+      serde_json::from_value(value)
+    }
+  }
+}
+
+/// The {{ plugin_name }} plugin.
+pub struct {{ plugin_struct }}<P: Params> {
+  invoke_handler: Box<dyn Fn(Invoke<P>) + Send + Sync>,
+}
+
+impl<P: Params> Default for {{ plugin_struct }}<P> {
+  fn default() -> Self {
+    Self {
+      invoke_handler: Box::new(tauri::generate_handler![commands::ping]),
+    }
+  }
+}
+
+impl<P: Params> {{ plugin_struct }}<P> {
+  /// Creates the plugin, registered with `tauri::Builder::plugin`.
+  pub fn new() -> Self {
+    Default::default()
+  }
+}
+
+impl<P: Params> Plugin<P> for {{ plugin_struct }}<P> {
+  fn name(&self) -> &'static str {
+    "{{ plugin_name }}"
+  }
+
+  fn initialize(&mut self, app: &App<P>, config: serde_json::Value) -> PluginResult<()> {
+    app
+      .plugin_handle(self.name())
+      .manage(Config::from_value(config)?);
+    Ok(())
+  }
+
+  fn commands(&self) -> &'static [&'static str] {
+    tauri::plugin_commands![ping]
+  }
+
+  fn extend_api(&mut self, invoke: Invoke<P>) {
+    (self.invoke_handler)(invoke)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::Config;
+  use serde_json::json;
+
+  #[test]
+  fn reads_config() {
+    assert_eq!(
+      Config::from_value(serde_json::Value::Null).unwrap().greeting,
+      "pong: "
+    );
+    assert_eq!(
+      Config::from_value(json!({ "greeting": "hello " }))
+        .unwrap()
+        .greeting,
+      "hello "
+    );
+    assert!(Config::from_value(json!({ "greting": "hello " })).is_err());
+  }
+}
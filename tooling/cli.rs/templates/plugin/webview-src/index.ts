@@ -0,0 +1,8 @@
+import { invoke } from '@tauri-apps/api/tauri'
+
+/**
+ * Sends the value to the plugin, resolving to the configured greeting followed by the value.
+ */
+export async function ping(value: string): Promise<string> {
+  return invoke<string>('plugin:{{ plugin_name }}|ping', { value })
+}
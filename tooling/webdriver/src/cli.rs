@@ -0,0 +1,54 @@
+// Copyright 2019-2021 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+use std::path::PathBuf;
+
+pub const HELP: &str = "\
+USAGE: tauri-driver [FLAGS] [OPTIONS]
+
+FLAGS:
+  -h, --help              Prints help information
+
+OPTIONS:
+  --port NUMBER           Sets the tauri-driver intermediary port [default: 4444]
+  --native-port NUMBER    Sets the port of the underlying WebDriver [default: 4445]
+  --native-driver PATH    Sets the path to the native WebDriver binary [default: found on the PATH]
+";
+
+/// The `tauri-driver` command line arguments.
+#[derive(Debug, Clone)]
+pub struct Args {
+  /// The port the WebDriver clients connect to.
+  pub port: u16,
+  /// The port of the native WebDriver.
+  pub native_port: u16,
+  /// The native WebDriver binary.
+  pub native_driver: Option<PathBuf>,
+}
+
+impl Args {
+  /// Parses the process arguments, printing the help and exiting on `--help`.
+  pub fn parse() -> Result<Self, pico_args::Error> {
+    let mut args = pico_args::Arguments::from_env();
+    if args.contains(["-h", "--help"]) {
+      println!("{}", HELP);
+      std::process::exit(0);
+    }
+
+    let parsed = Self {
+      port: args.opt_value_from_str("--port")?.unwrap_or(4444),
+      native_port: args.opt_value_from_str("--native-port")?.unwrap_or(4445),
+      native_driver: args.opt_value_from_str("--native-driver")?,
+    };
+
+    let remaining = args.finish();
+    if !remaining.is_empty() {
+      return Err(pico_args::Error::ArgumentParsingFailed {
+        cause: format!("unexpected arguments: {:?}", remaining),
+      });
+    }
+
+    Ok(parsed)
+  }
+}
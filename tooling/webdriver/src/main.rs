@@ -0,0 +1,39 @@
+// Copyright 2019-2021 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+//! A WebDriver server for Tauri applications.
+//!
+//! It starts the native WebDriver of the platform (`WebKitWebDriver` on Linux and `msedgedriver` on Windows)
+//! and proxies the WebDriver clients requests to it, mapping the `tauri:options` session capability
+//! to the native driver capabilities, so Selenium or WebdriverIO tests run against the real Tauri windows.
+
+mod cli;
+mod server;
+mod webdriver;
+
+use std::process::exit;
+
+#[tokio::main]
+async fn main() {
+  let args = match cli::Args::parse() {
+    Ok(args) => args,
+    Err(e) => {
+      eprintln!("{}\n\n{}", e, cli::HELP);
+      exit(1);
+    }
+  };
+
+  let driver = match webdriver::spawn_native(&args) {
+    Ok(driver) => driver,
+    Err(e) => {
+      eprintln!("failed to start the native WebDriver: {:#}", e);
+      exit(1);
+    }
+  };
+
+  if let Err(e) = server::run(args, driver).await {
+    eprintln!("error while running the WebDriver server: {:#}", e);
+    exit(1);
+  }
+}
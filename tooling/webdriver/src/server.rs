@@ -0,0 +1,118 @@
+// Copyright 2019-2021 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+use crate::{cli::Args, webdriver};
+
+use anyhow::Context;
+use hyper::{
+  client::HttpConnector,
+  header::{CONTENT_LENGTH, CONTENT_TYPE, HOST},
+  service::{make_service_fn, service_fn},
+  Body, Client, Method, Request, Response, Server, StatusCode,
+};
+use serde_json::{json, Value};
+use tokio::process::Child;
+
+use std::{convert::Infallible, net::SocketAddr};
+
+/// Proxies the WebDriver requests to the native WebDriver until the process is interrupted
+/// or the native WebDriver exits.
+pub async fn run(args: Args, mut driver: Child) -> anyhow::Result<()> {
+  let address = SocketAddr::from(([127, 0, 0, 1], args.port));
+  let native_port = args.native_port;
+  let client = Client::new();
+
+  let service = make_service_fn(move |_| {
+    let client = client.clone();
+    async move {
+      Ok::<_, Infallible>(service_fn(move |request| {
+        handle(client.clone(), request, native_port)
+      }))
+    }
+  });
+
+  let server = Server::try_bind(&address)
+    .with_context(|| format!("failed to listen on {}", address))?
+    .serve(service);
+  println!("tauri-driver listening on http://{}", address);
+
+  tokio::select! {
+    result = server => result.context("the server stopped"),
+    status = driver.wait() => {
+      anyhow::bail!("the native WebDriver exited with {}", status?)
+    }
+    _ = tokio::signal::ctrl_c() => Ok(()),
+  }
+}
+
+/// Forwards a request to the native WebDriver, mapping the capabilities of the new session requests.
+async fn handle(
+  client: Client<HttpConnector>,
+  request: Request<Body>,
+  native_port: u16,
+) -> Result<Response<Body>, Infallible> {
+  let (mut parts, body) = request.into_parts();
+  let mut body = match hyper::body::to_bytes(body).await {
+    Ok(body) => body.to_vec(),
+    Err(e) => return Ok(error_response(StatusCode::BAD_REQUEST, e.to_string())),
+  };
+
+  if parts.method == Method::POST && parts.uri.path().trim_end_matches('/') == "/session" {
+    let mapped = serde_json::from_slice::<Value>(&body)
+      .context("the new session body isn't valid JSON")
+      .and_then(|mut session| {
+        webdriver::map_new_session(&mut session)?;
+        Ok(serde_json::to_vec(&session)?)
+      });
+    match mapped {
+      Ok(mapped) => body = mapped,
+      Err(e) => return Ok(error_response(StatusCode::BAD_REQUEST, format!("{:#}", e))),
+    }
+    parts.headers.remove(CONTENT_LENGTH);
+  }
+
+  let path = parts
+    .uri
+    .path_and_query()
+    .map(|path| path.as_str())
+    .unwrap_or("/");
+  parts.uri = match format!("http://127.0.0.1:{}{}", native_port, path).parse() {
+    Ok(uri) => uri,
+    Err(e) => return Ok(error_response(StatusCode::BAD_REQUEST, e.to_string())),
+  };
+  // let the client set the host of the native WebDriver
+  parts.headers.remove(HOST);
+
+  match client
+    .request(Request::from_parts(parts, body.into()))
+    .await
+  {
+    Ok(response) => Ok(response),
+    Err(e) => Ok(error_response(
+      StatusCode::BAD_GATEWAY,
+      format!("failed to reach the native WebDriver: {}", e),
+    )),
+  }
+}
+
+/// A WebDriver error response.
+fn error_response(status: StatusCode, message: String) -> Response<Body> {
+  let error = if status == StatusCode::BAD_REQUEST {
+    "invalid argument"
+  } else {
+    "unknown error"
+  };
+  let body = json!({
+    "value": {
+      "error": error,
+      "message": message,
+      "stacktrace": "",
+    }
+  });
+  Response::builder()
+    .status(status)
+    .header(CONTENT_TYPE, "application/json; charset=utf-8")
+    .body(body.to_string().into())
+    .expect("failed to build the error response")
+}
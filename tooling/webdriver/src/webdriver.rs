@@ -0,0 +1,180 @@
+// Copyright 2019-2021 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+use crate::cli::Args;
+
+use anyhow::Context;
+use serde::Deserialize;
+use serde_json::{json, Map, Value};
+use tokio::process::Child;
+
+use std::path::PathBuf;
+
+#[cfg(target_os = "linux")]
+const NATIVE_DRIVER: &str = "WebKitWebDriver";
+#[cfg(windows)]
+const NATIVE_DRIVER: &str = "msedgedriver";
+
+/// The session capability that configures the Tauri application under test.
+const TAURI_OPTIONS: &str = "tauri:options";
+
+/// The `tauri:options` capability.
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TauriOptions {
+  /// The path to the built application binary.
+  application: PathBuf,
+  /// The command line arguments passed to the application.
+  #[serde(default)]
+  args: Vec<String>,
+  /// The `webviewOptions` passed to msedgedriver.
+  #[cfg(windows)]
+  #[serde(default)]
+  webview_options: Option<Value>,
+}
+
+impl TauriOptions {
+  /// Maps the options to the capabilities of the native WebDriver.
+  #[cfg(target_os = "linux")]
+  fn into_native_capabilities(self) -> Map<String, Value> {
+    let mut capabilities = Map::new();
+    capabilities.insert(
+      "webkitgtk:browserOptions".into(),
+      json!({
+        "binary": self.application,
+        "args": self.args,
+      }),
+    );
+    capabilities
+  }
+
+  /// Maps the options to the capabilities of the native WebDriver.
+  #[cfg(windows)]
+  fn into_native_capabilities(self) -> Map<String, Value> {
+    let mut capabilities = Map::new();
+    capabilities.insert("ms:edgeChromium".into(), true.into());
+    capabilities.insert("browserName".into(), "webview2".into());
+    capabilities.insert(
+      "ms:edgeOptions".into(),
+      json!({
+        "binary": self.application,
+        "args": self.args,
+        "webviewOptions": self.webview_options.unwrap_or_else(|| json!({})),
+      }),
+    );
+    capabilities
+  }
+
+  /// The other platforms have no native WebDriver.
+  #[cfg(not(any(target_os = "linux", windows)))]
+  fn into_native_capabilities(self) -> Map<String, Value> {
+    Map::new()
+  }
+
+  /// Resolves the application path relative to the current directory,
+  /// since the native WebDriver may be started elsewhere.
+  fn resolve_application(mut self) -> anyhow::Result<Self> {
+    if self.application.is_relative() {
+      self.application = std::env::current_dir()?.join(&self.application);
+    }
+    Ok(self)
+  }
+}
+
+/// Replaces the `tauri:options` of the capabilities object with the native WebDriver capabilities.
+fn map_capabilities_object(capabilities: &mut Value) -> anyhow::Result<()> {
+  if let Some(capabilities) = capabilities.as_object_mut() {
+    if let Some(options) = capabilities.remove(TAURI_OPTIONS) {
+      let options: TauriOptions = serde_json::from_value(options)
+        .with_context(|| format!("invalid `{}` capability", TAURI_OPTIONS))?;
+      capabilities.extend(options.resolve_application()?.into_native_capabilities());
+    }
+  }
+  Ok(())
+}
+
+/// Maps the `tauri:options` of a new session request body, on both the W3C `capabilities`
+/// (`alwaysMatch` and every `firstMatch` entry) and the legacy `desiredCapabilities`.
+pub fn map_new_session(body: &mut Value) -> anyhow::Result<()> {
+  if let Some(capabilities) = body.get_mut("capabilities") {
+    if let Some(always_match) = capabilities.get_mut("alwaysMatch") {
+      map_capabilities_object(always_match)?;
+    }
+    if let Some(Value::Array(first_match)) = capabilities.get_mut("firstMatch") {
+      for capabilities in first_match {
+        map_capabilities_object(capabilities)?;
+      }
+    }
+  }
+  if let Some(capabilities) = body.get_mut("desiredCapabilities") {
+    map_capabilities_object(capabilities)?;
+  }
+  Ok(())
+}
+
+/// Starts the native WebDriver listening on the `--native-port`.
+#[cfg(any(target_os = "linux", windows))]
+pub fn spawn_native(args: &Args) -> anyhow::Result<Child> {
+  let driver = match &args.native_driver {
+    Some(driver) => driver.clone(),
+    None => which::which(NATIVE_DRIVER).with_context(|| {
+      format!(
+        "can't find the `{}` binary on the PATH, install it or set its path with `--native-driver`",
+        NATIVE_DRIVER
+      )
+    })?,
+  };
+
+  tokio::process::Command::new(&driver)
+    .arg(format!("--port={}", args.native_port))
+    .kill_on_drop(true)
+    .spawn()
+    .with_context(|| format!("failed to run `{}`", driver.display()))
+}
+
+/// Starts the native WebDriver listening on the `--native-port`.
+#[cfg(not(any(target_os = "linux", windows)))]
+pub fn spawn_native(_args: &Args) -> anyhow::Result<Child> {
+  anyhow::bail!(
+    "tauri-driver is only supported on Linux and Windows, the platform webview has no WebDriver"
+  )
+}
+
+#[cfg(all(test, any(target_os = "linux", windows)))]
+mod tests {
+  use super::map_new_session;
+  use serde_json::json;
+
+  #[test]
+  fn maps_tauri_options() {
+    let mut body = json!({
+      "capabilities": {
+        "alwaysMatch": { "tauri:options": { "application": "/app/bin", "args": ["--flag"] } },
+        "firstMatch": [{}, { "tauri:options": { "application": "/app/other" } }]
+      }
+    });
+    map_new_session(&mut body).unwrap();
+
+    let always_match = &body["capabilities"]["alwaysMatch"];
+    assert!(always_match.get("tauri:options").is_none());
+    let options = always_match
+      .as_object()
+      .unwrap()
+      .values()
+      .find(|value| value.get("binary").is_some())
+      .unwrap();
+    assert_eq!(options["args"], json!(["--flag"]));
+    assert_eq!(body["capabilities"]["firstMatch"][0], json!({}));
+    assert!(body["capabilities"]["firstMatch"][1]
+      .get("tauri:options")
+      .is_none());
+  }
+
+  #[test]
+  fn rejects_invalid_options() {
+    let mut body =
+      json!({ "capabilities": { "alwaysMatch": { "tauri:options": { "args": [] } } } });
+    assert!(map_new_session(&mut body).is_err());
+  }
+}